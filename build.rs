@@ -0,0 +1,10 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto/engine.proto");
+
+    #[cfg(feature = "grpc-server")]
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/engine.proto"], &["proto"])
+        .expect("cannot compile engine protobuf definitions");
+}
@@ -31,6 +31,23 @@ locals {
   final_snapshot_name = "${var.final_snapshot_name}-${local.final_snap_timestamp}"
 }
 
+{% if database_parameters %}
+resource "aws_db_parameter_group" "postgresql_parameter_group" {
+  name   = "qovery-${var.postgresql_identifier}"
+  family = var.parameter_group_family
+
+  tags = local.postgres_database_tags
+  {%- for parameter in database_parameters %}
+
+  parameter {
+    name         = "{{ parameter.name }}"
+    value        = "{{ parameter.value }}"
+    apply_method = "{{ parameter.apply_method }}"
+  }
+  {%- endfor %}
+}
+{%- endif %}
+
 # Non snapshoted version
 resource "aws_db_instance" "postgresql_instance" {
   identifier = var.postgresql_identifier
@@ -46,7 +63,13 @@ resource "aws_db_instance" "postgresql_instance" {
     delete = "60m"
   }
   password = var.password
+  {%- if database_parameters %}
+  parameter_group_name = aws_db_parameter_group.postgresql_parameter_group.name
+  {%- endif %}
   storage_encrypted = var.encrypt_disk
+  {%- if kms_key_arn %}
+  kms_key_id = var.kms_key_arn
+  {%- endif %}
   {%- if snapshot and snapshot["snapshot_id"] %}
   # Snapshot
   snapshot_identifier = var.snapshot_identifier
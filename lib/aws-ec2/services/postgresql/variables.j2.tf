@@ -24,6 +24,12 @@ variable "postgresql_version" {
   type = string
 }
 
+variable "parameter_group_family" {
+  description = "RDS parameter group family"
+  default = "{{ parameter_group_family }}"
+  type = string
+}
+
 variable "storage_type" {
   description = "One of 'standard' (magnetic), 'gp2' (general purpose SSD), or 'io1' (provisioned IOPS SSD)."
   default = "{{ database_disk_type }}"
@@ -36,6 +42,12 @@ variable "encrypt_disk" {
   type = string
 }
 
+variable "kms_key_arn" {
+  description = "Customer managed KMS key used to encrypt the disk, default AWS key is used when empty"
+  default = "{{ kms_key_arn }}"
+  type = string
+}
+
 variable "instance_class" {
   description = "Type of instance: https://docs.aws.amazon.com/AmazonRDS/latest/UserGuide/Concepts.DBInstanceClass.html"
   default = "{{ database_instance_type }}"
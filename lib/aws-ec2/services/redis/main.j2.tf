@@ -95,6 +95,11 @@ resource "aws_elasticache_replication_group" "elasticache_cluster" {
 
   # Elasticache auth
   transit_encryption_enabled = true
+  {%- if kms_key_arn %}
+  # Customer managed key is only supported by replication groups
+  at_rest_encryption_enabled = true
+  kms_key_id = var.kms_key_arn
+  {%- endif %}
   auth_token = var.password
 
   # Network
@@ -0,0 +1,112 @@
+{%- if enable_efs -%}
+locals {
+  tags_efs = merge(
+    aws_eks_cluster.eks_cluster.tags,
+    {
+      "Service" = "EFS"
+    }
+  )
+  # a filesystem can only have one mount target per availability zone
+  efs_subnet_ids = [
+    {%- if user_provided_network %}
+    for zone_subnet_ids in [data.aws_subnet.eks_zone_a[*].id, data.aws_subnet.eks_zone_b[*].id, data.aws_subnet.eks_zone_c[*].id] :
+    {%- else %}
+    for zone_subnet_ids in [aws_subnet.eks_zone_a[*].id, aws_subnet.eks_zone_b[*].id, aws_subnet.eks_zone_c[*].id] :
+    {%- endif %}
+    zone_subnet_ids[0] if length(zone_subnet_ids) > 0
+  ]
+}
+
+data "aws_vpc" "efs" {
+  id = aws_eks_cluster.eks_cluster.vpc_config[0].vpc_id
+}
+
+# Filesystem backing the shared volumes of the environments
+resource "aws_efs_file_system" "shared_volumes" {
+  creation_token   = "qovery-${var.kubernetes_cluster_id}"
+  encrypted        = true
+  performance_mode = "generalPurpose"
+  throughput_mode  = "elastic"
+
+  lifecycle_policy {
+    transition_to_ia = "AFTER_30_DAYS"
+  }
+
+  tags = merge(
+    local.tags_efs,
+    {
+      Name = "qovery-${var.kubernetes_cluster_id}"
+    }
+  )
+}
+
+resource "aws_security_group" "efs" {
+  name        = "qovery-efs-${var.kubernetes_cluster_id}"
+  description = "NFS access to the shared volumes filesystem from the cluster"
+  vpc_id      = data.aws_vpc.efs.id
+
+  ingress {
+    description = "NFS from the cluster VPC"
+    from_port   = 2049
+    to_port     = 2049
+    protocol    = "tcp"
+    cidr_blocks = [data.aws_vpc.efs.cidr_block]
+  }
+
+  tags = local.tags_efs
+}
+
+resource "aws_efs_mount_target" "shared_volumes" {
+  count = length(local.efs_subnet_ids)
+
+  file_system_id  = aws_efs_file_system.shared_volumes.id
+  subnet_id       = local.efs_subnet_ids[count.index]
+  security_groups = [aws_security_group.efs.id]
+}
+
+resource "aws_eks_addon" "aws_efs_csi_driver" {
+  cluster_name             = aws_eks_cluster.eks_cluster.name
+  addon_name               = "aws-efs-csi-driver"
+  service_account_role_arn = aws_iam_role.efs_csi_irsa_role.arn
+
+  # No version pinned, AWS picks the default one for the k8s version
+  resolve_conflicts        = "OVERWRITE"
+
+  tags                     = local.tags_eks
+
+  depends_on = [
+    aws_efs_mount_target.shared_volumes
+  ]
+}
+
+resource "aws_iam_role" "efs_csi_irsa_role" {
+  name        = "eks-efs-csi-plugin-${var.kubernetes_cluster_id}"
+  description = "EFS CSI plugin role for EKS cluster ${var.kubernetes_cluster_id}"
+  tags        = local.tags_eks
+
+  assume_role_policy = <<POLICY
+{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Effect": "Allow",
+      "Principal": {
+        "Federated": "${aws_iam_openid_connect_provider.oidc.arn}"
+      },
+      "Action": "sts:AssumeRoleWithWebIdentity",
+      "Condition": {
+        "StringLike": {
+          "${replace(aws_iam_openid_connect_provider.oidc.url, "https://", "")}:sub": "system:serviceaccount:kube-system:efs-csi-*"
+        }
+      }
+    }
+  ]
+}
+POLICY
+}
+
+resource "aws_iam_role_policy_attachment" "efs_csi_irsa_policy" {
+  role       = aws_iam_role.efs_csi_irsa_role.name
+  policy_arn = "arn:aws:iam::aws:policy/service-role/AmazonEFSCSIDriverPolicy"
+}
+{%- endif %}
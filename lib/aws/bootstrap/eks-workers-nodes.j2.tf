@@ -1,3 +1,14 @@
+{% if eks_node_os_image == "Ubuntu" -%}
+# Ubuntu images are not managed by EKS, the latest Canonical EKS image of the workers version is used
+data "aws_ssm_parameter" "eks_ubuntu_ami_amd64" {
+  name = "/aws/service/canonical/ubuntu/eks/22.04/${var.eks_k8s_versions.workers}/stable/current/amd64/hvm/ebs-gp2/ami-id"
+}
+
+data "aws_ssm_parameter" "eks_ubuntu_ami_arm64" {
+  name = "/aws/service/canonical/ubuntu/eks/22.04/${var.eks_k8s_versions.workers}/stable/current/arm64/hvm/ebs-gp2/ami-id"
+}
+{%- endif %}
+
 {% for eks_worker_node in eks_worker_nodes %}
 
 resource "aws_launch_template" "eks_workers_nodes_{{ loop.index }}" {
@@ -9,8 +20,13 @@ resource "aws_launch_template" "eks_workers_nodes_{{ loop.index }}" {
     instance_metadata_tags = "enabled"
   }
 
+  {%- if eks_node_os_image == "Ubuntu" %}
+  image_id = {% if eks_worker_node.instance_architecture == "ARM64" %}data.aws_ssm_parameter.eks_ubuntu_ami_arm64.value{% else %}data.aws_ssm_parameter.eks_ubuntu_ami_amd64.value{% endif %}
+  {%- endif %}
+
   block_device_mappings {
-    device_name = "/dev/xvda"
+    # containers are stored on the data volume of Bottlerocket, the root one only holds the OS
+    device_name = {% if eks_node_os_image == "Bottlerocket" %}"/dev/xvdb"{% elif eks_node_os_image == "Ubuntu" %}"/dev/sda1"{% else %}"/dev/xvda"{% endif %}
 
     ebs {
       volume_size = {{ eks_worker_node.disk_size_in_gib }}
@@ -20,6 +36,96 @@ resource "aws_launch_template" "eks_workers_nodes_{{ loop.index }}" {
     }
   }
 
+  {%- if eks_node_os_image == "Ubuntu" %}
+  user_data = base64encode(<<-EOT
+    #!/bin/bash
+    set -ex
+    /etc/eks/bootstrap.sh ${aws_eks_cluster.eks_cluster.name} \
+      --b64-cluster-ca ${aws_eks_cluster.eks_cluster.certificate_authority[0].data} \
+      --apiserver-endpoint ${aws_eks_cluster.eks_cluster.endpoint} \
+      {%- if eks_kubelet_max_pods %}
+      --use-max-pods false \
+      {%- endif %}
+      --kubelet-extra-args '{{ eks_kubelet_extra_args }}'
+  EOT
+  )
+  {%- elif eks_node_os_image == "Bottlerocket" and eks_kubelet_extra_args %}
+  user_data = base64encode(<<-EOT
+    [settings.kubernetes]
+    {%- if eks_kubelet_max_pods %}
+    max-pods = {{ eks_kubelet_max_pods }}
+    {%- endif %}
+    {%- if eks_kubelet_eviction_hard %}
+    [settings.kubernetes.eviction-hard]
+    {%- for signal, threshold in eks_kubelet_eviction_hard %}
+    "{{ signal }}" = "{{ threshold }}"
+    {%- endfor %}
+    {%- endif %}
+    {%- if eks_kubelet_kube_reserved %}
+    [settings.kubernetes.kube-reserved]
+    {%- for resource, quantity in eks_kubelet_kube_reserved %}
+    {{ resource }} = "{{ quantity }}"
+    {%- endfor %}
+    {%- endif %}
+  EOT
+  )
+  {%- elif eks_node_os_image == "AmazonLinux2023" and eks_kubelet_extra_args %}
+  user_data = base64encode(<<-EOT
+    MIME-Version: 1.0
+    Content-Type: multipart/mixed; boundary="//"
+
+    --//
+    Content-Type: application/node.eks.aws
+
+    ---
+    apiVersion: node.eks.aws/v1alpha1
+    kind: NodeConfig
+    spec:
+      kubelet:
+        config:
+          {%- if eks_kubelet_max_pods %}
+          maxPods: {{ eks_kubelet_max_pods }}
+          {%- endif %}
+          {%- if eks_kubelet_eviction_hard %}
+          evictionHard:
+            {%- for signal, threshold in eks_kubelet_eviction_hard %}
+            {{ signal }}: "{{ threshold }}"
+            {%- endfor %}
+          {%- endif %}
+          {%- if eks_kubelet_kube_reserved %}
+          kubeReserved:
+            {%- for resource, quantity in eks_kubelet_kube_reserved %}
+            {{ resource }}: "{{ quantity }}"
+            {%- endfor %}
+          {%- endif %}
+
+    --//--
+  EOT
+  )
+  {%- elif eks_kubelet_extra_args %}
+  # EKS runs its bootstrap script after this one, the kubelet flags are given to it through its environment
+  user_data = base64encode(<<-EOT
+    MIME-Version: 1.0
+    Content-Type: multipart/mixed; boundary="//"
+
+    --//
+    Content-Type: text/x-shellscript; charset="us-ascii"
+
+    #!/bin/bash
+    set -ex
+    cat <<-EOF > /etc/profile.d/bootstrap.sh
+    {%- if eks_kubelet_max_pods %}
+    export USE_MAX_PODS=false
+    {%- endif %}
+    export KUBELET_EXTRA_ARGS="{{ eks_kubelet_extra_args }}"
+    EOF
+    sed -i '/^set -o errexit/a source /etc/profile.d/bootstrap.sh' /etc/eks/bootstrap.sh
+
+    --//--
+  EOT
+  )
+  {%- endif %}
+
   tags = local.tags_eks
   tag_specifications {
     resource_type = "instance"
@@ -29,7 +135,9 @@ resource "aws_launch_template" "eks_workers_nodes_{{ loop.index }}" {
 
 resource "aws_eks_node_group" "eks_cluster_workers_{{ loop.index }}" {
   cluster_name           = aws_eks_cluster.eks_cluster.name
+  {%- if eks_node_os_image != "Ubuntu" %}
   version                = var.eks_k8s_versions.workers
+  {%- endif %}
   node_role_arn          = aws_iam_role.eks_workers.arn
   node_group_name_prefix = "qovery-"
   {% if user_provided_network -%}
@@ -38,7 +146,16 @@ resource "aws_eks_node_group" "eks_cluster_workers_{{ loop.index }}" {
   subnet_ids       = flatten([aws_subnet.eks_zone_a[*].id, aws_subnet.eks_zone_b[*].id, aws_subnet.eks_zone_c[*].id])
   {%- endif %}
   instance_types   = ["{{ eks_worker_node.instance_type }}"]
-  {% if eks_worker_node.instance_architecture == "ARM64" -%}
+  {% if eks_node_os_image == "Ubuntu" -%}
+  ami_type         = "CUSTOM"
+  {%- elif eks_node_os_image == "Bottlerocket" -%}
+  ami_type         = "{% if eks_worker_node.instance_architecture == "ARM64" %}BOTTLEROCKET_ARM_64{% else %}BOTTLEROCKET_x86_64{% endif %}{% if eks_worker_node.gpu %}_NVIDIA{% endif %}"
+  {%- elif eks_node_os_image == "AmazonLinux2023" -%}
+  ami_type         = "{% if eks_worker_node.instance_architecture == "ARM64" %}AL2023_ARM_64{% else %}AL2023_x86_64{% endif %}_{% if eks_worker_node.gpu %}NVIDIA{% else %}STANDARD{% endif %}"
+  {%- elif eks_worker_node.gpu -%}
+  // AL2 only has GPU images for x86
+  ami_type         = "AL2_x86_64_GPU"
+  {%- elif eks_worker_node.instance_architecture == "ARM64" -%}
   ami_type         = "AL2_ARM_64"
   {%- else -%}
   ami_type         = "AL2_x86_64"
@@ -52,6 +169,24 @@ resource "aws_eks_node_group" "eks_cluster_workers_{{ loop.index }}" {
   }
   )
 
+  {%- if eks_worker_node.labels %}
+  labels = {
+    {%- for key, value in eks_worker_node.labels %}
+    "{{ key }}" = "{{ value }}"
+    {%- endfor %}
+  }
+  {%- endif %}
+  {%- for taint in eks_worker_node.taints %}
+
+  taint {
+    key    = "{{ taint.key }}"
+    {%- if taint.value %}
+    value  = "{{ taint.value }}"
+    {%- endif %}
+    effect = "{% if taint.effect == "NoSchedule" %}NO_SCHEDULE{% elif taint.effect == "PreferNoSchedule" %}PREFER_NO_SCHEDULE{% else %}NO_EXECUTE{% endif %}"
+  }
+  {%- endfor %}
+
   launch_template {
     id      = aws_launch_template.eks_workers_nodes_{{ loop.index }}.id
     version = aws_launch_template.eks_workers_nodes_{{ loop.index }}.latest_version
@@ -25,6 +25,23 @@ locals {
   final_snapshot_name = "${var.final_snapshot_name}-${local.final_snap_timestamp}"
 }
 
+{% if database_parameters %}
+resource "aws_docdb_cluster_parameter_group" "documentdb_parameter_group" {
+  name   = "qovery-${var.documentdb_identifier}"
+  family = var.parameter_group_family
+
+  tags = local.mongodb_database_tags
+  {%- for parameter in database_parameters %}
+
+  parameter {
+    name         = "{{ parameter.name }}"
+    value        = "{{ parameter.value }}"
+    apply_method = "{{ parameter.apply_method }}"
+  }
+  {%- endfor %}
+}
+{%- endif %}
+
 resource "aws_docdb_cluster_instance" "documentdb_cluster_instances" {
   count              = var.documentdb_instances_number
 
@@ -46,6 +63,9 @@ resource "aws_docdb_cluster" "documentdb_cluster" {
   tags = local.mongodb_database_tags
 
   engine_version     = var.documentdb_version
+  {%- if database_parameters %}
+  db_cluster_parameter_group_name = aws_docdb_cluster_parameter_group.documentdb_parameter_group.name
+  {%- endif %}
 
   # DocumentDB instance basics
   port = var.port
@@ -64,6 +84,9 @@ resource "aws_docdb_cluster" "documentdb_cluster" {
   engine = "docdb"
   {%- endif %}
   storage_encrypted = var.encrypt_disk
+  {%- if kms_key_arn %}
+  kms_key_id = var.kms_key_arn
+  {%- endif %}
 
   # Network
   availability_zones = var.kubernetes_cluster_az_list
@@ -12,6 +12,12 @@ variable "documentdb_version" {
   type = string
 }
 
+variable "parameter_group_family" {
+  description = "DocumentDB cluster parameter group family"
+  default = "{{ parameter_group_family }}"
+  type = string
+}
+
 variable "documentdb_instances_number" {
   description = "DocumentDB instance numbers"
   default = 1
@@ -46,4 +52,10 @@ variable "encrypt_disk" {
   description = "Enable disk encryption"
   default = "{{ encrypt_disk }}"
   type = string
+}
+
+variable "kms_key_arn" {
+  description = "Customer managed KMS key used to encrypt the disk, default AWS key is used when empty"
+  default = "{{ kms_key_arn }}"
+  type = string
 }
\ No newline at end of file
@@ -40,6 +40,14 @@ resource "aws_db_parameter_group" "mysql_parameter_group" {
     name  = "log_bin_trust_function_creators"
     value = "1"
   }
+  {%- for parameter in database_parameters %}
+
+  parameter {
+    name         = "{{ parameter.name }}"
+    value        = "{{ parameter.value }}"
+    apply_method = "{{ parameter.apply_method }}"
+  }
+  {%- endfor %}
 }
 
 # Non snapshoted version
@@ -60,6 +68,9 @@ resource "aws_db_instance" "mysql_instance" {
   db_name = var.database_name
   parameter_group_name = aws_db_parameter_group.mysql_parameter_group.name
   storage_encrypted = var.encrypt_disk
+  {%- if kms_key_arn %}
+  kms_key_id = var.kms_key_arn
+  {%- endif %}
   {%- if snapshot is defined and snapshot["snapshot_id"] %}
   # Snapshot
   snapshot_identifier = var.snapshot_identifier
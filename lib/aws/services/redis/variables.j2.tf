@@ -40,4 +40,10 @@ variable "instance_class" {
   description = "Type of instance: https://docs.aws.amazon.com/AmazonElastiCache/latest/red-ug/CacheNodes.SupportedTypes.html"
   default = "{{ database_instance_type }}"
   type = string
+}
+
+variable "kms_key_arn" {
+  description = "Customer managed KMS key used to encrypt the disk, default AWS key is used when empty"
+  default = "{{ kms_key_arn }}"
+  type = string
 }
\ No newline at end of file
@@ -61,3 +61,11 @@ Create the name of the service account to use
 {{- default "default" .Values.serviceAccount.name }}
 {{- end }}
 {{- end }}
+
+{{/*
+Whether cert-manager can solve DNS01 challenges through the provider, wildcard certificates can't be issued otherwise.
+cert-manager has no built-in solver for OVH, its certificates are issued per host through HTTP01 challenges.
+*/}}
+{{- define "cert-manager-configs.dns01Supported" -}}
+{{- if ne .Values.externalDnsProvider "ovh" }}true{{- end }}
+{{- end }}
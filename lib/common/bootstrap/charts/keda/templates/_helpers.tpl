@@ -0,0 +1,38 @@
+{{/*
+Labels of all the resources of the chart
+*/}}
+{{- define "keda.labels" -}}
+app.kubernetes.io/part-of: {{ .Chart.Name }}
+app.kubernetes.io/version: {{ .Chart.AppVersion | quote }}
+app.kubernetes.io/managed-by: {{ .Release.Service }}
+helm.sh/chart: {{ printf "%s-%s" .Chart.Name .Chart.Version }}
+{{- end -}}
+
+{{/*
+Image of a component, tagged with the chart appVersion unless overridden
+*/}}
+{{- define "keda.image" -}}
+{{- $image := index .context.Values.image .component -}}
+{{- printf "%s/%s:%s" $image.registry $image.repository (default .context.Chart.AppVersion $image.tag) -}}
+{{- end -}}
+
+{{/*
+Scheduling constraints shared by the pods of the chart
+*/}}
+{{- define "keda.scheduling" -}}
+{{- with .Values.priorityClassName }}
+priorityClassName: {{ . }}
+{{- end }}
+{{- with .Values.nodeSelector }}
+nodeSelector:
+  {{- toYaml . | nindent 2 }}
+{{- end }}
+{{- with .Values.tolerations }}
+tolerations:
+  {{- toYaml . | nindent 2 }}
+{{- end }}
+{{- with .Values.affinity }}
+affinity:
+  {{- toYaml . | nindent 2 }}
+{{- end }}
+{{- end -}}
@@ -0,0 +1,132 @@
+{{/*
+Expand the name of the chart.
+*/}}
+{{- define "nvidia-device-plugin.name" -}}
+{{- default .Chart.Name .Values.nameOverride | trunc 63 | trimSuffix "-" }}
+{{- end }}
+
+{{/*
+Create a default fully qualified app name.
+We truncate at 63 chars because some Kubernetes name fields are limited to this (by the DNS naming spec).
+If release name contains chart name it will be used as a full name.
+*/}}
+{{- define "nvidia-device-plugin.fullname" -}}
+{{- if .Values.fullnameOverride }}
+{{- .Values.fullnameOverride | trunc 63 | trimSuffix "-" }}
+{{- else }}
+{{- $name := default .Chart.Name .Values.nameOverride }}
+{{- if contains $name .Release.Name }}
+{{- .Release.Name | trunc 63 | trimSuffix "-" }}
+{{- else }}
+{{- printf "%s-%s" .Release.Name $name | trunc 63 | trimSuffix "-" }}
+{{- end }}
+{{- end }}
+{{- end }}
+
+{{/*
+Allow the release namespace to be overridden for multi-namespace deployments in combined charts
+*/}}
+{{- define "nvidia-device-plugin.namespace" -}}
+  {{- if .Values.namespaceOverride -}}
+    {{- .Values.namespaceOverride -}}
+  {{- else -}}
+    {{- .Release.Namespace -}}
+  {{- end -}}
+{{- end -}}
+
+{{/*
+Create chart name and version as used by the chart label.
+*/}}
+{{- define "nvidia-device-plugin.chart" -}}
+{{- printf "%s-%s" .Chart.Name .Chart.Version | replace "+" "_" | trunc 63 | trimSuffix "-" }}
+{{- end }}
+
+{{/*
+Common labels
+*/}}
+{{- define "nvidia-device-plugin.labels" -}}
+helm.sh/chart: {{ include "nvidia-device-plugin.chart" . }}
+{{ include "nvidia-device-plugin.templateLabels" . }}
+{{- if .Chart.AppVersion }}
+app.kubernetes.io/version: {{ .Chart.AppVersion | quote }}
+{{- end }}
+app.kubernetes.io/managed-by: {{ .Release.Service }}
+{{- end }}
+
+{{/*
+Template labels
+*/}}
+{{- define "nvidia-device-plugin.templateLabels" -}}
+app.kubernetes.io/name: {{ include "nvidia-device-plugin.name" . }}
+app.kubernetes.io/instance: {{ .Release.Name }}
+{{- if .Values.selectorLabelsOverride }}
+{{ toYaml .Values.selectorLabelsOverride }}
+{{- end }}
+{{- end }}
+
+{{/*
+Selector labels
+*/}}
+{{- define "nvidia-device-plugin.selectorLabels" -}}
+{{- if .Values.selectorLabelsOverride -}}
+{{ toYaml .Values.selectorLabelsOverride }}
+{{- else -}}
+{{ include "nvidia-device-plugin.templateLabels" . }}
+{{- end }}
+{{- end }}
+
+{{/*
+Full image name with tag
+*/}}
+{{- define "nvidia-device-plugin.fullimage" -}}
+{{- $tag := printf "v%s" .Chart.AppVersion }}
+{{- .Values.image.repository -}}:{{- .Values.image.tag | default $tag -}}
+{{- end }}
+
+{{/*
+Check if there is a ConfigMap in use or not
+*/}}
+{{- define "nvidia-device-plugin.hasConfigMap" -}}
+{{- $result := false -}}
+{{- if ne (include "nvidia-device-plugin.hasEmbeddedConfigMap" .) "false" -}}
+  {{- $result = true -}}
+{{- else if ne (include "nvidia-device-plugin.hasExternalConfigMap" .) "false" -}}
+  {{- $result = true -}}
+{{- end -}}
+{{- $result -}}
+{{- end }}
+
+{{/*
+Check if there is an embedded ConfigMap in use or not
+*/}}
+{{- define "nvidia-device-plugin.hasEmbeddedConfigMap" -}}
+{{- $result := false -}}
+{{- if .Values.config.map -}}
+  {{- $result = true -}}
+{{- end -}}
+{{- $result -}}
+{{- end }}
+
+{{/*
+Check if there is an external ConfigMap in use or not
+*/}}
+{{- define "nvidia-device-plugin.hasExternalConfigMap" -}}
+{{- $result := false -}}
+{{- if .Values.config.name -}}
+  {{- $result = true -}}
+{{- end -}}
+{{- $result -}}
+{{- end }}
+
+{{/*
+Get the name of the ConfigMap to use
+*/}}
+{{- define "nvidia-device-plugin.configMapName" -}}
+{{- $result := "" -}}
+{{- if ne (include "nvidia-device-plugin.hasEmbeddedConfigMap" .) "false" -}}
+  {{- $result = printf "%s-%s" (include "nvidia-device-plugin.fullname" .) "configs" -}}
+{{- else if ne (include "nvidia-device-plugin.hasExternalConfigMap" .) "false" -}}
+  {{- $result = .Values.config.name -}}
+{{- end -}}
+{{- $result -}}
+{{- end }}
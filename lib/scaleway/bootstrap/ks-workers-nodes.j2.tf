@@ -27,6 +27,7 @@ resource "scaleway_k8s_pool" "kubernetes_cluster_workers_{{ loop.index }}" {
   lifecycle {
     create_before_destroy = true
   }
-  tags          =  concat(local.tags_ks_list, ["QoveryNodeGroupName:{{ scw_ks_worker_node.name }}", "QoveryNodeGroupId:${var.kubernetes_cluster_id}_{{ scw_ks_worker_node.instance_type }}_{{ loop.index }}"])
+  # Kapsule sets tags prefixed by `noprefix=` as labels and the ones prefixed by `taint=` as taints of the nodes
+  tags          =  concat(local.tags_ks_list, ["QoveryNodeGroupName:{{ scw_ks_worker_node.name }}", "QoveryNodeGroupId:${var.kubernetes_cluster_id}_{{ scw_ks_worker_node.instance_type }}_{{ loop.index }}"{% for key, value in scw_ks_worker_node.labels %}, "noprefix={{ key }}={{ value }}"{% endfor %}{% for taint in scw_ks_worker_node.taints %}, "taint={{ taint.key }}={{ taint.value }}:{{ taint.effect }}"{% endfor %}])
 }
 {% endfor %}
@@ -0,0 +1,455 @@
+//! Runs the engine outside of the Qovery control plane, from the environment engine request it would have sent.
+//! i.e: `qovery-engine-cli deploy --payload request.json --kubeconfig ~/.kube/config`
+//! The janitor command reports the resources of the cloud account of a cluster left by deleted clusters and
+//! environments, from the cluster engine request and the existing owners exported from the Qovery API.
+
+use qovery_engine::cmd::docker::Docker;
+use qovery_engine::deployment_report::obfuscation_service::{ObfuscationService, StdObfuscationService};
+use qovery_engine::encryption::check_workspace_encryption_key;
+use qovery_engine::engine_task::environment_task::EnvironmentTask;
+use qovery_engine::engine_task::qovery_api::FakeQoveryApi;
+use qovery_engine::engine_task::Task;
+use qovery_engine::events::{EngineEvent, EventMessageVerbosity};
+use qovery_engine::io_models::context::{Context, Metadata};
+use qovery_engine::io_models::engine_request::{EnvironmentEngineRequest, InfrastructureEngineRequest};
+use qovery_engine::io_models::Action;
+use qovery_engine::janitor::{run_cluster_janitor, ExistingOwners, JanitorPolicy, JanitorReport};
+use qovery_engine::lib_archive::{fetch_lib_directory, lib_cache_dir};
+use qovery_engine::logger::Logger;
+use qovery_engine::metrics_registry::StdMetricsRegistry;
+use qovery_engine::msg_publisher::StdMsgPublisher;
+use std::fs::File;
+use std::io::BufReader;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::{env, fs};
+use url::Url;
+
+const USAGE: &str = "Usage: qovery-engine-cli <plan|deploy|delete> --payload <request.json> [options]
+       qovery-engine-cli janitor --payload <cluster-request.json> --owners <owners.json> [--apply]
+
+Commands:
+  plan      Lint the environment and dry run its deployment, nothing is applied on the cluster
+  deploy    Build and deploy the environment
+  delete    Delete the environment
+  janitor   Report the resources of the cloud account of the cluster left by deleted clusters and environments
+
+Options:
+  --payload <path>         Environment engine request, or cluster engine request for janitor, as sent by the control plane
+  --owners <path>          Clusters and environments of the organization that still exist, exported from the Qovery API
+                           as {\"cluster_ids\": [...], \"environment_ids\": [...]}
+  --apply                  Delete the resources reported by janitor, instead of only listing them
+  --kubeconfig <path>      Kubeconfig of the cluster, instead of the one stored in its object storage
+  --workspace <path>       Workspace root directory [env: WORKSPACE_ROOT_DIR, default: /tmp/qovery-engine]
+  --lib-dir <path>         Lib root directory holding the charts and terraform files [env: LIB_ROOT_DIR, default: lib]
+  --lib-archive-url <url>  Base url of the release lib archives, to use instead of the lib directory [env: LIB_ARCHIVE_URL]
+  --lib-digest <sha256>    Digest of the lib archive to fetch from `--lib-archive-url` [env: LIB_DIGEST]
+  --max-parallel-builds <n>  Maximum number of services built at the same time, below the one of the environment
+  --verbose                Output the internal logs of the engine too
+
+Environment:
+  WORKSPACE_ENCRYPTION_KEY  Base64 encoded 256 bits key encrypting the secrets written in the workspace, required by
+                            plan, deploy and delete";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CliCommand {
+    Plan,
+    Deploy,
+    Delete,
+    Janitor,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct CliArgs {
+    command: CliCommand,
+    payload_path: PathBuf,
+    kubeconfig_path: Option<PathBuf>,
+    workspace_root_dir: String,
+    lib_root_dir: String,
+    lib_archive: Option<(Url, String)>,
+    max_parallel_builds: Option<NonZeroUsize>,
+    owners_path: Option<PathBuf>,
+    apply: bool,
+    verbose: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<CliArgs, String> {
+    let command = match args.next().as_deref() {
+        Some("plan") => CliCommand::Plan,
+        Some("deploy") => CliCommand::Deploy,
+        Some("delete") => CliCommand::Delete,
+        Some("janitor") => CliCommand::Janitor,
+        Some(command) => return Err(format!("unknown command `{command}`")),
+        None => return Err("missing command".to_string()),
+    };
+
+    let mut payload_path = None;
+    let mut kubeconfig_path = None;
+    let mut workspace_root_dir = env::var("WORKSPACE_ROOT_DIR").unwrap_or_else(|_| "/tmp/qovery-engine".to_string());
+    let mut lib_root_dir = env::var("LIB_ROOT_DIR").unwrap_or_else(|_| "lib".to_string());
+    let mut lib_archive_url = env::var("LIB_ARCHIVE_URL").ok();
+    let mut lib_digest = env::var("LIB_DIGEST").ok();
+    let mut max_parallel_builds = None;
+    let mut owners_path = None;
+    let mut apply = false;
+    let mut verbose = false;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("missing value of `{name}`"));
+        match arg.as_str() {
+            "--payload" => payload_path = Some(PathBuf::from(value(&arg)?)),
+            "--kubeconfig" => kubeconfig_path = Some(PathBuf::from(value(&arg)?)),
+            "--workspace" => workspace_root_dir = value(&arg)?,
+            "--lib-dir" => lib_root_dir = value(&arg)?,
+            "--lib-archive-url" => lib_archive_url = Some(value(&arg)?),
+            "--lib-digest" => lib_digest = Some(value(&arg)?),
+            "--max-parallel-builds" => {
+                let max = value(&arg)?;
+                max_parallel_builds = Some(
+                    max.parse::<NonZeroUsize>()
+                        .map_err(|_| format!("invalid value of `{arg}`: {max}"))?,
+                )
+            }
+            "--owners" => owners_path = Some(PathBuf::from(value(&arg)?)),
+            "--apply" => apply = true,
+            "--verbose" => verbose = true,
+            _ => return Err(format!("unknown option `{arg}`")),
+        }
+    }
+
+    let lib_archive = match (lib_archive_url, lib_digest) {
+        (Some(url), Some(digest)) => Some((
+            Url::parse(&url).map_err(|err| format!("invalid value of `--lib-archive-url`: {err}"))?,
+            digest,
+        )),
+        (None, None) => None,
+        _ => return Err("`--lib-archive-url` and `--lib-digest` must be given together".to_string()),
+    };
+    if command == CliCommand::Janitor && owners_path.is_none() {
+        return Err("missing `--owners`".to_string());
+    }
+    if command != CliCommand::Janitor && (owners_path.is_some() || apply) {
+        return Err("`--owners` and `--apply` are only used by janitor".to_string());
+    }
+
+    Ok(CliArgs {
+        command,
+        payload_path: payload_path.ok_or_else(|| "missing `--payload`".to_string())?,
+        kubeconfig_path,
+        workspace_root_dir,
+        lib_root_dir,
+        lib_archive,
+        max_parallel_builds,
+        owners_path,
+        apply,
+        verbose,
+    })
+}
+
+/// Prints the events of the deployment as they come, and remembers whether one of them is an error
+struct CliLogger {
+    has_errors: Arc<AtomicBool>,
+    obfuscation_service: Box<dyn ObfuscationService>,
+}
+
+impl Logger for CliLogger {
+    fn log(&self, mut event: EngineEvent) {
+        if !event.get_details().stage().is_core_output() {
+            event.obfuscate(|txt| self.obfuscation_service.obfuscate_secrets(txt));
+        }
+
+        let (icon, verbosity) = match &event {
+            EngineEvent::Debug(_, _) => return,
+            EngineEvent::Info(_, _) => ("  ", EventMessageVerbosity::SafeOnly),
+            EngineEvent::Warning(_, _) => ("⚠️", EventMessageVerbosity::SafeOnly),
+            EngineEvent::Error(_, _) => {
+                self.has_errors.store(true, Ordering::Relaxed);
+                ("❌", EventMessageVerbosity::FullDetailsWithoutEnvVars)
+            }
+        };
+        let details = event.get_details();
+        println!(
+            "{} {} [{}] {}: {}",
+            chrono::Utc::now().format("%H:%M:%S"),
+            icon,
+            details.stage().sub_step_name(),
+            details.transmitter(),
+            event.message(verbosity)
+        );
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Logger> {
+        Box::new(CliLogger {
+            has_errors: self.has_errors.clone(),
+            obfuscation_service: self.obfuscation_service.clone_dyn(),
+        })
+    }
+
+    fn with_secrets(&self, secrets: Vec<String>) -> Box<dyn Logger> {
+        Box::new(CliLogger {
+            has_errors: self.has_errors.clone(),
+            obfuscation_service: self.obfuscation_service.with_secrets(secrets),
+        })
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(name: &str, path: &Path) -> Result<T, String> {
+    let file = File::open(path).map_err(|err| format!("cannot open {name} {}: {err}", path.display()))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|err| format!("invalid {name} {}: {err}", path.display()))
+}
+
+fn load_request(args: &CliArgs) -> Result<EnvironmentEngineRequest, String> {
+    let mut request: EnvironmentEngineRequest = read_json("payload", &args.payload_path)?;
+
+    if let Some(kubeconfig_path) = &args.kubeconfig_path {
+        let kubeconfig = fs::read_to_string(kubeconfig_path)
+            .map_err(|err| format!("cannot read kubeconfig {}: {err}", kubeconfig_path.display()))?;
+        request.kubernetes.kubeconfig = Some(kubeconfig);
+    }
+
+    let action = match args.command {
+        CliCommand::Plan | CliCommand::Deploy | CliCommand::Janitor => Action::Create,
+        CliCommand::Delete => Action::Delete,
+    };
+    request.action = action.clone();
+    request.target_environment.action = action;
+
+    if args.command == CliCommand::Plan {
+        let mut metadata = request
+            .metadata
+            .take()
+            .unwrap_or_else(|| Metadata::new(None, None, None, None));
+        metadata.dry_run_deploy = Some(true);
+        request.metadata = Some(metadata);
+    }
+
+    Ok(request)
+}
+
+fn docker() -> Result<Docker, String> {
+    let docker_host = env::var("DOCKER_HOST").ok().and_then(|host| Url::parse(&host).ok());
+    Docker::new_with_local_builder(docker_host).map_err(|err| format!("cannot use docker: {err}"))
+}
+
+/// Lists the orphan resources of the cloud account of the cluster, only deleting them with `--apply`
+fn janitor(args: &CliArgs) -> Result<JanitorReport, String> {
+    let request: InfrastructureEngineRequest = read_json("payload", &args.payload_path)?;
+    let owners: ExistingOwners = match &args.owners_path {
+        Some(owners_path) => read_json("owners", owners_path)?,
+        None => return Err("missing `--owners`".to_string()),
+    };
+    let context = Context::new(
+        request.organization_long_id,
+        request.kubernetes.long_id,
+        request.id.to_string(),
+        args.workspace_root_dir.clone(),
+        args.lib_root_dir.clone(),
+        request.test_cluster,
+        request.features.clone(),
+        request.metadata.clone(),
+        Arc::new(docker()?),
+        Arc::new(FakeQoveryApi {}),
+        request.event_details(),
+    );
+    let policy = JanitorPolicy {
+        dry_run: !args.apply,
+        ..Default::default()
+    };
+
+    run_cluster_janitor(&request, &context, &owners, &policy).map_err(|err| err.to_string())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_max_level(if args.verbose {
+            tracing::Level::INFO
+        } else {
+            tracing::Level::ERROR
+        })
+        .with_writer(std::io::stderr)
+        .init();
+
+    if args.command == CliCommand::Janitor {
+        return match janitor(&args) {
+            Ok(report) => {
+                print!("🧹 {report}");
+                if report.failed.is_empty() {
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::FAILURE
+                }
+            }
+            Err(err) => {
+                eprintln!("❌ {err}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    // secrets of the environment are written encrypted in the workspace
+    if let Err(err) = check_workspace_encryption_key() {
+        eprintln!("❌ {err}");
+        return ExitCode::FAILURE;
+    }
+
+    let request = match load_request(&args) {
+        Ok(request) => request,
+        Err(err) => {
+            eprintln!("❌ {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.command == CliCommand::Plan {
+        let lint_warnings = request.target_environment.lint(None);
+        println!("🔎 {} lint warning(s)", lint_warnings.len());
+        for warning in lint_warnings {
+            println!("⚠️ [{}] {}: {}", warning.rule, warning.service_name, warning.message);
+        }
+    }
+
+    let lib_root_dir = match &args.lib_archive {
+        Some((archive_base_url, digest)) => {
+            match fetch_lib_directory(archive_base_url, digest, &lib_cache_dir(&args.workspace_root_dir)) {
+                Ok(lib_root_dir) => lib_root_dir.to_string_lossy().to_string(),
+                Err(err) => {
+                    eprintln!("❌ cannot fetch lib archive {digest}: {err}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        None => args.lib_root_dir,
+    };
+
+    let docker = match docker() {
+        Ok(docker) => docker,
+        Err(err) => {
+            eprintln!("❌ {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let has_errors = Arc::new(AtomicBool::new(false));
+    let logger = CliLogger {
+        has_errors: has_errors.clone(),
+        obfuscation_service: Box::new(StdObfuscationService::new(vec![])),
+    };
+    let task = EnvironmentTask::new(
+        request,
+        args.workspace_root_dir,
+        lib_root_dir,
+        Arc::new(docker),
+        Box::new(logger),
+        Box::new(StdMetricsRegistry::new(Box::new(StdMsgPublisher::new()))),
+        Box::new(FakeQoveryApi {}),
+    )
+    .with_max_parallel_builds(args.max_parallel_builds);
+    task.run();
+
+    if has_errors.load(Ordering::Relaxed) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<CliArgs, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cli_args = args(&[
+            "plan",
+            "--payload",
+            "request.json",
+            "--kubeconfig",
+            "kubeconfig.yaml",
+            "--workspace",
+            "/tmp/workspace",
+            "--lib-dir",
+            "/tmp/lib",
+            "--max-parallel-builds",
+            "4",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli_args,
+            CliArgs {
+                command: CliCommand::Plan,
+                payload_path: PathBuf::from("request.json"),
+                kubeconfig_path: Some(PathBuf::from("kubeconfig.yaml")),
+                workspace_root_dir: "/tmp/workspace".to_string(),
+                lib_root_dir: "/tmp/lib".to_string(),
+                lib_archive: None,
+                max_parallel_builds: NonZeroUsize::new(4),
+                owners_path: None,
+                apply: false,
+                verbose: false,
+            }
+        );
+
+        assert_eq!(
+            args(&["delete", "--payload", "request.json", "--verbose"])
+                .unwrap()
+                .command,
+            CliCommand::Delete
+        );
+        assert!(args(&["deploy"]).is_err());
+        assert!(args(&["deploy", "--payload"]).is_err());
+        assert!(args(&["deploy", "--payload", "request.json", "--max-parallel-builds", "0"]).is_err());
+        assert!(args(&["upgrade", "--payload", "request.json"]).is_err());
+
+        let cli_args = args(&[
+            "deploy",
+            "--payload",
+            "request.json",
+            "--lib-archive-url",
+            "https://releases.example.com/lib/",
+            "--lib-digest",
+            "abc123",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli_args.lib_archive,
+            Some((Url::parse("https://releases.example.com/lib/").unwrap(), "abc123".to_string()))
+        );
+        assert!(args(&["deploy", "--payload", "request.json", "--lib-digest", "abc123"]).is_err());
+
+        // janitor only reports the orphans unless asked to delete them
+        let cli_args = args(&["janitor", "--payload", "cluster.json", "--owners", "owners.json"]).unwrap();
+        assert_eq!(cli_args.command, CliCommand::Janitor);
+        assert_eq!(cli_args.owners_path, Some(PathBuf::from("owners.json")));
+        assert!(!cli_args.apply);
+        assert!(
+            args(&[
+                "janitor",
+                "--payload",
+                "cluster.json",
+                "--owners",
+                "owners.json",
+                "--apply"
+            ])
+            .unwrap()
+            .apply
+        );
+        assert!(args(&["janitor", "--payload", "cluster.json"]).is_err());
+        assert!(args(&["deploy", "--payload", "request.json", "--apply"]).is_err());
+        assert!(args(&["deploy", "--payload", "request.json", "--owners", "owners.json"]).is_err());
+        assert!(args(&[]).is_err());
+    }
+}
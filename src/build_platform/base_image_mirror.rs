@@ -0,0 +1,262 @@
+use crate::cmd::docker::ContainerImage;
+use std::collections::HashSet;
+use url::Url;
+
+const DOCKER_HUB_REGISTRY: &str = "docker.io";
+// Tags are limited to 128 characters by the registries
+const MAX_TAG_LENGTH: usize = 128;
+
+/// Image referenced by a `FROM` instruction of a Dockerfile
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseImage {
+    /// Reference as written in the Dockerfile, i.e: `node:18-alpine`
+    pub reference: String,
+    /// Registry host, `docker.io` when omitted
+    pub registry: String,
+    /// Repository in the registry, i.e: `library/node`
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl BaseImage {
+    /// Parses an image reference, following the rules docker uses to find the registry host
+    pub fn parse(reference: &str) -> Option<BaseImage> {
+        if reference.is_empty() || reference.contains('$') || reference.eq_ignore_ascii_case("scratch") {
+            return None;
+        }
+
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) => (name, Some(digest.to_string())),
+            None => (reference, None),
+        };
+        // a colon after the last slash separates the tag, before it is the port of the registry
+        let (name, tag) = match name.rfind(':') {
+            Some(index) if !name[index..].contains('/') => (&name[..index], Some(name[index + 1..].to_string())),
+            _ => (name, None),
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, path)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+                (host.to_string(), path.to_string())
+            }
+            Some(_) => (DOCKER_HUB_REGISTRY.to_string(), name.to_string()),
+            None => (DOCKER_HUB_REGISTRY.to_string(), format!("library/{name}")),
+        };
+        let registry = match registry.as_str() {
+            "index.docker.io" | "registry-1.docker.io" => DOCKER_HUB_REGISTRY.to_string(),
+            _ => registry,
+        };
+
+        Some(BaseImage {
+            reference: reference.to_string(),
+            registry,
+            repository: repository.to_lowercase(),
+            tag,
+            digest,
+        })
+    }
+
+    pub fn to_container_image(&self) -> Option<ContainerImage> {
+        let registry = Url::parse(&format!("https://{}", self.registry)).ok()?;
+        Some(match &self.digest {
+            Some(digest) => ContainerImage::new_for_digest(registry, self.repository.clone(), digest.clone()),
+            None => ContainerImage::new(
+                registry,
+                self.repository.clone(),
+                vec![self.tag.clone().unwrap_or_else(|| "latest".to_string())],
+            ),
+        })
+    }
+
+    /// Tag of the image once mirrored, every base image of the cluster living in the same repository
+    fn mirror_tag(&self) -> String {
+        let version = match (&self.digest, &self.tag) {
+            (Some(digest), _) => digest.replace(':', "-"),
+            (None, Some(tag)) => tag.clone(),
+            (None, None) => "latest".to_string(),
+        };
+        let tag: String = format!("{}-{}-{}", self.registry, self.repository, version)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        if tag.len() <= MAX_TAG_LENGTH {
+            tag
+        } else {
+            // keep the end of the tag, that is the most specific part
+            tag[tag.len() - MAX_TAG_LENGTH..]
+                .trim_start_matches(['.', '-'])
+                .to_string()
+        }
+    }
+}
+
+/// Base images the Dockerfile pulls, build stages referenced by their alias being excluded
+pub fn extract_dockerfile_base_images(dockerfile_content: &str) -> Vec<BaseImage> {
+    let mut stages: HashSet<String> = HashSet::new();
+    let mut base_images: Vec<BaseImage> = vec![];
+
+    for line in dockerfile_content.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if !words.first().is_some_and(|word| word.eq_ignore_ascii_case("FROM")) {
+            continue;
+        }
+
+        let mut args = words[1..].iter().filter(|word| !word.starts_with("--"));
+        let Some(reference) = args.next() else {
+            continue;
+        };
+        let is_stage = stages.contains(&reference.to_lowercase());
+        if let (Some(as_keyword), Some(alias)) = (args.next(), args.next()) {
+            if as_keyword.eq_ignore_ascii_case("AS") {
+                stages.insert(alias.to_lowercase());
+            }
+        }
+
+        if is_stage {
+            continue;
+        }
+        if let Some(base_image) = BaseImage::parse(reference) {
+            if !base_images.contains(&base_image) {
+                base_images.push(base_image);
+            }
+        }
+    }
+
+    base_images
+}
+
+/// Cluster registry repository the public base images of the builds are copied into, so builds only pull them
+/// from the public registries once instead of on every build, and are not stopped by their rate limits
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseImageMirror {
+    pub registry_url: Url,
+    pub image_name: String,
+    /// Public registries whose images are mirrored, i.e: `docker.io`
+    pub source_registries: Vec<String>,
+}
+
+impl BaseImageMirror {
+    pub fn is_mirrored(&self, base_image: &BaseImage) -> bool {
+        self.source_registries
+            .iter()
+            .any(|registry| registry.eq_ignore_ascii_case(&base_image.registry))
+    }
+
+    pub fn mirrored_image(&self, base_image: &BaseImage) -> ContainerImage {
+        ContainerImage::new(
+            self.registry_url.clone(),
+            self.image_name.clone(),
+            vec![base_image.mirror_tag()],
+        )
+    }
+}
+
+/// Points the `FROM` instructions to the mirrored images, keeping their flags and stage alias
+pub fn rewrite_dockerfile_base_images(dockerfile_content: &str, mirrored: &[(BaseImage, ContainerImage)]) -> String {
+    let mut content: Vec<String> = dockerfile_content
+        .lines()
+        .map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if !words.first().is_some_and(|word| word.eq_ignore_ascii_case("FROM")) {
+                return line.to_string();
+            }
+
+            words
+                .iter()
+                .enumerate()
+                .map(|(index, word)| {
+                    let is_reference =
+                        index > 0 && !word.starts_with("--") && words[1..index].iter().all(|w| w.starts_with("--"));
+                    match mirrored
+                        .iter()
+                        .find(|(base_image, _)| is_reference && base_image.reference == *word)
+                    {
+                        Some((_, mirrored_image)) => mirrored_image.image_name(),
+                        None => word.to_string(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+
+    if dockerfile_content.ends_with('\n') {
+        content.push(String::new());
+    }
+    content.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_base_image() {
+        let node = BaseImage::parse("node:18-alpine").unwrap();
+        assert_eq!(node.registry, "docker.io");
+        assert_eq!(node.repository, "library/node");
+        assert_eq!(node.tag.as_deref(), Some("18-alpine"));
+
+        let quay = BaseImage::parse("quay.io/prometheus/busybox@sha256:abcd").unwrap();
+        assert_eq!(quay.registry, "quay.io");
+        assert_eq!(quay.repository, "prometheus/busybox");
+        assert_eq!(quay.tag, None);
+        assert_eq!(quay.digest.as_deref(), Some("sha256:abcd"));
+
+        let private = BaseImage::parse("registry.local:5000/team/app").unwrap();
+        assert_eq!(private.registry, "registry.local:5000");
+        assert_eq!(private.repository, "team/app");
+        assert_eq!(private.tag, None);
+
+        assert_eq!(BaseImage::parse("bitnami/redis").unwrap().repository, "bitnami/redis");
+        assert!(BaseImage::parse("scratch").is_none());
+        assert!(BaseImage::parse("node:${NODE_VERSION}").is_none());
+    }
+
+    #[test]
+    fn test_extract_dockerfile_base_images() {
+        let dockerfile = "
+        FROM --platform=$BUILDPLATFORM node:18 AS builder
+        RUN npm ci
+        FROM builder AS test
+        FROM quay.io/prometheus/busybox:latest
+        COPY --from=builder /app /app
+        FROM node:18
+        ";
+
+        let base_images = extract_dockerfile_base_images(dockerfile);
+        assert_eq!(
+            base_images
+                .iter()
+                .map(|image| image.reference.as_str())
+                .collect::<Vec<_>>(),
+            vec!["node:18", "quay.io/prometheus/busybox:latest"]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_dockerfile_base_images() {
+        let mirror = BaseImageMirror {
+            registry_url: Url::parse("https://123.dkr.ecr.eu-west-3.amazonaws.com").unwrap(),
+            image_name: "qovery-base-images".to_string(),
+            source_registries: vec!["docker.io".to_string()],
+        };
+        let node = BaseImage::parse("node:18").unwrap();
+        assert!(mirror.is_mirrored(&node));
+        assert!(!mirror.is_mirrored(&BaseImage::parse("quay.io/prometheus/busybox").unwrap()));
+
+        let dockerfile = "FROM --platform=linux/amd64 node:18 AS builder\nRUN echo node:18\n";
+        assert_eq!(
+            rewrite_dockerfile_base_images(dockerfile, &[(node.clone(), mirror.mirrored_image(&node))]),
+            "FROM --platform=linux/amd64 123.dkr.ecr.eu-west-3.amazonaws.com/qovery-base-images:docker.io-library_node-18 AS builder\nRUN echo node:18\n"
+        );
+    }
+}
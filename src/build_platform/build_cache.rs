@@ -0,0 +1,137 @@
+use std::fs;
+use std::fs::File;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use uuid::Uuid;
+
+use crate::cmd::docker::LocalCache;
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::ObjectStorage;
+
+/// BuildKit cache of a service kept in the object storage of the cluster, so it outlives the builders and the engine
+/// runs. The build exports its cache in a local directory, which is archived and uploaded once the build is done.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildCache {
+    pub bucket_name: String,
+    pub object_key: String,
+    pub local_cache: LocalCache,
+    dir: PathBuf,
+}
+
+impl BuildCache {
+    pub fn new(bucket_name: String, service_long_id: &Uuid, dir: PathBuf) -> Self {
+        BuildCache {
+            bucket_name,
+            object_key: format!("build-cache/{service_long_id}.tar.gz"),
+            local_cache: LocalCache {
+                from: None,
+                to: dir.join("export"),
+            },
+            dir,
+        }
+    }
+
+    /// Downloads and extracts the cache of the previous builds, returning whether there was one
+    pub fn import(&mut self, object_storage: &dyn ObjectStorage) -> Result<bool, Error> {
+        let exists = object_storage
+            .list_objects(&self.bucket_name, Some(&self.object_key))
+            .map_err(to_io_error)?
+            .filter_map(|object| object.ok())
+            .any(|object| object.key == self.object_key);
+        if !exists {
+            return Ok(false);
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let archive_path = self.dir.join("import.tar.gz");
+        object_storage
+            .get_stream(&self.bucket_name, &self.object_key, &mut File::create(&archive_path)?)
+            .map_err(to_io_error)?;
+
+        let import_dir = self.dir.join("import");
+        extract_archive(&archive_path, &import_dir)?;
+        let _ = fs::remove_file(&archive_path);
+        self.local_cache.from = Some(import_dir);
+
+        Ok(true)
+    }
+
+    /// Archives and uploads the cache exported by the build, returning its size.
+    /// Nothing is uploaded when the build did not run, i.e: the image already existed.
+    pub fn export(&self, object_storage: &dyn ObjectStorage) -> Result<Option<u64>, Error> {
+        if !self.local_cache.to.is_dir() {
+            return Ok(None);
+        }
+
+        let archive_path = self.dir.join("export.tar.gz");
+        archive_directory(&self.local_cache.to, &archive_path)?;
+        let size = object_storage
+            .put_stream(&self.bucket_name, &self.object_key, &mut File::open(&archive_path)?)
+            .map_err(to_io_error)?;
+        let _ = fs::remove_file(&archive_path);
+
+        Ok(Some(size))
+    }
+}
+
+fn to_io_error(err: ObjectStorageError) -> Error {
+    Error::new(ErrorKind::Other, err)
+}
+
+// cache layers are already compressed, a fast compression is enough for the index and manifests
+fn archive_directory(dir: &Path, archive_path: &Path) -> Result<(), Error> {
+    let mut tar = tar::Builder::new(GzEncoder::new(File::create(archive_path)?, Compression::fast()));
+    tar.append_dir_all(".", dir)?;
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn extract_archive(archive_path: &Path, dir: &Path) -> Result<(), Error> {
+    // a stale cache would be mixed with the downloaded one
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    tar::Archive::new(GzDecoder::new(File::open(archive_path)?)).unpack(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let tmp_dir = TempDir::new().unwrap();
+        let cache_dir = tmp_dir.path().join("cache");
+        fs::create_dir_all(cache_dir.join("blobs/sha256")).unwrap();
+        fs::write(cache_dir.join("index.json"), "{}").unwrap();
+        fs::write(cache_dir.join("blobs/sha256/42"), "layer").unwrap();
+
+        let archive_path = tmp_dir.path().join("cache.tar.gz");
+        archive_directory(&cache_dir, &archive_path).unwrap();
+
+        let extracted_dir = tmp_dir.path().join("extracted");
+        fs::create_dir_all(&extracted_dir).unwrap();
+        fs::write(extracted_dir.join("stale"), "stale").unwrap();
+        extract_archive(&archive_path, &extracted_dir).unwrap();
+
+        assert_eq!(fs::read_to_string(extracted_dir.join("index.json")).unwrap(), "{}");
+        assert_eq!(fs::read_to_string(extracted_dir.join("blobs/sha256/42")).unwrap(), "layer");
+        assert!(!extracted_dir.join("stale").exists());
+    }
+
+    #[test]
+    fn test_build_cache_paths() {
+        let service_long_id = Uuid::new_v4();
+        let build_cache = BuildCache::new("my-bucket".to_string(), &service_long_id, PathBuf::from("/tmp/cache"));
+
+        assert_eq!(build_cache.object_key, format!("build-cache/{service_long_id}.tar.gz"));
+        assert_eq!(build_cache.local_cache.from, None);
+        assert_eq!(build_cache.local_cache.to, PathBuf::from("/tmp/cache/export"));
+    }
+}
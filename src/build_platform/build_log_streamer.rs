@@ -0,0 +1,136 @@
+use std::mem;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Lines of the output of a build are sent together, at most at this interval
+pub const BUILD_LOG_STREAM_INTERVAL: Duration = Duration::from_millis(500);
+/// Lines are sent without waiting for the interval once there are this many pending, to keep the events small
+pub const BUILD_LOG_STREAM_MAX_LINES: usize = 100;
+
+struct PendingLines {
+    lines: Vec<String>,
+    last_sent_at: Instant,
+}
+
+/// Streams the output of a build to its listeners while it runs. Lines are batched, so a verbose build does not send
+/// an event for each one of them, and sent as soon as the interval is over, even if the build stays silent, as long
+/// as `tick` is called, i.e: from the cancel check of the command, polled while the build is waiting for output.
+pub struct BuildLogStreamer<'a> {
+    send: Box<dyn Fn(String) + 'a>,
+    now: Box<dyn Fn() -> Instant + 'a>,
+    interval: Duration,
+    max_lines: usize,
+    pending: Mutex<PendingLines>,
+}
+
+impl<'a> BuildLogStreamer<'a> {
+    pub fn new(send: impl Fn(String) + 'a, interval: Duration, max_lines: usize) -> Self {
+        BuildLogStreamer {
+            send: Box::new(send),
+            now: Box::new(Instant::now),
+            interval,
+            max_lines,
+            pending: Mutex::new(PendingLines {
+                lines: Vec::with_capacity(max_lines),
+                last_sent_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Measures the interval with the given clock instead of the system one
+    pub fn with_clock(mut self, now: impl Fn() -> Instant + 'a) -> Self {
+        self.now = Box::new(now);
+        if let Ok(pending) = self.pending.get_mut() {
+            pending.last_sent_at = (self.now)();
+        }
+        self
+    }
+
+    fn is_interval_over(&self, pending: &PendingLines) -> bool {
+        (self.now)().duration_since(pending.last_sent_at) >= self.interval
+    }
+
+    pub fn push(&self, line: String) {
+        let lines = {
+            let Ok(mut pending) = self.pending.lock() else {
+                return (self.send)(line);
+            };
+            pending.lines.push(line);
+            if pending.lines.len() < self.max_lines && !self.is_interval_over(&pending) {
+                return;
+            }
+            self.take_lines(&mut pending)
+        };
+        (self.send)(lines.join("\n"));
+    }
+
+    /// Sends the pending lines if they have waited for the whole interval
+    pub fn tick(&self) {
+        let lines = {
+            let Ok(mut pending) = self.pending.lock() else {
+                return;
+            };
+            if pending.lines.is_empty() || !self.is_interval_over(&pending) {
+                return;
+            }
+            self.take_lines(&mut pending)
+        };
+        (self.send)(lines.join("\n"));
+    }
+
+    pub fn flush(&self) {
+        let lines = match self.pending.lock() {
+            Ok(mut pending) if !pending.lines.is_empty() => self.take_lines(&mut pending),
+            _ => return,
+        };
+        (self.send)(lines.join("\n"));
+    }
+
+    fn take_lines(&self, pending: &mut PendingLines) -> Vec<String> {
+        pending.last_sent_at = (self.now)();
+        mem::take(&mut pending.lines)
+    }
+}
+
+impl Drop for BuildLogStreamer<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn test_build_log_streamer() {
+        let sent = RefCell::new(vec![]);
+        let now = Cell::new(Instant::now());
+        {
+            let streamer = BuildLogStreamer::new(|lines| sent.borrow_mut().push(lines), Duration::from_millis(200), 3)
+                .with_clock(|| now.get());
+
+            // lines are batched until there are enough of them
+            streamer.push("step 1".to_string());
+            streamer.push("step 2".to_string());
+            assert!(sent.borrow().is_empty());
+            streamer.push("step 3".to_string());
+            assert_eq!(*sent.borrow(), vec!["step 1\nstep 2\nstep 3".to_string()]);
+
+            // or until the interval is over, even without new lines
+            streamer.push("step 4".to_string());
+            now.set(now.get() + Duration::from_millis(199));
+            streamer.tick();
+            assert_eq!(sent.borrow().len(), 1);
+            now.set(now.get() + Duration::from_millis(1));
+            streamer.tick();
+            assert_eq!(sent.borrow().last(), Some(&"step 4".to_string()));
+
+            // the remaining lines are sent once the build is over
+            streamer.push("step 5".to_string());
+        }
+        assert_eq!(sent.borrow().last(), Some(&"step 5".to_string()));
+        assert_eq!(sent.borrow().len(), 3);
+    }
+}
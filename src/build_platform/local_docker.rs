@@ -11,17 +11,27 @@ use retry::delay::Fibonacci;
 use sysinfo::{DiskExt, RefreshKind, SystemExt};
 use uuid::Uuid;
 
+use crate::build_platform::base_image_mirror::{
+    extract_dockerfile_base_images, rewrite_dockerfile_base_images, BaseImage, BaseImageMirror,
+};
+use crate::build_platform::build_log_streamer::{
+    BuildLogStreamer, BUILD_LOG_STREAM_INTERVAL, BUILD_LOG_STREAM_MAX_LINES,
+};
 use crate::build_platform::dockerfile_utils::extract_dockerfile_args;
-use crate::build_platform::{to_build_error, Build, BuildError, BuildPlatform, Kind};
+use crate::build_platform::{to_build_error, Build, BuildError, BuildPlatform, CloningRepositoryErrorCause, Kind};
 use crate::cmd::command::CommandError::Killed;
 use crate::cmd::command::{CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::cosign::Cosign;
 use crate::cmd::docker::{Architecture, ContainerImage};
 use crate::cmd::git_lfs::{GitLfs, GitLfsError};
+use crate::cmd::syft::Syft;
 use crate::cmd::{command, docker};
 use crate::deployment_report::logger::EnvLogger;
 
+use crate::events::EventMessage;
 use crate::fs::workspace_directory;
 use crate::git;
+use crate::git::CloneError;
 use crate::io_models::container::Registry;
 use crate::io_models::context::Context;
 use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
@@ -114,6 +124,63 @@ impl LocalDocker {
         }
     }
 
+    /// Copy the public base images of the Dockerfile into the cluster registry, and build from there.
+    /// Best effort: a base image that cannot be mirrored is still pulled from its public registry.
+    fn mirror_base_images(
+        &self,
+        base_image_mirror: &BaseImageMirror,
+        dockerfile_content: &[u8],
+        dockerfile_complete_path: &str,
+        logger: &EnvLogger,
+        is_task_canceled: &dyn Fn() -> bool,
+    ) {
+        let Ok(dockerfile_content) = std::str::from_utf8(dockerfile_content) else {
+            return;
+        };
+
+        let mut mirrored_images: Vec<(BaseImage, ContainerImage)> = vec![];
+        for base_image in extract_dockerfile_base_images(dockerfile_content) {
+            if !base_image_mirror.is_mirrored(&base_image) {
+                continue;
+            }
+            let Some(source_image) = base_image.to_container_image() else {
+                continue;
+            };
+
+            let mirrored_image = base_image_mirror.mirrored_image(&base_image);
+            if let Ok(true) = self.context.docker.does_image_exist_remotely(&mirrored_image) {
+                mirrored_images.push((base_image, mirrored_image));
+                continue;
+            }
+
+            logger.send_progress(format!(
+                "🪞 Mirroring base image {} into the cluster registry",
+                base_image.reference
+            ));
+            match self.context.docker.mirror(
+                &source_image,
+                &mirrored_image,
+                &mut |line| info!("{}", line),
+                &mut |line| warn!("{}", line),
+                &CommandKiller::from(Duration::from_secs(60 * 9), is_task_canceled),
+            ) {
+                Ok(_) => mirrored_images.push((base_image, mirrored_image)),
+                Err(err) => logger.send_warning(format!(
+                    "Cannot mirror base image {}, pulling it from its registry: {}",
+                    base_image.reference, err
+                )),
+            }
+        }
+
+        if mirrored_images.is_empty() {
+            return;
+        }
+        let dockerfile = rewrite_dockerfile_base_images(dockerfile_content, &mirrored_images);
+        if let Err(err) = fs::write(dockerfile_complete_path, dockerfile) {
+            logger.send_warning(format!("Cannot use mirrored base images: {err}"));
+        }
+    }
+
     fn build_image_with_docker(
         &self,
         build: &mut Build,
@@ -132,7 +199,7 @@ impl LocalDocker {
             action_description: "reading dockerfile content".to_string(),
             raw_error: err,
         })?;
-        let dockerfile_args = match extract_dockerfile_args(dockerfile_content) {
+        let dockerfile_args = match extract_dockerfile_args(dockerfile_content.clone()) {
             Ok(dockerfile_args) => dockerfile_args,
             Err(err) => {
                 build_record.stop(StepStatus::Error);
@@ -210,30 +277,53 @@ impl LocalDocker {
             }
         }
 
+        if let Some(base_image_mirror) = &build.base_image_mirror {
+            self.mirror_base_images(
+                base_image_mirror,
+                &dockerfile_content,
+                dockerfile_complete_path,
+                logger,
+                is_task_canceled,
+            );
+        }
+
         // Actually do the build of the image
         let env_vars: Vec<(&str, &str)> = build
             .environment_variables
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_str()))
             .collect();
+        let secrets: Vec<(&str, &str)> = build.secrets.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
 
         let arch: Vec<Architecture> = build
             .architectures
             .iter()
             .map(|arch| docker::Architecture::from(arch))
             .collect();
+        let log_streamer = BuildLogStreamer::new(
+            |lines| logger.send_progress_message(EventMessage::new_from_safe(lines)),
+            BUILD_LOG_STREAM_INTERVAL,
+            BUILD_LOG_STREAM_MAX_LINES,
+        );
+        let is_task_canceled = || {
+            log_streamer.tick();
+            is_task_canceled()
+        };
         let exit_status = self.context.docker.build(
             Path::new(dockerfile_complete_path),
             Path::new(into_dir_docker_style),
             &image_to_build,
             &env_vars,
+            &secrets,
             &image_cache,
+            build.cache.as_ref().map(|cache| &cache.local_cache),
             true,
             &arch,
-            &mut |line| logger.send_progress(line),
-            &mut |line| logger.send_progress(line),
-            &CommandKiller::from(build.timeout, is_task_canceled),
+            &mut |line| log_streamer.push(logger.prefixed(&line)),
+            &mut |line| log_streamer.push(logger.prefixed(&line)),
+            &CommandKiller::from(build.timeout, &is_task_canceled),
         );
+        log_streamer.flush();
 
         if let Err(err) = exit_status {
             build_record.stop(StepStatus::Error);
@@ -274,8 +364,9 @@ impl LocalDocker {
 
             buildpacks_args.extend(vec!["--path", into_dir_docker_style]);
 
-            let mut args_buffer = Vec::with_capacity(build.environment_variables.len());
-            for (key, value) in &build.environment_variables {
+            // buildpacks have no secret mounts, the build secrets are only given to the build environment
+            let mut args_buffer = Vec::with_capacity(build.environment_variables.len() + build.secrets.len());
+            for (key, value) in build.environment_variables.iter().chain(&build.secrets) {
                 args_buffer.push("--env".to_string());
                 args_buffer.push(format!("{key}={value}"));
             }
@@ -333,12 +424,22 @@ impl LocalDocker {
             // buildpacks build
             let mut cmd = QoveryCommand::new("pack", &buildpacks_args, &self.get_docker_host_envs());
             cmd.set_kill_grace_period(Duration::from_secs(0));
-            let cmd_killer = CommandKiller::from(build.timeout, is_task_canceled);
+            let log_streamer = BuildLogStreamer::new(
+                |lines| logger.send_progress_message(EventMessage::new_from_safe(lines)),
+                BUILD_LOG_STREAM_INTERVAL,
+                BUILD_LOG_STREAM_MAX_LINES,
+            );
+            let is_task_canceled = || {
+                log_streamer.tick();
+                is_task_canceled()
+            };
+            let cmd_killer = CommandKiller::from(build.timeout, &is_task_canceled);
             exit_status = cmd.exec_with_abort(
-                &mut |line| logger.send_progress(line),
-                &mut |line| logger.send_progress(line),
+                &mut |line| log_streamer.push(logger.prefixed(&line)),
+                &mut |line| log_streamer.push(logger.prefixed(&line)),
                 &cmd_killer,
             );
+            log_streamer.flush();
 
             if exit_status.is_ok() {
                 // quit now if the builder successfully build the app
@@ -358,6 +459,82 @@ impl LocalDocker {
         }
     }
 
+    /// Signs the pushed image with the signing key of the cluster, if any.
+    /// Images already signed, i.e: skipped builds of an existing image, are not signed again.
+    fn sign_image(
+        &self,
+        build: &Build,
+        logger: &EnvLogger,
+        is_task_canceled: &dyn Fn() -> bool,
+    ) -> Result<(), BuildError> {
+        let Some(image_signing_key) = &build.image_signing_key else {
+            return Ok(());
+        };
+
+        let image = ContainerImage::new(
+            build.image.registry_url.clone(),
+            build.image.name(),
+            vec![build.image.tag.clone()],
+        );
+        let cosign = Cosign::new(image_signing_key.clone(), self.context.docker.config_path());
+        let cmd_killer = CommandKiller::from(build.timeout, is_task_canceled);
+        if cosign.verify(&image, &cmd_killer).is_ok() {
+            logger.send_progress(format!("🔏 Image {} is already signed", image.image_name()));
+            return Ok(());
+        }
+
+        logger.send_progress(format!("🔏 Signing image {}", image.image_name()));
+        match cosign.sign(&image, &cmd_killer) {
+            Ok(_) => Ok(()),
+            Err(err) if err.is_aborted() => Err(BuildError::Aborted {
+                application: build.image.service_id.clone(),
+            }),
+            Err(err) => Err(BuildError::ImageSigningError {
+                application: build.image.service_id.clone(),
+                raw_error: err,
+            }),
+        }
+    }
+
+    /// Generates the SBOM of the pushed image, to be uploaded once the builds are done.
+    /// The deployment goes on without it if it cannot be generated.
+    fn generate_sbom(
+        &self,
+        build: &Build,
+        logger: &EnvLogger,
+        is_task_canceled: &dyn Fn() -> bool,
+    ) -> Result<(), BuildError> {
+        let Some(sbom) = &build.sbom else {
+            return Ok(());
+        };
+
+        let image = ContainerImage::new(
+            build.image.registry_url.clone(),
+            build.image.name(),
+            vec![build.image.tag.clone()],
+        );
+        if let Some(sbom_dir) = sbom.path.parent() {
+            if let Err(err) = fs::create_dir_all(sbom_dir) {
+                logger.send_warning(format!("⚠️ Cannot generate SBOM, its directory cannot be created: {err}"));
+                return Ok(());
+            }
+        }
+
+        logger.send_progress(format!("📜 Generating {} SBOM of image {}", sbom.format, image.image_name()));
+        let syft = Syft::new(self.context.docker.config_path());
+        let cmd_killer = CommandKiller::from(build.timeout, is_task_canceled);
+        match syft.generate_sbom(&image, sbom.format.syft_output(), &sbom.path, &cmd_killer) {
+            Ok(_) => Ok(()),
+            Err(err) if err.is_aborted() => Err(BuildError::Aborted {
+                application: build.image.service_id.clone(),
+            }),
+            Err(err) => {
+                logger.send_warning(format!("⚠️ Cannot generate SBOM of image {}: {err}", image.image_name()));
+                Ok(())
+            }
+        }
+    }
+
     fn get_repository_build_root_path(&self, build: &Build) -> Result<PathBuf, BuildError> {
         workspace_directory(
             self.context.workspace_root_dir(),
@@ -453,16 +630,23 @@ impl BuildPlatform for LocalDocker {
         // Do the real git clone
         let git_clone_record =
             metrics_registry.start_record(build.image.service_long_id, StepLabel::Service, StepName::GitClone);
-        if let Err(clone_error) = git::clone_at_commit(
+        if let Err(clone_error) = git::clone_at_commit_with_submodules(
             &build.git_repository.url,
             &build.git_repository.commit_id,
             &repository_root_path,
             &get_credentials,
+            build.git_repository.recursive_submodules,
         ) {
             git_clone_record.stop(StepStatus::Error);
-            return Err(BuildError::GitError {
+            let cause = match &clone_error {
+                CloneError::Fetch(_) => CloningRepositoryErrorCause::Fetch,
+                CloneError::Checkout(_) => CloningRepositoryErrorCause::Checkout,
+                CloneError::Submodule { .. } => CloningRepositoryErrorCause::Submodule,
+            };
+            return Err(BuildError::CloningRepositoryError {
                 application: build.image.service_id.clone(),
-                raw_error: clone_error,
+                cause,
+                raw_error_message: clone_error.to_string(),
             });
         }
         git_clone_record.stop(StepStatus::Success);
@@ -485,47 +669,33 @@ impl BuildPlatform for LocalDocker {
         let app_id = build.image.service_id.clone();
 
         // Fetch git-lfs/big files for the repository if necessary
-        let git_lfs = if let Some(creds) = git_user_creds {
-            GitLfs::new(creds.login, creds.password)
-        } else {
-            GitLfs::default()
-        };
-        let cmd_killer = CommandKiller::from_cancelable(is_task_canceled);
-        let size_estimate_kb = git_lfs
-            .files_size_estimate_in_kb(&repository_root_path, &build.git_repository.commit_id, &cmd_killer)
-            .unwrap_or(0);
-
-        if size_estimate_kb > 0 {
-            if size_estimate_kb > MAX_GIT_LFS_SIZE_KB {
-                return Err(BuildError::InvalidConfig {
-                    application: app_id,
-                    raw_error_message: format!(
-                        "GIT LFS files size are too big and are over the max allowed size of {MAX_GIT_LFS_SIZE_GB} GB"
-                    ),
-                });
-            }
-
-            info!("fetching git-lfs files");
-            logger.send_progress("🗜️ Fetching git-lfs files for repository".to_string());
-            match git_lfs.checkout_files_for_commit(&repository_root_path, &build.git_repository.commit_id, &cmd_killer)
-            {
-                Ok(_) => {}
-                Err(GitLfsError::Aborted { .. }) => return Err(BuildError::Aborted { application: app_id }),
-                Err(GitLfsError::Timeout { .. }) => return Err(BuildError::Aborted { application: app_id }),
-                Err(GitLfsError::ExecutionError { raw_error }) => {
-                    return Err(BuildError::IoError {
-                        application: app_id,
-                        action_description: "git lfs checkout".to_string(),
-                        raw_error,
-                    })
-                }
-                Err(GitLfsError::ExitStatusError { .. }) => {
-                    return Err(BuildError::IoError {
+        if build.git_repository.lfs {
+            let git_lfs = if let Some(creds) = git_user_creds {
+                GitLfs::new(creds.login, creds.password)
+            } else {
+                GitLfs::default()
+            };
+            let cmd_killer = CommandKiller::from_cancelable(is_task_canceled);
+            let size_estimate_kb = git_lfs
+                .files_size_estimate_in_kb(&repository_root_path, &build.git_repository.commit_id, &cmd_killer)
+                .map_err(|err| to_lfs_build_error(app_id.clone(), err))?;
+
+            if size_estimate_kb > 0 {
+                if size_estimate_kb > MAX_GIT_LFS_SIZE_KB {
+                    return Err(BuildError::CloningRepositoryError {
                         application: app_id,
-                        action_description: "git lfs checkout".to_string(),
-                        raw_error: Error::new(ErrorKind::Other, "git lfs checkout failed"),
-                    })
+                        cause: CloningRepositoryErrorCause::LfsTooLarge,
+                        raw_error_message: format!(
+                            "GIT LFS files size are too big and are over the max allowed size of {MAX_GIT_LFS_SIZE_GB} GB"
+                        ),
+                    });
                 }
+
+                info!("fetching git-lfs files");
+                logger.send_progress("🗜️ Fetching git-lfs files for repository".to_string());
+                git_lfs
+                    .checkout_files_for_commit(&repository_root_path, &build.git_repository.commit_id, &cmd_killer)
+                    .map_err(|err| to_lfs_build_error(app_id.clone(), err))?;
             }
         }
 
@@ -558,7 +728,7 @@ impl BuildPlatform for LocalDocker {
 
         // now we have to decide if we use buildpack or docker to build our application
         // If no Dockerfile specified, we should use BuildPacks
-        if let Some(dockerfile_path) = &build.git_repository.dockerfile_path {
+        let build_result = if let Some(dockerfile_path) = &build.git_repository.dockerfile_path {
             // build container from the provided Dockerfile
 
             let dockerfile_absolute_path = repository_root_path.join(dockerfile_path);
@@ -599,6 +769,23 @@ impl BuildPlatform for LocalDocker {
                 StepStatus::Error
             });
             build_result
+        };
+        build_result?;
+
+        self.sign_image(build, logger, is_task_canceled)?;
+        self.generate_sbom(build, logger, is_task_canceled)
+    }
+}
+
+fn to_lfs_build_error(application: String, err: GitLfsError) -> BuildError {
+    match err {
+        GitLfsError::Aborted { .. } | GitLfsError::Timeout { .. } => BuildError::Aborted { application },
+        GitLfsError::ExecutionError { .. } | GitLfsError::ExitStatusError { .. } => {
+            BuildError::CloningRepositoryError {
+                application,
+                cause: CloningRepositoryErrorCause::Lfs,
+                raw_error_message: err.to_string(),
+            }
         }
     }
 }
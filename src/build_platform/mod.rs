@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::build_platform::base_image_mirror::BaseImageMirror;
+use crate::build_platform::build_cache::BuildCache;
+use crate::build_platform::sbom::Sbom;
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
 use crate::cmd::command::CommandError;
+use crate::cmd::cosign::CosignError;
 use crate::cmd::docker::DockerError;
 use crate::deployment_report::logger::EnvLogger;
 use crate::errors::EngineError;
@@ -20,8 +24,12 @@ use std::time::Duration;
 use url::Url;
 use uuid::Uuid;
 
+pub mod base_image_mirror;
+pub mod build_cache;
+pub mod build_log_streamer;
 pub mod dockerfile_utils;
 pub mod local_docker;
+pub mod sbom;
 
 #[derive(thiserror::Error, Debug)]
 pub enum BuildError {
@@ -31,10 +39,11 @@ pub enum BuildError {
         raw_error_message: String,
     },
 
-    #[error("Cannot build Application {application:?} due to an error with git: {raw_error:?}")]
-    GitError {
+    #[error("Cannot clone repository of Application {application:?} ({cause:?}): {raw_error_message:?}")]
+    CloningRepositoryError {
         application: String,
-        raw_error: git2::Error,
+        cause: CloningRepositoryErrorCause,
+        raw_error_message: String,
     },
 
     #[error("Build of Application {application:?} have been aborted at user request")]
@@ -58,6 +67,44 @@ pub enum BuildError {
         application: String,
         raw_error: CommandError,
     },
+
+    #[error("Cannot sign image of Application {application:?}: {raw_error:?}")]
+    ImageSigningError {
+        application: String,
+        raw_error: CosignError,
+    },
+}
+
+/// Step of the clone of the repository of a service which failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloningRepositoryErrorCause {
+    Fetch,
+    Checkout,
+    Submodule,
+    Lfs,
+    LfsTooLarge,
+}
+
+impl CloningRepositoryErrorCause {
+    pub fn hint(&self) -> &'static str {
+        match self {
+            CloningRepositoryErrorCause::Fetch => {
+                "Ensure the repository exists and that the git provider has granted access to it."
+            }
+            CloningRepositoryErrorCause::Checkout => {
+                "Ensure the commit still exists in the repository, it may have been removed by a force push."
+            }
+            CloningRepositoryErrorCause::Submodule => {
+                "Ensure the submodules use https urls and are reachable with the credentials of the repository."
+            }
+            CloningRepositoryErrorCause::Lfs => {
+                "Ensure the LFS objects have been pushed to the LFS server, or disable LFS for this service."
+            }
+            CloningRepositoryErrorCause::LfsTooLarge => {
+                "Reduce the size of the LFS objects of the commit, or disable LFS for this service."
+            }
+        }
+    }
 }
 
 pub fn to_build_error(service_id: String, err: DockerError) -> BuildError {
@@ -97,6 +144,8 @@ pub struct Build {
     pub git_repository: GitRepository,
     pub image: Image,
     pub environment_variables: BTreeMap<String, String>,
+    // mounted with BuildKit `--secret`, they are never passed as build args nor stored in the image layers
+    pub secrets: BTreeMap<String, String>,
     pub disable_cache: bool,
     pub timeout: Duration,
     pub architectures: Vec<CpuArchitecture>,
@@ -104,6 +153,16 @@ pub struct Build {
     pub max_ram_in_gib: u32,
     // registries used by the build where we need to login to pull image
     pub registries: Vec<Registry>,
+    // where public base images are copied before the build, to not pull them from their registry
+    pub base_image_mirror: Option<BaseImageMirror>,
+    // cosign key the image is signed with once pushed
+    pub image_signing_key: Option<String>,
+    // cache persisted in the object storage of the cluster between builds
+    pub cache: Option<BuildCache>,
+    // the persisted cache is not imported, the one of this build replacing it
+    pub bust_cache: bool,
+    // software bill of materials generated from the pushed image
+    pub sbom: Option<Sbom>,
 }
 
 impl Build {
@@ -119,6 +178,14 @@ impl Build {
     pub fn use_buildpacks(&self) -> bool {
         self.git_repository.dockerfile_path.is_none()
     }
+
+    /// Log in with the cluster Docker Hub account first, so registries of the user logged in after take precedence
+    pub fn with_cluster_docker_hub_registry(mut self, registry: Option<Registry>) -> Self {
+        if let Some(registry) = registry {
+            self.registries.insert(0, registry);
+        }
+        self
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -148,6 +215,8 @@ pub struct GitRepository {
     pub dockerfile_path: Option<PathBuf>,
     pub root_path: PathBuf,
     pub buildpack_language: Option<String>,
+    pub recursive_submodules: bool,
+    pub lfs: bool,
 }
 impl GitRepository {
     fn credentials(&self) -> Option<anyhow::Result<Credentials>> {
@@ -0,0 +1,111 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+
+use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::object_storage::ObjectStorage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SbomFormat {
+    CycloneDx,
+    Spdx,
+}
+
+impl SbomFormat {
+    pub fn syft_output(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cyclonedx-json",
+            SbomFormat::Spdx => "spdx-json",
+        }
+    }
+
+    fn file_extension(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+        }
+    }
+}
+
+impl Display for SbomFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SbomFormat::CycloneDx => write!(f, "CycloneDX"),
+            SbomFormat::Spdx => write!(f, "SPDX"),
+        }
+    }
+}
+
+/// Software bill of materials of the image built for a deployment, generated once the image is pushed.
+/// It is kept in the object storage of the cluster, one per service and deployment, to be audited later on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sbom {
+    pub format: SbomFormat,
+    pub bucket_name: String,
+    pub object_key: String,
+    pub path: PathBuf,
+}
+
+impl Sbom {
+    pub fn new(
+        format: SbomFormat,
+        bucket_name: String,
+        service_long_id: &Uuid,
+        execution_id: &str,
+        dir: PathBuf,
+    ) -> Self {
+        Sbom {
+            format,
+            bucket_name,
+            object_key: format!("sbom/{service_long_id}/{execution_id}.{}", format.file_extension()),
+            path: dir.join(format!("sbom.{}", format.file_extension())),
+        }
+    }
+
+    /// Where the SBOM is uploaded, i.e: `my-bucket/sbom/<service>/<execution>.cdx.json`
+    pub fn location(&self) -> String {
+        format!("{}/{}", self.bucket_name, self.object_key)
+    }
+
+    /// Uploads the generated SBOM, returning its size. Nothing is uploaded when it was not generated.
+    pub fn upload(&self, object_storage: &dyn ObjectStorage) -> Result<Option<u64>, Error> {
+        if !self.path.is_file() {
+            return Ok(None);
+        }
+
+        let size = object_storage
+            .put_stream(&self.bucket_name, &self.object_key, &mut File::open(&self.path)?)
+            .map_err(|err| Error::new(ErrorKind::Other, err))?;
+
+        Ok(Some(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sbom_paths() {
+        let service_long_id = Uuid::new_v4();
+        let sbom = Sbom::new(
+            SbomFormat::CycloneDx,
+            "my-bucket".to_string(),
+            &service_long_id,
+            "my-execution",
+            PathBuf::from("/tmp/sbom"),
+        );
+
+        assert_eq!(sbom.object_key, format!("sbom/{service_long_id}/my-execution.cdx.json"));
+        assert_eq!(
+            sbom.location(),
+            format!("my-bucket/sbom/{service_long_id}/my-execution.cdx.json")
+        );
+        assert_eq!(sbom.path, PathBuf::from("/tmp/sbom/sbom.cdx.json"));
+        assert_eq!(serde_json::from_str::<SbomFormat>(r#""spdx""#).unwrap(), SbomFormat::Spdx);
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use aws_sdk_ec2::model::{Filter, NetworkInterfaceStatus, VolumeState};
+use aws_types::SdkConfig;
+use chrono::{DateTime, TimeZone, Utc};
+use rusoto_core::credential::StaticProvider;
+use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
+use rusoto_s3::{GetBucketLocationRequest, GetBucketTaggingRequest, S3Client, S3 as RusotoS3};
+
+use crate::cloud_provider::aws::models::QoveryAwsSdkConfigLoadBalancer;
+use crate::cloud_provider::aws::regions::AwsRegion;
+use crate::cloud_provider::CloudProvider;
+use crate::janitor::{CloudResource, CloudResourceKind, CloudResourceScanner, JanitorError};
+use crate::models::ToCloudProviderFormat;
+use crate::object_storage::s3::S3;
+use crate::object_storage::{BucketDeleteStrategy, ObjectStorage};
+use crate::runtime::block_on;
+
+// tags set by terraform on the resources of the engine, and by kubernetes on the ones of the clusters
+const OWNER_TAG_KEYS: [&str; 3] = ["ClusterId", "cluster_id", "kubernetes.io/cluster/qovery-*"];
+
+/// Load balancers, detached volumes and network interfaces, snapshots and buckets of an AWS account region
+pub struct AwsResourceScanner {
+    sdk_config: SdkConfig,
+    access_key_id: String,
+    secret_access_key: String,
+    region: AwsRegion,
+}
+
+impl AwsResourceScanner {
+    pub fn new(cloud_provider: &dyn CloudProvider) -> Option<AwsResourceScanner> {
+        Some(AwsResourceScanner {
+            sdk_config: cloud_provider.aws_sdk_client()?,
+            access_key_id: cloud_provider.access_key_id(),
+            secret_access_key: cloud_provider.secret_access_key(),
+            region: AwsRegion::from_str(&cloud_provider.region()).ok()?,
+        })
+    }
+
+    fn s3_client(&self) -> S3Client {
+        let client = Client::new_with(
+            StaticProvider::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None),
+            HttpClient::new().expect("unable to create new Http client"),
+        );
+
+        S3Client::new_with_client(
+            client,
+            RusotoRegion::from_str(self.region.to_cloud_provider_format()).unwrap_or_default(),
+        )
+    }
+
+    fn ec2_client(&self) -> aws_sdk_ec2::Client {
+        aws_sdk_ec2::Client::new(&self.sdk_config)
+    }
+
+    fn owner_tags_filter() -> Filter {
+        Filter::builder()
+            .name("tag-key")
+            .set_values(Some(OWNER_TAG_KEYS.iter().map(|key| key.to_string()).collect()))
+            .build()
+    }
+
+    async fn list_load_balancers(&self) -> Result<Vec<CloudResource>, String> {
+        let load_balancers = self
+            .sdk_config
+            .list_all_aws_load_balancers()
+            .await
+            .map_err(|e| e.to_string())?
+            .load_balancers
+            .unwrap_or_default();
+        let created_at: HashMap<String, Option<DateTime<Utc>>> = load_balancers
+            .iter()
+            .filter_map(|lb| {
+                let arn = lb.load_balancer_arn()?.to_string();
+                Some((arn, lb.created_time().and_then(|date| to_utc(date.secs()))))
+            })
+            .collect();
+
+        let tags = self
+            .sdk_config
+            .get_aws_load_balancers_tags(load_balancers)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(tags
+            .into_iter()
+            .filter_map(|tag_description| {
+                let arn = tag_description.resource_arn()?.to_string();
+                Some(CloudResource {
+                    kind: CloudResourceKind::LoadBalancer,
+                    created_at: created_at.get(&arn).copied().flatten(),
+                    id: arn,
+                    tags: tag_description
+                        .tags()
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|tag| Some((tag.key()?.to_string(), tag.value().unwrap_or_default().to_string())))
+                        .collect(),
+                })
+            })
+            .collect())
+    }
+
+    async fn list_volumes(&self) -> Result<Vec<CloudResource>, String> {
+        let client = self.ec2_client();
+        let mut resources = vec![];
+        let mut next_token: Option<String> = None;
+        loop {
+            let output = client
+                .describe_volumes()
+                .filters(Self::owner_tags_filter())
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // attached volumes are still in use, whatever their tags
+            resources.extend(
+                output
+                    .volumes()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|volume| volume.state() == Some(&VolumeState::Available))
+                    .filter_map(|volume| {
+                        Some(CloudResource {
+                            kind: CloudResourceKind::Volume,
+                            id: volume.volume_id()?.to_string(),
+                            tags: ec2_tags(volume.tags()),
+                            created_at: volume.create_time().and_then(|date| to_utc(date.secs())),
+                        })
+                    }),
+            );
+
+            next_token = output.next_token().map(|token| token.to_string());
+            if next_token.is_none() {
+                return Ok(resources);
+            }
+        }
+    }
+
+    async fn list_snapshots(&self) -> Result<Vec<CloudResource>, String> {
+        let client = self.ec2_client();
+        let mut resources = vec![];
+        let mut next_token: Option<String> = None;
+        loop {
+            let output = client
+                .describe_snapshots()
+                .owner_ids("self")
+                .filters(Self::owner_tags_filter())
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            resources.extend(output.snapshots().unwrap_or_default().iter().filter_map(|snapshot| {
+                Some(CloudResource {
+                    kind: CloudResourceKind::Snapshot,
+                    id: snapshot.snapshot_id()?.to_string(),
+                    tags: ec2_tags(snapshot.tags()),
+                    created_at: snapshot.start_time().and_then(|date| to_utc(date.secs())),
+                })
+            }));
+
+            next_token = output.next_token().map(|token| token.to_string());
+            if next_token.is_none() {
+                return Ok(resources);
+            }
+        }
+    }
+
+    async fn list_network_interfaces(&self) -> Result<Vec<CloudResource>, String> {
+        let client = self.ec2_client();
+        let mut resources = vec![];
+        let mut next_token: Option<String> = None;
+        loop {
+            let output = client
+                .describe_network_interfaces()
+                .filters(Self::owner_tags_filter())
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // network interfaces attached to an instance are released with it
+            resources.extend(
+                output
+                    .network_interfaces()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|network_interface| network_interface.status() == Some(&NetworkInterfaceStatus::Available))
+                    .filter_map(|network_interface| {
+                        Some(CloudResource {
+                            kind: CloudResourceKind::NetworkInterface,
+                            id: network_interface.network_interface_id()?.to_string(),
+                            tags: ec2_tags(network_interface.tag_set()),
+                            created_at: None,
+                        })
+                    }),
+            );
+
+            next_token = output.next_token().map(|token| token.to_string());
+            if next_token.is_none() {
+                return Ok(resources);
+            }
+        }
+    }
+
+    async fn list_buckets(&self) -> Result<Vec<CloudResource>, String> {
+        let s3_client = self.s3_client();
+        let buckets = s3_client
+            .list_buckets()
+            .await
+            .map_err(|e| e.to_string())?
+            .buckets
+            .unwrap_or_default();
+
+        let mut resources = vec![];
+        for bucket in buckets {
+            let Some(bucket_name) = bucket.name else {
+                continue;
+            };
+
+            // buckets are listed for all regions, only the ones of the scanned region are considered
+            let location = s3_client
+                .get_bucket_location(GetBucketLocationRequest {
+                    bucket: bucket_name.clone(),
+                    expected_bucket_owner: None,
+                })
+                .await
+                .map_err(|e| e.to_string())?
+                .location_constraint
+                .filter(|location| !location.is_empty())
+                .unwrap_or_else(|| "us-east-1".to_string());
+            if location != self.region.to_cloud_provider_format() {
+                continue;
+            }
+
+            // buckets without tags answer with an error
+            let tags = match s3_client
+                .get_bucket_tagging(GetBucketTaggingRequest {
+                    bucket: bucket_name.clone(),
+                    expected_bucket_owner: None,
+                })
+                .await
+            {
+                Ok(output) => output.tag_set.into_iter().map(|tag| (tag.key, tag.value)).collect(),
+                Err(_) => continue,
+            };
+
+            resources.push(CloudResource {
+                kind: CloudResourceKind::Bucket,
+                id: bucket_name,
+                tags,
+                created_at: bucket
+                    .creation_date
+                    .and_then(|creation_date| DateTime::parse_from_rfc3339(&creation_date).ok())
+                    .map(|creation_date| creation_date.with_timezone(&Utc)),
+            });
+        }
+
+        Ok(resources)
+    }
+
+    async fn delete(&self, resource: &CloudResource) -> Result<(), String> {
+        let client = self.ec2_client();
+        match resource.kind {
+            CloudResourceKind::LoadBalancer => self
+                .sdk_config
+                .delete_aws_load_balancer(resource.id.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            CloudResourceKind::Volume => client
+                .delete_volume()
+                .volume_id(&resource.id)
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            CloudResourceKind::Snapshot => client
+                .delete_snapshot()
+                .snapshot_id(&resource.id)
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            CloudResourceKind::NetworkInterface => client
+                .delete_network_interface()
+                .network_interface_id(&resource.id)
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            CloudResourceKind::Bucket => Err("buckets are deleted with the object storage".to_string()),
+        }
+    }
+}
+
+fn ec2_tags(tags: Option<&[aws_sdk_ec2::model::Tag]>) -> HashMap<String, String> {
+    tags.unwrap_or_default()
+        .iter()
+        .filter_map(|tag| Some((tag.key()?.to_string(), tag.value().unwrap_or_default().to_string())))
+        .collect()
+}
+
+fn to_utc(secs: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(secs, 0).single()
+}
+
+impl CloudResourceScanner for AwsResourceScanner {
+    fn list_resources(&self) -> Result<Vec<CloudResource>, JanitorError> {
+        let mut resources = vec![];
+        for kind in [
+            CloudResourceKind::LoadBalancer,
+            CloudResourceKind::Volume,
+            CloudResourceKind::Snapshot,
+            CloudResourceKind::NetworkInterface,
+            CloudResourceKind::Bucket,
+        ] {
+            let listed = block_on(async {
+                match kind {
+                    CloudResourceKind::LoadBalancer => self.list_load_balancers().await,
+                    CloudResourceKind::Volume => self.list_volumes().await,
+                    CloudResourceKind::Snapshot => self.list_snapshots().await,
+                    CloudResourceKind::NetworkInterface => self.list_network_interfaces().await,
+                    CloudResourceKind::Bucket => self.list_buckets().await,
+                }
+            })
+            .map_err(|raw_error_message| JanitorError::CannotListResources {
+                kind,
+                raw_error_message,
+            })?;
+            resources.extend(listed);
+        }
+
+        Ok(resources)
+    }
+
+    fn delete_resource(&self, resource: &CloudResource) -> Result<(), JanitorError> {
+        let deleted = match resource.kind {
+            // buckets must be emptied first
+            CloudResourceKind::Bucket => S3::new(
+                "janitor".to_string(),
+                "janitor".to_string(),
+                self.access_key_id.clone(),
+                self.secret_access_key.clone(),
+                self.region.clone(),
+            )
+            .delete_bucket(&resource.id, BucketDeleteStrategy::HardDelete)
+            .map_err(|e| e.to_string()),
+            _ => block_on(self.delete(resource)),
+        };
+
+        deleted.map_err(|raw_error_message| JanitorError::CannotDeleteResource {
+            resource: resource.to_string(),
+            raw_error_message,
+        })
+    }
+}
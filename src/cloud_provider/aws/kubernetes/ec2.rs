@@ -22,6 +22,7 @@ use crate::io_models::context::Context;
 use crate::io_models::engine_request::{ChartValuesOverrideName, ChartValuesOverrideValues};
 use crate::logger::Logger;
 use crate::models::ToCloudProviderFormat;
+use crate::object_storage::retry::{RetryPolicy, RetryableObjectStorage};
 use crate::object_storage::s3::S3;
 use crate::object_storage::ObjectStorage;
 use crate::secret_manager::vault::QVaultClient;
@@ -58,7 +59,7 @@ pub struct EC2 {
     zones: Vec<AwsZone>,
     cloud_provider: Arc<dyn CloudProvider>,
     dns_provider: Arc<dyn DnsProvider>,
-    s3: S3,
+    s3: RetryableObjectStorage<S3>,
     template_directory: String,
     options: Options,
     instance: InstanceEc2,
@@ -487,6 +488,10 @@ impl Kubernetes for EC2 {
         &self.temp_dir
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.s3)
+    }
+
     /// Update the vault with the new cluster information
     /// !!! Can only work has been just updated with Terraform data !!!
     fn update_vault_config(
@@ -643,12 +648,15 @@ impl QoveryAwsSdkConfigEc2 for SdkConfig {
     }
 }
 
-pub fn mk_s3(region: &AwsRegion, cloud_provider: &dyn CloudProvider) -> S3 {
-    S3::new(
-        "s3-temp-id".to_string(),
-        "default-s3".to_string(),
-        cloud_provider.access_key_id(),
-        cloud_provider.secret_access_key(),
-        region.clone(),
+pub fn mk_s3(region: &AwsRegion, cloud_provider: &dyn CloudProvider) -> RetryableObjectStorage<S3> {
+    RetryableObjectStorage::new(
+        S3::new(
+            "s3-temp-id".to_string(),
+            "default-s3".to_string(),
+            cloud_provider.access_key_id(),
+            cloud_provider.secret_access_key(),
+            region.clone(),
+        ),
+        RetryPolicy::default(),
     )
 }
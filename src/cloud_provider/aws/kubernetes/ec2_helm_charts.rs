@@ -241,6 +241,7 @@ pub fn ec2_aws_helm_charts(
             .managed_dns_root_domain_helm_format
             .to_string(),
         chart_config_prerequisites.cluster_id.to_string(),
+        false, // EC2 clusters have no load balancer to publish
         UpdateStrategy::Recreate,
         false,
         HelmChartNamespaces::KubeSystem,
@@ -341,6 +342,13 @@ pub fn ec2_aws_helm_charts(
         None,
         HelmChartNamespaces::NginxIngress,
         None,
+        false,
+        chart_config_prerequisites.dns_provider_config.is_private_zone(),
+        chart_config_prerequisites.cluster_advanced_settings.nginx_waf_mode,
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .nginx_waf_custom_rules
+            .clone(),
     )
     .to_common_helm_chart()?;
 
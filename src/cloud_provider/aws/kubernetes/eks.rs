@@ -40,7 +40,9 @@ use crate::cloud_provider::aws::kubernetes::ec2::mk_s3;
 use crate::cloud_provider::kubeconfig_helper::{fetch_kubeconfig, write_kubeconfig_on_disk};
 use crate::cloud_provider::kubectl_utils::{check_workers_on_upgrade, delete_completed_jobs, delete_crashlooping_pods};
 use crate::models::ToCloudProviderFormat;
+use crate::object_storage::retry::RetryableObjectStorage;
 use crate::object_storage::s3::S3;
+use crate::object_storage::ObjectStorage;
 use base64::engine::general_purpose;
 use base64::Engine;
 use function_name::named;
@@ -69,7 +71,7 @@ pub struct EKS {
     zones: Vec<AwsZone>,
     cloud_provider: Arc<dyn CloudProvider>,
     dns_provider: Arc<dyn DnsProvider>,
-    s3: S3,
+    s3: RetryableObjectStorage<S3>,
     nodes_groups: Vec<NodeGroups>,
     template_directory: String,
     options: Options,
@@ -109,6 +111,9 @@ impl EKS {
             logger.log(EngineEvent::Error(*e.clone(), None));
             return Err(Box::new(*e));
         };
+        if let Err(reason) = options.kubelet_parameters.validate() {
+            return Err(Box::new(EngineError::new_invalid_kubelet_parameters(event_details, reason)));
+        }
         advanced_settings.validate(event_details.clone())?;
 
         let s3 = mk_s3(&region, &*cloud_provider);
@@ -171,6 +176,12 @@ impl EKS {
                     return Err(Box::new(err));
                 }
             }
+            if let Err(reason) = node_group.validate_labels_and_taints() {
+                return Err(Box::new(EngineError::new_invalid_node_group_labels_or_taints(
+                    event_details.clone(),
+                    reason,
+                )));
+            }
         }
         Ok(())
     }
@@ -669,6 +680,10 @@ impl Kubernetes for EKS {
         &self.temp_dir
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.s3)
+    }
+
     fn update_vault_config(
         &self,
         event_details: EventDetails,
@@ -743,6 +758,9 @@ impl NodeGroupsWithDesiredState {
             instance_type,
             disk_size_in_gib,
             instance_architecture: CpuArchitecture::AMD64,
+            labels: Default::default(),
+            taints: vec![],
+            gpu: false,
         }
     }
 }
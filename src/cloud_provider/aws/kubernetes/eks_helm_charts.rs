@@ -1,12 +1,17 @@
 use crate::cloud_provider::aws::kubernetes::helm_charts::karpenter::KarpenterChart;
+use crate::cloud_provider::aws::kubernetes::node_os::NodeOsImage;
 use crate::cloud_provider::aws::kubernetes::Options;
 use crate::cloud_provider::helm::{
     get_engine_helm_action_from_location, ChartInfo, ChartSetValue, CommonChart, HelmChart, HelmChartNamespaces,
     PriorityClass, UpdateStrategy,
 };
 use crate::cloud_provider::helm_charts::coredns_config_chart::CoreDNSConfigChart;
+use crate::cloud_provider::helm_charts::external_secrets_chart::ExternalSecretsChart;
+use crate::cloud_provider::helm_charts::external_secrets_configs_chart::ExternalSecretsConfigsChart;
 use crate::cloud_provider::helm_charts::k8s_event_logger::K8sEventLoggerChart;
+use crate::cloud_provider::helm_charts::keda_chart::KedaChart;
 use crate::cloud_provider::helm_charts::nginx_ingress_chart::NginxIngressChart;
+use crate::cloud_provider::helm_charts::nvidia_device_plugin_chart::NvidiaDevicePluginChart;
 use crate::cloud_provider::helm_charts::promtail_chart::PromtailChart;
 use crate::cloud_provider::helm_charts::qovery_shell_agent_chart::QoveryShellAgentChart;
 use crate::cloud_provider::helm_charts::qovery_storage_class_chart::{QoveryStorageClassChart, QoveryStorageType};
@@ -77,6 +82,9 @@ pub struct AwsEksQoveryTerraformConfig {
     pub loki_storage_config_aws_s3: String,
     pub karpenter_controller_aws_role_arn: String,
     pub cluster_security_group_id: String,
+    // empty when the cluster has no shared volumes
+    #[serde(default)]
+    pub aws_efs_file_system_id: String,
 }
 
 pub struct EksChartsConfigPrerequisites {
@@ -108,6 +116,7 @@ pub struct EksChartsConfigPrerequisites {
     pub infra_options: Options,
     pub cluster_advanced_settings: ClusterAdvancedSettings,
     pub disk_size_in_gib: Option<i32>,
+    pub gpu_node_groups: bool,
 }
 
 pub fn eks_aws_helm_charts(
@@ -141,7 +150,7 @@ pub fn eks_aws_helm_charts(
     let loki_kube_dns_name = format!("loki.{loki_namespace}.svc:3100");
 
     // Qovery storage class
-    let q_storage_class = QoveryStorageClassChart::new(
+    let mut q_storage_class = QoveryStorageClassChart::new(
         chart_prefix_path,
         Kind::Aws,
         HashSet::from_iter(vec![
@@ -153,6 +162,12 @@ pub fn eks_aws_helm_charts(
         HelmChartNamespaces::KubeSystem,
     )
     .to_common_helm_chart()?;
+    if !qovery_terraform_config.aws_efs_file_system_id.is_empty() {
+        q_storage_class.chart_info.values.push(ChartSetValue {
+            key: "efs.fileSystemId".to_string(),
+            value: qovery_terraform_config.aws_efs_file_system_id.clone(),
+        });
+    }
 
     // AWS IAM EKS user mapper
     let mut aws_iam_eks_user_mapper: Option<CommonChart> = None;
@@ -298,6 +313,9 @@ pub fn eks_aws_helm_charts(
             .managed_dns_root_domain_helm_format
             .to_string(),
         chart_config_prerequisites.cluster_id.to_string(),
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .network_enable_dual_stack,
         UpdateStrategy::RollingUpdate,
         true,
         HelmChartNamespaces::KubeSystem,
@@ -529,6 +547,15 @@ pub fn eks_aws_helm_charts(
         ),
         HelmChartNamespaces::NginxIngress,
         None,
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .network_enable_dual_stack,
+        chart_config_prerequisites.dns_provider_config.is_private_zone(),
+        chart_config_prerequisites.cluster_advanced_settings.nginx_waf_mode,
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .nginx_waf_custom_rules
+            .clone(),
     )
     .to_common_helm_chart()?;
 
@@ -716,13 +743,26 @@ pub fn eks_aws_helm_charts(
         level_5.push(Box::new(qovery_webhook));
     }
 
-    let level_6: Vec<Box<dyn HelmChart>> = vec![
+    let mut level_6: Vec<Box<dyn HelmChart>> = vec![
         Box::new(metrics_server),
         Box::new(aws_node_term_handler),
         Box::new(external_dns),
     ];
 
-    let level_7: Vec<Box<dyn HelmChart>> = vec![Box::new(nginx_ingress)];
+    let mut level_7: Vec<Box<dyn HelmChart>> = vec![Box::new(nginx_ingress)];
+    // secret stores need the CRDs of the external-secrets chart
+    if chart_config_prerequisites
+        .cluster_advanced_settings
+        .external_secrets_enabled
+    {
+        if let Some(external_secrets_configs) = ExternalSecretsConfigsChart::new(
+            chart_prefix_path,
+            HelmChartNamespaces::KubeSystem,
+            &chart_config_prerequisites.cluster_advanced_settings,
+        ) {
+            level_7.push(Box::new(external_secrets_configs.to_common_helm_chart()?));
+        }
+    }
 
     let level_8: Vec<Box<dyn HelmChart>> = vec![
         Box::new(cert_manager_config),
@@ -732,6 +772,29 @@ pub fn eks_aws_helm_charts(
         Box::new(k8s_event_logger),
     ];
 
+    if chart_config_prerequisites.cluster_advanced_settings.keda_enabled {
+        level_6.push(Box::new(
+            KedaChart::new(chart_prefix_path, HelmChartNamespaces::KubeSystem).to_common_helm_chart()?,
+        ));
+    }
+    if chart_config_prerequisites
+        .cluster_advanced_settings
+        .external_secrets_enabled
+    {
+        level_6.push(Box::new(
+            ExternalSecretsChart::new(chart_prefix_path, HelmChartNamespaces::KubeSystem).to_common_helm_chart()?,
+        ));
+    }
+
+    // Bottlerocket NVIDIA images already run the device plugin
+    if chart_config_prerequisites.gpu_node_groups
+        && chart_config_prerequisites.infra_options.node_os_image != NodeOsImage::Bottlerocket
+    {
+        level_6.push(Box::new(
+            NvidiaDevicePluginChart::new(chart_prefix_path, HelmChartNamespaces::KubeSystem).to_common_helm_chart()?,
+        ));
+    }
+
     // observability
     if let Some(kube_prometheus_stack_chart) = kube_prometheus_stack {
         level_2.push(Box::new(kube_prometheus_stack_chart));
@@ -70,6 +70,7 @@ use tokio::time::Duration;
 use self::addons::aws_kube_proxy::AwsKubeProxyAddon;
 use self::ec2::EC2;
 use self::eks::{delete_eks_nodegroups, select_nodegroups_autoscaling_group_behavior, NodeGroupsDeletionType};
+use self::node_os::{KubeletParameters, NodeOsImage};
 use crate::cmd::command::CommandKiller;
 use crate::dns_provider::DnsProvider;
 use crate::object_storage::ObjectStorage;
@@ -84,6 +85,7 @@ pub mod eks_helm_charts;
 pub mod helm_charts;
 mod karpenter;
 pub mod node;
+pub mod node_os;
 
 static AWS_EKS_DEFAULT_UPGRADE_TIMEOUT_DURATION: Lazy<ChronoDuration> = Lazy::new(|| ChronoDuration::hours(1));
 // https://docs.aws.amazon.com/eks/latest/userguide/managed-node-update-behavior.html
@@ -150,6 +152,10 @@ pub struct Options {
     pub aws_addon_coredns_version_override: Option<String>,
     #[serde(default)]
     pub ec2_exposed_port: Option<u16>,
+    #[serde(default)]
+    pub node_os_image: NodeOsImage,
+    #[serde(default)]
+    pub kubelet_parameters: KubeletParameters,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -425,6 +431,9 @@ fn tera_context(
     context.insert("enable_karpenter", &kubernetes.advanced_settings().aws_enable_karpenter);
     context.insert("bootstrap_on_fargate", &bootstrap_on_fargate);
 
+    // EFS filesystem of the shared volumes
+    context.insert("enable_efs", &kubernetes.advanced_settings().storage_shared_volumes_enabled);
+
     // AWS S3 tfstate storage
     context.insert(
         "aws_access_key_tfstates_account",
@@ -465,6 +474,11 @@ fn tera_context(
     context.insert("kubernetes_cluster_long_id", kubernetes.context().cluster_long_id());
     context.insert("eks_region_cluster_id", region_cluster_id.as_str());
     context.insert("eks_worker_nodes", &node_groups);
+    context.insert("eks_node_os_image", &options.node_os_image);
+    context.insert("eks_kubelet_max_pods", &options.kubelet_parameters.max_pods);
+    context.insert("eks_kubelet_eviction_hard", &options.kubelet_parameters.eviction_hard);
+    context.insert("eks_kubelet_kube_reserved", &options.kubelet_parameters.kube_reserved);
+    context.insert("eks_kubelet_extra_args", &options.kubelet_parameters.to_kubelet_extra_args());
     context.insert("ec2_zone_a_subnet_blocks_private", &ec2_zone_a_subnet_blocks_private);
     context.insert("ec2_zone_b_subnet_blocks_private", &ec2_zone_b_subnet_blocks_private);
     context.insert("ec2_zone_c_subnet_blocks_private", &ec2_zone_c_subnet_blocks_private);
@@ -1344,6 +1358,7 @@ fn create(
                 dns_provider_config: dns_provider.provider_configuration(),
                 cluster_advanced_settings: kubernetes.advanced_settings().clone(),
                 disk_size_in_gib,
+                gpu_node_groups: node_groups.iter().any(|node_group| node_group.gpu),
             };
             eks_aws_helm_charts(
                 qovery_terraform_config_file.clone().as_str(),
@@ -1424,6 +1439,8 @@ fn create(
                     .as_slice(),
                 helm_charts_to_deploy.clone(),
                 kubernetes.context().is_dry_run_deploy(),
+                kubernetes.logger(),
+                &event_details,
             ) {
                 Ok(_) => OperationResult::Ok(()),
                 Err(e) => {
@@ -1454,6 +1471,8 @@ fn create(
                 .as_slice(),
             helm_charts_to_deploy,
             kubernetes.context().is_dry_run_deploy(),
+            kubernetes.logger(),
+            &event_details,
         )
         .map_err(|e| Box::new(EngineError::new_helm_chart_error(event_details.clone(), e)))?;
 
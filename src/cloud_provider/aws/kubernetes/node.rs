@@ -3937,6 +3937,7 @@ mod tests {
     use crate::cloud_provider::aws::kubernetes::node::AwsInstancesType;
     use crate::cloud_provider::kubernetes::InstanceType;
     use crate::cloud_provider::models::{CpuArchitecture, NodeGroups};
+    use std::collections::BTreeMap;
     use std::str::FromStr;
     use strum::IntoEnumIterator;
 
@@ -4512,6 +4513,9 @@ mod tests {
                 disk_size_in_gib: 20,
                 desired_nodes: None,
                 instance_architecture: CpuArchitecture::AMD64,
+                labels: BTreeMap::new(),
+                taints: vec![],
+                gpu: false,
             }
         );
     }
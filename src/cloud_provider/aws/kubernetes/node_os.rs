@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Highest max pods value supported by EKS, with prefix delegation on the largest instances
+const MAX_PODS_LIMIT: u32 = 737;
+// https://kubernetes.io/docs/concepts/scheduling-eviction/node-pressure-eviction/#eviction-signals
+const EVICTION_SIGNALS: &[&str] = &[
+    "memory.available",
+    "nodefs.available",
+    "nodefs.inodesFree",
+    "imagefs.available",
+    "imagefs.inodesFree",
+    "pid.available",
+];
+const RESERVED_RESOURCES: &[&str] = &["cpu", "memory", "ephemeral-storage", "pid"];
+
+/// Operating system image of the EKS managed node groups
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum NodeOsImage {
+    #[default]
+    AmazonLinux2,
+    AmazonLinux2023,
+    Bottlerocket,
+    /// Canonical EKS images, bootstrapped by the engine as they are not managed by EKS
+    Ubuntu,
+}
+
+/// Kubelet parameters of the nodes, the defaults of the image are kept for the ones not set
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct KubeletParameters {
+    pub max_pods: Option<u32>,
+    /// Eviction signal to threshold, i.e: `memory.available` => `100Mi`
+    pub eviction_hard: BTreeMap<String, String>,
+    /// Resource to quantity reserved for kubernetes system daemons, i.e: `cpu` => `250m`
+    pub kube_reserved: BTreeMap<String, String>,
+}
+
+// Values end up in shell scripts and toml files, only quantities and percentages are allowed
+fn is_valid_quantity(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '%')
+}
+
+fn to_kubelet_flag_value(values: &BTreeMap<String, String>, separator: &str) -> String {
+    values
+        .iter()
+        .map(|(key, value)| format!("{key}{separator}{value}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl KubeletParameters {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(max_pods) = self.max_pods {
+            if max_pods == 0 || max_pods > MAX_PODS_LIMIT {
+                return Err(format!(
+                    "kubelet max_pods must be between 1 and {MAX_PODS_LIMIT}, got {max_pods}"
+                ));
+            }
+        }
+
+        for (signal, threshold) in &self.eviction_hard {
+            if !EVICTION_SIGNALS.contains(&signal.as_str()) {
+                return Err(format!(
+                    "`{signal}` is not a supported kubelet eviction signal, expected one of {}",
+                    EVICTION_SIGNALS.join(", ")
+                ));
+            }
+            if !is_valid_quantity(threshold) {
+                return Err(format!("Invalid kubelet eviction threshold `{threshold}` for `{signal}`"));
+            }
+        }
+
+        for (resource, quantity) in &self.kube_reserved {
+            if !RESERVED_RESOURCES.contains(&resource.as_str()) {
+                return Err(format!(
+                    "`{resource}` cannot be reserved for kubernetes daemons, expected one of {}",
+                    RESERVED_RESOURCES.join(", ")
+                ));
+            }
+            if !is_valid_quantity(quantity) || quantity.contains('%') {
+                return Err(format!("Invalid kubelet reserved quantity `{quantity}` for `{resource}`"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Kubelet command line flags, for images bootstrapped by the EKS bootstrap script (Amazon Linux 2, Ubuntu)
+    pub fn to_kubelet_extra_args(&self) -> String {
+        let mut args = vec![];
+        if let Some(max_pods) = self.max_pods {
+            args.push(format!("--max-pods={max_pods}"));
+        }
+        if !self.eviction_hard.is_empty() {
+            args.push(format!("--eviction-hard={}", to_kubelet_flag_value(&self.eviction_hard, "<")));
+        }
+        if !self.kube_reserved.is_empty() {
+            args.push(format!("--kube-reserved={}", to_kubelet_flag_value(&self.kube_reserved, "=")));
+        }
+
+        args.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kubelet_parameters_validate() {
+        assert!(KubeletParameters::default().validate().is_ok());
+
+        let parameters = KubeletParameters {
+            max_pods: Some(110),
+            eviction_hard: BTreeMap::from([
+                ("memory.available".to_string(), "200Mi".to_string()),
+                ("nodefs.available".to_string(), "10%".to_string()),
+            ]),
+            kube_reserved: BTreeMap::from([("cpu".to_string(), "250m".to_string())]),
+        };
+        assert!(parameters.validate().is_ok());
+
+        let with = |update: fn(&mut KubeletParameters)| {
+            let mut parameters = parameters.clone();
+            update(&mut parameters);
+            parameters.validate()
+        };
+        assert!(with(|p| p.max_pods = Some(0)).is_err());
+        assert!(with(|p| p.max_pods = Some(1000)).is_err());
+        assert!(with(|p| {
+            p.eviction_hard.insert("memory.free".to_string(), "1Gi".to_string());
+        })
+        .is_err());
+        assert!(with(|p| {
+            p.eviction_hard
+                .insert("pid.available".to_string(), "10\" && reboot".to_string());
+        })
+        .is_err());
+        assert!(with(|p| {
+            p.kube_reserved.insert("gpu".to_string(), "1".to_string());
+        })
+        .is_err());
+        assert!(with(|p| {
+            p.kube_reserved.insert("memory".to_string(), "10%".to_string());
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_to_kubelet_extra_args() {
+        assert_eq!(KubeletParameters::default().to_kubelet_extra_args(), "");
+
+        let parameters = KubeletParameters {
+            max_pods: Some(58),
+            eviction_hard: BTreeMap::from([
+                ("memory.available".to_string(), "200Mi".to_string()),
+                ("nodefs.available".to_string(), "10%".to_string()),
+            ]),
+            kube_reserved: BTreeMap::from([
+                ("cpu".to_string(), "250m".to_string()),
+                ("memory".to_string(), "1Gi".to_string()),
+            ]),
+        };
+        assert_eq!(
+            parameters.to_kubelet_extra_args(),
+            "--max-pods=58 --eviction-hard=memory.available<200Mi,nodefs.available<10% --kube-reserved=cpu=250m,memory=1Gi"
+        );
+    }
+}
@@ -21,6 +21,7 @@ use crate::runtime::block_on;
 use crate::utilities::to_short_id;
 
 pub mod database_instance_type;
+pub mod janitor;
 pub mod kubernetes;
 pub mod load_balancers;
 pub mod models;
@@ -77,6 +77,22 @@ pub trait QoveryAwsSdkConfigManagedDatabase {
         &self,
         db_id: &str,
     ) -> Result<aws_sdk_docdb::output::DescribeDbClustersOutput, SdkError<aws_sdk_docdb::error::DescribeDBClustersError>>;
+    async fn stop_managed_rds_database(
+        &self,
+        db_id: &str,
+    ) -> Result<aws_sdk_rds::output::StopDbInstanceOutput, SdkError<aws_sdk_rds::error::StopDBInstanceError>>;
+    async fn start_managed_rds_database(
+        &self,
+        db_id: &str,
+    ) -> Result<aws_sdk_rds::output::StartDbInstanceOutput, SdkError<aws_sdk_rds::error::StartDBInstanceError>>;
+    async fn stop_managed_doc_db_database(
+        &self,
+        db_id: &str,
+    ) -> Result<aws_sdk_docdb::output::StopDbClusterOutput, SdkError<aws_sdk_docdb::error::StopDBClusterError>>;
+    async fn start_managed_doc_db_database(
+        &self,
+        db_id: &str,
+    ) -> Result<aws_sdk_docdb::output::StartDbClusterOutput, SdkError<aws_sdk_docdb::error::StartDBClusterError>>;
 }
 
 #[async_trait]
@@ -2,14 +2,19 @@ use crate::cloud_provider::service::Action;
 
 use crate::events::{EnvironmentStep, EventDetails, Stage, Transmitter};
 use crate::io_models::context::Context;
+use crate::io_models::environment::{DeploymentWaves, EnvironmentServiceAccount, RemoteBuilder};
+use crate::io_models::shared_volume::SharedVolume;
 
 use crate::models::application::ApplicationService;
 use crate::models::container::ContainerService;
 use crate::models::database::DatabaseService;
 use crate::models::helm_chart::HelmChartService;
 use crate::models::job::JobService;
+use crate::models::kustomize::KustomizeService;
 use crate::models::router::RouterService;
+use crate::models::terraform_service::TerraformServiceTrait;
 use crate::utilities::to_short_id;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 pub struct Environment {
@@ -31,8 +36,18 @@ pub struct Environment {
     pub databases: Vec<Box<dyn DatabaseService>>,
     pub jobs: Vec<Box<dyn JobService>>,
     pub helm_charts: Vec<Box<dyn HelmChartService>>,
+    pub kustomizations: Vec<Box<dyn KustomizeService>>,
+    pub terraform_services: Vec<Box<dyn TerraformServiceTrait>>,
+    pub shared_volumes: Vec<SharedVolume>,
+    pub service_account: EnvironmentServiceAccount,
+    pub remote_builder: Option<RemoteBuilder>,
+    pub deployment_waves: Option<DeploymentWaves>,
+    pub service_dependencies: BTreeMap<Uuid, Vec<Uuid>>,
 }
 
+/// Same name in every namespace, so IRSA and workload identity bindings only depend on the environment namespace
+pub const ENVIRONMENT_SERVICE_ACCOUNT_NAME: &str = "qovery-environment";
+
 impl Environment {
     pub fn new(
         long_id: Uuid,
@@ -77,9 +92,56 @@ impl Environment {
             databases,
             jobs,
             helm_charts,
+            kustomizations: vec![],
+            terraform_services: vec![],
+            shared_volumes: vec![],
+            service_account: EnvironmentServiceAccount::default(),
+            remote_builder: None,
+            deployment_waves: None,
+            service_dependencies: BTreeMap::new(),
         }
     }
 
+    pub fn with_kustomizations(mut self, kustomizations: Vec<Box<dyn KustomizeService>>) -> Self {
+        self.kustomizations = kustomizations;
+        self
+    }
+
+    pub fn with_terraform_services(mut self, terraform_services: Vec<Box<dyn TerraformServiceTrait>>) -> Self {
+        self.terraform_services = terraform_services;
+        self
+    }
+
+    pub fn with_shared_volumes(mut self, shared_volumes: Vec<SharedVolume>) -> Self {
+        self.shared_volumes = shared_volumes;
+        self
+    }
+
+    pub fn with_service_account(mut self, service_account: EnvironmentServiceAccount) -> Self {
+        self.service_account = service_account;
+        self
+    }
+
+    pub fn with_remote_builder(mut self, remote_builder: Option<RemoteBuilder>) -> Self {
+        self.remote_builder = remote_builder;
+        self
+    }
+
+    pub fn with_deployment_waves(mut self, deployment_waves: Option<DeploymentWaves>) -> Self {
+        self.deployment_waves = deployment_waves;
+        self
+    }
+
+    pub fn with_service_dependencies(mut self, service_dependencies: BTreeMap<Uuid, Vec<Uuid>>) -> Self {
+        self.service_dependencies = service_dependencies;
+        self
+    }
+
+    /// Service account of the environment workloads, none when they use the namespace `default` one
+    pub fn service_account_name(&self) -> Option<&'static str> {
+        self.service_account.enabled.then_some(ENVIRONMENT_SERVICE_ACCOUNT_NAME)
+    }
+
     pub fn namespace(&self) -> &str {
         self.namespace.as_str()
     }
@@ -188,6 +188,9 @@ pub fn gcp_helm_charts(
             .managed_dns_root_domain_helm_format
             .to_string(),
         chart_config_prerequisites.cluster_id.to_string(),
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .network_enable_dual_stack,
         UpdateStrategy::RollingUpdate,
         false,
         HelmChartNamespaces::Qovery,
@@ -337,6 +340,15 @@ pub fn gcp_helm_charts(
         ),
         HelmChartNamespaces::Qovery,
         None,
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .network_enable_dual_stack,
+        chart_config_prerequisites.dns_provider_config.is_private_zone(),
+        chart_config_prerequisites.cluster_advanced_settings.nginx_waf_mode,
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .nginx_waf_custom_rules
+            .clone(),
     )
     .to_common_helm_chart()?;
 
@@ -40,6 +40,7 @@ use crate::models::third_parties::LetsEncryptConfig;
 use crate::models::ToCloudProviderFormat;
 use crate::object_storage::errors::ObjectStorageError;
 use crate::object_storage::google_object_storage::GoogleOS;
+use crate::object_storage::retry::{RetryPolicy, RetryableObjectStorage};
 use crate::object_storage::{BucketDeleteStrategy, ObjectStorage};
 use crate::runtime::block_on;
 use crate::secret_manager;
@@ -203,7 +204,7 @@ pub struct Gke {
     template_directory: String,
     cloud_provider: Arc<dyn CloudProvider>,
     dns_provider: Arc<dyn DnsProvider>,
-    object_storage: GoogleOS,
+    object_storage: RetryableObjectStorage<GoogleOS>,
     options: GkeOptions,
     logger: Box<dyn Logger>,
     advanced_settings: ClusterAdvancedSettings,
@@ -277,7 +278,7 @@ impl Gke {
             template_directory: format!("{}/gcp/bootstrap", context.lib_root_dir()),
             cloud_provider,
             dns_provider,
-            object_storage: google_object_storage,
+            object_storage: RetryableObjectStorage::new(google_object_storage, RetryPolicy::default()),
             options,
             logger,
             advanced_settings,
@@ -680,11 +681,20 @@ impl Gke {
             event_details.clone(),
             EventMessage::new_from_safe("Create Qovery managed object storage buckets".to_string()),
         ));
-        if let Err(e) = self.object_storage.create_bucket(
-            self.kubeconfig_bucket_name().as_str(),
-            self.advanced_settings.resource_ttl(),
-            false,
-        ) {
+        let bucket_encryption = self.advanced_settings.bucket_encryption();
+        if let Err(e) = self
+            .object_storage
+            .create_bucket(
+                self.kubeconfig_bucket_name().as_str(),
+                self.advanced_settings.resource_ttl(),
+                false,
+                &bucket_encryption,
+            )
+            .and_then(|_| {
+                self.object_storage
+                    .verify_bucket_encryption(self.kubeconfig_bucket_name().as_str(), &bucket_encryption)
+            })
+        {
             let error = EngineError::new_object_storage_error(event_details, e);
             self.logger().log(EngineEvent::Error(error.clone(), None));
             return Err(Box::new(error));
@@ -695,6 +705,7 @@ impl Gke {
             self.logs_bucket_name().as_str(),
             self.advanced_settings.resource_ttl(),
             false,
+            &bucket_encryption,
         ) {
             let error = EngineError::new_object_storage_error(event_details, e);
             self.logger().log(EngineEvent::Error(error.clone(), None));
@@ -834,6 +845,8 @@ impl Gke {
                 .as_slice(),
             helm_charts_to_deploy,
             self.context.is_dry_run_deploy(),
+            self.logger(),
+            &event_details,
         )
         .map_err(|e| Box::new(EngineError::new_helm_chart_error(event_details.clone(), e)))
     }
@@ -1564,6 +1577,10 @@ impl Kubernetes for Gke {
         &self.temp_dir
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.object_storage)
+    }
+
     fn update_vault_config(
         &self,
         event_details: EventDetails,
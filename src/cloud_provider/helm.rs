@@ -18,9 +18,11 @@ use thiserror::Error;
 
 use crate::cmd::command::CommandKiller;
 use crate::deployment_action::deploy_helm::default_helm_timeout;
-use crate::events::EventDetails;
+use crate::events::{EngineEvent, EventDetails};
+use crate::logger::Logger;
 use std::{fs, thread};
 
+use super::helm_charts::upgrade_impact::charts_upgrade_impacts;
 use super::helm_charts::{HelmChartDirectoryLocation, HelmPath, HelmPathType};
 use super::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
 
@@ -35,6 +37,9 @@ pub enum HelmChartError {
     #[error("Error while executing helm command")]
     HelmError(#[from] HelmError),
 
+    #[error("Workloads are not ready after install: {chart_name:?}: {msg:?}")]
+    WorkloadsNotReady { chart_name: String, msg: String },
+
     #[error("Error while executing command")]
     CommandError(#[from] CommandError),
 }
@@ -679,11 +684,23 @@ pub fn deploy_charts_levels(
     envs: &[(&str, &str)],
     charts: Vec<Vec<Box<dyn HelmChart>>>,
     dry_run: bool,
+    logger: &dyn Logger,
+    event_details: &EventDetails,
 ) -> Result<(), HelmChartError> {
     // first show diff
     let helm = Helm::new(kubernetes_config, envs)?;
 
     for level in charts {
+        // warn about the environments the upgrades of this level disrupt, before they happen or as part of the plan
+        let charts_to_deploy: Vec<&ChartInfo> = level
+            .iter()
+            .map(|chart| chart.get_chart_info())
+            .filter(|chart_info| chart_info.action == Deploy)
+            .collect();
+        for impact in charts_upgrade_impacts(kube_client, &helm, &charts_to_deploy, envs) {
+            logger.log(EngineEvent::Warning(event_details.clone(), impact.to_event_message()));
+        }
+
         // Show diff for all chart in this state
         for chart in &level {
             let chart_info = chart.get_chart_info();
@@ -1,5 +1,6 @@
 use crate::cloud_provider::helm::{
-    ChartInfo, ChartInstallationChecker, ChartSetValue, CommonChart, HelmChartError, HelmChartNamespaces,
+    ChartInfo, ChartInstallationChecker, ChartSetValue, ChartValuesGenerated, CommonChart, HelmChartError,
+    HelmChartNamespaces,
 };
 use crate::cloud_provider::helm_charts::{
     HelmChartDirectoryLocation, HelmChartPath, HelmChartValuesFilePath, ToCommonHelmChart,
@@ -47,6 +48,25 @@ impl<'a> CertManagerConfigsChart<'a> {
     pub fn chart_name() -> String {
         "cert-manager-configs".to_string()
     }
+
+    // helm --set would split the JSON key on its commas, it goes through a generated values file instead
+    fn clouddns_service_account_key_values(&self) -> Result<Vec<ChartValuesGenerated>, HelmChartError> {
+        let DnsProviderConfiguration::GcloudDns(config) = &self.dns_provider_configuration else {
+            return Ok(vec![]);
+        };
+
+        let values =
+            serde_json::json!({ "provider": { "clouddns": { "serviceAccountKey": config.service_account_key } } });
+        let yaml_content = serde_yaml::to_string(&values).map_err(|e| HelmChartError::RenderingError {
+            chart_name: CertManagerConfigsChart::chart_name(),
+            msg: e.to_string(),
+        })?;
+
+        Ok(vec![ChartValuesGenerated::new(
+            "qovery_cert_manager_configs_clouddns".to_string(),
+            yaml_content,
+        )])
+    }
 }
 
 impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
@@ -89,6 +109,9 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                                 cloudflare_config.cloudflare_api_token.to_string()
                             }
                             DnsProviderConfiguration::QoveryDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Route53(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::GcloudDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Ovh(_) => "not-set".to_string(),
                         },
                     },
                     ChartSetValue {
@@ -98,6 +121,9 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                                 cloudflare_config.cloudflare_email.to_string()
                             }
                             DnsProviderConfiguration::QoveryDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Route53(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::GcloudDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Ovh(_) => "not-set".to_string(),
                         },
                     },
                     // Qovery DNS
@@ -127,6 +153,9 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                                 format!("\"{}\"", qovery_dns_config.api_url_port)
                             }
                             DnsProviderConfiguration::Cloudflare(_) => "no-set".to_string(),
+                            DnsProviderConfiguration::Route53(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::GcloudDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Ovh(_) => "not-set".to_string(),
                         },
                     },
                     ChartSetValue {
@@ -136,6 +165,9 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                                 qovery_dns_config.api_url_scheme_and_domain.to_string()
                             }
                             DnsProviderConfiguration::Cloudflare(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Route53(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::GcloudDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Ovh(_) => "not-set".to_string(),
                         },
                     },
                     ChartSetValue {
@@ -145,9 +177,60 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                                 qovery_dns_config.api_key.to_string()
                             }
                             DnsProviderConfiguration::Cloudflare(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Route53(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::GcloudDns(_) => "not-set".to_string(),
+                            DnsProviderConfiguration::Ovh(_) => "not-set".to_string(),
+                        },
+                    },
+                    // Route53
+                    ChartSetValue {
+                        key: "provider.route53.accessKeyId".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => {
+                                route53_config.aws_access_key_id.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.route53.secretAccessKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => {
+                                route53_config.aws_secret_access_key.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.route53.hostedZoneId".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => {
+                                route53_config.hosted_zone_id.clone().unwrap_or_default()
+                            }
+                            _ => "".to_string(),
+                        },
+                    },
+                    // Google Cloud DNS
+                    ChartSetValue {
+                        key: "provider.clouddns.project".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::GcloudDns(gcloud_dns_config) => {
+                                gcloud_dns_config.project_id.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.clouddns.hostedZoneName".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::GcloudDns(gcloud_dns_config) => {
+                                gcloud_dns_config.managed_zone.clone().unwrap_or_default()
+                            }
+                            _ => "".to_string(),
                         },
                     },
                 ],
+                yaml_files_content: self.clouddns_service_account_key_values()?,
                 ..Default::default()
             },
             chart_installation_checker: Some(Box::new(CertManagerConfigsChartChecker::new())),
@@ -1,13 +1,14 @@
 use crate::cloud_provider::helm::{
-    ChartInfo, ChartInstallationChecker, ChartSetValue, CommonChart, CommonChartVpa, HelmChartError,
-    HelmChartNamespaces, UpdateStrategy, VpaConfig, VpaContainerPolicy, VpaTargetRef, VpaTargetRefApiVersion,
-    VpaTargetRefKind,
+    ChartInfo, ChartInstallationChecker, ChartSetValue, ChartValuesGenerated, CommonChart, CommonChartVpa,
+    HelmChartError, HelmChartNamespaces, UpdateStrategy, VpaConfig, VpaContainerPolicy, VpaTargetRef,
+    VpaTargetRefApiVersion, VpaTargetRefKind,
 };
 use crate::cloud_provider::helm_charts::{
     HelmChartDirectoryLocation, HelmChartPath, HelmChartValuesFilePath, ToCommonHelmChart,
 };
 use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
-use crate::dns_provider::DnsProviderConfiguration;
+use crate::dns_provider::ovh::OVH_DEFAULT_ENDPOINT;
+use crate::dns_provider::{DnsProviderConfiguration, DnsRecordType};
 use crate::errors::CommandError;
 use kube::Client;
 
@@ -18,6 +19,7 @@ pub struct ExternalDNSChart {
     dns_provider_configuration: DnsProviderConfiguration,
     managed_dns_domains_root_helm_format: String,
     cluster_id: String,
+    dual_stack_enabled: bool,
     update_strategy: UpdateStrategy,
     enable_vpa: bool,
     namespace: HelmChartNamespaces,
@@ -29,6 +31,7 @@ impl ExternalDNSChart {
         dns_provider_configuration: DnsProviderConfiguration,
         managed_dns_domains_root_helm_format: String,
         cluster_id: String,
+        dual_stack_enabled: bool,
         update_strategy: UpdateStrategy,
         enable_vpa: bool,
         namespace: HelmChartNamespaces,
@@ -48,6 +51,7 @@ impl ExternalDNSChart {
             dns_provider_configuration,
             managed_dns_domains_root_helm_format,
             cluster_id,
+            dual_stack_enabled,
             update_strategy,
             enable_vpa,
             namespace,
@@ -57,6 +61,32 @@ impl ExternalDNSChart {
     fn chart_name() -> String {
         "external-dns".to_string()
     }
+
+    // external-dns would otherwise write the records in every zone of the domain, public and private ones alike
+    fn zone_visibility(&self) -> String {
+        match self.dns_provider_configuration.is_private_zone() {
+            true => "private".to_string(),
+            false => "public".to_string(),
+        }
+    }
+
+    // service account key is a JSON document, it can't be passed through a --set value
+    fn google_service_account_key_values(&self) -> Result<Vec<ChartValuesGenerated>, HelmChartError> {
+        let DnsProviderConfiguration::GcloudDns(config) = &self.dns_provider_configuration else {
+            return Ok(vec![]);
+        };
+
+        let values = serde_json::json!({ "google": { "serviceAccountKey": config.service_account_key } });
+        let yaml_content = serde_yaml::to_string(&values).map_err(|e| HelmChartError::RenderingError {
+            chart_name: ExternalDNSChart::chart_name(),
+            msg: e.to_string(),
+        })?;
+
+        Ok(vec![ChartValuesGenerated::new(
+            "qovery_external_dns_google".to_string(),
+            yaml_content,
+        )])
+    }
 }
 
 impl ToCommonHelmChart for ExternalDNSChart {
@@ -82,7 +112,7 @@ impl ToCommonHelmChart for ExternalDNSChart {
                     },
                     ChartSetValue {
                         key: "provider".to_string(),
-                        value: self.dns_provider_configuration.get_cert_manager_config_name(),
+                        value: self.dns_provider_configuration.get_external_dns_provider_name(),
                     },
                     ChartSetValue {
                         key: "domainFilters".to_string(),
@@ -99,6 +129,18 @@ impl ToCommonHelmChart for ExternalDNSChart {
                         key: "txtPrefix".to_string(),
                         value: format!("qvy-{}-", self.cluster_id),
                     },
+                    ChartSetValue {
+                        key: "managedRecordTypes".to_string(),
+                        value: format!(
+                            "{{{}}}",
+                            self.dns_provider_configuration
+                                .managed_record_types(self.dual_stack_enabled)
+                                .iter()
+                                .map(DnsRecordType::as_str)
+                                .collect::<Vec<_>>()
+                                .join(",")
+                        ),
+                    },
                     // Providers configuration
                     // Cloudflare
                     ChartSetValue {
@@ -122,6 +164,67 @@ impl ToCommonHelmChart for ExternalDNSChart {
                             _ => "".to_string(),
                         },
                     },
+                    // Route53
+                    ChartSetValue {
+                        key: "aws.credentials.accessKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(config) => config.aws_access_key_id.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "aws.credentials.secretKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(config) => config.aws_secret_access_key.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "aws.zoneType".to_string(),
+                        value: self.zone_visibility(),
+                    },
+                    // Google Cloud DNS
+                    ChartSetValue {
+                        key: "google.project".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::GcloudDns(config) => config.project_id.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "google.zoneVisibility".to_string(),
+                        value: self.zone_visibility(),
+                    },
+                    // OVH
+                    ChartSetValue {
+                        key: "ovh.applicationKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Ovh(config) => config.application_key.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "ovh.applicationSecret".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Ovh(config) => config.application_secret.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "ovh.consumerKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Ovh(config) => config.consumer_key.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    // the chart has no value for it, an empty extra arg would be passed as a flag without value
+                    ChartSetValue {
+                        key: "extraArgs.ovh-endpoint".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Ovh(config) => config.endpoint.to_string(),
+                            _ => OVH_DEFAULT_ENDPOINT.to_string(),
+                        },
+                    },
                     // PDNS
                     ChartSetValue {
                         key: "pdns.apiUrl".to_string(),
@@ -145,6 +248,7 @@ impl ToCommonHelmChart for ExternalDNSChart {
                         },
                     },
                 ],
+                yaml_files_content: self.google_service_account_key_values()?,
                 ..Default::default()
             },
             chart_installation_checker: Some(Box::new(ExternalDNSChartInstallationChecker::new())),
@@ -223,6 +327,7 @@ mod tests {
             }),
             "whatever".to_string(),
             "whatever".to_string(),
+            false,
             UpdateStrategy::RollingUpdate,
             false,
             HelmChartNamespaces::KubeSystem,
@@ -258,6 +363,7 @@ mod tests {
             }),
             "whatever".to_string(),
             "whatever".to_string(),
+            false,
             UpdateStrategy::RollingUpdate,
             false,
             HelmChartNamespaces::KubeSystem,
@@ -297,6 +403,7 @@ mod tests {
             }),
             "whatever".to_string(),
             "whatever".to_string(),
+            false,
             UpdateStrategy::RollingUpdate,
             false,
             HelmChartNamespaces::KubeSystem,
@@ -0,0 +1,96 @@
+use crate::cloud_provider::helm::{ChartInfo, ChartSetValue, CommonChart, HelmChartError, HelmChartNamespaces};
+use crate::cloud_provider::helm_charts::{HelmChartDirectoryLocation, HelmChartPath, ToCommonHelmChart};
+use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
+
+pub struct ExternalSecretsChart {
+    chart_path: HelmChartPath,
+    namespace: HelmChartNamespaces,
+}
+
+impl ExternalSecretsChart {
+    pub fn new(chart_prefix_path: Option<&str>, namespace: HelmChartNamespaces) -> Self {
+        ExternalSecretsChart {
+            chart_path: HelmChartPath::new(
+                chart_prefix_path,
+                HelmChartDirectoryLocation::CommonFolder,
+                ExternalSecretsChart::chart_name(),
+            ),
+            namespace,
+        }
+    }
+
+    pub fn chart_name() -> String {
+        "external-secrets".to_string()
+    }
+}
+
+impl ToCommonHelmChart for ExternalSecretsChart {
+    fn to_common_helm_chart(&self) -> Result<CommonChart, HelmChartError> {
+        // keys of the upstream chart, pinned in helm-freeze
+        let values = vec![
+            ChartSetValue {
+                key: "installCRDs".to_string(),
+                value: "true".to_string(),
+            },
+            ChartSetValue {
+                key: "resources.limits.cpu".to_string(),
+                value: KubernetesCpuResourceUnit::MilliCpu(500).to_string(),
+            },
+            ChartSetValue {
+                key: "resources.limits.memory".to_string(),
+                value: KubernetesMemoryResourceUnit::MebiByte(256).to_string(),
+            },
+            ChartSetValue {
+                key: "resources.requests.cpu".to_string(),
+                value: KubernetesCpuResourceUnit::MilliCpu(100).to_string(),
+            },
+            ChartSetValue {
+                key: "resources.requests.memory".to_string(),
+                value: KubernetesMemoryResourceUnit::MebiByte(128).to_string(),
+            },
+        ];
+
+        Ok(CommonChart {
+            chart_info: ChartInfo {
+                name: ExternalSecretsChart::chart_name(),
+                namespace: self.namespace,
+                path: self.chart_path.to_string(),
+                values,
+                ..Default::default()
+            },
+            chart_installation_checker: None,
+            vertical_pod_autoscaler: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::helm::HelmChartNamespaces;
+    use crate::cloud_provider::helm_charts::external_secrets_chart::ExternalSecretsChart;
+    use crate::cloud_provider::helm_charts::{get_helm_path_kubernetes_provider_sub_folder_name, HelmChartType};
+    use std::env;
+
+    /// Makes sure chart directory containing all YAML files exists.
+    #[test]
+    fn external_secrets_chart_directory_exists_test() {
+        // setup:
+        let chart = ExternalSecretsChart::new(None, HelmChartNamespaces::KubeSystem);
+
+        let current_directory = env::current_dir().expect("Impossible to get current directory");
+        let chart_path = format!(
+            "{}/lib/{}/bootstrap/charts/{}/Chart.yaml",
+            current_directory
+                .to_str()
+                .expect("Impossible to convert current directory to string"),
+            get_helm_path_kubernetes_provider_sub_folder_name(chart.chart_path.helm_path(), HelmChartType::Shared),
+            ExternalSecretsChart::chart_name(),
+        );
+
+        // execute
+        let values_file = std::fs::File::open(&chart_path);
+
+        // verify:
+        assert!(values_file.is_ok(), "Chart directory should exist: `{chart_path}`");
+    }
+}
@@ -0,0 +1,144 @@
+use crate::cloud_provider::helm::{ChartInfo, ChartSetValue, CommonChart, HelmChartError, HelmChartNamespaces};
+use crate::cloud_provider::helm_charts::{HelmChartDirectoryLocation, HelmChartPath, ToCommonHelmChart};
+use crate::cloud_provider::io::ClusterAdvancedSettings;
+
+/// Vault server the `vault` ClusterSecretStore reads secrets from
+struct VaultSecretStore {
+    server: String,
+    path: String,
+    role: String,
+}
+
+/// Secret stores of the External Secrets Operator, deployed once its CRDs are installed by the external-secrets chart
+pub struct ExternalSecretsConfigsChart {
+    chart_path: HelmChartPath,
+    namespace: HelmChartNamespaces,
+    vault: VaultSecretStore,
+}
+
+impl ExternalSecretsConfigsChart {
+    /// None when no secret store is configured on the cluster
+    pub fn new(
+        chart_prefix_path: Option<&str>,
+        namespace: HelmChartNamespaces,
+        cluster_advanced_settings: &ClusterAdvancedSettings,
+    ) -> Option<Self> {
+        let server = cluster_advanced_settings.external_secrets_vault_server.as_ref()?;
+        Some(ExternalSecretsConfigsChart {
+            chart_path: HelmChartPath::new(
+                chart_prefix_path,
+                HelmChartDirectoryLocation::CommonFolder,
+                ExternalSecretsConfigsChart::chart_name(),
+            ),
+            namespace,
+            vault: VaultSecretStore {
+                server: server.clone(),
+                path: cluster_advanced_settings.external_secrets_vault_path.clone(),
+                role: cluster_advanced_settings.external_secrets_vault_role.clone(),
+            },
+        })
+    }
+
+    pub fn chart_name() -> String {
+        "external-secrets-configs".to_string()
+    }
+}
+
+impl ToCommonHelmChart for ExternalSecretsConfigsChart {
+    fn to_common_helm_chart(&self) -> Result<CommonChart, HelmChartError> {
+        Ok(CommonChart {
+            chart_info: ChartInfo {
+                name: ExternalSecretsConfigsChart::chart_name(),
+                namespace: self.namespace,
+                path: self.chart_path.to_string(),
+                values: vec![
+                    ChartSetValue {
+                        key: "vault.server".to_string(),
+                        value: self.vault.server.clone(),
+                    },
+                    ChartSetValue {
+                        key: "vault.path".to_string(),
+                        value: self.vault.path.clone(),
+                    },
+                    ChartSetValue {
+                        key: "vault.role".to_string(),
+                        value: self.vault.role.clone(),
+                    },
+                ],
+                ..Default::default()
+            },
+            chart_installation_checker: None,
+            vertical_pod_autoscaler: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::helm::HelmChartNamespaces;
+    use crate::cloud_provider::helm_charts::external_secrets_configs_chart::ExternalSecretsConfigsChart;
+    use crate::cloud_provider::helm_charts::{
+        get_helm_path_kubernetes_provider_sub_folder_name, HelmChartType, ToCommonHelmChart,
+    };
+    use crate::cloud_provider::io::ClusterAdvancedSettings;
+    use std::env;
+
+    fn cluster_advanced_settings() -> ClusterAdvancedSettings {
+        ClusterAdvancedSettings {
+            external_secrets_vault_server: Some("https://vault.example.com:8200".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Makes sure chart directory containing all YAML files exists.
+    #[test]
+    fn external_secrets_configs_chart_directory_exists_test() {
+        // setup:
+        let chart =
+            ExternalSecretsConfigsChart::new(None, HelmChartNamespaces::KubeSystem, &cluster_advanced_settings())
+                .expect("Chart should be deployed with a vault server");
+
+        let current_directory = env::current_dir().expect("Impossible to get current directory");
+        let chart_path = format!(
+            "{}/lib/{}/bootstrap/charts/{}/Chart.yaml",
+            current_directory
+                .to_str()
+                .expect("Impossible to convert current directory to string"),
+            get_helm_path_kubernetes_provider_sub_folder_name(chart.chart_path.helm_path(), HelmChartType::Shared),
+            ExternalSecretsConfigsChart::chart_name(),
+        );
+
+        // execute
+        let values_file = std::fs::File::open(&chart_path);
+
+        // verify:
+        assert!(values_file.is_ok(), "Chart directory should exist: `{chart_path}`");
+    }
+
+    #[test]
+    fn external_secrets_configs_chart_vault_values_test() {
+        assert!(ExternalSecretsConfigsChart::new(
+            None,
+            HelmChartNamespaces::KubeSystem,
+            &ClusterAdvancedSettings::default()
+        )
+        .is_none());
+
+        let chart =
+            ExternalSecretsConfigsChart::new(None, HelmChartNamespaces::KubeSystem, &cluster_advanced_settings())
+                .expect("Chart should be deployed with a vault server")
+                .to_common_helm_chart()
+                .expect("Cannot build chart");
+        let value = |key: &str| {
+            chart
+                .chart_info
+                .values
+                .iter()
+                .find(|v| v.key == key)
+                .map(|v| v.value.as_str())
+        };
+        assert_eq!(value("vault.server"), Some("https://vault.example.com:8200"));
+        assert_eq!(value("vault.path"), Some("secret"));
+        assert_eq!(value("vault.role"), Some("external-secrets"));
+    }
+}
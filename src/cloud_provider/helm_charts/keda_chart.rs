@@ -0,0 +1,95 @@
+use crate::cloud_provider::helm::{ChartInfo, ChartSetValue, CommonChart, HelmChartError, HelmChartNamespaces};
+use crate::cloud_provider::helm_charts::{HelmChartDirectoryLocation, HelmChartPath, ToCommonHelmChart};
+use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
+
+pub struct KedaChart {
+    chart_path: HelmChartPath,
+    namespace: HelmChartNamespaces,
+}
+
+impl KedaChart {
+    pub fn new(chart_prefix_path: Option<&str>, namespace: HelmChartNamespaces) -> Self {
+        KedaChart {
+            chart_path: HelmChartPath::new(
+                chart_prefix_path,
+                HelmChartDirectoryLocation::CommonFolder,
+                KedaChart::chart_name(),
+            ),
+            namespace,
+        }
+    }
+
+    pub fn chart_name() -> String {
+        "keda".to_string()
+    }
+}
+
+impl ToCommonHelmChart for KedaChart {
+    fn to_common_helm_chart(&self) -> Result<CommonChart, HelmChartError> {
+        let mut values = vec![];
+        // keys of the upstream chart, pinned in helm-freeze
+        for component in ["operator", "metricServer", "webhooks"] {
+            values.extend([
+                ChartSetValue {
+                    key: format!("resources.{component}.limits.cpu"),
+                    value: KubernetesCpuResourceUnit::MilliCpu(500).to_string(),
+                },
+                ChartSetValue {
+                    key: format!("resources.{component}.limits.memory"),
+                    value: KubernetesMemoryResourceUnit::MebiByte(512).to_string(),
+                },
+                ChartSetValue {
+                    key: format!("resources.{component}.requests.cpu"),
+                    value: KubernetesCpuResourceUnit::MilliCpu(100).to_string(),
+                },
+                ChartSetValue {
+                    key: format!("resources.{component}.requests.memory"),
+                    value: KubernetesMemoryResourceUnit::MebiByte(128).to_string(),
+                },
+            ]);
+        }
+
+        Ok(CommonChart {
+            chart_info: ChartInfo {
+                name: KedaChart::chart_name(),
+                namespace: self.namespace,
+                path: self.chart_path.to_string(),
+                values,
+                ..Default::default()
+            },
+            chart_installation_checker: None,
+            vertical_pod_autoscaler: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::helm::HelmChartNamespaces;
+    use crate::cloud_provider::helm_charts::keda_chart::KedaChart;
+    use crate::cloud_provider::helm_charts::{get_helm_path_kubernetes_provider_sub_folder_name, HelmChartType};
+    use std::env;
+
+    /// Makes sure chart directory containing all YAML files exists.
+    #[test]
+    fn keda_chart_directory_exists_test() {
+        // setup:
+        let chart = KedaChart::new(None, HelmChartNamespaces::KubeSystem);
+
+        let current_directory = env::current_dir().expect("Impossible to get current directory");
+        let chart_path = format!(
+            "{}/lib/{}/bootstrap/charts/{}/Chart.yaml",
+            current_directory
+                .to_str()
+                .expect("Impossible to convert current directory to string"),
+            get_helm_path_kubernetes_provider_sub_folder_name(chart.chart_path.helm_path(), HelmChartType::Shared),
+            KedaChart::chart_name(),
+        );
+
+        // execute
+        let values_file = std::fs::File::open(&chart_path);
+
+        // verify:
+        assert!(values_file.is_ok(), "Chart directory should exist: `{chart_path}`");
+    }
+}
@@ -12,13 +12,18 @@ pub mod cert_manager_chart;
 pub mod cert_manager_config_chart;
 pub mod coredns_config_chart;
 pub mod external_dns_chart;
+pub mod external_secrets_chart;
+pub mod external_secrets_configs_chart;
 pub mod grafana_chart;
 pub mod k8s_event_logger;
+pub mod keda_chart;
 pub mod kube_prometheus_stack_chart;
 pub mod kube_state_metrics;
 pub mod loki_chart;
 pub mod metrics_server_chart;
+pub mod nfs_server_provisioner_chart;
 pub mod nginx_ingress_chart;
+pub mod nvidia_device_plugin_chart;
 pub mod prometheus_adapter_chart;
 pub mod promtail_chart;
 pub mod qovery_cert_manager_webhook_chart;
@@ -26,6 +31,7 @@ pub mod qovery_cluster_agent_chart;
 pub mod qovery_priority_class_chart;
 pub mod qovery_shell_agent_chart;
 pub mod qovery_storage_class_chart;
+pub mod upgrade_impact;
 pub mod vertical_pod_autoscaler;
 
 pub enum HelmChartResourcesConstraintType {
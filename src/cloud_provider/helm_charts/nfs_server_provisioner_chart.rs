@@ -0,0 +1,148 @@
+use crate::cloud_provider::helm::{
+    ChartInfo, ChartSetValue, ChartValuesGenerated, CommonChart, HelmChartError, HelmChartNamespaces,
+};
+use crate::cloud_provider::helm_charts::{HelmChartDirectoryLocation, HelmChartPath, ToCommonHelmChart};
+use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
+
+/// ReadWriteMany storage class of the NFS server, used by the shared volumes on clusters without managed file storage
+pub const NFS_STORAGE_CLASS_NAME: &str = "qovery-nfs-rwx";
+/// Provisioner of the volumes of the storage class, kept when the server is reinstalled so existing volumes stay bound
+const NFS_PROVISIONER_NAME: &str = "qovery.com/nfs";
+
+pub struct NfsServerProvisionerChart {
+    chart_path: HelmChartPath,
+    namespace: HelmChartNamespaces,
+    backing_storage_class: String,
+    backing_volume_size_in_gib: u32,
+}
+
+impl NfsServerProvisionerChart {
+    /// `backing_storage_class` is the block storage class of the volume the NFS server exports the shared volumes from
+    pub fn new(
+        chart_prefix_path: Option<&str>,
+        namespace: HelmChartNamespaces,
+        backing_storage_class: String,
+        backing_volume_size_in_gib: u32,
+    ) -> Self {
+        NfsServerProvisionerChart {
+            chart_path: HelmChartPath::new(
+                chart_prefix_path,
+                HelmChartDirectoryLocation::CommonFolder,
+                NfsServerProvisionerChart::chart_name(),
+            ),
+            namespace,
+            backing_storage_class,
+            backing_volume_size_in_gib,
+        }
+    }
+
+    pub fn chart_name() -> String {
+        "nfs-server-provisioner".to_string()
+    }
+
+    // list values holding `=` cannot be passed through a --set value
+    fn storage_class_values(&self) -> Result<ChartValuesGenerated, HelmChartError> {
+        let values = serde_json::json!({ "storageClass": { "mountOptions": ["vers=4.1", "noatime"] } });
+        let yaml_content = serde_yaml::to_string(&values).map_err(|e| HelmChartError::RenderingError {
+            chart_name: NfsServerProvisionerChart::chart_name(),
+            msg: e.to_string(),
+        })?;
+
+        Ok(ChartValuesGenerated::new(
+            "qovery_nfs_server_provisioner_storage_class".to_string(),
+            yaml_content,
+        ))
+    }
+}
+
+impl ToCommonHelmChart for NfsServerProvisionerChart {
+    fn to_common_helm_chart(&self) -> Result<CommonChart, HelmChartError> {
+        Ok(CommonChart {
+            chart_info: ChartInfo {
+                name: NfsServerProvisionerChart::chart_name(),
+                namespace: self.namespace,
+                path: self.chart_path.to_string(),
+                // keys of the upstream chart, pinned in helm-freeze
+                values: vec![
+                    ChartSetValue {
+                        key: "storageClass.name".to_string(),
+                        value: NFS_STORAGE_CLASS_NAME.to_string(),
+                    },
+                    ChartSetValue {
+                        key: "storageClass.provisionerName".to_string(),
+                        value: NFS_PROVISIONER_NAME.to_string(),
+                    },
+                    ChartSetValue {
+                        key: "extraArgs.device-based-fsids".to_string(),
+                        value: "false".to_string(),
+                    },
+                    // exports live on a block volume, they would be lost with the pod otherwise
+                    ChartSetValue {
+                        key: "persistence.enabled".to_string(),
+                        value: "true".to_string(),
+                    },
+                    ChartSetValue {
+                        key: "persistence.storageClass".to_string(),
+                        value: self.backing_storage_class.clone(),
+                    },
+                    ChartSetValue {
+                        key: "persistence.size".to_string(),
+                        value: format!("{}Gi", self.backing_volume_size_in_gib),
+                    },
+                    ChartSetValue {
+                        key: "resources.limits.cpu".to_string(),
+                        value: KubernetesCpuResourceUnit::MilliCpu(500).to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.limits.memory".to_string(),
+                        value: KubernetesMemoryResourceUnit::MebiByte(256).to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.requests.cpu".to_string(),
+                        value: KubernetesCpuResourceUnit::MilliCpu(100).to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.requests.memory".to_string(),
+                        value: KubernetesMemoryResourceUnit::MebiByte(256).to_string(),
+                    },
+                ],
+                yaml_files_content: vec![self.storage_class_values()?],
+                ..Default::default()
+            },
+            chart_installation_checker: None,
+            vertical_pod_autoscaler: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::helm::HelmChartNamespaces;
+    use crate::cloud_provider::helm_charts::nfs_server_provisioner_chart::NfsServerProvisionerChart;
+    use crate::cloud_provider::helm_charts::{get_helm_path_kubernetes_provider_sub_folder_name, HelmChartType};
+    use std::env;
+
+    /// Makes sure chart directory containing all YAML files exists.
+    #[test]
+    fn nfs_server_provisioner_chart_directory_exists_test() {
+        // setup:
+        let chart =
+            NfsServerProvisionerChart::new(None, HelmChartNamespaces::KubeSystem, "scw-sbv-ssd-0".to_string(), 100);
+
+        let current_directory = env::current_dir().expect("Impossible to get current directory");
+        let chart_path = format!(
+            "{}/lib/{}/bootstrap/charts/{}/Chart.yaml",
+            current_directory
+                .to_str()
+                .expect("Impossible to convert current directory to string"),
+            get_helm_path_kubernetes_provider_sub_folder_name(chart.chart_path.helm_path(), HelmChartType::Shared),
+            NfsServerProvisionerChart::chart_name(),
+        );
+
+        // execute
+        let values_file = std::fs::File::open(&chart_path);
+
+        // verify:
+        assert!(values_file.is_ok(), "Chart directory should exist: `{chart_path}`");
+    }
+}
@@ -13,6 +13,7 @@ use crate::cloud_provider::models::{
 };
 use crate::cloud_provider::Kind;
 use crate::errors::CommandError;
+use crate::io_models::WafMode;
 use crate::models::domain::Domain;
 use kube::Client;
 use tera::{Context, Tera};
@@ -34,6 +35,10 @@ pub struct NginxIngressChart {
     nginx_hpa_target_cpu_utilization_percentage: Option<u32>,
     namespace: HelmChartNamespaces,
     loadbalancer_size: Option<String>,
+    dual_stack_enabled: bool,
+    internal_load_balancer: bool,
+    waf_mode: WafMode,
+    waf_custom_rules: Vec<String>,
 }
 
 impl NginxIngressChart {
@@ -51,6 +56,10 @@ impl NginxIngressChart {
         nginx_hpa_target_cpu_utilization_percentage: Option<u32>,
         namespace: HelmChartNamespaces,
         loadbalancer_size: Option<String>,
+        dual_stack_enabled: bool,
+        internal_load_balancer: bool,
+        waf_mode: WafMode,
+        waf_custom_rules: Vec<String>,
     ) -> Self {
         NginxIngressChart {
             chart_path: HelmChartPath::new(
@@ -91,6 +100,10 @@ impl NginxIngressChart {
             nginx_hpa_target_cpu_utilization_percentage,
             namespace,
             loadbalancer_size,
+            dual_stack_enabled,
+            internal_load_balancer,
+            waf_mode,
+            waf_custom_rules,
         }
     }
 
@@ -117,6 +130,17 @@ controller:
         requests:
             cpu: {{ controller_resources_requests_cpu }}
             memory: {{ controller_resources_requests_memory }}
+{%- if waf_enabled %}
+    # https://kubernetes.github.io/ingress-nginx/user-guide/third-party-addons/modsecurity/
+    config:
+        enable-modsecurity: "true"
+        enable-owasp-modsecurity-crs: "true"
+        modsecurity-snippet: |
+            SecRuleEngine {{ waf_rule_engine }}
+            {%- for rule in waf_custom_rules %}
+            {{ rule }}
+            {%- endfor %}
+{%- endif %}
 defaultBackend:
     resources:
         limits:
@@ -164,6 +188,9 @@ defaultBackend:
             "default_backend_resources_requests_memory",
             &self.default_backend_resources.request_memory.to_string(),
         );
+        context.insert("waf_enabled", &(self.waf_mode != WafMode::Disabled));
+        context.insert("waf_rule_engine", self.waf_mode.rule_engine());
+        context.insert("waf_custom_rules", &self.waf_custom_rules);
         let rendered_nginx_override = ChartValuesGenerated::new(
             "qovery_nginx_ingress".to_string(),
             tera.render("nginx_ingress_override", &context)
@@ -188,6 +215,8 @@ defaultBackend:
                 value: self.ff_metrics_history_enabled.to_string(),
             },
         ];
+        // annotations values must stay strings, even when they look like booleans
+        let mut chart_set_string_values = vec![];
         chart_set_values.push(ChartSetValue {
             key: "controller.autoscaling.enabled".to_string(),
             value: true.to_string(),
@@ -219,7 +248,14 @@ defaultBackend:
                         key: "controller.service.annotations.service\\.beta\\.kubernetes\\.io/aws-load-balancer-type"
                             .to_string(),
                         value: "nlb".to_string(),
-                    })
+                    });
+                    if self.internal_load_balancer {
+                        chart_set_string_values.push(ChartSetValue {
+                            key: "controller.service.annotations.service\\.beta\\.kubernetes\\.io/aws-load-balancer-internal"
+                                .to_string(),
+                            value: "true".to_string(),
+                        });
+                    }
                 };
             }
             Kind::Scw => {
@@ -237,7 +273,14 @@ defaultBackend:
                     },
                 });
             }
-            Kind::Gcp => {}
+            Kind::Gcp => {
+                if self.internal_load_balancer {
+                    chart_set_values.push(ChartSetValue {
+                        key: "controller.service.annotations.networking\\.gke\\.io/load-balancer-type".to_string(),
+                        value: "Internal".to_string(),
+                    });
+                }
+            }
             Kind::SelfManaged => {}
         }
         // external dns
@@ -245,7 +288,15 @@ defaultBackend:
             chart_set_values.push(ChartSetValue {
                 key: "controller.service.annotations.external-dns\\.alpha\\.kubernetes\\.io/hostname".to_string(),
                 value: self.domain.wildcarded().to_string(),
-            })
+            });
+            // on dual stack clusters the load balancer gets an IPv6 address as well, published as AAAA record
+            chart_set_values.push(ChartSetValue {
+                key: "controller.service.ipFamilyPolicy".to_string(),
+                value: match self.dual_stack_enabled {
+                    true => "PreferDualStack".to_string(),
+                    false => "SingleStack".to_string(),
+                },
+            });
         };
 
         Ok(CommonChart {
@@ -257,6 +308,7 @@ defaultBackend:
                 timeout_in_seconds: 300,
                 values_files: vec![self.chart_values_path.to_string()],
                 values: chart_set_values,
+                values_string: chart_set_string_values,
                 yaml_files_content: {
                     // order matters: last one overrides previous ones, so customer override should be last
                     let mut x = vec![rendered_nginx_override];
@@ -313,6 +365,7 @@ mod tests {
     use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
     use crate::cloud_provider::models::CustomerHelmChartsOverride;
     use crate::cloud_provider::Kind;
+    use crate::io_models::WafMode;
     use crate::models::domain::Domain;
     use std::env;
     use std::sync::Arc;
@@ -348,6 +401,10 @@ mod tests {
             Some(50),
             HelmChartNamespaces::NginxIngress,
             None,
+            false,
+            false,
+            WafMode::Disabled,
+            vec![],
         );
 
         let current_directory = env::current_dir().expect("Impossible to get current directory");
@@ -386,6 +443,10 @@ mod tests {
             Some(50),
             HelmChartNamespaces::NginxIngress,
             None,
+            false,
+            false,
+            WafMode::Disabled,
+            vec![],
         );
 
         let current_directory = env::current_dir().expect("Impossible to get current directory");
@@ -427,6 +488,10 @@ mod tests {
             Some(50),
             HelmChartNamespaces::NginxIngress,
             None,
+            false,
+            false,
+            WafMode::Disabled,
+            vec![],
         );
         let common_chart = chart.to_common_helm_chart().unwrap();
 
@@ -475,6 +540,10 @@ mod tests {
             None,
             HelmChartNamespaces::NginxIngress,
             None,
+            false,
+            false,
+            WafMode::Disabled,
+            vec![],
         );
         let common_chart = chart.to_common_helm_chart().unwrap();
 
@@ -494,4 +563,41 @@ mod tests {
         // verify:
         assert!(missing_fields.is_none(), "Some fields are missing in values file, add those (make sure they still exist in chart values), fields: {}", missing_fields.unwrap_or_default().join(","));
     }
+
+    #[test]
+    fn nginx_ingress_chart_waf_override_test() {
+        let chart = |waf_mode: WafMode| {
+            NginxIngressChart::new(
+                None,
+                HelmChartResourcesConstraintType::ChartDefault,
+                HelmChartResourcesConstraintType::ChartDefault,
+                true,
+                get_nginx_ingress_chart_override(),
+                get_domain().wildcarded(),
+                Kind::Aws,
+                KubernetesKind::Eks,
+                None,
+                None,
+                None,
+                HelmChartNamespaces::NginxIngress,
+                None,
+                false,
+                false,
+                waf_mode,
+                vec!["SecRuleRemoveById 920350".to_string()],
+            )
+            .to_common_helm_chart()
+            .unwrap()
+            .chart_info
+            .yaml_files_content[0]
+                .yaml_content
+                .clone()
+        };
+
+        let qovery_override = chart(WafMode::Blocking);
+        assert!(qovery_override.contains("enable-modsecurity: \"true\""));
+        assert!(qovery_override.contains("SecRuleEngine On\n            SecRuleRemoveById 920350"));
+        assert!(chart(WafMode::DetectionOnly).contains("SecRuleEngine DetectionOnly"));
+        assert!(!chart(WafMode::Disabled).contains("modsecurity"));
+    }
 }
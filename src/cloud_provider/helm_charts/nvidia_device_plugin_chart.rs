@@ -0,0 +1,135 @@
+use crate::cloud_provider::helm::{
+    ChartInfo, ChartSetValue, ChartValuesGenerated, CommonChart, HelmChartError, HelmChartNamespaces,
+};
+use crate::cloud_provider::helm_charts::{HelmChartDirectoryLocation, HelmChartPath, ToCommonHelmChart};
+use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit, GPU_NODE_LABEL};
+
+pub struct NvidiaDevicePluginChart {
+    chart_path: HelmChartPath,
+    namespace: HelmChartNamespaces,
+}
+
+impl NvidiaDevicePluginChart {
+    pub fn new(chart_prefix_path: Option<&str>, namespace: HelmChartNamespaces) -> Self {
+        NvidiaDevicePluginChart {
+            chart_path: HelmChartPath::new(
+                chart_prefix_path,
+                HelmChartDirectoryLocation::CommonFolder,
+                NvidiaDevicePluginChart::chart_name(),
+            ),
+            namespace,
+        }
+    }
+
+    pub fn chart_name() -> String {
+        "nvidia-device-plugin".to_string()
+    }
+
+    // upstream chart schedules the plugin on the nodes labelled by node-feature-discovery, which is not deployed:
+    // its affinity is dropped and the plugin runs on the nodes of GPU node groups instead
+    fn scheduling_values(&self) -> Result<ChartValuesGenerated, HelmChartError> {
+        let values = serde_json::json!({
+            "affinity": null,
+            "nodeSelector": { GPU_NODE_LABEL: "true" },
+        });
+        let yaml_content = serde_yaml::to_string(&values).map_err(|e| HelmChartError::RenderingError {
+            chart_name: NvidiaDevicePluginChart::chart_name(),
+            msg: e.to_string(),
+        })?;
+
+        Ok(ChartValuesGenerated::new(
+            "qovery_nvidia_device_plugin_scheduling".to_string(),
+            yaml_content,
+        ))
+    }
+}
+
+impl ToCommonHelmChart for NvidiaDevicePluginChart {
+    fn to_common_helm_chart(&self) -> Result<CommonChart, HelmChartError> {
+        Ok(CommonChart {
+            chart_info: ChartInfo {
+                name: NvidiaDevicePluginChart::chart_name(),
+                namespace: self.namespace,
+                path: self.chart_path.to_string(),
+                // keys of the upstream chart, pinned in helm-freeze
+                values: vec![
+                    ChartSetValue {
+                        key: "migStrategy".to_string(),
+                        value: "none".to_string(),
+                    },
+                    ChartSetValue {
+                        key: "failOnInitError".to_string(),
+                        value: "true".to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.limits.cpu".to_string(),
+                        value: KubernetesCpuResourceUnit::MilliCpu(100).to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.limits.memory".to_string(),
+                        value: KubernetesMemoryResourceUnit::MebiByte(128).to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.requests.cpu".to_string(),
+                        value: KubernetesCpuResourceUnit::MilliCpu(50).to_string(),
+                    },
+                    ChartSetValue {
+                        key: "resources.requests.memory".to_string(),
+                        value: KubernetesMemoryResourceUnit::MebiByte(64).to_string(),
+                    },
+                ],
+                yaml_files_content: vec![self.scheduling_values()?],
+                ..Default::default()
+            },
+            chart_installation_checker: None,
+            vertical_pod_autoscaler: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::helm::HelmChartNamespaces;
+    use crate::cloud_provider::helm_charts::nvidia_device_plugin_chart::NvidiaDevicePluginChart;
+    use crate::cloud_provider::helm_charts::{
+        get_helm_path_kubernetes_provider_sub_folder_name, HelmChartType, ToCommonHelmChart,
+    };
+    use crate::cloud_provider::models::GPU_NODE_LABEL;
+    use std::env;
+
+    /// Makes sure chart directory containing all YAML files exists.
+    #[test]
+    fn nvidia_device_plugin_chart_directory_exists_test() {
+        // setup:
+        let chart = NvidiaDevicePluginChart::new(None, HelmChartNamespaces::KubeSystem);
+
+        let current_directory = env::current_dir().expect("Impossible to get current directory");
+        let chart_path = format!(
+            "{}/lib/{}/bootstrap/charts/{}/Chart.yaml",
+            current_directory
+                .to_str()
+                .expect("Impossible to convert current directory to string"),
+            get_helm_path_kubernetes_provider_sub_folder_name(chart.chart_path.helm_path(), HelmChartType::Shared),
+            NvidiaDevicePluginChart::chart_name(),
+        );
+
+        // execute
+        let values_file = std::fs::File::open(&chart_path);
+
+        // verify:
+        assert!(values_file.is_ok(), "Chart directory should exist: `{chart_path}`");
+    }
+
+    #[test]
+    fn nvidia_device_plugin_chart_scheduling_values_test() {
+        let chart = NvidiaDevicePluginChart::new(None, HelmChartNamespaces::KubeSystem)
+            .to_common_helm_chart()
+            .expect("Cannot build chart");
+
+        let values: serde_yaml::Value =
+            serde_yaml::from_str(&chart.chart_info.yaml_files_content[0].yaml_content).expect("Invalid yaml values");
+        // null removes the affinity of the upstream values, which requires node-feature-discovery labels
+        assert_eq!(values["affinity"], serde_yaml::Value::Null);
+        assert_eq!(values["nodeSelector"][GPU_NODE_LABEL].as_str(), Some("true"));
+    }
+}
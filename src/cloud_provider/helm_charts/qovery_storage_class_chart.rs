@@ -13,6 +13,11 @@ use kube::Api;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 
+/// ReadWriteMany storage class of the EFS filesystem, deployed when the cluster has shared volumes enabled
+pub const AWS_EFS_STORAGE_CLASS_NAME: &str = "aws-efs-rwx";
+/// ReadWriteMany storage class GKE provides with its Filestore CSI driver
+pub const GKE_FILESTORE_STORAGE_CLASS_NAME: &str = "standard-rwx";
+
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub enum QoveryStorageType {
     Ssd,
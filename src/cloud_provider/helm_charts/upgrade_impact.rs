@@ -0,0 +1,279 @@
+use crate::cloud_provider::helm::ChartInfo;
+use crate::cmd::helm::Helm;
+use crate::events::{EventMessage, MessageCode};
+use crate::runtime::block_on;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::ListParams;
+use kube::Api;
+use semver::Version;
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::File;
+
+const ENVIRONMENT_ID_LABEL: &str = "qovery.com/environment-id";
+const SERVICE_ID_LABEL: &str = "qovery.com/service-id";
+const CERT_MANAGER_ISSUER_ANNOTATION: &str = "cert-manager.io/cluster-issuer";
+
+/// What environments go through while a cluster chart is upgraded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartDisruption {
+    WebhookDowntime,
+    LoadBalancerRecreation,
+    VolumeOperationsPause,
+}
+
+impl ChartDisruption {
+    pub fn for_chart(chart_name: &str) -> Option<ChartDisruption> {
+        match chart_name {
+            "cert-manager" | "qovery-cert-manager-webhook" => Some(ChartDisruption::WebhookDowntime),
+            // ingress-nginx is still released under its former name
+            "nginx-ingress" | "ingress-nginx" => Some(ChartDisruption::LoadBalancerRecreation),
+            "aws-ebs-csi-driver" => Some(ChartDisruption::VolumeOperationsPause),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ChartDisruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChartDisruption::WebhookDowntime => write!(
+                f,
+                "stops cert-manager webhooks while its pods restart, certificates can be neither issued nor renewed meanwhile"
+            ),
+            ChartDisruption::LoadBalancerRecreation => write!(
+                f,
+                "may recreate the ingress load balancer, traffic is cut until the DNS records target its new address"
+            ),
+            ChartDisruption::VolumeOperationsPause => write!(
+                f,
+                "restarts the CSI driver, volumes can be neither provisioned, attached nor resized meanwhile"
+            ),
+        }
+    }
+}
+
+/// Services a chart upgrade disrupts, by environment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChartUpgradeImpact {
+    pub chart_name: String,
+    pub installed_version: Version,
+    pub target_version: Version,
+    pub disruption: ChartDisruption,
+    pub disrupted_services: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ChartUpgradeImpact {
+    pub fn to_event_message(&self) -> EventMessage {
+        let details = self
+            .disrupted_services
+            .iter()
+            .map(|(environment_id, service_ids)| {
+                format!(
+                    "environment {}: {}",
+                    environment_id,
+                    service_ids.iter().cloned().collect::<Vec<_>>().join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        EventMessage::new_from_code(
+            MessageCode::ChartUpgradeImpact,
+            &[
+                ("chart", self.chart_name.to_string()),
+                ("installed_version", self.installed_version.to_string()),
+                ("target_version", self.target_version.to_string()),
+                ("disruption", self.disruption.to_string()),
+                (
+                    "services",
+                    self.disrupted_services
+                        .values()
+                        .map(BTreeSet::len)
+                        .sum::<usize>()
+                        .to_string(),
+                ),
+                ("environments", self.disrupted_services.len().to_string()),
+            ],
+            Some(details),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ChartDotYaml {
+    version: String,
+}
+
+fn chart_version(chart: &ChartInfo) -> Option<Version> {
+    let chart_file = File::open(format!("{}/Chart.yaml", chart.path)).ok()?;
+    let chart_dot_yaml: ChartDotYaml = serde_yaml::from_reader(chart_file).ok()?;
+
+    Version::parse(chart_dot_yaml.version.trim_start_matches('v')).ok()
+}
+
+fn group_by_environment(
+    labels: impl IntoIterator<Item = BTreeMap<String, String>>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut services_by_environment: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for labels in labels {
+        if let (Some(environment_id), Some(service_id)) =
+            (labels.get(ENVIRONMENT_ID_LABEL), labels.get(SERVICE_ID_LABEL))
+        {
+            services_by_environment
+                .entry(environment_id.to_string())
+                .or_default()
+                .insert(service_id.to_string());
+        }
+    }
+
+    services_by_environment
+}
+
+async fn disrupted_services(
+    kube: &kube::Client,
+    disruption: ChartDisruption,
+) -> Result<BTreeMap<String, BTreeSet<String>>, kube::Error> {
+    // only the resources of services deployed by Qovery carry the environment label
+    let params = ListParams::default().labels(ENVIRONMENT_ID_LABEL);
+    let labels: Vec<BTreeMap<String, String>> = match disruption {
+        ChartDisruption::WebhookDowntime | ChartDisruption::LoadBalancerRecreation => {
+            let ingresses: Api<Ingress> = Api::all(kube.clone());
+            ingresses
+                .list(&params)
+                .await?
+                .into_iter()
+                .filter(|ingress| {
+                    disruption != ChartDisruption::WebhookDowntime
+                        || ingress
+                            .metadata
+                            .annotations
+                            .as_ref()
+                            .map(|annotations| annotations.contains_key(CERT_MANAGER_ISSUER_ANNOTATION))
+                            .unwrap_or(false)
+                })
+                .filter_map(|ingress| ingress.metadata.labels)
+                .collect()
+        }
+        ChartDisruption::VolumeOperationsPause => {
+            let pvcs: Api<PersistentVolumeClaim> = Api::all(kube.clone());
+            pvcs.list(&params)
+                .await?
+                .into_iter()
+                .filter_map(|pvc| pvc.metadata.labels)
+                .collect()
+        }
+    };
+
+    Ok(group_by_environment(labels))
+}
+
+/// Impacts of upgrading the disruptive charts among the given ones, on the services deployed on the cluster.
+/// Fresh installs and charts keeping their version are left out, as well as charts whose impact cannot be analyzed:
+/// the analysis must never prevent the upgrade itself.
+pub fn charts_upgrade_impacts(
+    kube: &kube::Client,
+    helm: &Helm,
+    charts: &[&ChartInfo],
+    envs: &[(&str, &str)],
+) -> Vec<ChartUpgradeImpact> {
+    let mut impacts = vec![];
+
+    for chart in charts {
+        let Some(disruption) = ChartDisruption::for_chart(&chart.name) else {
+            continue;
+        };
+        let Some(target_version) = chart_version(chart) else {
+            continue;
+        };
+        let installed_version = match helm.get_chart_version(&chart.name, Some(&chart.get_namespace_string()), envs) {
+            Ok(versions) => versions.and_then(|versions| versions.chart_version),
+            Err(err) => {
+                warn!("Cannot get installed version of chart {}: {:?}", chart.name, err);
+                continue;
+            }
+        };
+        let Some(installed_version) = installed_version.filter(|version| version != &target_version) else {
+            continue;
+        };
+
+        let disrupted_services = match block_on(disrupted_services(kube, disruption)) {
+            Ok(disrupted_services) => disrupted_services,
+            Err(err) => {
+                warn!("Cannot analyze the impact of upgrading chart {}: {}", chart.name, err);
+                continue;
+            }
+        };
+        if disrupted_services.is_empty() {
+            continue;
+        }
+
+        impacts.push(ChartUpgradeImpact {
+            chart_name: chart.name.to_string(),
+            installed_version,
+            target_version,
+            disruption,
+            disrupted_services,
+        });
+    }
+
+    impacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventMessageVerbosity;
+
+    fn labels(environment_id: &str, service_id: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (ENVIRONMENT_ID_LABEL.to_string(), environment_id.to_string()),
+            (SERVICE_ID_LABEL.to_string(), service_id.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_group_by_environment() {
+        let services_by_environment = group_by_environment(vec![
+            labels("env-1", "app-1"),
+            labels("env-1", "app-2"),
+            // several volumes of the same service
+            labels("env-1", "app-2"),
+            labels("env-2", "db-1"),
+            BTreeMap::from([(ENVIRONMENT_ID_LABEL.to_string(), "env-3".to_string())]),
+        ]);
+
+        assert_eq!(
+            services_by_environment,
+            BTreeMap::from([
+                ("env-1".to_string(), BTreeSet::from(["app-1".to_string(), "app-2".to_string()])),
+                ("env-2".to_string(), BTreeSet::from(["db-1".to_string()])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_chart_upgrade_impact_event_message() {
+        let impact = ChartUpgradeImpact {
+            chart_name: "cert-manager".to_string(),
+            installed_version: Version::new(1, 11, 0),
+            target_version: Version::new(1, 14, 4),
+            disruption: ChartDisruption::for_chart("cert-manager").unwrap(),
+            disrupted_services: group_by_environment(vec![
+                labels("env-1", "app-1"),
+                labels("env-1", "app-2"),
+                labels("env-2", "app-3"),
+            ]),
+        };
+
+        let message = impact.to_event_message();
+        assert_eq!(message.parameters().get("services"), Some(&"3".to_string()));
+        assert_eq!(message.parameters().get("environments"), Some(&"2".to_string()));
+        assert!(message
+            .message(EventMessageVerbosity::FullDetailsWithoutEnvVars)
+            .ends_with("environment env-1: app-1, app-2\nenvironment env-2: app-3"));
+        assert_eq!(ChartDisruption::for_chart("loki"), None);
+    }
+}
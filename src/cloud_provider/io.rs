@@ -1,3 +1,7 @@
+use crate::build_platform::sbom::SbomFormat;
+use crate::io_models::container::{Credentials, Registry};
+use crate::io_models::WafMode;
+use crate::object_storage::bucket_encryption::BucketEncryption;
 use crate::{cloud_provider::Kind as KindModel, errors::EngineError, events::EventDetails};
 use base64::engine::general_purpose;
 use base64::Engine;
@@ -5,6 +9,10 @@ use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str;
 use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
+
+const DOCKER_HUB_URL: &str = "https://docker.io";
 
 pub const CLOUDWATCH_RETENTION_DAYS: &[u32] = &[
     0, 1, 3, 5, 7, 14, 30, 60, 90, 120, 150, 180, 365, 400, 545, 731, 1827, 2192, 2557, 2922, 3288, 3653,
@@ -104,6 +112,33 @@ pub struct ClusterAdvancedSettings {
     pub database_mongodb_allowed_cidrs: Vec<String>,
     #[serde(alias = "registry.mirroring_mode", default = "default_registry_mirroring_mode")]
     pub registry_mirroring_mode: RegistryMirroringMode,
+    /// Docker Hub account used instead of anonymous pulls, which are rate limited per node IP
+    #[serde(alias = "registry.docker_hub.login")]
+    pub registry_docker_hub_login: Option<String>,
+    #[serde(alias = "registry.docker_hub.password")]
+    pub registry_docker_hub_password: Option<String>,
+    /// Pull through cache of Docker Hub (i.e: https://mirror.gcr.io), public Docker Hub images are pulled from it
+    #[serde(alias = "registry.docker_hub.proxy_url")]
+    pub registry_docker_hub_proxy_url: Option<Url>,
+    /// Base images of the Dockerfiles are copied into the cluster registry before the builds, and pulled from it
+    #[serde(alias = "registry.base_image_mirroring.enabled")]
+    pub registry_base_image_mirroring_enabled: bool,
+    /// Public registries whose base images are copied, i.e: `docker.io`
+    #[serde(alias = "registry.base_image_mirroring.registries")]
+    pub registry_base_image_mirroring_registries: Vec<String>,
+    /// Cosign key the built images are signed with, and verified with before being deployed.
+    /// i.e: `hashivault://qovery-images` or `awskms:///alias/qovery-images`
+    #[serde(alias = "registry.image_signing.key")]
+    pub registry_image_signing_key: Option<String>,
+    /// Regions the images pushed to the registry of the cluster are replicated to, for the clusters running there
+    #[serde(alias = "registry.replication.regions")]
+    pub registry_replication_regions: Vec<String>,
+    /// BuildKit caches of the services are kept in the object storage of the cluster between builds
+    #[serde(alias = "build.object_storage_cache.enabled")]
+    pub build_object_storage_cache_enabled: bool,
+    /// Format of the SBOM generated for the built images and kept in the object storage of the cluster, none if disabled
+    #[serde(alias = "build.sbom.format")]
+    pub build_sbom_format: Option<SbomFormat>,
     #[serde(alias = "nginx.vcpu.request_in_milli_cpu")]
     pub nginx_vcpu_request_in_milli_cpu: u32,
     #[serde(alias = "nginx.vcpu.limit_in_milli_cpu")]
@@ -118,8 +153,44 @@ pub struct ClusterAdvancedSettings {
     pub nginx_hpa_min_number_instances: u32,
     #[serde(alias = "nginx.hpa.max_number_instances")]
     pub nginx_hpa_max_number_instances: u32,
+    /// Web application firewall of all the routers, unless they have their own mode
+    #[serde(alias = "nginx.waf.mode")]
+    pub nginx_waf_mode: WafMode,
+    /// ModSecurity rules added to the OWASP core rule set, i.e: `SecRuleRemoveById 920350`
+    #[serde(alias = "nginx.waf.custom_rules")]
+    pub nginx_waf_custom_rules: Vec<String>,
+    /// Routers can set their own `configuration-snippet` and `server-snippet` annotations, the raw nginx configuration
+    /// they hold applying to the whole controller
+    #[serde(alias = "nginx.custom_annotations.snippets_enabled")]
+    pub nginx_custom_snippet_annotations_enabled: bool,
     #[serde(alias = "scaleway.enable_private_network_migration")]
     pub scaleway_enable_private_network_migration: bool,
+    /// The cluster network has both IPv4 and IPv6 addresses, load balancers and DNS records get both
+    #[serde(alias = "network.enable_dual_stack")]
+    pub network_enable_dual_stack: bool,
+    /// KMS key (AWS key id or ARN, GCP Cloud KMS key resource name) required to encrypt the Qovery managed buckets
+    #[serde(alias = "object_storage.kms_key_id")]
+    pub object_storage_kms_key_id: Option<String>,
+    /// ReadWriteMany storage backing the shared volumes of the environments: an EFS filesystem on EKS, an NFS server
+    /// on Kapsule. GKE clusters always have it with Filestore.
+    #[serde(alias = "storage.shared_volumes_enabled")]
+    pub storage_shared_volumes_enabled: bool,
+    /// KEDA is installed on EKS and Kapsule clusters, scaling the services on the triggers of their
+    /// `hpa.keda.triggers` advanced setting
+    #[serde(alias = "keda.enabled")]
+    pub keda_enabled: bool,
+    /// External Secrets Operator is installed on EKS and Kapsule clusters, syncing the `deployment.external_secrets`
+    /// of the services from their secret store
+    #[serde(alias = "external_secrets.enabled")]
+    pub external_secrets_enabled: bool,
+    /// Address of a Vault server the `vault` secret store of the cluster reads the KV v2 secrets of, with the
+    /// kubernetes auth method
+    #[serde(alias = "external_secrets.vault.server")]
+    pub external_secrets_vault_server: Option<String>,
+    #[serde(alias = "external_secrets.vault.path")]
+    pub external_secrets_vault_path: String,
+    #[serde(alias = "external_secrets.vault.role")]
+    pub external_secrets_vault_role: String,
 }
 
 impl Default for ClusterAdvancedSettings {
@@ -148,6 +219,15 @@ impl Default for ClusterAdvancedSettings {
             database_mongodb_deny_public_access: false,
             database_mongodb_allowed_cidrs: default_database_cirds,
             registry_mirroring_mode: RegistryMirroringMode::Service,
+            registry_docker_hub_login: None,
+            registry_docker_hub_password: None,
+            registry_docker_hub_proxy_url: None,
+            registry_base_image_mirroring_enabled: false,
+            registry_base_image_mirroring_registries: vec!["docker.io".to_string(), "quay.io".to_string()],
+            registry_image_signing_key: None,
+            registry_replication_regions: vec![],
+            build_object_storage_cache_enabled: false,
+            build_sbom_format: None,
             nginx_vcpu_request_in_milli_cpu: 100,
             nginx_vcpu_limit_in_milli_cpu: 500,
             nginx_memory_request_in_mib: 768,
@@ -155,10 +235,21 @@ impl Default for ClusterAdvancedSettings {
             nginx_hpa_cpu_utilization_percentage_threshold: 50,
             nginx_hpa_min_number_instances: 2,
             nginx_hpa_max_number_instances: 25,
+            nginx_waf_mode: WafMode::Disabled,
+            nginx_waf_custom_rules: vec![],
+            nginx_custom_snippet_annotations_enabled: false,
             scaleway_enable_private_network_migration: false,
             aws_eks_encrypt_secrets_kms_key_arn: "".to_string(),
             aws_enable_karpenter: false,
             aws_karpenter_max_node_drain_in_sec: None,
+            network_enable_dual_stack: false,
+            object_storage_kms_key_id: None,
+            storage_shared_volumes_enabled: false,
+            keda_enabled: false,
+            external_secrets_enabled: false,
+            external_secrets_vault_server: None,
+            external_secrets_vault_path: "secret".to_string(),
+            external_secrets_vault_role: "external-secrets".to_string(),
         }
     }
 }
@@ -177,6 +268,35 @@ impl ClusterAdvancedSettings {
         Ok(())
     }
 
+    pub fn docker_hub_credentials(&self) -> Option<Credentials> {
+        match (&self.registry_docker_hub_login, &self.registry_docker_hub_password) {
+            (Some(login), Some(password)) if !login.is_empty() && !password.is_empty() => Some(Credentials {
+                login: login.to_string(),
+                password: password.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Docker Hub registry authenticated with the cluster account, to log in before pulling base images
+    pub fn docker_hub_registry(&self) -> Option<Registry> {
+        let url = Url::parse(DOCKER_HUB_URL).ok()?;
+        self.docker_hub_credentials().map(|credentials| Registry::DockerHub {
+            long_id: Uuid::nil(),
+            url,
+            credentials: Some(credentials),
+        })
+    }
+
+    pub fn bucket_encryption(&self) -> BucketEncryption {
+        match &self.object_storage_kms_key_id {
+            Some(key_id) if !key_id.is_empty() => BucketEncryption::Kms {
+                key_id: key_id.to_string(),
+            },
+            _ => BucketEncryption::ProviderManaged,
+        }
+    }
+
     pub fn resource_ttl(&self) -> Option<Duration> {
         if self.pleco_resources_ttl >= 0 {
             Some(Duration::new(self.pleco_resources_ttl as u64, 0))
@@ -214,6 +334,7 @@ mod tests {
     use uuid::Uuid;
 
     use crate::cloud_provider::io::{ClusterAdvancedSettings, RegistryMirroringMode};
+    use crate::io_models::WafMode;
     use crate::{
         cloud_provider::io::validate_aws_cloudwatch_eks_logs_retention_days,
         events::{EventDetails, Stage, Transmitter},
@@ -333,5 +454,21 @@ mod tests {
         );
         assert_eq!(cluster_advanced_settings.nginx_hpa_min_number_instances, 2);
         assert_eq!(cluster_advanced_settings.nginx_hpa_max_number_instances, 25);
+        assert_eq!(cluster_advanced_settings.nginx_waf_mode, WafMode::Disabled);
+    }
+
+    #[test]
+    fn test_nginx_waf_deserialization() {
+        let data = r#"
+        {
+            "nginx.waf.mode": "DetectionOnly",
+            "nginx.waf.custom_rules": ["SecRuleRemoveById 920350"]
+        }"#;
+        let cluster_advanced_settings: ClusterAdvancedSettings = serde_json::from_str(data).unwrap();
+        assert_eq!(cluster_advanced_settings.nginx_waf_mode, WafMode::DetectionOnly);
+        assert_eq!(
+            cluster_advanced_settings.nginx_waf_custom_rules,
+            vec!["SecRuleRemoveById 920350".to_string()]
+        );
     }
 }
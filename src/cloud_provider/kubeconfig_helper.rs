@@ -17,7 +17,7 @@ fn get_kubeconfig_filename(cluster_id: &Uuid) -> String {
     format!("{}.yaml", to_short_id(cluster_id))
 }
 
-fn get_bucket_name(cluster_id: &Uuid) -> String {
+pub(crate) fn get_bucket_name(cluster_id: &Uuid) -> String {
     format!("qovery-kubeconfigs-{}", to_short_id(cluster_id))
 }
 
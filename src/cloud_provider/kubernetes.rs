@@ -20,10 +20,14 @@ use tracing::Span;
 use uuid::Uuid;
 
 use crate::cloud_provider::io::ClusterAdvancedSettings;
-use crate::cloud_provider::models::{CpuArchitecture, CpuLimits, InstanceEc2, NodeGroups};
+use crate::cloud_provider::kubernetes_api::find_removed_apis;
+use crate::cloud_provider::models::{
+    CpuArchitecture, CpuLimits, InstanceEc2, NodeGroups, NodeTaint, TaintEffect, GPU_NODE_LABEL, GPU_RESOURCE_NAME,
+};
 use crate::cloud_provider::service::Action;
 use crate::cloud_provider::CloudProvider;
 use crate::cloud_provider::Kind as CloudProviderKind;
+use crate::cmd::helm::Helm;
 use crate::cmd::kubectl::kubectl_delete_apiservice;
 use crate::cmd::kubectl::{
     kubectl_delete_objects_in_all_namespaces, kubectl_exec_count_all_objects, kubectl_exec_get_node,
@@ -38,6 +42,8 @@ use crate::io_models::engine_request::{ChartValuesOverrideName, ChartValuesOverr
 use crate::io_models::QoveryIdentifier;
 use crate::logger::Logger;
 use crate::models::types::VersionsNumber;
+use crate::models::utils::validate_labels;
+use crate::object_storage::ObjectStorage;
 use crate::services::kube_client::QubeClient;
 
 use super::models::NodeGroupsWithDesiredState;
@@ -387,6 +393,10 @@ pub trait Kubernetes: Send + Sync {
     fn on_pause(&self) -> Result<(), Box<EngineError>>;
     fn on_delete(&self) -> Result<(), Box<EngineError>>;
     fn temp_dir(&self) -> &Path;
+    /// Object storage holding the kubeconfig of the cluster, self managed clusters have none
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        None
+    }
 
     fn update_vault_config(
         &self,
@@ -555,6 +565,9 @@ impl NodeGroupsWithDesiredState {
             instance_type: nodegroup.instance_type.clone(),
             disk_size_in_gib: nodegroup.disk_size_in_gib,
             instance_architecture: nodegroup.instance_architecture,
+            labels: nodegroup.node_labels(),
+            taints: nodegroup.node_taints(),
+            gpu: nodegroup.gpu,
         }
     }
 }
@@ -597,7 +610,7 @@ where
 
     // check workers versions
     let mut workers_version: Vec<VersionsNumber> = vec![];
-    let nodes = match kubectl_exec_get_node(kubernetes_config, envs) {
+    let nodes = match kubectl_exec_get_node(&kubernetes_config, envs.clone()) {
         Ok(n) => n,
         Err(e) => return Err(Box::new(EngineError::new_cannot_get_cluster_nodes(event_details, e))),
     };
@@ -626,7 +639,62 @@ where
         }
     }
 
-    check_kubernetes_upgrade_status(requested_version, masters_version, workers_version, event_details, logger)
+    let upgrade_status = check_kubernetes_upgrade_status(
+        requested_version.clone(),
+        masters_version,
+        workers_version,
+        event_details.clone(),
+        logger,
+    )?;
+    if upgrade_status.required_upgrade_on.is_some() {
+        warn_about_removed_apis(kubernetes_config, &requested_version, &envs, event_details, logger);
+    }
+
+    Ok(upgrade_status)
+}
+
+/// Warns about the deployed helm releases using API versions the requested cluster version does not serve anymore,
+/// as they cannot be upgraded nor rolled back once the cluster is upgraded
+fn warn_about_removed_apis<P>(
+    kubernetes_config: P,
+    requested_version: &KubernetesVersion,
+    envs: &[(&str, &str)],
+    event_details: EventDetails,
+    logger: &dyn Logger,
+) where
+    P: AsRef<Path>,
+{
+    let helm_releases = Helm::new(kubernetes_config, envs)
+        .and_then(|helm| helm.list_release_items(None, &[]).map(|releases| (helm, releases)));
+    let (helm, releases) = match helm_releases {
+        Ok(helm_releases) => helm_releases,
+        Err(err) => {
+            logger.log(EngineEvent::Warning(
+                event_details,
+                EventMessage::new(
+                    "Cannot check deployed releases against the APIs removed by the upgrade".to_string(),
+                    Some(err.to_string()),
+                ),
+            ));
+            return;
+        }
+    };
+
+    for release in releases {
+        let Ok(manifest) = helm.get_release_manifest(&release.name, &release.namespace, &[]) else {
+            continue;
+        };
+
+        for removed_api in find_removed_apis(&manifest, requested_version) {
+            logger.log(EngineEvent::Warning(
+                event_details.clone(),
+                EventMessage::new_from_safe(format!(
+                    "Release `{}` in namespace `{}`: {removed_api}",
+                    release.name, release.namespace
+                )),
+            ));
+        }
+    }
 }
 
 pub fn is_kubernetes_upgradable<P>(
@@ -1026,9 +1094,49 @@ impl NodeGroups {
             disk_size_in_gib,
             desired_nodes: None,
             instance_architecture,
+            labels: BTreeMap::new(),
+            taints: vec![],
+            gpu: false,
         })
     }
 
+    /// Labels set on the nodes, GPU nodes are labeled for the device plugin to find them
+    pub fn node_labels(&self) -> BTreeMap<String, String> {
+        let mut labels = self.labels.clone();
+        if self.gpu {
+            labels.insert(GPU_NODE_LABEL.to_string(), "true".to_string());
+        }
+        labels
+    }
+
+    /// Taints set on the nodes, GPU nodes are tainted unless the user already did it,
+    /// so the services not requesting GPUs do not take their place
+    pub fn node_taints(&self) -> Vec<NodeTaint> {
+        let mut taints = self.taints.clone();
+        if self.gpu && !taints.iter().any(|taint| taint.key == GPU_RESOURCE_NAME) {
+            taints.push(NodeTaint {
+                key: GPU_RESOURCE_NAME.to_string(),
+                value: "true".to_string(),
+                effect: TaintEffect::NoSchedule,
+            });
+        }
+        taints
+    }
+
+    pub fn validate_labels_and_taints(&self) -> Result<(), String> {
+        validate_labels(&self.labels)
+            .and_then(|_| {
+                validate_labels(
+                    &self
+                        .taints
+                        .iter()
+                        .map(|taint| (taint.key.clone(), taint.value.clone()))
+                        .collect(),
+                )
+            })
+            .map_err(|e| format!("Node group `{}`: {e}", self.name))
+    }
+
     pub fn to_ec2_instance(&self) -> InstanceEc2 {
         InstanceEc2 {
             instance_type: self.instance_type.clone(),
@@ -1081,7 +1189,7 @@ where
             "Infrastructure '{}' deletion is in progress...",
             kubernetes.name_with_id()
         )),
-        Action::Restart => None,
+        Action::Restart | Action::TriggerNow => None,
     };
 
     send_progress_on_long_task_with_message(kubernetes, waiting_message, action, long_task)
@@ -1147,8 +1255,8 @@ where
                             event_message,
                         ));
                     }
-                    Action::Restart => {
-                        // restart is not implemented yet
+                    Action::Restart | Action::TriggerNow => {
+                        // restart and trigger are not supported on infrastructure
                     }
                 };
 
@@ -1346,7 +1454,9 @@ mod tests {
         kube_copy_secret_to_another_namespace, kube_create_namespace_if_not_exists, kube_does_secret_exists,
         kube_list_services, KubernetesVersion as K8sVersion,
     };
-    use crate::cloud_provider::models::CpuLimits;
+    use crate::cloud_provider::models::{
+        CpuArchitecture, CpuLimits, NodeGroups, NodeTaint, TaintEffect, GPU_NODE_LABEL, GPU_RESOURCE_NAME,
+    };
     use crate::cmd::structs::{KubernetesList, KubernetesNode, KubernetesVersion};
     use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
     use crate::io_models::QoveryIdentifier;
@@ -2137,4 +2247,31 @@ mod tests {
         );
         assert_eq!(version_full.to_string(), "v1.24.16+k3s1".to_string())
     }
+
+    #[test]
+    fn test_gpu_node_group_labels_and_taints() {
+        let mut node_group =
+            NodeGroups::new("gpu".to_string(), 1, 2, "g5.xlarge".to_string(), 100, CpuArchitecture::AMD64).unwrap();
+        assert!(node_group.node_labels().is_empty());
+        assert!(node_group.node_taints().is_empty());
+
+        node_group.gpu = true;
+        assert_eq!(node_group.node_labels().get(GPU_NODE_LABEL).map(String::as_str), Some("true"));
+        assert_eq!(
+            node_group.node_taints(),
+            vec![NodeTaint {
+                key: GPU_RESOURCE_NAME.to_string(),
+                value: "true".to_string(),
+                effect: TaintEffect::NoSchedule,
+            }]
+        );
+
+        // a taint set by the user on the GPU resource is kept as is
+        node_group.taints = vec![NodeTaint {
+            key: GPU_RESOURCE_NAME.to_string(),
+            value: "".to_string(),
+            effect: TaintEffect::NoExecute,
+        }];
+        assert_eq!(node_group.node_taints(), node_group.taints);
+    }
 }
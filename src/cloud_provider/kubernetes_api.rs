@@ -0,0 +1,235 @@
+use crate::cloud_provider::kubernetes::KubernetesVersion;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// API version of a kind, served by the clusters from a minor version until the one removing it
+struct ServedApi {
+    kind: &'static str,
+    api_version: &'static str,
+    served_from: u8,
+    removed_in: Option<u8>,
+}
+
+const fn served_api(
+    kind: &'static str,
+    api_version: &'static str,
+    served_from: u8,
+    removed_in: Option<u8>,
+) -> ServedApi {
+    ServedApi {
+        kind,
+        api_version,
+        served_from,
+        removed_in,
+    }
+}
+
+// https://kubernetes.io/docs/reference/using-api/deprecation-guide/
+// versions of a kind are listed from the oldest to the most recent
+const SERVED_APIS: &[ServedApi] = &[
+    served_api("Ingress", "extensions/v1beta1", 0, Some(22)),
+    served_api("Ingress", "networking.k8s.io/v1beta1", 14, Some(22)),
+    served_api("Ingress", "networking.k8s.io/v1", 19, None),
+    served_api("IngressClass", "networking.k8s.io/v1beta1", 18, Some(22)),
+    served_api("IngressClass", "networking.k8s.io/v1", 19, None),
+    served_api("HorizontalPodAutoscaler", "autoscaling/v2beta1", 8, Some(25)),
+    served_api("HorizontalPodAutoscaler", "autoscaling/v2beta2", 12, Some(26)),
+    served_api("HorizontalPodAutoscaler", "autoscaling/v2", 23, None),
+    served_api("PodDisruptionBudget", "policy/v1beta1", 5, Some(25)),
+    served_api("PodDisruptionBudget", "policy/v1", 21, None),
+    served_api("CronJob", "batch/v1beta1", 8, Some(25)),
+    served_api("CronJob", "batch/v1", 21, None),
+    served_api("PodSecurityPolicy", "policy/v1beta1", 0, Some(25)),
+    served_api("EndpointSlice", "discovery.k8s.io/v1beta1", 17, Some(25)),
+    served_api("EndpointSlice", "discovery.k8s.io/v1", 21, None),
+    served_api("RuntimeClass", "node.k8s.io/v1beta1", 14, Some(25)),
+    served_api("RuntimeClass", "node.k8s.io/v1", 20, None),
+    served_api("FlowSchema", "flowcontrol.apiserver.k8s.io/v1beta1", 20, Some(26)),
+    served_api("FlowSchema", "flowcontrol.apiserver.k8s.io/v1beta2", 23, Some(29)),
+    served_api("FlowSchema", "flowcontrol.apiserver.k8s.io/v1beta3", 26, Some(32)),
+    served_api("FlowSchema", "flowcontrol.apiserver.k8s.io/v1", 29, None),
+    served_api("CSIStorageCapacity", "storage.k8s.io/v1beta1", 21, Some(27)),
+    served_api("CSIStorageCapacity", "storage.k8s.io/v1", 24, None),
+];
+
+/// Most recent API version of a kind served by a cluster, none if the kind is not known or not served anymore
+fn served_api_version(kind: &str, minor: u8) -> Option<&'static str> {
+    SERVED_APIS
+        .iter()
+        .filter(|api| {
+            api.kind == kind && api.served_from <= minor && api.removed_in.map_or(true, |removed_in| minor < removed_in)
+        })
+        .last()
+        .map(|api| api.api_version)
+}
+
+/// API versions the charts of the services are rendered with, the most recent ones served by the cluster
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct KubernetesApiVersions {
+    pub ingress: &'static str,
+    pub horizontal_pod_autoscaler: &'static str,
+    pub pod_disruption_budget: &'static str,
+    pub cron_job: &'static str,
+}
+
+impl KubernetesApiVersions {
+    pub fn new(version: &KubernetesVersion) -> Self {
+        let minor = version.minor();
+        KubernetesApiVersions {
+            ingress: served_api_version("Ingress", minor).unwrap_or("networking.k8s.io/v1"),
+            horizontal_pod_autoscaler: served_api_version("HorizontalPodAutoscaler", minor).unwrap_or("autoscaling/v2"),
+            pod_disruption_budget: served_api_version("PodDisruptionBudget", minor).unwrap_or("policy/v1"),
+            cron_job: served_api_version("CronJob", minor).unwrap_or("batch/v1"),
+        }
+    }
+}
+
+/// Resource of a manifest whose API version is not served anymore by a cluster version
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemovedApiUsage {
+    pub kind: String,
+    pub name: String,
+    pub api_version: String,
+    pub removed_in: u8,
+    pub replacement: Option<&'static str>,
+}
+
+impl Display for RemovedApiUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} `{}` uses {}, removed in Kubernetes 1.{}",
+            self.kind, self.name, self.api_version, self.removed_in
+        )?;
+        match self.replacement {
+            Some(replacement) => write!(f, ", {replacement} must be used instead"),
+            None => write!(f, ", without replacement"),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ManifestHeader {
+    #[serde(rename = "apiVersion")]
+    api_version: String,
+    kind: String,
+    metadata: ManifestMetadata,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ManifestMetadata {
+    name: String,
+}
+
+/// Resources of the manifests using an API version removed in, or before, a cluster version.
+/// Documents which are not kubernetes resources are ignored.
+pub fn find_removed_apis(manifests: &str, version: &KubernetesVersion) -> Vec<RemovedApiUsage> {
+    let minor = version.minor();
+    serde_yaml::Deserializer::from_str(manifests)
+        .filter_map(|document| ManifestHeader::deserialize(document).ok())
+        .filter_map(|header| {
+            let removed_in = SERVED_APIS
+                .iter()
+                .find(|api| api.kind == header.kind && api.api_version == header.api_version)?
+                .removed_in
+                .filter(|removed_in| *removed_in <= minor)?;
+
+            Some(RemovedApiUsage {
+                replacement: served_api_version(&header.kind, removed_in),
+                kind: header.kind,
+                name: header.metadata.name,
+                api_version: header.api_version,
+                removed_in,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(minor: u8) -> KubernetesVersion {
+        match minor {
+            23 => KubernetesVersion::V1_23 {
+                prefix: None,
+                patch: None,
+                suffix: None,
+            },
+            25 => KubernetesVersion::V1_25 {
+                prefix: None,
+                patch: None,
+                suffix: None,
+            },
+            _ => KubernetesVersion::V1_28 {
+                prefix: None,
+                patch: None,
+                suffix: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_api_versions() {
+        assert_eq!(
+            KubernetesApiVersions::new(&version(23)),
+            KubernetesApiVersions {
+                ingress: "networking.k8s.io/v1",
+                horizontal_pod_autoscaler: "autoscaling/v2",
+                pod_disruption_budget: "policy/v1",
+                cron_job: "batch/v1",
+            }
+        );
+        assert_eq!(served_api_version("Ingress", 18), Some("networking.k8s.io/v1beta1"));
+        assert_eq!(served_api_version("HorizontalPodAutoscaler", 22), Some("autoscaling/v2beta2"));
+        assert_eq!(served_api_version("PodSecurityPolicy", 25), None);
+        assert_eq!(served_api_version("Deployment", 25), None);
+    }
+
+    #[test]
+    fn test_find_removed_apis() {
+        let manifests = r#"
+---
+apiVersion: policy/v1beta1
+kind: PodDisruptionBudget
+metadata:
+  name: my-pdb
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: my-cronjob
+---
+apiVersion: policy/v1beta1
+kind: PodSecurityPolicy
+metadata:
+  name: my-psp
+---
+apiVersion: autoscaling/v2beta2
+kind: HorizontalPodAutoscaler
+metadata:
+  name: my-hpa
+---
+# an empty document
+"#;
+
+        assert!(find_removed_apis(manifests, &version(23)).is_empty());
+
+        let removed_apis = find_removed_apis(manifests, &version(25));
+        assert_eq!(
+            removed_apis
+                .iter()
+                .map(|usage| (usage.name.as_str(), usage.replacement))
+                .collect::<Vec<_>>(),
+            vec![("my-pdb", Some("policy/v1")), ("my-psp", None)]
+        );
+        assert_eq!(
+            removed_apis[0].to_string(),
+            "PodDisruptionBudget `my-pdb` uses policy/v1beta1, removed in Kubernetes 1.25, policy/v1 must be used instead"
+        );
+
+        assert_eq!(find_removed_apis(manifests, &version(28)).len(), 3);
+    }
+}
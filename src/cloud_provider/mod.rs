@@ -12,6 +12,7 @@ use crate::cloud_provider::service::Service;
 use crate::cmd::docker::Docker;
 use crate::cmd::helm::{to_engine_error, Helm};
 use crate::container_registry::ContainerRegistry;
+use crate::deployment_hook::DeploymentHooks;
 use crate::deployment_report::logger::EnvLogger;
 use crate::dns_provider::DnsProvider;
 use crate::engine::InfrastructureContext;
@@ -29,15 +30,17 @@ pub mod gcp;
 pub mod helm;
 pub mod helm_charts;
 pub mod io;
-mod kubeconfig_helper;
+pub(crate) mod kubeconfig_helper;
 mod kubectl_utils;
 pub mod kubernetes;
+pub mod kubernetes_api;
 pub mod metrics;
 pub mod models;
 pub mod qovery;
 pub mod scaleway;
 pub mod self_managed;
 pub mod service;
+pub mod support_bundle;
 pub mod utilities;
 pub mod vault;
 
@@ -136,6 +139,7 @@ pub struct DeploymentTarget<'a> {
     pub metrics_registry: Arc<dyn MetricsRegistry>,
     pub is_dry_run_deploy: bool,
     pub is_test_cluster: bool,
+    pub deployment_hooks: DeploymentHooks,
 }
 
 impl<'a> DeploymentTarget<'a> {
@@ -174,6 +178,7 @@ impl<'a> DeploymentTarget<'a> {
             is_dry_run_deploy: kubernetes.context().is_dry_run_deploy(),
             is_test_cluster: kubernetes.context().is_test_cluster(),
             metrics_registry: Arc::from(infra_ctx.metrics_registry().clone_dyn()),
+            deployment_hooks: infra_ctx.deployment_hooks().clone(),
         })
     }
 
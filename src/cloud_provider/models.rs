@@ -1,7 +1,13 @@
 use crate::cloud_provider::service::ServiceType;
+use crate::io_models::application::Protocol;
+use crate::io_models::router::{BackendProtocol, TrailingSlash};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 use uuid::Uuid;
+use x509_parser::pem::parse_x509_pem;
 
 use super::helm::ChartValuesGenerated;
 
@@ -32,6 +38,8 @@ pub struct Storage<T> {
     pub long_id: Uuid,
     pub name: String,
     pub storage_type: T,
+    /// Storage class of the volume claim, the one of the storage type when not set
+    pub storage_class: Option<String>,
     pub size_in_gib: u32,
     pub mount_point: String,
     pub snapshot_retention_in_days: u16,
@@ -53,6 +61,7 @@ pub struct CustomDomain {
     pub domain: String,
     pub target_domain: String,
     pub generate_certificate: bool,
+    pub certificate: Option<CustomCertificate>,
 }
 impl CustomDomain {
     const WILDCARD_PREFIX: &'static str = "*.";
@@ -66,6 +75,36 @@ impl CustomDomain {
     }
 }
 
+/// Certificate of a custom domain provided by the user, in PEM format
+#[derive(Clone)]
+pub struct CustomCertificate {
+    pub certificate_pem: String,
+    pub private_key_pem: String,
+}
+
+impl CustomCertificate {
+    /// Expiry of the certificate, the first one of the PEM being the one of the domain, the next ones its chain
+    pub fn expires_at(&self) -> Result<DateTime<Utc>, String> {
+        let (_, pem) = parse_x509_pem(self.certificate_pem.trim().as_bytes())
+            .map_err(|err| format!("cannot decode the PEM certificate: {err}"))?;
+        let certificate = pem
+            .parse_x509()
+            .map_err(|err| format!("cannot parse the X.509 certificate: {err}"))?;
+
+        DateTime::from_timestamp(certificate.validity().not_after.timestamp(), 0)
+            .ok_or_else(|| "the certificate expiry is out of range".to_string())
+    }
+}
+
+// the private key must never end up in the logs
+impl fmt::Debug for CustomCertificate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomCertificate")
+            .field("certificate_pem", &self.certificate_pem)
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq)]
 pub struct CustomDomainDataTemplate {
     pub domain: String,
@@ -81,6 +120,18 @@ pub struct HostDataTemplate {
 pub struct Route {
     pub path: String,
     pub service_long_id: Uuid,
+    pub backend_protocol: BackendProtocol,
+    pub rewrite_target: Option<String>,
+    pub permanent_redirect: Option<String>,
+    pub trailing_slash: TrailingSlash,
+}
+
+/// Port of a service exposed at the transport layer, behind a load balancer dedicated to the router
+pub struct L4Route {
+    pub protocol: Protocol,
+    pub port: u16,
+    pub target_port: u16,
+    pub service_long_id: Uuid,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,6 +168,34 @@ pub struct NodeGroups {
     pub instance_type: String,
     pub disk_size_in_gib: i32,
     pub instance_architecture: CpuArchitecture,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub taints: Vec<NodeTaint>,
+    /// Nodes have NVIDIA GPUs, they are tainted so only the services requesting GPUs are scheduled on them
+    #[serde(default)]
+    pub gpu: bool,
+}
+
+/// Extended resource the NVIDIA device plugin advertises the GPUs of the nodes with, also the key of their taint
+pub const GPU_RESOURCE_NAME: &str = "nvidia.com/gpu";
+/// Label of the nodes of GPU node groups, the device plugin only runs on them
+pub const GPU_NODE_LABEL: &str = "qovery.com/gpu-node";
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum TaintEffect {
+    NoSchedule,
+    PreferNoSchedule,
+    NoExecute,
+}
+
+/// Taint of the nodes of a node group, only pods tolerating it are scheduled on them, i.e: GPU or spot nodes
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+pub struct NodeTaint {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    pub effect: TaintEffect,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
@@ -145,6 +224,9 @@ pub struct NodeGroupsWithDesiredState {
     pub instance_type: String,
     pub disk_size_in_gib: i32,
     pub instance_architecture: CpuArchitecture,
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<NodeTaint>,
+    pub gpu: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -190,9 +272,7 @@ pub struct InvalidPVCStorage {
 
 /// Represents Kubernetes CPU resource unit
 /// https://kubernetes.io/docs/concepts/configuration/manage-resources-containers/#meaning-of-cpu
-///
-/// TODO(benjaminch): Implement From<String> for KubernetesCpuResourceUnit
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum KubernetesCpuResourceUnit {
     /// Milli CPU
     MilliCpu(u32),
@@ -209,11 +289,44 @@ impl Display for KubernetesCpuResourceUnit {
     }
 }
 
+impl KubernetesCpuResourceUnit {
+    pub fn to_milli_cpu(&self) -> u32 {
+        match self {
+            KubernetesCpuResourceUnit::MilliCpu(v) => *v,
+        }
+    }
+}
+
+fn parse_resource_quantity(quantity: &str) -> Option<f64> {
+    quantity
+        .parse::<f64>()
+        .ok()
+        .filter(|quantity| quantity.is_finite() && *quantity >= 0.0)
+}
+
+/// Parses CPU as written in kubernetes manifests, i.e: `500m`, `1` or `1.5`
+impl FromStr for KubernetesCpuResourceUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let milli_cpu = match s.strip_suffix('m') {
+            Some(milli_cpu) => parse_resource_quantity(milli_cpu),
+            None => parse_resource_quantity(s).map(|cpu| cpu * 1000.0),
+        };
+
+        match milli_cpu {
+            Some(milli_cpu) if milli_cpu.fract() == 0.0 && milli_cpu <= u32::MAX as f64 => {
+                Ok(KubernetesCpuResourceUnit::MilliCpu(milli_cpu as u32))
+            }
+            _ => Err(format!("`{s}` is not a valid CPU quantity, i.e: `500m` or `1.5`")),
+        }
+    }
+}
+
 /// Represents Kubernetes memory resource unit
 /// https://kubernetes.io/docs/concepts/configuration/manage-resources-containers/#meaning-of-memory
-///
-/// TODO(benjaminch): Implement From<String> for KubernetesMemoryResourceUnit
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum KubernetesMemoryResourceUnit {
     /// MebiByte: 1 Mebibyte (MiB) = (1024)^2 bytes = 1,048,576 bytes.
     MebiByte(u32),
@@ -239,6 +352,56 @@ impl Display for KubernetesMemoryResourceUnit {
     }
 }
 
+impl KubernetesMemoryResourceUnit {
+    pub fn to_bytes(&self) -> u64 {
+        match self {
+            KubernetesMemoryResourceUnit::MebiByte(v) => *v as u64 * 1024 * 1024,
+            KubernetesMemoryResourceUnit::MegaByte(v) => *v as u64 * 1000 * 1000,
+            KubernetesMemoryResourceUnit::GibiByte(v) => *v as u64 * 1024 * 1024 * 1024,
+            KubernetesMemoryResourceUnit::GigaByte(v) => *v as u64 * 1000 * 1000 * 1000,
+        }
+    }
+
+    pub fn to_mebibytes(&self) -> u32 {
+        (self.to_bytes() / (1024 * 1024)) as u32
+    }
+}
+
+/// Parses memory as written in kubernetes manifests, i.e: `512Mi` or `1.5Gi`.
+/// Decimal quantities of gigabytes are expressed in megabytes, the ones of gibibytes in mebibytes.
+impl FromStr for KubernetesMemoryResourceUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let to_u32 =
+            |quantity: f64| (quantity.fract() == 0.0 && quantity <= u32::MAX as f64).then_some(quantity as u32);
+        let memory = if let Some(quantity) = s.strip_suffix("Mi") {
+            parse_resource_quantity(quantity)
+                .and_then(to_u32)
+                .map(KubernetesMemoryResourceUnit::MebiByte)
+        } else if let Some(quantity) = s.strip_suffix("Gi") {
+            parse_resource_quantity(quantity).and_then(|quantity| match to_u32(quantity) {
+                Some(quantity) => Some(KubernetesMemoryResourceUnit::GibiByte(quantity)),
+                None => to_u32(quantity * 1024.0).map(KubernetesMemoryResourceUnit::MebiByte),
+            })
+        } else if let Some(quantity) = s.strip_suffix('M') {
+            parse_resource_quantity(quantity)
+                .and_then(to_u32)
+                .map(KubernetesMemoryResourceUnit::MegaByte)
+        } else if let Some(quantity) = s.strip_suffix('G') {
+            parse_resource_quantity(quantity).and_then(|quantity| match to_u32(quantity) {
+                Some(quantity) => Some(KubernetesMemoryResourceUnit::GigaByte(quantity)),
+                None => to_u32(quantity * 1000.0).map(KubernetesMemoryResourceUnit::MegaByte),
+            })
+        } else {
+            None
+        };
+
+        memory.ok_or_else(|| format!("`{s}` is not a valid memory quantity, i.e: `512Mi` or `1Gi`"))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CustomerHelmChartsOverride {
     pub chart_name: String,
@@ -257,6 +420,7 @@ impl CustomerHelmChartsOverride {
 #[cfg(test)]
 mod tests {
     use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
+    use std::str::FromStr;
 
     #[test]
     fn test_kubernetes_cpu_resource_unit_to_string() {
@@ -331,4 +495,51 @@ mod tests {
             assert_eq!(tc.output, tc.input.to_string());
         }
     }
+
+    #[test]
+    fn test_kubernetes_cpu_resource_unit_from_str() {
+        assert_eq!(
+            KubernetesCpuResourceUnit::from_str("500m"),
+            Ok(KubernetesCpuResourceUnit::MilliCpu(500))
+        );
+        assert_eq!(
+            KubernetesCpuResourceUnit::from_str("2"),
+            Ok(KubernetesCpuResourceUnit::MilliCpu(2000))
+        );
+        assert_eq!(
+            KubernetesCpuResourceUnit::from_str("1.5"),
+            Ok(KubernetesCpuResourceUnit::MilliCpu(1500))
+        );
+        assert!(KubernetesCpuResourceUnit::from_str("0.5m").is_err());
+        assert!(KubernetesCpuResourceUnit::from_str("-1").is_err());
+        assert!(KubernetesCpuResourceUnit::from_str("one").is_err());
+    }
+
+    #[test]
+    fn test_kubernetes_memory_resource_unit_from_str() {
+        assert_eq!(
+            KubernetesMemoryResourceUnit::from_str("512Mi"),
+            Ok(KubernetesMemoryResourceUnit::MebiByte(512))
+        );
+        assert_eq!(
+            KubernetesMemoryResourceUnit::from_str("1Gi"),
+            Ok(KubernetesMemoryResourceUnit::GibiByte(1))
+        );
+        assert_eq!(
+            KubernetesMemoryResourceUnit::from_str("1.5Gi"),
+            Ok(KubernetesMemoryResourceUnit::MebiByte(1536))
+        );
+        assert_eq!(
+            KubernetesMemoryResourceUnit::from_str("500M"),
+            Ok(KubernetesMemoryResourceUnit::MegaByte(500))
+        );
+        assert_eq!(
+            KubernetesMemoryResourceUnit::from_str("2G"),
+            Ok(KubernetesMemoryResourceUnit::GigaByte(2))
+        );
+        assert!(KubernetesMemoryResourceUnit::from_str("512").is_err());
+        assert!(KubernetesMemoryResourceUnit::from_str("1Ti").is_err());
+        assert_eq!(KubernetesMemoryResourceUnit::GibiByte(1).to_mebibytes(), 1024);
+        assert_eq!(KubernetesMemoryResourceUnit::GigaByte(1).to_mebibytes(), 953);
+    }
 }
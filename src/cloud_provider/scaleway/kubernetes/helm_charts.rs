@@ -2,7 +2,11 @@ use crate::cloud_provider::helm::{
     get_engine_helm_action_from_location, ChartInfo, ChartSetValue, CommonChart, HelmChart, HelmChartNamespaces,
     PriorityClass, UpdateStrategy,
 };
+use crate::cloud_provider::helm_charts::external_secrets_chart::ExternalSecretsChart;
+use crate::cloud_provider::helm_charts::external_secrets_configs_chart::ExternalSecretsConfigsChart;
 use crate::cloud_provider::helm_charts::k8s_event_logger::K8sEventLoggerChart;
+use crate::cloud_provider::helm_charts::keda_chart::KedaChart;
+use crate::cloud_provider::helm_charts::nfs_server_provisioner_chart::NfsServerProvisionerChart;
 use crate::cloud_provider::helm_charts::nginx_ingress_chart::NginxIngressChart;
 use crate::cloud_provider::helm_charts::promtail_chart::PromtailChart;
 use crate::cloud_provider::helm_charts::qovery_shell_agent_chart::QoveryShellAgentChart;
@@ -51,6 +55,9 @@ use std::path::Path;
 use std::sync::Arc;
 use url::Url;
 
+// Shared by every shared volume of the cluster, their size is not enforced by the NFS server
+const NFS_SERVER_VOLUME_SIZE_IN_GIB: u32 = 100;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScalewayQoveryTerraformConfig {
     pub loki_storage_config_scaleway_s3: String,
@@ -233,6 +240,9 @@ pub fn scw_helm_charts(
             .managed_dns_root_domain_helm_format
             .to_string(),
         chart_config_prerequisites.cluster_id.to_string(),
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .network_enable_dual_stack,
         UpdateStrategy::RollingUpdate,
         true,
         HelmChartNamespaces::KubeSystem,
@@ -455,6 +465,15 @@ pub fn scw_helm_charts(
                 .load_balancer_size
                 .clone(),
         ),
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .network_enable_dual_stack,
+        false,
+        chart_config_prerequisites.cluster_advanced_settings.nginx_waf_mode,
+        chart_config_prerequisites
+            .cluster_advanced_settings
+            .nginx_waf_custom_rules
+            .clone(),
     )
     .to_common_helm_chart()?;
 
@@ -599,6 +618,20 @@ pub fn scw_helm_charts(
     let mut level_1: Vec<Box<dyn HelmChart>> = vec![Box::new(q_storage_class), Box::new(coredns_config), Box::new(vpa)];
 
     let mut level_2: Vec<Box<dyn HelmChart>> = vec![];
+    if chart_config_prerequisites
+        .cluster_advanced_settings
+        .storage_shared_volumes_enabled
+    {
+        // Kapsule has no managed file storage, shared volumes are directories of a block volume exported over NFS
+        let nfs_server_provisioner = NfsServerProvisionerChart::new(
+            chart_prefix_path,
+            HelmChartNamespaces::KubeSystem,
+            "scw-sbv-ssd-0".to_string(),
+            NFS_SERVER_VOLUME_SIZE_IN_GIB,
+        )
+        .to_common_helm_chart()?;
+        level_2.push(Box::new(nfs_server_provisioner));
+    }
 
     let level_3: Vec<Box<dyn HelmChart>> = vec![Box::new(cert_manager)];
 
@@ -608,9 +641,35 @@ pub fn scw_helm_charts(
         vec![]
     };
 
-    let level_5: Vec<Box<dyn HelmChart>> = vec![Box::new(external_dns)];
+    let mut level_5: Vec<Box<dyn HelmChart>> = vec![Box::new(external_dns)];
+    if chart_config_prerequisites.cluster_advanced_settings.keda_enabled {
+        level_5.push(Box::new(
+            KedaChart::new(chart_prefix_path, HelmChartNamespaces::KubeSystem).to_common_helm_chart()?,
+        ));
+    }
+    if chart_config_prerequisites
+        .cluster_advanced_settings
+        .external_secrets_enabled
+    {
+        level_5.push(Box::new(
+            ExternalSecretsChart::new(chart_prefix_path, HelmChartNamespaces::KubeSystem).to_common_helm_chart()?,
+        ));
+    }
 
-    let level_6: Vec<Box<dyn HelmChart>> = vec![Box::new(nginx_ingress)];
+    let mut level_6: Vec<Box<dyn HelmChart>> = vec![Box::new(nginx_ingress)];
+    // secret stores need the CRDs of the external-secrets chart
+    if chart_config_prerequisites
+        .cluster_advanced_settings
+        .external_secrets_enabled
+    {
+        if let Some(external_secrets_configs) = ExternalSecretsConfigsChart::new(
+            chart_prefix_path,
+            HelmChartNamespaces::KubeSystem,
+            &chart_config_prerequisites.cluster_advanced_settings,
+        ) {
+            level_6.push(Box::new(external_secrets_configs.to_common_helm_chart()?));
+        }
+    }
 
     let level_7: Vec<Box<dyn HelmChart>> = vec![
         Box::new(cert_manager_config),
@@ -42,6 +42,7 @@ use crate::logger::Logger;
 use crate::models::domain::ToHelmString;
 use crate::models::scaleway::ScwZone;
 use crate::models::third_parties::LetsEncryptConfig;
+use crate::object_storage::retry::{RetryPolicy, RetryableObjectStorage};
 use crate::object_storage::scaleway_object_storage::ScalewayOS;
 use crate::object_storage::ObjectStorage;
 use crate::runtime::block_on;
@@ -159,7 +160,7 @@ pub struct Kapsule {
     zone: ScwZone,
     cloud_provider: Arc<dyn CloudProvider>,
     dns_provider: Arc<dyn DnsProvider>,
-    object_storage: ScalewayOS,
+    object_storage: RetryableObjectStorage<ScalewayOS>,
     nodes_groups: Vec<NodeGroups>,
     template_directory: String,
     options: KapsuleOptions,
@@ -230,16 +231,25 @@ impl Kapsule {
                     }
                 }
             }
+            if let Err(reason) = node_group.validate_labels_and_taints() {
+                return Err(Box::new(EngineError::new_invalid_node_group_labels_or_taints(
+                    event_details.clone(),
+                    reason,
+                )));
+            }
         }
 
         advanced_settings.validate(event_details.clone())?;
 
-        let object_storage = ScalewayOS::new(
-            "s3-temp-id".to_string(),
-            "default-s3".to_string(),
-            cloud_provider.access_key_id(),
-            cloud_provider.secret_access_key(),
-            zone,
+        let object_storage = RetryableObjectStorage::new(
+            ScalewayOS::new(
+                "s3-temp-id".to_string(),
+                "default-s3".to_string(),
+                cloud_provider.access_key_id(),
+                cloud_provider.secret_access_key(),
+                zone,
+            ),
+            RetryPolicy::default(),
         );
 
         let cluster = Kapsule {
@@ -614,8 +624,17 @@ impl Kapsule {
         context.insert("grafana_admin_user", self.options.grafana_admin_user.as_str());
         context.insert("grafana_admin_password", self.options.grafana_admin_password.as_str());
 
-        // Kubernetes workers
-        context.insert("scw_ks_worker_nodes", &self.nodes_groups);
+        // Kubernetes workers, GPU pools come with the NVIDIA GPU operator installed by Kapsule
+        let worker_nodes = self
+            .nodes_groups
+            .iter()
+            .map(|node_group| NodeGroups {
+                labels: node_group.node_labels(),
+                taints: node_group.node_taints(),
+                ..node_group.clone()
+            })
+            .collect::<Vec<_>>();
+        context.insert("scw_ks_worker_nodes", &worker_nodes);
         context.insert("scw_ks_pool_autoscale", &true);
 
         // Advanced settings
@@ -764,11 +783,20 @@ impl Kapsule {
             event_details.clone(),
             EventMessage::new_from_safe("Create Qovery managed object storage buckets".to_string()),
         ));
-        if let Err(e) = self.object_storage.create_bucket(
-            self.kubeconfig_bucket_name().as_str(),
-            self.advanced_settings.resource_ttl(),
-            false,
-        ) {
+        let bucket_encryption = self.advanced_settings.bucket_encryption();
+        if let Err(e) = self
+            .object_storage
+            .create_bucket(
+                self.kubeconfig_bucket_name().as_str(),
+                self.advanced_settings.resource_ttl(),
+                false,
+                &bucket_encryption,
+            )
+            .and_then(|_| {
+                self.object_storage
+                    .verify_bucket_encryption(self.kubeconfig_bucket_name().as_str(), &bucket_encryption)
+            })
+        {
             let error = EngineError::new_object_storage_error(event_details, e);
             self.logger().log(EngineEvent::Error(error.clone(), None));
             return Err(Box::new(error));
@@ -779,6 +807,7 @@ impl Kapsule {
             self.logs_bucket_name().as_str(),
             self.advanced_settings.resource_ttl(),
             false,
+            &bucket_encryption,
         ) {
             let error = EngineError::new_object_storage_error(event_details, e);
             self.logger().log(EngineEvent::Error(error.clone(), None));
@@ -1076,6 +1105,8 @@ impl Kapsule {
                 .as_slice(),
             helm_charts_to_deploy,
             self.context.is_dry_run_deploy(),
+            self.logger(),
+            &event_details,
         )
         .map_err(|e| Box::new(EngineError::new_helm_chart_error(event_details.clone(), e)))
     }
@@ -1817,6 +1848,10 @@ impl Kubernetes for Kapsule {
         &self.temp_dir
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.object_storage)
+    }
+
     fn update_vault_config(
         &self,
         event_details: EventDetails,
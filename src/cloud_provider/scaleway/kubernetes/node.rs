@@ -483,6 +483,7 @@ mod tests {
     use crate::cloud_provider::scaleway::kubernetes::node::ScwInstancesType;
     use crate::cloud_provider::kubernetes::InstanceType;
     use crate::cloud_provider::models::{CpuArchitecture, NodeGroups};
+    use std::collections::BTreeMap;
     use std::str::FromStr;
     use strum::IntoEnumIterator;
 
@@ -578,6 +579,9 @@ mod tests {
                 disk_size_in_gib: 20,
                 desired_nodes: None,
                 instance_architecture: CpuArchitecture::AMD64,
+                labels: BTreeMap::new(),
+                taints: vec![],
+                gpu: false,
             }
         );
     }
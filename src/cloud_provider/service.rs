@@ -15,6 +15,7 @@ use uuid::Uuid;
 
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::kubernetes::Kubernetes;
+use crate::cloud_provider::kubernetes_api::KubernetesApiVersions;
 use crate::cloud_provider::models::{EnvironmentVariable, InvalidStatefulsetStorage};
 use crate::cmd::terraform::TerraformError;
 use crate::errors::{CommandError, EngineError};
@@ -27,6 +28,7 @@ use crate::models;
 use crate::models::database::{Database, DatabaseMode};
 
 use crate::models::types::{CloudProvider, VersionsNumber};
+use crate::naming;
 use crate::runtime::block_on;
 
 pub trait Service: Send {
@@ -52,7 +54,10 @@ pub enum Action {
     Create,
     Pause,
     Delete,
+    /// Rollout restart of the pods of the service with their current spec, i.e: `kubectl rollout restart`
     Restart,
+    /// Run a cronjob right away, out of its schedule
+    TriggerNow,
 }
 
 impl Action {
@@ -62,6 +67,7 @@ impl Action {
             Action::Pause => EnvironmentStep::Pause,
             Action::Delete => EnvironmentStep::Delete,
             Action::Restart => EnvironmentStep::Restart,
+            Action::TriggerNow => EnvironmentStep::Deploy,
         }
     }
 }
@@ -76,6 +82,7 @@ impl Display for Action {
                 Action::Pause => "Pause",
                 Action::Delete => "Deletion",
                 Action::Restart => "Restart",
+                Action::TriggerNow => "Trigger",
             },
         )
     }
@@ -108,6 +115,8 @@ pub enum ServiceType {
     Container,
     Job,
     HelmChart,
+    Kustomize,
+    Terraform,
 }
 
 impl ServiceType {
@@ -119,6 +128,8 @@ impl ServiceType {
             ServiceType::Container => "Container".to_string(),
             ServiceType::Job => "Job".to_string(),
             ServiceType::HelmChart => "HelmChart".to_string(),
+            ServiceType::Kustomize => "Kustomize".to_string(),
+            ServiceType::Terraform => "Terraform".to_string(),
         }
     }
 }
@@ -147,9 +158,10 @@ pub fn default_tera_context(
     context.insert("region", kubernetes.region());
     context.insert("zone", kubernetes.default_zone().unwrap_or(""));
     context.insert("name", service.name());
-    context.insert("sanitized_name", &service.kube_name());
+    context.insert("sanitized_name", &naming::kube_name(service.kube_name()));
     context.insert("namespace", environment.namespace());
     context.insert("cluster_name", kubernetes.name());
+    context.insert("api_versions", &KubernetesApiVersions::new(&kubernetes.version()));
 
     context
 }
@@ -0,0 +1,272 @@
+use crate::cloud_provider::kubeconfig_helper::get_bucket_name;
+use crate::cloud_provider::kubernetes::Kubernetes;
+use crate::cloud_provider::CloudProvider;
+use crate::cmd::helm::Helm;
+use crate::cmd::structs::HelmListItem;
+use crate::cmd::terraform::terraform_state_list;
+use crate::errors::{EngineError, ErrorMessageVerbosity};
+use crate::object_storage::ObjectStorage;
+use crate::runtime::block_on;
+use crate::utilities::create_kube_client;
+use k8s_openapi::api::core::v1::{Event, Node};
+use kube::api::ListParams;
+use kube::Api;
+use serde::Serialize;
+use std::fs;
+use std::time::Duration;
+use url::Url;
+
+const SUPPORT_BUNDLE_URL_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const MAX_CLUSTER_EVENTS: usize = 200;
+const MAX_ERROR_DETAILS_LINES: usize = 500;
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct NodeConditions {
+    pub name: String,
+    pub kubelet_version: String,
+    /// Conditions not in their healthy state, i.e: `Ready` being `False` or `MemoryPressure` being `True`
+    pub unhealthy_conditions: Vec<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct ClusterEvent {
+    pub namespace: String,
+    pub object: String,
+    pub reason: String,
+    pub message: String,
+    pub last_timestamp: String,
+}
+
+/// Everything support needs to investigate a failed cluster operation without access to the cluster.
+/// Sections that cannot be collected are left empty, their error being kept in `collection_errors`.
+#[derive(Serialize, Debug, Default)]
+pub struct SupportBundle {
+    pub cluster_id: String,
+    pub execution_id: String,
+    pub engine_version: String,
+    pub kubernetes_version: String,
+    pub error_tag: String,
+    /// Last lines of the error, holding the output of the failing terraform or helm command
+    pub error_details: Vec<String>,
+    pub terraform_state: Vec<String>,
+    pub helm_releases: Vec<HelmListItem>,
+    pub node_conditions: Vec<NodeConditions>,
+    pub cluster_events: Vec<ClusterEvent>,
+    pub collection_errors: Vec<String>,
+}
+
+fn unhealthy_conditions(node: &Node) -> Vec<String> {
+    node.status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .map(|conditions| {
+            conditions
+                .iter()
+                .filter(|condition| (condition.type_ == "Ready") != (condition.status == "True"))
+                .map(|condition| {
+                    format!(
+                        "{}={}: {}",
+                        condition.type_,
+                        condition.status,
+                        condition.message.clone().unwrap_or_default()
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn last_lines(text: &str, count: usize) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+async fn collect_cluster_state(kube: &kube::Client, bundle: &mut SupportBundle) -> Result<(), kube::Error> {
+    let nodes: Api<Node> = Api::all(kube.clone());
+    bundle.node_conditions = nodes
+        .list(&ListParams::default())
+        .await?
+        .into_iter()
+        .map(|node| NodeConditions {
+            unhealthy_conditions: unhealthy_conditions(&node),
+            kubelet_version: node
+                .status
+                .as_ref()
+                .and_then(|status| status.node_info.as_ref())
+                .map(|node_info| node_info.kubelet_version.to_string())
+                .unwrap_or_default(),
+            name: node.metadata.name.unwrap_or_default(),
+        })
+        .collect();
+
+    let events: Api<Event> = Api::all(kube.clone());
+    let mut events: Vec<Event> = events.list(&ListParams::default()).await?.items;
+    events.sort_by(|a, b| a.last_timestamp.cmp(&b.last_timestamp));
+    bundle.cluster_events = events
+        .into_iter()
+        .rev()
+        .take(MAX_CLUSTER_EVENTS)
+        .map(|event| ClusterEvent {
+            namespace: event.metadata.namespace.unwrap_or_default(),
+            object: format!(
+                "{}/{}",
+                event.involved_object.kind.unwrap_or_default(),
+                event.involved_object.name.unwrap_or_default()
+            ),
+            reason: event.reason.unwrap_or_default(),
+            message: event.message.unwrap_or_default(),
+            last_timestamp: event
+                .last_timestamp
+                .map(|timestamp| timestamp.0.to_rfc3339())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(())
+}
+
+/// Collects the bundle on a best effort basis, a cluster failing to be created may not even be reachable
+pub fn collect_support_bundle(
+    kubernetes: &dyn Kubernetes,
+    cloud_provider: &dyn CloudProvider,
+    engine_error: &EngineError,
+) -> SupportBundle {
+    let context = kubernetes.context();
+    let mut bundle = SupportBundle {
+        cluster_id: kubernetes.long_id().to_string(),
+        execution_id: context.execution_id().to_string(),
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        kubernetes_version: kubernetes.version().to_string(),
+        error_tag: format!("{:?}", engine_error.tag()),
+        error_details: last_lines(
+            &engine_error.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars),
+            MAX_ERROR_DETAILS_LINES,
+        ),
+        ..Default::default()
+    };
+
+    let envs = cloud_provider.credentials_environment_variables();
+    match terraform_state_list(kubernetes.temp_dir().to_string_lossy().as_ref(), envs.as_slice()) {
+        Ok(terraform_state) => bundle.terraform_state = terraform_state,
+        Err(err) => bundle
+            .collection_errors
+            .push(format!("terraform state: {}", err.to_safe_message())),
+    }
+
+    let kubeconfig_path = kubernetes.kubeconfig_local_file_path();
+    if !kubeconfig_path.exists() {
+        bundle
+            .collection_errors
+            .push("cluster state: no kubeconfig, the cluster is not reachable".to_string());
+        return bundle;
+    }
+
+    match Helm::new(&kubeconfig_path, envs.as_slice()).and_then(|helm| helm.list_release_items(None, &[])) {
+        Ok(helm_releases) => bundle.helm_releases = helm_releases,
+        Err(err) => bundle.collection_errors.push(format!("helm releases: {err}")),
+    }
+
+    let kube_credentials: Vec<(String, String)> = envs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    let cluster_state = block_on(async {
+        let kube = create_kube_client(&kubeconfig_path, kube_credentials.as_slice()).await?;
+        collect_cluster_state(&kube, &mut bundle).await
+    });
+    if let Err(err) = cluster_state {
+        bundle.collection_errors.push(format!("cluster state: {err}"));
+    }
+
+    bundle
+}
+
+/// Uploads the bundle next to the kubeconfig of the cluster, returning a download url valid for a week
+pub fn upload_support_bundle(
+    kubernetes: &dyn Kubernetes,
+    object_storage: &dyn ObjectStorage,
+    bundle: &SupportBundle,
+) -> Result<Url, String> {
+    let bucket_name = get_bucket_name(kubernetes.long_id());
+    let object_key = format!("support-bundles/{}.json", bundle.execution_id);
+    let bundle_path = kubernetes
+        .temp_dir()
+        .join(format!("support-bundle-{}.json", bundle.execution_id));
+
+    let content = serde_json::to_vec_pretty(bundle).map_err(|err| err.to_string())?;
+    fs::write(&bundle_path, content).map_err(|err| err.to_string())?;
+    object_storage
+        .put_object(&bucket_name, &object_key, &bundle_path)
+        .map_err(|err| err.to_string())?;
+    let _ = fs::remove_file(&bundle_path);
+
+    object_storage
+        .generate_presigned_url(&bucket_name, &object_key, SUPPORT_BUNDLE_URL_TTL)
+        .map_err(|err| err.to_string())
+}
+
+/// Produces the support bundle of a failed cluster operation and points to it from the error.
+/// The error is returned untouched when the cluster has no object storage or the bundle cannot be uploaded.
+pub fn attach_support_bundle(
+    kubernetes: &dyn Kubernetes,
+    cloud_provider: &dyn CloudProvider,
+    engine_error: EngineError,
+) -> EngineError {
+    let Some(object_storage) = kubernetes.object_storage() else {
+        return engine_error;
+    };
+
+    let bundle = collect_support_bundle(kubernetes, cloud_provider, &engine_error);
+    match upload_support_bundle(kubernetes, object_storage, &bundle) {
+        Ok(url) => engine_error.with_support_bundle_url(&url),
+        Err(err) => {
+            error!("Cannot upload support bundle of cluster {}: {}", kubernetes.long_id(), err);
+            engine_error
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{NodeCondition, NodeStatus};
+
+    fn condition(type_: &str, status: &str) -> NodeCondition {
+        NodeCondition {
+            type_: type_.to_string(),
+            status: status.to_string(),
+            message: Some("kubelet says so".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unhealthy_conditions() {
+        let node = Node {
+            status: Some(NodeStatus {
+                conditions: Some(vec![
+                    condition("Ready", "False"),
+                    condition("MemoryPressure", "False"),
+                    condition("DiskPressure", "True"),
+                ]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            unhealthy_conditions(&node),
+            vec![
+                "Ready=False: kubelet says so".to_string(),
+                "DiskPressure=True: kubelet says so".to_string()
+            ]
+        );
+        assert!(unhealthy_conditions(&Node::default()).is_empty());
+    }
+
+    #[test]
+    fn test_last_lines() {
+        assert_eq!(last_lines("a\nb\nc", 2), vec!["b".to_string(), "c".to_string()]);
+        assert_eq!(last_lines("a", 2), vec!["a".to_string()]);
+    }
+}
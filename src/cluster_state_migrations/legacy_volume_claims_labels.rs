@@ -0,0 +1,135 @@
+use crate::cluster_state_migrations::ClusterStateMigration;
+use async_trait::async_trait;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use std::collections::HashMap;
+
+const SERVICE_ID_LABEL: &str = "qovery.com/service-id";
+const LEGACY_SERVICE_ID_LABEL: &str = "appId";
+
+/// Volume claims created from the legacy template of the statefulsets only carry the short id of their service.
+/// They get the long one, so they are deleted with the other resources of the service, selected by the new labels.
+pub(super) struct LegacyVolumeClaimsLabels;
+
+/// Long id of the services, by namespace and short id, from the selectors of their statefulsets
+fn service_long_ids(statefulsets: &[StatefulSet]) -> HashMap<(String, String), String> {
+    statefulsets
+        .iter()
+        .filter_map(|statefulset| {
+            let namespace = statefulset.metadata.namespace.clone()?;
+            let short_id = statefulset
+                .spec
+                .as_ref()?
+                .selector
+                .match_labels
+                .as_ref()?
+                .get(LEGACY_SERVICE_ID_LABEL)?
+                .clone();
+            let long_id = statefulset.metadata.labels.as_ref()?.get(SERVICE_ID_LABEL)?.clone();
+            Some(((namespace, short_id), long_id))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl ClusterStateMigration for LegacyVolumeClaimsLabels {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn description(&self) -> &'static str {
+        "label legacy volume claims with the long id of their service"
+    }
+
+    async fn migrate(&self, kube: &kube::Client) -> Result<(), kube::Error> {
+        let statefulsets: Api<StatefulSet> = Api::all(kube.clone());
+        let statefulsets = statefulsets
+            .list(&ListParams::default().labels(SERVICE_ID_LABEL))
+            .await?
+            .items;
+        let service_long_ids = service_long_ids(&statefulsets);
+
+        let volume_claims: Api<PersistentVolumeClaim> = Api::all(kube.clone());
+        let legacy_selector = format!("{LEGACY_SERVICE_ID_LABEL},!{SERVICE_ID_LABEL}");
+        for volume_claim in volume_claims
+            .list(&ListParams::default().labels(&legacy_selector))
+            .await?
+            .items
+        {
+            let (Some(namespace), Some(name)) = (volume_claim.metadata.namespace, volume_claim.metadata.name) else {
+                continue;
+            };
+            let Some(short_id) = volume_claim
+                .metadata
+                .labels
+                .and_then(|mut labels| labels.remove(LEGACY_SERVICE_ID_LABEL))
+            else {
+                continue;
+            };
+            // claims left by a deleted service have nothing to be deleted with
+            let Some(long_id) = service_long_ids.get(&(namespace.clone(), short_id)) else {
+                continue;
+            };
+
+            info!("Labelling volume claim {}/{} of service {}", namespace, name, long_id);
+            let patch = serde_json::json!({ "metadata": { "labels": { SERVICE_ID_LABEL: long_id } } });
+            let volume_claims: Api<PersistentVolumeClaim> = Api::namespaced(kube.clone(), &namespace);
+            volume_claims
+                .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::StatefulSetSpec;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+    use std::collections::BTreeMap;
+
+    fn statefulset(match_labels: &[(&str, &str)], labels: &[(&str, &str)]) -> StatefulSet {
+        let to_map = |labels: &[(&str, &str)]| {
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        StatefulSet {
+            metadata: ObjectMeta {
+                namespace: Some("z42-env".to_string()),
+                labels: Some(to_map(labels)),
+                ..Default::default()
+            },
+            spec: Some(StatefulSetSpec {
+                selector: LabelSelector {
+                    match_labels: Some(to_map(match_labels)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_service_long_ids() {
+        let statefulsets = vec![
+            statefulset(&[("appId", "z1234")], &[(SERVICE_ID_LABEL, "11111111-long-id")]),
+            // statefulsets selecting their pods with the new labels have no legacy claims
+            statefulset(
+                &[(SERVICE_ID_LABEL, "22222222-long-id")],
+                &[(SERVICE_ID_LABEL, "22222222-long-id")],
+            ),
+        ];
+
+        assert_eq!(
+            service_long_ids(&statefulsets),
+            HashMap::from([(("z42-env".to_string(), "z1234".to_string()), "11111111-long-id".to_string())])
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::cluster_state_migrations::legacy_volume_claims_labels::LegacyVolumeClaimsLabels;
+use crate::engine::InfrastructureContext;
+use crate::errors::EngineError;
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::logger::Logger;
+use crate::runtime::block_on;
+use crate::utilities::create_kube_client;
+use async_trait::async_trait;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Patch, PatchParams};
+use kube::Api;
+
+mod legacy_volume_claims_labels;
+
+// The version lives in the cluster itself, so each migration runs once whatever engine instance reaches it first
+const CLUSTER_STATE_CONFIGMAP_NAME: &str = "qovery-engine-state";
+const CLUSTER_STATE_CONFIGMAP_NAMESPACE: &str = "qovery";
+const CLUSTER_STATE_VERSION_KEY: &str = "migration-version";
+const CLUSTER_STATE_FIELD_MANAGER: &str = "qovery-engine";
+
+/// Step bringing the state the engine keeps in a cluster (config maps, secrets, labels of the resources) to the
+/// layout the current engine expects. A step interrupted before being recorded runs again, so it must be idempotent.
+#[async_trait]
+pub trait ClusterStateMigration: Send + Sync {
+    /// Position of the step, a released version is never reused nor reordered
+    fn version(&self) -> u32;
+    fn description(&self) -> &'static str;
+    async fn migrate(&self, kube: &kube::Client) -> Result<(), kube::Error>;
+}
+
+/// All the migrations, ordered by version
+fn migrations() -> Vec<Box<dyn ClusterStateMigration>> {
+    vec![Box::new(LegacyVolumeClaimsLabels)]
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClusterStateMigrationError {
+    #[error("Cannot read the state version of the cluster: {raw_error}")]
+    CannotReadVersion { raw_error: kube::Error },
+
+    #[error("Migration {version} of the cluster state ({description}) failed: {raw_error}")]
+    MigrationFailed {
+        version: u32,
+        description: &'static str,
+        raw_error: kube::Error,
+    },
+
+    #[error("Cannot record the cluster state version {version}: {raw_error}")]
+    CannotRecordVersion { version: u32, raw_error: kube::Error },
+}
+
+/// Version of the state of the cluster, 0 if it was never migrated.
+/// An unreadable version runs all the migrations again, which is safe as they are idempotent.
+pub async fn get_cluster_state_version(kube: &kube::Client) -> Result<u32, kube::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(kube.clone(), CLUSTER_STATE_CONFIGMAP_NAMESPACE);
+    let version = config_maps
+        .get_opt(CLUSTER_STATE_CONFIGMAP_NAME)
+        .await?
+        .and_then(|config_map| config_map.data)
+        .and_then(|data| data.get(CLUSTER_STATE_VERSION_KEY).cloned())
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(0);
+
+    Ok(version)
+}
+
+async fn set_cluster_state_version(kube: &kube::Client, version: u32) -> Result<(), kube::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(kube.clone(), CLUSTER_STATE_CONFIGMAP_NAMESPACE);
+    let patch = serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "ConfigMap",
+        "metadata": { "name": CLUSTER_STATE_CONFIGMAP_NAME },
+        "data": { CLUSTER_STATE_VERSION_KEY: version.to_string() },
+    });
+
+    config_maps
+        .patch(
+            CLUSTER_STATE_CONFIGMAP_NAME,
+            &PatchParams::apply(CLUSTER_STATE_FIELD_MANAGER),
+            &Patch::Apply(&patch),
+        )
+        .await
+        .map(|_| ())
+}
+
+fn pending_migrations(
+    migrations: &[Box<dyn ClusterStateMigration>],
+    current_version: u32,
+) -> impl Iterator<Item = &dyn ClusterStateMigration> {
+    migrations
+        .iter()
+        .map(|migration| migration.as_ref())
+        .filter(move |migration| migration.version() > current_version)
+}
+
+/// Runs, in order, the migrations not applied yet to the cluster, the version being recorded after each of them.
+/// `on_migrated` is called once a migration is recorded.
+pub async fn run_cluster_state_migrations(
+    kube: &kube::Client,
+    migrations: &[Box<dyn ClusterStateMigration>],
+    on_migrated: impl Fn(&dyn ClusterStateMigration),
+) -> Result<(), ClusterStateMigrationError> {
+    let current_version = get_cluster_state_version(kube)
+        .await
+        .map_err(|raw_error| ClusterStateMigrationError::CannotReadVersion { raw_error })?;
+
+    for migration in pending_migrations(migrations, current_version) {
+        let version = migration.version();
+        migration
+            .migrate(kube)
+            .await
+            .map_err(|raw_error| ClusterStateMigrationError::MigrationFailed {
+                version,
+                description: migration.description(),
+                raw_error,
+            })?;
+        set_cluster_state_version(kube, version)
+            .await
+            .map_err(|raw_error| ClusterStateMigrationError::CannotRecordVersion { version, raw_error })?;
+        on_migrated(migration);
+    }
+
+    Ok(())
+}
+
+/// Migrates the state of the cluster on the first contact of an upgraded engine with it.
+/// Clusters that cannot be reached yet, i.e: being created, are migrated on a later request.
+pub fn migrate_cluster_state(
+    infra_ctx: &InfrastructureContext,
+    event_details: EventDetails,
+    logger: &dyn Logger,
+) -> Result<(), Box<EngineError>> {
+    let kubeconfig_path = infra_ctx.kubernetes().kubeconfig_local_file_path();
+    let kube_credentials: Vec<(String, String)> = infra_ctx
+        .cloud_provider()
+        .credentials_environment_variables()
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    let ret = block_on(async {
+        let kube = match create_kube_client(&kubeconfig_path, kube_credentials.as_slice()).await {
+            Ok(kube) => kube,
+            Err(err) => {
+                info!("Cannot reach the cluster, its state is not migrated: {}", err);
+                return Ok(());
+            }
+        };
+
+        run_cluster_state_migrations(&kube, &migrations(), |migration| {
+            logger.log(EngineEvent::Info(
+                event_details.clone(),
+                EventMessage::new_from_safe(format!(
+                    "🛠️ Cluster state migrated to version {}: {}",
+                    migration.version(),
+                    migration.description()
+                )),
+            ))
+        })
+        .await
+    });
+
+    match ret {
+        Ok(()) => Ok(()),
+        Err(err @ ClusterStateMigrationError::CannotReadVersion { .. }) => {
+            info!("{}, its state is not migrated", err);
+            Ok(())
+        }
+        Err(err) => Err(Box::new(EngineError::new_cluster_state_migration_failed(event_details, err))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopMigration(u32);
+
+    #[async_trait]
+    impl ClusterStateMigration for NoopMigration {
+        fn version(&self) -> u32 {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "noop"
+        }
+
+        async fn migrate(&self, _kube: &kube::Client) -> Result<(), kube::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        let versions: Vec<u32> = migrations().iter().map(|migration| migration.version()).collect();
+        let expected_versions: Vec<u32> = (1..=versions.len() as u32).collect();
+        assert_eq!(versions, expected_versions);
+    }
+
+    #[test]
+    fn test_pending_migrations() {
+        let migrations: Vec<Box<dyn ClusterStateMigration>> = vec![
+            Box::new(NoopMigration(1)),
+            Box::new(NoopMigration(2)),
+            Box::new(NoopMigration(3)),
+        ];
+
+        let pending = |current_version| {
+            pending_migrations(&migrations, current_version)
+                .map(|migration| migration.version())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pending(0), vec![1, 2, 3]);
+        assert_eq!(pending(2), vec![3]);
+        assert!(pending(3).is_empty());
+        // a cluster migrated by a more recent engine is left as is
+        assert!(pending(42).is_empty());
+    }
+}
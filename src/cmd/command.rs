@@ -1,5 +1,5 @@
 use std::ffi::OsStr;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 use std::io::{Error, ErrorKind};
 use std::path::Path;
 use std::process::{Child, Command, ExitStatus, Stdio};
@@ -130,6 +130,7 @@ pub trait ExecutableCommand {
 pub struct QoveryCommand {
     command: Command,
     kill_grace_period: Duration,
+    stdin: Option<Vec<u8>>,
 }
 
 impl QoveryCommand {
@@ -144,6 +145,7 @@ impl QoveryCommand {
         QoveryCommand {
             command,
             kill_grace_period: Duration::from_secs(60 * 5),
+            stdin: None,
         }
     }
 
@@ -154,6 +156,12 @@ impl QoveryCommand {
     pub fn set_current_dir<P: AsRef<Path>>(&mut self, root_dir: P) {
         self.command.current_dir(root_dir);
     }
+
+    /// Content written to the standard input of the command, i.e: decrypted secrets that must not touch the disk
+    pub fn set_stdin(&mut self, content: Vec<u8>) {
+        self.command.stdin(Stdio::piped());
+        self.stdin = Some(content);
+    }
 }
 
 impl ExecutableCommand for QoveryCommand {
@@ -226,6 +234,15 @@ impl ExecutableCommand for QoveryCommand {
             .spawn()
             .map_err(ExecutionError)?;
 
+        // written from another thread, as the command may fill its output pipes before reading all its input
+        if let (Some(content), Some(mut stdin)) = (self.stdin.take(), cmd_handle.stdin.take()) {
+            std::thread::spawn(move || {
+                if let Err(err) = stdin.write_all(&content) {
+                    error!("Cannot write to stdin of command: {:?}", err);
+                }
+            });
+        }
+
         // Read stdout/stderr until timeout is reached
         let reader_timeout = Duration::from_secs(1);
         let stdout = cmd_handle
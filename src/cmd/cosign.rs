@@ -0,0 +1,119 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::ContainerImage;
+
+use std::path::Path;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CosignError {
+    #[error("Cosign terminated with a non success exit status code: {exit_status:?}: {output}")]
+    ExitStatusError { exit_status: ExitStatus, output: String },
+
+    #[error("Cosign terminated with an unknown error: {raw_error:?}")]
+    ExecutionError { raw_error: std::io::Error },
+
+    #[error("Cosign aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted { raw_error_message: String },
+
+    #[error("Cosign command terminated due to timeout: {raw_error_message:?}")]
+    Timeout { raw_error_message: String },
+}
+
+impl CosignError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, Self::Aborted { .. })
+    }
+}
+
+/// Signs and verifies images with a key held by a KMS or Vault, the private key never leaving it.
+/// i.e: `hashivault://qovery-images`, `awskms:///alias/qovery-images`, `gcpkms://projects/...`
+#[derive(Debug)]
+pub struct Cosign {
+    key_ref: String,
+    common_envs: Vec<(String, String)>,
+}
+
+impl Cosign {
+    /// Registries are accessed with the credentials of the docker config the images are pushed with.
+    /// Credentials of the KMS or Vault are the ones of the engine environment, i.e: `VAULT_ADDR` and `VAULT_TOKEN`
+    pub fn new(key_ref: String, docker_config_path: &Path) -> Self {
+        Self {
+            key_ref,
+            common_envs: vec![(
+                "DOCKER_CONFIG".to_string(),
+                docker_config_path.to_str().unwrap_or_default().to_string(),
+            )],
+        }
+    }
+
+    pub fn key_ref(&self) -> &str {
+        &self.key_ref
+    }
+
+    pub fn sign(&self, image: &ContainerImage, cmd_killer: &CommandKiller) -> Result<(), CosignError> {
+        let image_name = image.image_name();
+        info!("Signing image {} with key {}", image_name, self.key_ref);
+
+        // signatures of private images are not published to the public transparency log
+        let args = &[
+            "sign",
+            "--yes",
+            "--tlog-upload=false",
+            "--key",
+            &self.key_ref,
+            &image_name,
+        ];
+        cosign_exec(args, &self.get_all_envs(&[]), cmd_killer)
+    }
+
+    pub fn verify(&self, image: &ContainerImage, cmd_killer: &CommandKiller) -> Result<(), CosignError> {
+        let image_name = image.image_name();
+        info!("Verifying signature of image {} with key {}", image_name, self.key_ref);
+
+        let args = &[
+            "verify",
+            "--insecure-ignore-tlog=true",
+            "--key",
+            &self.key_ref,
+            "--output",
+            "json",
+            &image_name,
+        ];
+        cosign_exec(args, &self.get_all_envs(&[]), cmd_killer)
+    }
+
+    fn get_all_envs<'a>(&'a self, envs: &'a [(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+        let mut all_envs: Vec<(&str, &str)> = self.common_envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        all_envs.append(&mut envs.to_vec());
+
+        all_envs
+    }
+}
+
+fn cosign_exec(args: &[&str], envs: &[(&str, &str)], cmd_killer: &CommandKiller) -> Result<(), CosignError> {
+    let mut cmd = QoveryCommand::new("cosign", args, envs);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+
+    // the reason of a failed verification is only written on stderr
+    let mut output: Vec<String> = vec![];
+    let ret = cmd.exec_with_abort(
+        &mut |line| info!("{}", line),
+        &mut |line| {
+            info!("{}", line);
+            output.push(line);
+        },
+        cmd_killer,
+    );
+
+    match ret {
+        Ok(_) => Ok(()),
+        Err(CommandError::TimeoutError(msg)) => Err(CosignError::Timeout { raw_error_message: msg }),
+        Err(CommandError::Killed(msg)) => Err(CosignError::Aborted { raw_error_message: msg }),
+        Err(CommandError::ExitStatusError(err)) => Err(CosignError::ExitStatusError {
+            exit_status: err,
+            output: output.join("\n"),
+        }),
+        Err(CommandError::ExecutionError(err)) => Err(CosignError::ExecutionError { raw_error: err }),
+    }
+}
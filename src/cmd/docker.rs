@@ -1,5 +1,6 @@
 use crate::cloud_provider::models::CpuArchitecture;
 use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::io_models::environment::RemoteBuilder;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use std::collections::BTreeSet;
@@ -114,7 +115,7 @@ impl ContainerImage {
         }
     }
 
-    fn _new_for_digest(registry: Url, name: String, digest: String) -> Self {
+    pub fn new_for_digest(registry: Url, name: String, digest: String) -> Self {
         ContainerImage {
             registry,
             name,
@@ -152,6 +153,13 @@ impl ContainerImage {
     }
 }
 
+/// Directories a build imports its cache from and exports it to, in addition to the cache of the registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalCache {
+    pub from: Option<PathBuf>,
+    pub to: PathBuf,
+}
+
 #[derive(Debug, Clone)]
 enum BuilderLocation {
     Local,
@@ -394,6 +402,71 @@ impl Docker {
         }
     }
 
+    /// Register a BuildKit daemon running outside of the engine, nothing is spawned.
+    /// Its certificates are written in the docker config directory, deleted with it.
+    /// Reference doc https://docs.docker.com/build/drivers/remote/
+    pub fn spawn_remote_builder(
+        &self,
+        nb_builder: NonZeroUsize,
+        remote_builder: &RemoteBuilder,
+        should_abort: &CommandKiller,
+    ) -> Result<BuilderHandle, DockerError> {
+        let builder_name = "engine-remote-builder";
+
+        // We create build handle here to force the drop to run if some operation fail
+        let build_handle = BuilderHandle {
+            config_path: self.config_path.path().to_path_buf(),
+            nb_builder,
+            builder_name: Some(builder_name.to_string()),
+        };
+
+        let mut driver_opts: Vec<String> = vec![];
+        if let Some(tls) = &remote_builder.tls {
+            let certs_dir = self.config_path.path().join("buildkit");
+            let write_cert = |file_name: &str, content: &str| -> Result<String, DockerError> {
+                let path = certs_dir.join(file_name);
+                fs::create_dir_all(&certs_dir)
+                    .and_then(|_| fs::write(&path, content))
+                    .map_err(|err| DockerError::InvalidConfig {
+                        raw_error_message: format!("Cannot write remote builder certificate {file_name}: {err}"),
+                    })?;
+                Ok(path.to_str().unwrap_or_default().to_string())
+            };
+
+            driver_opts.push(format!("cacert={}", write_cert("ca.pem", &tls.ca_cert)?));
+            driver_opts.push(format!("cert={}", write_cert("cert.pem", &tls.client_cert)?));
+            driver_opts.push(format!("key={}", write_cert("key.pem", &tls.client_key)?));
+            if let Some(server_name) = &tls.server_name {
+                driver_opts.push(format!("servername={server_name}"));
+            }
+        }
+
+        let mut args = vec![
+            "--config",
+            self.config_path.path().to_str().unwrap_or(""),
+            "buildx",
+            "create",
+            "--name",
+            builder_name,
+            "--driver=remote",
+        ];
+        let driver_opts = driver_opts.join(",");
+        if !driver_opts.is_empty() {
+            args.push("--driver-opt");
+            args.push(&driver_opts);
+        }
+        args.extend([remote_builder.endpoint.as_str(), "--bootstrap", "--use"]);
+        docker_exec(
+            &args,
+            &self.get_all_envs(&[]),
+            &mut |line| info!("{}", line),
+            &mut |line| info!("{}", line),
+            should_abort,
+        )?;
+
+        Ok(build_handle)
+    }
+
     pub fn socket_url(&self) -> &Option<Url> {
         &self.socket_location
     }
@@ -491,6 +564,11 @@ impl Docker {
         Ok(())
     }
 
+    /// Docker config holding the credentials of the registries it is logged in
+    pub fn config_path(&self) -> &Path {
+        self.config_path.path()
+    }
+
     pub fn login(&self, registry: &Url) -> Result<(), DockerError> {
         let username = match urlencoding::decode(registry.username()) {
             Ok(decoded_username) => decoded_username,
@@ -607,7 +685,9 @@ impl Docker {
         context: &Path,
         image_to_build: &ContainerImage,
         build_args: &[(&str, &str)],
+        build_secrets: &[(&str, &str)],
         cache: &ContainerImage,
+        local_cache: Option<&LocalCache>,
         push_after_build: bool,
         architectures: &[Architecture],
         stdout_output: &mut Stdout,
@@ -636,7 +716,9 @@ impl Docker {
             context,
             image_to_build,
             build_args,
+            build_secrets,
             cache,
+            local_cache,
             push_after_build,
             architectures,
             stdout_output,
@@ -651,7 +733,9 @@ impl Docker {
         context: &Path,
         image_to_build: &ContainerImage,
         build_args: &[(&str, &str)],
+        build_secrets: &[(&str, &str)],
         cache: &ContainerImage,
+        local_cache: Option<&LocalCache>,
         push_after_build: bool,
         architectures: &[Architecture],
         stdout_output: &mut Stdout,
@@ -686,6 +770,19 @@ impl Docker {
             dockerfile.to_str().unwrap_or_default().to_string(),
         ];
 
+        // Layers of intermediate stages are exported too, they are the ones saving most of the build time
+        if let Some(local_cache) = local_cache {
+            if let Some(from) = &local_cache.from {
+                args_string.push("--cache-from".to_string());
+                args_string.push(format!("type=local,src={}", from.to_str().unwrap_or_default()));
+            }
+            args_string.push("--cache-to".to_string());
+            args_string.push(format!(
+                "type=local,dest={},mode=max",
+                local_cache.to.to_str().unwrap_or_default()
+            ));
+        }
+
         // Build for all requested architectures, if empty build for the current architecture the engine is running on
         if !architectures.is_empty() {
             args_string.push(format!(
@@ -704,11 +801,17 @@ impl Docker {
             args_string.push(format!("{k}={v}"));
         }
 
+        // Secrets are read from the environment of the docker cli, so their values never appear in its arguments
+        for (id, _) in build_secrets {
+            args_string.push("--secret".to_string());
+            args_string.push(format!("id={id},env={id}"));
+        }
+
         args_string.push(context.to_str().unwrap_or_default().to_string());
 
         docker_exec(
             &args_string.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
-            &self.get_all_envs(&[]),
+            &self.get_all_envs(build_secrets),
             stdout_output,
             stderr_output,
             should_abort,
@@ -991,7 +1094,9 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
+            None,
             false,
             &[Architecture::AMD64],
             &mut |msg| println!("{msg}"),
@@ -1006,7 +1111,9 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
+            None,
             false,
             &[Architecture::AMD64],
             &mut |msg| println!("{msg}"),
@@ -1039,7 +1146,9 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
+            None,
             false,
             &[Architecture::AMD64],
             &mut |msg| println!("{msg}"),
@@ -1163,7 +1272,9 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
+            None,
             false,
             &[Architecture::AMD64],
             &mut |msg| println!("{msg}"),
@@ -6,12 +6,16 @@ use tracing::{error, info};
 
 use crate::cloud_provider::helm::ChartInfo;
 use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
-use crate::cmd::helm::HelmCommand::{DEPENDENCY, FETCH, LIST, LOGIN, PULL, REPO, ROLLBACK, STATUS, UNINSTALL, UPGRADE};
+use crate::cmd::helm::HelmCommand::{
+    DEPENDENCY, FETCH, GET, LIST, LOGIN, PULL, REPO, ROLLBACK, STATUS, UNINSTALL, UPGRADE,
+};
 use crate::cmd::helm::HelmError::{
     CannotRollback, CmdError, InvalidKubeConfig, InvalidRepositoryConfig, ReleaseDoesNotExist,
 };
 use crate::cmd::helm_utils::ChartYAML;
+use crate::cmd::kubectl::kubectl_exec_delete_secret;
 use crate::cmd::structs::{HelmChart, HelmChartVersions, HelmListItem};
+use crate::encryption::{is_encrypted_file, read_encrypted_file};
 use crate::errors;
 use crate::errors::EngineError;
 use crate::events::EventDetails;
@@ -92,6 +96,7 @@ pub enum HelmCommand {
     DEPENDENCY,
     SHOW,
     REPO,
+    GET,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -265,25 +270,48 @@ impl Helm {
     }
 
     fn unlock_release(&self, chart: &ChartInfo, envs: &[(&str, &str)]) -> Result<(), HelmError> {
-        match self.check_release_exist(chart, envs) {
-            Ok(release) if release.is_locked() && release.version <= 1 => {
-                info!("Helm lock detected. Uninstalling it as it is the first version and rollback is not possible");
-                self.uninstall(chart, envs, &CommandKiller::never(), &mut |_| {}, &mut |_| {})?;
-            }
-            Ok(release) if release.is_locked() => {
-                info!("Helm lock detected. Forcing rollback to previous version");
-                self.rollback(chart, envs)?;
-            }
+        let release = match self.check_release_exist(chart, envs) {
+            Ok(release) if release.is_locked() => release,
             Ok(release) => {
                 // Happy path nothing to do
-                debug!("Helm release status: {:?}", release)
+                debug!("Helm release status: {:?}", release);
+                return Ok(());
             }
-            Err(_) => {} // Happy path nothing to do
+            Err(_) => return Ok(()), // Happy path nothing to do
+        };
+
+        let repair_ret = if release.version <= 1 {
+            info!("Helm lock detected. Uninstalling it as it is the first version and rollback is not possible");
+            self.uninstall(chart, envs, &CommandKiller::never(), &mut |_| {}, &mut |_| {})
+        } else {
+            info!("Helm lock detected. Forcing rollback to previous version");
+            self.rollback(chart, envs)
+        };
+
+        // Dropping the pending revision hands the release back to its previous revision, or removes it if it was the first
+        if let Err(err) = repair_ret {
+            warn!(
+                "Cannot repair helm release {} stuck in {}, deleting its pending revision: {}",
+                chart.name, release.info.status, err
+            );
+            self.delete_release_revision(chart, release.version, envs)?;
         }
 
         Ok(())
     }
 
+    fn delete_release_revision(&self, chart: &ChartInfo, version: u64, envs: &[(&str, &str)]) -> Result<(), HelmError> {
+        // https://github.com/helm/helm/blob/main/pkg/storage/driver/secrets.go
+        let release_secret_name = format!("sh.helm.release.v1.{}.v{}", chart.name, version);
+        kubectl_exec_delete_secret(
+            &self.kubernetes_config,
+            &chart.get_namespace_string(),
+            &release_secret_name,
+            self.get_all_envs(envs),
+        )
+        .map_err(|err| CmdError(chart.name.clone(), ROLLBACK, err))
+    }
+
     /// List deployed helm charts
     ///
     /// # Arguments
@@ -291,6 +319,37 @@ impl Helm {
     /// * `envs` - environment variables required for kubernetes connection
     /// * `namespace` - list charts from a kubernetes namespace or use None to select all namespaces
     pub fn list_release(&self, namespace: Option<&str>, envs: &[(&str, &str)]) -> Result<Vec<HelmChart>, HelmError> {
+        let mut helms_charts: Vec<HelmChart> = Vec::new();
+        for helm in self.list_release_items(namespace, envs)? {
+            // chart version is stored in chart name (i.e loki-3.4.5) so we look for last dash position to parse name.
+            let mut last_dash_pos = helm.chart.rfind('-').expect("Can't parse helm chart") + 1;
+            // sometime chart version in name start with 'v' (i.e loki-v3.4.5). We squeeze it.
+            if helm.chart[last_dash_pos..].starts_with('v') {
+                last_dash_pos += 1
+            }
+
+            let chart_version_raw = helm.chart[last_dash_pos..].to_string();
+            let chart_version = Version::from_str(chart_version_raw.as_str()).ok();
+
+            let mut app_version_raw = helm.app_version;
+            // sometime app version start with 'v'. We squeeze it.
+            if app_version_raw.starts_with('v') {
+                app_version_raw = app_version_raw[1..].to_string()
+            }
+            let app_version = Version::from_str(app_version_raw.as_str()).ok();
+
+            helms_charts.push(HelmChart::new(helm.name, helm.namespace, chart_version, app_version))
+        }
+
+        Ok(helms_charts)
+    }
+
+    /// Releases as listed by helm, with their status and revision
+    pub fn list_release_items(
+        &self,
+        namespace: Option<&str>,
+        envs: &[(&str, &str)],
+    ) -> Result<Vec<HelmListItem>, HelmError> {
         let mut helm_args = vec![
             "list",
             "-a",
@@ -315,35 +374,8 @@ impl Helm {
             return Err(CmdError("none".to_string(), LIST, cmd_error.into()));
         }
 
-        let values = serde_json::from_str::<Vec<HelmListItem>>(&output_string.join(""));
-        let mut helms_charts: Vec<HelmChart> = Vec::new();
-
-        match values {
-            Ok(all_helms) => {
-                for helm in all_helms {
-                    // chart version is stored in chart name (i.e loki-3.4.5) so we look for last dash position to parse name.
-                    let mut last_dash_pos = helm.chart.rfind('-').expect("Can't parse helm chart") + 1;
-                    // sometime chart version in name start with 'v' (i.e loki-v3.4.5). We squeeze it.
-                    if helm.chart[last_dash_pos..].starts_with('v') {
-                        last_dash_pos += 1
-                    }
-
-                    let chart_version_raw = helm.chart[last_dash_pos..].to_string();
-                    let chart_version = Version::from_str(chart_version_raw.as_str()).ok();
-
-                    let mut app_version_raw = helm.app_version;
-                    // sometime app version start with 'v'. We squeeze it.
-                    if app_version_raw.starts_with('v') {
-                        app_version_raw = app_version_raw[1..].to_string()
-                    }
-                    let app_version = Version::from_str(app_version_raw.as_str()).ok();
-
-                    helms_charts.push(HelmChart::new(helm.name, helm.namespace, chart_version, app_version))
-                }
-
-                Ok(helms_charts)
-            }
-            Err(e) => Err(CmdError(
+        serde_json::from_str::<Vec<HelmListItem>>(&output_string.join("")).map_err(|e| {
+            CmdError(
                 "none".to_string(),
                 LIST,
                 errors::CommandError::new(
@@ -355,7 +387,61 @@ impl Helm {
                             .collect::<Vec<(String, String)>>(),
                     ),
                 ),
-            )),
+            )
+        })
+    }
+
+    /// Values of a deployed release as yaml, computed ones included
+    pub fn get_release_values(
+        &self,
+        release_name: &str,
+        namespace: &str,
+        envs: &[(&str, &str)],
+    ) -> Result<String, HelmError> {
+        self.get_release(release_name, namespace, "values", &["--all", "-o", "yaml"], envs)
+    }
+
+    /// Rendered kubernetes manifest of a deployed release
+    pub fn get_release_manifest(
+        &self,
+        release_name: &str,
+        namespace: &str,
+        envs: &[(&str, &str)],
+    ) -> Result<String, HelmError> {
+        self.get_release(release_name, namespace, "manifest", &[], envs)
+    }
+
+    fn get_release(
+        &self,
+        release_name: &str,
+        namespace: &str,
+        subcommand: &str,
+        extra_args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> Result<String, HelmError> {
+        let mut args = vec![
+            "get",
+            subcommand,
+            release_name,
+            "--kubeconfig",
+            self.kubernetes_config.to_str().unwrap_or_default(),
+            "--namespace",
+            namespace,
+        ];
+        args.extend_from_slice(extra_args);
+
+        let mut stdout: Vec<String> = vec![];
+        let mut stderr = String::new();
+        match helm_exec_with_output(
+            &args,
+            &self.get_all_envs(envs),
+            &mut |line| stdout.push(line),
+            &mut |line| stderr.push_str(&line),
+            &CommandKiller::never(),
+        ) {
+            Err(_) if stderr.contains("release: not found") => Err(ReleaseDoesNotExist(release_name.to_string())),
+            Err(err) => Err(CmdError(release_name.to_string(), GET, err.into())),
+            Ok(_) => Ok(stdout.join("\n")),
         }
     }
 
@@ -772,10 +858,9 @@ impl Helm {
             args_string.push(format!("{}={}", value.key, value.value));
         }
 
-        for value_file in &chart.values_files {
-            args_string.push("-f".to_string());
-            args_string.push(value_file.clone());
-        }
+        let (values_files_args, stdin) = values_files_args(&chart.values_files)
+            .map_err(|err| CmdError(chart.name.clone(), HelmCommand::DIFF, err))?;
+        args_string.extend(values_files_args);
 
         for value_file in &chart.yaml_files_content {
             let file_path = format!("{}/{}", chart.path, &value_file.filename);
@@ -808,9 +893,10 @@ impl Helm {
         args_string.push(chart.path.clone());
 
         let mut stderr_msg = String::new();
-        let helm_ret = helm_exec_with_output(
+        let helm_ret = helm_exec_with_input(
             &args_string.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
             &self.get_all_envs(envs),
+            stdin,
             &mut |line| {
                 info!("{}", line);
             },
@@ -889,10 +975,9 @@ impl Helm {
             args_string.push(format!("{}={}", value.key, value.value));
         }
 
-        for value_file in &chart.values_files {
-            args_string.push("-f".to_string());
-            args_string.push(value_file.clone());
-        }
+        let (values_files_args, stdin) =
+            values_files_args(&chart.values_files).map_err(|err| CmdError(chart.name.clone(), UPGRADE, err))?;
+        args_string.extend(values_files_args);
         for value_file in &chart.yaml_files_content {
             let file_path = format!("{}/{}", chart.path, &value_file.filename);
             let file_create = || -> Result<(), Error> {
@@ -925,9 +1010,10 @@ impl Helm {
 
         let mut error_message: Vec<String> = vec![];
 
-        let helm_ret = helm_exec_with_output(
+        let helm_ret = helm_exec_with_input(
             &args_string.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
             &self.get_all_envs(envs),
+            stdin,
             &mut |line| {
                 info!("chart {}: {}", chart.name, line);
             },
@@ -1406,10 +1492,9 @@ impl Helm {
             args_string.push(format!("{}={}", value.key, value.value));
         }
 
-        for value_file in &chart.values_files {
-            args_string.push("-f".to_string());
-            args_string.push(value_file.clone());
-        }
+        let (values_files_args, stdin) = values_files_args(&chart.values_files)
+            .map_err(|err| CmdError(chart.name.clone(), HelmCommand::TEMPLATE, err))?;
+        args_string.extend(values_files_args);
 
         for value_file in &chart.yaml_files_content {
             let file_path = format!("{}/{}", chart.path, &value_file.filename);
@@ -1442,9 +1527,10 @@ impl Helm {
         args_string.push(chart.path.clone());
 
         let mut stderr_msg = String::new();
-        let helm_ret = helm_exec_with_output(
+        let helm_ret = helm_exec_with_input(
             &args_string.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
             &self.get_all_envs(envs),
+            stdin,
             &mut |line| {
                 debug!("{}", line);
             },
@@ -1473,6 +1559,21 @@ fn helm_exec_with_output<STDOUT, STDERR>(
     stderr_output: &mut STDERR,
     cmd_killer: &CommandKiller,
 ) -> Result<(), CommandError>
+where
+    STDOUT: FnMut(String),
+    STDERR: FnMut(String),
+{
+    helm_exec_with_input(args, envs, None, stdout_output, stderr_output, cmd_killer)
+}
+
+fn helm_exec_with_input<STDOUT, STDERR>(
+    args: &[&str],
+    envs: &[(&str, &str)],
+    stdin: Option<Vec<u8>>,
+    stdout_output: &mut STDOUT,
+    stderr_output: &mut STDERR,
+    cmd_killer: &CommandKiller,
+) -> Result<(), CommandError>
 where
     STDOUT: FnMut(String),
     STDERR: FnMut(String),
@@ -1481,12 +1582,46 @@ where
     // Helm returns an error each time a command does not succeed as they want. Which leads to handling error with status code 1
     // It means that the command successfully ran, but it didn't terminate as expected
     let mut cmd = QoveryCommand::new("helm", args, envs);
+    if let Some(stdin) = stdin {
+        cmd.set_stdin(stdin);
+    }
     match cmd.exec_with_abort(stdout_output, stderr_output, cmd_killer) {
         Err(err) => Err(err),
         _ => Ok(()),
     }
 }
 
+/// Arguments of the values files of a chart. The encrypted one, i.e: a rendered `qovery-values.yaml`, is decrypted in
+/// memory and read by helm from its standard input, so it never touches the disk in clear.
+fn values_files_args(values_files: &[String]) -> Result<(Vec<String>, Option<Vec<u8>>), errors::CommandError> {
+    let mut args = Vec::with_capacity(values_files.len() * 2);
+    let mut stdin = None;
+    for value_file in values_files {
+        args.push("-f".to_string());
+        if !is_encrypted_file(Path::new(value_file)) {
+            args.push(value_file.clone());
+            continue;
+        }
+
+        if stdin.is_some() {
+            return Err(errors::CommandError::new_from_safe_message(format!(
+                "Only one encrypted values file can be given to helm, cannot use `{value_file}`"
+            )));
+        }
+        let content = read_encrypted_file(Path::new(value_file)).map_err(|err| {
+            errors::CommandError::new(
+                format!("Cannot decrypt values file `{value_file}`"),
+                Some(err.to_string()),
+                None,
+            )
+        })?;
+        stdin = Some(content);
+        args.push("-".to_string());
+    }
+
+    Ok((args, stdin))
+}
+
 pub fn to_engine_error(event_details: &EventDetails, error: HelmError) -> EngineError {
     EngineError::new_helm_error(event_details.clone(), error)
 }
@@ -1645,8 +1780,9 @@ impl Drop for HelmRegistry<'_> {
 mod tests {
     use crate::cloud_provider::helm::{ChartInfo, ChartSetValue};
     use crate::cmd::command::{CommandKiller, ExecutableCommand, QoveryCommand};
-    use crate::cmd::helm::{helm_exec_with_output, Helm, HelmError};
+    use crate::cmd::helm::{helm_exec_with_output, values_files_args, Helm, HelmError};
     use crate::deployment_action::deploy_helm::default_helm_timeout;
+    use crate::encryption::write_encrypted_file;
     use crate::io_models::container::Registry::GenericCr;
     use semver::Version;
     use std::path::Path;
@@ -1712,6 +1848,25 @@ mod tests {
         assert!(output.contains("Version:\"v3.12.3\""));
     }
 
+    #[test]
+    fn test_values_files_args() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let clear_values = dir.path().join("values.yaml");
+        let encrypted_values = dir.path().join("qovery-values.yaml");
+        std::fs::write(&clear_values, "replicas: 1").expect("Cannot write file");
+        write_encrypted_file(&encrypted_values, b"password: secret").expect("Cannot write file");
+        let clear_values = clear_values.to_string_lossy().to_string();
+        let encrypted_values = encrypted_values.to_string_lossy().to_string();
+
+        let (args, stdin) =
+            values_files_args(&[clear_values.clone(), encrypted_values.clone()]).expect("Cannot get values args");
+        assert_eq!(args, vec!["-f".to_string(), clear_values, "-f".to_string(), "-".to_string()]);
+        assert_eq!(stdin, Some(b"password: secret".to_vec()));
+
+        // helm has a single standard input
+        assert!(values_files_args(&[encrypted_values.clone(), encrypted_values]).is_err());
+    }
+
     #[test]
     fn test_release_exist() {
         let HelmTestCtx { ref helm, ref charts } = HelmTestCtx::new("test-release-exist");
@@ -1205,6 +1205,18 @@ where
     kubectl_exec_raw_output::<P>(cmd_args, kubernetes_config, envs, false)
 }
 
+/// Renders the manifests of the kustomization in the directory, as `kustomize build` does
+pub fn kubectl_exec_kustomize_build<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+    kustomization_dir: &str,
+) -> Result<String, CommandError>
+where
+    P: AsRef<Path>,
+{
+    kubectl_exec_raw_output::<P>(vec!["kustomize", kustomization_dir], kubernetes_config, envs, true)
+}
+
 pub fn kubectl_create_secret<P>(
     kubernetes_config: P,
     envs: Vec<(&str, &str)>,
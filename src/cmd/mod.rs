@@ -1,4 +1,5 @@
 pub mod command;
+pub mod cosign;
 pub mod docker;
 pub mod git_lfs;
 pub mod helm;
@@ -7,4 +8,5 @@ pub mod kubectl;
 pub mod kubectl_utils;
 pub mod skopeo;
 pub mod structs;
+pub mod syft;
 pub mod terraform;
@@ -390,7 +390,7 @@ pub struct ServerVersion {
     pub git_version: String,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HelmListItem {
     pub name: String,
     pub namespace: String,
@@ -0,0 +1,94 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::ContainerImage;
+
+use std::path::Path;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SyftError {
+    #[error("Syft terminated with a non success exit status code: {exit_status:?}: {output}")]
+    ExitStatusError { exit_status: ExitStatus, output: String },
+
+    #[error("Syft terminated with an unknown error: {raw_error:?}")]
+    ExecutionError { raw_error: std::io::Error },
+
+    #[error("Syft aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted { raw_error_message: String },
+
+    #[error("Syft command terminated due to timeout: {raw_error_message:?}")]
+    Timeout { raw_error_message: String },
+}
+
+impl SyftError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, Self::Aborted { .. })
+    }
+}
+
+/// Generates the software bill of materials of images, read from their registry.
+/// Registries are accessed with the credentials of the docker config the images are pushed with.
+#[derive(Debug)]
+pub struct Syft {
+    common_envs: Vec<(String, String)>,
+}
+
+impl Syft {
+    pub fn new(docker_config_path: &Path) -> Self {
+        Self {
+            common_envs: vec![
+                (
+                    "DOCKER_CONFIG".to_string(),
+                    docker_config_path.to_str().unwrap_or_default().to_string(),
+                ),
+                // the check for a newer version of syft is useless, and slow behind restricted egress
+                ("SYFT_CHECK_FOR_APP_UPDATE".to_string(), "false".to_string()),
+            ],
+        }
+    }
+
+    /// Writes the SBOM of the image to `output_path`, i.e: `cyclonedx-json` or `spdx-json` as `output_format`
+    pub fn generate_sbom(
+        &self,
+        image: &ContainerImage,
+        output_format: &str,
+        output_path: &Path,
+        cmd_killer: &CommandKiller,
+    ) -> Result<(), SyftError> {
+        let image_name = image.image_name();
+        info!("Generating {} SBOM of image {}", output_format, image_name);
+
+        let source = format!("registry:{image_name}");
+        let output = format!("{}={}", output_format, output_path.to_str().unwrap_or_default());
+        let args = &["scan", "--quiet", &source, "--output", &output];
+        let envs: Vec<(&str, &str)> = self.common_envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+        syft_exec(args, &envs, cmd_killer)
+    }
+}
+
+fn syft_exec(args: &[&str], envs: &[(&str, &str)], cmd_killer: &CommandKiller) -> Result<(), SyftError> {
+    let mut cmd = QoveryCommand::new("syft", args, envs);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+
+    let mut output: Vec<String> = vec![];
+    let ret = cmd.exec_with_abort(
+        &mut |line| info!("{}", line),
+        &mut |line| {
+            info!("{}", line);
+            output.push(line);
+        },
+        cmd_killer,
+    );
+
+    match ret {
+        Ok(_) => Ok(()),
+        Err(CommandError::TimeoutError(msg)) => Err(SyftError::Timeout { raw_error_message: msg }),
+        Err(CommandError::Killed(msg)) => Err(SyftError::Aborted { raw_error_message: msg }),
+        Err(CommandError::ExitStatusError(err)) => Err(SyftError::ExitStatusError {
+            exit_status: err,
+            output: output.join("\n"),
+        }),
+        Err(CommandError::ExecutionError(err)) => Err(SyftError::ExecutionError { raw_error: err }),
+    }
+}
@@ -1073,6 +1073,20 @@ pub fn terraform_state_list(root_dir: &str, envs: &[(&str, &str)]) -> Result<Vec
     }
 }
 
+/// Outputs of the state as json, with their value, type and whether they are sensitive.
+/// Unlike the other commands, the output is not logged as it holds the sensitive values.
+pub fn terraform_output(root_dir: &str, envs: &[(&str, &str)]) -> Result<String, TerraformError> {
+    let mut cmd = QoveryCommand::new("terraform", &["output", "-json"], envs);
+    cmd.set_current_dir(root_dir);
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    match cmd.exec_with_output(&mut |line| stdout.push(line), &mut |line| stderr.push(line)) {
+        Ok(_) => Ok(stdout.join("\n")),
+        Err(_) => Err(TerraformError::new(cmd.get_args(), String::new(), stderr.join("\n"))),
+    }
+}
+
 pub fn terraform_plan(root_dir: &str, envs: &[(&str, &str)]) -> Result<Vec<String>, TerraformError> {
     // plan
     let terraform_args = vec!["plan", "-no-color", "-out", "tf_plan"];
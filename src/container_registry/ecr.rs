@@ -12,12 +12,15 @@ use rusoto_ecr::{
     BatchDeleteImageRequest, CreateRepositoryRequest, DeleteRepositoryError, DeleteRepositoryRequest,
     DescribeImagesRequest, DescribeRepositoriesError, DescribeRepositoriesRequest, Ecr, EcrClient,
     GetAuthorizationTokenRequest, ImageDetail, ImageIdentifier, ListTagsForResourceRequest, PutLifecyclePolicyRequest,
-    Tag, TagResourceRequest,
+    PutReplicationConfigurationRequest, ReplicationConfiguration, ReplicationDestination, ReplicationRule, Tag,
+    TagResourceRequest,
 };
 use rusoto_sts::{GetCallerIdentityRequest, Sts, StsClient};
 
 use crate::build_platform::Image;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::image_retention::ImageRetentionPolicy;
+use crate::container_registry::replication::replication_regions;
 use crate::container_registry::{ContainerRegistry, ContainerRegistryInfo, Kind, Repository, RepositoryInfo};
 use crate::events::{EngineEvent, EventMessage, InfrastructureStep, Stage};
 use crate::io_models::context::Context;
@@ -133,6 +136,10 @@ impl ECR {
     }
 
     fn get_image(&self, image: &Image) -> Option<ImageDetail> {
+        self.get_image_in_region(image, self.region.clone())
+    }
+
+    fn get_image_in_region(&self, image: &Image, region: Region) -> Option<ImageDetail> {
         let mut dir = DescribeImagesRequest::default();
         dir.repository_name = image.name();
 
@@ -140,7 +147,7 @@ impl ECR {
         image_identifier.image_tag = Some(image.tag.to_string());
         dir.image_ids = Some(vec![image_identifier]);
 
-        let r = block_on_with_timeout(self.ecr_client().describe_images(dir));
+        let r = block_on_with_timeout(EcrClient::new_with_client(self.client(), region).describe_images(dir));
 
         match r {
             Err(_) | Ok(Err(_)) => None,
@@ -509,6 +516,107 @@ impl ContainerRegistry for ECR {
     fn image_exists(&self, image: &Image) -> bool {
         self.get_image(image).is_some()
     }
+
+    fn set_image_retention_policy(
+        &self,
+        image: &Image,
+        policy: &ImageRetentionPolicy,
+    ) -> Result<bool, ContainerRegistryError> {
+        let mut rules: Vec<serde_json::Value> = vec![];
+        if let Some(keep_last_tags) = policy.keep_last_tags {
+            rules.push(json!({
+              "action": { "type": "expire" },
+              "selection": {
+                "countType": "imageCountMoreThan",
+                "countNumber": keep_last_tags.max(1),
+                "tagStatus": "any"
+              },
+              "description": "Keep the last images of the application",
+              "rulePriority": rules.len() + 1
+            }));
+        }
+        if let Some(expire_after_days) = policy.expire_after_days {
+            rules.push(json!({
+              "action": { "type": "expire" },
+              "selection": {
+                "countType": "sinceImagePushed",
+                "countUnit": "days",
+                "countNumber": expire_after_days.max(1),
+                "tagStatus": "any"
+              },
+              "description": "Expire the old images of the application",
+              "rulePriority": rules.len() + 1
+            }));
+        }
+
+        let plp = PutLifecyclePolicyRequest {
+            repository_name: image.repository_name().to_string(),
+            lifecycle_policy_text: json!({ "rules": rules }).to_string(),
+            ..Default::default()
+        };
+        match block_on_with_timeout(self.ecr_client().put_lifecycle_policy(plp)) {
+            Ok(Ok(_)) => Ok(true),
+            Ok(Err(err)) => Err(ContainerRegistryError::CannotSetRepositoryLifecyclePolicy {
+                registry_name: self.name.to_string(),
+                repository_name: image.repository_name().to_string(),
+                raw_error_message: err.to_string(),
+            }),
+            Err(err) => Err(ContainerRegistryError::CannotSetRepositoryLifecyclePolicy {
+                registry_name: self.name.to_string(),
+                repository_name: image.repository_name().to_string(),
+                raw_error_message: err.to_string(),
+            }),
+        }
+    }
+
+    fn set_replication_regions(&self, regions: &[String]) -> Result<bool, ContainerRegistryError> {
+        let regions = replication_regions(regions, self.region.name());
+        if regions.is_empty() {
+            return Ok(true);
+        }
+        let cannot_set_replication = |raw_error_message: String| ContainerRegistryError::CannotSetReplication {
+            registry_name: self.name.to_string(),
+            regions: regions.clone(),
+            raw_error_message,
+        };
+
+        // images are replicated to the registries of the same account, the rules apply to all its repositories
+        let client = StsClient::new_with_client(self.client(), Region::default());
+        let account_id = match block_on_with_timeout(client.get_caller_identity(GetCallerIdentityRequest::default())) {
+            Ok(Ok(identity)) => identity
+                .account
+                .ok_or_else(|| cannot_set_replication("no account id for the credentials".to_string()))?,
+            Ok(Err(err)) => return Err(cannot_set_replication(err.to_string())),
+            Err(err) => return Err(cannot_set_replication(err.to_string())),
+        };
+
+        let request = PutReplicationConfigurationRequest {
+            replication_configuration: ReplicationConfiguration {
+                rules: vec![ReplicationRule {
+                    destinations: regions
+                        .iter()
+                        .map(|region| ReplicationDestination {
+                            region: region.to_string(),
+                            registry_id: account_id.clone(),
+                        })
+                        .collect(),
+                    ..Default::default()
+                }],
+            },
+        };
+        match block_on_with_timeout(self.ecr_client().put_replication_configuration(request)) {
+            Ok(Ok(_)) => Ok(true),
+            Ok(Err(err)) => Err(cannot_set_replication(err.to_string())),
+            Err(err) => Err(cannot_set_replication(err.to_string())),
+        }
+    }
+
+    fn is_image_replicated(&self, image: &Image, region: &str) -> Result<Option<bool>, ContainerRegistryError> {
+        match Region::from_str(region) {
+            Ok(region) => Ok(Some(self.get_image_in_region(image, region).is_some())),
+            Err(_) => Ok(None),
+        }
+    }
 }
 
 pub struct ECRCredentials {
@@ -99,6 +99,13 @@ pub enum ContainerRegistryError {
         raw_error_message: String,
     },
 
+    #[error("Cannot set replication of registry `{registry_name:?}` to regions {regions:?}: {raw_error_message:?}.")]
+    CannotSetReplication {
+        registry_name: String,
+        regions: Vec<String>,
+        raw_error_message: String,
+    },
+
     #[error("Repository name `{repository_name:?}` in registry `{registry_name:?}  is invalid, following rules are broken: {broken_rules:?}")]
     RepositoryNameNotValid {
         registry_name: String,
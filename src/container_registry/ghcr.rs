@@ -0,0 +1,445 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::build_platform::Image;
+use crate::cmd::docker::ContainerImage;
+use crate::cmd::skopeo::Skopeo;
+use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::{ContainerRegistry, ContainerRegistryInfo, Kind, Repository, RepositoryInfo};
+use crate::io_models::context::Context;
+use crate::runtime::block_on;
+use url::Url;
+use uuid::Uuid;
+
+const GHCR_URL: &str = "https://ghcr.io";
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITHUB_API_VERSION: &str = "2022-11-28";
+// maximum allowed by GitHub, the following pages being linked by each response
+const GITHUB_API_PAGE_SIZE: u32 = 100;
+
+/// GitHub serves the packages of users and organizations on different API routes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PackagesOwnerKind {
+    User,
+    Organization,
+}
+
+#[derive(Deserialize)]
+struct GithubOwner {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct GithubPackage {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GithubPackageVersion {
+    id: u64,
+    metadata: GithubPackageVersionMetadata,
+}
+
+#[derive(Deserialize)]
+struct GithubPackageVersionMetadata {
+    container: GithubContainerMetadata,
+}
+
+#[derive(Deserialize)]
+struct GithubContainerMetadata {
+    tags: Vec<String>,
+}
+
+/// GitHub Container Registry, images are pushed as container packages of a user or an organization and login
+/// is done with a personal access token allowed to read, write and delete packages
+pub struct GithubContainerRegistry {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    owner: String,
+    owner_kind: PackagesOwnerKind,
+    token: String,
+    skopeo: Skopeo,
+    registry_info: ContainerRegistryInfo,
+}
+
+impl GithubContainerRegistry {
+    pub fn new(
+        context: Context,
+        id: &str,
+        long_id: Uuid,
+        name: &str,
+        owner: &str,
+        login: &str,
+        token: &str,
+    ) -> Result<Self, ContainerRegistryError> {
+        // GitHub owners are case insensitive but image names must be lowercase
+        let owner = owner.to_lowercase();
+        let mut registry = Url::parse(GHCR_URL).map_err(|_e| ContainerRegistryError::InvalidRegistryUrl {
+            registry_url: GHCR_URL.to_string(),
+        })?;
+        let _ = registry.set_username(login);
+        let _ = registry.set_password(Some(token));
+
+        if context.docker.login(&registry).is_err() {
+            return Err(ContainerRegistryError::InvalidCredentials);
+        }
+
+        let owner_kind = match get_owner_kind(&owner, token) {
+            Ok(owner_kind) => owner_kind,
+            Err(StatusCode::UNAUTHORIZED) => return Err(ContainerRegistryError::InvalidCredentials),
+            Err(status) => {
+                return Err(ContainerRegistryError::RegistryDoesntExist {
+                    registry_name: name.to_string(),
+                    raw_error_message: format!("Cannot get GitHub owner `{owner}`: {status}"),
+                })
+            }
+        };
+
+        let skopeo = Skopeo::new(Some((login.to_string(), token.to_string()))).map_err(|err| {
+            ContainerRegistryError::CannotInstantiateClient {
+                raw_error_message: err.to_string(),
+            }
+        })?;
+
+        let registry_info = ContainerRegistryInfo {
+            endpoint: registry,
+            registry_name: name.to_string(),
+            registry_docker_json_config: Some(Self::get_docker_json_config_raw(login, token)),
+            get_image_name: Box::new({
+                let owner = owner.clone();
+                move |img_name| format!("{owner}/{img_name}")
+            }),
+            // repositories are the packages of the owner
+            get_repository_name: Box::new(|repository_name| repository_name.to_string()),
+        };
+
+        Ok(GithubContainerRegistry {
+            context,
+            id: id.to_string(),
+            long_id,
+            name: name.to_string(),
+            owner,
+            owner_kind,
+            token: token.to_string(),
+            skopeo,
+            registry_info,
+        })
+    }
+
+    fn get_docker_json_config_raw(login: &str, token: &str) -> String {
+        general_purpose::STANDARD.encode(
+            format!(
+                r#"{{"auths":{{"ghcr.io":{{"auth":"{}"}}}}}}"#,
+                general_purpose::STANDARD.encode(format!("{login}:{token}").as_bytes())
+            )
+            .as_bytes(),
+        )
+    }
+
+    fn package_path(&self, repository_name: &str) -> String {
+        package_path(self.owner_kind, &self.owner, repository_name)
+    }
+
+    fn repository(&self, repository_name: &str) -> Repository {
+        Repository {
+            registry_id: self.owner.to_string(),
+            name: repository_name.to_string(),
+            uri: Some(format!("ghcr.io/{}/{}", self.owner, repository_name)),
+            ttl: None,
+            labels: None,
+        }
+    }
+
+    fn get_package_versions(&self, repository_name: &str) -> Result<Vec<GithubPackageVersion>, String> {
+        let path = format!("{}/versions", self.package_path(repository_name));
+        github_list(&path, &self.token).map_err(|status| status.to_string())
+    }
+}
+
+fn package_path(owner_kind: PackagesOwnerKind, owner: &str, repository_name: &str) -> String {
+    let owner_path = match owner_kind {
+        PackagesOwnerKind::User => format!("/users/{owner}"),
+        PackagesOwnerKind::Organization => format!("/orgs/{owner}"),
+    };
+
+    // nested package names must be sent url encoded, i.e: `app%2Fworker`
+    format!("{owner_path}/packages/container/{}", urlencoding::encode(repository_name))
+}
+
+fn first_page_url(path: &str) -> String {
+    format!("{GITHUB_API_URL}{path}?per_page={GITHUB_API_PAGE_SIZE}")
+}
+
+/// Url of the next page given by the `Link` header of a GitHub API response, i.e:
+/// `<https://api.github.com/user/packages?page=2>; rel="next", <https://api.github.com/user/packages?page=5>; rel="last"`
+fn next_page_url(link_header: &str) -> Option<String> {
+    link_header.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+/// Items of all the pages, from the first one, each page giving the url of the next one if any
+fn collect_pages<T>(
+    first_page_url: String,
+    mut get_page: impl FnMut(&str) -> Result<(Vec<T>, Option<String>), StatusCode>,
+) -> Result<Vec<T>, StatusCode> {
+    let mut items = vec![];
+    let mut page_url = Some(first_page_url);
+    while let Some(url) = page_url {
+        let (page_items, next_page_url) = get_page(&url)?;
+        items.extend(page_items);
+        // a page linking to itself would never end
+        page_url = next_page_url.filter(|next_page_url| next_page_url != &url);
+    }
+
+    Ok(items)
+}
+
+/// Sends a request to the GitHub API, `None` being returned when the resource does not exist. The response comes
+/// with the url of its next page, if the route is paginated and it is not the last one.
+fn github_send<T: DeserializeOwned>(
+    method: Method,
+    url: &str,
+    token: &str,
+) -> Result<Option<(T, Option<String>)>, StatusCode> {
+    block_on(async {
+        let response = reqwest::Client::new()
+            .request(method.clone(), url)
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+            .header("User-Agent", "qovery-engine")
+            .send()
+            .await
+            .map_err(|e| e.status().unwrap_or(StatusCode::SERVICE_UNAVAILABLE))?;
+        let next_page_url = response
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|link| link.to_str().ok())
+            .and_then(next_page_url);
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if !status.is_success() => Err(status),
+            // deletions answer with an empty body
+            _ if method == Method::DELETE => Ok(None),
+            _ => response
+                .json()
+                .await
+                .map(|body| Some((body, next_page_url)))
+                .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY),
+        }
+    })
+}
+
+/// Sends a request to the GitHub API, `None` being returned when the resource does not exist
+fn github_request<T: DeserializeOwned>(method: Method, path: &str, token: &str) -> Result<Option<T>, StatusCode> {
+    github_send(method, &format!("{GITHUB_API_URL}{path}"), token).map(|response| response.map(|(body, _)| body))
+}
+
+/// Lists the items of a paginated GitHub API route, none when it does not exist
+fn github_list<T: DeserializeOwned>(path: &str, token: &str) -> Result<Vec<T>, StatusCode> {
+    collect_pages(first_page_url(path), |url| {
+        Ok(github_send(Method::GET, url, token)?.unwrap_or_default())
+    })
+}
+
+fn get_owner_kind(owner: &str, token: &str) -> Result<PackagesOwnerKind, StatusCode> {
+    let github_owner: GithubOwner =
+        github_request(Method::GET, &format!("/users/{owner}"), token)?.ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(match github_owner.kind.as_str() {
+        "Organization" => PackagesOwnerKind::Organization,
+        _ => PackagesOwnerKind::User,
+    })
+}
+
+impl ContainerRegistry for GithubContainerRegistry {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Ghcr
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn registry_info(&self) -> &ContainerRegistryInfo {
+        &self.registry_info
+    }
+
+    fn create_registry(&self) -> Result<(), ContainerRegistryError> {
+        // Nothing to do, the registry of the owner is always there
+        Ok(())
+    }
+
+    fn create_repository(
+        &self,
+        repository_name: &str,
+        _image_retention_time_in_seconds: u32,
+        _resource_ttl: Option<Duration>,
+    ) -> Result<(Repository, RepositoryInfo), ContainerRegistryError> {
+        match self.get_repository(repository_name) {
+            Ok(repository) => Ok((repository, RepositoryInfo { created: false })),
+            // GHCR creates the package on the first push of one of its images
+            Err(ContainerRegistryError::RepositoryDoesntExistInRegistry { .. }) => {
+                Ok((self.repository(repository_name), RepositoryInfo { created: false }))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn get_repository(&self, repository_name: &str) -> Result<Repository, ContainerRegistryError> {
+        match github_request::<GithubPackage>(Method::GET, &self.package_path(repository_name), &self.token) {
+            Ok(Some(package)) => Ok(self.repository(&package.name)),
+            Ok(None) => Err(ContainerRegistryError::RepositoryDoesntExistInRegistry {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+            }),
+            Err(status) => Err(ContainerRegistryError::CannotGetRepository {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: status.to_string(),
+            }),
+        }
+    }
+
+    fn delete_repository(&self, repository_name: &str) -> Result<(), ContainerRegistryError> {
+        github_request::<()>(Method::DELETE, &self.package_path(repository_name), &self.token)
+            .map(|_| ())
+            .map_err(|status| ContainerRegistryError::CannotDeleteRepository {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: status.to_string(),
+            })
+    }
+
+    fn delete_image(&self, image: &Image) -> Result<(), ContainerRegistryError> {
+        let repository_name = image.repository_name();
+        let on_error = |raw_error_message: String| ContainerRegistryError::CannotDeleteImage {
+            registry_name: self.name.to_string(),
+            repository_name: repository_name.to_string(),
+            image_name: image.name(),
+            raw_error_message,
+        };
+
+        let versions = self.get_package_versions(repository_name).map_err(on_error)?;
+        let Some(version) = versions
+            .iter()
+            .find(|version| version.metadata.container.tags.contains(&image.tag))
+        else {
+            return Ok(());
+        };
+
+        // GitHub refuses to delete the last version of a package, the package itself must be deleted instead
+        if versions.len() == 1 {
+            return self.delete_repository(repository_name);
+        }
+
+        let path = format!("{}/versions/{}", self.package_path(repository_name), version.id);
+        github_request::<()>(Method::DELETE, &path, &self.token)
+            .map(|_| ())
+            .map_err(|status| on_error(status.to_string()))
+    }
+
+    fn image_exists(&self, image: &Image) -> bool {
+        let container =
+            ContainerImage::new(self.registry_info.endpoint.clone(), image.name.clone(), vec![image.tag.clone()]);
+        let Ok(tags) = self.skopeo.list_tags(&container, true) else {
+            return false;
+        };
+
+        tags.contains(&image.tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_package_path() {
+        assert_eq!(
+            package_path(PackagesOwnerKind::User, "octocat", "app"),
+            "/users/octocat/packages/container/app"
+        );
+        assert_eq!(
+            package_path(PackagesOwnerKind::Organization, "qovery", "app/worker"),
+            "/orgs/qovery/packages/container/app%2Fworker"
+        );
+        assert_eq!(
+            first_page_url("/orgs/qovery/packages/container/app%2Fworker/versions"),
+            "https://api.github.com/orgs/qovery/packages/container/app%2Fworker/versions?per_page=100"
+        );
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        assert_eq!(
+            next_page_url(
+                r#"<https://api.github.com/orgs/qovery/packages/container/app/versions?per_page=100&page=2>; rel="next", <https://api.github.com/orgs/qovery/packages/container/app/versions?per_page=100&page=3>; rel="last""#
+            ),
+            Some("https://api.github.com/orgs/qovery/packages/container/app/versions?per_page=100&page=2".to_string())
+        );
+        // last page
+        assert_eq!(
+            next_page_url(
+                r#"<https://api.github.com/orgs/qovery/packages/container/app/versions?per_page=100&page=1>; rel="first", <https://api.github.com/orgs/qovery/packages/container/app/versions?per_page=100&page=2>; rel="prev""#
+            ),
+            None
+        );
+        assert_eq!(next_page_url(""), None);
+    }
+
+    #[test]
+    fn test_collect_pages() {
+        let pages: HashMap<&str, (Vec<u32>, Option<String>)> = HashMap::from([
+            ("page=1", (vec![1, 2], Some("page=2".to_string()))),
+            ("page=2", (vec![3, 4], Some("page=3".to_string()))),
+            ("page=3", (vec![5], None)),
+            ("loop", (vec![6], Some("loop".to_string()))),
+        ]);
+        let mut requested_urls = vec![];
+        let items = collect_pages("page=1".to_string(), |url| {
+            requested_urls.push(url.to_string());
+            Ok(pages[url].clone())
+        })
+        .unwrap();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        assert_eq!(requested_urls, vec!["page=1", "page=2", "page=3"]);
+
+        assert_eq!(
+            collect_pages("loop".to_string(), |url| Ok(pages[url].clone())).unwrap(),
+            vec![6]
+        );
+
+        let failing_page = collect_pages("page=1".to_string(), |url| match url {
+            "page=2" => Err(StatusCode::FORBIDDEN),
+            url => Ok(pages[url].clone()),
+        });
+        assert_eq!(failing_page, Err(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,470 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::build_platform::Image;
+use crate::cmd::docker::ContainerImage;
+use crate::cmd::skopeo::Skopeo;
+use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::{
+    ContainerRegistry, ContainerRegistryInfo, Kind, RegistryImage, Repository, RepositoryInfo,
+};
+use crate::io_models::context::Context;
+use crate::runtime::block_on;
+use url::Url;
+use uuid::Uuid;
+
+const HARBOR_API_PATH: &str = "api/v2.0";
+const RETENTION_SCHEDULE_CRON: &str = "0 0 0 * * *";
+const ARTIFACTS_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct HarborProject {
+    project_id: u64,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct HarborRepository {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct HarborTag {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct HarborArtifact {
+    digest: String,
+    push_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    tags: Option<Vec<HarborTag>>,
+}
+
+/// Harbor registry of on-premise clusters, images of a registry are pushed in a single Harbor project which is
+/// created when missing. Login is done with a robot account, which must be a system one for the project to be created.
+pub struct Harbor {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    url: Url,
+    project_name: String,
+    login: String,
+    password: String,
+    skip_tls_verification: bool,
+    skopeo: Skopeo,
+    registry_info: ContainerRegistryInfo,
+}
+
+impl Harbor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        context: Context,
+        id: &str,
+        long_id: Uuid,
+        name: &str,
+        url: Url,
+        project_name: &str,
+        login: &str,
+        password: &str,
+        skip_tls_verification: bool,
+    ) -> Result<Self, ContainerRegistryError> {
+        let mut registry = url.clone();
+        let _ = registry.set_username(login);
+        let _ = registry.set_password(Some(password));
+
+        if context.docker.login(&registry).is_err() {
+            return Err(ContainerRegistryError::InvalidCredentials);
+        }
+
+        let skopeo = Skopeo::new(Some((login.to_string(), password.to_string()))).map_err(|err| {
+            ContainerRegistryError::CannotInstantiateClient {
+                raw_error_message: err.to_string(),
+            }
+        })?;
+
+        let registry_info = ContainerRegistryInfo {
+            endpoint: registry,
+            registry_name: name.to_string(),
+            registry_docker_json_config: Some(Self::get_docker_json_config_raw(&url, login, password)),
+            get_image_name: Box::new({
+                let project_name = project_name.to_string();
+                move |img_name| format!("{project_name}/{img_name}")
+            }),
+            // repositories are named relatively to their project
+            get_repository_name: Box::new(|repository_name| repository_name.to_string()),
+        };
+
+        let harbor = Harbor {
+            context,
+            id: id.to_string(),
+            long_id,
+            name: name.to_string(),
+            url,
+            project_name: project_name.to_string(),
+            login: login.to_string(),
+            password: password.to_string(),
+            skip_tls_verification,
+            skopeo,
+            registry_info,
+        };
+
+        // docker login accepts robot accounts whose project has been deleted, the api is more picky
+        match harbor.get_project() {
+            Ok(_) => Ok(harbor),
+            Err(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => Err(ContainerRegistryError::InvalidCredentials),
+            Err(status) => Err(ContainerRegistryError::CannotInstantiateClient {
+                raw_error_message: format!("Cannot reach Harbor api of {}: {}", harbor.url, status),
+            }),
+        }
+    }
+
+    fn get_docker_json_config_raw(url: &Url, login: &str, password: &str) -> String {
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        general_purpose::STANDARD.encode(
+            format!(
+                r#"{{"auths":{{"{}":{{"auth":"{}"}}}}}}"#,
+                host,
+                general_purpose::STANDARD.encode(format!("{login}:{password}").as_bytes())
+            )
+            .as_bytes(),
+        )
+    }
+
+    /// Sends a request to the Harbor API, `None` being returned when the resource does not exist or when the
+    /// request answers with an empty body, i.e: creations and deletions
+    fn harbor_request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<Option<T>, StatusCode> {
+        block_on(async {
+            let client = reqwest::Client::builder()
+                .danger_accept_invalid_certs(self.skip_tls_verification)
+                .build()
+                .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+            let mut request = client
+                .request(
+                    method.clone(),
+                    format!("{}/{}{}", self.url.as_str().trim_end_matches('/'), HARBOR_API_PATH, path),
+                )
+                .basic_auth(&self.login, Some(&self.password))
+                // projects are looked up by name rather than by id
+                .header("X-Is-Resource-Name", "true");
+            if let Some(body) = body {
+                request = request.json(&body);
+            }
+
+            let response = request
+                .send()
+                .await
+                .map_err(|e| e.status().unwrap_or(StatusCode::SERVICE_UNAVAILABLE))?;
+
+            match response.status() {
+                StatusCode::NOT_FOUND => Ok(None),
+                status if !status.is_success() => Err(status),
+                _ if method != Method::GET => Ok(None),
+                _ => response
+                    .json()
+                    .await
+                    .map(Some)
+                    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY),
+            }
+        })
+    }
+
+    fn get_project(&self) -> Result<Option<HarborProject>, StatusCode> {
+        self.harbor_request(Method::GET, &format!("/projects/{}", self.project_name), None)
+    }
+
+    fn create_project(&self) -> Result<HarborProject, StatusCode> {
+        self.harbor_request::<()>(
+            Method::POST,
+            "/projects",
+            Some(json!({
+                "project_name": self.project_name,
+                "metadata": { "public": "false" },
+            })),
+        )?;
+
+        self.get_project()?.ok_or(StatusCode::NOT_FOUND)
+    }
+
+    /// Harbor retention policies are set per project, the last repository created sets it for all of them
+    fn set_retention_policy(&self, project: &HarborProject, retention_in_days: u32) -> Result<(), StatusCode> {
+        let policy = retention_policy(project.project_id, retention_in_days);
+        match project.metadata.get("retention_id") {
+            Some(retention_id) => {
+                self.harbor_request::<()>(Method::PUT, &format!("/retentions/{retention_id}"), Some(policy))?
+            }
+            None => self.harbor_request::<()>(Method::POST, "/retentions", Some(policy))?,
+        };
+
+        Ok(())
+    }
+
+    fn repository_path(&self, repository_name: &str) -> String {
+        // nested repository names must be sent url encoded twice, i.e: `app%252Fworker`
+        format!(
+            "/projects/{}/repositories/{}",
+            self.project_name,
+            urlencoding::encode(&urlencoding::encode(repository_name))
+        )
+    }
+
+    fn repository(&self, repository_name: &str) -> Repository {
+        Repository {
+            registry_id: self.project_name.to_string(),
+            name: repository_name.to_string(),
+            uri: Some(format!(
+                "{}/{}/{}",
+                self.url.host_str().unwrap_or_default(),
+                self.project_name,
+                repository_name
+            )),
+            ttl: None,
+            labels: None,
+        }
+    }
+}
+
+/// Keeps the artifacts pushed during the last days, whatever their tag
+fn retention_policy(project_id: u64, retention_in_days: u32) -> serde_json::Value {
+    json!({
+        "algorithm": "or",
+        "rules": [{
+            "disabled": false,
+            "action": "retain",
+            "template": "nDaysSinceLastPush",
+            "params": { "nDaysSinceLastPush": retention_in_days },
+            "tag_selectors": [{ "kind": "doublestar", "decoration": "matches", "pattern": "**" }],
+            "scope_selectors": {
+                "repository": [{ "kind": "doublestar", "decoration": "repoMatches", "pattern": "**" }]
+            },
+        }],
+        "trigger": { "kind": "Schedule", "settings": { "cron": RETENTION_SCHEDULE_CRON } },
+        "scope": { "level": "project", "ref": project_id },
+    })
+}
+
+impl ContainerRegistry for Harbor {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Harbor
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn registry_info(&self) -> &ContainerRegistryInfo {
+        &self.registry_info
+    }
+
+    fn create_registry(&self) -> Result<(), ContainerRegistryError> {
+        let on_error = |status: StatusCode| ContainerRegistryError::CannotCreateRegistry {
+            registry_name: self.name.to_string(),
+            raw_error_message: format!("Cannot create Harbor project `{}`: {}", self.project_name, status),
+        };
+
+        match self.get_project().map_err(on_error)? {
+            Some(_) => Ok(()),
+            None => self.create_project().map(|_| ()).map_err(on_error),
+        }
+    }
+
+    fn create_repository(
+        &self,
+        repository_name: &str,
+        image_retention_time_in_seconds: u32,
+        _resource_ttl: Option<Duration>,
+    ) -> Result<(Repository, RepositoryInfo), ContainerRegistryError> {
+        let project = match self.get_project() {
+            Ok(Some(project)) => Ok(project),
+            Ok(None) => self.create_project(),
+            Err(status) => Err(status),
+        }
+        .map_err(|status| ContainerRegistryError::CannotCreateRepository {
+            registry_name: self.name.to_string(),
+            repository_name: repository_name.to_string(),
+            raw_error_message: format!("Cannot get Harbor project `{}`: {}", self.project_name, status),
+        })?;
+
+        let retention_in_days = (image_retention_time_in_seconds / (24 * 60 * 60)).max(1);
+        self.set_retention_policy(&project, retention_in_days)
+            .map_err(|status| ContainerRegistryError::CannotSetRepositoryLifecyclePolicy {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: status.to_string(),
+            })?;
+
+        // Harbor creates the repository on the first push of one of its images
+        Ok((self.repository(repository_name), RepositoryInfo { created: false }))
+    }
+
+    fn get_repository(&self, repository_name: &str) -> Result<Repository, ContainerRegistryError> {
+        match self.harbor_request::<HarborRepository>(Method::GET, &self.repository_path(repository_name), None) {
+            // repository names are prefixed by their project
+            Ok(Some(repository)) => {
+                Ok(self.repository(repository.name.trim_start_matches(&format!("{}/", self.project_name))))
+            }
+            Ok(None) => Err(ContainerRegistryError::RepositoryDoesntExistInRegistry {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+            }),
+            Err(status) => Err(ContainerRegistryError::CannotGetRepository {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: status.to_string(),
+            }),
+        }
+    }
+
+    fn delete_repository(&self, repository_name: &str) -> Result<(), ContainerRegistryError> {
+        self.harbor_request::<()>(Method::DELETE, &self.repository_path(repository_name), None)
+            .map(|_| ())
+            .map_err(|status| ContainerRegistryError::CannotDeleteRepository {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: status.to_string(),
+            })
+    }
+
+    fn delete_image(&self, image: &Image) -> Result<(), ContainerRegistryError> {
+        let repository_name = image.repository_name();
+        let path = format!("{}/artifacts/{}", self.repository_path(repository_name), image.tag);
+        self.harbor_request::<()>(Method::DELETE, &path, None)
+            .map(|_| ())
+            .map_err(|status| ContainerRegistryError::CannotDeleteImage {
+                registry_name: self.name.to_string(),
+                repository_name: repository_name.to_string(),
+                image_name: image.name(),
+                raw_error_message: status.to_string(),
+            })
+    }
+
+    fn image_exists(&self, image: &Image) -> bool {
+        let container =
+            ContainerImage::new(self.registry_info.endpoint.clone(), image.name.clone(), vec![image.tag.clone()]);
+        let Ok(tags) = self.skopeo.list_tags(&container, !self.skip_tls_verification) else {
+            return false;
+        };
+
+        tags.contains(&image.tag)
+    }
+
+    // Harbor retention policies are set per project, so the ones of a single service are applied by the engine
+    fn list_images(&self, image: &Image) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        let mut images: Vec<RegistryImage> = vec![];
+        for page in 1.. {
+            let path = format!(
+                "{}/artifacts?with_tag=true&page={}&page_size={}",
+                self.repository_path(image.repository_name()),
+                page,
+                ARTIFACTS_PAGE_SIZE
+            );
+            let artifacts = self
+                .harbor_request::<Vec<HarborArtifact>>(Method::GET, &path, None)
+                .map_err(|status| ContainerRegistryError::CannotGetRepository {
+                    registry_name: self.name.to_string(),
+                    repository_name: image.repository_name().to_string(),
+                    raw_error_message: status.to_string(),
+                })?
+                .unwrap_or_default();
+
+            let is_last_page = artifacts.len() < ARTIFACTS_PAGE_SIZE;
+            images.extend(artifacts.into_iter().map(|artifact| {
+                RegistryImage {
+                    id: artifact.digest,
+                    tags: artifact
+                        .tags
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tag| tag.name)
+                        .collect(),
+                    pushed_at: artifact.push_time,
+                }
+            }));
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(images)
+    }
+
+    fn delete_registry_image(
+        &self,
+        image: &Image,
+        registry_image: &RegistryImage,
+    ) -> Result<(), ContainerRegistryError> {
+        let path = format!(
+            "{}/artifacts/{}",
+            self.repository_path(image.repository_name()),
+            registry_image.id
+        );
+        self.harbor_request::<()>(Method::DELETE, &path, None)
+            .map(|_| ())
+            .map_err(|status| ContainerRegistryError::CannotDeleteImage {
+                registry_name: self.name.to_string(),
+                repository_name: image.repository_name().to_string(),
+                image_name: image.name(),
+                raw_error_message: status.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_policy() {
+        let policy = retention_policy(42, 7);
+
+        assert_eq!(policy["scope"]["ref"], json!(42));
+        assert_eq!(policy["rules"][0]["params"]["nDaysSinceLastPush"], json!(7));
+        assert_eq!(policy["trigger"]["settings"]["cron"], json!(RETENTION_SCHEDULE_CRON));
+    }
+
+    #[test]
+    fn test_docker_json_config() {
+        let url = Url::parse("https://harbor.example.com:8443").unwrap();
+        let config = general_purpose::STANDARD
+            .decode(Harbor::get_docker_json_config_raw(&url, "robot$qovery", "secret"))
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(config).unwrap(),
+            format!(
+                r#"{{"auths":{{"harbor.example.com:8443":{{"auth":"{}"}}}}}}"#,
+                general_purpose::STANDARD.encode("robot$qovery:secret")
+            )
+        );
+    }
+}
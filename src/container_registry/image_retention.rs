@@ -0,0 +1,129 @@
+use crate::build_platform::Image;
+use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::{ContainerRegistry, RegistryImage};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Retention rules of the images of a service, images matching any of them being deleted
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImageRetentionPolicy {
+    /// Only the images of the last N pushes are kept
+    pub keep_last_tags: Option<u32>,
+    /// Images pushed more than X days ago are deleted
+    pub expire_after_days: Option<u32>,
+}
+
+impl ImageRetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.keep_last_tags.is_none() && self.expire_after_days.is_none()
+    }
+}
+
+/// Images out of the policy, the most recent first. The deployed tag is never part of them, whatever its age.
+/// Images without push date cannot be ordered, so they are always kept.
+pub fn images_to_expire(
+    mut images: Vec<RegistryImage>,
+    policy: &ImageRetentionPolicy,
+    now: DateTime<Utc>,
+    deployed_tag: &str,
+) -> Vec<RegistryImage> {
+    images.retain(|image| image.pushed_at.is_some() && !image.tags.iter().any(|tag| tag == deployed_tag));
+    images.sort_by(|a, b| b.pushed_at.cmp(&a.pushed_at));
+
+    // the deployed image counts as one of the kept ones
+    let keep_last = policy
+        .keep_last_tags
+        .map(|keep_last_tags| keep_last_tags.saturating_sub(1) as usize);
+    let expiration_date = policy
+        .expire_after_days
+        .map(|expire_after_days| now - Duration::days(expire_after_days as i64));
+
+    images
+        .into_iter()
+        .enumerate()
+        .filter(|(index, image)| {
+            keep_last.is_some_and(|keep_last| *index >= keep_last)
+                || matches!((expiration_date, image.pushed_at), (Some(expiration_date), Some(pushed_at)) if pushed_at < expiration_date)
+        })
+        .map(|(_, image)| image)
+        .collect()
+}
+
+/// Applies the retention policy of the images of a service, with the lifecycle rules of the registry when it has
+/// some, otherwise by deleting the expired images right away. Returns the number of images deleted.
+pub fn apply_image_retention_policy(
+    container_registry: &dyn ContainerRegistry,
+    image: &Image,
+    policy: &ImageRetentionPolicy,
+) -> Result<usize, ContainerRegistryError> {
+    if policy.is_empty() || container_registry.set_image_retention_policy(image, policy)? {
+        return Ok(0);
+    }
+
+    let images = container_registry.list_images(image)?;
+    let expired_images = images_to_expire(images, policy, Utc::now(), &image.tag);
+    for expired_image in &expired_images {
+        container_registry.delete_registry_image(image, expired_image)?;
+    }
+
+    Ok(expired_images.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(tag: &str, pushed_days_ago: Option<i64>) -> RegistryImage {
+        RegistryImage {
+            id: tag.to_string(),
+            tags: vec![tag.to_string()],
+            pushed_at: pushed_days_ago.map(|days| Utc::now() - Duration::days(days)),
+        }
+    }
+
+    fn tags(images: Vec<RegistryImage>) -> Vec<String> {
+        images.into_iter().flat_map(|image| image.tags).collect()
+    }
+
+    #[test]
+    fn test_images_to_expire() {
+        let images = vec![
+            image("v1", Some(40)),
+            image("v4", Some(1)),
+            image("v2", Some(20)),
+            image("v3", Some(10)),
+            image("unknown", None),
+        ];
+
+        let keep_last = ImageRetentionPolicy {
+            keep_last_tags: Some(2),
+            expire_after_days: None,
+        };
+        assert_eq!(
+            tags(images_to_expire(images.clone(), &keep_last, Utc::now(), "v4")),
+            vec!["v2", "v1"]
+        );
+        // an old deployed image is kept, and counts as one of the last ones
+        assert_eq!(
+            tags(images_to_expire(images.clone(), &keep_last, Utc::now(), "v1")),
+            vec!["v3", "v2"]
+        );
+
+        let expire_after = ImageRetentionPolicy {
+            keep_last_tags: None,
+            expire_after_days: Some(15),
+        };
+        assert_eq!(
+            tags(images_to_expire(images.clone(), &expire_after, Utc::now(), "v4")),
+            vec!["v2", "v1"]
+        );
+
+        let both = ImageRetentionPolicy {
+            keep_last_tags: Some(3),
+            expire_after_days: Some(30),
+        };
+        assert_eq!(tags(images_to_expire(images.clone(), &both, Utc::now(), "v4")), vec!["v1"]);
+
+        assert!(images_to_expire(images, &ImageRetentionPolicy::default(), Utc::now(), "v4").is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -6,6 +7,7 @@ use uuid::Uuid;
 
 use crate::build_platform::Image;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::image_retention::ImageRetentionPolicy;
 use crate::errors::EngineError;
 use crate::events::{EventDetails, Stage, Transmitter};
 use crate::io_models::context::Context;
@@ -14,7 +16,11 @@ use crate::io_models::QoveryIdentifier;
 pub mod ecr;
 pub mod errors;
 pub mod generic_cr;
+pub mod ghcr;
 pub mod google_artifact_registry;
+pub mod harbor;
+pub mod image_retention;
+pub mod replication;
 pub mod scaleway_container_registry;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -26,6 +32,15 @@ pub struct Repository {
     pub labels: Option<HashMap<String, String>>,
 }
 
+/// Image stored in a repository, all its tags pointing to the same manifest
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistryImage {
+    /// Identifier of the image for the registry API, i.e: its digest
+    pub id: String,
+    pub tags: Vec<String>,
+    pub pushed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DockerImage {
     pub repository_id: String,
@@ -70,6 +85,41 @@ pub trait ContainerRegistry: Send + Sync {
     // Check on the registry if a specific image already exists
     fn image_exists(&self, image: &Image) -> bool;
 
+    // Set the retention rules of the repository of the image with the registry lifecycle policies
+    // Returns false when the registry has none, expired images are then deleted by the engine
+    fn set_image_retention_policy(
+        &self,
+        _image: &Image,
+        _policy: &ImageRetentionPolicy,
+    ) -> Result<bool, ContainerRegistryError> {
+        Ok(false)
+    }
+
+    // List the images of the repository of the image, registries not able to list them return none
+    fn list_images(&self, _image: &Image) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        Ok(vec![])
+    }
+
+    // Delete an image of the repository of the image, with all its tags
+    fn delete_registry_image(
+        &self,
+        _image: &Image,
+        _registry_image: &RegistryImage,
+    ) -> Result<(), ContainerRegistryError> {
+        Ok(())
+    }
+
+    // Replicate every image pushed to the registry to other regions with the registry replication rules
+    // Returns false when the registry has none
+    fn set_replication_regions(&self, _regions: &[String]) -> Result<bool, ContainerRegistryError> {
+        Ok(false)
+    }
+
+    // Whether the image is available in the registry of a replication region, none when the registry cannot tell
+    fn is_image_replicated(&self, _image: &Image, _region: &str) -> Result<Option<bool>, ContainerRegistryError> {
+        Ok(None)
+    }
+
     fn get_event_details(&self, stage: Stage) -> EventDetails {
         let context = self.context();
         let ev = EventDetails::new(
@@ -120,6 +170,8 @@ pub enum Kind {
     ScalewayCr,
     GcpArtifactRegistry,
     GenericCr,
+    Ghcr,
+    Harbor,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -0,0 +1,86 @@
+use crate::build_platform::Image;
+use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::ContainerRegistry;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageReplicationStatus {
+    Replicated,
+    InProgress,
+    Unknown,
+}
+
+/// State of the copy of an image in the registry of another region
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageReplication {
+    pub region: String,
+    pub status: ImageReplicationStatus,
+}
+
+impl Display for ImageReplication {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.status {
+            ImageReplicationStatus::Replicated => write!(f, "replicated to {}", self.region),
+            ImageReplicationStatus::InProgress => write!(f, "replication to {} in progress", self.region),
+            ImageReplicationStatus::Unknown => write!(f, "replication to {} requested", self.region),
+        }
+    }
+}
+
+/// Regions to replicate to, without duplicates nor the region of the registry itself
+pub fn replication_regions(regions: &[String], registry_region: &str) -> Vec<String> {
+    let mut replication_regions: Vec<String> = vec![];
+    for region in regions.iter().map(|region| region.trim().to_lowercase()) {
+        if !region.is_empty() && region != registry_region && !replication_regions.contains(&region) {
+            replication_regions.push(region);
+        }
+    }
+
+    replication_regions
+}
+
+/// Replicates the images of the registry to other regions, so clusters running there pull them without rebuilding
+/// them, and tells where the image is already available. Returns none when the registry cannot replicate its images.
+pub fn replicate_image(
+    container_registry: &dyn ContainerRegistry,
+    image: &Image,
+    regions: &[String],
+) -> Result<Option<Vec<ImageReplication>>, ContainerRegistryError> {
+    if regions.is_empty() || !container_registry.set_replication_regions(regions)? {
+        return Ok(None);
+    }
+
+    let mut replications = Vec::with_capacity(regions.len());
+    for region in regions {
+        let status = match container_registry.is_image_replicated(image, region)? {
+            Some(true) => ImageReplicationStatus::Replicated,
+            Some(false) => ImageReplicationStatus::InProgress,
+            None => ImageReplicationStatus::Unknown,
+        };
+        replications.push(ImageReplication {
+            region: region.clone(),
+            status,
+        });
+    }
+
+    Ok(Some(replications))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replication_regions() {
+        let regions = vec![
+            "us-east-1".to_string(),
+            " EU-WEST-1".to_string(),
+            "eu-west-3".to_string(),
+            "us-east-1".to_string(),
+            "".to_string(),
+        ];
+
+        assert_eq!(replication_regions(&regions, "eu-west-3"), vec!["us-east-1", "eu-west-1"]);
+        assert!(replication_regions(&[], "eu-west-3").is_empty());
+    }
+}
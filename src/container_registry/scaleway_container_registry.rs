@@ -4,12 +4,15 @@ use self::scaleway_api_rs::models::scaleway_registry_v1_namespace::Status;
 use crate::build_platform::Image;
 use crate::cmd::docker;
 use crate::container_registry::errors::{ContainerRegistryError, RepositoryNamingRule};
-use crate::container_registry::{ContainerRegistry, ContainerRegistryInfo, Kind, Repository, RepositoryInfo};
+use crate::container_registry::{
+    ContainerRegistry, ContainerRegistryInfo, Kind, RegistryImage, Repository, RepositoryInfo,
+};
 use crate::io_models::context::Context;
 use crate::models::scaleway::ScwZone;
 use crate::runtime::block_on_with_timeout;
 use base64::engine::general_purpose;
 use base64::Engine;
+use chrono::{DateTime, Utc};
 use retry::delay::Fixed;
 use retry::OperationResult;
 use std::collections::HashSet;
@@ -391,6 +394,79 @@ impl ContainerRegistry for ScalewayCR {
 
         image_exists.is_ok()
     }
+
+    fn list_images(&self, image: &Image) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        // https://developers.scaleway.com/en/products/registry/api/#get-a6f1bc
+        let scaleway_images = match block_on_with_timeout(scaleway_api_rs::apis::images_api::list_images(
+            &self.get_configuration(),
+            self.zone.region().to_string().as_str(),
+            None,
+            None,
+            None,
+            None,
+            Some(image.name().as_str()),
+            None,
+            Some(self.default_project_id.as_str()),
+        )) {
+            Ok(Ok(res)) => res.images.unwrap_or_default(),
+            Ok(Err(e)) => {
+                return Err(ContainerRegistryError::CannotGetRepository {
+                    registry_name: self.name.to_string(),
+                    repository_name: image.repository_name().to_string(),
+                    raw_error_message: e.to_string(),
+                })
+            }
+            Err(e) => {
+                return Err(ContainerRegistryError::CannotGetRepository {
+                    registry_name: self.name.to_string(),
+                    repository_name: image.repository_name().to_string(),
+                    raw_error_message: e.to_string(),
+                })
+            }
+        };
+
+        Ok(scaleway_images
+            .into_iter()
+            .filter_map(|scaleway_image| {
+                Some(RegistryImage {
+                    id: scaleway_image.id?,
+                    tags: scaleway_image.tags.unwrap_or_default(),
+                    // images are updated when a tag is pushed
+                    pushed_at: scaleway_image
+                        .updated_at
+                        .and_then(|updated_at| DateTime::parse_from_rfc3339(&updated_at).ok())
+                        .map(|updated_at| updated_at.with_timezone(&Utc)),
+                })
+            })
+            .collect())
+    }
+
+    fn delete_registry_image(
+        &self,
+        image: &Image,
+        registry_image: &RegistryImage,
+    ) -> Result<(), ContainerRegistryError> {
+        // https://developers.scaleway.com/en/products/registry/api/#delete-67dbf7
+        match block_on_with_timeout(scaleway_api_rs::apis::images_api::delete_image(
+            &self.get_configuration(),
+            self.zone.region().to_string().as_str(),
+            registry_image.id.as_str(),
+        )) {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(ContainerRegistryError::CannotDeleteImage {
+                registry_name: self.name.to_string(),
+                repository_name: image.repository_name().to_string(),
+                image_name: image.name(),
+                raw_error_message: e.to_string(),
+            }),
+            Err(e) => Err(ContainerRegistryError::CannotDeleteImage {
+                registry_name: self.name.to_string(),
+                repository_name: image.repository_name().to_string(),
+                image_name: image.name(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
 }
 
 #[cfg(test)]
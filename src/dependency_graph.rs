@@ -0,0 +1,89 @@
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+/// Services left without a deployment level, because they depend on each other directly or through other services
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DependencyCycle {
+    pub services: Vec<Uuid>,
+}
+
+/// Group the services in levels following their dependencies: every service of a level only depends on services of
+/// the previous ones, so the services of a level can be deployed in parallel once the previous level is deployed.
+/// Services keep their given order within a level. Dependencies on services not in `services` are ignored, as they
+/// are not part of this deployment.
+pub fn dependency_levels(
+    services: impl IntoIterator<Item = Uuid>,
+    dependencies: &BTreeMap<Uuid, Vec<Uuid>>,
+) -> Result<Vec<Vec<Uuid>>, DependencyCycle> {
+    let mut remaining: Vec<Uuid> = services.into_iter().collect();
+    let all: HashSet<Uuid> = remaining.iter().copied().collect();
+    let mut deployed: HashSet<Uuid> = HashSet::with_capacity(remaining.len());
+    let mut levels: Vec<Vec<Uuid>> = vec![];
+
+    while !remaining.is_empty() {
+        let (level, blocked): (Vec<Uuid>, Vec<Uuid>) = remaining.into_iter().partition(|service| {
+            dependencies
+                .get(service)
+                .into_iter()
+                .flatten()
+                .all(|dependency| !all.contains(dependency) || deployed.contains(dependency))
+        });
+        if level.is_empty() {
+            return Err(DependencyCycle { services: blocked });
+        }
+
+        deployed.extend(level.iter().copied());
+        levels.push(level);
+        remaining = blocked;
+    }
+
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_levels() {
+        let (database, migration, app, worker, front) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let services = [front, worker, app, migration, database];
+
+        // without dependencies, everything is deployed at once
+        assert_eq!(
+            dependency_levels(services, &BTreeMap::new()),
+            Ok(vec![vec![front, worker, app, migration, database]])
+        );
+
+        let dependencies = BTreeMap::from([
+            (migration, vec![database]),
+            (app, vec![database]),
+            (worker, vec![migration, app]),
+            // not part of the deployment
+            (front, vec![Uuid::new_v4()]),
+        ]);
+        assert_eq!(
+            dependency_levels(services, &dependencies),
+            Ok(vec![vec![front, database], vec![app, migration], vec![worker]])
+        );
+        assert_eq!(dependency_levels([worker], &dependencies), Ok(vec![vec![worker]]));
+    }
+
+    #[test]
+    fn test_dependency_levels_cycle() {
+        let (database, app, worker) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let dependencies = BTreeMap::from([(app, vec![worker]), (worker, vec![app])]);
+
+        assert_eq!(
+            dependency_levels([database, app, worker], &dependencies),
+            Err(DependencyCycle {
+                services: vec![app, worker]
+            })
+        );
+        assert_eq!(
+            dependency_levels([app], &BTreeMap::from([(app, vec![app])])),
+            Err(DependencyCycle { services: vec![app] })
+        );
+    }
+}
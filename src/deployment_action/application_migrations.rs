@@ -0,0 +1,295 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::deploy_job::{await_job_termination, JobStatus};
+use crate::deployment_action::service_secrets::{docker_registry_secret, env_vars_secret};
+use crate::deployment_action::utils::forward_job_logs;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::io_models::application::{ApplicationMigrations, MigrationLockStrategy};
+use crate::kubers_utils::{kube_create_from_resource, kube_get_resources_by_selector};
+use crate::models::application::ApplicationService;
+use crate::naming;
+use crate::runtime::block_on;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::batch::v1::{Job as K8sJob, JobSpec};
+use k8s_openapi::api::core::v1::{
+    Container, EnvFromSource, LocalObjectReference, PodSpec, PodTemplateSpec, Secret, SecretEnvSource,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::DeleteParams;
+use kube::Api;
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MIGRATIONS_LABEL: &str = "qovery.com/migrations";
+const MIGRATIONS_CONTAINER_NAME: &str = "migrations";
+// Jobs are deleted once their logs are forwarded, this is only for runs interrupted in the middle
+const MIGRATIONS_JOB_TTL_IN_SECONDS: i32 = 24 * 60 * 60;
+const MIGRATIONS_LOCK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Job name is reused as a label value by kubernetes, so it must fit in 63 characters
+fn migrations_job_name(kube_name: &str, now: DateTime<Utc>) -> String {
+    let prefix: String = kube_name.chars().take(40).collect();
+    format!("{}-migrations-{}", prefix.trim_end_matches('-'), now.timestamp())
+}
+
+fn migrations_labels(service_long_id: &str) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("qovery.com/service-id".to_string(), service_long_id.to_string()),
+        (MIGRATIONS_LABEL.to_string(), "true".to_string()),
+    ])
+}
+
+fn migrations_job(
+    name: &str,
+    labels: &BTreeMap<String, String>,
+    image: &str,
+    migrations: &ApplicationMigrations,
+    registry_secret_name: Option<&str>,
+    service_account_name: Option<&str>,
+) -> K8sJob {
+    let metadata = ObjectMeta {
+        name: Some(name.to_string()),
+        labels: Some(labels.clone()),
+        ..Default::default()
+    };
+
+    K8sJob {
+        metadata,
+        spec: Some(JobSpec {
+            // a failed migration is not retried, it blocks the rollout instead
+            backoff_limit: Some(0),
+            active_deadline_seconds: Some(i64::from(migrations.timeout_in_seconds)),
+            ttl_seconds_after_finished: Some(MIGRATIONS_JOB_TTL_IN_SECONDS),
+            template: PodTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(labels.clone()),
+                    ..Default::default()
+                }),
+                spec: Some(PodSpec {
+                    restart_policy: Some("Never".to_string()),
+                    service_account_name: service_account_name.map(str::to_string),
+                    image_pull_secrets: registry_secret_name.map(|secret_name| {
+                        vec![LocalObjectReference {
+                            name: Some(secret_name.to_string()),
+                        }]
+                    }),
+                    containers: vec![Container {
+                        name: MIGRATIONS_CONTAINER_NAME.to_string(),
+                        image: Some(image.to_string()),
+                        command: Some(migrations.command.clone()),
+                        env_from: Some(vec![EnvFromSource {
+                            secret_ref: Some(SecretEnvSource {
+                                name: Some(name.to_string()),
+                                optional: Some(false),
+                            }),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        status: None,
+    }
+}
+
+fn is_job_active(job: &K8sJob) -> bool {
+    job.status
+        .as_ref()
+        .and_then(|status| status.active)
+        .map(|active| active > 0)
+        .unwrap_or(false)
+}
+
+/// Wait for the migrations of the application started by a previous deployment to be over
+fn await_migrations_lock(
+    target: &DeploymentTarget,
+    selector: &str,
+    timeout: Duration,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>> {
+    let started_at = Instant::now();
+    loop {
+        let jobs: Vec<K8sJob> = block_on(kube_get_resources_by_selector(
+            &target.kube,
+            target.environment.namespace(),
+            selector,
+        ))
+        .map_err(|err| {
+            EngineError::new_job_error(event_details.clone(), format!("Cannot list running migrations: {err}"))
+        })?
+        .items;
+        let Some(running_job) = jobs.iter().find(|job| is_job_active(job)) else {
+            return Ok(());
+        };
+
+        if started_at.elapsed() > timeout {
+            return Err(Box::new(EngineError::new_job_error(
+                event_details.clone(),
+                format!(
+                    "Migrations {} are still running, cannot start new ones",
+                    running_job.metadata.name.as_deref().unwrap_or_default()
+                ),
+            )));
+        }
+        logger.info(format!(
+            "⏳ Waiting for migrations {} to finish before starting new ones",
+            running_job.metadata.name.as_deref().unwrap_or_default()
+        ));
+        thread::sleep(MIGRATIONS_LOCK_POLL_INTERVAL);
+    }
+}
+
+/// Run the migrations of the application with a kubernetes job, before its new version is rolled out.
+/// The output of the job is forwarded to the deployment logs, and a failure aborts the deployment.
+pub(super) fn run_application_migrations(
+    app: &dyn ApplicationService,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let Some(migrations) = app.migrations() else {
+        return Ok(());
+    };
+    if target.is_dry_run_deploy {
+        return Ok(());
+    }
+
+    let namespace = target.environment.namespace();
+    let timeout = Duration::from_secs(u64::from(migrations.timeout_in_seconds));
+    let labels = migrations_labels(&app.long_id().to_string());
+    let selector = format!("qovery.com/service-id={},{}=true", app.long_id(), MIGRATIONS_LABEL);
+    if migrations.lock_strategy == MigrationLockStrategy::Exclusive {
+        await_migrations_lock(target, &selector, timeout, logger, event_details)?;
+    }
+
+    let to_engine_error = |msg: String| Box::new(EngineError::new_job_error(event_details.clone(), msg));
+    let job_name = migrations_job_name(app.kube_name(), Utc::now());
+    let environment_variables: Vec<(String, String)> = app
+        .get_environment_variables()
+        .into_iter()
+        .map(|env| (env.key, env.value))
+        .collect();
+    let env_secret = env_vars_secret(&job_name, &labels, &environment_variables).map_err(to_engine_error)?;
+    let registry_secret = match &target.container_registry.registry_info().registry_docker_json_config {
+        Some(docker_json_config) => Some(
+            docker_registry_secret(&naming::secret_name(&job_name, "registry"), &labels, docker_json_config)
+                .map_err(to_engine_error)?,
+        ),
+        None => None,
+    };
+    let image = migrations
+        .image
+        .clone()
+        .unwrap_or_else(|| app.get_build().image.full_image_name_with_tag());
+    let service_account_name = &app.advanced_settings().security_service_account_name;
+    let job = migrations_job(
+        &job_name,
+        &labels,
+        &image,
+        migrations,
+        registry_secret
+            .as_ref()
+            .and_then(|secret| secret.metadata.name.as_deref()),
+        (!service_account_name.is_empty()).then_some(service_account_name.as_str()),
+    );
+    let mut secret_names = vec![job_name.clone()];
+    secret_names.extend(registry_secret.as_ref().and_then(|secret| secret.metadata.name.clone()));
+
+    logger.info(format!("🛠️ Running migrations of {} with image {}", app.name(), image));
+    let created = block_on(kube_create_from_resource(&target.kube, namespace, env_secret))
+        .and_then(|_| match registry_secret {
+            Some(secret) => block_on(kube_create_from_resource(&target.kube, namespace, secret)),
+            None => Ok(()),
+        })
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, job)));
+
+    let job_status = match created {
+        Ok(_) => {
+            let job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), namespace);
+            let job_status =
+                await_job_termination(job_api, &job_name, timeout + Duration::from_secs(60), event_details);
+            if let Err(err) = forward_job_logs(target, &job_name, logger) {
+                logger.warning(format!("Cannot retrieve logs of migrations: {err}"));
+            }
+            job_status
+        }
+        Err(err) => Err(to_engine_error(format!("Cannot create migrations job: {err}"))),
+    };
+
+    // env variables may hold secrets, do not leave them around
+    let job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), namespace);
+    let secret_api: Api<Secret> = Api::namespaced(target.kube.clone(), namespace);
+    let _ = block_on(job_api.delete(&job_name, &DeleteParams::background()));
+    for secret_name in &secret_names {
+        if let Err(err) = block_on(secret_api.delete(secret_name, &DeleteParams::default())) {
+            logger.warning(format!("Cannot delete migrations secret {secret_name}: {err}"));
+        }
+    }
+
+    match job_status? {
+        JobStatus::Success => {
+            logger.info("✅ Migrations ran successfully".to_string());
+            Ok(())
+        }
+        JobStatus::Failure { reason, message } => Err(to_engine_error(format!(
+            "Migrations failed, new version is not rolled out: {reason} {message}"
+        ))),
+        JobStatus::Running | JobStatus::NotRunning => Err(to_engine_error(format!(
+            "Migrations did not finish within {}s, new version is not rolled out",
+            migrations.timeout_in_seconds
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn migrations() -> ApplicationMigrations {
+        ApplicationMigrations {
+            command: vec!["npm".to_string(), "run".to_string(), "migrate".to_string()],
+            image: None,
+            timeout_in_seconds: 300,
+            lock_strategy: MigrationLockStrategy::Exclusive,
+        }
+    }
+
+    #[test]
+    fn test_migrations_job_name() {
+        let now = Utc.with_ymd_and_hms(2023, 11, 2, 10, 0, 0).unwrap();
+        assert_eq!(migrations_job_name("app-z1234", now), "app-z1234-migrations-1698919200");
+        assert!(migrations_job_name(&"a".repeat(80), now).len() <= 63);
+    }
+
+    #[test]
+    fn test_migrations_job() {
+        let labels = migrations_labels("service");
+        let job = migrations_job(
+            "app-migrations",
+            &labels,
+            "registry/app:1234",
+            &migrations(),
+            Some("app-migrations-registry"),
+            None,
+        );
+
+        let spec = job.spec.unwrap();
+        assert_eq!(spec.backoff_limit, Some(0));
+        assert_eq!(spec.active_deadline_seconds, Some(300));
+        let pod_spec = spec.template.spec.unwrap();
+        assert_eq!(
+            pod_spec.image_pull_secrets.unwrap()[0].name.as_deref(),
+            Some("app-migrations-registry")
+        );
+        assert_eq!(pod_spec.containers[0].image.as_deref(), Some("registry/app:1234"));
+        assert_eq!(pod_spec.containers[0].command, Some(migrations().command));
+        assert_eq!(spec.template.metadata.unwrap().labels, Some(labels));
+    }
+}
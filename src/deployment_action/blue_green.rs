@@ -0,0 +1,509 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::canary::is_pod_ready;
+use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::new_version::render_new_version;
+use crate::deployment_action::smoke_test::run_smoke_test;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::kubers_utils::{
+    kube_create_from_resource, kube_delete_all_from_selector, kube_get_resources_by_selector, KubeDeleteMode,
+};
+use crate::models::application::ApplicationService;
+use crate::runtime::block_on;
+use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet};
+use k8s_openapi::api::core::v1::{Pod, Secret, Service};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+use kube::api::{Patch, PatchParams};
+use kube::Api;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BLUE_GREEN_OF_LABEL: &str = "qovery.com/blue-green-of";
+const SERVICE_ID_LABEL: &str = "qovery.com/service-id";
+// Set by kubernetes on the pods of each replicaset of a deployment, services are pinned to a version with it
+const POD_TEMPLATE_HASH_LABEL: &str = "pod-template-hash";
+const REVISION_ANNOTATION: &str = "deployment.kubernetes.io/revision";
+const GREEN_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Name of the deployment of the new version, kept within the 63 characters kubernetes accepts for labels
+fn green_name(name: &str) -> String {
+    let prefix: String = name.chars().take(57).collect();
+    format!("{}-green", prefix.trim_end_matches('-'))
+}
+
+/// New version rendered by the chart, with as many instances as the stable deployment runs.
+/// Its pods keep the labels of the service, they only receive traffic once the services are pinned to them.
+fn green_deployment(rendered: &Deployment, service_long_id: &str, replicas: i32) -> Option<Deployment> {
+    let name = green_name(rendered.metadata.name.as_deref()?);
+    let green_labels = BTreeMap::from([(BLUE_GREEN_OF_LABEL.to_string(), service_long_id.to_string())]);
+    let mut spec = rendered.spec.clone()?;
+    spec.replicas = Some(replicas.max(1));
+    spec.selector = LabelSelector {
+        match_labels: Some(green_labels.clone()),
+        match_expressions: None,
+    };
+
+    let pod_metadata = spec.template.metadata.get_or_insert_with(Default::default);
+    pod_metadata
+        .labels
+        .get_or_insert_with(Default::default)
+        .extend(green_labels.clone());
+
+    Some(Deployment {
+        metadata: ObjectMeta {
+            name: Some(name),
+            // not labelled as the service, it must not be taken for its stable deployment
+            labels: Some(green_labels),
+            ..Default::default()
+        },
+        spec: Some(spec),
+        status: None,
+    })
+}
+
+/// Pod template hash of the replicaset of the current revision of a deployment, if it has ready pods
+fn current_pod_template_hash(deployment: &Deployment, replicasets: &[ReplicaSet]) -> Option<String> {
+    let uid = deployment.metadata.uid.as_deref()?;
+    let revision = deployment.metadata.annotations.as_ref()?.get(REVISION_ANNOTATION)?;
+
+    replicasets
+        .iter()
+        .filter(|replicaset| {
+            replicaset
+                .metadata
+                .owner_references
+                .iter()
+                .flatten()
+                .any(|owner| owner.uid == uid)
+        })
+        .find(|replicaset| {
+            replicaset
+                .metadata
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(REVISION_ANNOTATION))
+                == Some(revision)
+        })
+        .filter(|replicaset| {
+            replicaset
+                .status
+                .as_ref()
+                .and_then(|status| status.ready_replicas)
+                .unwrap_or(0)
+                > 0
+        })
+        .and_then(|replicaset| {
+            replicaset
+                .metadata
+                .labels
+                .as_ref()?
+                .get(POD_TEMPLATE_HASH_LABEL)
+                .cloned()
+        })
+}
+
+/// Pins the services to the pods of a single replicaset, or selects all the pods of the application again.
+/// Changing the selector of a service is atomic, its endpoints switch from one version to the other at once.
+/// Helm keeps the pin when it upgrades the release, as the selector it renders does not know about it.
+fn pin_services(
+    target: &DeploymentTarget,
+    service_names: &[String],
+    pod_template_hash: Option<&str>,
+) -> Result<(), String> {
+    let service_api: Api<Service> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let patch = json!({ "spec": { "selector": { POD_TEMPLATE_HASH_LABEL: pod_template_hash } } });
+    for service_name in service_names {
+        block_on(service_api.patch(service_name, &PatchParams::default(), &Patch::Merge(&patch)))
+            .map_err(|err| format!("cannot update the selector of service {service_name}: {err}"))?;
+    }
+
+    Ok(())
+}
+
+fn delete_green(target: &DeploymentTarget, selector: &str, logger: &EnvProgressLogger) {
+    let namespace = target.environment.namespace();
+    if let Err(err) = block_on(kube_delete_all_from_selector::<Deployment>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    )) {
+        logger.warning(format!("Cannot delete the deployment of the new version: {err}"));
+    }
+    // env variables may hold secrets, do not leave them around
+    if let Err(err) = block_on(kube_delete_all_from_selector::<Secret>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    )) {
+        logger.warning(format!("Cannot delete the secret of the new version: {err}"));
+    }
+}
+
+/// Wait for all the pods of the new version to be ready, returning them
+fn await_green_pods(
+    app: &dyn ApplicationService,
+    target: &DeploymentTarget,
+    selector: &str,
+    replicas: usize,
+) -> Result<Vec<Pod>, String> {
+    let started_at = Instant::now();
+    loop {
+        let pods: Vec<Pod> = block_on(kube_get_resources_by_selector(
+            &target.kube,
+            target.environment.namespace(),
+            selector,
+        ))
+        .map_err(|err| err.to_string())?
+        .items;
+
+        let restarts: i32 = pods
+            .iter()
+            .flat_map(|pod| {
+                pod.status
+                    .iter()
+                    .flat_map(|status| status.container_statuses.iter().flatten())
+            })
+            .map(|status| status.restart_count)
+            .sum();
+        // a pod crashing on start does not get a chance to be ready
+        if restarts > 0 {
+            return Err(format!("its pods restarted {restarts} time(s) before being ready"));
+        }
+        if pods.iter().filter(|pod| is_pod_ready(pod)).count() >= replicas {
+            return Ok(pods);
+        }
+        if started_at.elapsed() > app.startup_timeout() {
+            return Err(format!("it is not ready after {}s", app.startup_timeout().as_secs()));
+        }
+        thread::sleep(GREEN_CHECK_INTERVAL);
+    }
+}
+
+/// Services of an application switched to the new version during a blue/green deployment
+pub(super) struct BlueGreenSwitch {
+    service_names: Vec<String>,
+    green_selector: String,
+}
+
+impl BlueGreenSwitch {
+    /// Once the stable deployment is rolled out, its services select all of its pods again and the copy is removed.
+    /// The copy is kept if the services cannot be unpinned, they would be left without any pod.
+    pub(super) fn finish(
+        self,
+        target: &DeploymentTarget,
+        logger: &EnvProgressLogger,
+        event_details: &EventDetails,
+    ) -> Result<(), Box<EngineError>> {
+        pin_services(target, &self.service_names, None).map_err(|err| {
+            Box::new(EngineError::new_blue_green_deployment_failed(
+                event_details.clone(),
+                "cannot switch the services to the rolled out version".to_string(),
+                Some(CommandError::new_from_safe_message(err)),
+            ))
+        })?;
+        delete_green(target, &self.green_selector, logger);
+        logger.info("✅ New version is rolled out, its copy is removed".to_string());
+        Ok(())
+    }
+
+    /// Services select the pods of the stable deployment again, which is back to the previous version
+    /// when its roll out failed, and the copy running the new version is removed
+    pub(super) fn rollback(&self, target: &DeploymentTarget, logger: &EnvProgressLogger) {
+        if let Err(err) = pin_services(target, &self.service_names, None) {
+            logger.warning(format!("Cannot switch the traffic back to the previous version: {err}"));
+        }
+        delete_green(target, &self.green_selector, logger);
+    }
+}
+
+/// Deploy the new version of the application next to the previous one, with as many instances. Services are pinned
+/// to the pods of the previous version until all the new ones are ready and pass the smoke test, if any, then they
+/// are switched to the new version at once while the stable deployment is rolled out. Traffic goes back to the
+/// previous version if the new one fails before the switch, the deployment failing.
+/// Only a new version of an application with ports, running as a deployment, can be deployed blue/green.
+pub(super) fn run_blue_green_deployment(
+    app: &dyn ApplicationService,
+    helm: &HelmDeployment,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<Option<BlueGreenSwitch>, Box<EngineError>> {
+    if target.is_dry_run_deploy {
+        return Ok(None);
+    }
+    let namespace = target.environment.namespace();
+    let service_long_id = app.long_id().to_string();
+    let to_engine_error = |reason: &str, err: CommandError| {
+        Box::new(EngineError::new_blue_green_deployment_failed(
+            event_details.clone(),
+            reason.to_string(),
+            Some(err),
+        ))
+    };
+
+    let stable_selector = format!("{SERVICE_ID_LABEL}={service_long_id}");
+    let Some(blue_deployment) = block_on(kube_get_resources_by_selector::<Deployment>(
+        &target.kube,
+        namespace,
+        &stable_selector,
+    ))
+    .map_err(|err| to_engine_error("cannot get the stable deployment", err))?
+    .items
+    .into_iter()
+    .next() else {
+        logger.info("🔵 No version is running yet, the first one is deployed without blue/green".to_string());
+        return Ok(None);
+    };
+    let services = block_on(kube_get_resources_by_selector::<Service>(
+        &target.kube,
+        namespace,
+        &stable_selector,
+    ))
+    .map_err(|err| to_engine_error("cannot get the services", err))?
+    .items;
+    if !services
+        .iter()
+        .filter(|service| {
+            service
+                .spec
+                .as_ref()
+                .and_then(|spec| spec.type_.as_deref())
+                .unwrap_or("ClusterIP")
+                == "ClusterIP"
+        })
+        .any(|service| {
+            service
+                .spec
+                .iter()
+                .any(|spec| spec.ports.as_ref().is_some_and(|ports| !ports.is_empty()))
+        })
+    {
+        logger.info("🔵 Application has no port, it is deployed without blue/green".to_string());
+        return Ok(None);
+    }
+    let switch = BlueGreenSwitch {
+        service_names: services
+            .iter()
+            .filter_map(|service| service.metadata.name.clone())
+            .collect(),
+        green_selector: format!("{BLUE_GREEN_OF_LABEL}={service_long_id}"),
+    };
+
+    // a copy left by an interrupted deployment is replaced, traffic going back to the stable deployment first
+    if let Err(err) = pin_services(target, &switch.service_names, None) {
+        return Err(to_engine_error(
+            "cannot reset the selector of the services",
+            CommandError::new_from_safe_message(err),
+        ));
+    }
+    delete_green(target, &switch.green_selector, logger);
+
+    let replicasets = block_on(kube_get_resources_by_selector::<ReplicaSet>(
+        &target.kube,
+        namespace,
+        &stable_selector,
+    ))
+    .map_err(|err| to_engine_error("cannot get the replicasets of the stable deployment", err))?
+    .items;
+    let Some(blue_hash) = current_pod_template_hash(&blue_deployment, &replicasets) else {
+        logger.info("🔵 Previous version has no ready pod, the new one is deployed without blue/green".to_string());
+        return Ok(None);
+    };
+
+    let labels = BTreeMap::from([(BLUE_GREEN_OF_LABEL.to_string(), service_long_id.clone())]);
+    let Some(new_version) = render_new_version(helm, target, "green", &labels, event_details)? else {
+        logger.info("🔵 New version does not run as a deployment, it is deployed without blue/green".to_string());
+        return Ok(None);
+    };
+    let image = app.get_build().image.full_image_name_with_tag();
+    let blue_replicas = blue_deployment
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.replicas)
+        .unwrap_or(1);
+    let green_deployment =
+        green_deployment(&new_version.deployment, &service_long_id, blue_replicas).ok_or_else(|| {
+            to_engine_error(
+                "cannot create it from the rendered chart",
+                CommandError::new_from_safe_message("Rendered deployment has no spec".to_string()),
+            )
+        })?;
+    let replicas = green_deployment
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.replicas)
+        .unwrap_or(1) as usize;
+
+    logger.info(format!(
+        "🔵 Deploying {} instance(s) of the new version of {} with image {}, next to the previous one",
+        replicas,
+        app.name(),
+        image
+    ));
+    let rollback = |reason: String, err: Option<CommandError>| {
+        switch.rollback(target, logger);
+        Box::new(EngineError::new_blue_green_deployment_failed(
+            event_details.clone(),
+            reason,
+            err,
+        ))
+    };
+    pin_services(target, &switch.service_names, Some(&blue_hash)).map_err(|err| rollback(err, None))?;
+    new_version
+        .secrets
+        .into_iter()
+        .try_for_each(|secret| block_on(kube_create_from_resource(&target.kube, namespace, secret)))
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, green_deployment)))
+        .map_err(|err| rollback("cannot create the new version".to_string(), Some(err)))?;
+
+    let green_pods =
+        await_green_pods(app, target, &switch.green_selector, replicas).map_err(|err| rollback(err, None))?;
+    if app.smoke_test().is_some() {
+        // services still select the previous version, the smoke test targets a pod of the new one
+        let Some(pod_ip) = green_pods
+            .iter()
+            .filter(|pod| is_pod_ready(pod))
+            .find_map(|pod| pod.status.as_ref()?.pod_ip.clone())
+        else {
+            return Err(rollback("its pods have no IP to run the smoke test against".to_string(), None));
+        };
+        logger.info(format!("🔵 New version is ready, running its smoke test against pod {pod_ip}"));
+        run_smoke_test(
+            app,
+            &pod_ip,
+            |logger| switch.rollback(target, logger),
+            logger,
+            event_details,
+            target,
+        )?;
+    }
+
+    let Some(green_hash) = green_pods
+        .iter()
+        .find_map(|pod| pod.metadata.labels.as_ref()?.get(POD_TEMPLATE_HASH_LABEL).cloned())
+    else {
+        return Err(rollback("its pods have no pod template hash".to_string(), None));
+    };
+    pin_services(target, &switch.service_names, Some(&green_hash)).map_err(|err| rollback(err, None))?;
+    logger.info("🟢 Traffic is switched to the new version, rolling out the stable deployment".to_string());
+
+    Ok(Some(switch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::{DeploymentSpec, ReplicaSetStatus};
+    use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+
+    fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+        labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_green_name() {
+        assert_eq!(green_name("app-z1234"), "app-z1234-green");
+        assert_eq!(green_name(&"a".repeat(70)).len(), 63);
+    }
+
+    #[test]
+    fn test_green_deployment() {
+        let rendered = Deployment {
+            metadata: ObjectMeta {
+                name: Some("app-z1234".to_string()),
+                labels: Some(labels(&[(SERVICE_ID_LABEL, "long-id"), ("envId", "z42")])),
+                ..Default::default()
+            },
+            spec: Some(DeploymentSpec {
+                replicas: None,
+                template: PodTemplateSpec {
+                    metadata: Some(ObjectMeta {
+                        labels: Some(labels(&[(SERVICE_ID_LABEL, "long-id"), ("appId", "z1234")])),
+                        ..Default::default()
+                    }),
+                    spec: Some(PodSpec {
+                        containers: vec![Container {
+                            name: "app-z1234".to_string(),
+                            image: Some("registry/app:v2".to_string()),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }),
+                },
+                ..Default::default()
+            }),
+            status: None,
+        };
+
+        let green = green_deployment(&rendered, "long-id", 3).unwrap();
+        assert_eq!(green.metadata.name.as_deref(), Some("app-z1234-green"));
+        assert_eq!(green.metadata.labels, Some(labels(&[(BLUE_GREEN_OF_LABEL, "long-id")])));
+        let spec = green.spec.unwrap();
+        assert_eq!(spec.replicas, Some(3));
+        assert_eq!(spec.selector.match_labels, Some(labels(&[(BLUE_GREEN_OF_LABEL, "long-id")])));
+        // pods of the new version can be selected by the services of the application
+        assert_eq!(
+            spec.template.metadata.unwrap().labels,
+            Some(labels(&[
+                (SERVICE_ID_LABEL, "long-id"),
+                ("appId", "z1234"),
+                (BLUE_GREEN_OF_LABEL, "long-id")
+            ]))
+        );
+        // containers are the ones rendered by the chart for the new version
+        assert_eq!(
+            spec.template.spec,
+            rendered.spec.as_ref().and_then(|spec| spec.template.spec.clone())
+        );
+    }
+
+    #[test]
+    fn test_current_pod_template_hash() {
+        let deployment = Deployment {
+            metadata: ObjectMeta {
+                uid: Some("deployment-uid".to_string()),
+                annotations: Some(labels(&[(REVISION_ANNOTATION, "2")])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let replicaset = |owner_uid: &str, revision: &str, hash: &str, ready_replicas: i32| ReplicaSet {
+            metadata: ObjectMeta {
+                labels: Some(labels(&[(POD_TEMPLATE_HASH_LABEL, hash)])),
+                annotations: Some(labels(&[(REVISION_ANNOTATION, revision)])),
+                owner_references: Some(vec![OwnerReference {
+                    uid: owner_uid.to_string(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            spec: None,
+            status: Some(ReplicaSetStatus {
+                ready_replicas: Some(ready_replicas),
+                ..Default::default()
+            }),
+        };
+
+        assert_eq!(
+            current_pod_template_hash(
+                &deployment,
+                &[
+                    replicaset("deployment-uid", "1", "old", 0),
+                    replicaset("green-uid", "2", "green", 3),
+                    replicaset("deployment-uid", "2", "current", 3),
+                ]
+            ),
+            Some("current".to_string())
+        );
+        assert_eq!(
+            current_pod_template_hash(&deployment, &[replicaset("deployment-uid", "2", "current", 0)]),
+            None
+        );
+    }
+}
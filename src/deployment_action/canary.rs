@@ -0,0 +1,735 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::new_version::render_new_version;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::kubers_utils::{
+    kube_create_from_resource, kube_delete_all_from_selector, kube_get_resources_by_selector, KubeDeleteMode,
+};
+use crate::models::application::ApplicationService;
+use crate::naming;
+use crate::runtime::block_on;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Pod, Secret, Service};
+use k8s_openapi::api::networking::v1::{HTTPIngressRuleValue, Ingress};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+use kube::api::DeleteParams;
+use kube::Api;
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+use url::Url;
+
+const CANARY_OF_LABEL: &str = "qovery.com/canary-of";
+const SERVICE_ID_LABEL: &str = "qovery.com/service-id";
+const ASSOCIATED_SERVICE_ID_LABEL: &str = "qovery.com/associated-service-id";
+// Labels selecting the pods of the stable version, legacy deployments still select them with the short ones
+const STABLE_POD_LABELS: [&str; 3] = [SERVICE_ID_LABEL, "appId", "app"];
+// Requests sent with this header always reach the canary, whatever its weight, so its error rate is known
+const CANARY_HEADER: &str = "X-Qovery-Canary";
+const CANARY_HEADER_VALUE: &str = "always";
+const CANARY_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const CANARY_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Name of a canary resource, kept within the 63 characters kubernetes accepts for services
+fn canary_name(name: &str) -> String {
+    naming::truncate_with_hash(&format!("{name}-canary"), naming::KUBE_NAME_MAX_LENGTH)
+}
+
+fn canary_labels(labels: Option<&BTreeMap<String, String>>, service_long_id: &str) -> BTreeMap<String, String> {
+    let mut labels: BTreeMap<String, String> = labels
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| !STABLE_POD_LABELS.contains(&key.as_str()) && key.as_str() != ASSOCIATED_SERVICE_ID_LABEL)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    labels.insert(CANARY_OF_LABEL.to_string(), service_long_id.to_string());
+    labels
+}
+
+/// Single instance of the new version rendered by the chart, running next to the stable deployment.
+/// Its pods are not labelled as the ones of the service, so they are only reached through the canary service.
+fn canary_deployment(rendered: &Deployment, service_long_id: &str) -> Option<Deployment> {
+    let name = canary_name(rendered.metadata.name.as_deref()?);
+    let mut spec = rendered.spec.clone()?;
+    spec.replicas = Some(1);
+    spec.selector = LabelSelector {
+        match_labels: Some(BTreeMap::from([(CANARY_OF_LABEL.to_string(), service_long_id.to_string())])),
+        match_expressions: None,
+    };
+
+    let pod_metadata = spec.template.metadata.get_or_insert_with(Default::default);
+    pod_metadata.labels = Some(canary_labels(pod_metadata.labels.as_ref(), service_long_id));
+
+    Some(Deployment {
+        metadata: ObjectMeta {
+            name: Some(name),
+            labels: Some(canary_labels(rendered.metadata.labels.as_ref(), service_long_id)),
+            ..Default::default()
+        },
+        spec: Some(spec),
+        status: None,
+    })
+}
+
+fn canary_service(stable: &Service, service_long_id: &str) -> Option<Service> {
+    let mut spec = stable.spec.clone()?;
+    spec.selector = Some(BTreeMap::from([(CANARY_OF_LABEL.to_string(), service_long_id.to_string())]));
+    spec.cluster_ip = None;
+    spec.cluster_ips = None;
+
+    Some(Service {
+        metadata: ObjectMeta {
+            name: Some(canary_name(stable.metadata.name.as_deref()?)),
+            labels: Some(canary_labels(stable.metadata.labels.as_ref(), service_long_id)),
+            ..Default::default()
+        },
+        spec: Some(spec),
+        status: None,
+    })
+}
+
+/// https://kubernetes.github.io/ingress-nginx/user-guide/nginx-configuration/annotations/#canary
+fn canary_ingress_annotations(weight_percent: u32) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("nginx.ingress.kubernetes.io/canary".to_string(), "true".to_string()),
+        (
+            "nginx.ingress.kubernetes.io/canary-weight".to_string(),
+            weight_percent.min(100).to_string(),
+        ),
+        (
+            "nginx.ingress.kubernetes.io/canary-by-header".to_string(),
+            CANARY_HEADER.to_string(),
+        ),
+    ])
+}
+
+/// Copy of an ingress of a router, only keeping its paths to the stable service, sent to the canary one instead.
+/// Certificates and DNS records are left to the stable ingress, the controller serves the canary on the same hosts.
+fn canary_ingress(
+    stable: &Ingress,
+    stable_service_name: &str,
+    canary_service_name: &str,
+    service_long_id: &str,
+    weight_percent: u32,
+) -> Option<Ingress> {
+    let mut spec = stable.spec.clone()?;
+    let mut rules = spec.rules.take().unwrap_or_default();
+    for rule in rules.iter_mut() {
+        let paths = rule
+            .http
+            .take()
+            .map(|http| http.paths)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|mut path| {
+                let backend_service = path.backend.service.as_mut()?;
+                if backend_service.name != stable_service_name {
+                    return None;
+                }
+                backend_service.name = canary_service_name.to_string();
+                Some(path)
+            })
+            .collect::<Vec<_>>();
+        rule.http = (!paths.is_empty()).then_some(HTTPIngressRuleValue { paths });
+    }
+    rules.retain(|rule| rule.http.is_some());
+    if rules.is_empty() {
+        return None;
+    }
+    spec.rules = Some(rules);
+
+    let mut annotations: BTreeMap<String, String> = stable
+        .metadata
+        .annotations
+        .iter()
+        .flatten()
+        .filter(|(key, _)| {
+            key.as_str() == "kubernetes.io/ingress.class" || key.starts_with("nginx.ingress.kubernetes.io/")
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    annotations.extend(canary_ingress_annotations(weight_percent));
+
+    Some(Ingress {
+        metadata: ObjectMeta {
+            name: Some(canary_name(stable.metadata.name.as_deref()?)),
+            labels: Some(canary_labels(stable.metadata.labels.as_ref(), service_long_id)),
+            annotations: Some(annotations),
+            ..Default::default()
+        },
+        spec: Some(spec),
+        status: None,
+    })
+}
+
+/// Urls of the routes served by a canary ingress, wildcard hosts being skipped as there is nothing to request
+fn canary_urls(ingress: &Ingress) -> Vec<Url> {
+    ingress
+        .spec
+        .iter()
+        .flat_map(|spec| spec.rules.iter().flatten())
+        .filter_map(|rule| Some((rule.host.as_deref()?, rule.http.as_ref()?)))
+        .filter(|(host, _)| !host.contains('*'))
+        .flat_map(|(host, http)| {
+            http.paths.iter().filter_map(move |path| {
+                let path = path.path.as_deref().unwrap_or("/");
+                Url::parse(&format!("https://{host}/{}", path.trim_start_matches('/'))).ok()
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct CanaryAnalysis {
+    requests: u32,
+    failed_requests: u32,
+    restarts: i32,
+    unready_pods: usize,
+}
+
+impl CanaryAnalysis {
+    fn error_rate_percent(&self) -> u32 {
+        match self.requests {
+            0 => 0,
+            requests => self.failed_requests.saturating_mul(100) / requests,
+        }
+    }
+
+    /// Why the canary must be rolled back, if it must
+    fn rollback_reason(&self, max_error_rate_percent: u32) -> Option<String> {
+        if self.restarts > 0 {
+            return Some(format!("its pods restarted {} time(s)", self.restarts));
+        }
+        if self.unready_pods > 0 {
+            return Some(format!("{} of its pods are not ready anymore", self.unready_pods));
+        }
+        if self.error_rate_percent() > max_error_rate_percent {
+            return Some(format!(
+                "{}% of its requests failed ({}/{}), above the {}% allowed",
+                self.error_rate_percent(),
+                self.failed_requests,
+                self.requests,
+                max_error_rate_percent
+            ));
+        }
+        None
+    }
+}
+
+pub(super) fn is_pod_ready(pod: &Pod) -> bool {
+    match pod
+        .status
+        .as_ref()
+        .and_then(|status| status.container_statuses.as_ref())
+    {
+        Some(statuses) => !statuses.is_empty() && statuses.iter().all(|status| status.ready),
+        None => false,
+    }
+}
+
+/// Updates the analysis with the restarts and readiness of the pods of the canary, returning their number
+fn check_canary_pods(
+    target: &DeploymentTarget,
+    selector: &str,
+    analysis: &mut CanaryAnalysis,
+) -> Result<usize, String> {
+    let pods: Vec<Pod> = block_on(kube_get_resources_by_selector(
+        &target.kube,
+        target.environment.namespace(),
+        selector,
+    ))
+    .map_err(|err| err.to_string())?
+    .items;
+
+    analysis.restarts = pods
+        .iter()
+        .flat_map(|pod| {
+            pod.status
+                .iter()
+                .flat_map(|status| status.container_statuses.iter().flatten())
+        })
+        .map(|status| status.restart_count)
+        .sum();
+    analysis.unready_pods = pods.iter().filter(|pod| !is_pod_ready(pod)).count();
+    Ok(pods.len())
+}
+
+fn request_canary(client: &reqwest::blocking::Client, url: &Url) -> reqwest::Result<reqwest::StatusCode> {
+    client
+        .get(url.clone())
+        .header(CANARY_HEADER, CANARY_HEADER_VALUE)
+        .send()
+        .map(|response| response.status())
+}
+
+/// Routes of the canary the engine can reach. The other ones, i.e: hosts not resolved yet or only reachable from a
+/// private network, are left out of its analysis as their failures would not be the ones of the canary.
+fn reachable_canary_urls(client: &reqwest::blocking::Client, urls: &[Url], logger: &EnvProgressLogger) -> Vec<Url> {
+    urls.iter()
+        .filter(|url| match request_canary(client, url) {
+            Ok(_) => true,
+            Err(err) => {
+                logger.warning(format!("Canary cannot be requested on {url}, it is not analysed on it: {err}"));
+                false
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Requests every route of the canary with the header reaching it, counting server errors. Unanswered requests are
+/// not counted, a network failure between the engine and the cluster does not tell anything about the canary.
+fn check_canary_requests(client: &reqwest::blocking::Client, urls: &[Url], analysis: &mut CanaryAnalysis) {
+    for url in urls {
+        if let Ok(status) = request_canary(client, url) {
+            analysis.requests += 1;
+            if status.is_server_error() {
+                analysis.failed_requests += 1;
+            }
+        }
+    }
+}
+
+fn delete_canary(target: &DeploymentTarget, selector: &str, service_names: &[String], logger: &EnvProgressLogger) {
+    let namespace = target.environment.namespace();
+    if let Err(err) = block_on(kube_delete_all_from_selector::<Ingress>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    )) {
+        logger.warning(format!("Cannot delete canary ingresses: {err}"));
+    }
+    // services cannot be deleted by collection
+    let service_api: Api<Service> = Api::namespaced(target.kube.clone(), namespace);
+    for service_name in service_names {
+        match block_on(service_api.delete(service_name, &DeleteParams::default())) {
+            Ok(_) => {}
+            Err(kube::Error::Api(err)) if err.code == 404 => {}
+            Err(err) => logger.warning(format!("Cannot delete canary service {service_name}: {err}")),
+        }
+    }
+    if let Err(err) = block_on(kube_delete_all_from_selector::<Deployment>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    )) {
+        logger.warning(format!("Cannot delete canary deployment: {err}"));
+    }
+    // env variables may hold secrets, do not leave them around
+    if let Err(err) = block_on(kube_delete_all_from_selector::<Secret>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    )) {
+        logger.warning(format!("Cannot delete canary secret: {err}"));
+    }
+}
+
+/// Wait for the canary to be ready, then analyse it for the configured duration
+fn analyse_canary(
+    app: &dyn ApplicationService,
+    target: &DeploymentTarget,
+    selector: &str,
+    urls: &[Url],
+    logger: &EnvProgressLogger,
+) -> Result<CanaryAnalysis, String> {
+    let settings = app.advanced_settings();
+    let mut analysis = CanaryAnalysis::default();
+
+    let started_at = Instant::now();
+    loop {
+        let pods = check_canary_pods(target, selector, &mut analysis)?;
+        // a pod crashing on start does not get a chance to be ready
+        if analysis.restarts > 0 {
+            return Err(format!("its pods restarted {} time(s) before being ready", analysis.restarts));
+        }
+        if pods > 0 && analysis.unready_pods == 0 {
+            break;
+        }
+        if started_at.elapsed() > app.startup_timeout() {
+            return Err(format!("it is not ready after {}s", app.startup_timeout().as_secs()));
+        }
+        thread::sleep(CANARY_CHECK_INTERVAL);
+    }
+
+    let urls = match reqwest::blocking::Client::builder()
+        .timeout(CANARY_REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+    {
+        Ok(client) => Some((reachable_canary_urls(&client, urls, logger), client)),
+        Err(err) => {
+            logger.warning(format!("Cannot create the client requesting the canary: {err}"));
+            None
+        }
+    };
+    if urls.as_ref().map_or(true, |(urls, _)| urls.is_empty()) {
+        logger.warning("No route of the canary can be requested, it is only analysed on its pods".to_string());
+    }
+
+    logger.info(format!(
+        "🐤 Canary is ready, analysing it for {}s",
+        settings.deployment_canary_analysis_duration_seconds
+    ));
+    let analysis_duration = Duration::from_secs(u64::from(settings.deployment_canary_analysis_duration_seconds));
+    let started_at = Instant::now();
+    while started_at.elapsed() < analysis_duration {
+        thread::sleep(CANARY_CHECK_INTERVAL);
+        if let Some((urls, client)) = &urls {
+            check_canary_requests(client, urls, &mut analysis);
+        }
+        check_canary_pods(target, selector, &mut analysis)?;
+        if let Some(reason) = analysis.rollback_reason(settings.deployment_canary_max_error_rate_percent) {
+            return Err(reason);
+        }
+    }
+
+    Ok(analysis)
+}
+
+/// Deploy the new version of the application next to the stable one, with a share of the traffic of its routers,
+/// before rolling it out. The canary is promoted if it stays ready without failing too many requests during its
+/// analysis, otherwise it is rolled back and the deployment fails, the stable version keeping all the traffic.
+/// Only a new version of an application exposed by a router, running as a deployment, can be a canary.
+pub(super) fn run_canary_deployment(
+    app: &dyn ApplicationService,
+    helm: &HelmDeployment,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let settings = app.advanced_settings();
+    if settings.deployment_canary_weight_percent == 0 || target.is_dry_run_deploy {
+        return Ok(());
+    }
+    let namespace = target.environment.namespace();
+    let service_long_id = app.long_id().to_string();
+    let to_engine_error = |reason: &str, err: CommandError| {
+        Box::new(EngineError::new_canary_deployment_failed(
+            event_details.clone(),
+            reason.to_string(),
+            Some(err),
+        ))
+    };
+
+    let stable_selector = format!("{SERVICE_ID_LABEL}={service_long_id}");
+    if block_on(kube_get_resources_by_selector::<Deployment>(
+        &target.kube,
+        namespace,
+        &stable_selector,
+    ))
+    .map_err(|err| to_engine_error("cannot get the stable deployment", err))?
+    .items
+    .is_empty()
+    {
+        logger.info("🐤 No version is running yet, the first one is deployed without canary".to_string());
+        return Ok(());
+    }
+    let Some(stable_service) = block_on(kube_get_resources_by_selector::<Service>(
+        &target.kube,
+        namespace,
+        &stable_selector,
+    ))
+    .map_err(|err| to_engine_error("cannot get the stable service", err))?
+    .items
+    .into_iter()
+    .find(|service| {
+        service
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.type_.as_deref())
+            .unwrap_or("ClusterIP")
+            == "ClusterIP"
+    }) else {
+        logger.info("🐤 Application has no port, it is deployed without canary".to_string());
+        return Ok(());
+    };
+    let stable_ingresses = block_on(kube_get_resources_by_selector::<Ingress>(
+        &target.kube,
+        namespace,
+        &format!("{ASSOCIATED_SERVICE_ID_LABEL}={service_long_id}"),
+    ))
+    .map_err(|err| to_engine_error("cannot get the ingresses of the routers", err))?
+    .items;
+
+    let stable_service_name = stable_service.metadata.name.clone().unwrap_or_default();
+    let canary_service_name = canary_name(&stable_service_name);
+    let weight_percent = settings.deployment_canary_weight_percent;
+    let canary_ingresses: Vec<Ingress> = stable_ingresses
+        .iter()
+        .filter_map(|ingress| {
+            canary_ingress(
+                ingress,
+                &stable_service_name,
+                &canary_service_name,
+                &service_long_id,
+                weight_percent,
+            )
+        })
+        .collect();
+    if canary_ingresses.is_empty() {
+        logger.info("🐤 Application is not exposed by a router, it is deployed without canary".to_string());
+        return Ok(());
+    }
+
+    let labels = BTreeMap::from([(CANARY_OF_LABEL.to_string(), service_long_id.clone())]);
+    let Some(new_version) = render_new_version(helm, target, "canary", &labels, event_details)? else {
+        logger.info("🐤 New version does not run as a deployment, it is deployed without canary".to_string());
+        return Ok(());
+    };
+    let image = app.get_build().image.full_image_name_with_tag();
+    let canary_deployment = canary_deployment(&new_version.deployment, &service_long_id).ok_or_else(|| {
+        to_engine_error(
+            "cannot create it from the rendered chart",
+            CommandError::new_from_safe_message("Rendered deployment has no spec".to_string()),
+        )
+    })?;
+    let canary_service = canary_service(&stable_service, &service_long_id).ok_or_else(|| {
+        to_engine_error(
+            "cannot copy the stable service",
+            CommandError::new_from_safe_message("Stable service has no spec".to_string()),
+        )
+    })?;
+    let urls: Vec<Url> = canary_ingresses.iter().flat_map(canary_urls).collect();
+
+    logger.info(format!(
+        "🐤 Deploying canary of {} with image {}, receiving {}% of the traffic",
+        app.name(),
+        image,
+        weight_percent.min(100)
+    ));
+    let canary_selector = format!("{CANARY_OF_LABEL}={service_long_id}");
+    // a canary left by an interrupted deployment is replaced
+    delete_canary(target, &canary_selector, &[canary_service_name.clone()], logger);
+    let created = new_version
+        .secrets
+        .into_iter()
+        .try_for_each(|secret| block_on(kube_create_from_resource(&target.kube, namespace, secret)))
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, canary_deployment)))
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, canary_service)))
+        .and_then(|_| {
+            canary_ingresses
+                .into_iter()
+                .try_for_each(|ingress| block_on(kube_create_from_resource(&target.kube, namespace, ingress)))
+        });
+
+    let analysis = match created {
+        Ok(_) => analyse_canary(app, target, &canary_selector, &urls, logger),
+        Err(err) => {
+            delete_canary(target, &canary_selector, &[canary_service_name], logger);
+            return Err(to_engine_error("cannot create it", err));
+        }
+    };
+    delete_canary(target, &canary_selector, &[canary_service_name], logger);
+
+    match analysis {
+        Ok(analysis) => {
+            logger.info(format!(
+                "✅ Canary is promoted, {}% of its {} requests failed, rolling out the new version",
+                analysis.error_rate_percent(),
+                analysis.requests
+            ));
+            Ok(())
+        }
+        Err(reason) => Err(Box::new(EngineError::new_canary_deployment_failed(
+            event_details.clone(),
+            reason,
+            None,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::DeploymentSpec;
+    use k8s_openapi::api::core::v1::{Container, EnvVar, EnvVarSource, PodSpec, PodTemplateSpec, SecretKeySelector};
+    use k8s_openapi::api::networking::v1::{
+        HTTPIngressPath, IngressBackend, IngressRule, IngressServiceBackend, IngressSpec,
+    };
+
+    fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+        labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_canary_name() {
+        assert_eq!(canary_name("app-z1234"), "app-z1234-canary");
+        assert_eq!(canary_name(&"a".repeat(70)).len(), 63);
+    }
+
+    fn secret_env_var(key: &str, secret_name: &str) -> EnvVar {
+        EnvVar {
+            name: key.to_string(),
+            value_from: Some(EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    name: Some(secret_name.to_string()),
+                    key: key.to_string(),
+                    optional: None,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_canary_deployment() {
+        let rendered = Deployment {
+            metadata: ObjectMeta {
+                name: Some("app-z1234".to_string()),
+                labels: Some(labels(&[(SERVICE_ID_LABEL, "long-id"), ("envId", "z42")])),
+                ..Default::default()
+            },
+            spec: Some(DeploymentSpec {
+                replicas: Some(3),
+                template: PodTemplateSpec {
+                    metadata: Some(ObjectMeta {
+                        labels: Some(labels(&[(SERVICE_ID_LABEL, "long-id"), ("appId", "z1234")])),
+                        ..Default::default()
+                    }),
+                    spec: Some(PodSpec {
+                        containers: vec![Container {
+                            name: "app-z1234".to_string(),
+                            image: Some("registry/app:v2".to_string()),
+                            env: Some(vec![
+                                secret_env_var("NEW", "app-z1234-canary"),
+                                secret_env_var("DB_PASSWORD", "app-z1234-external"),
+                            ]),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }),
+                },
+                ..Default::default()
+            }),
+            status: None,
+        };
+
+        let canary = canary_deployment(&rendered, "long-id").unwrap();
+        assert_eq!(canary.metadata.name.as_deref(), Some("app-z1234-canary"));
+        assert_eq!(
+            canary.metadata.labels,
+            Some(labels(&[(CANARY_OF_LABEL, "long-id"), ("envId", "z42")]))
+        );
+        let spec = canary.spec.unwrap();
+        assert_eq!(spec.replicas, Some(1));
+        assert_eq!(spec.selector.match_labels, Some(labels(&[(CANARY_OF_LABEL, "long-id")])));
+        // pods of the canary are not selected by the service of the stable version
+        assert_eq!(
+            spec.template.metadata.unwrap().labels,
+            Some(labels(&[(CANARY_OF_LABEL, "long-id")]))
+        );
+        // containers are the ones rendered by the chart for the new version
+        assert_eq!(
+            spec.template.spec,
+            rendered.spec.as_ref().and_then(|spec| spec.template.spec.clone())
+        );
+    }
+
+    #[test]
+    fn test_canary_ingress() {
+        let path = |service_name: &str| HTTPIngressPath {
+            path: Some("/".to_string()),
+            path_type: "Prefix".to_string(),
+            backend: IngressBackend {
+                service: Some(IngressServiceBackend {
+                    name: service_name.to_string(),
+                    port: None,
+                }),
+                resource: None,
+            },
+        };
+        let rule = |host: &str, service_name: &str| IngressRule {
+            host: Some(host.to_string()),
+            http: Some(HTTPIngressRuleValue {
+                paths: vec![path(service_name)],
+            }),
+        };
+        let stable = Ingress {
+            metadata: ObjectMeta {
+                name: Some("router-z5678".to_string()),
+                labels: Some(labels(&[
+                    (SERVICE_ID_LABEL, "router-id"),
+                    (ASSOCIATED_SERVICE_ID_LABEL, "long-id"),
+                ])),
+                annotations: Some(labels(&[
+                    ("cert-manager.io/cluster-issuer", "letsencrypt-qovery"),
+                    ("kubernetes.io/ingress.class", "nginx-qovery"),
+                    ("nginx.ingress.kubernetes.io/proxy-body-size", "100m"),
+                ])),
+                ..Default::default()
+            },
+            spec: Some(IngressSpec {
+                rules: Some(vec![
+                    rule("app.example.com", "app-z1234"),
+                    rule("other.example.com", "other-z9999"),
+                    rule("*.example.com", "app-z1234"),
+                ]),
+                ..Default::default()
+            }),
+            status: None,
+        };
+
+        let canary = canary_ingress(&stable, "app-z1234", "app-z1234-canary", "long-id", 20).unwrap();
+        assert_eq!(canary.metadata.name.as_deref(), Some("router-z5678-canary"));
+        assert_eq!(canary.metadata.labels, Some(labels(&[(CANARY_OF_LABEL, "long-id")])));
+        assert_eq!(
+            canary.metadata.annotations,
+            Some(labels(&[
+                ("kubernetes.io/ingress.class", "nginx-qovery"),
+                ("nginx.ingress.kubernetes.io/canary", "true"),
+                ("nginx.ingress.kubernetes.io/canary-by-header", CANARY_HEADER),
+                ("nginx.ingress.kubernetes.io/canary-weight", "20"),
+                ("nginx.ingress.kubernetes.io/proxy-body-size", "100m"),
+            ]))
+        );
+        // routes to the other services are left to the stable ingress
+        let rules = canary.spec.as_ref().unwrap().rules.as_ref().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0].http.as_ref().unwrap().paths[0]
+                .backend
+                .service
+                .as_ref()
+                .unwrap()
+                .name,
+            "app-z1234-canary"
+        );
+        assert_eq!(canary_urls(&canary), vec![Url::parse("https://app.example.com/").unwrap()]);
+
+        assert!(canary_ingress(&stable, "unknown", "unknown-canary", "long-id", 20).is_none());
+    }
+
+    #[test]
+    fn test_canary_analysis_rollback_reason() {
+        let analysis = CanaryAnalysis {
+            requests: 40,
+            failed_requests: 2,
+            restarts: 0,
+            unready_pods: 0,
+        };
+        assert_eq!(analysis.error_rate_percent(), 5);
+        assert_eq!(analysis.rollback_reason(5), None);
+        assert!(analysis.rollback_reason(4).is_some());
+
+        assert!(CanaryAnalysis {
+            restarts: 1,
+            ..analysis
+        }
+        .rollback_reason(100)
+        .is_some());
+        assert!(CanaryAnalysis {
+            unready_pods: 1,
+            ..analysis
+        }
+        .rollback_reason(100)
+        .is_some());
+        assert_eq!(CanaryAnalysis::default().rollback_reason(0), None);
+    }
+}
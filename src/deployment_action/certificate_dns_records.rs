@@ -0,0 +1,153 @@
+use crate::cloud_provider::helm::HelmChartNamespaces;
+use crate::cloud_provider::kubernetes::{filter_svc_loadbalancers, kube_list_services};
+use crate::cloud_provider::models::CustomDomain;
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::dns_provider::caa::{lookup_caa_records, LETS_ENCRYPT_CAA_ISSUER};
+use crate::dns_provider::dns_check::DnsResolver;
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{DnsProvider, DnsRecordType};
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::runtime::block_on;
+
+/// Makes sure Let's Encrypt can issue the certificates of the custom domains: their CAA records must allow it, and
+/// the wildcard record of the cluster domain, which their CNAME ends up on, must target the cluster load balancer.
+/// Records are only written in the zones managed by the DNS provider, mismatches anywhere else are left to the user.
+pub(super) fn ensure_certificate_dns_records(
+    custom_domains: &[&CustomDomain],
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    if custom_domains.is_empty() {
+        return Ok(());
+    }
+
+    let resolvers = DnsResolver::defaults_for(target.dns_provider);
+    for custom_domain in custom_domains {
+        ensure_caa_records_allow_lets_encrypt(custom_domain, &resolvers, logger, event_details, target.dns_provider)?;
+    }
+
+    ensure_cluster_wildcard_record(logger, event_details, target)
+}
+
+fn ensure_caa_records_allow_lets_encrypt(
+    custom_domain: &CustomDomain,
+    resolvers: &[DnsResolver],
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    dns_provider: &dyn DnsProvider,
+) -> Result<(), Box<EngineError>> {
+    let domain = custom_domain.domain.as_str();
+    let Some(resolver) = resolvers.first() else {
+        return Ok(());
+    };
+    let caa_record_set = match lookup_caa_records(resolver, domain) {
+        Ok(Some(caa_record_set)) => caa_record_set,
+        Ok(None) => return Ok(()),
+        Err(err) => {
+            // not being able to tell does not mean the certificate cannot be issued
+            logger.warning(format!("Cannot check CAA records of domain {domain}: {err}"));
+            return Ok(());
+        }
+    };
+
+    let wildcard = custom_domain.is_wildcard();
+    if caa_record_set.allows_issuer(LETS_ENCRYPT_CAA_ISSUER, wildcard) {
+        return Ok(());
+    }
+
+    let caa_records: Vec<String> = caa_record_set
+        .with_issuer_allowed(LETS_ENCRYPT_CAA_ISSUER, wildcard)
+        .iter()
+        .map(|record| record.to_string())
+        .collect();
+    let lets_encrypt_record = caa_records.last().cloned().unwrap_or_default();
+
+    match dns_provider.set_records(&caa_record_set.name, DnsRecordType::Caa, &caa_records) {
+        Ok(()) => {
+            logger.info(format!(
+                "🔏 Added CAA record `{}` to {} so Let's Encrypt can issue the certificate of {}",
+                lets_encrypt_record, caa_record_set.name, domain
+            ));
+            Ok(())
+        }
+        // the records are held by a zone out of our reach, only the user can fix them
+        Err(DnsProviderError::RecordsManagementNotSupported { .. } | DnsProviderError::ZoneNotFound { .. }) => {
+            Err(Box::new(EngineError::new_dns_caa_records_forbid_certificate_issuance(
+                event_details.clone(),
+                domain,
+                &caa_record_set.name,
+                &lets_encrypt_record,
+            )))
+        }
+        Err(err) => Err(Box::new(err.to_engine_error(event_details.clone()))),
+    }
+}
+
+/// Hostname or ip of the load balancer of the cluster ingress, with the type of record targeting it
+fn cluster_load_balancer(target: &DeploymentTarget) -> Option<(DnsRecordType, String)> {
+    let services = block_on(kube_list_services(
+        &target.kube,
+        Some(HelmChartNamespaces::NginxIngress.to_string().as_str()),
+        None,
+    ))
+    .ok()?;
+
+    filter_svc_loadbalancers(services)
+        .into_iter()
+        .filter_map(|service| service.status?.load_balancer?.ingress?.into_iter().next())
+        .find_map(|ingress| match (ingress.hostname, ingress.ip) {
+            (Some(hostname), _) => Some((DnsRecordType::Cname, hostname)),
+            (None, Some(ip)) => Some((DnsRecordType::A, ip)),
+            (None, None) => None,
+        })
+}
+
+fn ensure_cluster_wildcard_record(
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let Some((record_type, load_balancer)) = cluster_load_balancer(target) else {
+        return Ok(());
+    };
+
+    let wildcard_domain = target.dns_provider.domain().wildcarded().to_string();
+    let current_values = match target.dns_provider.get_records(&wildcard_domain, record_type) {
+        Ok(current_values) => current_values,
+        // external-dns keeps managing the record on its own
+        Err(DnsProviderError::RecordsManagementNotSupported { .. }) => return Ok(()),
+        Err(err) => return Err(Box::new(err.to_engine_error(event_details.clone()))),
+    };
+
+    if current_values.is_empty() {
+        target
+            .dns_provider
+            .set_records(&wildcard_domain, record_type, &[load_balancer.clone()])
+            .map_err(|err| Box::new(err.to_engine_error(event_details.clone())))?;
+        logger.info(format!(
+            "🌍 Created {} record {} targeting the cluster load balancer {}",
+            record_type.as_str(),
+            wildcard_domain,
+            load_balancer
+        ));
+        return Ok(());
+    }
+
+    let normalize = |value: &str| value.trim_end_matches('.').to_lowercase();
+    if !current_values
+        .iter()
+        .any(|value| normalize(value) == normalize(&load_balancer))
+    {
+        return Err(Box::new(EngineError::new_dns_wildcard_record_mismatch(
+            event_details.clone(),
+            &wildcard_domain,
+            &current_values,
+            &load_balancer,
+        )));
+    }
+
+    Ok(())
+}
@@ -1,97 +1,131 @@
 use crate::cloud_provider::models::CustomDomain;
-use crate::cloud_provider::utilities::{await_domain_resolve_cname, await_domain_resolve_ip};
 use crate::cloud_provider::DeploymentTarget;
-use crate::cmd::command::CommandKiller;
 use crate::deployment_action::DeploymentAction;
+use crate::dns_provider::dns_check::{check_propagation, DnsCheckConfig, DnsCheckReport, DnsExpectation};
 use crate::errors::EngineError;
-use std::net::IpAddr;
-use std::time::Duration;
+use crate::events::EventMessage;
 
 pub struct CheckDnsForDomains<'a> {
     pub resolve_to_ip: Vec<String>,
     pub resolve_to_cname: Vec<CustomDomain>,
-    pub log: Box<dyn Fn(String) + 'a + Send + Sync>,
+    pub dns_check_config: DnsCheckConfig,
+    pub log: Box<dyn Fn(EventMessage) + 'a + Send + Sync>,
 }
 
-const DEFAULT_CHECK_FREQUENCY: Duration = Duration::from_secs(30);
+fn resolved_on(report: &DnsCheckReport) -> String {
+    format!("{}/{} resolvers", report.propagated_checks().count(), report.checks.len())
+}
 
-fn check_domain_resolve_ip(domain: &str, log: &impl Fn(String), should_abort: &dyn Fn() -> bool) {
+fn check_domain_resolve_ip(
+    domain: &str,
+    config: &DnsCheckConfig,
+    log: &impl Fn(EventMessage),
+    should_abort: &dyn Fn() -> bool,
+) {
     // We use send_success because if on_check is called it means the DB is already correctly deployed
-    (log)(format!(
+    (log)(EventMessage::new_from_safe(format!(
         "🌍 Checking DNS Ip resolution for domain {domain}. Please wait, it can take some time..."
-    ));
-
-    let get_domain = || {
-        (log)(format!("🌍 Waiting domain {domain} resolve to an Ip address..."));
-        domain
-    };
-
-    let should_abort = CommandKiller::from(Duration::from_secs(60 * 5), should_abort);
-    let does_resolve = await_domain_resolve_ip(get_domain, DEFAULT_CHECK_FREQUENCY, should_abort);
-
-    match does_resolve {
-        Ok(ip) => {
-            (log)(format!(
-                "✨ Domain {} resolved to ip {}",
+    )));
+
+    let report = check_propagation(
+        domain,
+        &DnsExpectation::Ip,
+        config,
+        &|check| (log)(check.to_event_message(domain)),
+        should_abort,
+    );
+
+    let resolved_ip = report
+        .propagated_checks()
+        .filter_map(|check| check.resolution.as_ref().ok())
+        .filter_map(|resolution| resolution.ips.first())
+        .next();
+
+    match resolved_ip {
+        Some(ip) if report.is_fully_propagated() => {
+            (log)(EventMessage::new_from_safe(format!("✨ Domain {domain} resolved to ip {ip}")));
+        }
+        Some(ip) => {
+            (log)(EventMessage::new_from_safe(format!(
+                "✨ Domain {} resolved to ip {} on {}, it is still propagating to the others. Note: this is not critical.",
                 domain,
-                ip.iter().next().unwrap_or_else(|| IpAddr::from([0_u8, 0, 0, 0]))
-            ));
+                ip,
+                resolved_on(&report)
+            )));
         }
-        Err(_) => {
+        None => {
             let message = format!(
                 "💥 Unable to check domain availability for '{}'. It can be due to a \
                         too long domain propagation. Note: this is not critical.",
                 &domain
             );
-            (log)(message);
+            (log)(EventMessage::new_from_safe(message));
         }
     }
 }
 
-fn check_domain_resolve_cname(custom_domain: &CustomDomain, log: &impl Fn(String), should_abort: &dyn Fn() -> bool) {
+fn check_domain_resolve_cname(
+    custom_domain: &CustomDomain,
+    config: &DnsCheckConfig,
+    log: &impl Fn(EventMessage),
+    should_abort: &dyn Fn() -> bool,
+) {
     // We use send_success because if on_check is called it means the DB is already correctly deployed
-    (log)(format!(
-        "🌍 Checking DNS CNAME resolution for domain {}. Please wait, it can take some time...",
-        &custom_domain.domain,
-    ));
-
-    let get_domain = || {
-        (log)(format!(
-            "🌍 Waiting domain {} to resolve to DNS CNAME {}",
-            &custom_domain.domain, &custom_domain.target_domain
-        ));
-        custom_domain.domain.as_str()
-    };
-
-    let should_abort = CommandKiller::from(Duration::from_secs(60 * 5), should_abort);
-    let does_resolve = await_domain_resolve_cname(get_domain, DEFAULT_CHECK_FREQUENCY, should_abort);
+    (log)(EventMessage::new_from_safe(format!(
+        "🌍 Checking DNS CNAME resolution for domain {} to {}. Please wait, it can take some time...",
+        &custom_domain.domain, &custom_domain.target_domain
+    )));
 
-    match does_resolve {
-        Ok(cname) => {
-            (log)(format!(
+    let report = check_propagation(
+        &custom_domain.domain,
+        &DnsExpectation::Cname(custom_domain.target_domain.to_string()),
+        config,
+        &|check| (log)(check.to_event_message(&custom_domain.domain)),
+        should_abort,
+    );
+
+    match report.propagated_checks().next() {
+        Some(_) if report.is_fully_propagated() => {
+            (log)(EventMessage::new_from_safe(format!(
                 "✨ Domain {} resolved to CNAME {}",
+                custom_domain.domain, custom_domain.target_domain
+            )));
+        }
+        Some(_) => {
+            (log)(EventMessage::new_from_safe(format!(
+                "✨ Domain {} resolved to CNAME {} on {}, it is still propagating to the others",
                 custom_domain.domain,
-                cname.to_utf8()
-            ));
+                custom_domain.target_domain,
+                resolved_on(&report)
+            )));
         }
-        Err(_) => {
+        None => {
             let message = format!(
                 "💥 Resolution of CNAME for domain {} failed. Please check that you have correctly configured your CNAME. If you are using a CDN you can forget this message",
                 &custom_domain.domain
             );
-            (log)(message);
+            (log)(EventMessage::new_from_safe(message));
         }
     }
 }
 
 impl<'a> DeploymentAction for CheckDnsForDomains<'a> {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
-        for domain in &self.resolve_to_ip {
-            check_domain_resolve_ip(domain, &self.log, target.should_abort);
+        // records of private zones are only resolvable from inside the VPC, public resolvers would never see them
+        if target.dns_provider.is_private_zone() {
+            for domain in &self.resolve_to_ip {
+                (self.log)(EventMessage::new_from_safe(format!(
+                    "🔒 Domain {domain} belongs to a private DNS zone, skipping its public propagation check"
+                )));
+            }
+        } else {
+            for domain in &self.resolve_to_ip {
+                check_domain_resolve_ip(domain, &self.dns_check_config, &self.log, target.should_abort);
+            }
         }
 
         for domain in &self.resolve_to_cname {
-            check_domain_resolve_cname(domain, &self.log, target.should_abort);
+            check_domain_resolve_cname(domain, &self.dns_check_config, &self.log, target.should_abort);
         }
 
         Ok(())
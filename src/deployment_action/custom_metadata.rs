@@ -0,0 +1,262 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::errors::CommandError;
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::io_models::CustomMetadata;
+use crate::kubers_utils::{kube_get_resources_by_selector, kube_patch_metadata};
+use crate::runtime::block_on;
+use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Service};
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::NamespaceResourceScope;
+use kube::Resource;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+// Keys applied by the previous deployment, so the ones removed by users since are removed from resources too
+const APPLIED_LABELS_ANNOTATION: &str = "qovery.com/custom-labels";
+const APPLIED_ANNOTATIONS_ANNOTATION: &str = "qovery.com/custom-annotations";
+
+type MetadataPatch = BTreeMap<String, Option<String>>;
+
+fn applied_keys(metadata: &ObjectMeta, tracking_annotation: &str) -> Vec<String> {
+    metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(tracking_annotation))
+        .map(|keys| {
+            keys.split(',')
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn to_metadata_patch(previous_keys: Vec<String>, values: &BTreeMap<String, String>) -> MetadataPatch {
+    let mut patch: MetadataPatch = previous_keys.into_iter().map(|key| (key, None)).collect();
+    patch.extend(
+        values
+            .iter()
+            .map(|(key, value)| (key.to_string(), Some(value.to_string()))),
+    );
+    patch
+}
+
+fn tracking_value(values: &BTreeMap<String, String>) -> Option<String> {
+    match values.is_empty() {
+        true => None,
+        false => Some(values.keys().cloned().collect::<Vec<_>>().join(",")),
+    }
+}
+
+/// Labels and annotations patches to apply on a resource, None if it has nothing to add nor to remove
+fn metadata_patch(metadata: &ObjectMeta, custom_metadata: &CustomMetadata) -> Option<(MetadataPatch, MetadataPatch)> {
+    let previous_labels = applied_keys(metadata, APPLIED_LABELS_ANNOTATION);
+    let previous_annotations = applied_keys(metadata, APPLIED_ANNOTATIONS_ANNOTATION);
+    if custom_metadata.is_empty() && previous_labels.is_empty() && previous_annotations.is_empty() {
+        return None;
+    }
+
+    let labels = to_metadata_patch(previous_labels, &custom_metadata.labels);
+    let mut annotations = to_metadata_patch(previous_annotations, &custom_metadata.annotations);
+    annotations.insert(APPLIED_LABELS_ANNOTATION.to_string(), tracking_value(&custom_metadata.labels));
+    annotations.insert(
+        APPLIED_ANNOTATIONS_ANNOTATION.to_string(),
+        tracking_value(&custom_metadata.annotations),
+    );
+
+    Some((labels, annotations))
+}
+
+async fn patch_resources<K>(
+    client: &kube::Client,
+    namespace: &str,
+    resources: &[K],
+    custom_metadata: &CustomMetadata,
+) -> Result<(), CommandError>
+where
+    K: Clone + DeserializeOwned + Debug + Resource<Scope = NamespaceResourceScope>,
+    <K as Resource>::DynamicType: Default,
+{
+    for resource in resources {
+        let metadata = resource.meta();
+        let (Some(name), Some((labels, annotations))) =
+            (metadata.name.as_deref(), metadata_patch(metadata, custom_metadata))
+        else {
+            continue;
+        };
+        kube_patch_metadata::<K>(client, namespace, name, &labels, &annotations).await?;
+    }
+
+    Ok(())
+}
+
+// Volumes created out of a statefulset claim template are named `{template}-{statefulset}-{ordinal}`
+fn is_statefulset_volume(pvc_name: &str, statefulsets: &[StatefulSet]) -> bool {
+    statefulsets.iter().any(|statefulset| {
+        let Some(statefulset_name) = statefulset.metadata.name.as_deref() else {
+            return false;
+        };
+        statefulset
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.volume_claim_templates.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|template| template.metadata.name.as_deref())
+            .any(|template_name| {
+                pvc_name
+                    .strip_prefix(&format!("{template_name}-{statefulset_name}-"))
+                    .map_or(false, |ordinal| {
+                        !ordinal.is_empty() && ordinal.chars().all(|c| c.is_ascii_digit())
+                    })
+            })
+    })
+}
+
+async fn patch_service_resources(
+    client: &kube::Client,
+    namespace: &str,
+    selector: &str,
+    custom_metadata: &CustomMetadata,
+) -> Result<(), CommandError> {
+    let deployments = kube_get_resources_by_selector::<Deployment>(client, namespace, selector).await?;
+    patch_resources(client, namespace, &deployments.items, custom_metadata).await?;
+
+    let statefulsets = kube_get_resources_by_selector::<StatefulSet>(client, namespace, selector).await?;
+    patch_resources(client, namespace, &statefulsets.items, custom_metadata).await?;
+
+    let services = kube_get_resources_by_selector::<Service>(client, namespace, selector).await?;
+    patch_resources(client, namespace, &services.items, custom_metadata).await?;
+
+    let ingresses = kube_get_resources_by_selector::<Ingress>(client, namespace, selector).await?;
+    patch_resources(client, namespace, &ingresses.items, custom_metadata).await?;
+
+    if !statefulsets.items.is_empty() {
+        // volumes are not always labelled like their statefulset, i.e: for databases
+        let pvcs: Vec<PersistentVolumeClaim> = kube_get_resources_by_selector(client, namespace, "")
+            .await?
+            .items
+            .into_iter()
+            .filter(|pvc| {
+                pvc.metadata
+                    .name
+                    .as_deref()
+                    .map_or(false, |name| is_statefulset_volume(name, &statefulsets.items))
+            })
+            .collect();
+        patch_resources(client, namespace, &pvcs, custom_metadata).await?;
+    }
+
+    Ok(())
+}
+
+/// Apply the custom labels and annotations of a service on its deployments, statefulsets, services, ingresses and volumes.
+/// Resources are patched once deployed, so statefulsets volume claim templates, which are immutable, stay untouched.
+/// A failure does not fail the deployment, it is only reported as a warning.
+pub(super) fn apply_custom_metadata(
+    target: &DeploymentTarget,
+    selector: &str,
+    custom_metadata: &CustomMetadata,
+    event_details: &EventDetails,
+) {
+    if target.is_dry_run_deploy {
+        return;
+    }
+
+    if let Err(err) = block_on(patch_service_resources(
+        &target.kube,
+        target.environment.namespace(),
+        selector,
+        custom_metadata,
+    )) {
+        target.kubernetes.logger().log(EngineEvent::Warning(
+            event_details.clone(),
+            EventMessage::new("Cannot apply custom labels and annotations".to_string(), Some(err.to_string())),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::StatefulSetSpec;
+
+    fn custom_metadata(labels: &[(&str, &str)], annotations: &[(&str, &str)]) -> CustomMetadata {
+        let to_map = |values: &[(&str, &str)]| {
+            values
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        CustomMetadata {
+            labels: to_map(labels),
+            annotations: to_map(annotations),
+        }
+    }
+
+    #[test]
+    fn test_metadata_patch() {
+        // nothing applied before and nothing to apply
+        assert_eq!(metadata_patch(&ObjectMeta::default(), &CustomMetadata::default()), None);
+
+        let (labels, annotations) = metadata_patch(
+            &ObjectMeta::default(),
+            &custom_metadata(&[("team", "backend")], &[("backup.example.com/schedule", "daily")]),
+        )
+        .unwrap();
+        assert_eq!(labels, BTreeMap::from([("team".to_string(), Some("backend".to_string()))]));
+        assert_eq!(annotations.get("backup.example.com/schedule"), Some(&Some("daily".to_string())));
+        assert_eq!(annotations.get(APPLIED_LABELS_ANNOTATION), Some(&Some("team".to_string())));
+
+        // keys dropped since the previous deployment are removed
+        let previously_applied = ObjectMeta {
+            annotations: Some(BTreeMap::from([
+                (APPLIED_LABELS_ANNOTATION.to_string(), "team,tier".to_string()),
+                (
+                    APPLIED_ANNOTATIONS_ANNOTATION.to_string(),
+                    "backup.example.com/schedule".to_string(),
+                ),
+            ])),
+            ..Default::default()
+        };
+        let (labels, annotations) =
+            metadata_patch(&previously_applied, &custom_metadata(&[("team", "frontend")], &[])).unwrap();
+        assert_eq!(
+            labels,
+            BTreeMap::from([
+                ("team".to_string(), Some("frontend".to_string())),
+                ("tier".to_string(), None),
+            ])
+        );
+        assert_eq!(annotations.get("backup.example.com/schedule"), Some(&None));
+        assert_eq!(annotations.get(APPLIED_ANNOTATIONS_ANNOTATION), Some(&None));
+    }
+
+    #[test]
+    fn test_is_statefulset_volume() {
+        let statefulset = StatefulSet {
+            metadata: ObjectMeta {
+                name: Some("postgresql-mydb".to_string()),
+                ..Default::default()
+            },
+            spec: Some(StatefulSetSpec {
+                volume_claim_templates: Some(vec![PersistentVolumeClaim {
+                    metadata: ObjectMeta {
+                        name: Some("data".to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert!(is_statefulset_volume("data-postgresql-mydb-0", &[statefulset.clone()]));
+        assert!(!is_statefulset_volume("data-postgresql-mydb-other-0", &[statefulset.clone()]));
+        assert!(!is_statefulset_volume("data-postgresql-mydb-", &[statefulset]));
+    }
+}
@@ -0,0 +1,485 @@
+use crate::cloud_provider::service::DatabaseType as DbType;
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::deploy_job::{await_job_termination, JobStatus};
+use crate::deployment_action::utils::forward_job_logs;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::io_models::database::{DatabaseInitScript, DatabaseInitScriptSource, DatabaseOptions};
+use crate::kubers_utils::{kube_create_from_resource, kube_delete_all_from_selector, KubeDeleteMode};
+use crate::models::database::{Database, DatabaseError, DatabaseMode, DatabaseType};
+use crate::models::types::CloudProvider;
+use crate::runtime::block_on;
+use k8s_openapi::api::batch::v1::{Job as K8sJob, JobSpec};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Container, EmptyDirVolumeSource, EnvVar, EnvVarSource, KeyToPath, PodSpec, PodTemplateSpec, Secret,
+    SecretKeySelector, SecretVolumeSource, Volume, VolumeMount,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::Api;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+const INIT_SCRIPTS_LABEL: &str = "qovery.com/database-init-scripts";
+const INIT_SCRIPTS_CONTAINER_NAME: &str = "init-scripts";
+const INLINE_SCRIPTS_DIR: &str = "/inline-scripts";
+const DOWNLOADED_SCRIPTS_DIR: &str = "/downloaded-scripts";
+const SCRIPTS_DOWNLOADER_IMAGE: &str = "public.ecr.aws/aws-cli/aws-cli:2.15.0";
+const INIT_SCRIPTS_TIMEOUT_IN_SECONDS: i64 = 3600;
+const PASSWORD_SECRET_KEY: &str = "password";
+
+/// Kubernetes resources needed to run the init scripts of a database
+struct InitScriptsResources {
+    secret: Secret,
+    job: K8sJob,
+}
+
+pub(super) fn init_scripts_selector(service_long_id: &Uuid) -> String {
+    format!("qovery.com/service-id={service_long_id},{INIT_SCRIPTS_LABEL}=true")
+}
+
+// Job name is reused as a label value by kubernetes, so it must fit in 63 characters
+fn init_scripts_resources_name(kube_name: &str) -> String {
+    let prefix: String = kube_name.chars().take(50).collect();
+    format!("{}-init-scripts", prefix.trim_end_matches('-'))
+}
+
+// Scripts are prefixed by their position in the payload, so they keep their order once on disk
+fn script_file_name(index: usize, script: &DatabaseInitScript) -> String {
+    format!("{:03}-{}", index + 1, script.name)
+}
+
+fn script_path(index: usize, script: &DatabaseInitScript) -> String {
+    let dir = match script.source {
+        DatabaseInitScriptSource::Inline { .. } => INLINE_SCRIPTS_DIR,
+        DatabaseInitScriptSource::ObjectStorage { .. } => DOWNLOADED_SCRIPTS_DIR,
+    };
+    format!("{}/{}", dir, script_file_name(index, script))
+}
+
+fn client_image(db_type: DbType) -> Option<&'static str> {
+    match db_type {
+        DbType::PostgreSQL => Some("public.ecr.aws/docker/library/postgres:16-alpine"),
+        DbType::MySQL => Some("public.ecr.aws/docker/library/mysql:8.0"),
+        DbType::MongoDB => Some("public.ecr.aws/docker/library/mongo:7.0"),
+        DbType::Redis => None,
+    }
+}
+
+/// Shell script running every init script with the database client, stopping at the first failure
+fn client_command(db_type: DbType, scripts: &[DatabaseInitScript]) -> String {
+    let mut command = "set -e\n".to_string();
+    for (index, script) in scripts.iter().enumerate() {
+        let path = script_path(index, script);
+        let run = match db_type {
+            DbType::PostgreSQL => {
+                format!("psql -v ON_ERROR_STOP=1 -h \"$DB_HOST\" -p \"$DB_PORT\" -U \"$DB_USER\" -d postgres -f {path}")
+            }
+            DbType::MySQL => format!("mysql -h \"$DB_HOST\" -P \"$DB_PORT\" -u \"$DB_USER\" < {path}"),
+            DbType::MongoDB => format!(
+                "mongosh --quiet --host \"$DB_HOST\" --port \"$DB_PORT\" -u \"$DB_USER\" -p \"$DB_PASSWORD\" --authenticationDatabase admin {path}"
+            ),
+            DbType::Redis => continue,
+        };
+        command.push_str(&format!("echo \"Running init script {}\"\n{}\n", script.name, run));
+    }
+    command
+}
+
+pub(super) fn secret_env_var(name: &str, secret_name: &str, key: &str) -> EnvVar {
+    EnvVar {
+        name: name.to_string(),
+        value_from: Some(EnvVarSource {
+            secret_key_ref: Some(SecretKeySelector {
+                name: Some(secret_name.to_string()),
+                key: key.to_string(),
+                optional: Some(false),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+pub(super) fn env_var(name: &str, value: &str) -> EnvVar {
+    EnvVar {
+        name: name.to_string(),
+        value: Some(value.to_string()),
+        ..Default::default()
+    }
+}
+
+fn init_scripts_resources(
+    name: &str,
+    labels: BTreeMap<String, String>,
+    db_type: DbType,
+    options: &DatabaseOptions,
+) -> Option<InitScriptsResources> {
+    let image = client_image(db_type)?;
+    let mut secret_data: BTreeMap<String, String> =
+        BTreeMap::from([(PASSWORD_SECRET_KEY.to_string(), options.password.clone())]);
+    let mut inline_items: Vec<KeyToPath> = vec![];
+    let mut downloaders: Vec<Container> = vec![];
+
+    for (index, script) in options.init_scripts.iter().enumerate() {
+        let file_name = script_file_name(index, script);
+        match &script.source {
+            DatabaseInitScriptSource::Inline { content } => {
+                secret_data.insert(file_name.clone(), content.clone());
+                inline_items.push(KeyToPath {
+                    key: file_name.clone(),
+                    path: file_name,
+                    mode: None,
+                });
+            }
+            DatabaseInitScriptSource::ObjectStorage {
+                bucket_name,
+                key,
+                region,
+                endpoint,
+                access_key_id,
+                secret_access_key,
+            } => {
+                let access_key_id_key = format!("{:03}-access-key-id", index + 1);
+                let secret_access_key_key = format!("{:03}-secret-access-key", index + 1);
+                secret_data.insert(access_key_id_key.clone(), access_key_id.clone());
+                secret_data.insert(secret_access_key_key.clone(), secret_access_key.clone());
+
+                let mut args = vec![
+                    "s3".to_string(),
+                    "cp".to_string(),
+                    format!("s3://{bucket_name}/{key}"),
+                    script_path(index, script),
+                ];
+                if let Some(endpoint) = endpoint {
+                    args.push("--endpoint-url".to_string());
+                    args.push(endpoint.clone());
+                }
+                downloaders.push(Container {
+                    name: format!("download-{:03}", index + 1),
+                    image: Some(SCRIPTS_DOWNLOADER_IMAGE.to_string()),
+                    command: Some(vec!["aws".to_string()]),
+                    args: Some(args),
+                    env: Some(vec![
+                        env_var("AWS_DEFAULT_REGION", region),
+                        secret_env_var("AWS_ACCESS_KEY_ID", name, &access_key_id_key),
+                        secret_env_var("AWS_SECRET_ACCESS_KEY", name, &secret_access_key_key),
+                    ]),
+                    volume_mounts: Some(vec![VolumeMount {
+                        name: "downloaded-scripts".to_string(),
+                        mount_path: DOWNLOADED_SCRIPTS_DIR.to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    let mut volumes: Vec<Volume> = vec![];
+    let mut volume_mounts: Vec<VolumeMount> = vec![];
+    if !inline_items.is_empty() {
+        volumes.push(Volume {
+            name: "inline-scripts".to_string(),
+            secret: Some(SecretVolumeSource {
+                secret_name: Some(name.to_string()),
+                items: Some(inline_items),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            name: "inline-scripts".to_string(),
+            mount_path: INLINE_SCRIPTS_DIR.to_string(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+    }
+    if !downloaders.is_empty() {
+        volumes.push(Volume {
+            name: "downloaded-scripts".to_string(),
+            empty_dir: Some(EmptyDirVolumeSource::default()),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            name: "downloaded-scripts".to_string(),
+            mount_path: DOWNLOADED_SCRIPTS_DIR.to_string(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+    }
+
+    let password_env_name = match db_type {
+        DbType::PostgreSQL => "PGPASSWORD",
+        DbType::MySQL => "MYSQL_PWD",
+        DbType::MongoDB | DbType::Redis => "DB_PASSWORD",
+    };
+    let metadata = ObjectMeta {
+        name: Some(name.to_string()),
+        labels: Some(labels.clone()),
+        ..Default::default()
+    };
+
+    let secret = Secret {
+        metadata: metadata.clone(),
+        string_data: Some(secret_data),
+        ..Default::default()
+    };
+    let job = K8sJob {
+        metadata,
+        spec: Some(JobSpec {
+            // scripts are not expected to be idempotent, never run them twice
+            backoff_limit: Some(0),
+            active_deadline_seconds: Some(INIT_SCRIPTS_TIMEOUT_IN_SECONDS),
+            template: PodTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(labels),
+                    ..Default::default()
+                }),
+                spec: Some(PodSpec {
+                    restart_policy: Some("Never".to_string()),
+                    init_containers: (!downloaders.is_empty()).then_some(downloaders),
+                    containers: vec![Container {
+                        name: INIT_SCRIPTS_CONTAINER_NAME.to_string(),
+                        image: Some(image.to_string()),
+                        command: Some(vec![
+                            "sh".to_string(),
+                            "-c".to_string(),
+                            client_command(db_type, &options.init_scripts),
+                        ]),
+                        env: Some(vec![
+                            env_var("DB_HOST", &options.host),
+                            env_var("DB_PORT", &options.port.to_string()),
+                            env_var("DB_USER", &options.login),
+                            secret_env_var(password_env_name, name, PASSWORD_SECRET_KEY),
+                        ]),
+                        volume_mounts: Some(volume_mounts),
+                        ..Default::default()
+                    }],
+                    volumes: Some(volumes),
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        status: None,
+    };
+
+    Some(InitScriptsResources { secret, job })
+}
+
+fn delete_init_scripts_job(target: &DeploymentTarget, selector: &str) -> Result<(), DatabaseError> {
+    let namespace = target.environment.namespace();
+    block_on(kube_delete_all_from_selector::<K8sJob>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    ))
+    .and_then(|_| {
+        block_on(kube_delete_all_from_selector::<Secret>(
+            &target.kube,
+            selector,
+            namespace,
+            KubeDeleteMode::Normal,
+        ))
+    })
+    .map_err(|err| DatabaseError::InitScriptsFailed(format!("Cannot clean up init scripts job: {err}")))
+}
+
+/// Run the init scripts of the database with a kubernetes job, once the database is ready.
+/// A config map is left behind on success, so the scripts only run on the first creation of the database.
+pub(super) fn run_database_init_scripts<C, M, T>(
+    db: &Database<C, M, T>,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>>
+where
+    C: CloudProvider,
+    M: DatabaseMode,
+    T: DatabaseType<C, M, DatabaseOptions = DatabaseOptions>,
+{
+    if db.options.init_scripts.is_empty() || target.is_dry_run_deploy {
+        return Ok(());
+    }
+
+    let to_engine_error = |err: DatabaseError| Box::new(EngineError::new_database_error(event_details.clone(), err));
+    let namespace = target.environment.namespace();
+    let name = init_scripts_resources_name(&db.kube_name);
+    let selector = init_scripts_selector(&db.long_id);
+
+    let config_map_api: Api<ConfigMap> = Api::namespaced(target.kube.clone(), namespace);
+    match block_on(config_map_api.get_opt(&name)) {
+        Ok(Some(_)) => return Ok(()),
+        Ok(None) => {}
+        Err(err) => {
+            return Err(to_engine_error(DatabaseError::InitScriptsFailed(format!(
+                "Cannot check if init scripts already ran: {err}"
+            ))))
+        }
+    }
+
+    let labels = BTreeMap::from([
+        ("qovery.com/service-id".to_string(), db.long_id.to_string()),
+        (INIT_SCRIPTS_LABEL.to_string(), "true".to_string()),
+    ]);
+    let Some(resources) = init_scripts_resources(&name, labels.clone(), T::db_type(), &db.options) else {
+        return Err(to_engine_error(DatabaseError::InitScriptsFailed(format!(
+            "Init scripts are not supported for {:?} databases",
+            T::db_type()
+        ))));
+    };
+
+    // leftovers of a previous failed attempt
+    delete_init_scripts_job(target, &selector).map_err(to_engine_error)?;
+
+    logger.info(format!(
+        "📜 Running {} init script(s) of database {}",
+        db.options.init_scripts.len(),
+        db.name
+    ));
+    block_on(kube_create_from_resource(&target.kube, namespace, resources.secret))
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, resources.job)))
+        .map_err(|err| to_engine_error(DatabaseError::InitScriptsFailed(err.to_string())))?;
+
+    let job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), namespace);
+    let timeout = Duration::from_secs(INIT_SCRIPTS_TIMEOUT_IN_SECONDS as u64 + 60);
+    let job_status = await_job_termination(job_api, &name, timeout, event_details)?;
+    if let Err(err) = forward_job_logs(target, &name, logger) {
+        logger.warning(format!("Cannot retrieve logs of init scripts: {err}"));
+    }
+
+    match job_status {
+        JobStatus::Success => {}
+        JobStatus::Failure { reason, message } => {
+            return Err(to_engine_error(DatabaseError::InitScriptsFailed(format!("{reason} {message}"))))
+        }
+        JobStatus::Running | JobStatus::NotRunning => {
+            return Err(to_engine_error(DatabaseError::InitScriptsFailed(
+                "Init scripts job did not terminate in time".to_string(),
+            )))
+        }
+    }
+
+    delete_init_scripts_job(target, &selector).map_err(to_engine_error)?;
+    let marker = ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(name),
+            labels: Some(labels),
+            ..Default::default()
+        },
+        data: Some(BTreeMap::from([(
+            "scripts".to_string(),
+            db.options
+                .init_scripts
+                .iter()
+                .map(|script| script.name.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )])),
+        ..Default::default()
+    };
+    block_on(kube_create_from_resource(&target.kube, namespace, marker))
+        .map_err(|err| to_engine_error(DatabaseError::InitScriptsFailed(err.to_string())))?;
+
+    logger.info("✅ Init scripts of the database ran successfully".to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_models::database::{DatabaseAdvancedSettings, DatabaseMode as IoDatabaseMode};
+
+    fn options(init_scripts: Vec<DatabaseInitScript>) -> DatabaseOptions {
+        DatabaseOptions {
+            login: "superuser".to_string(),
+            password: "p@ssword".to_string(),
+            host: "my-db.namespace.svc.cluster.local".to_string(),
+            port: 5432,
+            mode: IoDatabaseMode::CONTAINER,
+            disk_size_in_gib: 10,
+            database_disk_type: "gp2".to_string(),
+            encrypt_disk: false,
+            activate_high_availability: false,
+            activate_backups: false,
+            publicly_accessible: false,
+            advanced_settings: DatabaseAdvancedSettings::default(),
+            kms_key_arn: None,
+            init_scripts,
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
+        }
+    }
+
+    fn scripts() -> Vec<DatabaseInitScript> {
+        vec![
+            DatabaseInitScript {
+                name: "schema.sql".to_string(),
+                source: DatabaseInitScriptSource::Inline {
+                    content: "CREATE TABLE users (id INT);".to_string(),
+                },
+            },
+            DatabaseInitScript {
+                name: "seed.sql".to_string(),
+                source: DatabaseInitScriptSource::ObjectStorage {
+                    bucket_name: "my-bucket".to_string(),
+                    key: "seeds/seed.sql".to_string(),
+                    region: "eu-west-3".to_string(),
+                    endpoint: None,
+                    access_key_id: "AKIA".to_string(),
+                    secret_access_key: "secret".to_string(),
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn test_init_scripts_resources_name() {
+        assert_eq!(init_scripts_resources_name("postgresql-abc"), "postgresql-abc-init-scripts");
+        assert!(init_scripts_resources_name(&"a".repeat(80)).len() <= 63);
+    }
+
+    #[test]
+    fn test_client_command_keeps_scripts_order() {
+        let command = client_command(DbType::PostgreSQL, &scripts());
+        let schema = command.find("-f /inline-scripts/001-schema.sql").unwrap();
+        let seed = command.find("-f /downloaded-scripts/002-seed.sql").unwrap();
+        assert!(command.starts_with("set -e"));
+        assert!(schema < seed);
+    }
+
+    #[test]
+    fn test_init_scripts_resources() {
+        let resources =
+            init_scripts_resources("pg-init-scripts", BTreeMap::new(), DbType::PostgreSQL, &options(scripts()))
+                .unwrap();
+
+        let secret_data = resources.secret.string_data.unwrap();
+        assert_eq!(secret_data.get("001-schema.sql").unwrap(), "CREATE TABLE users (id INT);");
+        assert_eq!(secret_data.get("002-access-key-id").unwrap(), "AKIA");
+        assert_eq!(secret_data.get(PASSWORD_SECRET_KEY).unwrap(), "p@ssword");
+
+        let pod_spec = resources.job.spec.unwrap().template.spec.unwrap();
+        let downloaders = pod_spec.init_containers.unwrap();
+        assert_eq!(downloaders.len(), 1);
+        assert_eq!(
+            downloaders[0].args.as_ref().unwrap()[2..],
+            ["s3://my-bucket/seeds/seed.sql", "/downloaded-scripts/002-seed.sql"]
+        );
+        assert_eq!(pod_spec.volumes.unwrap().len(), 2);
+        // password is never written in the job spec
+        assert!(pod_spec.containers[0]
+            .env
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|env| env.value.as_deref() != Some("p@ssword")));
+
+        assert!(
+            init_scripts_resources("redis-init-scripts", BTreeMap::new(), DbType::Redis, &options(scripts())).is_none()
+        );
+    }
+}
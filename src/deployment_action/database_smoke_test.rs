@@ -0,0 +1,390 @@
+use crate::cloud_provider::service::DatabaseType as DbType;
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::database_init_scripts::{env_var, secret_env_var};
+use crate::deployment_action::deploy_job::{await_job_termination, JobStatus};
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::io_models::database::DatabaseOptions;
+use crate::kubers_utils::{
+    kube_create_from_resource, kube_delete_all_from_selector, kube_get_resources_by_selector, KubeDeleteMode,
+};
+use crate::models::database::{Database, DatabaseError, DatabaseMode, DatabaseType};
+use crate::models::types::CloudProvider;
+use crate::runtime::block_on;
+use k8s_openapi::api::batch::v1::{Job as K8sJob, JobSpec};
+use k8s_openapi::api::core::v1::{Container, Pod, PodSpec, PodTemplateSpec, Secret};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::LogParams;
+use kube::Api;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+const SMOKE_TEST_LABEL: &str = "qovery.com/database-smoke-test";
+const SMOKE_TEST_CONTAINER_NAME: &str = "smoke-test";
+const SMOKE_TEST_TIMEOUT_IN_SECONDS: i64 = 180;
+const SMOKE_TEST_ATTEMPTS: u32 = 5;
+const SMOKE_TEST_LOGS_MAX_LINES: usize = 20;
+const PASSWORD_SECRET_KEY: &str = "password";
+
+/// Why the database cannot be reached by the applications, guessed from the output of its client
+#[derive(Debug, PartialEq, Eq)]
+enum SmokeTestFailure {
+    Authentication,
+    Network,
+    Unknown,
+}
+
+fn smoke_test_selector(service_long_id: &Uuid) -> String {
+    format!("qovery.com/service-id={service_long_id},{SMOKE_TEST_LABEL}=true")
+}
+
+// Job name is reused as a label value by kubernetes, so it must fit in 63 characters
+fn smoke_test_resources_name(kube_name: &str) -> String {
+    let prefix: String = kube_name.chars().take(51).collect();
+    format!("{}-smoke-test", prefix.trim_end_matches('-'))
+}
+
+fn client_image(db_type: DbType) -> &'static str {
+    match db_type {
+        DbType::PostgreSQL => "public.ecr.aws/docker/library/postgres:16-alpine",
+        DbType::MySQL => "public.ecr.aws/docker/library/mysql:8.0",
+        DbType::MongoDB => "public.ecr.aws/docker/library/mongo:7.0",
+        DbType::Redis => "public.ecr.aws/docker/library/redis:7.2-alpine",
+    }
+}
+
+/// Managed redis and DocumentDB only accept TLS connections, unless TLS has been disabled with a parameter
+fn requires_tls(db_type: DbType, is_managed: bool, options: &DatabaseOptions) -> bool {
+    is_managed
+        && match db_type {
+            DbType::Redis => true,
+            DbType::MongoDB => options.parameters.get("tls").map(|tls| tls.as_str()) != Some("disabled"),
+            DbType::PostgreSQL | DbType::MySQL => false,
+        }
+}
+
+/// Shell script sending the simplest query of the protocol, retried as the database service may take a few
+/// seconds to route to ready pods
+fn client_command(db_type: DbType, tls: bool) -> String {
+    let query = match db_type {
+        DbType::PostgreSQL => {
+            "psql -h \"$DB_HOST\" -p \"$DB_PORT\" -U \"$DB_USER\" -d postgres -tA -c 'SELECT 1'".to_string()
+        }
+        DbType::MySQL => {
+            "mysql --connect-timeout=10 -h \"$DB_HOST\" -P \"$DB_PORT\" -u \"$DB_USER\" -e 'SELECT 1'".to_string()
+        }
+        DbType::MongoDB => format!(
+            "mongosh --quiet --host \"$DB_HOST\" --port \"$DB_PORT\" -u \"$DB_USER\" -p \"$DB_PASSWORD\" --authenticationDatabase admin{} --eval 'db.adminCommand({{ ismaster: 1 }}).ok'",
+            if tls { " --tls --tlsAllowInvalidCertificates" } else { "" }
+        ),
+        // redis-cli exits successfully on error replies, so the reply itself is checked
+        DbType::Redis => format!(
+            "reply=$(redis-cli -h \"$DB_HOST\" -p \"$DB_PORT\"{} PING 2>&1); echo \"$reply\"; [ \"$reply\" = \"PONG\" ]",
+            if tls { " --tls --insecure" } else { "" }
+        ),
+    };
+
+    format!("for attempt in $(seq 1 {SMOKE_TEST_ATTEMPTS}); do\n  {query} && exit 0\n  sleep 5\ndone\nexit 1\n")
+}
+
+fn smoke_test_failure(logs: &str) -> SmokeTestFailure {
+    let logs = logs.to_lowercase();
+    let is_in_logs = |patterns: &[&str]| patterns.iter().any(|pattern| logs.contains(pattern));
+
+    if is_in_logs(&[
+        "password authentication failed",
+        "access denied",
+        "authentication failed",
+        "wrongpass",
+        "noauth",
+        "invalid password",
+        "invalid username-password pair",
+    ]) {
+        SmokeTestFailure::Authentication
+    } else if is_in_logs(&[
+        "could not translate host name",
+        "name or service not known",
+        "unknown mysql server host",
+        "getaddrinfo",
+        "connection refused",
+        "econnrefused",
+        "could not connect",
+        "can't connect",
+        "no route to host",
+        "timed out",
+        "timeout",
+    ]) {
+        SmokeTestFailure::Network
+    } else {
+        SmokeTestFailure::Unknown
+    }
+}
+
+fn smoke_test_diagnostic(db_type: DbType, options: &DatabaseOptions, logs: &str) -> String {
+    let cause = match smoke_test_failure(logs) {
+        SmokeTestFailure::Authentication => format!(
+            "The database is reachable but refuses the credentials of user `{}`. The password may have been changed outside of Qovery, or the database may not have been initialized with the generated credentials.",
+            options.login
+        ),
+        SmokeTestFailure::Network => format!(
+            "The database cannot be reached at `{}:{}` from the cluster. Check the database service and DNS record exist, and that no network policy or security group blocks the port.",
+            options.host, options.port
+        ),
+        SmokeTestFailure::Unknown => format!(
+            "{:?} client cannot query the database at `{}:{}`.",
+            db_type, options.host, options.port
+        ),
+    };
+
+    let lines: Vec<&str> = logs.lines().filter(|line| !line.trim().is_empty()).collect();
+    let last_lines = lines[lines.len().saturating_sub(SMOKE_TEST_LOGS_MAX_LINES)..].join("\n");
+    if last_lines.is_empty() {
+        cause
+    } else {
+        format!("{cause}\nClient output:\n{last_lines}")
+    }
+}
+
+fn smoke_test_resources(
+    name: &str,
+    labels: BTreeMap<String, String>,
+    db_type: DbType,
+    tls: bool,
+    options: &DatabaseOptions,
+) -> (Secret, K8sJob) {
+    let password_env_name = match db_type {
+        DbType::PostgreSQL => "PGPASSWORD",
+        DbType::MySQL => "MYSQL_PWD",
+        DbType::MongoDB => "DB_PASSWORD",
+        DbType::Redis => "REDISCLI_AUTH",
+    };
+    let metadata = ObjectMeta {
+        name: Some(name.to_string()),
+        labels: Some(labels.clone()),
+        ..Default::default()
+    };
+
+    let secret = Secret {
+        metadata: metadata.clone(),
+        string_data: Some(BTreeMap::from([(PASSWORD_SECRET_KEY.to_string(), options.password.clone())])),
+        ..Default::default()
+    };
+    let job = K8sJob {
+        metadata,
+        spec: Some(JobSpec {
+            // attempts are retried by the command itself, a new pod would only slow down the diagnostic
+            backoff_limit: Some(0),
+            active_deadline_seconds: Some(SMOKE_TEST_TIMEOUT_IN_SECONDS),
+            template: PodTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(labels),
+                    ..Default::default()
+                }),
+                spec: Some(PodSpec {
+                    restart_policy: Some("Never".to_string()),
+                    containers: vec![Container {
+                        name: SMOKE_TEST_CONTAINER_NAME.to_string(),
+                        image: Some(client_image(db_type).to_string()),
+                        command: Some(vec!["sh".to_string(), "-c".to_string(), client_command(db_type, tls)]),
+                        env: Some(vec![
+                            env_var("DB_HOST", &options.host),
+                            env_var("DB_PORT", &options.port.to_string()),
+                            env_var("DB_USER", &options.login),
+                            env_var("PGCONNECT_TIMEOUT", "10"),
+                            secret_env_var(password_env_name, name, PASSWORD_SECRET_KEY),
+                        ]),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        status: None,
+    };
+
+    (secret, job)
+}
+
+fn delete_smoke_test_job(target: &DeploymentTarget, selector: &str) -> Result<(), DatabaseError> {
+    let namespace = target.environment.namespace();
+    block_on(kube_delete_all_from_selector::<K8sJob>(
+        &target.kube,
+        selector,
+        namespace,
+        KubeDeleteMode::Normal,
+    ))
+    .and_then(|_| {
+        block_on(kube_delete_all_from_selector::<Secret>(
+            &target.kube,
+            selector,
+            namespace,
+            KubeDeleteMode::Normal,
+        ))
+    })
+    .map_err(|err| DatabaseError::SmokeTestFailed(format!("Cannot clean up smoke test job: {err}")))
+}
+
+fn smoke_test_logs(target: &DeploymentTarget, job_name: &str) -> String {
+    let namespace = target.environment.namespace();
+    let Ok(pods) = block_on(kube_get_resources_by_selector::<Pod>(
+        &target.kube,
+        namespace,
+        &format!("job-name={job_name}"),
+    )) else {
+        return String::new();
+    };
+
+    let pod_api: Api<Pod> = Api::namespaced(target.kube.clone(), namespace);
+    let log_params = LogParams {
+        container: Some(SMOKE_TEST_CONTAINER_NAME.to_string()),
+        ..Default::default()
+    };
+    pods.items
+        .iter()
+        .filter_map(|pod| pod.metadata.name.as_deref())
+        .filter_map(|pod_name| block_on(pod_api.logs(pod_name, &log_params)).ok())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Connect to the database the way applications do, with its generated credentials and from a pod of the
+/// environment namespace. Pods being ready does not mean the credentials or the network path are valid.
+pub(super) fn run_database_smoke_test<C, M, T>(
+    db: &Database<C, M, T>,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>>
+where
+    C: CloudProvider,
+    M: DatabaseMode,
+    T: DatabaseType<C, M, DatabaseOptions = DatabaseOptions>,
+{
+    if !db.options.advanced_settings.smoke_test_enabled || target.is_dry_run_deploy {
+        return Ok(());
+    }
+
+    let to_engine_error = |err: DatabaseError| Box::new(EngineError::new_database_error(event_details.clone(), err));
+    let namespace = target.environment.namespace();
+    let name = smoke_test_resources_name(&db.kube_name);
+    let selector = smoke_test_selector(&db.long_id);
+    let labels = BTreeMap::from([
+        ("qovery.com/service-id".to_string(), db.long_id.to_string()),
+        (SMOKE_TEST_LABEL.to_string(), "true".to_string()),
+    ]);
+    let tls = requires_tls(T::db_type(), M::is_managed(), &db.options);
+    let (secret, job) = smoke_test_resources(&name, labels, T::db_type(), tls, &db.options);
+
+    // leftovers of a previous interrupted deployment
+    delete_smoke_test_job(target, &selector).map_err(to_engine_error)?;
+
+    logger.info(format!("🔌 Checking applications can connect to database {}", db.name));
+    block_on(kube_create_from_resource(&target.kube, namespace, secret))
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, job)))
+        .map_err(|err| to_engine_error(DatabaseError::SmokeTestFailed(err.to_string())))?;
+
+    let job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), namespace);
+    let timeout = Duration::from_secs(SMOKE_TEST_TIMEOUT_IN_SECONDS as u64 + 60);
+    let job_status = await_job_termination(job_api, &name, timeout, event_details)?;
+    let failure = match job_status {
+        JobStatus::Success => None,
+        JobStatus::Failure { reason, message } => Some(format!("{reason} {message}")),
+        JobStatus::Running | JobStatus::NotRunning => Some("Smoke test job did not terminate in time".to_string()),
+    };
+
+    if let Some(failure) = failure {
+        let logs = smoke_test_logs(target, &name);
+        let diagnostic = if logs.trim().is_empty() {
+            failure
+        } else {
+            smoke_test_diagnostic(T::db_type(), &db.options, &logs)
+        };
+        if let Err(err) = delete_smoke_test_job(target, &selector) {
+            logger.warning(err.to_string());
+        }
+        return Err(to_engine_error(DatabaseError::SmokeTestFailed(diagnostic)));
+    }
+
+    delete_smoke_test_job(target, &selector).map_err(to_engine_error)?;
+    logger.info("✅ Database accepts connections with its credentials".to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_models::database::{DatabaseAdvancedSettings, DatabaseMode as IoDatabaseMode};
+
+    fn options() -> DatabaseOptions {
+        DatabaseOptions {
+            login: "superuser".to_string(),
+            password: "p@ssword".to_string(),
+            host: "my-db.namespace.svc.cluster.local".to_string(),
+            port: 6379,
+            mode: IoDatabaseMode::MANAGED,
+            disk_size_in_gib: 10,
+            database_disk_type: "gp2".to_string(),
+            encrypt_disk: false,
+            activate_high_availability: false,
+            activate_backups: false,
+            publicly_accessible: false,
+            advanced_settings: DatabaseAdvancedSettings::default(),
+            kms_key_arn: None,
+            init_scripts: vec![],
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_smoke_test_failure() {
+        assert_eq!(
+            smoke_test_failure("psql: error: FATAL:  password authentication failed for user \"superuser\""),
+            SmokeTestFailure::Authentication
+        );
+        assert_eq!(
+            smoke_test_failure("AUTH failed: WRONGPASS invalid username-password pair"),
+            SmokeTestFailure::Authentication
+        );
+        assert_eq!(
+            smoke_test_failure("ERROR 2005 (HY000): Unknown MySQL server host 'my-db' (-2)"),
+            SmokeTestFailure::Network
+        );
+        assert_eq!(
+            smoke_test_failure("MongoServerSelectionError: connect ECONNREFUSED 10.0.0.1:27017"),
+            SmokeTestFailure::Network
+        );
+        assert_eq!(smoke_test_failure("segmentation fault"), SmokeTestFailure::Unknown);
+    }
+
+    #[test]
+    fn test_requires_tls() {
+        let mut options = options();
+        assert!(requires_tls(DbType::Redis, true, &options));
+        assert!(!requires_tls(DbType::Redis, false, &options));
+        assert!(requires_tls(DbType::MongoDB, true, &options));
+        options.parameters.insert("tls".to_string(), "disabled".to_string());
+        assert!(!requires_tls(DbType::MongoDB, true, &options));
+        assert!(!requires_tls(DbType::PostgreSQL, true, &options));
+    }
+
+    #[test]
+    fn test_smoke_test_resources() {
+        let (secret, job) = smoke_test_resources("redis-smoke-test", BTreeMap::new(), DbType::Redis, true, &options());
+        assert_eq!(secret.string_data.unwrap().get(PASSWORD_SECRET_KEY).unwrap(), "p@ssword");
+
+        let container = &job.spec.unwrap().template.spec.unwrap().containers[0];
+        assert!(container.command.as_ref().unwrap()[2].contains("--tls --insecure PING"));
+        // password is never written in the job spec
+        assert!(container
+            .env
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|env| env.value.as_deref() != Some("p@ssword")));
+        assert!(smoke_test_resources_name(&"a".repeat(80)).len() <= 63);
+    }
+}
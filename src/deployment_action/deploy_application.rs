@@ -1,21 +1,42 @@
+use crate::build_platform::Image;
 use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
 use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::command::CommandKiller;
+use crate::cmd::cosign::Cosign;
+use crate::cmd::docker::ContainerImage;
+use crate::container_registry::image_retention::apply_image_retention_policy;
+use crate::container_registry::replication::replicate_image;
+use crate::deployment_action::application_migrations::run_application_migrations;
+use crate::deployment_action::blue_green::run_blue_green_deployment;
+use crate::deployment_action::canary::run_canary_deployment;
+use crate::deployment_action::custom_metadata::apply_custom_metadata;
 use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::env_vars_update::{insert_spec_checksum, update_env_vars_only_if_possible};
+use crate::deployment_action::lifecycle_hooks::{run_lifecycle_hook, LifecycleHookKind};
 use crate::deployment_action::pause_service::PauseServiceAction;
+use crate::deployment_action::readiness_gates::await_readiness_gates;
+use crate::deployment_action::smoke_test::{rollback_helm_release, run_smoke_test};
+use crate::deployment_action::utils::{
+    check_external_secrets_supported, check_hpa_custom_metrics_supported, check_keda_triggers_supported,
+};
 use crate::deployment_action::DeploymentAction;
+use crate::deployment_hook::DeploymentHookStage;
 use crate::deployment_report::application::reporter::ApplicationDeploymentReporter;
 use crate::deployment_report::execute_long_deployment;
 use crate::errors::{CommandError, EngineError};
-use crate::events::{EngineEvent, EnvironmentStep, EventMessage, Stage};
-use crate::kubers_utils::{kube_delete_all_from_selector, KubeDeleteMode};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
+use crate::io_models::{ConfigReloadStrategy, DeploymentStrategy};
+use crate::kubers_utils::{kube_annotate_pods_by_selector, kube_delete_all_from_selector, KubeDeleteMode};
 use crate::models::application::{get_application_with_invalid_storage_size, Application, ApplicationService};
 use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::models::utils::CONFIG_CHECKSUM_ANNOTATION;
 use crate::runtime::block_on;
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
 
 use crate::cloud_provider::utilities::update_pvcs;
 use crate::deployment_action::restart_service::RestartServiceAction;
+use crate::deployment_action::statefulset_partition::stage_statefulset_update;
 use crate::deployment_report::logger::EnvProgressLogger;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -26,8 +47,8 @@ where
     Application<T>: ToTeraContext,
 {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
-        let long_task = |_logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
-            let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        let long_task = |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
             // If the service have been paused, we must ensure we un-pause it first as hpa will not kick in
             let _ = PauseServiceAction::new(
                 self.kube_label_selector(),
@@ -60,6 +81,63 @@ where
                 )),
             };
 
+            check_keda_triggers_supported(
+                &self.advanced_settings().hpa_keda_triggers,
+                self.name(),
+                target,
+                &event_details,
+            )?;
+            check_hpa_custom_metrics_supported(
+                &self.advanced_settings().hpa_custom_metrics,
+                self.name(),
+                target,
+                &event_details,
+            )?;
+            check_external_secrets_supported(
+                &self.advanced_settings().deployment_external_secrets,
+                self.name(),
+                target,
+                &event_details,
+            )?;
+            let mut tera_context = self.to_tera_context(target)?;
+            insert_spec_checksum(&mut tera_context);
+            if self.advanced_settings().deployment_env_vars_fast_path_enabled {
+                if let Some(env_vars_update) = update_env_vars_only_if_possible(
+                    &self.kube_label_selector(),
+                    self.is_stateful(),
+                    &tera_context,
+                    logger,
+                    &event_details,
+                    target,
+                )? {
+                    // the helm release is untouched, only the environment variables have to be rolled back
+                    return run_smoke_test(
+                        self,
+                        self.kube_name(),
+                        |logger| match env_vars_update.rollback(&event_details, target) {
+                            Ok(()) => logger.info("⏪ Previous environment variables are restored".to_string()),
+                            Err(err) => logger.warning(format!(
+                                "Cannot restore the previous environment variables: {}",
+                                err.user_log_message()
+                            )),
+                        },
+                        logger,
+                        &event_details,
+                        target,
+                    );
+                }
+            }
+
+            // Nothing is rolled out while the dependencies of the application are down
+            target
+                .deployment_hooks
+                .run_stage(DeploymentHookStage::Readiness, &event_details, || {
+                    await_readiness_gates(self, logger, &event_details, target)
+                })?;
+
+            // The new version must not receive traffic before its migrations succeed
+            run_application_migrations(self, logger, &event_details, target)?;
+
             let chart = ChartInfo {
                 name: self.helm_release_name(),
                 path: self.workspace_directory().to_string(),
@@ -72,18 +150,134 @@ where
 
             let helm = HelmDeployment::new(
                 event_details.clone(),
-                self.to_tera_context(target)?,
+                tera_context,
                 PathBuf::from(self.helm_chart_dir()),
                 None,
                 chart,
             );
 
-            helm.on_create(target)?;
+            // Only images signed at build time with the key of the cluster are deployed
+            if let Some(image_signing_key) = &target.kubernetes.advanced_settings().registry_image_signing_key {
+                verify_image_signature(&self.build().image, image_signing_key, logger, &event_details, target)?;
+            }
+
+            // Applications with storages run as statefulsets, their new version cannot run next to the stable one
+            let blue_green_switch = match self.advanced_settings().deployment_strategy {
+                _ if self.is_stateful() => None,
+                DeploymentStrategy::RollingUpdate => None,
+                DeploymentStrategy::Canary => {
+                    run_canary_deployment(self, &helm, logger, &event_details, target)?;
+                    None
+                }
+                DeploymentStrategy::BlueGreen => {
+                    run_blue_green_deployment(self, &helm, logger, &event_details, target)?
+                }
+            };
+            // new version of a blue/green deployment passed its smoke test before traffic was switched to it
+            let smoke_tested = blue_green_switch.is_some();
+
+            match (helm.on_create(target), blue_green_switch) {
+                (Ok(()), Some(switch)) => switch.finish(target, logger, &event_details)?,
+                (Ok(()), None) => {}
+                (Err(err), Some(switch)) => {
+                    switch.rollback(target, logger);
+                    return Err(err);
+                }
+                (Err(err), None) => return Err(err),
+            }
+
+            if self.is_stateful() {
+                stage_statefulset_update(
+                    self.kube_label_selector(),
+                    &self.advanced_settings().statefulset_update_strategy_type,
+                    self.advanced_settings()
+                        .statefulset_update_strategy_rolling_update_partition,
+                    logger,
+                    &event_details,
+                    target,
+                )?;
+            }
+
+            if !smoke_tested {
+                run_smoke_test(
+                    self,
+                    self.kube_name(),
+                    |logger| rollback_helm_release(&helm.helm_chart, logger, target),
+                    logger,
+                    &event_details,
+                    target,
+                )?;
+            }
+
+            if self.advanced_settings().deployment_config_reload_strategy != ConfigReloadStrategy::RollingRestart {
+                // Pods are not restarted when mounted files change, the annotation lets them know they have to reload
+                if let Err(err) = block_on(kube_annotate_pods_by_selector(
+                    &target.kube,
+                    target.environment.namespace(),
+                    &self.kube_label_selector(),
+                    CONFIG_CHECKSUM_ANNOTATION,
+                    &self.config_checksum(),
+                )) {
+                    target.kubernetes.logger().log(EngineEvent::Warning(
+                        event_details.clone(),
+                        EventMessage::new(
+                            "Cannot update config checksum of running pods".to_string(),
+                            Some(err.to_string()),
+                        ),
+                    ));
+                }
+            }
+
+            apply_custom_metadata(target, &self.kube_label_selector(), self.custom_metadata(), &event_details);
+
+            // Clusters of the other regions pull the image from their own region registry instead of rebuilding it
+            let replication_regions = &target.kubernetes.advanced_settings().registry_replication_regions;
+            match replicate_image(target.container_registry, &self.build().image, replication_regions) {
+                Ok(Some(replications)) => {
+                    for replication in replications {
+                        logger.info(format!("🌍 Image {replication}"));
+                    }
+                }
+                Ok(None) if !replication_regions.is_empty() => logger.warning(
+                    "Image is not replicated, the registry of the cluster cannot replicate its images".to_string(),
+                ),
+                Ok(None) => {}
+                Err(err) => logger.warning(format!("Cannot replicate the image: {err}")),
+            }
+
+            // Old images are only cleaned up once the new version runs, a failing cleanup does not fail the deployment
+            match apply_image_retention_policy(
+                target.container_registry,
+                &self.build().image,
+                &self.advanced_settings().image_retention_policy(),
+            ) {
+                Ok(0) => {}
+                Ok(deleted_images) => logger.info(format!(
+                    "🧹 Deleted {deleted_images} image(s) out of the retention policy of the application"
+                )),
+                Err(err) => logger.warning(format!("Cannot apply the image retention policy: {err}")),
+            }
 
             Ok(())
         };
 
-        execute_long_deployment(ApplicationDeploymentReporter::new(self, target, Action::Create), long_task)
+        execute_long_deployment(
+            ApplicationDeploymentReporter::new(self, target, Action::Create),
+            |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+                long_task(logger)?;
+                run_lifecycle_hook(
+                    self,
+                    LifecycleHookKind::PostCreate,
+                    self.lifecycle_hooks().post_create.as_ref(),
+                    &[],
+                    &self.build().image.full_image_name_with_tag(),
+                    &self.advanced_settings().security_service_account_name,
+                    logger,
+                    &event_details,
+                    target,
+                )
+            },
+        )
     }
 
     fn on_pause(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
@@ -106,6 +300,18 @@ where
         execute_long_deployment(
             ApplicationDeploymentReporter::new(self, target, Action::Delete),
             |logger: &EnvProgressLogger| {
+                run_lifecycle_hook(
+                    self,
+                    LifecycleHookKind::PreDelete,
+                    self.lifecycle_hooks().pre_delete.as_ref(),
+                    &[],
+                    &self.build().image.full_image_name_with_tag(),
+                    &self.advanced_settings().security_service_account_name,
+                    logger,
+                    &event_details,
+                    target,
+                )?;
+
                 let chart = ChartInfo {
                     name: self.helm_release_name(),
                     namespace: HelmChartNamespaces::Custom,
@@ -141,8 +347,8 @@ where
                             CommandError::new_from_safe_message(err.to_string()),
                         )));
                     }
-                    // Trying to delete PVCs using old labels
-                    // TODO(benjaminch): should be removed once PVCs are migrated to new labels
+                    // Claims of applications are still created from the legacy template, with the old labels only.
+                    // The ones of the other services are labelled by the cluster state migrations.
                     if let Err(err) = block_on(kube_delete_all_from_selector::<PersistentVolumeClaim>(
                         &target.kube,
                         &self.kube_legacy_label_selector(),
@@ -191,3 +397,30 @@ where
         )
     }
 }
+
+fn verify_image_signature(
+    image: &Image,
+    image_signing_key: &str,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    if target.is_dry_run_deploy {
+        return Ok(());
+    }
+
+    let image = ContainerImage::new(image.registry_url.clone(), image.name(), vec![image.tag.clone()]);
+    let cosign = Cosign::new(image_signing_key.to_string(), target.docker.config_path());
+    cosign
+        .verify(&image, &CommandKiller::from_cancelable(target.should_abort))
+        .map_err(|err| {
+            Box::new(EngineError::new_image_signature_verification_failed(
+                event_details.clone(),
+                image.image_name(),
+                err,
+            ))
+        })?;
+    logger.info(format!("🔏 Signature of image {} verified", image.image_name()));
+
+    Ok(())
+}
@@ -2,21 +2,27 @@ use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
 use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::DeploymentTarget;
 use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::env_vars_update::{insert_spec_checksum, update_env_vars_only_if_possible};
+use crate::deployment_action::lifecycle_hooks::{run_lifecycle_hook, LifecycleHookKind};
 use crate::deployment_action::pause_service::PauseServiceAction;
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::application::reporter::ApplicationDeploymentReporter;
 use crate::deployment_report::{execute_long_deployment, DeploymentTaskImpl};
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EngineEvent, EnvironmentStep, EventMessage, Stage};
-use crate::kubers_utils::{kube_delete_all_from_selector, KubeDeleteMode};
+use crate::io_models::ConfigReloadStrategy;
+use crate::kubers_utils::{kube_annotate_pods_by_selector, kube_delete_all_from_selector, KubeDeleteMode};
 use crate::models::container::{get_container_with_invalid_storage_size, Container, ContainerService};
 use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::models::utils::CONFIG_CHECKSUM_ANNOTATION;
 use crate::runtime::block_on;
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
 
 use crate::cloud_provider::utilities::update_pvcs;
 use crate::deployment_action::restart_service::RestartServiceAction;
+use crate::deployment_action::statefulset_partition::stage_statefulset_update;
 use crate::deployment_action::utils::{
+    check_external_secrets_supported, check_hpa_custom_metrics_supported, check_keda_triggers_supported,
     delete_cached_image, get_last_deployed_image, mirror_image_if_necessary, KubeObjectKind,
 };
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
@@ -62,7 +68,7 @@ where
             })
         };
 
-        let long_task = |_logger: &EnvProgressLogger, state: TaskContext| -> Result<TaskContext, Box<EngineError>> {
+        let long_task = |logger: &EnvProgressLogger, state: TaskContext| -> Result<TaskContext, Box<EngineError>> {
             // If the service have been paused, we must ensure we un-pause it first as hpa will not kick in
             let _ = PauseServiceAction::new(
                 self.kube_label_selector(),
@@ -95,6 +101,40 @@ where
                 )),
             };
 
+            check_keda_triggers_supported(
+                &self.advanced_settings().hpa_keda_triggers,
+                self.name(),
+                target,
+                &event_details,
+            )?;
+            check_hpa_custom_metrics_supported(
+                &self.advanced_settings().hpa_custom_metrics,
+                self.name(),
+                target,
+                &event_details,
+            )?;
+            check_external_secrets_supported(
+                &self.advanced_settings().deployment_external_secrets,
+                self.name(),
+                target,
+                &event_details,
+            )?;
+            let mut tera_context = self.to_tera_context(target)?;
+            insert_spec_checksum(&mut tera_context);
+            if self.advanced_settings().deployment_env_vars_fast_path_enabled
+                && update_env_vars_only_if_possible(
+                    &self.kube_label_selector(),
+                    self.is_stateful(),
+                    &tera_context,
+                    logger,
+                    &event_details,
+                    target,
+                )?
+                .is_some()
+            {
+                return Ok(state);
+            }
+
             let chart = ChartInfo {
                 name: self.helm_release_name(),
                 path: self.workspace_directory().to_string(),
@@ -107,7 +147,7 @@ where
 
             let helm = HelmDeployment::new(
                 event_details.clone(),
-                self.to_tera_context(target)?,
+                tera_context,
                 PathBuf::from(self.helm_chart_dir()),
                 None,
                 chart,
@@ -115,9 +155,57 @@ where
 
             helm.on_create(target)?;
 
+            if self.is_stateful() {
+                stage_statefulset_update(
+                    self.kube_label_selector(),
+                    &self.advanced_settings().statefulset_update_strategy_type,
+                    self.advanced_settings()
+                        .statefulset_update_strategy_rolling_update_partition,
+                    logger,
+                    &event_details,
+                    target,
+                )?;
+            }
+
+            if self.advanced_settings().deployment_config_reload_strategy != ConfigReloadStrategy::RollingRestart {
+                // Pods are not restarted when mounted files change, the annotation lets them know they have to reload
+                if let Err(err) = block_on(kube_annotate_pods_by_selector(
+                    &target.kube,
+                    target.environment.namespace(),
+                    &self.kube_label_selector(),
+                    CONFIG_CHECKSUM_ANNOTATION,
+                    &self.config_checksum(),
+                )) {
+                    target.kubernetes.logger().log(EngineEvent::Warning(
+                        event_details.clone(),
+                        EventMessage::new(
+                            "Cannot update config checksum of running pods".to_string(),
+                            Some(err.to_string()),
+                        ),
+                    ));
+                }
+            }
+
             Ok(state)
         };
 
+        let long_task_with_hook =
+            |logger: &EnvProgressLogger, state: TaskContext| -> Result<TaskContext, Box<EngineError>> {
+                let state = long_task(logger, state)?;
+                run_lifecycle_hook(
+                    self,
+                    LifecycleHookKind::PostCreate,
+                    self.lifecycle_hooks().post_create.as_ref(),
+                    &[],
+                    &self.mirrored_image_full(target),
+                    &self.advanced_settings().security_service_account_name,
+                    logger,
+                    &event_details,
+                    target,
+                )?;
+                Ok(state)
+            };
+
         let post_task = |logger: &EnvSuccessLogger, state: TaskContext| {
             // Delete previous image from cache to cleanup resources
             let _ = delete_cached_image(
@@ -139,7 +227,7 @@ where
             ApplicationDeploymentReporter::new_for_container(self, target, Action::Create),
             DeploymentTaskImpl {
                 pre_run: &pre_task,
-                run: &long_task,
+                run: &long_task_with_hook,
                 post_run_success: &post_task,
             },
         )
@@ -186,6 +274,18 @@ where
 
         // Execute the deployment
         let long_task = |logger: &EnvProgressLogger, state: TaskContext| -> Result<TaskContext, Box<EngineError>> {
+            run_lifecycle_hook(
+                self,
+                LifecycleHookKind::PreDelete,
+                self.lifecycle_hooks().pre_delete.as_ref(),
+                &[],
+                &self.mirrored_image_full(target),
+                &self.advanced_settings().security_service_account_name,
+                logger,
+                &event_details,
+                target,
+            )?;
+
             let chart = ChartInfo {
                 name: self.helm_release_name(),
                 namespace: HelmChartNamespaces::Custom,
@@ -219,21 +319,6 @@ where
                         CommandError::new_from_safe_message(err.to_string()),
                     )));
                 }
-
-                // Trying to delete PVCs using old labels
-                // TODO(benjaminch): should be removed once PVCs are migrated to new labels
-                if let Err(err) = block_on(kube_delete_all_from_selector::<PersistentVolumeClaim>(
-                    &target.kube,
-                    &self.kube_legacy_label_selector(),
-                    target.environment.namespace(),
-                    KubeDeleteMode::Normal,
-                )) {
-                    return Err(Box::new(EngineError::new_k8s_cannot_delete_pvcs(
-                        event_details.clone(),
-                        self.kube_legacy_label_selector(),
-                        CommandError::new_from_safe_message(err.to_string()),
-                    )));
-                }
             }
 
             Ok(state)
@@ -6,22 +6,31 @@ use crate::cmd;
 use crate::cmd::command::{ExecutableCommand, QoveryCommand};
 use crate::constants::AWS_DEFAULT_REGION;
 use crate::deployment_action::check_dns::CheckDnsForDomains;
+use crate::deployment_action::custom_metadata::apply_custom_metadata;
+use crate::deployment_action::database_init_scripts::{init_scripts_selector, run_database_init_scripts};
+use crate::deployment_action::database_smoke_test::run_database_smoke_test;
 use crate::deployment_action::deploy_helm::HelmDeployment;
 use crate::deployment_action::deploy_terraform::TerraformDeployment;
 use crate::deployment_action::pause_service::PauseServiceAction;
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::database::reporter::DatabaseDeploymentReporter;
 use crate::deployment_report::{execute_long_deployment, DeploymentTaskImpl};
+use crate::dns_provider::dns_check::{DnsCheckConfig, DnsResolver};
 use crate::errors::{CommandError, EngineError, Tag};
 use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
-use crate::kubers_utils::{kube_delete_all_from_selector, KubeDeleteMode};
+use crate::io_models::database::DatabaseOptions;
+use crate::kubers_utils::{kube_delete_all_from_selector, kube_get_resources_by_selector, KubeDeleteMode};
 use crate::models::database::{
-    get_database_with_invalid_storage_size, Container, Database, DatabaseError, DatabaseService, DatabaseType, Managed,
+    get_database_statefulset_disk_size_in_gib, get_database_with_invalid_storage_size,
+    get_database_with_storage_to_auto_resize, supports_high_availability, Container, Database, DatabaseError,
+    DatabaseService, DatabaseType, Managed,
 };
 use crate::models::types::{CloudProvider, ToTeraContext, VersionsNumber};
 use crate::runtime::block_on;
 use aws_types::SdkConfig;
-use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use chrono::Utc;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{ConfigMap, PersistentVolumeClaim};
 use semver::Version;
 use serde::Deserialize;
 use std::collections::BTreeMap;
@@ -29,15 +38,16 @@ use std::collections::BTreeMap;
 use crate::cloud_provider::aws::models::QoveryAwsSdkConfigManagedDatabase;
 use crate::cloud_provider::utilities::{are_pvcs_bound, update_pvcs};
 use crate::deployment_action::restart_service::RestartServiceAction;
+use crate::deployment_action::statefulset_partition::{await_statefulsets_ready, stage_statefulset_update};
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
 use async_trait::async_trait;
-use aws_sdk_docdb::error::DescribeDBClustersError;
-use aws_sdk_docdb::output::DescribeDbClustersOutput;
+use aws_sdk_docdb::error::{DescribeDBClustersError, StartDBClusterError, StopDBClusterError};
+use aws_sdk_docdb::output::{DescribeDbClustersOutput, StartDbClusterOutput, StopDbClusterOutput};
 use aws_sdk_docdb::types::SdkError;
 use aws_sdk_elasticache::error::DescribeCacheClustersError;
 use aws_sdk_elasticache::output::DescribeCacheClustersOutput;
-use aws_sdk_rds::error::DescribeDBInstancesError;
-use aws_sdk_rds::output::DescribeDbInstancesOutput;
+use aws_sdk_rds::error::{DescribeDBInstancesError, StartDBInstanceError, StopDBInstanceError};
+use aws_sdk_rds::output::{DescribeDbInstancesOutput, StartDbInstanceOutput, StopDbInstanceOutput};
 use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -51,6 +61,8 @@ struct CacheCluster {
     pub cache_cluster_id: String,
     #[serde(alias = "CacheClusterStatus")]
     pub cache_cluster_status: String,
+    #[serde(alias = "EngineVersion")]
+    pub engine_version: String,
 }
 
 #[derive(Deserialize, Default)]
@@ -63,6 +75,8 @@ struct CacheClustersResponse {
 struct DbInstance {
     #[serde(alias = "DBInstanceStatus")]
     pub db_instance_status: String,
+    #[serde(alias = "EngineVersion")]
+    pub engine_version: String,
 }
 
 #[derive(Deserialize, Default)]
@@ -75,6 +89,8 @@ struct DbInstancesResponse {
 struct DocDbCluster {
     #[serde(alias = "Status")]
     pub status: String,
+    #[serde(alias = "EngineVersion")]
+    pub engine_version: String,
 }
 
 #[derive(Deserialize, Default)]
@@ -83,11 +99,25 @@ struct DocDbClustersResponse {
     pub db_cluster: Vec<DocDbCluster>,
 }
 
+#[derive(Default)]
+struct ManagedDatabaseInfo {
+    pub status: String,
+    pub engine_version: String,
+}
+
 fn get_managed_database_status(
     db_type: service::DatabaseType,
     db_id: &str,
     credentials: &[(&str, &str)],
 ) -> Result<String, (cmd::command::CommandError, String)> {
+    get_managed_database_info(db_type, db_id, credentials).map(|info| info.status)
+}
+
+fn get_managed_database_info(
+    db_type: service::DatabaseType,
+    db_id: &str,
+    credentials: &[(&str, &str)],
+) -> Result<ManagedDatabaseInfo, (cmd::command::CommandError, String)> {
     let mut cmd = match db_type {
         service::DatabaseType::PostgreSQL | service::DatabaseType::MySQL => QoveryCommand::new(
             "aws",
@@ -102,7 +132,7 @@ fn get_managed_database_status(
         service::DatabaseType::Redis => {
             let redis_cache_cluster_id = find_redis_cache_cluster_id(db_id, credentials)?;
             if redis_cache_cluster_id.is_empty() {
-                return Ok("".to_string());
+                return Ok(ManagedDatabaseInfo::default());
             }
             QoveryCommand::new(
                 "aws",
@@ -133,13 +163,23 @@ fn get_managed_database_status(
             Ok(payload
                 .db_instances
                 .first()
-                .map(|c| c.db_instance_status.clone())
+                .map(|c| ManagedDatabaseInfo {
+                    status: c.db_instance_status.clone(),
+                    engine_version: c.engine_version.clone(),
+                })
                 .unwrap_or_default())
         }
         service::DatabaseType::MongoDB => {
             let payload: DocDbClustersResponse =
                 serde_json::from_str(output_stdout.join("").as_str()).unwrap_or_default();
-            Ok(payload.db_cluster.first().map(|c| c.status.clone()).unwrap_or_default())
+            Ok(payload
+                .db_cluster
+                .first()
+                .map(|c| ManagedDatabaseInfo {
+                    status: c.status.clone(),
+                    engine_version: c.engine_version.clone(),
+                })
+                .unwrap_or_default())
         }
         service::DatabaseType::Redis => {
             let payload: CacheClustersResponse =
@@ -147,7 +187,10 @@ fn get_managed_database_status(
             Ok(payload
                 .cache_clusters
                 .first()
-                .map(|c| c.cache_cluster_status.clone())
+                .map(|c| ManagedDatabaseInfo {
+                    status: c.cache_cluster_status.clone(),
+                    engine_version: c.engine_version.clone(),
+                })
                 .unwrap_or_default())
         }
     }
@@ -191,54 +234,32 @@ fn find_redis_cache_cluster_id(
     Ok(cache_cluster_id_or_default)
 }
 
+/// Stop or start a managed database through the AWS SDK.
+/// Elasticache clusters cannot be stopped, so nothing is done for Redis.
 fn start_stop_managed_database(
     db_type: service::DatabaseType,
     db_id: &str,
-    credentials: &[(&str, &str)],
+    sdk_config: &SdkConfig,
     should_stop: bool,
-) -> Result<(), (cmd::command::CommandError, String)> {
-    let action = if should_stop { "stop" } else { "start" };
-
-    let mut output_stdout: Vec<String> = vec![];
-    let mut output_stderr: Vec<String> = vec![];
-    let ret = match db_type {
-        service::DatabaseType::PostgreSQL | service::DatabaseType::MySQL => {
-            let mut cmd = QoveryCommand::new(
-                "aws",
-                &[
-                    "rds",
-                    &format!("{action}-db-instance"),
-                    "--db-instance-identifier",
-                    db_id,
-                ],
-                credentials,
-            );
-            cmd.exec_with_output(&mut |line| output_stdout.push(line), &mut |line| output_stderr.push(line))
-        }
-        service::DatabaseType::MongoDB => {
-            let mut cmd = QoveryCommand::new(
-                "aws",
-                &[
-                    "docdb",
-                    &format!("{action}-db-cluster"),
-                    "--db-cluster-identifier",
-                    db_id,
-                ],
-                credentials,
-            );
-            cmd.exec_with_output(&mut |line| output_stdout.push(line), &mut |line| output_stderr.push(line))
+) -> Result<(), String> {
+    match (db_type, should_stop) {
+        (service::DatabaseType::PostgreSQL | service::DatabaseType::MySQL, true) => {
+            block_on(sdk_config.stop_managed_rds_database(db_id))
+                .map(|_| ())
+                .map_err(|e| e.to_string())
         }
-        service::DatabaseType::Redis => {
-            // can't pause elasticache
-            Ok(())
+        (service::DatabaseType::PostgreSQL | service::DatabaseType::MySQL, false) => {
+            block_on(sdk_config.start_managed_rds_database(db_id))
+                .map(|_| ())
+                .map_err(|e| e.to_string())
         }
-    };
-
-    if let Err(cmd_error) = ret {
-        output_stdout.extend(output_stderr);
-        Err((cmd_error, output_stdout.join("\n").trim().to_string()))
-    } else {
-        Ok(())
+        (service::DatabaseType::MongoDB, true) => block_on(sdk_config.stop_managed_doc_db_database(db_id))
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        (service::DatabaseType::MongoDB, false) => block_on(sdk_config.start_managed_doc_db_database(db_id))
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        (service::DatabaseType::Redis, _) => Ok(()),
     }
 }
 
@@ -264,6 +285,174 @@ fn await_db_state(
     }
 }
 
+/// Returns true if the describe call of a managed database failed because it does not exist on AWS yet
+fn is_managed_database_not_found(output: &str) -> bool {
+    ["DBInstanceNotFound", "DBClusterNotFoundFault", "CacheClusterNotFound"]
+        .iter()
+        .any(|error_code| output.contains(error_code))
+}
+
+/// Returns true if `target_version` is a newer engine version than `current_version`.
+/// Only components present in both versions are compared, as AWS can report a more precise version (i.e: 6.2.6)
+/// than the one requested (i.e: 6.2). A non numeric component (i.e: 6.x) stops the comparison.
+fn is_engine_version_upgrade(current_version: &str, target_version: &str) -> bool {
+    let current_version = current_version.trim().trim_start_matches('v');
+    let target_version = target_version.trim().trim_start_matches('v');
+    if current_version.is_empty() || target_version.is_empty() {
+        return false;
+    }
+
+    for (current, target) in current_version.split('.').zip(target_version.split('.')) {
+        match (current.parse::<u64>(), target.parse::<u64>()) {
+            (Ok(current), Ok(target)) if current == target => continue,
+            (Ok(current), Ok(target)) => return target > current,
+            _ => return false,
+        }
+    }
+
+    false
+}
+
+fn create_managed_database_snapshot(
+    db_type: service::DatabaseType,
+    db_id: &str,
+    snapshot_id: &str,
+    credentials: &[(&str, &str)],
+) -> Result<(), (cmd::command::CommandError, String)> {
+    let mut cmd = match db_type {
+        service::DatabaseType::PostgreSQL | service::DatabaseType::MySQL => QoveryCommand::new(
+            "aws",
+            &[
+                "rds",
+                "create-db-snapshot",
+                "--db-instance-identifier",
+                db_id,
+                "--db-snapshot-identifier",
+                snapshot_id,
+            ],
+            credentials,
+        ),
+        service::DatabaseType::MongoDB => QoveryCommand::new(
+            "aws",
+            &[
+                "docdb",
+                "create-db-cluster-snapshot",
+                "--db-cluster-identifier",
+                db_id,
+                "--db-cluster-snapshot-identifier",
+                snapshot_id,
+            ],
+            credentials,
+        ),
+        service::DatabaseType::Redis => {
+            let redis_cache_cluster_id = find_redis_cache_cluster_id(db_id, credentials)?;
+            QoveryCommand::new(
+                "aws",
+                &[
+                    "elasticache",
+                    "create-snapshot",
+                    "--cache-cluster-id",
+                    &redis_cache_cluster_id,
+                    "--snapshot-name",
+                    snapshot_id,
+                ],
+                credentials,
+            )
+        }
+    };
+
+    let mut output_stdout: Vec<String> = vec![];
+    let mut output_stderr: Vec<String> = vec![];
+    if let Err(cmd_error) =
+        cmd.exec_with_output(&mut |line| output_stdout.push(line), &mut |line| output_stderr.push(line))
+    {
+        output_stdout.extend(output_stderr);
+        return Err((cmd_error, output_stdout.join("\n").trim().to_string()));
+    }
+
+    Ok(())
+}
+
+/// If the deployment is going to bump the engine version of an existing managed database,
+/// take a snapshot beforehand so the database can be restored if the upgrade goes wrong.
+fn snapshot_managed_database_before_upgrade<C: CloudProvider, T: DatabaseType<C, Managed>>(
+    db: &Database<C, Managed, T>,
+    target_version: &str,
+    logger: &EnvProgressLogger,
+    event_details: EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>>
+where
+    Database<C, Managed, T>: DatabaseService,
+{
+    let credentials = {
+        let mut credentials = target.cloud_provider.credentials_environment_variables();
+        credentials.push((AWS_DEFAULT_REGION, target.kubernetes.region()));
+        credentials
+    };
+
+    let to_engine_error = |command_error: CommandError| {
+        Box::new(EngineError::new_cannot_snapshot_managed_database(
+            event_details.clone(),
+            &db.fqdn_id,
+            command_error,
+        ))
+    };
+
+    // If the database does not exist, it has not been created yet, so there is nothing to snapshot.
+    // Any other error (i.e: throttling, missing permissions) fails the deployment, the upgrade must not run without
+    // a snapshot.
+    let current_version = match get_managed_database_info(db.db_type(), &db.fqdn_id, &credentials) {
+        Ok(info) => info.engine_version,
+        Err((_, output)) if is_managed_database_not_found(&output) => return Ok(()),
+        Err((cmd_err, output)) => {
+            return Err(to_engine_error(CommandError::new_from_legacy_command_error(
+                cmd_err,
+                Some(output),
+            )))
+        }
+    };
+    if !is_engine_version_upgrade(&current_version, target_version) {
+        return Ok(());
+    }
+
+    let snapshot_id = format!("qovery-{}-pre-upgrade-{}", db.fqdn_id, Utc::now().format("%Y%m%d%H%M%S"));
+    logger.info(format!(
+        "📸 Database engine version is going to be upgraded from {current_version} to {target_version}, taking snapshot {snapshot_id} first"
+    ));
+    create_managed_database_snapshot(db.db_type(), &db.fqdn_id, &snapshot_id, &credentials)
+        .map_err(|(cmd_err, msg)| to_engine_error(CommandError::new_from_legacy_command_error(cmd_err, Some(msg))))?;
+
+    // While the snapshot is taken, the database is not in the available state and cannot be modified
+    match await_db_state(
+        Duration::from_secs(60 * 30),
+        db.db_type(),
+        &db.fqdn_id,
+        &credentials,
+        DB_READY_STATE,
+    ) {
+        Ok(_) => {}
+        Err(None) => {
+            return Err(to_engine_error(CommandError::new_from_safe_message(format!(
+                "Timeout reached waiting for the database to be in {DB_READY_STATE} state after snapshot {snapshot_id}"
+            ))))
+        }
+        Err(Some((cmd_err, msg))) => {
+            return Err(to_engine_error(CommandError::new_from_legacy_command_error(cmd_err, Some(msg))))
+        }
+    }
+
+    logger.log(EngineEvent::Info(
+        event_details,
+        EventMessage::new_from_safe(format!(
+            "📸 Snapshot {snapshot_id} of database {} has been taken before upgrading from version {current_version} to {target_version}. It can be used to restore the database if needed",
+            db.fqdn_id
+        )),
+    ));
+
+    Ok(())
+}
+
 fn on_create_managed_impl<C: CloudProvider, T: DatabaseType<C, Managed>>(
     db: &Database<C, Managed, T>,
     logger: &EnvProgressLogger,
@@ -285,6 +474,13 @@ where
         event_details.clone(),
         target.is_dry_run_deploy,
     );
+
+    if target.cloud_provider.kind() == Aws && !target.is_dry_run_deploy {
+        if let Some(target_version) = tera_context.get("version").and_then(|version| version.as_str()) {
+            snapshot_managed_database_before_upgrade(db, target_version, logger, event_details.clone(), target)?;
+        }
+    }
+
     terraform_deploy.on_create(target)?;
 
     // Our terraform give us back a file with all the info we need to deploy the remaining stuff
@@ -378,11 +574,27 @@ where
         credentials
     };
 
-    // If the database is not in the available state, try to start it
+    // If the database is not in the available state, try to start it (i.e: resume a paused database)
     match get_managed_database_status(db.db_type(), &db.fqdn_id, &credentials) {
         Ok(status) if status == DB_READY_STATE => {}
-        Ok(_) | Err(_) => {
-            let _ = start_stop_managed_database(db.db_type(), &db.fqdn_id, &credentials, false);
+        Ok(status) => match target.cloud_provider.aws_sdk_client() {
+            Some(sdk_config) => {
+                if let Err(err) = start_stop_managed_database(db.db_type(), &db.fqdn_id, &sdk_config, false) {
+                    // starting a database which is not stopped fails, it is expected while it is creating/modifying
+                    if status == DB_STOPPED_STATE {
+                        logger.warning(format!("Cannot resume stopped database {}: {}", db.name(), err));
+                    }
+                }
+            }
+            None => logger.warning(format!(
+                "Cannot get AWS SDK client to resume database {} which is in {status} state",
+                db.name()
+            )),
+        },
+        Err(_) => {
+            if let Some(sdk_config) = target.cloud_provider.aws_sdk_client() {
+                let _ = start_stop_managed_database(db.db_type(), &db.fqdn_id, &sdk_config, false);
+            }
         }
     }
 
@@ -444,6 +656,38 @@ impl QoveryAwsSdkConfigManagedDatabase for SdkConfig {
         let client = aws_sdk_docdb::Client::new(self);
         client.describe_db_clusters().db_cluster_identifier(db_id).send().await
     }
+
+    async fn stop_managed_rds_database(
+        &self,
+        db_id: &str,
+    ) -> Result<StopDbInstanceOutput, SdkError<StopDBInstanceError>> {
+        let client = aws_sdk_rds::Client::new(self);
+        client.stop_db_instance().db_instance_identifier(db_id).send().await
+    }
+
+    async fn start_managed_rds_database(
+        &self,
+        db_id: &str,
+    ) -> Result<StartDbInstanceOutput, SdkError<StartDBInstanceError>> {
+        let client = aws_sdk_rds::Client::new(self);
+        client.start_db_instance().db_instance_identifier(db_id).send().await
+    }
+
+    async fn stop_managed_doc_db_database(
+        &self,
+        db_id: &str,
+    ) -> Result<StopDbClusterOutput, SdkError<StopDBClusterError>> {
+        let client = aws_sdk_docdb::Client::new(self);
+        client.stop_db_cluster().db_cluster_identifier(db_id).send().await
+    }
+
+    async fn start_managed_doc_db_database(
+        &self,
+        db_id: &str,
+    ) -> Result<StartDbClusterOutput, SdkError<StartDBClusterError>> {
+        let client = aws_sdk_docdb::Client::new(self);
+        client.start_db_cluster().db_cluster_identifier(db_id).send().await
+    }
 }
 
 fn managed_database_exists(
@@ -550,7 +794,8 @@ fn managed_database_exists(
 }
 
 // For Managed database
-impl<C: CloudProvider, T: DatabaseType<C, Managed>> DeploymentAction for Database<C, Managed, T>
+impl<C: CloudProvider, T: DatabaseType<C, Managed, DatabaseOptions = DatabaseOptions>> DeploymentAction
+    for Database<C, Managed, T>
 where
     Database<C, Managed, T>: ToTeraContext,
 {
@@ -558,14 +803,23 @@ where
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
         let pre_run = |_: &EnvProgressLogger| -> Result<(), Box<EngineError>> { Ok(()) };
         let run = |logger: &EnvProgressLogger, _: ()| -> Result<(), Box<EngineError>> {
-            on_create_managed_impl(self, logger, event_details.clone(), target)
+            on_create_managed_impl(self, logger, event_details.clone(), target)?;
+            apply_custom_metadata(
+                target,
+                &self.kube_label_selector(),
+                &self.options.custom_metadata,
+                &event_details,
+            );
+            run_database_smoke_test(self, logger, &event_details, target)?;
+            run_database_init_scripts(self, logger, &event_details, target)
         };
         let post_run = |logger: &EnvSuccessLogger, _: ()| {
             if self.publicly_accessible {
                 let domain_checker = CheckDnsForDomains {
                     resolve_to_ip: vec![self.fqdn.to_string()],
                     resolve_to_cname: vec![],
-                    log: Box::new(move |msg| logger.send_success(msg)),
+                    dns_check_config: DnsCheckConfig::new(DnsResolver::defaults_for(target.dns_provider)),
+                    log: Box::new(move |msg| logger.send_success_message(msg)),
                 };
 
                 let _ = domain_checker.on_create(target);
@@ -587,33 +841,57 @@ where
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Pause));
         execute_long_deployment(
             DatabaseDeploymentReporter::new(self, target, Action::Pause),
-            |_logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
                 // We don't manage PAUSE for managed database elsewhere than for AWS
                 if target.cloud_provider.kind() != Aws {
+                    logger.warning(format!(
+                        "Pausing managed databases is not supported on {}, database {} keeps running",
+                        target.cloud_provider.kind(),
+                        self.name()
+                    ));
                     return Ok(());
                 }
 
                 // Elasticache does not support being stopped/paused
                 if self.db_type() == service::DatabaseType::Redis {
+                    logger.warning(format!(
+                        "Elasticache clusters cannot be stopped, database {} keeps running while the environment is paused",
+                        self.name()
+                    ));
                     return Ok(());
                 }
 
-                // Terraform does not ensure that the database is correctly started
-                // So we must force it ourselves in case
                 let credentials = {
                     let mut credentials = target.cloud_provider.credentials_environment_variables();
                     credentials.push((AWS_DEFAULT_REGION, target.kubernetes.region()));
                     credentials
                 };
+
+                // Stopping an already stopped database is refused by AWS
+                if let Ok(status) = get_managed_database_status(self.db_type(), &self.fqdn_id, &credentials) {
+                    if status == DB_STOPPED_STATE {
+                        return Ok(());
+                    }
+                }
+
+                let sdk_config = target
+                    .cloud_provider
+                    .aws_sdk_client()
+                    .ok_or_else(|| EngineError::new_aws_sdk_cannot_get_client(event_details.clone()))?;
+
                 // We use the fqdn_id as db identifier, why not id or name like everything else ¯\_(ツ)_/¯
-                start_stop_managed_database(self.db_type(), &self.fqdn_id, &credentials, true).map_err(
-                    |(cmd_error, msg)| {
-                        EngineError::new_cannot_pause_managed_database(
-                            event_details.clone(),
-                            CommandError::new_from_legacy_command_error(cmd_error, Some(msg)),
-                        )
-                    },
-                )?;
+                start_stop_managed_database(self.db_type(), &self.fqdn_id, &sdk_config, true).map_err(|msg| {
+                    EngineError::new_cannot_pause_managed_database(
+                        event_details.clone(),
+                        CommandError::new_from_safe_message(msg),
+                    )
+                })?;
+
+                // AWS restarts by itself databases which have been stopped for 7 days
+                logger.warning(format!(
+                    "Database {} is stopping. AWS automatically starts it back after 7 days being stopped",
+                    self.name()
+                ));
 
                 let ret = await_db_state(
                     Duration::from_secs(60 * 30),
@@ -694,7 +972,19 @@ where
                     chart,
                 );
 
-                helm.on_delete(target)
+                helm.on_delete(target)?;
+
+                // so init scripts run again if the database is re-created
+                if let Err(err) = block_on(kube_delete_all_from_selector::<ConfigMap>(
+                    &target.kube,
+                    &init_scripts_selector(&self.long_id),
+                    target.environment.namespace(),
+                    KubeDeleteMode::Normal,
+                )) {
+                    logger.warning(format!("Cannot delete init scripts marker of the database: {err}"));
+                }
+
+                Ok(())
             },
         )
     }
@@ -710,8 +1000,56 @@ where
     }
 }
 
+/// A deployed PostgreSQL keeps its architecture: the primary statefulset is renamed with read replicas, so switching
+/// would start the database again from empty volumes
+fn check_high_availability_mode_unchanged<
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+>(
+    database: &Database<C, Container, T>,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let statefulsets = match block_on(kube_get_resources_by_selector::<StatefulSet>(
+        &target.kube,
+        target.environment.namespace(),
+        &database.kube_label_selector(),
+    )) {
+        Ok(statefulsets) => statefulsets.items,
+        Err(err) => {
+            target.kubernetes.logger().log(EngineEvent::Warning(
+                event_details.clone(),
+                EventMessage::new_from_safe(format!("Cannot list the deployed statefulsets: {err}")),
+            ));
+            return Ok(());
+        }
+    };
+
+    if statefulsets.is_empty() {
+        return Ok(());
+    }
+    // the chart only deploys read replicas with the replication architecture
+    let deployed_with_replicas = statefulsets.iter().any(|statefulset| {
+        statefulset
+            .metadata
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get("app.kubernetes.io/component"))
+            .map(|component| component == "read")
+            .unwrap_or(false)
+    });
+    match deployed_with_replicas == database.is_high_availability() {
+        true => Ok(()),
+        false => Err(Box::new(EngineError::new_database_high_availability_mode_changed(
+            event_details.clone(),
+            database.name(),
+        ))),
+    }
+}
+
 // For Container database
-impl<C: CloudProvider, T: DatabaseType<C, Container>> DeploymentAction for Database<C, Container, T>
+impl<C: CloudProvider, T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>> DeploymentAction
+    for Database<C, Container, T>
 where
     Database<C, Container, T>: ToTeraContext,
 {
@@ -742,6 +1080,59 @@ where
                 )),
             }
 
+            // grow volumes running out of space, without waiting for the disk size to be changed by the user
+            let advanced_settings = &self.options.advanced_settings;
+            match get_database_with_storage_to_auto_resize(
+                self,
+                advanced_settings,
+                &target.kube,
+                target.environment.namespace(),
+                &event_details,
+            ) {
+                Ok(Some(statefulset_storage)) => {
+                    for pvc in &statefulset_storage.invalid_pvcs {
+                        logger.info(format!(
+                            "💾 Volume {} is running out of space, growing it to {}GiB",
+                            pvc.pvc_name, pvc.required_disk_size_in_gib
+                        ));
+                    }
+                    update_pvcs(
+                        self.as_service(),
+                        &statefulset_storage,
+                        target.environment.namespace(),
+                        &event_details,
+                        &target.kube,
+                    )?;
+                }
+                Ok(None) => {}
+                Err(e) => logger.warning(format!("Cannot check volumes usage: {}", e.user_log_message())),
+            }
+
+            if self.options.activate_high_availability && !supports_high_availability(T::db_type()) {
+                logger.warning(format!(
+                    "High availability is not supported for container {} databases, the database runs a single instance",
+                    T::short_name()
+                ));
+            }
+            if T::db_type() == service::DatabaseType::PostgreSQL {
+                check_high_availability_mode_unchanged(self, &event_details, target)?;
+            }
+
+            let mut tera_context = self.to_tera_context(target)?;
+            if advanced_settings.storage_auto_resize_enabled {
+                // volume claim templates cannot shrink, keep the size reached by the auto resize
+                if let Ok(Some(current_size)) = get_database_statefulset_disk_size_in_gib(
+                    self,
+                    &target.kube,
+                    target.environment.namespace(),
+                    &event_details,
+                ) {
+                    if current_size > self.total_disk_size_in_gb {
+                        tera_context.insert("database_disk_size_in_gib", &current_size);
+                    }
+                }
+            }
+
             let chart = ChartInfo {
                 name: self.helm_release_name(),
                 path: self.workspace_directory().to_string(),
@@ -760,7 +1151,7 @@ where
             };
             let helm = HelmDeployment::new(
                 event_details.clone(),
-                self.to_tera_context(target)?,
+                tera_context,
                 PathBuf::from(self.helm_chart_dir()),
                 Some(PathBuf::from(format!("{}/qovery-values.j2.yaml", self.helm_chart_values_dir()))),
                 chart,
@@ -784,7 +1175,32 @@ where
                 };
             };
 
-            Ok(())
+            stage_statefulset_update(
+                self.kube_label_selector(),
+                &advanced_settings.update_strategy_type,
+                advanced_settings.update_strategy_rolling_update_partition,
+                logger,
+                &event_details,
+                target,
+            )?;
+            if self.is_high_availability() {
+                logger.info("⏳ Waiting for all the instances of the database to be ready".to_string());
+                await_statefulsets_ready(
+                    &self.kube_label_selector(),
+                    Duration::from_secs(10 * 60),
+                    &event_details,
+                    target,
+                )?;
+            }
+
+            apply_custom_metadata(
+                target,
+                &self.kube_label_selector(),
+                &self.options.custom_metadata,
+                &event_details,
+            );
+            run_database_smoke_test(self, logger, &event_details, target)?;
+            run_database_init_scripts(self, logger, &event_details, target)
         };
 
         let post_run = |logger: &EnvSuccessLogger, _: ()| {
@@ -793,7 +1209,8 @@ where
                 let domain_checker = CheckDnsForDomains {
                     resolve_to_ip: vec![self.fqdn.to_string()],
                     resolve_to_cname: vec![],
-                    log: Box::new(move |msg| logger.send_success(msg)),
+                    dns_check_config: DnsCheckConfig::new(DnsResolver::defaults_for(target.dns_provider)),
+                    log: Box::new(move |msg| logger.send_success_message(msg)),
                 };
 
                 let _ = domain_checker.on_create(target);
@@ -864,6 +1281,16 @@ where
                     )));
                 }
 
+                // so init scripts run again if the database is re-created
+                if let Err(err) = block_on(kube_delete_all_from_selector::<ConfigMap>(
+                    &target.kube,
+                    &init_scripts_selector(&self.long_id),
+                    target.environment.namespace(),
+                    KubeDeleteMode::Normal,
+                )) {
+                    logger.warning(format!("Cannot delete init scripts marker of the database: {err}"));
+                }
+
                 Ok(())
             },
         )
@@ -883,3 +1310,86 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{is_engine_version_upgrade, is_managed_database_not_found};
+
+    #[test]
+    fn test_is_managed_database_not_found() {
+        assert!(is_managed_database_not_found(
+            "An error occurred (DBInstanceNotFound) when calling the DescribeDBInstances operation: DBInstance zabcd1234 not found."
+        ));
+        assert!(is_managed_database_not_found(
+            "An error occurred (DBClusterNotFoundFault) when calling the DescribeDBClusters operation: DBCluster zabcd1234 not found"
+        ));
+        assert!(!is_managed_database_not_found(
+            "An error occurred (Throttling) when calling the DescribeDBInstances operation (reached max retries: 2): Rate exceeded"
+        ));
+        assert!(!is_managed_database_not_found(
+            "An error occurred (AccessDenied) when calling the DescribeDBInstances operation: User is not authorized to perform: rds:DescribeDBInstances"
+        ));
+    }
+
+    #[test]
+    fn test_is_engine_version_upgrade() {
+        struct TestCase<'a> {
+            current_version: &'a str,
+            target_version: &'a str,
+            expected: bool,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                current_version: "13.8",
+                target_version: "14.6",
+                expected: true,
+            },
+            TestCase {
+                current_version: "13.8",
+                target_version: "13.10",
+                expected: true,
+            },
+            TestCase {
+                current_version: "14.6",
+                target_version: "14.6",
+                expected: false,
+            },
+            TestCase {
+                current_version: "14.6",
+                target_version: "13.8",
+                expected: false,
+            },
+            TestCase {
+                current_version: "6.2.6",
+                target_version: "6.2",
+                expected: false,
+            },
+            TestCase {
+                current_version: "6.2.6",
+                target_version: "7.0",
+                expected: true,
+            },
+            TestCase {
+                current_version: "6.2.6",
+                target_version: "6.x",
+                expected: false,
+            },
+            TestCase {
+                current_version: "",
+                target_version: "8.0.32",
+                expected: false,
+            },
+        ];
+
+        for tc in test_cases {
+            assert_eq!(
+                is_engine_version_upgrade(tc.current_version, tc.target_version),
+                tc.expected,
+                "{} -> {}",
+                tc.current_version,
+                tc.target_version
+            );
+        }
+    }
+}
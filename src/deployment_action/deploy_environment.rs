@@ -2,26 +2,28 @@ use crate::cloud_provider::aws::load_balancers::clean_up_deleted_k8s_nlb;
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::service::Action;
 use crate::cloud_provider::DeploymentTarget;
+use crate::dependency_graph::dependency_levels;
 use crate::deployment_action::deploy_namespace::NamespaceDeployment;
 use crate::deployment_action::DeploymentAction;
 use crate::engine::InfrastructureContext;
 use crate::errors::{CommandError, EngineError, ErrorMessageVerbosity};
 use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage};
+use crate::io_models::environment::DeploymentWaves;
 use crate::logger::Logger;
 use crate::metrics_registry::{StepLabel, StepName, StepStatus};
 use crate::models::router::RouterService;
 use crate::runtime::block_on;
 use itertools::Itertools;
-use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::api::core::v1::{Namespace, Pod};
 use kube::api::ListParams;
 use kube::Api;
 use std::cmp::{max, min};
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::ScopedJoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 pub struct EnvironmentDeployment<'a> {
@@ -58,6 +60,12 @@ impl<'a> EnvironmentDeployment<'a> {
                     .iter()
                     .map(|s| (*s.long_id(), s.as_deployment_action(), *s.action())),
             )
+            .chain(
+                environment
+                    .terraform_services
+                    .iter()
+                    .map(|s| (*s.long_id(), s.as_deployment_action(), *s.action())),
+            )
             .chain(
                 environment
                     .jobs
@@ -82,6 +90,12 @@ impl<'a> EnvironmentDeployment<'a> {
                     .iter()
                     .map(|s| (*s.long_id(), s.as_deployment_action(), *s.action())),
             )
+            .chain(
+                environment
+                    .kustomizations
+                    .iter()
+                    .map(|s| (*s.long_id(), s.as_deployment_action(), *s.action())),
+            )
     }
 
     fn services_routers_iter(
@@ -131,7 +145,7 @@ impl<'a> EnvironmentDeployment<'a> {
         };
         ns.exec_action(target, target.environment.action)?;
 
-        let services_to_deploy = Self::services_without_routers_iter(target.environment);
+        let services_to_deploy = Self::services_without_routers_iter(target.environment).collect_vec();
         let parallel_deploys = max(target.environment.max_parallel_deploy as usize, 1);
 
         self.logger.log(EngineEvent::Info(
@@ -142,35 +156,90 @@ impl<'a> EnvironmentDeployment<'a> {
             )),
         ));
 
-        let deployment_threads_pool = DeploymentThreadsPool::new();
-        deployment_threads_pool.run(
-            services_to_deploy
-                .into_iter()
-                .map(|(service_id, service, service_action)| {
-                    let queueing_record =
-                        metrics_registry.start_record(service_id, StepLabel::Service, StepName::DeploymentQueueing);
-                    let deployed_services = self.deployed_services.clone();
-                    let opt_router = Self::get_associated_router(&target.environment.routers, service_id);
-                    move || {
-                        queueing_record.stop(StepStatus::Success);
+        // a service starts once all the services of the previous dependency level are deployed
+        let levels = dependency_levels(
+            services_to_deploy.iter().map(|(service_id, _, _)| *service_id),
+            &target.environment.service_dependencies,
+        )
+        .map_err(|cycle| {
+            Box::new(EngineError::new_service_dependency_cycle(
+                event_details.clone(),
+                cycle.services.iter().map(Uuid::to_string).collect(),
+            ))
+        })?;
+        if levels.len() > 1 {
+            self.logger.log(EngineEvent::Info(
+                event_details.clone(),
+                EventMessage::new_from_safe(format!(
+                    "🧩 Deploying services in {} levels, following their dependencies",
+                    levels.len()
+                )),
+            ));
+        }
 
-                        // creating services first
-                        deployed_services.lock().unwrap().insert(service_id);
-                        service.exec_action(target, service_action)?;
+        let deployment_waves = &target.environment.deployment_waves;
+        let mut services_by_id: HashMap<Uuid, _> = services_to_deploy
+            .into_iter()
+            .map(|service| (service.0, service))
+            .collect();
+        let waves = levels
+            .into_iter()
+            .flat_map(|level| {
+                split_in_waves(
+                    level
+                        .into_iter()
+                        .filter_map(|service_id| services_by_id.remove(&service_id))
+                        .collect_vec(),
+                    deployment_waves.as_ref().map(|waves| waves.batch_size),
+                )
+            })
+            .collect_vec();
+        let nb_waves = waves.len();
+        let deployment_threads_pool = DeploymentThreadsPool::new();
+        for (wave_ix, wave) in waves.into_iter().enumerate() {
+            if let Some(deployment_waves) = deployment_waves {
+                if wave_ix > 0 {
+                    self.wait_for_wave_stabilization(deployment_waves, &event_details, &should_abort)?;
+                }
+                self.logger.log(EngineEvent::Info(
+                    event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "🌊 Deploying wave {}/{} of {} service(s)",
+                        wave_ix + 1,
+                        nb_waves,
+                        wave.len()
+                    )),
+                ));
+            }
 
-                        // then routers
-                        if let Some(router) = opt_router {
-                            deployed_services.lock().unwrap().insert(*router.long_id());
-                            return router.exec_action(target, *router.action());
+            deployment_threads_pool.run(
+                wave.into_iter()
+                    .map(|(service_id, service, service_action)| {
+                        let queueing_record =
+                            metrics_registry.start_record(service_id, StepLabel::Service, StepName::DeploymentQueueing);
+                        let deployed_services = self.deployed_services.clone();
+                        let opt_router = Self::get_associated_router(&target.environment.routers, service_id);
+                        move || {
+                            queueing_record.stop(StepStatus::Success);
+
+                            // creating services first
+                            deployed_services.lock().unwrap().insert(service_id);
+                            service.exec_action(target, service_action)?;
+
+                            // then routers
+                            if let Some(router) = opt_router {
+                                deployed_services.lock().unwrap().insert(*router.long_id());
+                                return router.exec_action(target, *router.action());
+                            }
+                            Ok(())
                         }
-                        Ok(())
-                    }
-                })
-                .collect_vec(),
-            || should_abort().is_err(),
-            NonZeroUsize::new(parallel_deploys)
-                .unwrap_or(NonZeroUsize::new(1).expect("error trying to instantiate NonZeroUsize")),
-        )?;
+                    })
+                    .collect_vec(),
+                || should_abort().is_err(),
+                NonZeroUsize::new(parallel_deploys)
+                    .unwrap_or(NonZeroUsize::new(1).expect("error trying to instantiate NonZeroUsize")),
+            )?;
+        }
 
         // clean up nlb
         if let Err(err) = clean_up_deleted_k8s_nlb(event_details.clone(), target) {
@@ -183,6 +252,56 @@ impl<'a> EnvironmentDeployment<'a> {
         Ok(())
     }
 
+    /// Before starting a wave, wait for the pods of the previous ones to be scheduled, so the cluster autoscaler and the
+    /// image pulls catch up. Pods still pending at the timeout are only reported, as their services are deployed already.
+    fn wait_for_wave_stabilization(
+        &self,
+        deployment_waves: &DeploymentWaves,
+        event_details: &EventDetails,
+        should_abort: &impl Fn() -> Result<(), Box<EngineError>>,
+    ) -> Result<(), Box<EngineError>> {
+        let target = &self.deployment_target;
+        let pods: Api<Pod> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+        let timeout = Duration::from_secs(deployment_waves.stabilization_timeout_sec as u64);
+        let deadline = Instant::now() + timeout;
+        loop {
+            should_abort()?;
+            let pending_pods =
+                block_on(pods.list(&ListParams::default().fields("status.phase=Pending"))).map(|pods| pods.items.len());
+            match pending_pods {
+                Ok(0) => break,
+                _ if Instant::now() < deadline => thread::sleep(Duration::from_secs(5)),
+                Ok(nb_pending_pods) => {
+                    self.logger.log(EngineEvent::Warning(
+                        event_details.clone(),
+                        EventMessage::new_from_safe(format!(
+                            "⚠️ {} pod(s) still pending after {}s, proceeding with the next wave",
+                            nb_pending_pods,
+                            timeout.as_secs()
+                        )),
+                    ));
+                    break;
+                }
+                Err(err) => {
+                    self.logger.log(EngineEvent::Warning(
+                        event_details.clone(),
+                        EventMessage::new(
+                            "⚠️ Cannot check pending pods, proceeding with the next wave".to_string(),
+                            Some(err.to_string()),
+                        ),
+                    ));
+                    break;
+                }
+            }
+        }
+
+        if deployment_waves.interval_sec > 0 {
+            thread::sleep(Duration::from_secs(deployment_waves.interval_sec as u64));
+        }
+
+        Ok(())
+    }
+
     pub fn on_pause(&mut self) -> Result<(), Box<EngineError>> {
         let event_details = self
             .deployment_target
@@ -381,6 +500,24 @@ impl<'a> EnvironmentDeployment<'a> {
     }
 }
 
+/// Services split in waves of `batch_size`, or a single wave when not set
+fn split_in_waves<T>(services: Vec<T>, batch_size: Option<u32>) -> Vec<Vec<T>> {
+    let Some(batch_size) = batch_size else {
+        return vec![services];
+    };
+
+    let mut waves: Vec<Vec<T>> = vec![];
+    for (ix, service) in services.into_iter().enumerate() {
+        if ix % max(batch_size as usize, 1) == 0 {
+            waves.push(vec![]);
+        }
+        if let Some(wave) = waves.last_mut() {
+            wave.push(service);
+        }
+    }
+    waves
+}
+
 struct DeploymentThreadsPool {}
 
 impl DeploymentThreadsPool {
@@ -483,6 +620,17 @@ mod test {
     use std::sync::atomic::Ordering;
     use std::time::Duration;
 
+    #[test]
+    fn test_split_in_waves() {
+        assert_eq!(split_in_waves(vec![1, 2, 3], None), vec![vec![1, 2, 3]]);
+        assert_eq!(
+            split_in_waves(vec![1, 2, 3, 4, 5], Some(2)),
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        );
+        assert_eq!(split_in_waves(vec![1, 2], Some(0)), vec![vec![1], vec![2]]);
+        assert!(split_in_waves(Vec::<u32>::new(), Some(2)).is_empty());
+    }
+
     #[test]
     fn test_deployment_thread_pool_parallelism() {
         // setup:
@@ -1,14 +1,18 @@
 use crate::cloud_provider::helm::{ChartInfo, HelmChart, ServiceChart};
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::command::CommandKiller;
+use crate::cmd::helm::{HelmCommand, HelmError};
 use crate::deployment_action::DeploymentAction;
+use crate::deployment_hook::DeploymentHookStage;
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EnvironmentStep, EventDetails, Stage};
 use crate::runtime::block_on;
 use crate::template::generate_and_copy_all_files_into_dir;
 use k8s_openapi::api::core::v1::Pod;
 use kube::api::ListParams;
-use kube::Api;
+use kube::{Api, Resource};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -87,6 +91,66 @@ impl HelmDeployment {
 
         Ok(())
     }
+
+    /// Manifests rendered by the chart with `helm template`, one value per document, nothing is installed
+    pub fn render_manifests(&self, target: &DeploymentTarget) -> Result<Vec<serde_yaml::Value>, Box<EngineError>> {
+        self.prepare_helm_chart()?;
+
+        let set_args: Vec<String> = self
+            .helm_chart
+            .values
+            .iter()
+            .map(|value| format!("--set={}={}", value.key, value.value))
+            .chain(
+                self.helm_chart
+                    .values_string
+                    .iter()
+                    .map(|value| format!("--set-string={}={}", value.key, value.value)),
+            )
+            .collect();
+        let manifests = target
+            .helm
+            .template_raw(
+                &self.helm_chart.name,
+                Path::new(&self.helm_chart.path),
+                &self.helm_chart.get_namespace_string(),
+                &set_args.iter().map(String::as_str).collect::<Vec<_>>(),
+                target.cloud_provider.credentials_environment_variables().as_slice(),
+                &CommandKiller::from_cancelable(target.should_abort),
+                &mut |_| {},
+            )
+            .map_err(|e| Box::new(EngineError::new_helm_error(self.event_details.clone(), e)))?;
+
+        serde_yaml::Deserializer::from_str(&manifests)
+            .map(serde_yaml::Value::deserialize)
+            .filter(|document| !matches!(document, Ok(serde_yaml::Value::Null)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                Box::new(EngineError::new_helm_error(
+                    self.event_details.clone(),
+                    HelmError::CmdError(
+                        self.helm_chart.name.clone(),
+                        HelmCommand::TEMPLATE,
+                        CommandError::new_from_safe_message(format!("Cannot parse the rendered manifests: {e}")),
+                    ),
+                ))
+            })
+    }
+}
+
+/// Resources of the given kind among rendered manifests
+pub fn rendered_resources<K>(manifests: &[serde_yaml::Value]) -> Result<Vec<K>, String>
+where
+    K: Resource<DynamicType = ()> + DeserializeOwned,
+{
+    let kind = K::kind(&());
+    manifests
+        .iter()
+        .filter(|manifest| manifest.get("kind").and_then(|kind| kind.as_str()) == Some(kind.as_ref()))
+        .map(|manifest| {
+            serde_yaml::from_value(manifest.clone()).map_err(|e| format!("Cannot parse a rendered {kind}: {e}"))
+        })
+        .collect()
 }
 
 impl DeploymentAction for HelmDeployment {
@@ -95,14 +159,18 @@ impl DeploymentAction for HelmDeployment {
 
         let service_chart = ServiceChart::new(self.helm_chart.clone());
         let chart: Box<dyn HelmChart> = Box::new(service_chart);
-        chart
-            .run(
-                &target.kube,
-                &target.kubernetes.kubeconfig_local_file_path(),
-                target.cloud_provider.credentials_environment_variables().as_slice(),
-                &CommandKiller::from_cancelable(target.should_abort),
-            )
-            .map_err(|e| Box::new(EngineError::new_helm_chart_error(self.event_details.clone(), e)))?;
+        target
+            .deployment_hooks
+            .run_stage(DeploymentHookStage::Helm, &self.event_details, || {
+                chart
+                    .run(
+                        &target.kube,
+                        &target.kubernetes.kubeconfig_local_file_path(),
+                        target.cloud_provider.credentials_environment_variables().as_slice(),
+                        &CommandKiller::from_cancelable(target.should_abort),
+                    )
+                    .map_err(|e| Box::new(EngineError::new_helm_chart_error(self.event_details.clone(), e)))
+            })?;
         Ok(())
     }
 
@@ -112,15 +180,19 @@ impl DeploymentAction for HelmDeployment {
 
     fn on_delete(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
         target
-            .helm
-            .uninstall(
-                &self.helm_chart,
-                &[],
-                &CommandKiller::from_cancelable(&target.should_abort),
-                &mut |_| {},
-                &mut |_| {},
-            )
-            .map_err(|e| EngineError::new_helm_error(self.event_details.clone(), e))?;
+            .deployment_hooks
+            .run_stage(DeploymentHookStage::Helm, &self.event_details, || {
+                target
+                    .helm
+                    .uninstall(
+                        &self.helm_chart,
+                        &[],
+                        &CommandKiller::from_cancelable(&target.should_abort),
+                        &mut |_| {},
+                        &mut |_| {},
+                    )
+                    .map_err(|e| Box::new(EngineError::new_helm_error(self.event_details.clone(), e)))
+            })?;
 
         // helm does not wait for pod to terminate https://github.com/helm/helm/issues/10586
         // So wait for
@@ -166,15 +238,55 @@ impl DeploymentAction for HelmDeployment {
 mod tests {
     use crate::cloud_provider::helm::ChartInfo;
     use crate::cmd::helm::Helm;
-    use crate::deployment_action::deploy_helm::{default_helm_timeout, HelmDeployment};
+    use crate::deployment_action::deploy_helm::{default_helm_timeout, rendered_resources, HelmDeployment};
     use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
     use crate::io_models::QoveryIdentifier;
     use function_name::named;
+    use k8s_openapi::api::apps::v1::Deployment;
+    use k8s_openapi::api::core::v1::Secret;
+    use serde::Deserialize;
 
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
     use uuid::Uuid;
 
+    #[test]
+    fn test_rendered_resources() {
+        let manifests: Vec<serde_yaml::Value> = serde_yaml::Deserializer::from_str(
+            r#"
+---
+apiVersion: v1
+kind: Secret
+metadata:
+  name: app-z1234
+type: Opaque
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: app-z1234
+spec:
+  selector:
+    matchLabels:
+      qovery.com/service-id: long-id
+  template:
+    spec:
+      containers:
+        - name: app-z1234
+          image: registry/app:v2
+"#,
+        )
+        .map(|document| serde_yaml::Value::deserialize(document).unwrap())
+        .collect();
+
+        let deployments = rendered_resources::<Deployment>(&manifests).unwrap();
+        assert_eq!(deployments.len(), 1);
+        assert_eq!(deployments[0].metadata.name.as_deref(), Some("app-z1234"));
+        let secrets = rendered_resources::<Secret>(&manifests).unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].type_.as_deref(), Some("Opaque"));
+    }
+
     #[test]
     #[named]
     fn test_helm_deployment() -> Result<(), Box<dyn std::error::Error>> {
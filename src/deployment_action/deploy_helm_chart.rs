@@ -1,6 +1,7 @@
 use crate::build_platform::{Credentials, SshKey};
 
 use crate::cloud_provider::helm::{ChartInfo, HelmChartError};
+use crate::cloud_provider::kubernetes_api::find_removed_apis;
 use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::command::CommandKiller;
@@ -16,21 +17,27 @@ use crate::git;
 use crate::io_models::variable_utils::VariableInfo;
 use crate::models::helm_chart::{HelmChart, HelmChartSource, HelmValueSource};
 use crate::models::types::CloudProvider;
+use crate::runtime::block_on;
 use anyhow::anyhow;
 use git2::{Cred, CredentialType};
 use itertools::Itertools;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
 use kube::api::PartialObjectMeta;
+use kube::Api;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::fs;
 use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::thread;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 const HELM_CHART_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const HELM_CHART_STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 impl<T: CloudProvider> DeploymentAction for HelmChart<T> {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
@@ -71,6 +78,7 @@ impl<T: CloudProvider> DeploymentAction for HelmChart<T> {
                 )
                 .map_err(|err| (event_details.clone(), HelmChartError::HelmError(err)))?;
 
+            check_release_workloads_are_ready(self, target, event_details.clone(), logger)?;
             Ok(())
         };
 
@@ -310,7 +318,7 @@ fn replace_qovery_env_variable<'a>(
     Ok(line)
 }
 
-fn git_credentials_callback<'a>(
+pub(super) fn git_credentials_callback<'a>(
     git_credentials: &'a Option<Credentials>,
     ssh_keys: &'a [SshKey],
 ) -> impl Fn(&str) -> Vec<(CredentialType, Cred)> + 'a {
@@ -516,6 +524,134 @@ fn prepare_helm_chart_directory<T: CloudProvider>(
     Ok(())
 }
 
+/// Workload of a release, helm does not wait for its pods to be ready unless asked to
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct ReleaseWorkload {
+    kind: String,
+    name: String,
+    namespace: String,
+}
+
+impl Display for ReleaseWorkload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}/{}", self.kind, self.namespace, self.name)
+    }
+}
+
+pub(super) fn release_workloads(manifest: &str, default_namespace: &str) -> Vec<ReleaseWorkload> {
+    serde_yaml::Deserializer::from_str(manifest)
+        .filter_map(|document| PartialObjectMeta::<()>::deserialize(document).ok())
+        .filter_map(|kube_obj| {
+            let kind = kube_obj.types?.kind;
+            if !matches!(kind.as_str(), "Deployment" | "StatefulSet" | "DaemonSet") {
+                return None;
+            }
+            Some(ReleaseWorkload {
+                kind,
+                name: kube_obj.metadata.name?,
+                namespace: kube_obj
+                    .metadata
+                    .namespace
+                    .unwrap_or_else(|| default_namespace.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn is_deployment_ready(deployment: &Deployment) -> bool {
+    let replicas = deployment.spec.as_ref().and_then(|spec| spec.replicas).unwrap_or(1);
+    let Some(status) = &deployment.status else {
+        return replicas == 0;
+    };
+
+    status.observed_generation >= deployment.metadata.generation
+        && status.updated_replicas.unwrap_or(0) >= replicas
+        && status.ready_replicas.unwrap_or(0) >= replicas
+}
+
+fn is_statefulset_ready(statefulset: &StatefulSet) -> bool {
+    let replicas = statefulset.spec.as_ref().and_then(|spec| spec.replicas).unwrap_or(1);
+    let Some(status) = &statefulset.status else {
+        return replicas == 0;
+    };
+
+    status.observed_generation >= statefulset.metadata.generation
+        && status.updated_replicas.unwrap_or(0) >= replicas
+        && status.ready_replicas.unwrap_or(0) >= replicas
+}
+
+fn is_daemonset_ready(daemonset: &DaemonSet) -> bool {
+    let Some(status) = &daemonset.status else {
+        return false;
+    };
+
+    status.observed_generation >= daemonset.metadata.generation
+        && status.updated_number_scheduled.unwrap_or(0) >= status.desired_number_scheduled
+        && status.number_ready >= status.desired_number_scheduled
+}
+
+pub(super) fn is_workload_ready(kube: &kube::Client, workload: &ReleaseWorkload) -> bool {
+    match workload.kind.as_str() {
+        "Deployment" => block_on(Api::<Deployment>::namespaced(kube.clone(), &workload.namespace).get(&workload.name))
+            .map(|deployment| is_deployment_ready(&deployment))
+            .unwrap_or(false),
+        "StatefulSet" => {
+            block_on(Api::<StatefulSet>::namespaced(kube.clone(), &workload.namespace).get(&workload.name))
+                .map(|statefulset| is_statefulset_ready(&statefulset))
+                .unwrap_or(false)
+        }
+        "DaemonSet" => block_on(Api::<DaemonSet>::namespaced(kube.clone(), &workload.namespace).get(&workload.name))
+            .map(|daemonset| is_daemonset_ready(&daemonset))
+            .unwrap_or(false),
+        _ => true,
+    }
+}
+
+/// Once installed, the deployments, statefulsets and daemonsets of the release must all be rolled out
+fn check_release_workloads_are_ready<T: CloudProvider>(
+    this: &HelmChart<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    logger: &EnvProgressLogger,
+) -> Result<(), Box<EngineError>> {
+    let manifest = target
+        .helm
+        .get_release_manifest(this.helm_release_name(), target.environment.namespace(), &[])
+        .map_err(|e| (event_details.clone(), e))?;
+    let workloads = release_workloads(&manifest, target.environment.namespace());
+    if workloads.is_empty() {
+        return Ok(());
+    }
+
+    logger.info(format!(
+        "🩺 Waiting for the {} workload(s) of the Helm chart to be ready",
+        workloads.len()
+    ));
+    let started_at = Instant::now();
+    loop {
+        let not_ready_workloads: Vec<String> = workloads
+            .iter()
+            .filter(|workload| !is_workload_ready(&target.kube, workload))
+            .map(|workload| workload.to_string())
+            .collect();
+        if not_ready_workloads.is_empty() {
+            return Ok(());
+        }
+
+        if started_at.elapsed() >= this.helm_timeout() || (target.should_abort)() {
+            return Err((
+                event_details,
+                HelmChartError::WorkloadsNotReady {
+                    chart_name: this.name().to_string(),
+                    msg: not_ready_workloads.join(", "),
+                },
+            )
+                .into());
+        }
+        thread::sleep(HELM_CHART_STATUS_CHECK_INTERVAL);
+    }
+}
+
 fn check_resources_are_allowed_to_install<T: CloudProvider>(
     this: &HelmChart<T>,
     target: &DeploymentTarget,
@@ -541,6 +677,13 @@ fn check_resources_are_allowed_to_install<T: CloudProvider>(
         )
         .map_err(|e| (event_details.clone(), e))?;
 
+    // Resources the next cluster version does not serve anymore would break the chart once the cluster is upgraded
+    let kubernetes_version = target.kubernetes.version();
+    let upgrade_version = kubernetes_version.next_version().unwrap_or(kubernetes_version);
+    for removed_api in find_removed_apis(&template, &upgrade_version) {
+        logger.warning(format!("⚠️ {removed_api}"));
+    }
+
     for document in serde_yaml::Deserializer::from_str(&template) {
         let kube_obj: PartialObjectMeta<()> = PartialObjectMeta::deserialize(document).map_err(|err| {
             error!("Cannot deserialize yaml into kube resource {:?}", err);
@@ -668,6 +811,7 @@ fn is_allowed_namespaced_resource(namespace: &str, kube_obj: &PartialObjectMeta<
 mod tests {
     use super::*;
     use crate::models;
+    use k8s_openapi::api::apps::v1::{DeploymentSpec, DeploymentStatus};
 
     use maplit::hashmap;
 
@@ -937,4 +1081,71 @@ spec:
             PartialObjectMeta::deserialize(serde_yaml::Deserializer::from_str(resource)).unwrap();
         assert!(is_allowed_namespaced_resource("tesotron", &ns).is_err());
     }
+
+    #[test]
+    fn test_release_workloads() {
+        let manifest = r#"
+---
+# Source: my-chart/templates/service.yaml
+apiVersion: v1
+kind: Service
+metadata:
+  name: my-app
+---
+# Source: my-chart/templates/deployment.yaml
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: my-app
+---
+# Source: my-chart/templates/statefulset.yaml
+apiVersion: apps/v1
+kind: StatefulSet
+metadata:
+  name: my-db
+  namespace: other
+"#;
+
+        assert_eq!(
+            release_workloads(manifest, "z-env"),
+            vec![
+                ReleaseWorkload {
+                    kind: "Deployment".to_string(),
+                    name: "my-app".to_string(),
+                    namespace: "z-env".to_string(),
+                },
+                ReleaseWorkload {
+                    kind: "StatefulSet".to_string(),
+                    name: "my-db".to_string(),
+                    namespace: "other".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_deployment_ready() {
+        let deployment = |generation: i64, observed_generation: i64, updated: i32, ready: i32| {
+            let mut deployment = Deployment::default();
+            deployment.metadata.generation = Some(generation);
+            deployment.spec = Some(DeploymentSpec {
+                replicas: Some(2),
+                ..Default::default()
+            });
+            deployment.status = Some(DeploymentStatus {
+                observed_generation: Some(observed_generation),
+                updated_replicas: Some(updated),
+                ready_replicas: Some(ready),
+                ..Default::default()
+            });
+            deployment
+        };
+
+        assert!(is_deployment_ready(&deployment(2, 2, 2, 2)));
+        // the new spec is not rolled out yet
+        assert!(!is_deployment_ready(&deployment(3, 2, 2, 2)));
+        assert!(!is_deployment_ready(&deployment(2, 2, 1, 2)));
+        assert!(!is_deployment_ready(&deployment(2, 2, 2, 1)));
+        assert!(!is_deployment_ready(&Deployment::default()));
+    }
 }
@@ -15,19 +15,24 @@ use crate::events::EngineEvent;
 use crate::events::{EnvironmentStep, EventDetails, EventMessage, Stage};
 use crate::io_models::job::JobSchedule;
 use crate::models::job::{ImageSource, Job, JobService};
+use crate::models::job_runs::{MANUAL_TRIGGER_ANNOTATION, MANUAL_TRIGGER_ANNOTATION_VALUE};
 use crate::models::types::{CloudProvider, ToTeraContext};
 use crate::runtime::block_on;
+use chrono::{DateTime, Utc};
 use k8s_openapi::api::batch::v1::{CronJob, Job as K8sJob};
 use k8s_openapi::api::core::v1::Pod;
 use kube::api::{AttachParams, ListParams, PostParams};
 use kube::runtime::wait::{await_condition, Condition};
-use kube::Api;
+use kube::{Api, Resource};
 use retry::{Error, OperationResult};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+// We wait 1h + delta max for the job to be terminated
+const JOB_TERMINATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3800);
+
 impl<T: CloudProvider> DeploymentAction for Job<T>
 where
     Job<T>: ToTeraContext,
@@ -132,6 +137,20 @@ where
             command_error,
         )));
     }
+
+    fn on_trigger_now(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        // Only cronjobs can be run out of their schedule, other jobs run on deployment
+        if !self.is_cron_job() {
+            return self.on_create(target);
+        }
+
+        let event_details = self.get_event_details(Stage::Environment(self.action().to_environment_step()));
+        let task = |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            trigger_cronjob_now(self, target, logger, &event_details)
+        };
+
+        execute_long_deployment(JobDeploymentReporter::new(self, target, Action::TriggerNow), task)
+    }
 }
 
 struct TaskContext {
@@ -346,49 +365,18 @@ where
                 )
             })?;
 
-            let mut job_template = cronjob.spec.unwrap().job_template;
+            let mut job_to_start = job_from_cronjob(cronjob);
             // For kube to automatically cleanup the job for us
-            job_template.spec.as_mut().unwrap().ttl_seconds_after_finished = Some(10);
-            let job_to_start = K8sJob {
-                metadata: job_template.metadata.unwrap(),
-                spec: job_template.spec,
-                status: None,
-            };
+            if let Some(spec) = job_to_start.spec.as_mut() {
+                spec.ttl_seconds_after_finished = Some(10);
+            }
             block_on(k8s_job_api.create(&PostParams::default(), &job_to_start)).map_err(|err| {
                 EngineError::new_job_error(event_details.clone(), format!("Cannot create job from cronjob: {err}"))
             })?;
 
-            let fut = async {
-                match tokio::time::timeout(
-                    std::time::Duration::from_secs(3800), // We wait 1h + delta max for the job to be terminated
-                    await_condition(k8s_job_api, job.kube_name(), is_job_terminated()),
-                )
-                .await
-                {
-                    Ok(Ok(job_st)) => Ok(job_status(&job_st.as_ref())),
-                    Ok(Err(err)) => Err(err),
-                    Err(_) => Ok(JobStatus::Running), // timeout
-                }
-            };
-            let job_status = block_on(fut).map_err(|_err| {
-                EngineError::new_job_error(event_details.clone(), "Cannot find job for terminated pod".to_string())
-            })?;
-
-            let cronjob_result = match job_status {
-                JobStatus::Success => Ok(()),
-                JobStatus::Running => {
-                    logger.info("Job is still running after 1h. Stopping waiting for it. Please check live-logs and service status to know its status".to_string());
-                    Ok(())
-                }
-                JobStatus::NotRunning => {
-                    let msg = "Job failed to correctly run due to `NotRunning`. This should not happen".to_string();
-                    Err(EngineError::new_job_error(event_details.clone(), msg))
-                }
-                JobStatus::Failure { reason, message } => {
-                    let msg = format!("Job failed to correctly run due to {reason} {message}");
-                    Err(EngineError::new_job_error(event_details.clone(), msg))
-                }
-            };
+            let job_status =
+                await_job_termination(k8s_job_api, job.kube_name(), JOB_TERMINATION_TIMEOUT, event_details)?;
+            let cronjob_result = job_status_to_result(job_status, logger, event_details);
 
             // uninstall cronjob if it was already present
             if !cronjob_is_already_installed {
@@ -513,7 +501,112 @@ where
     (pre_run, task, post_run)
 }
 
-enum JobStatus {
+/// Build a job out of the job template of a cronjob
+fn job_from_cronjob(cronjob: CronJob) -> K8sJob {
+    let job_template = cronjob.spec.map(|spec| spec.job_template).unwrap_or_default();
+    K8sJob {
+        metadata: job_template.metadata.unwrap_or_default(),
+        spec: job_template.spec,
+        status: None,
+    }
+}
+
+/// Job started on demand out of a cronjob, owned by the cronjob so it shows up in its history and gets cleaned up with it
+fn manual_job_from_cronjob(cronjob: CronJob, now: DateTime<Utc>) -> K8sJob {
+    let owner_reference = cronjob.controller_owner_ref(&());
+    let cronjob_name = cronjob.metadata.name.clone().unwrap_or_default();
+    let mut job = job_from_cronjob(cronjob);
+
+    // Job name must stay a valid label value (63 chars max), the same way the cronjob controller truncates names
+    let cronjob_name = &cronjob_name[..cronjob_name.len().min(52)];
+    job.metadata.name = Some(format!("{}-{}", cronjob_name, now.timestamp()));
+    job.metadata.annotations.get_or_insert_with(Default::default).insert(
+        MANUAL_TRIGGER_ANNOTATION.to_string(),
+        MANUAL_TRIGGER_ANNOTATION_VALUE.to_string(),
+    );
+    job.metadata.owner_references = owner_reference.map(|owner| vec![owner]);
+    job
+}
+
+pub(super) fn await_job_termination(
+    k8s_job_api: Api<K8sJob>,
+    job_name: &str,
+    timeout: std::time::Duration,
+    event_details: &EventDetails,
+) -> Result<JobStatus, Box<EngineError>> {
+    let fut = async {
+        match tokio::time::timeout(timeout, await_condition(k8s_job_api, job_name, is_job_terminated())).await {
+            Ok(Ok(job_st)) => Ok(job_status(&job_st.as_ref())),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Ok(JobStatus::Running), // timeout
+        }
+    };
+
+    block_on(fut).map_err(|_err| {
+        Box::new(EngineError::new_job_error(
+            event_details.clone(),
+            "Cannot find job for terminated pod".to_string(),
+        ))
+    })
+}
+
+fn job_status_to_result(
+    job_status: JobStatus,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>> {
+    match job_status {
+        JobStatus::Success => Ok(()),
+        JobStatus::Running => {
+            logger.info("Job is still running after 1h. Stopping waiting for it. Please check live-logs and service status to know its status".to_string());
+            Ok(())
+        }
+        JobStatus::NotRunning => {
+            let msg = "Job failed to correctly run due to `NotRunning`. This should not happen".to_string();
+            Err(Box::new(EngineError::new_job_error(event_details.clone(), msg)))
+        }
+        JobStatus::Failure { reason, message } => {
+            let msg = format!("Job failed to correctly run due to {reason} {message}");
+            Err(Box::new(EngineError::new_job_error(event_details.clone(), msg)))
+        }
+    }
+}
+
+/// Run the already deployed cronjob right away, without waiting for its schedule
+fn trigger_cronjob_now<T: CloudProvider>(
+    job: &Job<T>,
+    target: &DeploymentTarget,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>>
+where
+    Job<T>: JobService,
+{
+    let k8s_cronjob_api: Api<CronJob> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let cronjob = block_on(k8s_cronjob_api.get(job.kube_name())).map_err(|err| {
+        EngineError::new_job_error(
+            event_details.clone(),
+            format!(
+                "Cannot get cronjob {}, it must be deployed before being triggered: {}",
+                job.kube_name(),
+                err
+            ),
+        )
+    })?;
+
+    let k8s_job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let job_to_start = manual_job_from_cronjob(cronjob, Utc::now());
+    let job_name = job_to_start.metadata.name.clone().unwrap_or_default();
+    block_on(k8s_job_api.create(&PostParams::default(), &job_to_start)).map_err(|err| {
+        EngineError::new_job_error(event_details.clone(), format!("Cannot create job from cronjob: {err}"))
+    })?;
+    logger.info(format!("Job {job_name} has been created from cronjob {}", job.kube_name()));
+
+    let job_status = await_job_termination(k8s_job_api, &job_name, JOB_TERMINATION_TIMEOUT, event_details)?;
+    job_status_to_result(job_status, logger, event_details)
+}
+
+pub(super) enum JobStatus {
     NotRunning,
     Running,
     Success,
@@ -674,7 +767,7 @@ fn is_job_pod_container_terminated(job_container_name: &str) -> impl Condition<P
 // Used to validate the job json output format with serde
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
 #[serde(default)]
-struct JobOutputVariable {
+pub(super) struct JobOutputVariable {
     pub value: String,
     pub sensitive: bool,
 }
@@ -688,7 +781,7 @@ impl Default for JobOutputVariable {
     }
 }
 
-fn serialize_job_output(json: &str) -> Result<HashMap<String, JobOutputVariable>, serde_json::Error> {
+pub(super) fn serialize_job_output(json: &str) -> Result<HashMap<String, JobOutputVariable>, serde_json::Error> {
     let serde_hash_map: HashMap<&str, Value> = serde_json::from_str(json)?;
     let mut job_output_variables: HashMap<String, JobOutputVariable> = HashMap::new();
 
@@ -730,7 +823,11 @@ fn serialize_job_output(json: &str) -> Result<HashMap<String, JobOutputVariable>
 
 #[cfg(test)]
 mod test {
-    use crate::deployment_action::deploy_job::{serialize_job_output, JobOutputVariable};
+    use crate::deployment_action::deploy_job::{manual_job_from_cronjob, serialize_job_output, JobOutputVariable};
+    use crate::models::job_runs::MANUAL_TRIGGER_ANNOTATION;
+    use chrono::{TimeZone, Utc};
+    use k8s_openapi::api::batch::v1::{CronJob, CronJobSpec, JobTemplateSpec};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
 
     #[test]
     fn should_serialize_json_to_job_output_variable_with_string_value() {
@@ -787,4 +884,44 @@ mod test {
         let json_final = serde_json::to_string(&hashmap).unwrap();
         println!("{json_final}");
     }
+
+    #[test]
+    fn should_create_manual_job_from_cronjob() {
+        // given
+        let cronjob = CronJob {
+            metadata: ObjectMeta {
+                name: Some("a".repeat(60)),
+                uid: Some("6f1c3b1e-5a31-4e64-9c39-6d3d5f0c1a2b".to_string()),
+                ..Default::default()
+            },
+            spec: Some(CronJobSpec {
+                job_template: JobTemplateSpec {
+                    metadata: Some(ObjectMeta {
+                        name: Some("a".repeat(60)),
+                        ..Default::default()
+                    }),
+                    spec: Some(Default::default()),
+                },
+                ..Default::default()
+            }),
+            status: None,
+        };
+        let now = Utc.with_ymd_and_hms(2023, 11, 2, 10, 0, 0).unwrap();
+
+        // execute
+        let job = manual_job_from_cronjob(cronjob, now);
+
+        // verify
+        let job_name = job.metadata.name.unwrap();
+        assert_eq!(job_name, format!("{}-{}", "a".repeat(52), now.timestamp()));
+        assert!(job_name.len() <= 63);
+        assert_eq!(
+            job.metadata.annotations.unwrap().get(MANUAL_TRIGGER_ANNOTATION),
+            Some(&"manual".to_string())
+        );
+        let owner = &job.metadata.owner_references.unwrap()[0];
+        assert_eq!(owner.kind, "CronJob");
+        assert_eq!(owner.uid, "6f1c3b1e-5a31-4e64-9c39-6d3d5f0c1a2b");
+        assert!(job.spec.is_some());
+    }
 }
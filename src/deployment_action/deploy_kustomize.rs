@@ -0,0 +1,373 @@
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::kubectl::{kubectl_exec_kustomize_build, kubectl_exec_with_output};
+use crate::constants::KUBECONFIG;
+use crate::deployment_action::deploy_helm_chart::{git_credentials_callback, is_workload_ready, release_workloads};
+use crate::deployment_action::pause_service::PauseServiceAction;
+use crate::deployment_action::restart_service::RestartServiceAction;
+use crate::deployment_action::{DeploymentAction, K8sResourceType};
+use crate::deployment_report::kustomize::reporter::KustomizeDeploymentReporter;
+use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
+use crate::deployment_report::{execute_long_deployment, DeploymentTaskImpl};
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EnvironmentStep, EventDetails, Stage};
+use crate::git;
+use crate::models::kustomize::Kustomize;
+use kube::api::PartialObjectMeta;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const KUSTOMIZE_STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Kinds of the resources of an overlay removed with the service, they all carry its labels
+const KUSTOMIZE_DELETED_KINDS: &[&str] = &[
+    "deployments",
+    "statefulsets",
+    "daemonsets",
+    "cronjobs",
+    "jobs",
+    "services",
+    "ingresses",
+    "configmaps",
+    "secrets",
+    "serviceaccounts",
+    "roles",
+    "rolebindings",
+    "poddisruptionbudgets",
+    "horizontalpodautoscalers",
+    "networkpolicies",
+    "persistentvolumeclaims",
+];
+
+impl DeploymentAction for Kustomize {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+
+        let pre_run = |logger: &EnvProgressLogger| -> Result<PathBuf, Box<EngineError>> {
+            render_manifests(self, target, event_details.clone(), logger)
+        };
+
+        let run = |logger: &EnvProgressLogger, manifests_path: PathBuf| -> Result<PathBuf, Box<EngineError>> {
+            // unpause cron job if necessary
+            let _ = PauseServiceAction::new_with_resource_type(
+                self.kube_label_selector(),
+                K8sResourceType::CronJob,
+                Duration::from_secs(5 * 60),
+                event_details.clone(),
+                false,
+            )
+            .unpause_if_needed(target);
+
+            // resources removed from the overlay since the last deployment are pruned, thanks to the service label
+            logger.info("🚢 Applying the manifests of the overlay".to_string());
+            let selector = self.kube_label_selector();
+            let manifests_file = manifests_path.to_string_lossy().to_string();
+            kubectl(
+                target,
+                vec![
+                    "apply",
+                    "-n",
+                    target.environment.namespace(),
+                    "--prune",
+                    "-l",
+                    selector.as_str(),
+                    "-f",
+                    manifests_file.as_str(),
+                ],
+                logger,
+            )
+            .map_err(|err| {
+                to_error(
+                    self,
+                    &event_details,
+                    "Cannot apply the manifests of the overlay".to_string(),
+                    Some(err),
+                )
+            })?;
+
+            check_workloads_are_ready(self, target, &manifests_path, event_details.clone(), logger)?;
+            Ok(manifests_path)
+        };
+
+        let post_run = |_logger: &EnvSuccessLogger, _manifests_path: PathBuf| {};
+
+        let task = DeploymentTaskImpl {
+            pre_run: &pre_run,
+            run: &run,
+            post_run_success: &post_run,
+        };
+
+        execute_long_deployment(KustomizeDeploymentReporter::new(self, target, Action::Create), task)
+    }
+
+    fn on_pause(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Pause));
+
+        let task = |_logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            for resource_type in [
+                K8sResourceType::CronJob,
+                K8sResourceType::Deployment,
+                K8sResourceType::StateFulSet,
+            ] {
+                PauseServiceAction::new_with_resource_type(
+                    self.kube_label_selector(),
+                    resource_type,
+                    Duration::from_secs(5 * 60),
+                    event_details.clone(),
+                    false,
+                )
+                .on_pause(target)?;
+            }
+            Ok(())
+        };
+
+        execute_long_deployment(KustomizeDeploymentReporter::new(self, target, Action::Pause), task)
+    }
+
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Delete));
+
+        let task = |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            let kinds = KUSTOMIZE_DELETED_KINDS.join(",");
+            let selector = self.kube_label_selector();
+            kubectl(
+                target,
+                vec![
+                    "delete",
+                    kinds.as_str(),
+                    "-n",
+                    target.environment.namespace(),
+                    "-l",
+                    selector.as_str(),
+                    "--ignore-not-found",
+                    "--wait",
+                ],
+                logger,
+            )
+            .map_err(|err| {
+                to_error(
+                    self,
+                    &event_details,
+                    "Cannot delete the resources of the overlay".to_string(),
+                    Some(err),
+                )
+            })
+        };
+
+        execute_long_deployment(KustomizeDeploymentReporter::new(self, target, Action::Delete), task)
+    }
+
+    fn on_restart(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Restart));
+
+        let task = |_logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            for resource_type in [
+                K8sResourceType::DaemonSet,
+                K8sResourceType::Deployment,
+                K8sResourceType::StateFulSet,
+            ] {
+                RestartServiceAction::new_with_resource_type(
+                    self.kube_label_selector(),
+                    resource_type,
+                    event_details.clone(),
+                    false,
+                )
+                .on_restart(target)?;
+            }
+            Ok(())
+        };
+
+        execute_long_deployment(KustomizeDeploymentReporter::new(self, target, Action::Restart), task)
+    }
+}
+
+fn to_error(
+    this: &Kustomize,
+    event_details: &EventDetails,
+    message: String,
+    raw_error: Option<CommandError>,
+) -> Box<EngineError> {
+    Box::new(EngineError::new_kustomize_deployment_failed(
+        event_details.clone(),
+        this.name(),
+        message,
+        raw_error,
+    ))
+}
+
+fn kubectl(target: &DeploymentTarget, args: Vec<&str>, logger: &EnvProgressLogger) -> Result<(), CommandError> {
+    let kubeconfig = target.kubernetes.kubeconfig_local_file_path();
+    let mut envs = vec![(KUBECONFIG, kubeconfig.to_str().unwrap_or_default())];
+    envs.extend(target.cloud_provider.credentials_environment_variables());
+
+    kubectl_exec_with_output(args, envs, &mut |line| logger.info(line), &mut |line| logger.warning(line))
+}
+
+// Goal is to get the manifests of the overlay, patched by the engine, ready to be applied
+// 1. Clone the repository at the commit
+// 2. Write a kustomization on top of the overlay, with the namespace, the images and the labels of the service
+// 3. Render it and check it only holds resources of the environment namespace
+fn render_manifests(
+    this: &Kustomize,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    logger: &EnvProgressLogger,
+) -> Result<PathBuf, Box<EngineError>> {
+    let source = this.source();
+    logger.info(format!(
+        "📥 Cloning kustomize overlay from git repository {} at commit {}",
+        source.git_url, source.commit_id
+    ));
+
+    let git_creds = (source.get_credentials)()
+        .map_err(|e| to_error(this, &event_details, format!("Cannot get git credentials due to {e}"), None))?;
+    git::clone_at_commit(
+        &source.git_url,
+        &source.commit_id,
+        &this.repository_directory(),
+        &git_credentials_callback(&git_creds, &source.ssh_keys),
+    )
+    .map_err(|e| to_error(this, &event_details, format!("Cannot clone git repository due to {e}"), None))?;
+
+    if !this.repository_directory().join(&source.overlay_path).is_dir() {
+        return Err(to_error(
+            this,
+            &event_details,
+            format!("Overlay directory {:?} does not exist in the repository", source.overlay_path),
+            None,
+        ));
+    }
+
+    let kustomization = this.kustomization(
+        target.environment.namespace(),
+        &target.environment.long_id,
+        &target.environment.project_long_id,
+    );
+    let kustomization = serde_yaml::to_string(&kustomization)
+        .map_err(|e| to_error(this, &event_details, format!("Cannot serialize kustomization due to {e}"), None))?;
+    fs::create_dir_all(this.kustomization_directory())
+        .and_then(|_| fs::write(this.kustomization_directory().join("kustomization.yaml"), kustomization))
+        .map_err(|e| to_error(this, &event_details, format!("Cannot write kustomization due to {e}"), None))?;
+
+    logger.info(format!("🔨 Rendering kustomize overlay {:?}", source.overlay_path));
+    let manifests = kubectl_exec_kustomize_build(
+        target.kubernetes.kubeconfig_local_file_path(),
+        target.cloud_provider.credentials_environment_variables(),
+        &this.kustomization_directory().to_string_lossy(),
+    )
+    .map_err(|err| to_error(this, &event_details, "Cannot render the overlay".to_string(), Some(err)))?;
+
+    let cluster_wide_resources = cluster_wide_resources(&manifests);
+    if !cluster_wide_resources.is_empty() {
+        return Err(to_error(
+            this,
+            &event_details,
+            format!(
+                "Only resources of the environment namespace can be deployed, the overlay holds {}",
+                cluster_wide_resources.join(", ")
+            ),
+            None,
+        ));
+    }
+
+    let manifests_path = this.workspace_directory().join("manifests.yaml");
+    fs::write(&manifests_path, manifests).map_err(|e| {
+        to_error(
+            this,
+            &event_details,
+            format!("Cannot write rendered manifests due to {e}"),
+            None,
+        )
+    })?;
+
+    Ok(manifests_path)
+}
+
+/// Resources without a namespace once rendered, kustomize sets the one of the environment on all the namespaced ones
+fn cluster_wide_resources(manifests: &str) -> Vec<String> {
+    serde_yaml::Deserializer::from_str(manifests)
+        .filter_map(|document| PartialObjectMeta::<()>::deserialize(document).ok())
+        .filter(|kube_obj| kube_obj.metadata.namespace.is_none())
+        .map(|kube_obj| {
+            format!(
+                "{} {}",
+                kube_obj.types.map(|types| types.kind).unwrap_or_default(),
+                kube_obj.metadata.name.unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+/// Once applied, the deployments, statefulsets and daemonsets of the overlay must all be rolled out
+fn check_workloads_are_ready(
+    this: &Kustomize,
+    target: &DeploymentTarget,
+    manifests_path: &Path,
+    event_details: EventDetails,
+    logger: &EnvProgressLogger,
+) -> Result<(), Box<EngineError>> {
+    let manifests = fs::read_to_string(manifests_path)
+        .map_err(|e| to_error(this, &event_details, format!("Cannot read rendered manifests due to {e}"), None))?;
+    let workloads = release_workloads(&manifests, target.environment.namespace());
+    if workloads.is_empty() {
+        return Ok(());
+    }
+
+    logger.info(format!(
+        "🩺 Waiting for the {} workload(s) of the overlay to be ready",
+        workloads.len()
+    ));
+    let started_at = Instant::now();
+    loop {
+        let not_ready_workloads: Vec<String> = workloads
+            .iter()
+            .filter(|workload| !is_workload_ready(&target.kube, workload))
+            .map(|workload| workload.to_string())
+            .collect();
+        if not_ready_workloads.is_empty() {
+            return Ok(());
+        }
+
+        if started_at.elapsed() >= this.timeout() || (target.should_abort)() {
+            return Err(to_error(
+                this,
+                &event_details,
+                format!("Workloads are not ready: {}", not_ready_workloads.join(", ")),
+                None,
+            ));
+        }
+        thread::sleep(KUSTOMIZE_STATUS_CHECK_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_wide_resources() {
+        let manifests = r#"
+apiVersion: v1
+kind: Service
+metadata:
+  name: api
+  namespace: z-env
+---
+apiVersion: rbac.authorization.k8s.io/v1
+kind: ClusterRole
+metadata:
+  name: api-reader
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: api
+  namespace: z-env
+"#;
+
+        assert_eq!(cluster_wide_resources(manifests), vec!["ClusterRole api-reader".to_string()]);
+        assert!(cluster_wide_resources("").is_empty());
+    }
+}
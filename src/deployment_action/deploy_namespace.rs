@@ -1,17 +1,32 @@
+use crate::cloud_provider::environment::{Environment, ENVIRONMENT_SERVICE_ACCOUNT_NAME};
+use crate::cloud_provider::helm_charts::nfs_server_provisioner_chart::NFS_STORAGE_CLASS_NAME;
+use crate::cloud_provider::helm_charts::qovery_storage_class_chart::{
+    AWS_EFS_STORAGE_CLASS_NAME, GKE_FILESTORE_STORAGE_CLASS_NAME,
+};
 use crate::cloud_provider::kubernetes::{
     kube_copy_secret_to_another_namespace, kube_create_namespace_if_not_exists, kube_does_secret_exists, Kind,
+    Kubernetes,
 };
 use crate::cloud_provider::DeploymentTarget;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::{CommandError, EngineError};
 use crate::events::EventDetails;
+use crate::io_models::shared_volume::SharedVolume;
+use crate::kubers_utils::kube_apply_resource;
 use crate::runtime::block_on;
-use k8s_openapi::api::core::v1::Namespace;
-use kube::api::DeleteParams;
+use k8s_openapi::api::core::v1::{
+    Namespace, PersistentVolumeClaim, PersistentVolumeClaimSpec, ResourceRequirements, ServiceAccount,
+};
+use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{DeleteParams, ListParams};
 use kube::Api;
 use std::collections::BTreeMap;
 use std::time::Duration;
 
+const SHARED_VOLUME_ID_LABEL: &str = "qovery.com/shared-volume-id";
+
 pub struct NamespaceDeployment {
     pub resource_expiration: Option<Duration>,
     pub event_details: EventDetails,
@@ -49,6 +64,38 @@ impl DeploymentAction for NamespaceDeployment {
             )
         })?;
 
+        if let Some(service_account) = environment_service_account(target.environment) {
+            let role = environment_service_account_role(target.environment);
+            let role_binding = environment_service_account_role_binding(target.environment);
+            let namespace = target.environment.namespace();
+            block_on(kube_apply_resource(&target.kube, namespace, &service_account)).map_err(|e| {
+                EngineError::new_k8s_cannot_apply_from_resource(self.event_details.clone(), service_account.clone(), e)
+            })?;
+            block_on(kube_apply_resource(&target.kube, namespace, &role)).map_err(|e| {
+                EngineError::new_k8s_cannot_apply_from_resource(self.event_details.clone(), role.clone(), e)
+            })?;
+            block_on(kube_apply_resource(&target.kube, namespace, &role_binding)).map_err(|e| {
+                EngineError::new_k8s_cannot_apply_from_resource(self.event_details.clone(), role_binding.clone(), e)
+            })?;
+        }
+
+        if !target.environment.shared_volumes.is_empty() {
+            let Some(storage_class) = shared_volume_storage_class(target.kubernetes) else {
+                return Err(Box::new(EngineError::new_shared_volumes_not_supported(
+                    self.event_details.clone(),
+                    &target.kubernetes.kind().to_string(),
+                )));
+            };
+            let namespace = target.environment.namespace();
+            for shared_volume in &target.environment.shared_volumes {
+                let claim = shared_volume_claim(target.environment, shared_volume, storage_class);
+                block_on(kube_apply_resource(&target.kube, namespace, &claim)).map_err(|e| {
+                    EngineError::new_k8s_cannot_apply_from_resource(self.event_details.clone(), claim.clone(), e)
+                })?;
+            }
+        }
+        delete_removed_shared_volumes(target);
+
         // upmc-enterprises/registry-creds sometimes is too long to copy the secret to the namespace
         // this workaround speed up the process to avoid application fails with ImagePullError on the first deployment
         if target.kubernetes.kind() == Kind::Ec2 {
@@ -99,3 +146,124 @@ impl DeploymentAction for NamespaceDeployment {
         Ok(())
     }
 }
+
+fn environment_metadata(environment: &Environment, name: &str) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(name.to_string()),
+        namespace: Some(environment.namespace().to_string()),
+        labels: Some(BTreeMap::from([(
+            "qovery.com/environment-id".to_string(),
+            environment.long_id.to_string(),
+        )])),
+        ..Default::default()
+    }
+}
+
+fn environment_service_account(environment: &Environment) -> Option<ServiceAccount> {
+    let name = environment.service_account_name()?;
+    let mut metadata = environment_metadata(environment, name);
+    metadata.annotations = Some(environment.service_account.annotations.clone());
+
+    Some(ServiceAccount {
+        metadata,
+        automount_service_account_token: Some(environment.service_account.automount_token),
+        ..Default::default()
+    })
+}
+
+// Read only access to its own namespace, enough for peers discovery without exposing secrets
+fn environment_service_account_role(environment: &Environment) -> Role {
+    Role {
+        metadata: environment_metadata(environment, ENVIRONMENT_SERVICE_ACCOUNT_NAME),
+        rules: Some(vec![PolicyRule {
+            api_groups: Some(vec!["".to_string()]),
+            resources: Some(vec!["pods".to_string(), "services".to_string(), "endpoints".to_string()]),
+            verbs: vec!["get".to_string(), "list".to_string(), "watch".to_string()],
+            ..Default::default()
+        }]),
+    }
+}
+
+fn environment_service_account_role_binding(environment: &Environment) -> RoleBinding {
+    RoleBinding {
+        metadata: environment_metadata(environment, ENVIRONMENT_SERVICE_ACCOUNT_NAME),
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "Role".to_string(),
+            name: ENVIRONMENT_SERVICE_ACCOUNT_NAME.to_string(),
+        },
+        subjects: Some(vec![Subject {
+            kind: "ServiceAccount".to_string(),
+            name: ENVIRONMENT_SERVICE_ACCOUNT_NAME.to_string(),
+            namespace: Some(environment.namespace().to_string()),
+            ..Default::default()
+        }]),
+    }
+}
+
+/// Storage class of the shared volumes, none when the cluster provides no ReadWriteMany storage
+fn shared_volume_storage_class(kubernetes: &dyn Kubernetes) -> Option<&'static str> {
+    let shared_volumes_enabled = kubernetes.advanced_settings().storage_shared_volumes_enabled;
+    match kubernetes.kind() {
+        // the Filestore CSI driver is enabled on every autopilot cluster
+        Kind::Gke => Some(GKE_FILESTORE_STORAGE_CLASS_NAME),
+        Kind::Eks if shared_volumes_enabled => Some(AWS_EFS_STORAGE_CLASS_NAME),
+        Kind::ScwKapsule if shared_volumes_enabled => Some(NFS_STORAGE_CLASS_NAME),
+        _ => None,
+    }
+}
+
+fn shared_volume_claim(
+    environment: &Environment,
+    shared_volume: &SharedVolume,
+    storage_class: &str,
+) -> PersistentVolumeClaim {
+    let mut metadata = environment_metadata(environment, &shared_volume.claim_name());
+    if let Some(labels) = metadata.labels.as_mut() {
+        labels.insert(SHARED_VOLUME_ID_LABEL.to_string(), shared_volume.long_id.to_string());
+    }
+    metadata.annotations = Some(BTreeMap::from([(
+        "qovery.com/shared-volume-name".to_string(),
+        shared_volume.name.clone(),
+    )]));
+
+    PersistentVolumeClaim {
+        metadata,
+        spec: Some(PersistentVolumeClaimSpec {
+            access_modes: Some(vec!["ReadWriteMany".to_string()]),
+            storage_class_name: Some(storage_class.to_string()),
+            resources: Some(ResourceRequirements {
+                requests: Some(BTreeMap::from([(
+                    "storage".to_string(),
+                    Quantity(format!("{}Gi", shared_volume.size_in_gib)),
+                )])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        status: None,
+    }
+}
+
+// Claims of the shared volumes removed from the environment. Kubernetes keeps them until no pod mounts them anymore,
+// so the services still mounting them are not broken in the middle of the deployment.
+fn delete_removed_shared_volumes(target: &DeploymentTarget) {
+    let api: Api<PersistentVolumeClaim> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let Ok(claims) = block_on(api.list(&ListParams::default().labels(SHARED_VOLUME_ID_LABEL))) else {
+        return;
+    };
+    for claim in claims {
+        let Some(name) = claim.metadata.name else {
+            continue;
+        };
+        let is_removed = !target
+            .environment
+            .shared_volumes
+            .iter()
+            .any(|shared_volume| shared_volume.claim_name() == name);
+        if is_removed {
+            // do not catch potential error, the claim is deleted again on the next deployment
+            let _ = block_on(api.delete(&name, &DeleteParams::default()));
+        }
+    }
+}
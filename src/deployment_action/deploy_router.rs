@@ -2,17 +2,24 @@ use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
 use crate::cloud_provider::models::CustomDomain;
 use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::certificate_dns_records::ensure_certificate_dns_records;
 use crate::deployment_action::check_dns::CheckDnsForDomains;
+use crate::deployment_action::custom_metadata::apply_custom_metadata;
 use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::router_probe::{route_probes, run_route_probes, RouteProbeOutcome};
+use crate::deployment_action::waf_report::{blocked_requests, effective_waf_mode, WAF_REPORT_PERIOD_SECONDS};
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::router::reporter::RouterDeploymentReporter;
 use crate::deployment_report::{execute_long_deployment, DeploymentTaskImpl};
+use crate::dns_provider::dns_check::{DnsCheckConfig, DnsResolver};
 use crate::errors::EngineError;
-use crate::events::{EnvironmentStep, Stage};
-use crate::models::router::Router;
+use crate::events::{EngineEvent, EnvironmentStep, Stage};
+use crate::io_models::WafMode;
+use crate::models::router::{custom_certificate_warnings, requires_certificate, Router};
 use crate::models::types::{CloudProvider, ToTeraContext};
 
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
+use chrono::Utc;
 use std::path::PathBuf;
 
 impl<T: CloudProvider> DeploymentAction for Router<T>
@@ -21,8 +28,22 @@ where
 {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
-        let pre_run = |_: &EnvProgressLogger| -> Result<(), Box<EngineError>> { Ok(()) };
-        let run = |logger: &EnvProgressLogger, _: ()| -> Result<(), Box<EngineError>> {
+        let pre_run = |_: &EnvProgressLogger| -> Result<Vec<RouteProbeOutcome>, Box<EngineError>> { Ok(vec![]) };
+        let run = |logger: &EnvProgressLogger,
+                   _: Vec<RouteProbeOutcome>|
+         -> Result<Vec<RouteProbeOutcome>, Box<EngineError>> {
+            // Let's Encrypt must be allowed to issue the certificates before cert-manager requests them
+            let cluster_domain = target.dns_provider.domain().to_string();
+            let domains_with_certificate: Vec<&CustomDomain> = self
+                .custom_domains
+                .iter()
+                .filter(|custom_domain| requires_certificate(custom_domain, &cluster_domain))
+                .collect();
+            ensure_certificate_dns_records(&domains_with_certificate, logger, &event_details, target)?;
+            for warning in custom_certificate_warnings(&self.custom_domains, Utc::now()) {
+                logger.warning(format!("🔒 {warning}"));
+            }
+
             let chart = ChartInfo {
                 name: self.helm_release_name(),
                 path: self.workspace_directory().to_string(),
@@ -40,6 +61,7 @@ where
             );
 
             helm.on_create(target)?;
+            apply_custom_metadata(target, &self.kube_label_selector(), &self.custom_metadata, &event_details);
 
             // check non custom domains
             let custom_domains_to_check: Vec<CustomDomain> = if self.advanced_settings.custom_domain_check_enabled {
@@ -48,24 +70,73 @@ where
                 vec![]
             };
 
+            let event_details = event_details.clone();
             let domain_checker = CheckDnsForDomains {
                 resolve_to_ip: vec![self.default_domain.clone()],
-                resolve_to_cname: custom_domains_to_check,
-                log: Box::new(move |msg| logger.info(msg)),
+                resolve_to_cname: custom_domains_to_check.clone(),
+                dns_check_config: DnsCheckConfig::new(DnsResolver::defaults_for(target.dns_provider)),
+                log: Box::new(move |msg| logger.log(EngineEvent::Info(event_details.clone(), msg))),
             };
             let _ = domain_checker.on_create(target);
 
-            Ok(())
+            let waf_mode = effective_waf_mode(
+                self.advanced_settings.waf.as_ref().map(|waf| waf.mode),
+                target.kubernetes.advanced_settings().nginx_waf_mode,
+            );
+            if waf_mode != WafMode::Disabled && !target.is_dry_run_deploy {
+                let hosts: Vec<String> = std::iter::once(self.default_domain.clone())
+                    .chain(
+                        self.custom_domains
+                            .iter()
+                            .map(|custom_domain| custom_domain.domain.clone()),
+                    )
+                    .collect();
+                match blocked_requests(target, &hosts) {
+                    Ok(blocked) => logger.info(format!(
+                        "🛡️ Web application firewall blocked {blocked} request(s) to the router in the last {}h",
+                        WAF_REPORT_PERIOD_SECONDS / 3600
+                    )),
+                    Err(err) => logger.warning(format!(
+                        "Cannot count the requests blocked by the web application firewall: {}",
+                        err.message_safe()
+                    )),
+                }
+            }
+
+            // Pods being ready does not mean the routes are reachable through the load balancer
+            if !self.advanced_settings.probe_enabled || target.is_dry_run_deploy {
+                return Ok(vec![]);
+            }
+            let probes = route_probes(
+                &self.default_domain,
+                &custom_domains_to_check,
+                &self.routes,
+                &self.advanced_settings.probe_expected_status_codes,
+            );
+
+            Ok(run_route_probes(&probes, logger))
         };
 
-        let empty_post_run = |_logger: &EnvSuccessLogger, _: ()| {};
+        let post_run = |logger: &EnvSuccessLogger, probe_outcomes: Vec<RouteProbeOutcome>| {
+            let reachable_routes: Vec<String> = probe_outcomes
+                .iter()
+                .filter(|outcome| outcome.failure.is_none())
+                .map(|outcome| format!("- {outcome}"))
+                .collect();
+            if !reachable_routes.is_empty() {
+                logger.send_success(format!(
+                    "🌐 Router is reachable from the internet:\n{}",
+                    reachable_routes.join("\n")
+                ));
+            }
+        };
 
         execute_long_deployment(
             RouterDeploymentReporter::new(self, target, Action::Create),
             DeploymentTaskImpl {
                 pre_run: &pre_run,
                 run: &run,
-                post_run_success: &empty_post_run,
+                post_run_success: &post_run,
             },
         )
     }
@@ -3,8 +3,10 @@ use crate::cloud_provider::{CloudProvider, DeploymentTarget};
 use crate::cmd;
 use crate::cmd::kubectl::kubectl_exec_delete_secret;
 use crate::deployment_action::DeploymentAction;
+use crate::deployment_hook::DeploymentHookStage;
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EnvironmentStep, EventDetails, Stage};
+use crate::heartbeat::{Heartbeat, StepDurationHistory};
 use crate::template::generate_and_copy_all_files_into_dir;
 use serde_json::Value;
 use std::path::PathBuf;
@@ -72,6 +74,15 @@ impl TerraformDeployment {
         Ok(())
     }
 
+    fn start_heartbeat(&self, step: &str, target: &DeploymentTarget) -> Heartbeat {
+        Heartbeat::start(
+            step,
+            self.event_details.clone(),
+            target.kubernetes.logger().clone_dyn(),
+            StepDurationHistory::new(target.kubernetes.context().workspace_root_dir()),
+        )
+    }
+
     pub fn delete_tfstate_secret(
         kubernetes: &dyn Kubernetes,
         cloud_provider: &dyn CloudProvider,
@@ -93,17 +104,24 @@ impl TerraformDeployment {
 impl DeploymentAction for TerraformDeployment {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
         self.prepare_terraform_files()?;
-        let ret = cmd::terraform::terraform_init_validate_plan_apply(
-            &self.destination_folder.to_string_lossy(),
-            self.is_dry_run,
-            target.cloud_provider.credentials_environment_variables().as_slice(),
-        );
+        target
+            .deployment_hooks
+            .run_stage(DeploymentHookStage::Terraform, &self.event_details, || {
+                let heartbeat = self.start_heartbeat("Terraform apply", target);
+                let ret = cmd::terraform::terraform_init_validate_plan_apply(
+                    &self.destination_folder.to_string_lossy(),
+                    self.is_dry_run,
+                    target.cloud_provider.credentials_environment_variables().as_slice(),
+                );
+                // a dry run stops at the plan, it would lower the usual duration of an apply
+                heartbeat.stop(ret.is_ok() && !self.is_dry_run);
 
-        if let Err(err) = ret {
-            Err(Box::new(EngineError::new_terraform_error(self.event_details.clone(), err)))
-        } else {
-            Ok(())
-        }
+                if let Err(err) = ret {
+                    Err(Box::new(EngineError::new_terraform_error(self.event_details.clone(), err)))
+                } else {
+                    Ok(())
+                }
+            })
     }
 
     fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
@@ -112,24 +130,30 @@ impl DeploymentAction for TerraformDeployment {
 
     fn on_delete(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
         self.prepare_terraform_files()?;
-        match cmd::terraform::terraform_init_validate_destroy(
-            &self.destination_folder.to_string_lossy(),
-            false,
-            target.cloud_provider.credentials_environment_variables().as_slice(),
+        target
+            .deployment_hooks
+            .run_stage(DeploymentHookStage::Terraform, &self.event_details, || {
+                let heartbeat = self.start_heartbeat("Terraform destroy", target);
+                let ret = cmd::terraform::terraform_init_validate_destroy(
+                    &self.destination_folder.to_string_lossy(),
+                    false,
+                    target.cloud_provider.credentials_environment_variables().as_slice(),
+                );
+                heartbeat.stop(ret.is_ok());
+
+                ret.map(|_| ())
+                    .map_err(|e| Box::new(EngineError::new_terraform_error(self.event_details.clone(), e)))
+            })?;
+
+        if let Err(err) = TerraformDeployment::delete_tfstate_secret(
+            target.kubernetes,
+            target.cloud_provider,
+            target.environment.namespace(),
+            self.tera_context.get("tfstate_name").and_then(Value::as_str).unwrap(),
         ) {
-            Ok(_) => {
-                if let Err(err) = TerraformDeployment::delete_tfstate_secret(
-                    target.kubernetes,
-                    target.cloud_provider,
-                    target.environment.namespace(),
-                    self.tera_context.get("tfstate_name").and_then(Value::as_str).unwrap(),
-                ) {
-                    warn!("Cannot delete tfstate {} for {:?}", err, self.tera_context);
-                }
-                Ok(())
-            }
-            Err(e) => Err(Box::new(EngineError::new_terraform_error(self.event_details.clone(), e))),
+            warn!("Cannot delete tfstate {} for {:?}", err, self.tera_context);
         }
+        Ok(())
     }
 
     fn on_restart(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
@@ -0,0 +1,428 @@
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::terraform::{terraform_init_validate_destroy, terraform_init_validate_plan_apply, terraform_output};
+use crate::deployment_action::deploy_helm_chart::git_credentials_callback;
+use crate::deployment_action::deploy_job::{serialize_job_output, JobOutputVariable};
+use crate::deployment_action::deploy_terraform::TerraformDeployment;
+use crate::deployment_action::DeploymentAction;
+use crate::deployment_hook::DeploymentHookStage;
+use crate::deployment_report::execute_long_deployment;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::deployment_report::terraform_service::reporter::TerraformServiceDeploymentReporter;
+use crate::encryption::{read_encrypted_file, write_encrypted_file};
+use crate::errors::EngineError;
+use crate::events::{EnvironmentStep, EventDetails, Stage};
+use crate::git;
+use crate::kubers_utils::{
+    kube_apply_resource, kube_create_service_account_token, kube_delete_all_from_selector, KubeDeleteMode,
+};
+use crate::models::terraform_service::TerraformService;
+use crate::naming;
+use crate::runtime::block_on;
+use crate::utilities::service_account_kubeconfig;
+use k8s_openapi::api::core::v1::ServiceAccount;
+use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::config::Kubeconfig;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+
+const BACKEND_OVERRIDE_FILE_NAME: &str = "qovery_backend_override.tf";
+const BACKEND_KUBECONFIG_FILE_NAME: &str = "backend_kubeconfig.yaml";
+// The token is not renewed while terraform runs, it must outlive the longest apply of a module
+const BACKEND_TOKEN_EXPIRATION_IN_SECONDS: i64 = 6 * 60 * 60;
+// Not loaded by terraform on its own, variables are decrypted in memory and given as TF_VAR_ environment variables
+const VARIABLES_FILE_NAME: &str = "qovery.tfvars.json.enc";
+
+impl DeploymentAction for TerraformService {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+
+        let task = |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            prepare_module_directory(self, target, &event_details, logger)?;
+            let module_directory = self.module_directory().to_string_lossy().to_string();
+            let variables_envs = module_variables_envs(self, &event_details)?;
+            let envs = module_envs(self, &variables_envs);
+
+            logger.info("🏗️ Applying the terraform module".to_string());
+            target
+                .deployment_hooks
+                .run_stage(DeploymentHookStage::Terraform, &event_details, || {
+                    terraform_init_validate_plan_apply(&module_directory, target.is_dry_run_deploy, envs.as_slice())
+                        .map_err(|err| Box::new(EngineError::new_terraform_error(event_details.clone(), err)))
+                })?;
+
+            if self.exposed_outputs().is_empty() || target.is_dry_run_deploy {
+                return Ok(());
+            }
+
+            let outputs = terraform_output(&module_directory, envs.as_slice())
+                .map_err(|err| Box::new(EngineError::new_terraform_error(event_details.clone(), err)))?;
+            let outputs = serialize_job_output(&outputs).map_err(|err| {
+                to_error(self, &event_details, format!("Cannot read the outputs of the module: {err}"))
+            })?;
+            let variables = exposed_output_variables(outputs, self.exposed_outputs())
+                .map_err(|err| to_error(self, &event_details, err))?;
+            let json = serde_json::to_string(&variables).map_err(|err| {
+                to_error(
+                    self,
+                    &event_details,
+                    format!("Cannot serialize the outputs of the module: {err}"),
+                )
+            })?;
+            logger.core_configuration_for_terraform(
+                "Terraform outputs succeeded. Environment variables will be synchronized.".to_string(),
+                json,
+            );
+            Ok(())
+        };
+
+        execute_long_deployment(TerraformServiceDeploymentReporter::new(self, target, Action::Create), task)
+    }
+
+    fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        // Resources of the module are not running in the cluster, there is nothing to pause
+        Ok(())
+    }
+
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Delete));
+
+        let task = |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            prepare_module_directory(self, target, &event_details, logger)?;
+            let module_directory = self.module_directory().to_string_lossy().to_string();
+            let variables_envs = module_variables_envs(self, &event_details)?;
+            let envs = module_envs(self, &variables_envs);
+
+            logger.info("💣 Destroying the resources of the terraform module".to_string());
+            target
+                .deployment_hooks
+                .run_stage(DeploymentHookStage::Terraform, &event_details, || {
+                    terraform_init_validate_destroy(&module_directory, false, envs.as_slice())
+                        .map_err(|err| Box::new(EngineError::new_terraform_error(event_details.clone(), err)))
+                })?;
+
+            TerraformDeployment::delete_tfstate_secret(
+                target.kubernetes,
+                target.cloud_provider,
+                target.environment.namespace(),
+                &self.tfstate_name(),
+            )?;
+            delete_backend_service_account(self, target, &event_details)
+        };
+
+        execute_long_deployment(TerraformServiceDeploymentReporter::new(self, target, Action::Delete), task)
+    }
+
+    fn on_restart(&self, _target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        // Resources of the module are not running in the cluster, there is nothing to restart
+        Ok(())
+    }
+}
+
+fn to_error(this: &TerraformService, event_details: &EventDetails, message: String) -> Box<EngineError> {
+    Box::new(EngineError::new_terraform_service_deployment_failed(
+        event_details.clone(),
+        this.name(),
+        message,
+    ))
+}
+
+// Goal is to get the module ready to be run by terraform
+// 1. Clone the repository at the commit
+// 2. Override the backend of the module, to keep its state in a secret of the environment namespace
+// 3. Write the input variables of the module
+fn prepare_module_directory(
+    this: &TerraformService,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+    logger: &EnvProgressLogger,
+) -> Result<(), Box<EngineError>> {
+    let source = this.source();
+    logger.info(format!(
+        "📥 Cloning terraform module from git repository {} at commit {}",
+        source.git_url, source.commit_id
+    ));
+
+    // the module is cloned again when deleting after a deployment of the same execution
+    let _ = fs::remove_dir_all(this.repository_directory());
+    let git_creds = (source.get_credentials)()
+        .map_err(|e| to_error(this, event_details, format!("Cannot get git credentials due to {e}")))?;
+    git::clone_at_commit(
+        &source.git_url,
+        &source.commit_id,
+        this.repository_directory(),
+        &git_credentials_callback(&git_creds, &source.ssh_keys),
+    )
+    .map_err(|e| to_error(this, event_details, format!("Cannot clone git repository due to {e}")))?;
+
+    let module_directory = this.module_directory();
+    if !module_directory.is_dir() {
+        return Err(to_error(
+            this,
+            event_details,
+            format!("Module directory {:?} does not exist in the repository", source.root_path),
+        ));
+    }
+
+    let kubeconfig_path = write_backend_kubeconfig(this, target, event_details)?;
+    let backend = this.backend_override(&kubeconfig_path, target.environment.namespace());
+    fs::write(module_directory.join(BACKEND_OVERRIDE_FILE_NAME), backend)
+        .map_err(|e| to_error(this, event_details, format!("Cannot write terraform backend due to {e}")))?;
+
+    let variables = serde_json::to_string(this.variables())
+        .map_err(|e| to_error(this, event_details, format!("Cannot serialize terraform variables due to {e}")))?;
+    write_encrypted_file(&module_directory.join(VARIABLES_FILE_NAME), variables.as_bytes())
+        .map_err(|e| to_error(this, event_details, format!("Cannot write terraform variables due to {e}")))?;
+
+    Ok(())
+}
+
+fn backend_service_account_name(this: &TerraformService) -> String {
+    naming::kube_name(&format!("{}-tfstate", this.kube_name()))
+}
+
+fn backend_metadata(this: &TerraformService, name: &str) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(name.to_string()),
+        labels: Some(BTreeMap::from([(
+            "qovery.com/service-id".to_string(),
+            this.long_id().to_string(),
+        )])),
+        ..Default::default()
+    }
+}
+
+// Only the state of the module and its lock, kubernetes cannot restrict the creation or the listing of resources by
+// name, and terraform lists the state secrets of the namespace to find its workspaces
+fn backend_role(this: &TerraformService, name: &str) -> Role {
+    let rule = |api_group: &str, resource: &str, resource_name: Option<String>, verbs: &[&str]| PolicyRule {
+        api_groups: Some(vec![api_group.to_string()]),
+        resources: Some(vec![resource.to_string()]),
+        resource_names: resource_name.map(|resource_name| vec![resource_name]),
+        verbs: verbs.iter().map(|verb| verb.to_string()).collect(),
+        ..Default::default()
+    };
+
+    Role {
+        metadata: backend_metadata(this, name),
+        rules: Some(vec![
+            rule("", "secrets", None, &["create", "list"]),
+            rule("", "secrets", Some(this.tfstate_name()), &["get", "update", "patch", "delete"]),
+            rule("coordination.k8s.io", "leases", None, &["create"]),
+            rule(
+                "coordination.k8s.io",
+                "leases",
+                Some(this.tfstate_lock_name()),
+                &["get", "update", "delete"],
+            ),
+        ]),
+    }
+}
+
+fn backend_role_binding(this: &TerraformService, name: &str, namespace: &str) -> RoleBinding {
+    RoleBinding {
+        metadata: backend_metadata(this, name),
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "Role".to_string(),
+            name: name.to_string(),
+        },
+        subjects: Some(vec![Subject {
+            kind: "ServiceAccount".to_string(),
+            name: name.to_string(),
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        }]),
+    }
+}
+
+/// Kubeconfig the backend stores the state with, the one of a service account of the environment namespace,
+/// so the module never gets the admin access to the cluster
+fn write_backend_kubeconfig(
+    this: &TerraformService,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<PathBuf, Box<EngineError>> {
+    let namespace = target.environment.namespace();
+    let name = backend_service_account_name(this);
+    let service_account = ServiceAccount {
+        metadata: backend_metadata(this, &name),
+        automount_service_account_token: Some(false),
+        ..Default::default()
+    };
+    let token = block_on(async {
+        kube_apply_resource(&target.kube, namespace, &service_account).await?;
+        kube_apply_resource(&target.kube, namespace, &backend_role(this, &name)).await?;
+        kube_apply_resource(&target.kube, namespace, &backend_role_binding(this, &name, namespace)).await?;
+        kube_create_service_account_token(&target.kube, namespace, &name, BACKEND_TOKEN_EXPIRATION_IN_SECONDS).await
+    })
+    .map_err(|e| {
+        to_error(
+            this,
+            event_details,
+            format!("Cannot create the service account of the backend due to {e}"),
+        )
+    })?;
+
+    let admin_kubeconfig = Kubeconfig::read_from(target.kubernetes.kubeconfig_local_file_path()).map_err(|e| {
+        to_error(
+            this,
+            event_details,
+            format!("Cannot read the kubeconfig of the cluster due to {e}"),
+        )
+    })?;
+    let kubeconfig = service_account_kubeconfig(&admin_kubeconfig, namespace, &token)
+        .map_err(|e| to_error(this, event_details, format!("Cannot create the kubeconfig of the backend: {e}")))?;
+
+    // kept out of the repository directory, and only readable by the engine as it holds the token
+    let kubeconfig_path = this.workspace_directory().join(BACKEND_KUBECONFIG_FILE_NAME);
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&kubeconfig_path)
+        .and_then(|mut file| file.write_all(kubeconfig.as_bytes()))
+        .map_err(|e| {
+            to_error(
+                this,
+                event_details,
+                format!("Cannot write the kubeconfig of the backend due to {e}"),
+            )
+        })?;
+
+    Ok(kubeconfig_path)
+}
+
+fn delete_backend_service_account(
+    this: &TerraformService,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>> {
+    let namespace = target.environment.namespace();
+    let selector = this.kube_label_selector();
+    block_on(async {
+        kube_delete_all_from_selector::<RoleBinding>(&target.kube, &selector, namespace, KubeDeleteMode::Normal)
+            .await?;
+        kube_delete_all_from_selector::<Role>(&target.kube, &selector, namespace, KubeDeleteMode::Normal).await?;
+        kube_delete_all_from_selector::<ServiceAccount>(&target.kube, &selector, namespace, KubeDeleteMode::Normal)
+            .await
+    })
+    .map_err(|e| {
+        to_error(
+            this,
+            event_details,
+            format!("Cannot delete the service account of the backend due to {e}"),
+        )
+    })
+}
+
+/// Credentials of the module and its input variables, the credentials of the cluster are never given to terraform
+fn module_envs<'a>(this: &'a TerraformService, variables_envs: &'a [(String, String)]) -> Vec<(&'a str, &'a str)> {
+    this.provider_credentials()
+        .iter()
+        .chain(variables_envs.iter().map(|(k, v)| (k, v)))
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+/// Input variables of the module, decrypted in memory only
+fn module_variables_envs(
+    this: &TerraformService,
+    event_details: &EventDetails,
+) -> Result<Vec<(String, String)>, Box<EngineError>> {
+    let variables = read_encrypted_file(&this.module_directory().join(VARIABLES_FILE_NAME))
+        .map_err(|e| to_error(this, event_details, format!("Cannot read terraform variables due to {e}")))?;
+    let variables: BTreeMap<String, String> = serde_json::from_slice(&variables)
+        .map_err(|e| to_error(this, event_details, format!("Cannot read terraform variables due to {e}")))?;
+
+    Ok(variables_to_envs(variables))
+}
+
+fn variables_to_envs(variables: BTreeMap<String, String>) -> Vec<(String, String)> {
+    variables
+        .into_iter()
+        .map(|(name, value)| (format!("TF_VAR_{name}"), value))
+        .collect()
+}
+
+/// Outputs exposed to the other services, named after their environment variable
+fn exposed_output_variables(
+    mut outputs: HashMap<String, JobOutputVariable>,
+    exposed_outputs: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, JobOutputVariable>, String> {
+    exposed_outputs
+        .iter()
+        .map(|(output_name, env_var_name)| {
+            outputs
+                .remove(output_name)
+                .map(|output| (env_var_name.to_uppercase(), output))
+                .ok_or_else(|| format!("Output `{output_name}` is not defined by the module"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variables_to_envs() {
+        let variables = BTreeMap::from([
+            ("bucket_name".to_string(), "my-bucket".to_string()),
+            ("tags".to_string(), "{\"env\" = \"prod\"}".to_string()),
+        ]);
+
+        assert_eq!(
+            variables_to_envs(variables),
+            vec![
+                ("TF_VAR_bucket_name".to_string(), "my-bucket".to_string()),
+                ("TF_VAR_tags".to_string(), "{\"env\" = \"prod\"}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exposed_output_variables() {
+        let outputs = serialize_job_output(
+            r#"{
+  "bucket_name": {"sensitive": false, "type": "string", "value": "my-bucket"},
+  "bucket_key": {"sensitive": true, "type": "string", "value": "secret"},
+  "bucket_region": {"sensitive": false, "type": "string", "value": "eu-west-3"}
+}"#,
+        )
+        .unwrap();
+
+        let exposed_outputs = BTreeMap::from([
+            ("bucket_name".to_string(), "S3_BUCKET".to_string()),
+            ("bucket_key".to_string(), "s3_key".to_string()),
+        ]);
+        let variables = exposed_output_variables(outputs.clone(), &exposed_outputs).unwrap();
+        assert_eq!(
+            variables,
+            BTreeMap::from([
+                (
+                    "S3_BUCKET".to_string(),
+                    JobOutputVariable {
+                        value: "my-bucket".to_string(),
+                        sensitive: false,
+                    }
+                ),
+                (
+                    "S3_KEY".to_string(),
+                    JobOutputVariable {
+                        value: "secret".to_string(),
+                        sensitive: true,
+                    }
+                ),
+            ])
+        );
+
+        let missing_output = BTreeMap::from([("bucket_arn".to_string(), "S3_ARN".to_string())]);
+        assert!(exposed_output_variables(outputs, &missing_output).is_err());
+    }
+}
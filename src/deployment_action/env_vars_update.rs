@@ -0,0 +1,313 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::restart_service::RestartServiceAction;
+use crate::deployment_action::DeploymentAction;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::kubers_utils::{kube_get_resources_by_selector, kube_patch_secret_data};
+use crate::models::utils::{spec_checksum, SPEC_CHECKSUM_ANNOTATION, SPEC_CHECKSUM_CONTEXT_KEY};
+use crate::runtime::block_on;
+use base64::engine::general_purpose;
+use base64::Engine;
+use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::ByteString;
+use kube::Api;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tera::Context as TeraContext;
+
+// Subset of the tera context rendered into the secrets of the q-container chart
+#[derive(Deserialize)]
+struct SecretsTeraContext {
+    service: SecretsServiceTeraContext,
+    environment_variables: Vec<SecretsEnvironmentVariable>,
+    registry: Option<SecretsRegistryTeraContext>,
+}
+
+#[derive(Deserialize)]
+struct SecretsServiceTeraContext {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct SecretsEnvironmentVariable {
+    key: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct SecretsRegistryTeraContext {
+    secret_name: String,
+    docker_json_config: Option<String>,
+}
+
+impl SecretsTeraContext {
+    fn secrets_data(self) -> Vec<(String, BTreeMap<String, String>)> {
+        let environment_variables = self
+            .environment_variables
+            .into_iter()
+            .map(|environment_variable| (environment_variable.key, environment_variable.value))
+            .collect();
+        let mut secrets = vec![(self.service.name, environment_variables)];
+
+        if let Some(SecretsRegistryTeraContext {
+            secret_name,
+            docker_json_config: Some(docker_json_config),
+        }) = self.registry
+        {
+            secrets.push((
+                secret_name,
+                BTreeMap::from([(".dockerconfigjson".to_string(), docker_json_config)]),
+            ));
+        }
+
+        secrets
+    }
+}
+
+/// Exposes the spec checksum to the chart, so the deployed workload remembers what it has been rendered from
+pub(super) fn insert_spec_checksum(tera_context: &mut TeraContext) {
+    let spec_checksum = spec_checksum(tera_context);
+    tera_context.insert(SPEC_CHECKSUM_CONTEXT_KEY, &spec_checksum);
+}
+
+// Spec checksum of the running workload, none when it has no ready pod as it must be redeployed for real
+async fn get_running_spec_checksum(
+    kube: &kube::Client,
+    namespace: &str,
+    selector: &str,
+    is_stateful: bool,
+) -> Option<String> {
+    let (annotations, ready_replicas) = if is_stateful {
+        let statefulset = kube_get_resources_by_selector::<StatefulSet>(kube, namespace, selector)
+            .await
+            .ok()?
+            .items
+            .into_iter()
+            .next()?;
+        (
+            statefulset.metadata.annotations,
+            statefulset.status.and_then(|status| status.ready_replicas),
+        )
+    } else {
+        let deployment = kube_get_resources_by_selector::<Deployment>(kube, namespace, selector)
+            .await
+            .ok()?
+            .items
+            .into_iter()
+            .next()?;
+        (
+            deployment.metadata.annotations,
+            deployment.status.and_then(|status| status.ready_replicas),
+        )
+    };
+
+    match ready_replicas {
+        Some(ready_replicas) if ready_replicas > 0 => annotations?.remove(SPEC_CHECKSUM_ANNOTATION),
+        _ => None,
+    }
+}
+
+// Patch restoring the data of a secret as it was before `updated_data` was merged into it
+fn restore_secret_data(
+    previous_data: Option<BTreeMap<String, ByteString>>,
+    updated_data: &BTreeMap<String, String>,
+) -> BTreeMap<String, Option<String>> {
+    let mut data: BTreeMap<String, Option<String>> = updated_data.keys().map(|key| (key.clone(), None)).collect();
+    data.extend(
+        previous_data
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| (key, Some(general_purpose::STANDARD.encode(value.0)))),
+    );
+    data
+}
+
+/// Environment variables updated without redeploying the service, with the previous data of its secrets
+pub(super) struct EnvVarsUpdate {
+    selector: String,
+    is_stateful: bool,
+    restore_secrets: Vec<(String, BTreeMap<String, Option<String>>)>,
+}
+
+impl EnvVarsUpdate {
+    /// Restores the previous environment variables of the service and restarts it again to apply them
+    pub(super) fn rollback(
+        &self,
+        event_details: &EventDetails,
+        target: &DeploymentTarget,
+    ) -> Result<(), Box<EngineError>> {
+        let namespace = target.environment.namespace();
+        for (secret_name, data) in &self.restore_secrets {
+            block_on(kube_patch_secret_data(&target.kube, namespace, secret_name, data))
+                .map_err(|err| Box::new(EngineError::new_k8s_patch_secret_error(event_details.clone(), err)))?;
+        }
+
+        RestartServiceAction::new(self.selector.clone(), self.is_stateful, event_details.clone()).on_restart(target)
+    }
+}
+
+/// When only the values of the environment variables of a running service changed since its last deployment,
+/// patch its secrets and restart its pods instead of going through a whole helm upgrade.
+/// Returns none when the service has to be deployed the regular way.
+pub(super) fn update_env_vars_only_if_possible(
+    selector: &str,
+    is_stateful: bool,
+    tera_context: &TeraContext,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<Option<EnvVarsUpdate>, Box<EngineError>> {
+    if target.is_dry_run_deploy {
+        return Ok(None);
+    }
+
+    let Some(spec_checksum) = tera_context
+        .get(SPEC_CHECKSUM_CONTEXT_KEY)
+        .and_then(|value| value.as_str())
+    else {
+        return Ok(None);
+    };
+    let namespace = target.environment.namespace();
+    let running_spec_checksum = block_on(get_running_spec_checksum(&target.kube, namespace, selector, is_stateful));
+    if running_spec_checksum.as_deref() != Some(spec_checksum) {
+        return Ok(None);
+    }
+
+    let secrets = match serde_json::from_value::<SecretsTeraContext>(tera_context.clone().into_json()) {
+        Ok(secrets_tera_context) => secrets_tera_context.secrets_data(),
+        Err(err) => {
+            warn!(
+                "Cannot read secrets from the tera context, falling back to a full deployment: {}",
+                err
+            );
+            return Ok(None);
+        }
+    };
+
+    logger.info("⚡ Only environment variables changed, updating them without redeploying the service".to_string());
+    let secret_api: Api<Secret> = Api::namespaced(target.kube.clone(), namespace);
+    let mut update = EnvVarsUpdate {
+        selector: selector.to_string(),
+        is_stateful,
+        restore_secrets: Vec::with_capacity(secrets.len()),
+    };
+    for (secret_name, data) in secrets {
+        // Nothing has been restarted yet, a full deployment can still take over
+        let previous_data = match block_on(secret_api.get(&secret_name)) {
+            Ok(secret) => secret.data,
+            Err(err) => {
+                target.kubernetes.logger().log(EngineEvent::Warning(
+                    event_details.clone(),
+                    EventMessage::new(
+                        format!("Cannot read secret {secret_name}, falling back to a full deployment"),
+                        Some(err.to_string()),
+                    ),
+                ));
+                return Ok(None);
+            }
+        };
+        if let Err(err) = block_on(kube_patch_secret_data(&target.kube, namespace, &secret_name, &data)) {
+            target.kubernetes.logger().log(EngineEvent::Warning(
+                event_details.clone(),
+                EventMessage::new(
+                    format!("Cannot update secret {secret_name}, falling back to a full deployment"),
+                    Some(err.to_string()),
+                ),
+            ));
+            return Ok(None);
+        }
+        update
+            .restore_secrets
+            .push((secret_name, restore_secret_data(previous_data, &data)));
+    }
+
+    logger.info("🔄 Restarting the service to apply its new environment variables".to_string());
+    if let Err(err) =
+        RestartServiceAction::new(selector.to_string(), is_stateful, event_details.clone()).on_restart(target)
+    {
+        logger.warning("⏪ Restart failed, restoring the previous environment variables".to_string());
+        if let Err(rollback_err) = update.rollback(event_details, target) {
+            logger.warning(format!(
+                "Cannot restore the previous environment variables: {}",
+                rollback_err.user_log_message()
+            ));
+        }
+        return Err(err);
+    }
+
+    Ok(Some(update))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_data() {
+        let secrets_tera_context: SecretsTeraContext = serde_json::from_value(serde_json::json!({
+            "service": { "name": "app-z1234", "long_id": "00000000-0000-0000-0000-000000000000" },
+            "environment_variables": [
+                { "key": "PORT", "value": "ODA4MA==", "is_secret": false },
+                { "key": "API_KEY", "value": "c2VjcmV0", "is_secret": true },
+            ],
+            "registry": { "secret_name": "app-z1234-registry", "docker_json_config": "e30=" },
+            "namespace": "z5678",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            secrets_tera_context.secrets_data(),
+            vec![
+                (
+                    "app-z1234".to_string(),
+                    BTreeMap::from([
+                        ("API_KEY".to_string(), "c2VjcmV0".to_string()),
+                        ("PORT".to_string(), "ODA4MA==".to_string()),
+                    ])
+                ),
+                (
+                    "app-z1234-registry".to_string(),
+                    BTreeMap::from([(".dockerconfigjson".to_string(), "e30=".to_string())])
+                ),
+            ]
+        );
+
+        let secrets_tera_context: SecretsTeraContext = serde_json::from_value(serde_json::json!({
+            "service": { "name": "app-z1234" },
+            "environment_variables": [],
+            "registry": null,
+        }))
+        .unwrap();
+        assert_eq!(
+            secrets_tera_context.secrets_data(),
+            vec![("app-z1234".to_string(), BTreeMap::new())]
+        );
+    }
+
+    #[test]
+    fn test_restore_secret_data() {
+        let previous_data = BTreeMap::from([
+            ("PORT".to_string(), ByteString(b"8080".to_vec())),
+            ("REMOVED".to_string(), ByteString(b"old".to_vec())),
+        ]);
+        let updated_data = BTreeMap::from([
+            ("PORT".to_string(), "ODA4MQ==".to_string()),
+            ("ADDED".to_string(), "bmV3".to_string()),
+        ]);
+
+        assert_eq!(
+            restore_secret_data(Some(previous_data), &updated_data),
+            BTreeMap::from([
+                ("ADDED".to_string(), None),
+                ("PORT".to_string(), Some("ODA4MA==".to_string())),
+                ("REMOVED".to_string(), Some("b2xk".to_string())),
+            ])
+        );
+        assert_eq!(
+            restore_secret_data(None, &updated_data),
+            BTreeMap::from([("ADDED".to_string(), None), ("PORT".to_string(), None)])
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use crate::cloud_provider::service::Service;
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::deploy_job::{await_job_termination, JobStatus};
+use crate::deployment_action::service_secrets::{docker_registry_secret, env_vars_secret};
+use crate::deployment_action::utils::forward_job_logs;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::io_models::{LifecycleHook, LifecycleHookFailurePolicy};
+use crate::kubers_utils::kube_create_from_resource;
+use crate::models::utils::resolve_service_account_name;
+use crate::naming;
+use crate::runtime::block_on;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::batch::v1::{Job as K8sJob, JobSpec};
+use k8s_openapi::api::core::v1::{
+    Container, EnvFromSource, EnvVar, LocalObjectReference, PodSpec, PodTemplateSpec, Secret, SecretEnvSource,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::DeleteParams;
+use kube::Api;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+const LIFECYCLE_HOOK_LABEL: &str = "qovery.com/lifecycle-hook";
+const LIFECYCLE_HOOK_CONTAINER_NAME: &str = "lifecycle-hook";
+// Jobs are deleted once their logs are forwarded, this is only for runs interrupted in the middle
+const LIFECYCLE_HOOK_JOB_TTL_IN_SECONDS: i32 = 24 * 60 * 60;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum LifecycleHookKind {
+    PostCreate,
+    PreDelete,
+    SmokeTest,
+}
+
+impl Display for LifecycleHookKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LifecycleHookKind::PostCreate => "post-create",
+            LifecycleHookKind::PreDelete => "pre-delete",
+            LifecycleHookKind::SmokeTest => "smoke-test",
+        })
+    }
+}
+
+// Job name is reused as a label value by kubernetes, so it must fit in 63 characters
+fn lifecycle_hook_job_name(kube_name: &str, kind: LifecycleHookKind, now: DateTime<Utc>) -> String {
+    let prefix: String = kube_name.chars().take(36).collect();
+    format!("{}-{}-{}", prefix.trim_end_matches('-'), kind, now.timestamp())
+}
+
+fn lifecycle_hook_labels(service_long_id: &str, kind: LifecycleHookKind) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("qovery.com/service-id".to_string(), service_long_id.to_string()),
+        (LIFECYCLE_HOOK_LABEL.to_string(), kind.to_string()),
+    ])
+}
+
+fn lifecycle_hook_job(
+    name: &str,
+    labels: &BTreeMap<String, String>,
+    image: &str,
+    hook: &LifecycleHook,
+    hook_environment_variables: &[(&str, &str)],
+    registry_secret_name: Option<&str>,
+    service_account_name: Option<&str>,
+) -> K8sJob {
+    K8sJob {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            labels: Some(labels.clone()),
+            ..Default::default()
+        },
+        spec: Some(JobSpec {
+            backoff_limit: Some(0),
+            active_deadline_seconds: Some(i64::from(hook.timeout_in_seconds)),
+            ttl_seconds_after_finished: Some(LIFECYCLE_HOOK_JOB_TTL_IN_SECONDS),
+            template: PodTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(labels.clone()),
+                    ..Default::default()
+                }),
+                spec: Some(PodSpec {
+                    restart_policy: Some("Never".to_string()),
+                    service_account_name: service_account_name.map(str::to_string),
+                    image_pull_secrets: registry_secret_name.map(|secret_name| {
+                        vec![LocalObjectReference {
+                            name: Some(secret_name.to_string()),
+                        }]
+                    }),
+                    containers: vec![Container {
+                        name: LIFECYCLE_HOOK_CONTAINER_NAME.to_string(),
+                        image: Some(image.to_string()),
+                        command: Some(hook.command.clone()),
+                        env: (!hook_environment_variables.is_empty()).then(|| {
+                            hook_environment_variables
+                                .iter()
+                                .map(|(key, value)| EnvVar {
+                                    name: key.to_string(),
+                                    value: Some(value.to_string()),
+                                    value_from: None,
+                                })
+                                .collect()
+                        }),
+                        env_from: Some(vec![EnvFromSource {
+                            secret_ref: Some(SecretEnvSource {
+                                name: Some(name.to_string()),
+                                optional: Some(false),
+                            }),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        status: None,
+    }
+}
+
+/// Run a lifecycle hook of the service with a kubernetes job, its output is forwarded to the deployment logs.
+/// A failure of the hook fails the deployment, unless its failure policy only asks for a warning.
+/// `hook_environment_variables` are given to the hook on top of the environment variables of the service.
+pub(super) fn run_lifecycle_hook(
+    service: &dyn Service,
+    kind: LifecycleHookKind,
+    hook: Option<&LifecycleHook>,
+    hook_environment_variables: &[(&str, &str)],
+    service_image: &str,
+    service_account_name: &str,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let Some(hook) = hook else {
+        return Ok(());
+    };
+    if target.is_dry_run_deploy {
+        return Ok(());
+    }
+
+    match execute_lifecycle_hook(
+        service,
+        kind,
+        hook,
+        hook_environment_variables,
+        service_image,
+        service_account_name,
+        logger,
+        event_details,
+        target,
+    ) {
+        Ok(()) => {
+            logger.info(format!("✅ {kind} hook ran successfully"));
+            Ok(())
+        }
+        Err(err) if hook.failure_policy == LifecycleHookFailurePolicy::Warn => {
+            logger.warning(format!(
+                "{kind} hook failed, ignoring it as requested: {}",
+                err.user_log_message()
+            ));
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn execute_lifecycle_hook(
+    service: &dyn Service,
+    kind: LifecycleHookKind,
+    hook: &LifecycleHook,
+    hook_environment_variables: &[(&str, &str)],
+    service_image: &str,
+    service_account_name: &str,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let namespace = target.environment.namespace();
+    let labels = lifecycle_hook_labels(&service.long_id().to_string(), kind);
+    let to_engine_error = |msg: String| Box::new(EngineError::new_job_error(event_details.clone(), msg));
+    let job_name = lifecycle_hook_job_name(service.kube_name(), kind, Utc::now());
+    let environment_variables: Vec<(String, String)> = service
+        .get_environment_variables()
+        .into_iter()
+        .map(|env| (env.key, env.value))
+        .collect();
+    let env_secret = env_vars_secret(&job_name, &labels, &environment_variables).map_err(to_engine_error)?;
+    let registry_secret = match &target.container_registry.registry_info().registry_docker_json_config {
+        Some(docker_json_config) => Some(
+            docker_registry_secret(&naming::secret_name(&job_name, "registry"), &labels, docker_json_config)
+                .map_err(to_engine_error)?,
+        ),
+        None => None,
+    };
+    let image = hook.image.as_deref().unwrap_or(service_image);
+    let service_account_name =
+        resolve_service_account_name(service_account_name, target.environment.service_account_name());
+    let job = lifecycle_hook_job(
+        &job_name,
+        &labels,
+        image,
+        hook,
+        hook_environment_variables,
+        registry_secret
+            .as_ref()
+            .and_then(|secret| secret.metadata.name.as_deref()),
+        (!service_account_name.is_empty()).then_some(service_account_name.as_str()),
+    );
+    let mut secret_names = vec![job_name.clone()];
+    secret_names.extend(registry_secret.as_ref().and_then(|secret| secret.metadata.name.clone()));
+
+    logger.info(format!("🪝 Running {kind} hook of {} with image {}", service.name(), image));
+    let created = block_on(kube_create_from_resource(&target.kube, namespace, env_secret))
+        .and_then(|_| match registry_secret {
+            Some(secret) => block_on(kube_create_from_resource(&target.kube, namespace, secret)),
+            None => Ok(()),
+        })
+        .and_then(|_| block_on(kube_create_from_resource(&target.kube, namespace, job)));
+
+    let timeout = Duration::from_secs(u64::from(hook.timeout_in_seconds));
+    let job_status = match created {
+        Ok(_) => {
+            let job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), namespace);
+            let job_status =
+                await_job_termination(job_api, &job_name, timeout + Duration::from_secs(60), event_details);
+            if let Err(err) = forward_job_logs(target, &job_name, logger) {
+                logger.warning(format!("Cannot retrieve logs of {kind} hook: {err}"));
+            }
+            job_status
+        }
+        Err(err) => Err(to_engine_error(format!("Cannot create {kind} hook job: {err}"))),
+    };
+
+    // env variables may hold secrets, do not leave them around
+    let job_api: Api<K8sJob> = Api::namespaced(target.kube.clone(), namespace);
+    let secret_api: Api<Secret> = Api::namespaced(target.kube.clone(), namespace);
+    let _ = block_on(job_api.delete(&job_name, &DeleteParams::background()));
+    for secret_name in &secret_names {
+        if let Err(err) = block_on(secret_api.delete(secret_name, &DeleteParams::default())) {
+            logger.warning(format!("Cannot delete {kind} hook secret {secret_name}: {err}"));
+        }
+    }
+
+    match job_status? {
+        JobStatus::Success => Ok(()),
+        JobStatus::Failure { reason, message } => {
+            Err(to_engine_error(format!("{kind} hook failed: {reason} {message}")))
+        }
+        JobStatus::Running | JobStatus::NotRunning => Err(to_engine_error(format!(
+            "{kind} hook did not finish within {}s",
+            hook.timeout_in_seconds
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_lifecycle_hook_job_name() {
+        let now = Utc.with_ymd_and_hms(2023, 11, 2, 10, 0, 0).unwrap();
+        assert_eq!(
+            lifecycle_hook_job_name("app-z1234", LifecycleHookKind::PostCreate, now),
+            "app-z1234-post-create-1698919200"
+        );
+        assert!(lifecycle_hook_job_name(&"a".repeat(80), LifecycleHookKind::PostCreate, now).len() <= 63);
+        assert!(lifecycle_hook_job_name(&"a".repeat(80), LifecycleHookKind::PreDelete, now).len() <= 63);
+        assert!(lifecycle_hook_job_name(&"a".repeat(80), LifecycleHookKind::SmokeTest, now).len() <= 63);
+    }
+
+    #[test]
+    fn test_lifecycle_hook_job() {
+        let hook = LifecycleHook {
+            command: vec!["./notify".to_string(), "--deployed".to_string()],
+            image: None,
+            timeout_in_seconds: 120,
+            failure_policy: LifecycleHookFailurePolicy::Warn,
+        };
+        let labels = lifecycle_hook_labels("service", LifecycleHookKind::PostCreate);
+        let job = lifecycle_hook_job(
+            "app-post-create",
+            &labels,
+            "registry/app:1234",
+            &hook,
+            &[],
+            None,
+            Some("qovery-environment"),
+        );
+
+        assert_eq!(labels.get(LIFECYCLE_HOOK_LABEL).map(String::as_str), Some("post-create"));
+        let spec = job.spec.unwrap();
+        assert_eq!(spec.backoff_limit, Some(0));
+        assert_eq!(spec.active_deadline_seconds, Some(120));
+        let pod_spec = spec.template.spec.unwrap();
+        assert_eq!(pod_spec.image_pull_secrets, None);
+        assert_eq!(pod_spec.service_account_name.as_deref(), Some("qovery-environment"));
+        assert_eq!(pod_spec.containers[0].image.as_deref(), Some("registry/app:1234"));
+        assert_eq!(pod_spec.containers[0].env, None);
+        assert_eq!(pod_spec.containers[0].command, Some(hook.command));
+    }
+}
@@ -2,7 +2,14 @@ use crate::cloud_provider::service::Action;
 use crate::cloud_provider::DeploymentTarget;
 use crate::errors::EngineError;
 
+mod application_migrations;
+mod blue_green;
+mod canary;
+mod certificate_dns_records;
 mod check_dns;
+mod custom_metadata;
+mod database_init_scripts;
+mod database_smoke_test;
 mod deploy_application;
 mod deploy_container;
 mod deploy_database;
@@ -10,26 +17,43 @@ pub mod deploy_environment;
 pub mod deploy_helm;
 mod deploy_helm_chart;
 mod deploy_job;
+mod deploy_kustomize;
 pub mod deploy_namespace;
 mod deploy_router;
 mod deploy_terraform;
+mod deploy_terraform_service;
+mod env_vars_update;
+mod lifecycle_hooks;
+mod new_version;
 mod pause_service;
+mod readiness_gates;
 mod restart_service;
+mod router_probe;
+mod service_secrets;
+mod smoke_test;
+mod statefulset_partition;
 #[cfg(test)]
 mod test_utils;
+pub mod traffic_failover;
 mod utils;
+mod waf_report;
 
 pub trait DeploymentAction: Send + Sync {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>>;
     fn on_pause(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>>;
     fn on_delete(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>>;
     fn on_restart(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>>;
+    // Only cronjobs have something to run on demand, other services are just deployed
+    fn on_trigger_now(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        self.on_create(target)
+    }
     fn exec_action(&self, deployment_target: &DeploymentTarget, action: Action) -> Result<(), Box<EngineError>> {
         match action {
             Action::Create => self.on_create(deployment_target),
             Action::Delete => self.on_delete(deployment_target),
             Action::Pause => self.on_pause(deployment_target),
             Action::Restart => self.on_restart(deployment_target),
+            Action::TriggerNow => self.on_trigger_now(deployment_target),
         }
     }
 }
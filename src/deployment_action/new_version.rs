@@ -0,0 +1,236 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::{HelmCommand, HelmError};
+use crate::deployment_action::deploy_helm::{rendered_resources, HelmDeployment};
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::naming;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{PodSpec, Secret};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Deployment of the new version of an application as rendered by its chart, with the secrets its pods read.
+/// Helm only updates the secrets of the service when it rolls the new version out, so the pods started next to the
+/// previous version read copies of them.
+pub(super) struct NewVersion {
+    pub deployment: Deployment,
+    pub secrets: Vec<Secret>,
+}
+
+/// Renders the chart of the application, None when it does not run as a deployment
+pub(super) fn render_new_version(
+    helm: &HelmDeployment,
+    target: &DeploymentTarget,
+    secret_suffix: &str,
+    labels: &BTreeMap<String, String>,
+    event_details: &EventDetails,
+) -> Result<Option<NewVersion>, Box<EngineError>> {
+    let manifests = helm.render_manifests(target)?;
+    let to_engine_error = |err: String| {
+        Box::new(EngineError::new_helm_error(
+            event_details.clone(),
+            HelmError::CmdError(
+                helm.helm_chart.name.clone(),
+                HelmCommand::TEMPLATE,
+                CommandError::new_from_safe_message(err),
+            ),
+        ))
+    };
+    let Some(deployment) = rendered_resources::<Deployment>(&manifests)
+        .map_err(to_engine_error)?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let secrets = rendered_resources::<Secret>(&manifests).map_err(to_engine_error)?;
+
+    Ok(new_version(deployment, &secrets, secret_suffix, labels))
+}
+
+/// Names of the secrets read by the pods, through their environment variables or volumes
+fn referenced_secrets(pod_spec: &mut PodSpec) -> Vec<&mut String> {
+    let mut names: Vec<&mut String> = vec![];
+    for container in pod_spec
+        .containers
+        .iter_mut()
+        .chain(pod_spec.init_containers.iter_mut().flatten())
+    {
+        names.extend(
+            container
+                .env
+                .iter_mut()
+                .flatten()
+                .filter_map(|env| env.value_from.as_mut()?.secret_key_ref.as_mut()?.name.as_mut()),
+        );
+        names.extend(
+            container
+                .env_from
+                .iter_mut()
+                .flatten()
+                .filter_map(|env_from| env_from.secret_ref.as_mut()?.name.as_mut()),
+        );
+    }
+    names.extend(
+        pod_spec
+            .volumes
+            .iter_mut()
+            .flatten()
+            .filter_map(|volume| volume.secret.as_mut()?.secret_name.as_mut()),
+    );
+    names
+}
+
+/// Points the pods of the rendered deployment to copies of the rendered secrets they read. Secrets the chart does
+/// not render, i.e: the ones synced from external stores, are shared by all the versions and kept as is.
+fn new_version(
+    mut deployment: Deployment,
+    rendered_secrets: &[Secret],
+    secret_suffix: &str,
+    labels: &BTreeMap<String, String>,
+) -> Option<NewVersion> {
+    let rendered_secrets: BTreeMap<&str, &Secret> = rendered_secrets
+        .iter()
+        .filter_map(|secret| Some((secret.metadata.name.as_deref()?, secret)))
+        .collect();
+    let pod_spec = deployment.spec.as_mut()?.template.spec.as_mut()?;
+
+    let mut copied_secrets = BTreeSet::new();
+    for name in referenced_secrets(pod_spec) {
+        if rendered_secrets.contains_key(name.as_str()) {
+            copied_secrets.insert(name.clone());
+            *name = naming::secret_name(name.as_str(), secret_suffix);
+        }
+    }
+    let secrets = copied_secrets
+        .iter()
+        .filter_map(|name| rendered_secrets.get(name.as_str()))
+        .map(|secret| Secret {
+            metadata: ObjectMeta {
+                name: Some(naming::secret_name(
+                    secret.metadata.name.as_deref().unwrap_or_default(),
+                    secret_suffix,
+                )),
+                labels: Some(labels.clone()),
+                ..Default::default()
+            },
+            data: secret.data.clone(),
+            string_data: secret.string_data.clone(),
+            type_: secret.type_.clone(),
+            immutable: None,
+        })
+        .collect();
+
+    Some(NewVersion { deployment, secrets })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::apps::v1::DeploymentSpec;
+    use k8s_openapi::api::core::v1::{
+        Container, EnvVar, EnvVarSource, PodTemplateSpec, SecretKeySelector, SecretVolumeSource, Volume,
+    };
+
+    fn secret_env_var(key: &str, secret_name: &str) -> EnvVar {
+        EnvVar {
+            name: key.to_string(),
+            value_from: Some(EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    name: Some(secret_name.to_string()),
+                    key: key.to_string(),
+                    optional: None,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn secret(name: &str) -> Secret {
+        Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            type_: Some("Opaque".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_version() {
+        let rendered = Deployment {
+            metadata: ObjectMeta {
+                name: Some("app-z1234".to_string()),
+                ..Default::default()
+            },
+            spec: Some(DeploymentSpec {
+                template: PodTemplateSpec {
+                    metadata: None,
+                    spec: Some(PodSpec {
+                        containers: vec![Container {
+                            name: "app-z1234".to_string(),
+                            image: Some("registry/app:v2".to_string()),
+                            env: Some(vec![
+                                secret_env_var("NEW", "app-z1234"),
+                                secret_env_var("DB_PASSWORD", "app-z1234-external"),
+                            ]),
+                            ..Default::default()
+                        }],
+                        volumes: Some(vec![Volume {
+                            name: "config".to_string(),
+                            secret: Some(SecretVolumeSource {
+                                secret_name: Some("config-z1234".to_string()),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }),
+                },
+                ..Default::default()
+            }),
+            status: None,
+        };
+        let labels = BTreeMap::from([("qovery.com/canary-of".to_string(), "long-id".to_string())]);
+
+        let new_version = new_version(
+            rendered,
+            &[secret("app-z1234"), secret("config-z1234"), secret("registry-z1234")],
+            "canary",
+            &labels,
+        )
+        .unwrap();
+        let pod_spec = new_version.deployment.spec.unwrap().template.spec.unwrap();
+        assert_eq!(pod_spec.containers[0].image.as_deref(), Some("registry/app:v2"));
+        // variables synced from the external stores are kept
+        assert_eq!(
+            pod_spec.containers[0].env,
+            Some(vec![
+                secret_env_var("NEW", "app-z1234-canary"),
+                secret_env_var("DB_PASSWORD", "app-z1234-external")
+            ])
+        );
+        assert_eq!(
+            pod_spec.volumes.unwrap()[0]
+                .secret
+                .as_ref()
+                .and_then(|secret| secret.secret_name.as_deref()),
+            Some("config-z1234-canary")
+        );
+        // only the secrets read by the pods are copied
+        let secrets: Vec<(Option<String>, Option<BTreeMap<String, String>>)> = new_version
+            .secrets
+            .into_iter()
+            .map(|secret| (secret.metadata.name, secret.metadata.labels))
+            .collect();
+        assert_eq!(
+            secrets,
+            vec![
+                (Some("app-z1234-canary".to_string()), Some(labels.clone())),
+                (Some("config-z1234-canary".to_string()), Some(labels)),
+            ]
+        );
+    }
+}
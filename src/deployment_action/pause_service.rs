@@ -7,12 +7,17 @@ use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
 use k8s_openapi::api::autoscaling::v1::{Scale, ScaleSpec};
 use k8s_openapi::api::batch::v1::CronJob;
 use k8s_openapi::api::core::v1::Pod;
-use kube::api::{ListParams, Patch, PatchParams};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams, Patch, PatchParams};
 use kube::runtime::wait::{await_condition, Condition};
 use kube::{Api, Client};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::time::Duration;
 
+const KEDA_PAUSED_REPLICAS_ANNOTATION: &str = "autoscaling.keda.sh/paused-replicas";
+// Replicas of a statefulset before it got paused, so a highly available database gets all its replicas back
+const REPLICAS_BEFORE_PAUSE_ANNOTATION: &str = "qovery.com/replicas-before-pause";
+
 fn has_deployment_ready_replicas(nb_ready_replicas: usize) -> impl Condition<Deployment> {
     move |deployment: Option<&Deployment>| {
         deployment
@@ -42,6 +47,44 @@ fn has_cron_job_suspended_value(suspend: bool) -> impl Condition<CronJob> {
     }
 }
 
+// KEDA scales a paused deployment back up as soon as one of its triggers is active, unless its ScaledObject is paused
+async fn set_scaled_objects_paused(
+    kube: &kube::Client,
+    namespace: &str,
+    list_params: &ListParams,
+    paused: bool,
+    is_cluster_wide_resources_allowed: bool,
+) -> Result<(), kube::Error> {
+    let resource = ApiResource::from_gvk(&GroupVersionKind::gvk("keda.sh", "v1alpha1", "ScaledObject"));
+    let scaled_objects: Api<DynamicObject> = if is_cluster_wide_resources_allowed {
+        Api::all_with(kube.clone(), &resource)
+    } else {
+        Api::namespaced_with(kube.clone(), namespace, &resource)
+    };
+    let scaled_objects = match scaled_objects.list(list_params).await {
+        Ok(scaled_objects) => scaled_objects,
+        // KEDA is not installed on the cluster
+        Err(kube::Error::Api(err)) if err.code == 404 => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    let patch = Patch::Merge(json!({
+        "metadata": {
+            "annotations": {
+                KEDA_PAUSED_REPLICAS_ANNOTATION: if paused { Some("0") } else { None }
+            }
+        }
+    }));
+    for scaled_object in scaled_objects {
+        if let (Some(namespace), Some(name)) = (scaled_object.metadata.namespace, scaled_object.metadata.name) {
+            let scaled_objects: Api<DynamicObject> = Api::namespaced_with(kube.clone(), &namespace, &resource);
+            scaled_objects.patch(&name, &PatchParams::default(), &patch).await?;
+        }
+    }
+
+    Ok(())
+}
+
 async fn pause_service(
     kube: &kube::Client,
     namespace: &str,
@@ -62,8 +105,21 @@ async fn pause_service(
                 Api::namespaced(kube.clone(), namespace)
             };
             for statefulset in statefulsets.list(&list_params).await? {
+                let replicas = statefulset.spec.as_ref().and_then(|spec| spec.replicas).unwrap_or(0);
                 if let (Some(namespace), Some(name)) = (statefulset.metadata.namespace, statefulset.metadata.name) {
                     let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), &namespace); // patch_scale need to have statefulsets with namespace
+                    if desired_size == 0 && replicas > 0 {
+                        let annotation = json!({
+                            "metadata": {
+                                "annotations": {
+                                    REPLICAS_BEFORE_PAUSE_ANNOTATION: replicas.to_string()
+                                }
+                            }
+                        });
+                        statefulsets
+                            .patch(&name, &PatchParams::default(), &Patch::Merge(annotation))
+                            .await?;
+                    }
                     statefulsets.patch_scale(&name, &patch_params, &patch).await?;
                     let _ = await_condition(statefulsets.clone(), &name, has_statefulset_ready_replicas(0)).await;
                 }
@@ -79,6 +135,7 @@ async fn pause_service(
         }
         K8sResourceType::Deployment => {
             let (list_params, patch_params, patch) = get_patch_merge(selector, desired_size);
+            set_scaled_objects_paused(kube, namespace, &list_params, true, is_cluster_wide_resources_allowed).await?;
             let deployments: Api<Deployment> = if is_cluster_wide_resources_allowed {
                 Api::all(kube.clone())
             } else {
@@ -132,7 +189,7 @@ async fn unpause_service_if_needed(
 ) -> Result<(), kube::Error> {
     match k8s_resource_type {
         K8sResourceType::StateFulSet => {
-            let (list_params, patch_params, patch) = get_patch_merge(selector, 1);
+            let list_params = ListParams::default().labels(selector);
             let statefulsets: Api<StatefulSet> = if is_cluster_wide_resources_allowed {
                 Api::all(kube.clone())
             } else {
@@ -140,6 +197,8 @@ async fn unpause_service_if_needed(
             };
             for statefulset in statefulsets.list(&list_params).await? {
                 if statefulset.status.map(|s| s.replicas).unwrap_or(0) == 0 {
+                    let replicas = replicas_before_pause(&statefulset.metadata);
+                    let (_, patch_params, patch) = get_patch_merge(selector, replicas);
                     if let (Some(namespace), Some(name)) = (statefulset.metadata.namespace, statefulset.metadata.name) {
                         let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), &namespace); // patch_scale needs to have statefulsets with namespace
                         statefulsets.patch_scale(&name, &patch_params, &patch).await?;
@@ -162,6 +221,7 @@ async fn unpause_service_if_needed(
                     }
                 }
             }
+            set_scaled_objects_paused(kube, namespace, &list_params, false, is_cluster_wide_resources_allowed).await?;
         }
         K8sResourceType::CronJob => {
             let (list_params, patch_params, patch) = get_patch_suspend(selector, false);
@@ -184,6 +244,16 @@ async fn unpause_service_if_needed(
     Ok(())
 }
 
+fn replicas_before_pause(metadata: &ObjectMeta) -> usize {
+    metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(REPLICAS_BEFORE_PAUSE_ANNOTATION))
+        .and_then(|replicas| replicas.parse::<usize>().ok())
+        .filter(|replicas| *replicas > 0)
+        .unwrap_or(1)
+}
+
 fn get_patch_merge(selector: &str, desired_size: usize) -> (ListParams, PatchParams, Patch<Scale>) {
     let list_params = ListParams::default().labels(selector);
     let patch_params = PatchParams::default();
@@ -387,7 +457,7 @@ impl DeploymentAction for PauseServiceAction {
 mod tests {
     use crate::deployment_action::pause_service::{
         has_cron_job_suspended_value, has_deployment_ready_replicas, has_statefulset_ready_replicas, pause_service,
-        unpause_service_if_needed, K8sResourceType,
+        replicas_before_pause, unpause_service_if_needed, K8sResourceType, REPLICAS_BEFORE_PAUSE_ANNOTATION,
     };
     use crate::deployment_action::test_utils::{
         get_simple_cron_job, get_simple_deployment, get_simple_hpa, get_simple_statefulset, NamespaceForTest,
@@ -396,11 +466,29 @@ mod tests {
     use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
     use k8s_openapi::api::autoscaling::v1::HorizontalPodAutoscaler;
     use k8s_openapi::api::batch::v1::CronJob;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
     use kube::api::PostParams;
     use kube::runtime::wait::await_condition;
     use kube::Api;
+    use std::collections::BTreeMap;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+    #[test]
+    fn test_replicas_before_pause() {
+        let metadata = |replicas: &str| ObjectMeta {
+            annotations: Some(BTreeMap::from([(
+                REPLICAS_BEFORE_PAUSE_ANNOTATION.to_string(),
+                replicas.to_string(),
+            )])),
+            ..Default::default()
+        };
+
+        assert_eq!(replicas_before_pause(&ObjectMeta::default()), 1);
+        assert_eq!(replicas_before_pause(&metadata("3")), 3);
+        assert_eq!(replicas_before_pause(&metadata("0")), 1);
+        assert_eq!(replicas_before_pause(&metadata("not a number")), 1);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[named]
     async fn test_scale_deployment() -> Result<(), Box<dyn std::error::Error>> {
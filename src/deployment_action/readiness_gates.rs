@@ -0,0 +1,63 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::models::application::ApplicationService;
+use crate::models::readiness_gates::check_readiness;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const READINESS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wait for the external dependencies declared by the application to be ready, before its rollout starts.
+/// Checks are retried until the readiness gates timeout, then the deployment is aborted.
+pub(super) fn await_readiness_gates(
+    app: &dyn ApplicationService,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let Some(readiness_gates) = app.readiness_gates() else {
+        return Ok(());
+    };
+    if readiness_gates.checks.is_empty() || target.is_dry_run_deploy {
+        return Ok(());
+    }
+
+    logger.info(format!(
+        "🚦 Checking {} external dependencies before rolling out the application",
+        readiness_gates.checks.len()
+    ));
+    let deadline = Instant::now() + Duration::from_secs(u64::from(readiness_gates.timeout_in_seconds));
+    loop {
+        let failing_checks: Vec<String> = readiness_gates
+            .checks
+            .iter()
+            .filter_map(|check| {
+                check_readiness(check, READINESS_CHECK_TIMEOUT)
+                    .err()
+                    .map(|reason| format!("{check} ({reason})"))
+            })
+            .collect();
+
+        if failing_checks.is_empty() {
+            logger.info("🟢 All external dependencies are ready".to_string());
+            return Ok(());
+        }
+
+        if Instant::now() + READINESS_CHECK_INTERVAL > deadline {
+            return Err(Box::new(EngineError::new_client_service_dependencies_not_ready(
+                event_details.clone(),
+                app.name().to_string(),
+                failing_checks,
+            )));
+        }
+
+        logger.warning(format!(
+            "⏳ Waiting for external dependencies to be ready: {}",
+            failing_checks.join(", ")
+        ));
+        thread::sleep(READINESS_CHECK_INTERVAL);
+    }
+}
@@ -0,0 +1,314 @@
+use crate::cloud_provider::models::{CustomDomain, Route};
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::io_models::router::BackendProtocol;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::thread;
+use std::time::{Duration, Instant};
+use url::Url;
+
+const ROUTE_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+const ROUTE_PROBE_ATTEMPTS: u32 = 6;
+const ROUTE_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+// https://github.com/grpc/grpc/blob/master/doc/health-checking.md
+const GRPC_HEALTH_CHECK_PATH: &str = "/grpc.health.v1.Health/Check";
+// an empty request message: not compressed, of length 0
+const GRPC_EMPTY_MESSAGE: [u8; 5] = [0; 5];
+
+/// Route of the router requested from the internet, through the public DNS and the load balancer of the cluster
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct RouteProbe {
+    pub url: Url,
+    /// Any status but a server error is accepted when not set
+    pub expected_status_code: Option<u16>,
+    /// gRPC routes are probed with a health check call, the other HTTP/2 ones with a plain request
+    pub backend_protocol: BackendProtocol,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct RouteProbeOutcome {
+    pub url: Url,
+    pub status_code: Option<u16>,
+    pub latency: Duration,
+    /// Whether the certificate served by the load balancer has been verified
+    pub tls_verified: bool,
+    pub failure: Option<String>,
+}
+
+impl Display for RouteProbeOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.failure, self.status_code) {
+            (Some(failure), _) => write!(f, "{} failed: {}", self.url, failure),
+            (None, Some(status_code)) => {
+                write!(f, "{} answered {} in {}ms", self.url, status_code, self.latency.as_millis())?;
+                if self.tls_verified {
+                    write!(f, ", TLS certificate verified")?;
+                }
+                Ok(())
+            }
+            (None, None) => write!(f, "{} did not answer", self.url),
+        }
+    }
+}
+
+/// Whether the answer comes from a gRPC server, whatever the status of the call, i.e: the health service not being
+/// implemented. The proxy answers the requests it cannot forward without the gRPC content type.
+fn is_grpc_answer(status_code: u16, content_type: Option<&str>) -> bool {
+    status_code == 200 && content_type.map_or(false, |content_type| content_type.starts_with("application/grpc"))
+}
+
+fn is_expected_status_code(status_code: u16, expected_status_code: Option<u16>) -> bool {
+    match expected_status_code {
+        Some(expected_status_code) => status_code == expected_status_code,
+        None => status_code < 500,
+    }
+}
+
+/// Probes of every route of the router, on its default domain and on its custom domains.
+/// Wildcard domains are skipped, as there is no host to request.
+pub(super) fn route_probes(
+    default_domain: &str,
+    custom_domains: &[CustomDomain],
+    routes: &[Route],
+    expected_status_codes: &BTreeMap<String, u16>,
+) -> Vec<RouteProbe> {
+    let hosts = std::iter::once(default_domain)
+        .chain(custom_domains.iter().map(|custom_domain| custom_domain.domain.as_str()))
+        .filter(|host| !host.is_empty() && !host.contains('*'));
+
+    hosts
+        .flat_map(|host| {
+            routes.iter().filter_map(move |route| {
+                let path = format!("/{}", route.path.trim_start_matches('/'));
+                Some(RouteProbe {
+                    url: Url::parse(&format!("https://{host}{path}")).ok()?,
+                    expected_status_code: expected_status_codes.get(&route.path).copied(),
+                    backend_protocol: route.backend_protocol,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Error with its causes, TLS and DNS failures being only described by the latter
+fn error_with_causes(err: &reqwest::Error) -> String {
+    if err.is_timeout() {
+        return format!("no answer after {}s", ROUTE_PROBE_TIMEOUT.as_secs());
+    }
+
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+fn probe_route(probe: &RouteProbe) -> RouteProbeOutcome {
+    let mut outcome = RouteProbeOutcome {
+        url: probe.url.clone(),
+        status_code: None,
+        latency: Duration::ZERO,
+        tls_verified: false,
+        failure: None,
+    };
+
+    // redirections are not followed, they are answers of the route as any other
+    let mut client_builder = reqwest::blocking::Client::builder()
+        .timeout(ROUTE_PROBE_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .tls_info(true);
+    if probe.backend_protocol.is_http2() {
+        client_builder = client_builder.http2_prior_knowledge();
+    }
+    let client = match client_builder.build() {
+        Ok(client) => client,
+        Err(err) => {
+            outcome.failure = Some(error_with_causes(&err));
+            return outcome;
+        }
+    };
+
+    let request = match probe.backend_protocol {
+        BackendProtocol::Grpc => {
+            let mut health_check_url = probe.url.clone();
+            health_check_url.set_path(GRPC_HEALTH_CHECK_PATH);
+            client
+                .post(health_check_url)
+                .header(reqwest::header::CONTENT_TYPE, "application/grpc")
+                .header(reqwest::header::TE, "trailers")
+                .body(GRPC_EMPTY_MESSAGE.to_vec())
+        }
+        BackendProtocol::Http | BackendProtocol::H2c => client.get(probe.url.clone()),
+    };
+
+    let started_at = Instant::now();
+    match request.send() {
+        Ok(response) => {
+            outcome.latency = started_at.elapsed();
+            outcome.status_code = Some(response.status().as_u16());
+            outcome.tls_verified = response
+                .extensions()
+                .get::<reqwest::tls::TlsInfo>()
+                .and_then(|tls_info| tls_info.peer_certificate())
+                .is_some();
+            if probe.backend_protocol == BackendProtocol::Grpc {
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|content_type| content_type.to_str().ok());
+                if !is_grpc_answer(response.status().as_u16(), content_type) {
+                    outcome.failure = Some(format!("answered {} instead of a gRPC response", response.status()));
+                }
+            } else if !is_expected_status_code(response.status().as_u16(), probe.expected_status_code) {
+                outcome.failure = Some(match probe.expected_status_code {
+                    Some(expected_status_code) => {
+                        format!("answered {} instead of {}", response.status(), expected_status_code)
+                    }
+                    None => format!("answered {}", response.status()),
+                });
+            }
+        }
+        Err(err) => {
+            outcome.latency = started_at.elapsed();
+            outcome.failure = Some(error_with_causes(&err));
+        }
+    }
+
+    outcome
+}
+
+/// Request each route from the internet once the router is deployed, to catch load balancer, DNS or certificate
+/// misconfigurations the readiness of the pods does not show. Routes are retried while the DNS records propagate,
+/// and the ones still failing are only reported as warnings.
+pub(super) fn run_route_probes(probes: &[RouteProbe], logger: &EnvProgressLogger) -> Vec<RouteProbeOutcome> {
+    if probes.is_empty() {
+        return vec![];
+    }
+
+    logger.info(format!("🌐 Checking {} route(s) of the router from the internet", probes.len()));
+    let mut outcomes: Vec<RouteProbeOutcome> = probes.iter().map(probe_route).collect();
+    for _ in 1..ROUTE_PROBE_ATTEMPTS {
+        if outcomes.iter().all(|outcome| outcome.failure.is_none()) {
+            break;
+        }
+
+        thread::sleep(ROUTE_PROBE_INTERVAL);
+        for (probe, outcome) in probes.iter().zip(outcomes.iter_mut()) {
+            if outcome.failure.is_some() {
+                *outcome = probe_route(probe);
+            }
+        }
+    }
+
+    for outcome in outcomes.iter().filter(|outcome| outcome.failure.is_some()) {
+        logger.warning(format!("⚠️ Route {outcome}"));
+    }
+
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_models::router::TrailingSlash;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_route_probes() {
+        let routes = vec![
+            Route {
+                path: "/".to_string(),
+                service_long_id: Uuid::new_v4(),
+                backend_protocol: BackendProtocol::Http,
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: TrailingSlash::Keep,
+            },
+            Route {
+                path: "api".to_string(),
+                service_long_id: Uuid::new_v4(),
+                backend_protocol: BackendProtocol::Grpc,
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: TrailingSlash::Keep,
+            },
+        ];
+        let custom_domains = vec![
+            CustomDomain {
+                domain: "www.example.com".to_string(),
+                target_domain: "my-router.qovery.io".to_string(),
+                generate_certificate: true,
+                certificate: None,
+            },
+            CustomDomain {
+                domain: "*.example.com".to_string(),
+                target_domain: "my-router.qovery.io".to_string(),
+                generate_certificate: true,
+                certificate: None,
+            },
+        ];
+        let expected_status_codes = BTreeMap::from([("api".to_string(), 401)]);
+
+        let probes = route_probes("my-router.qovery.io", &custom_domains, &routes, &expected_status_codes);
+        assert_eq!(
+            probes
+                .iter()
+                .map(|probe| (probe.url.as_str(), probe.expected_status_code, probe.backend_protocol))
+                .collect::<Vec<_>>(),
+            vec![
+                ("https://my-router.qovery.io/", None, BackendProtocol::Http),
+                ("https://my-router.qovery.io/api", Some(401), BackendProtocol::Grpc),
+                ("https://www.example.com/", None, BackendProtocol::Http),
+                ("https://www.example.com/api", Some(401), BackendProtocol::Grpc),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_expected_status_code() {
+        assert!(is_expected_status_code(200, None));
+        assert!(is_expected_status_code(404, None));
+        assert!(!is_expected_status_code(502, None));
+        assert!(is_expected_status_code(401, Some(401)));
+        assert!(!is_expected_status_code(200, Some(401)));
+    }
+
+    #[test]
+    fn test_is_grpc_answer() {
+        assert!(is_grpc_answer(200, Some("application/grpc")));
+        assert!(is_grpc_answer(200, Some("application/grpc+proto")));
+        assert!(!is_grpc_answer(200, Some("text/html")));
+        assert!(!is_grpc_answer(502, Some("application/grpc")));
+        assert!(!is_grpc_answer(200, None));
+    }
+
+    #[test]
+    fn test_probe_route() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+        });
+
+        let outcome = probe_route(&RouteProbe {
+            url: Url::parse(&format!("http://127.0.0.1:{port}/")).unwrap(),
+            expected_status_code: None,
+            backend_protocol: BackendProtocol::Http,
+        });
+        server.join().unwrap();
+
+        assert_eq!(outcome.status_code, Some(503));
+        assert!(!outcome.tls_verified);
+        assert_eq!(outcome.failure.as_deref(), Some("answered 503 Service Unavailable"));
+    }
+}
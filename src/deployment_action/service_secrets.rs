@@ -0,0 +1,95 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::ByteString;
+use std::collections::BTreeMap;
+
+// Secrets created outside of the service chart, for the pods the engine starts itself (migrations and lifecycle hooks)
+
+fn decode_base64(value: &str) -> Result<ByteString, String> {
+    general_purpose::STANDARD
+        .decode(value)
+        .map(ByteString)
+        .map_err(|err| format!("invalid base64 value: {err}"))
+}
+
+/// Environment variables of the service, values are already base64 encoded
+pub(super) fn env_vars_secret(
+    name: &str,
+    labels: &BTreeMap<String, String>,
+    environment_variables: &[(String, String)],
+) -> Result<Secret, String> {
+    let mut data = BTreeMap::new();
+    for (key, value) in environment_variables {
+        data.insert(
+            key.clone(),
+            decode_base64(value).map_err(|err| format!("Environment variable {key} has an {err}"))?,
+        );
+    }
+
+    Ok(Secret {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            labels: Some(labels.clone()),
+            ..Default::default()
+        },
+        data: Some(data),
+        type_: Some("Opaque".to_string()),
+        ..Default::default()
+    })
+}
+
+/// Credentials to pull the image of the service, the docker json config being base64 encoded
+pub(super) fn docker_registry_secret(
+    name: &str,
+    labels: &BTreeMap<String, String>,
+    docker_json_config: &str,
+) -> Result<Secret, String> {
+    Ok(Secret {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            labels: Some(labels.clone()),
+            ..Default::default()
+        },
+        data: Some(BTreeMap::from([(
+            ".dockerconfigjson".to_string(),
+            decode_base64(docker_json_config).map_err(|err| format!("Registry credentials have an {err}"))?,
+        )])),
+        type_: Some("kubernetes.io/dockerconfigjson".to_string()),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_vars_secret() {
+        let labels = BTreeMap::from([("qovery.com/service-id".to_string(), "service".to_string())]);
+        let secret = env_vars_secret(
+            "app-migrations",
+            &labels,
+            &[("DATABASE_URL".to_string(), general_purpose::STANDARD.encode("postgres://db"))],
+        )
+        .unwrap();
+        assert_eq!(secret.metadata.labels, Some(labels.clone()));
+        assert_eq!(
+            secret.data.unwrap().get("DATABASE_URL"),
+            Some(&ByteString(b"postgres://db".to_vec()))
+        );
+
+        assert!(env_vars_secret("app-migrations", &labels, &[("KEY".to_string(), "%%%".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_docker_registry_secret() {
+        let secret =
+            docker_registry_secret("app-registry", &BTreeMap::new(), &general_purpose::STANDARD.encode("{}")).unwrap();
+        assert_eq!(secret.type_.as_deref(), Some("kubernetes.io/dockerconfigjson"));
+        assert_eq!(secret.data.unwrap().get(".dockerconfigjson"), Some(&ByteString(b"{}".to_vec())));
+
+        assert!(docker_registry_secret("app-registry", &BTreeMap::new(), "%%%").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::cloud_provider::helm::ChartInfo;
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::HelmError;
+use crate::deployment_action::lifecycle_hooks::{run_lifecycle_hook, LifecycleHookKind};
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::io_models::{LifecycleHook, LifecycleHookFailurePolicy, SmokeTest, SmokeTestCheck};
+use crate::models::application::ApplicationService;
+
+const SMOKE_TEST_HTTP_IMAGE: &str = "curlimages/curl:8.5.0";
+// Host the smoke test targets, given to command checks which do not always target the service
+const SMOKE_TEST_HOST_ENV: &str = "QOVERY_SMOKE_TEST_HOST";
+// Url and expected status are given as arguments of the script, they are never interpreted by the shell
+const SMOKE_TEST_HTTP_SCRIPT: &str = "status=$(curl -s -o /dev/null -w '%{http_code}' --retry 5 --retry-connrefused --retry-delay 5 --max-time 30 \"$1\"); echo \"GET $1 answered with HTTP status $status, expecting $2\"; [ \"$status\" = \"$2\" ]";
+
+/// Job running the smoke test against `host`. An http check is sent with curl from the namespace, retried while
+/// the host starts routing to the new pods.
+fn smoke_test_hook(smoke_test: &SmokeTest, host: &str) -> LifecycleHook {
+    let (command, image) = match &smoke_test.check {
+        SmokeTestCheck::Http {
+            path,
+            port,
+            expected_status,
+        } => {
+            // pod IPs may be IPv6 ones
+            let host = if host.contains(':') {
+                format!("[{host}]")
+            } else {
+                host.to_string()
+            };
+            (
+                vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    SMOKE_TEST_HTTP_SCRIPT.to_string(),
+                    "smoke-test".to_string(),
+                    format!("http://{host}:{port}{path}"),
+                    expected_status.to_string(),
+                ],
+                Some(SMOKE_TEST_HTTP_IMAGE.to_string()),
+            )
+        }
+        SmokeTestCheck::Command { command, image } => (command.clone(), image.clone()),
+    };
+
+    LifecycleHook {
+        command,
+        image,
+        timeout_in_seconds: smoke_test.timeout_in_seconds,
+        failure_policy: LifecycleHookFailurePolicy::FailDeployment,
+    }
+}
+
+/// Roll the helm release of the application back to its previous revision, the first version of an application
+/// being left as is
+pub(super) fn rollback_helm_release(chart: &ChartInfo, logger: &EnvProgressLogger, target: &DeploymentTarget) {
+    match target
+        .helm
+        .rollback(chart, target.cloud_provider.credentials_environment_variables().as_slice())
+    {
+        Ok(()) => logger.info("⏪ Application is rolled back to its previous version".to_string()),
+        Err(HelmError::CannotRollback(_)) => {
+            logger.warning("First version of the application has no previous version to roll back to".to_string())
+        }
+        Err(rollback_err) => logger.warning(format!("Cannot roll back the application: {rollback_err}")),
+    }
+}
+
+/// Run the smoke test of the application against `host`, its service once the new version is rolled out or a pod
+/// of the new version before traffic is switched to it. When it fails, `rollback` restores the previous version
+/// before failing the deployment.
+pub(super) fn run_smoke_test(
+    app: &dyn ApplicationService,
+    host: &str,
+    rollback: impl FnOnce(&EnvProgressLogger),
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let Some(smoke_test) = app.smoke_test() else {
+        return Ok(());
+    };
+
+    let hook = smoke_test_hook(smoke_test, host);
+    let Err(err) = run_lifecycle_hook(
+        app.as_service(),
+        LifecycleHookKind::SmokeTest,
+        Some(&hook),
+        &[(SMOKE_TEST_HOST_ENV, host)],
+        &app.get_build().image.full_image_name_with_tag(),
+        &app.advanced_settings().security_service_account_name,
+        logger,
+        event_details,
+        target,
+    ) else {
+        return Ok(());
+    };
+
+    logger.warning("⏪ Smoke test failed, rolling back the application to its previous version".to_string());
+    rollback(logger);
+
+    Err(Box::new(EngineError::new_application_smoke_test_failed(
+        event_details.clone(),
+        err.user_log_message().to_string(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoke_test_hook() {
+        let hook = smoke_test_hook(
+            &SmokeTest {
+                check: SmokeTestCheck::Http {
+                    path: "/health".to_string(),
+                    port: 8080,
+                    expected_status: 204,
+                },
+                timeout_in_seconds: 60,
+            },
+            "app-z1234",
+        );
+        assert_eq!(hook.image.as_deref(), Some(SMOKE_TEST_HTTP_IMAGE));
+        assert_eq!(hook.timeout_in_seconds, 60);
+        assert_eq!(hook.failure_policy, LifecycleHookFailurePolicy::FailDeployment);
+        assert_eq!(&hook.command[4..], &["http://app-z1234:8080/health", "204"]);
+
+        let hook = smoke_test_hook(
+            &SmokeTest {
+                check: SmokeTestCheck::Http {
+                    path: "/health".to_string(),
+                    port: 8080,
+                    expected_status: 200,
+                },
+                timeout_in_seconds: 60,
+            },
+            "fd00::1",
+        );
+        assert_eq!(&hook.command[4..], &["http://[fd00::1]:8080/health", "200"]);
+
+        let command = vec!["./smoke-test".to_string(), "--quick".to_string()];
+        let hook = smoke_test_hook(
+            &SmokeTest {
+                check: SmokeTestCheck::Command {
+                    command: command.clone(),
+                    image: None,
+                },
+                timeout_in_seconds: 300,
+            },
+            "app-z1234",
+        );
+        assert_eq!(hook.command, command);
+        assert_eq!(hook.image, None);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_report::logger::EnvProgressLogger;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::StatefulSetUpdateStrategy;
+use crate::runtime::block_on;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::runtime::wait::{await_condition, Condition};
+use kube::Api;
+use std::time::Duration;
+
+/// Rollout state of a statefulset once its partition has been reached
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatefulSetRollout {
+    pub name: String,
+    pub replicas: i32,
+    pub updated_replicas: i32,
+}
+
+/// Moves the rolling update partition of the statefulsets matching the selector. Only the pods with an ordinal
+/// greater or equal to the partition get the new spec, lowering it step by step lets a risky update be validated
+/// on a few pods before reaching the others.
+pub struct StatefulSetPartitionAction {
+    selector: String,
+    partition: u32,
+    timeout: Duration,
+    event_details: EventDetails,
+}
+
+impl StatefulSetPartitionAction {
+    pub fn new(selector: String, partition: u32, event_details: EventDetails) -> StatefulSetPartitionAction {
+        StatefulSetPartitionAction {
+            selector,
+            partition,
+            timeout: Duration::from_secs(10 * 60),
+            event_details,
+        }
+    }
+
+    /// Sets the partition and waits for the pods at or above it to run the update revision
+    pub fn advance(&self, target: &DeploymentTarget) -> Result<Vec<StatefulSetRollout>, Box<EngineError>> {
+        let namespace = target.environment.namespace();
+        let future = advance_partition(&target.kube, namespace, &self.selector, self.partition);
+        // tokio::time::timeout needs a living runtime, so it must be created inside the block_on
+        let ret = block_on(async { tokio::time::timeout(self.timeout, future).await });
+
+        let command_error = match ret {
+            Ok(Ok(rollouts)) => return Ok(rollouts),
+            Ok(Err(kube_error)) => CommandError::new(
+                "Cannot advance statefulset partition".to_string(),
+                Some(kube_error.to_string()),
+                None,
+            ),
+            Err(_) => CommandError::new_from_safe_message(format!(
+                "Timeout of {}s exceeded while waiting for the statefulset pods to be updated",
+                self.timeout.as_secs()
+            )),
+        };
+
+        Err(Box::new(EngineError::new_cannot_advance_statefulset_partition(
+            self.event_details.clone(),
+            namespace,
+            &self.selector,
+            self.partition,
+            command_error,
+        )))
+    }
+}
+
+/// Holds the update of the statefulsets back on the pods below the partition, nothing to do when it is 0 as
+/// the rolling update already reaches every pod
+pub(super) fn stage_statefulset_update(
+    selector: String,
+    update_strategy: &StatefulSetUpdateStrategy,
+    partition: u32,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    if *update_strategy != StatefulSetUpdateStrategy::RollingUpdate || partition == 0 {
+        return Ok(());
+    }
+
+    let rollouts = StatefulSetPartitionAction::new(selector, partition, event_details.clone()).advance(target)?;
+    for rollout in rollouts {
+        logger.info(format!(
+            "⏸️ Update of {} is staged on {}/{} pods, pods with an ordinal lower than {} keep the previous version until the partition is lowered",
+            rollout.name, rollout.updated_replicas, rollout.replicas, partition
+        ));
+    }
+
+    Ok(())
+}
+
+fn is_rolled_out_to_partition(partition: u32) -> impl Condition<StatefulSet> {
+    move |statefulset: Option<&StatefulSet>| match statefulset {
+        Some(statefulset) => {
+            let replicas = statefulset.spec.as_ref().and_then(|spec| spec.replicas).unwrap_or(1);
+            let staged_replicas = (replicas - partition as i32).max(0);
+            statefulset
+                .status
+                .as_ref()
+                .map(|status| {
+                    // the status must describe the spec carrying the new partition
+                    status.observed_generation >= statefulset.metadata.generation
+                        && status.updated_replicas.unwrap_or(0) >= staged_replicas
+                        && status.ready_replicas.unwrap_or(0) >= replicas
+                })
+                .unwrap_or(false)
+        }
+        None => false,
+    }
+}
+
+async fn advance_partition(
+    kube: &kube::Client,
+    namespace: &str,
+    selector: &str,
+    partition: u32,
+) -> Result<Vec<StatefulSetRollout>, kube::Error> {
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+    let patch = serde_json::json!({ "spec": { "updateStrategy": { "rollingUpdate": { "partition": partition } } } });
+    let mut rollouts = vec![];
+
+    for statefulset in statefulsets.list(&ListParams::default().labels(selector)).await? {
+        let is_rolling_update = statefulset
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.update_strategy.as_ref())
+            .and_then(|strategy| strategy.type_.as_deref())
+            .map(|strategy_type| strategy_type == "RollingUpdate")
+            // RollingUpdate is the default strategy of statefulsets
+            .unwrap_or(true);
+        // pods of OnDelete statefulsets are only replaced when deleted, there is no partition to move
+        let Some(name) = statefulset.metadata.name.filter(|_| is_rolling_update) else {
+            continue;
+        };
+
+        info!("Setting rolling update partition of k8s StatefulSet {} to {}", name, partition);
+        statefulsets
+            .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+        let _ = await_condition(statefulsets.clone(), &name, is_rolled_out_to_partition(partition)).await;
+
+        let status = statefulsets.get_status(&name).await?.status;
+        rollouts.push(StatefulSetRollout {
+            name,
+            replicas: status.as_ref().map(|status| status.replicas).unwrap_or(0),
+            updated_replicas: status.and_then(|status| status.updated_replicas).unwrap_or(0),
+        });
+    }
+
+    Ok(rollouts)
+}
+
+fn has_all_replicas_ready(statefulset: Option<&StatefulSet>) -> bool {
+    let Some(statefulset) = statefulset else {
+        return false;
+    };
+    let replicas = statefulset.spec.as_ref().and_then(|spec| spec.replicas).unwrap_or(1);
+    statefulset
+        .status
+        .as_ref()
+        .map(|status| {
+            status.observed_generation >= statefulset.metadata.generation
+                && status.ready_replicas.unwrap_or(0) >= replicas
+        })
+        .unwrap_or(false)
+}
+
+async fn await_all_replicas_ready(kube: &kube::Client, namespace: &str, selector: &str) -> Result<(), kube::Error> {
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+    for statefulset in statefulsets.list(&ListParams::default().labels(selector)).await? {
+        if let Some(name) = statefulset.metadata.name {
+            let _ = await_condition(statefulsets.clone(), &name, has_all_replicas_ready).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Waits for every pod of the statefulsets matching the selector to be ready. Helm only waits for the pods it
+/// rolls out, the standbys of a database running several instances must have joined the primary as well.
+pub(super) fn await_statefulsets_ready(
+    selector: &str,
+    timeout: Duration,
+    event_details: &EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), Box<EngineError>> {
+    let namespace = target.environment.namespace();
+    let future = await_all_replicas_ready(&target.kube, namespace, selector);
+    let command_error = match block_on(async { tokio::time::timeout(timeout, future).await }) {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(kube_error)) => {
+            CommandError::new("Cannot get the statefulsets".to_string(), Some(kube_error.to_string()), None)
+        }
+        Err(_) => CommandError::new_from_safe_message(format!(
+            "Timeout of {}s exceeded while waiting for all the statefulset pods to be ready",
+            timeout.as_secs()
+        )),
+    };
+
+    Err(Box::new(EngineError::new_k8s_pod_not_ready(
+        event_details.clone(),
+        selector.to_string(),
+        namespace.to_string(),
+        command_error,
+    )))
+}
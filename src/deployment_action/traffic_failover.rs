@@ -0,0 +1,364 @@
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{DnsProvider, DnsRecordType, WeightedRecord};
+use crate::errors::EngineError;
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::logger::Logger;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Number of consecutive failed probes before a cluster is considered down
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+/// Number of consecutive successful probes before traffic is sent back to a recovered cluster.
+/// Higher than the failure threshold to avoid flapping between clusters.
+pub const DEFAULT_RECOVERY_THRESHOLD: u32 = 5;
+const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClusterHealth {
+    Healthy,
+    Unhealthy,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailoverTransition {
+    pub cluster_long_id: Uuid,
+    pub from: ClusterHealth,
+    pub to: ClusterHealth,
+}
+
+/// A cluster serving the same traffic, with the DNS weight it gets when healthy
+#[derive(Clone, Debug)]
+pub struct ClusterEndpoint {
+    pub cluster_long_id: Uuid,
+    /// host:port probed by the engine, i.e: the load balancer of the cluster
+    pub probe_address: String,
+    /// Hostname of the cluster load balancer the record points to
+    pub record_value: String,
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug)]
+struct ClusterState {
+    endpoint: ClusterEndpoint,
+    health: ClusterHealth,
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+}
+
+/// Track health of clusters sharing the same DNS record and compute the weights to apply on it.
+/// A cluster is only marked unhealthy/healthy after several consecutive probes agree (hysteresis).
+pub struct TrafficFailover {
+    clusters: Vec<ClusterState>,
+    failure_threshold: u32,
+    recovery_threshold: u32,
+    probe_timeout: Duration,
+}
+
+impl TrafficFailover {
+    pub fn new(endpoints: Vec<ClusterEndpoint>) -> Self {
+        TrafficFailover {
+            clusters: endpoints
+                .into_iter()
+                .map(|endpoint| ClusterState {
+                    endpoint,
+                    health: ClusterHealth::Healthy,
+                    consecutive_failures: 0,
+                    consecutive_successes: 0,
+                })
+                .collect(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            recovery_threshold: DEFAULT_RECOVERY_THRESHOLD,
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+
+    pub fn with_thresholds(mut self, failure_threshold: u32, recovery_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.recovery_threshold = recovery_threshold.max(1);
+        self
+    }
+
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+
+    pub fn health(&self, cluster_long_id: &Uuid) -> Option<ClusterHealth> {
+        self.clusters
+            .iter()
+            .find(|c| &c.endpoint.cluster_long_id == cluster_long_id)
+            .map(|c| c.health)
+    }
+
+    /// Record the result of a probe, returns the transition if the cluster health changed
+    pub fn record_probe(&mut self, cluster_long_id: &Uuid, is_healthy: bool) -> Option<FailoverTransition> {
+        let (failure_threshold, recovery_threshold) = (self.failure_threshold, self.recovery_threshold);
+        let cluster = self
+            .clusters
+            .iter_mut()
+            .find(|c| &c.endpoint.cluster_long_id == cluster_long_id)?;
+
+        if is_healthy {
+            cluster.consecutive_failures = 0;
+            cluster.consecutive_successes = cluster.consecutive_successes.saturating_add(1);
+        } else {
+            cluster.consecutive_successes = 0;
+            cluster.consecutive_failures = cluster.consecutive_failures.saturating_add(1);
+        }
+
+        let new_health = match cluster.health {
+            ClusterHealth::Healthy if cluster.consecutive_failures >= failure_threshold => ClusterHealth::Unhealthy,
+            ClusterHealth::Unhealthy if cluster.consecutive_successes >= recovery_threshold => ClusterHealth::Healthy,
+            health => health,
+        };
+
+        if new_health == cluster.health {
+            return None;
+        }
+
+        let transition = FailoverTransition {
+            cluster_long_id: *cluster_long_id,
+            from: cluster.health,
+            to: new_health,
+        };
+        cluster.health = new_health;
+        Some(transition)
+    }
+
+    /// DNS weight of each cluster. Unhealthy clusters get no traffic, unless every cluster is unhealthy:
+    /// in this case, weights are left untouched as there is nowhere else to send the traffic.
+    pub fn dns_weights(&self) -> Vec<(Uuid, u32)> {
+        let all_unhealthy = self.clusters.iter().all(|c| c.health == ClusterHealth::Unhealthy);
+        self.clusters
+            .iter()
+            .map(|c| {
+                let weight = match c.health {
+                    ClusterHealth::Unhealthy if !all_unhealthy => 0,
+                    _ => c.endpoint.weight,
+                };
+                (c.endpoint.cluster_long_id, weight)
+            })
+            .collect()
+    }
+
+    /// Weighted records sharing the failover name, one per cluster identified by its id
+    pub fn weighted_records(&self) -> Vec<WeightedRecord> {
+        self.dns_weights()
+            .into_iter()
+            .zip(&self.clusters)
+            .map(|((cluster_long_id, weight), cluster)| WeightedRecord {
+                set_identifier: cluster_long_id.to_string(),
+                value: cluster.endpoint.record_value.clone(),
+                weight,
+            })
+            .collect()
+    }
+
+    /// Probe every cluster once and report health changes.
+    /// Returns the transitions, callers have to push the new `dns_weights()` to the DNS provider when not empty.
+    pub fn probe_all(&mut self, event_details: &EventDetails, logger: &dyn Logger) -> Vec<FailoverTransition> {
+        let probes: Vec<(Uuid, bool)> = self
+            .clusters
+            .iter()
+            .map(|c| {
+                (
+                    c.endpoint.cluster_long_id,
+                    tcp_probe(&c.endpoint.probe_address, self.probe_timeout),
+                )
+            })
+            .collect();
+
+        let transitions: Vec<FailoverTransition> = probes
+            .into_iter()
+            .filter_map(|(cluster_long_id, is_healthy)| self.record_probe(&cluster_long_id, is_healthy))
+            .collect();
+
+        for transition in &transitions {
+            let event = match transition.to {
+                ClusterHealth::Unhealthy => EngineEvent::Warning(
+                    event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "Cluster {} is unhealthy, shifting its traffic to the other clusters",
+                        transition.cluster_long_id
+                    )),
+                ),
+                ClusterHealth::Healthy => EngineEvent::Info(
+                    event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "Cluster {} recovered, sending traffic back to it",
+                        transition.cluster_long_id
+                    )),
+                ),
+            };
+            logger.log(event);
+        }
+
+        transitions
+    }
+}
+
+/// Probes the clusters until `should_stop` returns true, pushing the weights to the DNS provider on every health change.
+/// Weights are pushed once at start to reconcile the records with the current state, and pushed again on the next
+/// round when the DNS provider failed to apply them.
+pub fn run_traffic_failover(
+    failover: &mut TrafficFailover,
+    record_name: &str,
+    dns_provider: &dyn DnsProvider,
+    probe_interval: Duration,
+    event_details: &EventDetails,
+    logger: &dyn Logger,
+    should_stop: &dyn Fn() -> bool,
+) -> Result<(), Box<EngineError>> {
+    // records pushed at start must be valid, otherwise there is no point in monitoring the clusters
+    push_weighted_records(failover, record_name, dns_provider, event_details, logger)
+        .map_err(|err| Box::new(err.to_engine_error(event_details.clone())))?;
+
+    let mut are_records_outdated = false;
+    while !should_stop() {
+        let next_round = Instant::now() + probe_interval;
+        while !should_stop() && Instant::now() < next_round {
+            thread::sleep(Duration::from_millis(500).min(probe_interval));
+        }
+        if should_stop() {
+            break;
+        }
+
+        let transitions = failover.probe_all(event_details, logger);
+        if transitions.is_empty() && !are_records_outdated {
+            continue;
+        }
+
+        are_records_outdated = match push_weighted_records(failover, record_name, dns_provider, event_details, logger) {
+            Ok(()) => false,
+            Err(err) => {
+                logger.log(EngineEvent::Warning(
+                    event_details.clone(),
+                    EventMessage::new(
+                        format!("Cannot update the DNS weights of `{record_name}`, retrying on next probe"),
+                        Some(err.to_string()),
+                    ),
+                ));
+                true
+            }
+        };
+    }
+
+    Ok(())
+}
+
+fn push_weighted_records(
+    failover: &TrafficFailover,
+    record_name: &str,
+    dns_provider: &dyn DnsProvider,
+    event_details: &EventDetails,
+    logger: &dyn Logger,
+) -> Result<(), DnsProviderError> {
+    let records = failover.weighted_records();
+    dns_provider.set_weighted_records(record_name, DnsRecordType::Cname, &records)?;
+
+    let weights = records
+        .iter()
+        .map(|record| format!("{}={}", record.set_identifier, record.weight))
+        .collect::<Vec<_>>()
+        .join(", ");
+    logger.log(EngineEvent::Info(
+        event_details.clone(),
+        EventMessage::new_from_safe(format!("DNS weights of `{record_name}` updated: {weights}")),
+    ));
+
+    Ok(())
+}
+
+fn tcp_probe(address: &str, timeout: Duration) -> bool {
+    let addresses: Vec<SocketAddr> = match address.to_socket_addrs() {
+        Ok(addresses) => addresses.collect(),
+        Err(_) => return false,
+    };
+
+    addresses
+        .iter()
+        .any(|address| TcpStream::connect_timeout(address, timeout).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failover(weights: &[u32]) -> (TrafficFailover, Vec<Uuid>) {
+        let ids: Vec<Uuid> = weights.iter().map(|_| Uuid::new_v4()).collect();
+        let endpoints = ids
+            .iter()
+            .zip(weights)
+            .map(|(id, weight)| ClusterEndpoint {
+                cluster_long_id: *id,
+                probe_address: "127.0.0.1:443".to_string(),
+                record_value: format!("{id}.lb.example.com"),
+                weight: *weight,
+            })
+            .collect();
+
+        (TrafficFailover::new(endpoints).with_thresholds(2, 3), ids)
+    }
+
+    #[test]
+    fn test_cluster_marked_unhealthy_after_consecutive_failures() {
+        let (mut failover, ids) = failover(&[50, 50]);
+
+        assert_eq!(failover.record_probe(&ids[0], false), None);
+        // a success resets the failure count
+        assert_eq!(failover.record_probe(&ids[0], true), None);
+        assert_eq!(failover.record_probe(&ids[0], false), None);
+        assert_eq!(
+            failover.record_probe(&ids[0], false),
+            Some(FailoverTransition {
+                cluster_long_id: ids[0],
+                from: ClusterHealth::Healthy,
+                to: ClusterHealth::Unhealthy,
+            })
+        );
+        assert_eq!(failover.dns_weights(), vec![(ids[0], 0), (ids[1], 50)]);
+        assert_eq!(
+            failover.weighted_records(),
+            vec![
+                WeightedRecord {
+                    set_identifier: ids[0].to_string(),
+                    value: format!("{}.lb.example.com", ids[0]),
+                    weight: 0,
+                },
+                WeightedRecord {
+                    set_identifier: ids[1].to_string(),
+                    value: format!("{}.lb.example.com", ids[1]),
+                    weight: 50,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cluster_recovery_requires_more_successes() {
+        let (mut failover, ids) = failover(&[50, 50]);
+        failover.record_probe(&ids[0], false);
+        failover.record_probe(&ids[0], false);
+        assert_eq!(failover.health(&ids[0]), Some(ClusterHealth::Unhealthy));
+
+        assert_eq!(failover.record_probe(&ids[0], true), None);
+        assert_eq!(failover.record_probe(&ids[0], true), None);
+        assert!(failover.record_probe(&ids[0], true).is_some());
+        assert_eq!(failover.health(&ids[0]), Some(ClusterHealth::Healthy));
+        assert_eq!(failover.dns_weights(), vec![(ids[0], 50), (ids[1], 50)]);
+    }
+
+    #[test]
+    fn test_weights_kept_when_every_cluster_is_unhealthy() {
+        let (mut failover, ids) = failover(&[70, 30]);
+        for id in &ids {
+            failover.record_probe(id, false);
+            failover.record_probe(id, false);
+        }
+
+        assert_eq!(failover.dns_weights(), vec![(ids[0], 70), (ids[1], 30)]);
+        assert_eq!(failover.record_probe(&Uuid::new_v4(), false), None);
+    }
+}
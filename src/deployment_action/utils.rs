@@ -1,5 +1,6 @@
 use crate::build_platform::Image;
 use crate::cloud_provider::io::RegistryMirroringMode;
+use crate::cloud_provider::kubernetes::Kind;
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::command::CommandKiller;
 use crate::cmd::docker::ContainerImage;
@@ -7,6 +8,8 @@ use crate::container_registry::errors::ContainerRegistryError;
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
 use crate::errors::EngineError;
 use crate::events::EventDetails;
+use crate::io_models::context::Features;
+use crate::io_models::{ExternalSecretVariable, HpaCustomMetric, KedaTrigger};
 
 use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
 use crate::models::container::get_mirror_repository_name;
@@ -14,8 +17,12 @@ use crate::models::registry_image_source::RegistryImageSource;
 
 use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
 use k8s_openapi::api::batch::v1::CronJob;
+use k8s_openapi::api::core::v1::Pod;
 
-use kube::api::ListParams;
+use crate::errors::CommandError;
+use crate::kubers_utils::kube_get_resources_by_selector;
+use crate::runtime::block_on;
+use kube::api::{ListParams, LogParams};
 use kube::Api;
 use retry::delay::{Fibonacci, Fixed};
 use retry::OperationResult;
@@ -302,3 +309,104 @@ pub async fn get_last_deployed_image(
         }
     }
 }
+
+const JOB_LOGS_MAX_BYTES: i64 = 256 * 1024;
+
+/// Forward the output of every container of a job, init containers included, to the deployment logs
+pub fn forward_job_logs(
+    target: &DeploymentTarget,
+    job_name: &str,
+    logger: &EnvProgressLogger,
+) -> Result<(), CommandError> {
+    let namespace = target.environment.namespace();
+    let pods: Vec<Pod> = block_on(kube_get_resources_by_selector(
+        &target.kube,
+        namespace,
+        &format!("job-name={job_name}"),
+    ))?
+    .items;
+
+    let pod_api: Api<Pod> = Api::namespaced(target.kube.clone(), namespace);
+    for pod in &pods {
+        let (Some(pod_name), Some(spec)) = (pod.metadata.name.as_deref(), pod.spec.as_ref()) else {
+            continue;
+        };
+        let containers = spec.init_containers.iter().flatten().chain(spec.containers.iter());
+        for container in containers {
+            let log_params = LogParams {
+                container: Some(container.name.clone()),
+                limit_bytes: Some(JOB_LOGS_MAX_BYTES),
+                ..Default::default()
+            };
+            // containers following a failed init container never started, they have no logs
+            let Ok(logs) = block_on(pod_api.logs(pod_name, &log_params)) else {
+                continue;
+            };
+            for line in logs.lines().filter(|line| !line.trim().is_empty()) {
+                logger.info(format!("📜 [{}] {}", container.name, line));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Triggers are rendered as a KEDA ScaledObject, which is only reconciled when KEDA runs in the cluster.
+/// KEDA is only installed on EKS and Kapsule clusters, the autopilot of GKE does not allow its RBAC in `kube-system`.
+pub fn check_keda_triggers_supported(
+    triggers: &[KedaTrigger],
+    service_name: &str,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>> {
+    let keda_installed = matches!(target.kubernetes.kind(), Kind::Eks | Kind::ScwKapsule)
+        && target.kubernetes.advanced_settings().keda_enabled;
+    if triggers.is_empty() || keda_installed {
+        return Ok(());
+    }
+
+    Err(Box::new(EngineError::new_event_driven_autoscaling_not_enabled(
+        event_details.clone(),
+        service_name,
+    )))
+}
+
+pub fn check_external_secrets_supported(
+    external_secrets: &[ExternalSecretVariable],
+    service_name: &str,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>> {
+    let external_secrets_installed = matches!(target.kubernetes.kind(), Kind::Eks | Kind::ScwKapsule)
+        && target.kubernetes.advanced_settings().external_secrets_enabled;
+    if external_secrets.is_empty() || external_secrets_installed {
+        return Ok(());
+    }
+
+    Err(Box::new(EngineError::new_external_secrets_not_enabled(
+        event_details.clone(),
+        service_name,
+    )))
+}
+
+/// Custom metrics are served by the prometheus adapter, only installed along with the metrics history
+pub fn check_hpa_custom_metrics_supported(
+    custom_metrics: &[HpaCustomMetric],
+    service_name: &str,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), Box<EngineError>> {
+    let prometheus_adapter_installed = matches!(target.kubernetes.kind(), Kind::Eks | Kind::ScwKapsule)
+        && target
+            .kubernetes
+            .context()
+            .is_feature_enabled(&Features::MetricsHistory);
+    if custom_metrics.is_empty() || prometheus_adapter_installed {
+        return Ok(());
+    }
+
+    Err(Box::new(EngineError::new_custom_metrics_not_available(
+        event_details.clone(),
+        service_name,
+    )))
+}
@@ -0,0 +1,90 @@
+use crate::cloud_provider::helm::HelmChartNamespaces;
+use crate::cloud_provider::DeploymentTarget;
+use crate::errors::CommandError;
+use crate::io_models::WafMode;
+use crate::kubers_utils::kube_get_resources_by_selector;
+use crate::runtime::block_on;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::LogParams;
+use kube::Api;
+
+const NGINX_CONTROLLER_SELECTOR: &str = "app.kubernetes.io/name=ingress-nginx,app.kubernetes.io/component=controller";
+const MODSECURITY_ACCESS_DENIED: &str = "ModSecurity: Access denied";
+/// Blocked requests are counted over this period, the controller logs do not go further back anyway
+pub(super) const WAF_REPORT_PERIOD_SECONDS: i64 = 24 * 60 * 60;
+
+/// Number of requests to one of the hosts that ModSecurity denied, from the error log lines of the nginx controller,
+/// i.e: `ModSecurity: Access denied with code 403 (phase 2). [...] [hostname "app.example.com"] [uri "/"] [...]`
+pub(super) fn count_blocked_requests<'a>(log_lines: impl Iterator<Item = &'a str>, hosts: &[String]) -> usize {
+    let hostname_tags: Vec<String> = hosts.iter().map(|host| format!("[hostname \"{host}\"]")).collect();
+    log_lines
+        .filter(|line| line.contains(MODSECURITY_ACCESS_DENIED))
+        .filter(|line| hostname_tags.iter().any(|tag| line.contains(tag.as_str())))
+        .count()
+}
+
+/// Counts the requests to the hosts blocked by the web application firewall during the report period
+pub(super) fn blocked_requests(target: &DeploymentTarget, hosts: &[String]) -> Result<usize, CommandError> {
+    let namespace = HelmChartNamespaces::NginxIngress.to_string();
+    let pods = block_on(kube_get_resources_by_selector::<Pod>(
+        &target.kube,
+        &namespace,
+        NGINX_CONTROLLER_SELECTOR,
+    ))?
+    .items;
+
+    let pod_api: Api<Pod> = Api::namespaced(target.kube.clone(), &namespace);
+    let log_params = LogParams {
+        since_seconds: Some(WAF_REPORT_PERIOD_SECONDS),
+        ..Default::default()
+    };
+    let mut blocked = 0;
+    for pod_name in pods.iter().filter_map(|pod| pod.metadata.name.as_deref()) {
+        let logs = block_on(pod_api.logs(pod_name, &log_params)).map_err(|err| {
+            CommandError::new(
+                format!("Cannot read the logs of nginx controller pod {pod_name}"),
+                Some(err.to_string()),
+                None,
+            )
+        })?;
+        blocked += count_blocked_requests(logs.lines(), hosts);
+    }
+
+    Ok(blocked)
+}
+
+/// Mode of the firewall in front of the router, the one of the router taking precedence over the one of the cluster
+pub(super) fn effective_waf_mode(router_mode: Option<WafMode>, cluster_mode: WafMode) -> WafMode {
+    router_mode.unwrap_or(cluster_mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_blocked_requests() {
+        let logs = r#"2024/01/01 10:00:00 [error] 42#42: *1 [client 1.2.3.4] ModSecurity: Access denied with code 403 (phase 2). Matched "Operator `Ge' with parameter `5'" [hostname "app.example.com"] [uri "/admin"] [unique_id "abc"]
+2024/01/01 10:00:01 [warn] 42#42: *2 [client 1.2.3.4] ModSecurity: Warning. Matched "Operator `Rx'" [hostname "app.example.com"] [uri "/"] [unique_id "def"]
+2024/01/01 10:00:02 [error] 42#42: *3 [client 1.2.3.4] ModSecurity: Access denied with code 403 (phase 1). [hostname "other.example.com"] [uri "/"] [unique_id "ghi"]
+2024/01/01 10:00:03 [error] 42#42: *4 [client 1.2.3.4] ModSecurity: Access denied with code 403 (phase 2). [hostname "api.example.com"] [uri "/"] [unique_id "jkl"]
+1.2.3.4 - - [01/Jan/2024:10:00:04 +0000] "GET / HTTP/1.1" 200 12 "-" "curl/8.0" app.example.com"#;
+        let hosts = vec!["app.example.com".to_string(), "api.example.com".to_string()];
+
+        assert_eq!(count_blocked_requests(logs.lines(), &hosts), 2);
+        assert_eq!(count_blocked_requests(logs.lines(), &[]), 0);
+    }
+
+    #[test]
+    fn test_effective_waf_mode() {
+        assert_eq!(effective_waf_mode(None, WafMode::Blocking), WafMode::Blocking);
+        assert_eq!(
+            effective_waf_mode(Some(WafMode::Disabled), WafMode::Blocking),
+            WafMode::Disabled
+        );
+        assert_eq!(
+            effective_waf_mode(Some(WafMode::DetectionOnly), WafMode::Disabled),
+            WafMode::DetectionOnly
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::engine::InfrastructureContext;
+use crate::errors::EngineError;
+use crate::events::{EngineEvent, EventDetails, EventMessage, MessageCode};
+use crate::logger::Logger;
+use crate::runtime::block_on;
+use crate::utilities::create_kube_client;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Patch, PatchParams};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use uuid::Uuid;
+
+// Freezes live in the cluster itself, so they apply whatever engine instance handles the requests
+const DEPLOYMENT_FREEZE_CONFIGMAP_NAME: &str = "qovery-deployment-freeze";
+const DEPLOYMENT_FREEZE_CONFIGMAP_NAMESPACE: &str = "qovery";
+const DEPLOYMENT_FREEZE_FIELD_MANAGER: &str = "qovery-engine";
+
+/// What a freeze applies to, a cluster freeze covering all its environments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FreezeScope {
+    Cluster,
+    Environment(Uuid),
+}
+
+impl FreezeScope {
+    fn key(&self) -> String {
+        match self {
+            FreezeScope::Cluster => "cluster".to_string(),
+            FreezeScope::Environment(environment_id) => format!("environment.{environment_id}"),
+        }
+    }
+}
+
+impl fmt::Display for FreezeScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FreezeScope::Cluster => write!(f, "cluster"),
+            FreezeScope::Environment(environment_id) => write!(f, "environment {environment_id}"),
+        }
+    }
+}
+
+/// Freeze set during an incident or an audit, rejecting every mutating request of its scope
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DeploymentFreeze {
+    pub reason: String,
+    pub frozen_by: String,
+    pub frozen_at: DateTime<Utc>,
+}
+
+/// Break-glass override of a freeze, given with the request that must go through anyway
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DeploymentFreezeOverride {
+    pub reason: String,
+    pub requested_by: String,
+}
+
+fn find_freeze(data: &BTreeMap<String, String>, scopes: &[FreezeScope]) -> Option<(FreezeScope, DeploymentFreeze)> {
+    scopes.iter().find_map(|scope| {
+        let freeze = data.get(&scope.key())?;
+        match serde_json::from_str(freeze) {
+            Ok(freeze) => Some((*scope, freeze)),
+            Err(err) => {
+                // an unreadable entry still means someone wanted the scope frozen
+                warn!("Cannot parse deployment freeze of {}: {}", scope, err);
+                Some((
+                    *scope,
+                    DeploymentFreeze {
+                        reason: freeze.to_string(),
+                        frozen_by: "unknown".to_string(),
+                        frozen_at: Utc::now(),
+                    },
+                ))
+            }
+        }
+    })
+}
+
+/// First frozen scope among the given ones, if any
+pub async fn get_deployment_freeze(
+    kube: &kube::Client,
+    scopes: &[FreezeScope],
+) -> Result<Option<(FreezeScope, DeploymentFreeze)>, kube::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(kube.clone(), DEPLOYMENT_FREEZE_CONFIGMAP_NAMESPACE);
+    let data = config_maps
+        .get_opt(DEPLOYMENT_FREEZE_CONFIGMAP_NAME)
+        .await?
+        .and_then(|config_map| config_map.data)
+        .unwrap_or_default();
+
+    Ok(find_freeze(&data, scopes))
+}
+
+pub async fn freeze_deployments(
+    kube: &kube::Client,
+    scope: FreezeScope,
+    freeze: &DeploymentFreeze,
+) -> Result<(), kube::Error> {
+    let freeze = serde_json::to_string(freeze).map_err(kube::Error::SerdeError)?;
+    patch_freezes(kube, serde_json::json!({ scope.key(): freeze })).await
+}
+
+pub async fn unfreeze_deployments(kube: &kube::Client, scope: FreezeScope) -> Result<(), kube::Error> {
+    patch_freezes(kube, serde_json::json!({ scope.key(): null })).await
+}
+
+async fn patch_freezes(kube: &kube::Client, data: serde_json::Value) -> Result<(), kube::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(kube.clone(), DEPLOYMENT_FREEZE_CONFIGMAP_NAMESPACE);
+    let patch = serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "ConfigMap",
+        "metadata": { "name": DEPLOYMENT_FREEZE_CONFIGMAP_NAME },
+        "data": data,
+    });
+
+    // server side apply creates the config map on the first freeze
+    config_maps
+        .patch(
+            DEPLOYMENT_FREEZE_CONFIGMAP_NAME,
+            &PatchParams::apply(DEPLOYMENT_FREEZE_FIELD_MANAGER),
+            &Patch::Apply(&patch),
+        )
+        .await
+        .map(|_| ())
+}
+
+/// Whether the cluster API could not be reached at all, as opposed to answering with an error
+fn is_cluster_unreachable(err: &kube::Error) -> bool {
+    !matches!(err, kube::Error::Api(_) | kube::Error::SerdeError(_))
+}
+
+/// Rejects the request when one of the scopes is frozen, unless it comes with a break-glass override, which is then
+/// recorded in the events of the request. A freeze which cannot be read because the cluster API answered with an
+/// error rejects the request too. When the cluster cannot be reached at all, the request is only let through if
+/// `allow_unreachable_cluster`: a cluster being created cannot be reached nor frozen yet, and a broken cluster must
+/// still be paused or deleted.
+pub fn enforce_deployment_freeze(
+    infra_ctx: &InfrastructureContext,
+    scopes: &[FreezeScope],
+    freeze_override: Option<&DeploymentFreezeOverride>,
+    allow_unreachable_cluster: bool,
+    event_details: EventDetails,
+    logger: &dyn Logger,
+) -> Result<(), Box<EngineError>> {
+    let kubeconfig_path = infra_ctx.kubernetes().kubeconfig_local_file_path();
+    let kube_credentials: Vec<(String, String)> = infra_ctx
+        .cloud_provider()
+        .credentials_environment_variables()
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    let freeze = block_on(async {
+        let kube = create_kube_client(&kubeconfig_path, kube_credentials.as_slice()).await?;
+        get_deployment_freeze(&kube, scopes).await
+    });
+    let (scope, freeze) = match freeze {
+        Ok(Some(freeze)) => freeze,
+        Ok(None) => return Ok(()),
+        Err(err) if allow_unreachable_cluster && is_cluster_unreachable(&err) => {
+            logger.log(EngineEvent::Warning(
+                event_details,
+                EventMessage::new(
+                    "Cannot reach the cluster to check its deployment freeze, it is not checked for this action".to_string(),
+                    Some(err.to_string()),
+                ),
+            ));
+            return Ok(());
+        }
+        Err(err) => {
+            return Err(Box::new(EngineError::new_cannot_check_deployment_freeze(
+                event_details,
+                err.into(),
+            )))
+        }
+    };
+
+    let Some(freeze_override) = freeze_override else {
+        return Err(Box::new(EngineError::new_deployment_frozen(
+            event_details,
+            &scope.to_string(),
+            &freeze,
+        )));
+    };
+
+    logger.log(EngineEvent::Warning(
+        event_details,
+        EventMessage::new_from_code(
+            MessageCode::DeploymentFreezeOverridden,
+            &[
+                ("scope", scope.to_string()),
+                ("freeze_reason", freeze.reason),
+                ("frozen_by", freeze.frozen_by),
+                ("requested_by", freeze_override.requested_by.to_string()),
+                ("reason", freeze_override.reason.to_string()),
+            ],
+            None,
+        ),
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_freeze() {
+        let environment_id = Uuid::new_v4();
+        let freeze = DeploymentFreeze {
+            reason: "incident #42".to_string(),
+            frozen_by: "oncall@example.com".to_string(),
+            frozen_at: Utc::now(),
+        };
+        let data = BTreeMap::from([(
+            FreezeScope::Environment(environment_id).key(),
+            serde_json::to_string(&freeze).unwrap(),
+        )]);
+
+        assert_eq!(
+            find_freeze(&data, &[FreezeScope::Cluster, FreezeScope::Environment(environment_id)]),
+            Some((FreezeScope::Environment(environment_id), freeze))
+        );
+        assert_eq!(
+            find_freeze(&data, &[FreezeScope::Cluster, FreezeScope::Environment(Uuid::new_v4())]),
+            None
+        );
+
+        let data = BTreeMap::from([(FreezeScope::Cluster.key(), "audit in progress".to_string())]);
+        let (scope, freeze) = find_freeze(&data, &[FreezeScope::Cluster]).unwrap();
+        assert_eq!(scope, FreezeScope::Cluster);
+        assert_eq!(freeze.reason, "audit in progress");
+    }
+
+    #[test]
+    fn test_is_cluster_unreachable() {
+        let forbidden = kube::Error::Api(kube::error::ErrorResponse {
+            status: "Failure".to_string(),
+            message: "configmaps is forbidden".to_string(),
+            reason: "Forbidden".to_string(),
+            code: 403,
+        });
+        let connection_refused = kube::Error::Service("connection refused".into());
+
+        assert!(!is_cluster_unreachable(&forbidden));
+        assert!(is_cluster_unreachable(&connection_refused));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use std::fmt;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Deployment stages custom logic can be run around
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeploymentHookStage {
+    Build,
+    Terraform,
+    Helm,
+    Readiness,
+}
+
+impl fmt::Display for DeploymentHookStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeploymentHookStage::Build => write!(f, "build"),
+            DeploymentHookStage::Terraform => write!(f, "terraform"),
+            DeploymentHookStage::Helm => write!(f, "helm"),
+            DeploymentHookStage::Readiness => write!(f, "readiness"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeploymentHookPhase {
+    Before,
+    After,
+}
+
+impl fmt::Display for DeploymentHookPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeploymentHookPhase::Before => write!(f, "before"),
+            DeploymentHookPhase::After => write!(f, "after"),
+        }
+    }
+}
+
+/// Reason given by a hook to stop the deployment, shown to the user
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("{message}")]
+pub struct DeploymentHookError {
+    pub message: String,
+}
+
+impl DeploymentHookError {
+    pub fn new(message: impl Into<String>) -> Self {
+        DeploymentHookError {
+            message: message.into(),
+        }
+    }
+}
+
+/// Custom logic of the integrators embedding the engine, i.e: checking a change-management ticket is approved
+/// before applying terraform. Event details identify the organization, cluster, execution and service concerned.
+pub trait DeploymentHook: Send + Sync {
+    fn name(&self) -> &str;
+    /// Failing stops the deployment before the stage starts
+    fn before(&self, _stage: DeploymentHookStage, _event_details: &EventDetails) -> Result<(), DeploymentHookError> {
+        Ok(())
+    }
+    /// Called whatever the outcome of the stage, failing turns a successful stage into a failed one
+    fn after(
+        &self,
+        _stage: DeploymentHookStage,
+        _event_details: &EventDetails,
+        _stage_succeeded: bool,
+    ) -> Result<(), DeploymentHookError> {
+        Ok(())
+    }
+}
+
+/// Hooks registered by the integrator, run in their registration order
+#[derive(Clone, Default)]
+pub struct DeploymentHooks {
+    hooks: Vec<Arc<dyn DeploymentHook>>,
+}
+
+impl DeploymentHooks {
+    pub fn register(&mut self, hook: Arc<dyn DeploymentHook>) {
+        self.hooks.push(hook);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    fn run_phase(
+        &self,
+        stage: DeploymentHookStage,
+        phase: DeploymentHookPhase,
+        event_details: &EventDetails,
+        stage_succeeded: bool,
+    ) -> Result<(), Box<EngineError>> {
+        for hook in &self.hooks {
+            let ret = match phase {
+                DeploymentHookPhase::Before => hook.before(stage, event_details),
+                DeploymentHookPhase::After => hook.after(stage, event_details, stage_succeeded),
+            };
+
+            ret.map_err(|err| {
+                Box::new(EngineError::new_deployment_hook_failed(
+                    event_details.clone(),
+                    hook.name(),
+                    stage,
+                    phase,
+                    err,
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the stage between the hooks, the error of a failed stage prevailing over the one of an after hook
+    pub fn run_stage<T>(
+        &self,
+        stage: DeploymentHookStage,
+        event_details: &EventDetails,
+        run: impl FnOnce() -> Result<T, Box<EngineError>>,
+    ) -> Result<T, Box<EngineError>> {
+        self.run_phase(stage, DeploymentHookPhase::Before, event_details, false)?;
+        let ret = run();
+        let after_ret = self.run_phase(stage, DeploymentHookPhase::After, event_details, ret.is_ok());
+
+        let value = ret?;
+        after_ret?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{InfrastructureStep, Stage, Transmitter};
+    use crate::io_models::QoveryIdentifier;
+    use std::sync::Mutex;
+
+    struct RecordingHook {
+        calls: Mutex<Vec<String>>,
+        rejected_stage: Option<DeploymentHookStage>,
+    }
+
+    impl DeploymentHook for RecordingHook {
+        fn name(&self) -> &str {
+            "change-management"
+        }
+
+        fn before(&self, stage: DeploymentHookStage, _event_details: &EventDetails) -> Result<(), DeploymentHookError> {
+            self.calls.lock().unwrap().push(format!("before {stage}"));
+            match self.rejected_stage {
+                Some(rejected_stage) if rejected_stage == stage => Err(DeploymentHookError::new("ticket not approved")),
+                _ => Ok(()),
+            }
+        }
+
+        fn after(
+            &self,
+            stage: DeploymentHookStage,
+            _event_details: &EventDetails,
+            stage_succeeded: bool,
+        ) -> Result<(), DeploymentHookError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("after {stage} {stage_succeeded}"));
+            Ok(())
+        }
+    }
+
+    fn event_details() -> EventDetails {
+        EventDetails::new(
+            None,
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random(),
+            "execution".to_string(),
+            Stage::Infrastructure(InfrastructureStep::Create),
+            Transmitter::TaskManager(uuid::Uuid::new_v4(), "engine".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_run_stage_between_hooks() {
+        let hook = Arc::new(RecordingHook {
+            calls: Mutex::new(vec![]),
+            rejected_stage: Some(DeploymentHookStage::Terraform),
+        });
+        let mut hooks = DeploymentHooks::default();
+        hooks.register(hook.clone());
+
+        assert_eq!(hooks.run_stage(DeploymentHookStage::Helm, &event_details(), || Ok(42)), Ok(42));
+
+        let mut stage_ran = false;
+        let err = hooks
+            .run_stage(DeploymentHookStage::Terraform, &event_details(), || {
+                stage_ran = true;
+                Ok(())
+            })
+            .unwrap_err();
+        assert!(!stage_ran);
+        assert!(err.user_log_message().contains("ticket not approved"));
+
+        assert_eq!(
+            *hook.calls.lock().unwrap(),
+            vec!["before helm", "after helm true", "before terraform"]
+        );
+    }
+}
@@ -156,6 +156,18 @@ impl<T: Send + Sync> DeploymentReporter for JobDeploymentReporter<T> {
             return;
         }
 
+        if self.action == Action::TriggerNow {
+            if let JobType::CronJob(schedule) = &self.job_type {
+                self.logger.send_progress(format!(
+                    "🚀 Manual run of cronjob with schedule `{}` at tag {} is starting with a timeout/max duration of {}",
+                    schedule,
+                    self.tag,
+                    self.max_duration_human_str()
+                ));
+                return;
+            }
+        }
+
         // Normal flow, checking if the job should be trigerred on this event
         match &self.job_type {
             JobType::Job(trigger_on_action) => {
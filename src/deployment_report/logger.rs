@@ -4,7 +4,7 @@ use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, St
 use crate::logger::Logger;
 use std::sync::Arc;
 
-use crate::events::EnvironmentStep::{DatabaseOutput, JobOutput};
+use crate::events::EnvironmentStep::{DatabaseOutput, JobOutput, TerraformOutput};
 #[cfg(feature = "env-logger-check")]
 use std::sync::atomic::AtomicUsize;
 #[cfg(feature = "env-logger-check")]
@@ -14,6 +14,7 @@ pub struct EnvLogger {
     logger: Arc<Box<dyn Logger>>,
     event_details_progress: EventDetails,
     event_details_success: EventDetails,
+    prefix: String,
     #[cfg(feature = "env-logger-check")]
     state: AtomicUsize,
 }
@@ -35,12 +36,28 @@ impl EnvLogger {
             logger,
             event_details_progress,
             event_details_success,
+            prefix: String::new(),
             #[cfg(feature = "env-logger-check")]
             state: AtomicUsize::new(LoggerState::Progress as usize),
         }
     }
 
+    /// Prefixes the progress and warning messages, to tell apart the services logging at the same time
+    pub fn with_prefix(mut self, prefix: String) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Line prefixed like the progress messages, for the ones batched before being sent
+    pub fn prefixed(&self, line: &str) -> String {
+        format!("{}{line}", self.prefix)
+    }
+
     pub fn send_progress(&self, msg: String) {
+        self.send_progress_message(EventMessage::new_from_safe(self.prefixed(&msg)));
+    }
+
+    pub fn send_progress_message(&self, msg: EventMessage) {
         #[cfg(feature = "env-logger-check")]
         {
             assert!(
@@ -49,10 +66,8 @@ impl EnvLogger {
             );
         }
 
-        self.logger.log(EngineEvent::Info(
-            self.event_details_progress.clone(),
-            EventMessage::new_from_safe(msg),
-        ));
+        self.logger
+            .log(EngineEvent::Info(self.event_details_progress.clone(), msg));
     }
 
     pub fn send_recap(&self, msg: String) {
@@ -81,7 +96,7 @@ impl EnvLogger {
 
         self.logger.log(EngineEvent::Warning(
             self.event_details_progress.clone(),
-            EventMessage::new_from_safe(msg),
+            EventMessage::new_from_safe(self.prefixed(&msg)),
         ));
     }
 
@@ -98,6 +113,10 @@ impl EnvLogger {
     }
 
     pub fn send_success(&self, msg: String) {
+        self.send_success_message(EventMessage::new_from_safe(msg));
+    }
+
+    pub fn send_success_message(&self, msg: EventMessage) {
         #[cfg(feature = "env-logger-check")]
         {
             assert!(
@@ -107,10 +126,8 @@ impl EnvLogger {
             self.state.store(LoggerState::Success as usize, Ordering::Release);
         }
 
-        self.logger.log(EngineEvent::Info(
-            self.event_details_success.clone(),
-            EventMessage::new_from_safe(msg),
-        ));
+        self.logger
+            .log(EngineEvent::Info(self.event_details_success.clone(), msg));
     }
 
     pub fn send_error(&self, err: EngineError) {
@@ -136,6 +153,10 @@ impl EnvLogger {
         self.send_core_configuration(safe_message, json, DatabaseOutput);
     }
 
+    pub fn send_core_configuration_for_terraform(&self, safe_message: String, json: String) {
+        self.send_core_configuration(safe_message, json, TerraformOutput);
+    }
+
     fn send_core_configuration(&self, safe_message: String, json: String, step: EnvironmentStep) {
         #[cfg(feature = "env-logger-check")]
         {
@@ -180,6 +201,10 @@ impl<'a> EnvProgressLogger<'a> {
     pub fn core_configuration_for_database(&self, msg: String, json: String) {
         self.logger.send_core_configuration_for_database(msg, json)
     }
+
+    pub fn core_configuration_for_terraform(&self, msg: String, json: String) {
+        self.logger.send_core_configuration_for_terraform(msg, json)
+    }
 }
 
 pub struct EnvSuccessLogger<'a> {
@@ -194,6 +219,10 @@ impl<'a> EnvSuccessLogger<'a> {
     pub fn send_success(&self, msg: String) {
         self.logger.send_success(msg);
     }
+
+    pub fn send_success_message(&self, msg: EventMessage) {
+        self.logger.send_success_message(msg);
+    }
 }
 
 #[cfg(feature = "env-logger-check")]
@@ -0,0 +1,99 @@
+use crate::cloud_provider::service::Action;
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_report::logger::EnvLogger;
+use crate::deployment_report::DeploymentReporter;
+use crate::errors::EngineError;
+use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
+use crate::models::terraform_service::TerraformServiceTrait;
+use crate::utilities::to_short_id;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct TerraformServiceDeploymentReporter {
+    long_id: Uuid,
+    logger: EnvLogger,
+    metrics_registry: Arc<dyn MetricsRegistry>,
+    action: Action,
+}
+
+impl TerraformServiceDeploymentReporter {
+    pub fn new(
+        terraform_service: &impl TerraformServiceTrait,
+        deployment_target: &DeploymentTarget,
+        action: Action,
+    ) -> Self {
+        Self {
+            long_id: *terraform_service.long_id(),
+            logger: deployment_target.env_logger(terraform_service, action.to_environment_step()),
+            metrics_registry: deployment_target.metrics_registry.clone(),
+            action,
+        }
+    }
+
+    pub(crate) fn stop_record(&self, step_status: StepStatus) {
+        self.metrics_registry
+            .stop_record(self.long_id, StepName::Deployment, step_status.clone());
+        self.metrics_registry
+            .stop_record(self.long_id, StepName::Total, step_status);
+    }
+}
+
+impl DeploymentReporter for TerraformServiceDeploymentReporter {
+    type DeploymentResult = ();
+    type DeploymentState = ();
+    type Logger = EnvLogger;
+
+    fn logger(&self) -> &Self::Logger {
+        &self.logger
+    }
+
+    fn new_state(&self) -> Self::DeploymentState {}
+
+    fn deployment_before_start(&self, _: &mut Self::DeploymentState) {
+        self.metrics_registry
+            .start_record(self.long_id, StepLabel::Service, StepName::Deployment);
+        self.logger.send_progress(format!(
+            "🚀 {} of terraform service `{}` is starting",
+            self.action,
+            to_short_id(&self.long_id)
+        ));
+    }
+
+    fn deployment_in_progress(&self, _: &mut Self::DeploymentState) {
+        // Terraform only reports once a command is done
+    }
+
+    fn deployment_terminated(
+        &self,
+        result: &Result<Self::DeploymentResult, Box<EngineError>>,
+        _: &mut Self::DeploymentState,
+    ) {
+        let error = match result {
+            Ok(_) => {
+                self.stop_record(StepStatus::Success);
+                self.logger
+                    .send_success(format!("✅ {} of terraform service succeeded", self.action));
+                return;
+            }
+            Err(err) => err,
+        };
+
+        if error.tag().is_cancel() {
+            self.stop_record(StepStatus::Cancel);
+            self.logger.send_error(EngineError::new_engine_error(
+                *error.clone(),
+                format!("🚫 {} has been cancelled", self.action),
+                None,
+            ));
+            return;
+        }
+
+        self.stop_record(StepStatus::Error);
+        self.logger.send_error(*error.clone());
+        self.logger.send_error(EngineError::new_engine_error(
+            *error.clone(),
+            format!("❌ {} of terraform service failed !", self.action),
+            None,
+        ));
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::proto::rr::RecordType;
+use trust_dns_resolver::Resolver;
+
+use crate::dns_provider::dns_check::DnsResolver;
+
+/// Issuer domain of Let's Encrypt, the CA of the certificates generated by cert-manager
+pub const LETS_ENCRYPT_CAA_ISSUER: &str = "letsencrypt.org";
+const CAA_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+// https://www.rfc-editor.org/rfc/rfc8659#section-4.1, CAs must refuse to issue on a critical tag they don't know
+const CAA_CRITICAL_FLAG: u8 = 128;
+const KNOWN_CAA_TAGS: [&str; 3] = ["issue", "issuewild", "iodef"];
+
+/// Certification Authority Authorization record, restricting the CAs allowed to issue certificates for a domain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaaRecord {
+    pub flags: u8,
+    pub tag: String,
+    pub value: String,
+}
+
+impl CaaRecord {
+    pub fn new(tag: &str, value: &str) -> Self {
+        CaaRecord {
+            flags: 0,
+            tag: tag.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tag.eq_ignore_ascii_case(tag)
+    }
+
+    /// CA named by an issue or issuewild record, the parameters following it don't restrict the CA itself
+    fn issuer(&self) -> &str {
+        self.value.split(';').next().unwrap_or_default().trim()
+    }
+}
+
+impl FromStr for CaaRecord {
+    type Err = String;
+
+    /// Parses the zone file format of a record, i.e: `0 issue "letsencrypt.org"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_record = || format!("Invalid CAA record `{s}`");
+        let (flags, tag_and_value) = s.trim().split_once(char::is_whitespace).ok_or_else(invalid_record)?;
+        let (tag, value) = tag_and_value
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .ok_or_else(invalid_record)?;
+
+        Ok(CaaRecord {
+            flags: flags.parse().map_err(|_| invalid_record())?,
+            tag: tag.to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+        })
+    }
+}
+
+impl fmt::Display for CaaRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} \"{}\"", self.flags, self.tag, self.value)
+    }
+}
+
+/// CAA records applying to a domain, held by the domain itself or its closest ancestor having some
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaaRecordSet {
+    pub name: String,
+    pub records: Vec<CaaRecord>,
+}
+
+impl CaaRecordSet {
+    /// Whether the issuer can deliver a certificate for the domain, wildcard ones being ruled by issuewild records
+    pub fn allows_issuer(&self, issuer: &str, wildcard: bool) -> bool {
+        if self.records.iter().any(|record| {
+            record.flags & CAA_CRITICAL_FLAG != 0 && !KNOWN_CAA_TAGS.iter().any(|tag| record.has_tag(tag))
+        }) {
+            return false;
+        }
+
+        let issue_tag = self.issue_tag(wildcard);
+        let mut issue_records = self
+            .records
+            .iter()
+            .filter(|record| record.has_tag(issue_tag))
+            .peekable();
+
+        // without any issue record, every CA is allowed
+        issue_records.peek().is_none() || issue_records.any(|record| record.issuer().eq_ignore_ascii_case(issuer))
+    }
+
+    /// Records of the set with the issuer allowed in addition to the CAs already allowed
+    pub fn with_issuer_allowed(&self, issuer: &str, wildcard: bool) -> Vec<CaaRecord> {
+        let mut records = self.records.clone();
+        records.push(CaaRecord::new(self.issue_tag(wildcard), issuer));
+
+        records
+    }
+
+    fn issue_tag(&self, wildcard: bool) -> &'static str {
+        // issuewild records replace the issue ones for wildcard certificates, as soon as there is one
+        match wildcard && self.records.iter().any(|record| record.has_tag("issuewild")) {
+            true => "issuewild",
+            false => "issue",
+        }
+    }
+}
+
+trait CaaLookup {
+    fn caa(&self, name: &str) -> Result<Vec<CaaRecord>, String>;
+}
+
+impl CaaLookup for Resolver {
+    fn caa(&self, name: &str) -> Result<Vec<CaaRecord>, String> {
+        match self.lookup(name, RecordType::CAA) {
+            Ok(lookup) => Ok(lookup
+                .iter()
+                .filter_map(|rdata| rdata.to_string().parse().ok())
+                .collect()),
+            Err(err) if matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(vec![]),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+fn find_caa_records(lookup: &impl CaaLookup, domain: &str) -> Result<Option<CaaRecordSet>, String> {
+    let domain = domain.trim_start_matches("*.").trim_end_matches('.').to_lowercase();
+    let labels: Vec<&str> = domain.split('.').collect();
+
+    // the top level domain is left out, registries don't restrict the CAs of their whole TLD
+    for index in 0..labels.len().saturating_sub(1) {
+        let name = labels[index..].join(".");
+        let records = lookup.caa(&name)?;
+        if !records.is_empty() {
+            return Ok(Some(CaaRecordSet { name, records }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Looks up the CAA records a CA would check before issuing a certificate for the domain, climbing up to its
+/// closest ancestor having some. None means every CA is allowed.
+pub fn lookup_caa_records(resolver: &DnsResolver, domain: &str) -> Result<Option<CaaRecordSet>, String> {
+    let resolver = resolver
+        .to_resolver(CAA_QUERY_TIMEOUT)
+        .map_err(|err| format!("Cannot create resolver {resolver}: {err}"))?;
+
+    find_caa_records(&resolver, domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct FakeLookup(HashMap<String, Vec<CaaRecord>>);
+
+    impl CaaLookup for FakeLookup {
+        fn caa(&self, name: &str) -> Result<Vec<CaaRecord>, String> {
+            Ok(self.0.get(name).cloned().unwrap_or_default())
+        }
+    }
+
+    fn record_set(records: &[&str]) -> CaaRecordSet {
+        CaaRecordSet {
+            name: "example.com".to_string(),
+            records: records.iter().map(|record| record.parse().unwrap()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_caa_record() {
+        let record: CaaRecord = "0 issue \"letsencrypt.org; validationmethods=http-01\""
+            .parse()
+            .unwrap();
+        assert_eq!(record.flags, 0);
+        assert_eq!(record.tag, "issue");
+        assert_eq!(record.issuer(), "letsencrypt.org");
+        assert_eq!(
+            CaaRecord::new("issuewild", "letsencrypt.org").to_string(),
+            "0 issuewild \"letsencrypt.org\""
+        );
+        assert!("issue letsencrypt.org".parse::<CaaRecord>().is_err());
+    }
+
+    #[test]
+    fn test_allows_issuer() {
+        assert!(record_set(&["0 iodef \"mailto:security@example.com\""]).allows_issuer(LETS_ENCRYPT_CAA_ISSUER, false));
+        assert!(record_set(&["0 issue \"pki.goog\"", "0 issue \"letsencrypt.org\""])
+            .allows_issuer(LETS_ENCRYPT_CAA_ISSUER, false));
+        assert!(!record_set(&["0 issue \"pki.goog\""]).allows_issuer(LETS_ENCRYPT_CAA_ISSUER, false));
+        // issuewild records only rule wildcard certificates
+        let wildcard_restricted = record_set(&["0 issue \"letsencrypt.org\"", "0 issuewild \";\""]);
+        assert!(wildcard_restricted.allows_issuer(LETS_ENCRYPT_CAA_ISSUER, false));
+        assert!(!wildcard_restricted.allows_issuer(LETS_ENCRYPT_CAA_ISSUER, true));
+        assert!(!record_set(&["128 tbs \"unknown\""]).allows_issuer(LETS_ENCRYPT_CAA_ISSUER, false));
+    }
+
+    #[test]
+    fn test_with_issuer_allowed() {
+        let records = record_set(&["0 issue \"pki.goog\"", "0 issuewild \"pki.goog\""]);
+
+        let allowed = CaaRecordSet {
+            name: records.name.clone(),
+            records: records.with_issuer_allowed(LETS_ENCRYPT_CAA_ISSUER, true),
+        };
+        assert!(allowed.allows_issuer(LETS_ENCRYPT_CAA_ISSUER, true));
+        assert!(!allowed.allows_issuer(LETS_ENCRYPT_CAA_ISSUER, false));
+    }
+
+    #[test]
+    fn test_find_caa_records() {
+        let lookup = FakeLookup(HashMap::from([(
+            "example.com".to_string(),
+            vec![CaaRecord::new("issue", "pki.goog")],
+        )]));
+
+        let record_set = find_caa_records(&lookup, "*.App.example.com.").unwrap().unwrap();
+        assert_eq!(record_set.name, "example.com");
+        assert_eq!(record_set.records, vec![CaaRecord::new("issue", "pki.goog")]);
+        assert_eq!(find_caa_records(&lookup, "example.org").unwrap(), None);
+    }
+}
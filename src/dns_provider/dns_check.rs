@@ -0,0 +1,448 @@
+#![allow(clippy::field_reassign_with_default)]
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr};
+use std::thread;
+use std::time::{Duration, Instant};
+use trust_dns_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::ResolveErrorKind;
+use trust_dns_resolver::proto::rr::{RData, RecordType};
+use trust_dns_resolver::Resolver;
+
+use crate::dns_provider::DnsProvider;
+use crate::events::{EventMessage, MessageCode};
+
+const PUBLIC_RESOLVERS: [(&str, Ipv4Addr); 2] = [
+    ("cloudflare", Ipv4Addr::new(1, 1, 1, 1)),
+    ("google", Ipv4Addr::new(8, 8, 8, 8)),
+];
+// resolvers themselves give up after a handful of hops, a longer chain is most likely a misconfiguration
+const MAX_CNAME_CHAIN_LENGTH: usize = 8;
+
+/// Recursive resolver queried to check the propagation of a domain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsResolver {
+    pub name: String,
+    pub ip: IpAddr,
+}
+
+impl DnsResolver {
+    pub fn new(name: &str, ip: IpAddr) -> Self {
+        DnsResolver {
+            name: name.to_string(),
+            ip,
+        }
+    }
+
+    /// Resolvers of the DNS provider, followed by the public ones most users end up querying
+    pub fn defaults_for(dns_provider: &dyn DnsProvider) -> Vec<DnsResolver> {
+        let mut resolvers: Vec<DnsResolver> = dns_provider
+            .resolvers()
+            .into_iter()
+            .map(|ip| DnsResolver::new(dns_provider.provider_name(), IpAddr::V4(ip)))
+            .collect();
+
+        for (name, ip) in PUBLIC_RESOLVERS {
+            if !resolvers.iter().any(|resolver| resolver.ip == ip) {
+                resolvers.push(DnsResolver::new(name, IpAddr::V4(ip)));
+            }
+        }
+
+        resolvers
+    }
+
+    pub(super) fn to_resolver(&self, query_timeout: Duration) -> Result<Resolver, std::io::Error> {
+        let mut resolver_options = ResolverOpts::default();
+        // each resolver must answer by itself, without any cache or host file hiding a missing record
+        resolver_options.cache_size = 0;
+        resolver_options.use_hosts_file = false;
+        resolver_options.timeout = query_timeout;
+        resolver_options.attempts = 1;
+
+        Resolver::new(
+            ResolverConfig::from_parts(None, vec![], NameServerConfigGroup::from_ips_clear(&[self.ip], 53, true)),
+            resolver_options,
+        )
+    }
+}
+
+impl fmt::Display for DnsResolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.ip)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DnsCheckConfig {
+    pub resolvers: Vec<DnsResolver>,
+    /// Time after which the check gives up on the resolvers still not returning the expected records
+    pub timeout: Duration,
+    /// Delay before querying again the lagging resolvers, doubled after each attempt up to `max_backoff`
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Timeout of a single DNS query
+    pub query_timeout: Duration,
+}
+
+impl DnsCheckConfig {
+    pub fn new(resolvers: Vec<DnsResolver>) -> Self {
+        DnsCheckConfig {
+            resolvers,
+            timeout: Duration::from_secs(60 * 5),
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(30),
+            query_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DnsExpectation {
+    /// Domain resolves to at least one IP address, whatever the CNAME records in between
+    Ip,
+    /// Domain CNAME chain goes through the target domain
+    Cname(String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DnsResolution {
+    /// CNAME records followed from the domain, in order
+    pub cname_chain: Vec<String>,
+    pub ips: Vec<IpAddr>,
+}
+
+impl DnsResolution {
+    fn satisfies(&self, expectation: &DnsExpectation) -> bool {
+        match expectation {
+            DnsExpectation::Ip => !self.ips.is_empty(),
+            DnsExpectation::Cname(target) => self.cname_chain.contains(&normalize(target)),
+        }
+    }
+}
+
+impl fmt::Display for DnsResolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut hops = self.cname_chain.clone();
+        if !self.ips.is_empty() {
+            hops.push(
+                self.ips
+                    .iter()
+                    .map(|ip| ip.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            );
+        }
+
+        match hops.is_empty() {
+            true => write!(f, "no record"),
+            false => write!(f, "{}", hops.join(" -> ")),
+        }
+    }
+}
+
+/// Latest answer of a resolver for the checked domain
+#[derive(Clone, Debug)]
+pub struct ResolverCheck {
+    pub resolver: DnsResolver,
+    pub resolution: Result<DnsResolution, String>,
+    pub propagated: bool,
+}
+
+impl ResolverCheck {
+    fn new(resolver: DnsResolver, expectation: &DnsExpectation, resolution: Result<DnsResolution, String>) -> Self {
+        let propagated = resolution
+            .as_ref()
+            .map(|resolution| resolution.satisfies(expectation))
+            .unwrap_or(false);
+
+        ResolverCheck {
+            resolver,
+            resolution,
+            propagated,
+        }
+    }
+
+    pub fn to_event_message(&self, domain: &str) -> EventMessage {
+        let resolver = ("resolver", self.resolver.to_string());
+        match (&self.resolution, self.propagated) {
+            (Ok(resolution), true) => EventMessage::new_from_code(
+                MessageCode::DnsResolverPropagated,
+                &[
+                    ("domain", domain.to_string()),
+                    resolver,
+                    ("resolution", resolution.to_string()),
+                ],
+                None,
+            ),
+            (Ok(resolution), false) => EventMessage::new_from_code(
+                MessageCode::DnsResolverNotPropagated,
+                &[
+                    ("domain", domain.to_string()),
+                    resolver,
+                    ("reason", format!("got {resolution}")),
+                ],
+                None,
+            ),
+            (Err(err), _) => EventMessage::new_from_code(
+                MessageCode::DnsResolverNotPropagated,
+                &[("domain", domain.to_string()), resolver, ("reason", err.to_string())],
+                None,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DnsCheckReport {
+    pub domain: String,
+    pub checks: Vec<ResolverCheck>,
+}
+
+impl DnsCheckReport {
+    pub fn propagated_checks(&self) -> impl Iterator<Item = &ResolverCheck> {
+        self.checks.iter().filter(|check| check.propagated)
+    }
+
+    pub fn is_fully_propagated(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|check| check.propagated)
+    }
+}
+
+/// Queries needed to follow a domain down to its addresses
+trait DnsLookup {
+    fn cname(&self, name: &str) -> Result<Option<String>, String>;
+    fn ips(&self, name: &str) -> Result<Vec<IpAddr>, String>;
+}
+
+impl DnsLookup for Resolver {
+    fn cname(&self, name: &str) -> Result<Option<String>, String> {
+        match self.lookup(name, RecordType::CNAME) {
+            Ok(lookup) => Ok(lookup.into_iter().find_map(|rdata| match rdata {
+                RData::CNAME(cname) => Some(cname.0.to_utf8()),
+                _ => None,
+            })),
+            Err(err) if matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    fn ips(&self, name: &str) -> Result<Vec<IpAddr>, String> {
+        self.lookup_ip(name)
+            .map(|lookup| lookup.iter().collect())
+            .map_err(|err| err.to_string())
+    }
+}
+
+fn normalize(domain: &str) -> String {
+    domain.trim_end_matches('.').to_lowercase()
+}
+
+fn resolve(lookup: &impl DnsLookup, domain: &str) -> Result<DnsResolution, String> {
+    let domain = normalize(domain);
+    let mut cname_chain: Vec<String> = vec![];
+    let mut name = domain.clone();
+
+    while let Some(cname) = lookup.cname(&name)? {
+        let cname = normalize(&cname);
+        if cname == domain || cname_chain.contains(&cname) {
+            return Err(format!("CNAME loop detected on {cname}"));
+        }
+        if cname_chain.len() >= MAX_CNAME_CHAIN_LENGTH {
+            return Err(format!("CNAME chain is longer than {MAX_CNAME_CHAIN_LENGTH} records"));
+        }
+
+        cname_chain.push(cname.clone());
+        name = cname;
+    }
+
+    let ips = match lookup.ips(&name) {
+        Ok(ips) => ips,
+        // the end of the chain can be out of the user hands, i.e: a CDN, the chain alone is still worth reporting
+        Err(_) if !cname_chain.is_empty() => vec![],
+        Err(err) => return Err(err),
+    };
+
+    Ok(DnsResolution { cname_chain, ips })
+}
+
+/// Queries every configured resolver until all of them return the expected records, or the timeout is reached.
+/// `on_check` is called with each answer, so callers can report the propagation progress resolver per resolver.
+pub fn check_propagation(
+    domain: &str,
+    expectation: &DnsExpectation,
+    config: &DnsCheckConfig,
+    on_check: &dyn Fn(&ResolverCheck),
+    should_abort: &dyn Fn() -> bool,
+) -> DnsCheckReport {
+    let resolvers: Vec<(DnsResolver, Resolver)> = config
+        .resolvers
+        .iter()
+        .filter_map(|resolver| match resolver.to_resolver(config.query_timeout) {
+            Ok(dns_resolver) => Some((resolver.clone(), dns_resolver)),
+            Err(err) => {
+                warn!("Cannot create DNS resolver {}: {}", resolver, err);
+                None
+            }
+        })
+        .collect();
+
+    wait_for_propagation(domain, expectation, &resolvers, config, on_check, should_abort)
+}
+
+fn wait_for_propagation<L: DnsLookup>(
+    domain: &str,
+    expectation: &DnsExpectation,
+    resolvers: &[(DnsResolver, L)],
+    config: &DnsCheckConfig,
+    on_check: &dyn Fn(&ResolverCheck),
+    should_abort: &dyn Fn() -> bool,
+) -> DnsCheckReport {
+    let started_at = Instant::now();
+    let mut backoff = config.initial_backoff;
+    let mut checks: Vec<Option<ResolverCheck>> = vec![None; resolvers.len()];
+
+    loop {
+        for ((resolver, lookup), check) in resolvers.iter().zip(checks.iter_mut()) {
+            // records do not vanish once propagated, no need to query this resolver again
+            if check.as_ref().map(|check| check.propagated).unwrap_or(false) {
+                continue;
+            }
+
+            let new_check = ResolverCheck::new(resolver.clone(), expectation, resolve(lookup, domain));
+            on_check(&new_check);
+            *check = Some(new_check);
+        }
+
+        let report = DnsCheckReport {
+            domain: domain.to_string(),
+            checks: checks.iter().flatten().cloned().collect(),
+        };
+        let remaining = config.timeout.saturating_sub(started_at.elapsed());
+        if report.is_fully_propagated() || remaining.is_zero() || should_abort() {
+            return report;
+        }
+
+        thread::sleep(backoff.min(remaining));
+        backoff = (backoff * 2).min(config.max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeLookup {
+        cnames: HashMap<String, String>,
+        ips: HashMap<String, Vec<IpAddr>>,
+    }
+
+    impl DnsLookup for FakeLookup {
+        fn cname(&self, name: &str) -> Result<Option<String>, String> {
+            Ok(self.cnames.get(name).cloned())
+        }
+
+        fn ips(&self, name: &str) -> Result<Vec<IpAddr>, String> {
+            self.ips
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("no record found for {name}"))
+        }
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn test_resolve_follows_cname_chain() {
+        let lookup = FakeLookup {
+            cnames: HashMap::from([
+                ("app.example.com".to_string(), "lb.example.net.".to_string()),
+                ("lb.example.net".to_string(), "eu.lb.example.net.".to_string()),
+            ]),
+            ips: HashMap::from([("eu.lb.example.net".to_string(), vec![ip("10.0.0.1")])]),
+            ..Default::default()
+        };
+
+        let resolution = resolve(&lookup, "App.Example.com.").unwrap();
+        assert_eq!(resolution.cname_chain, vec!["lb.example.net", "eu.lb.example.net"]);
+        assert_eq!(resolution.ips, vec![ip("10.0.0.1")]);
+        assert_eq!(resolution.to_string(), "lb.example.net -> eu.lb.example.net -> 10.0.0.1");
+        assert!(resolution.satisfies(&DnsExpectation::Ip));
+        assert!(resolution.satisfies(&DnsExpectation::Cname("lb.example.net.".to_string())));
+        assert!(!resolution.satisfies(&DnsExpectation::Cname("other.example.net".to_string())));
+    }
+
+    #[test]
+    fn test_resolve_cname_errors() {
+        let looping_lookup = FakeLookup {
+            cnames: HashMap::from([
+                ("a.example.com".to_string(), "b.example.com".to_string()),
+                ("b.example.com".to_string(), "a.example.com".to_string()),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve(&looping_lookup, "a.example.com"),
+            Err("CNAME loop detected on a.example.com".to_string())
+        );
+
+        // a target resolving to nothing still reports the chain, i.e: a CDN not yet provisioned
+        let dangling_lookup = FakeLookup {
+            cnames: HashMap::from([("app.example.com".to_string(), "cdn.example.net".to_string())]),
+            ..Default::default()
+        };
+        let resolution = resolve(&dangling_lookup, "app.example.com").unwrap();
+        assert_eq!(resolution.cname_chain, vec!["cdn.example.net"]);
+        assert!(!resolution.satisfies(&DnsExpectation::Ip));
+    }
+
+    #[test]
+    fn test_wait_for_propagation() {
+        // setup:
+        let resolvers = vec![
+            (
+                DnsResolver::new("up-to-date", ip("10.0.0.53")),
+                FakeLookup {
+                    ips: HashMap::from([("app.example.com".to_string(), vec![ip("10.0.0.1")])]),
+                    ..Default::default()
+                },
+            ),
+            (DnsResolver::new("lagging", ip("10.0.1.53")), FakeLookup::default()),
+        ];
+        let config = DnsCheckConfig {
+            timeout: Duration::from_millis(200),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+            ..DnsCheckConfig::new(resolvers.iter().map(|(resolver, _)| resolver.clone()).collect())
+        };
+        let queried_resolvers = Mutex::new(vec![]);
+
+        // execute:
+        let report = wait_for_propagation(
+            "app.example.com",
+            &DnsExpectation::Ip,
+            &resolvers,
+            &config,
+            &|check| queried_resolvers.lock().unwrap().push(check.resolver.name.clone()),
+            &|| false,
+        );
+
+        // verify:
+        assert!(!report.is_fully_propagated());
+        assert_eq!(
+            report
+                .propagated_checks()
+                .map(|check| check.resolver.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["up-to-date"]
+        );
+        // the propagated resolver is queried only once, the lagging one until the timeout
+        let queried_resolvers = queried_resolvers.into_inner().unwrap();
+        assert_eq!(queried_resolvers.iter().filter(|name| *name == "up-to-date").count(), 1);
+        assert!(queried_resolvers.iter().filter(|name| *name == "lagging").count() > 2);
+    }
+}
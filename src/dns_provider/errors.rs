@@ -1,4 +1,4 @@
-use crate::errors::EngineError;
+use crate::errors::{CommandError, EngineError};
 use crate::events::EventDetails;
 use thiserror::Error;
 
@@ -8,6 +8,14 @@ pub enum DnsProviderError {
     InvalidCredentials,
     #[error("Invalid API url error.")]
     InvalidApiUrl,
+    #[error("No public DNS zone found for domain `{domain}`.")]
+    ZoneNotFound { domain: String },
+    #[error("Cannot list DNS zones: {raw_error_message}.")]
+    CannotListZones { raw_error_message: String },
+    #[error("DNS provider `{provider}` cannot manage records.")]
+    RecordsManagementNotSupported { provider: String },
+    #[error("Cannot manage DNS records of `{name}`: {raw_error_message}.")]
+    CannotManageRecords { name: String, raw_error_message: String },
 }
 
 impl DnsProviderError {
@@ -17,6 +25,29 @@ impl DnsProviderError {
                 EngineError::new_error_on_dns_provider_invalid_credentials(event_details)
             }
             DnsProviderError::InvalidApiUrl => EngineError::new_error_on_dns_provider_invalid_api_url(event_details),
+            DnsProviderError::ZoneNotFound { domain } => {
+                EngineError::new_error_on_dns_provider_zone_not_found(event_details, domain)
+            }
+            DnsProviderError::CannotListZones { raw_error_message } => {
+                EngineError::new_error_on_dns_provider_information(
+                    event_details,
+                    CommandError::new_from_safe_message(raw_error_message.to_string()),
+                )
+            }
+            DnsProviderError::RecordsManagementNotSupported { .. } => {
+                EngineError::new_error_on_dns_provider_information(
+                    event_details,
+                    CommandError::new_from_safe_message(self.to_string()),
+                )
+            }
+            DnsProviderError::CannotManageRecords {
+                name,
+                raw_error_message,
+            } => EngineError::new_error_on_dns_provider_records(
+                event_details,
+                name,
+                CommandError::new_from_safe_message(raw_error_message.to_string()),
+            ),
         }
     }
 }
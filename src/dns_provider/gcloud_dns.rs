@@ -0,0 +1,205 @@
+use once_cell::sync::OnceCell;
+use std::net::Ipv4Addr;
+use tera::Context as TeraContext;
+use uuid::Uuid;
+
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{is_domain_in_zone, DnsProvider, DnsProviderConfiguration, Kind};
+use crate::io_models::context::Context;
+use crate::models::domain::Domain;
+use crate::models::gcp::io::JsonCredentials as JsonCredentialsIo;
+use crate::models::gcp::JsonCredentials;
+use crate::services::gcp::cloud_dns_service::{CloudDnsService, CloudDnsServiceError, ManagedZone};
+
+#[derive(Clone, Debug)]
+pub struct GcloudDnsConfig {
+    /// Project holding the managed zones, which can differ from the one of the service account
+    pub project_id: String,
+    /// Service account key JSON, as downloaded from the Google Cloud console
+    pub service_account_key: String,
+    /// Managed zone holding the records of the domain, i.e: `example-com`
+    pub managed_zone: Option<String>,
+    /// Whether the managed zone is a private one, only visible from the VPC networks it is bound to
+    pub private_zone: bool,
+}
+
+pub struct GcloudDns {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    domain: Domain,
+    credentials: JsonCredentials,
+    project_id: String,
+    // either given by the user or looked up from the domain when validating the provider
+    managed_zone: OnceCell<String>,
+    private_zone: bool,
+}
+
+impl GcloudDns {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        domain: Domain,
+        credentials: JsonCredentials,
+        project_id: Option<&str>,
+        managed_zone: Option<&str>,
+        private_zone: bool,
+    ) -> Self {
+        let managed_zone_cell = OnceCell::new();
+        if let Some(managed_zone) = managed_zone.filter(|zone| !zone.is_empty()) {
+            let _ = managed_zone_cell.set(managed_zone.to_string());
+        }
+
+        GcloudDns {
+            context,
+            long_id,
+            name: name.to_string(),
+            domain,
+            project_id: project_id
+                .filter(|id| !id.is_empty())
+                .unwrap_or(credentials.project_id.as_str())
+                .to_string(),
+            credentials,
+            managed_zone: managed_zone_cell,
+            private_zone,
+        }
+    }
+
+    fn lookup_managed_zone(&self) -> Result<String, DnsProviderError> {
+        let cloud_dns_service =
+            CloudDnsService::new(self.credentials.clone()).map_err(|_| DnsProviderError::InvalidCredentials)?;
+        let managed_zones = cloud_dns_service
+            .list_managed_zones(&self.project_id)
+            .map_err(|e| match e {
+                CloudDnsServiceError::CannotListManagedZones { raw_error_message, .. } => {
+                    DnsProviderError::CannotListZones { raw_error_message }
+                }
+                CloudDnsServiceError::CannotCreateService { .. } | CloudDnsServiceError::InvalidCredentials { .. } => {
+                    DnsProviderError::InvalidCredentials
+                }
+            })?;
+
+        let domain = self.domain.to_string();
+        match self.managed_zone.get() {
+            Some(managed_zone) => managed_zones
+                .iter()
+                .find(|zone| &zone.name == managed_zone && zone_matches_domain(zone, &domain, self.private_zone)),
+            None => find_managed_zone(&domain, &managed_zones, self.private_zone),
+        }
+        .map(|zone| zone.name.to_string())
+        .ok_or(DnsProviderError::ZoneNotFound { domain })
+    }
+}
+
+fn zone_matches_domain(zone: &ManagedZone, domain: &str, private_zone: bool) -> bool {
+    zone.is_private() == private_zone && is_domain_in_zone(domain, &zone.dns_name)
+}
+
+/// Most specific zone of the given visibility holding the domain, records of delegated sub domains must go to their
+/// own zone
+fn find_managed_zone<'a>(
+    domain: &str,
+    managed_zones: &'a [ManagedZone],
+    private_zone: bool,
+) -> Option<&'a ManagedZone> {
+    managed_zones
+        .iter()
+        .filter(|zone| zone_matches_domain(zone, domain, private_zone))
+        .max_by_key(|zone| zone.dns_name.trim_end_matches('.').len())
+}
+
+impl DnsProvider for GcloudDns {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn provider_name(&self) -> &str {
+        "gcloud_dns"
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::GcloudDns
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn insert_into_teracontext<'a>(&self, context: &'a mut TeraContext) -> &'a mut TeraContext {
+        context.insert("external_dns_provider", &self.provider_name());
+        context.insert("gcloud_dns_project_id", &self.project_id);
+        context.insert("gcloud_dns_managed_zone", &self.managed_zone.get());
+        context
+    }
+
+    fn provider_configuration(&self) -> DnsProviderConfiguration {
+        DnsProviderConfiguration::GcloudDns(GcloudDnsConfig {
+            project_id: self.project_id.to_string(),
+            service_account_key: serde_json::to_string(&JsonCredentialsIo::from(self.credentials.clone()))
+                .unwrap_or_default(),
+            managed_zone: self.managed_zone.get().cloned(),
+            private_zone: self.private_zone,
+        })
+    }
+
+    fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    fn resolvers(&self) -> Vec<Ipv4Addr> {
+        vec![Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)]
+    }
+
+    fn is_private_zone(&self) -> bool {
+        self.private_zone
+    }
+
+    fn is_valid(&self) -> Result<(), DnsProviderError> {
+        let managed_zone = self.lookup_managed_zone()?;
+        let _ = self.managed_zone.set(managed_zone);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str, dns_name: &str, visibility: Option<&str>) -> ManagedZone {
+        ManagedZone {
+            name: name.to_string(),
+            dns_name: dns_name.to_string(),
+            visibility: visibility.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_managed_zone() {
+        let zones = vec![
+            zone("example-com", "example.com.", Some("public")),
+            zone("qovery-example-com", "qovery.example.com.", None),
+            zone("internal-example-com", "internal.example.com.", Some("private")),
+            zone("other-io", "other.io.", Some("public")),
+        ];
+        let found_zone_name = |domain: &str| find_managed_zone(domain, &zones, false).map(|zone| zone.name.as_str());
+
+        assert_eq!(found_zone_name("example.com"), Some("example-com"));
+        assert_eq!(found_zone_name("app.example.com"), Some("example-com"));
+        assert_eq!(found_zone_name("cluster.qovery.example.com"), Some("qovery-example-com"));
+        // private zones are not resolvable by Let's Encrypt and users
+        assert_eq!(found_zone_name("db.internal.example.com"), Some("example-com"));
+        assert_eq!(found_zone_name("notexample.com"), None);
+
+        // clusters of split-horizon setups only use the private zones
+        let found_private_zone_name =
+            |domain: &str| find_managed_zone(domain, &zones, true).map(|zone| zone.name.as_str());
+        assert_eq!(found_private_zone_name("db.internal.example.com"), Some("internal-example-com"));
+        assert_eq!(found_private_zone_name("app.example.com"), None);
+    }
+}
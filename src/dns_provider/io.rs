@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 pub enum Kind {
     Cloudflare,
     QoveryDns,
+    Route53,
+    GcloudDns,
+    Ovh,
 }
 
 impl From<dns_provider::Kind> for Kind {
@@ -13,6 +16,9 @@ impl From<dns_provider::Kind> for Kind {
         match kind {
             dns_provider::Kind::Cloudflare => Kind::Cloudflare,
             dns_provider::Kind::QoveryDns => Kind::QoveryDns,
+            dns_provider::Kind::Route53 => Kind::Route53,
+            dns_provider::Kind::GcloudDns => Kind::GcloudDns,
+            dns_provider::Kind::Ovh => Kind::Ovh,
         }
     }
 }
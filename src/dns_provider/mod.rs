@@ -2,7 +2,10 @@ use std::net::Ipv4Addr;
 
 use crate::dns_provider::cloudflare::CloudflareDnsConfig;
 use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::gcloud_dns::GcloudDnsConfig;
+use crate::dns_provider::ovh::OvhDnsConfig;
 use crate::dns_provider::qoverydns::QoveryDnsConfig;
+use crate::dns_provider::route53::Route53DnsConfig;
 use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
 use tera::Context as TeraContext;
 use uuid::Uuid;
@@ -11,21 +14,60 @@ use crate::io_models::context::Context;
 use crate::io_models::QoveryIdentifier;
 use crate::models::domain::Domain;
 
+pub mod caa;
 pub mod cloudflare;
+pub mod dns_check;
 pub mod errors;
+pub mod gcloud_dns;
 pub mod io;
+pub mod ovh;
 pub mod qoverydns;
+pub mod route53;
 
 #[derive(Clone, Debug)]
 pub enum Kind {
     Cloudflare,
     QoveryDns,
+    Route53,
+    GcloudDns,
+    Ovh,
 }
 
 #[derive(Clone, Debug)]
 pub enum DnsProviderConfiguration {
     Cloudflare(CloudflareDnsConfig),
     QoveryDns(QoveryDnsConfig),
+    Route53(Route53DnsConfig),
+    GcloudDns(GcloudDnsConfig),
+    Ovh(OvhDnsConfig),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DnsRecordType {
+    A,
+    Aaaa,
+    Cname,
+    Caa,
+}
+
+impl DnsRecordType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DnsRecordType::A => "A",
+            DnsRecordType::Aaaa => "AAAA",
+            DnsRecordType::Cname => "CNAME",
+            DnsRecordType::Caa => "CAA",
+        }
+    }
+}
+
+/// One of the records sharing a name, receiving a share of the traffic proportional to its weight
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedRecord {
+    /// Distinguishes the records of the same name, i.e: the id of the cluster the record points to
+    pub set_identifier: String,
+    pub value: String,
+    pub weight: u32,
 }
 
 impl DnsProviderConfiguration {
@@ -33,9 +75,57 @@ impl DnsProviderConfiguration {
         match self {
             DnsProviderConfiguration::Cloudflare(_) => "cloudflare",
             DnsProviderConfiguration::QoveryDns(_) => "pdns",
+            DnsProviderConfiguration::Route53(_) => "route53",
+            DnsProviderConfiguration::GcloudDns(_) => "clouddns",
+            DnsProviderConfiguration::Ovh(_) => "ovh",
         }
         .to_string()
     }
+
+    /// Provider name expected by external-dns, which differs from the cert-manager one for Route53 and Cloud DNS
+    pub fn get_external_dns_provider_name(&self) -> String {
+        match self {
+            DnsProviderConfiguration::Route53(_) => "aws".to_string(),
+            DnsProviderConfiguration::GcloudDns(_) => "google".to_string(),
+            _ => self.get_cert_manager_config_name(),
+        }
+    }
+
+    /// Records created for the load balancers, AAAA ones publish their IPv6 addresses on dual stack clusters
+    pub fn managed_record_types(&self, dual_stack_enabled: bool) -> Vec<DnsRecordType> {
+        match (self, dual_stack_enabled) {
+            (
+                DnsProviderConfiguration::Cloudflare(_)
+                | DnsProviderConfiguration::QoveryDns(_)
+                | DnsProviderConfiguration::Route53(_)
+                | DnsProviderConfiguration::GcloudDns(_)
+                | DnsProviderConfiguration::Ovh(_),
+                true,
+            ) => {
+                vec![DnsRecordType::A, DnsRecordType::Aaaa, DnsRecordType::Cname]
+            }
+            (_, false) => vec![DnsRecordType::A, DnsRecordType::Cname],
+        }
+    }
+
+    /// Whether the records go to a zone only resolvable from inside the VPC of the cluster
+    pub fn is_private_zone(&self) -> bool {
+        match self {
+            DnsProviderConfiguration::Route53(config) => config.private_zone,
+            DnsProviderConfiguration::GcloudDns(config) => config.private_zone,
+            DnsProviderConfiguration::Cloudflare(_)
+            | DnsProviderConfiguration::QoveryDns(_)
+            | DnsProviderConfiguration::Ovh(_) => false,
+        }
+    }
+}
+
+/// Whether a zone can hold the records of the domain, i.e: `example.com.` for `qovery.example.com`
+pub(crate) fn is_domain_in_zone(domain: &str, zone_name: &str) -> bool {
+    let zone_name = zone_name.trim_end_matches('.').to_lowercase();
+    let domain = domain.trim_end_matches('.').to_lowercase();
+
+    domain == zone_name || domain.ends_with(&format!(".{zone_name}"))
 }
 
 pub trait DnsProvider: Send + Sync {
@@ -49,6 +139,39 @@ pub trait DnsProvider: Send + Sync {
     fn domain(&self) -> &Domain;
     fn resolvers(&self) -> Vec<Ipv4Addr>;
     fn is_valid(&self) -> Result<(), DnsProviderError>;
+    /// Private zones are only resolvable from inside the VPC of the cluster, split-horizon setups keeping a public
+    /// zone of the same name for the outside world. Public resolvers cannot be used to check their records.
+    fn is_private_zone(&self) -> bool {
+        false
+    }
+    /// Values of the records of a name, as written in a zone file, i.e: `0 issue "letsencrypt.org"` for a CAA record
+    fn get_records(&self, _name: &str, _record_type: DnsRecordType) -> Result<Vec<String>, DnsProviderError> {
+        Err(DnsProviderError::RecordsManagementNotSupported {
+            provider: self.provider_name().to_string(),
+        })
+    }
+    /// Replaces the records of a name by the given values, creating them when missing
+    fn set_records(
+        &self,
+        _name: &str,
+        _record_type: DnsRecordType,
+        _values: &[String],
+    ) -> Result<(), DnsProviderError> {
+        Err(DnsProviderError::RecordsManagementNotSupported {
+            provider: self.provider_name().to_string(),
+        })
+    }
+    /// Replaces the weighted records of a name, creating the missing ones. A record with a weight of 0 gets no traffic.
+    fn set_weighted_records(
+        &self,
+        _name: &str,
+        _record_type: DnsRecordType,
+        _records: &[WeightedRecord],
+    ) -> Result<(), DnsProviderError> {
+        Err(DnsProviderError::RecordsManagementNotSupported {
+            provider: self.provider_name().to_string(),
+        })
+    }
     fn event_details(&self) -> EventDetails {
         EventDetails::new(
             None,
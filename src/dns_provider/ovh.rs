@@ -0,0 +1,376 @@
+use reqwest::{Method, StatusCode};
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::net::Ipv4Addr;
+use tera::Context as TeraContext;
+use uuid::Uuid;
+
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{is_domain_in_zone, DnsProvider, DnsProviderConfiguration, DnsRecordType, Kind};
+use crate::io_models::context::Context;
+use crate::models::domain::Domain;
+use crate::runtime::block_on;
+
+pub const OVH_DEFAULT_ENDPOINT: &str = "ovh-eu";
+const RECORDS_TTL_IN_SECONDS: u32 = 300;
+
+#[derive(Clone, Debug)]
+pub struct OvhDnsConfig {
+    /// API endpoint, as named by external-dns and the OVH SDKs, i.e: `ovh-eu`
+    pub endpoint: String,
+    pub application_key: String,
+    pub application_secret: String,
+    pub consumer_key: String,
+}
+
+pub struct Ovh {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    domain: Domain,
+    dns_config: OvhDnsConfig,
+}
+
+impl Ovh {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        domain: Domain,
+        endpoint: Option<&str>,
+        application_key: &str,
+        application_secret: &str,
+        consumer_key: &str,
+    ) -> Self {
+        Ovh {
+            context,
+            long_id,
+            name: name.to_string(),
+            domain,
+            dns_config: OvhDnsConfig {
+                endpoint: endpoint
+                    .filter(|endpoint| !endpoint.is_empty())
+                    .unwrap_or(OVH_DEFAULT_ENDPOINT)
+                    .to_string(),
+                application_key: application_key.to_string(),
+                application_secret: application_secret.to_string(),
+                consumer_key: consumer_key.to_string(),
+            },
+        }
+    }
+
+    fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        on_error: &dyn Fn(String) -> DnsProviderError,
+    ) -> Result<T, DnsProviderError> {
+        let api_url = api_url(&self.dns_config.endpoint).ok_or(DnsProviderError::InvalidApiUrl)?;
+        let body = body.map(|body| body.to_string()).unwrap_or_default();
+        let client = reqwest::Client::new();
+
+        block_on(async {
+            // requests are refused when signed with a timestamp too far from the one of OVH
+            let timestamp: u64 = client
+                .get(format!("{api_url}/auth/time"))
+                .send()
+                .await
+                .map_err(|e| on_error(e.to_string()))?
+                .json()
+                .await
+                .map_err(|e| on_error(e.to_string()))?;
+
+            let url = format!("{api_url}{path}");
+            let response = client
+                .request(method.clone(), &url)
+                .header("X-Ovh-Application", &self.dns_config.application_key)
+                .header("X-Ovh-Consumer", &self.dns_config.consumer_key)
+                .header("X-Ovh-Timestamp", timestamp.to_string())
+                .header(
+                    "X-Ovh-Signature",
+                    signature(
+                        &self.dns_config.application_secret,
+                        &self.dns_config.consumer_key,
+                        method.as_str(),
+                        &url,
+                        &body,
+                        timestamp,
+                    ),
+                )
+                .header("Content-Type", "application/json")
+                .body(body.clone())
+                .send()
+                .await
+                .map_err(|e| on_error(e.to_string()))?;
+
+            match response.status() {
+                StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    Err(DnsProviderError::InvalidCredentials)
+                }
+                status if !status.is_success() => {
+                    Err(on_error(format!("{status}: {}", response.text().await.unwrap_or_default())))
+                }
+                _ => response.json().await.map_err(|e| on_error(e.to_string())),
+            }
+        })
+    }
+
+    fn list_zones(&self) -> Result<Vec<String>, DnsProviderError> {
+        let cannot_list_zones = |raw_error_message: String| DnsProviderError::CannotListZones { raw_error_message };
+
+        self.request(Method::GET, "/domain/zone", None, &cannot_list_zones)
+    }
+
+    /// Zone holding the records of the name, with the sub domain OVH identifies them by
+    fn zone_and_sub_domain(&self, name: &str) -> Result<(String, String), DnsProviderError> {
+        let zones = self.list_zones()?;
+        let zone = find_zone(name, &zones).ok_or_else(|| DnsProviderError::ZoneNotFound {
+            domain: name.to_string(),
+        })?;
+
+        Ok((zone.to_string(), sub_domain(name, zone)))
+    }
+
+    fn list_record_ids(
+        &self,
+        zone: &str,
+        sub_domain: &str,
+        record_type: DnsRecordType,
+        on_error: &dyn Fn(String) -> DnsProviderError,
+    ) -> Result<Vec<u64>, DnsProviderError> {
+        self.request(
+            Method::GET,
+            &format!(
+                "/domain/zone/{zone}/record?fieldType={}&subDomain={}",
+                record_type.as_str(),
+                urlencoding::encode(sub_domain)
+            ),
+            None,
+            on_error,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct OvhRecord {
+    target: String,
+}
+
+fn api_url(endpoint: &str) -> Option<&'static str> {
+    match endpoint {
+        "ovh-eu" => Some("https://eu.api.ovh.com/1.0"),
+        "ovh-ca" => Some("https://ca.api.ovh.com/1.0"),
+        "ovh-us" => Some("https://api.us.ovhcloud.com/1.0"),
+        _ => None,
+    }
+}
+
+// https://help.ovhcloud.com/csm/en-api-getting-started-ovhcloud-api
+fn signature(
+    application_secret: &str,
+    consumer_key: &str,
+    method: &str,
+    url: &str,
+    body: &str,
+    timestamp: u64,
+) -> String {
+    let to_sign = format!("{application_secret}+{consumer_key}+{method}+{url}+{body}+{timestamp}");
+    let hash = digest(&SHA1_FOR_LEGACY_USE_ONLY, to_sign.as_bytes());
+
+    format!(
+        "$1${}",
+        hash.as_ref()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+    )
+}
+
+/// Most specific zone holding the domain, OVH zones being named after their domain, i.e: `example.com`
+fn find_zone<'a>(domain: &str, zones: &'a [String]) -> Option<&'a String> {
+    zones
+        .iter()
+        .filter(|zone| is_domain_in_zone(domain, zone))
+        .max_by_key(|zone| zone.trim_end_matches('.').len())
+}
+
+/// Name of the records relative to their zone, empty for the apex of the zone
+fn sub_domain(name: &str, zone: &str) -> String {
+    let name = name.trim_end_matches('.').to_lowercase();
+    let zone = zone.trim_end_matches('.').to_lowercase();
+
+    name.strip_suffix(&zone)
+        .map(|sub_domain| sub_domain.trim_end_matches('.').to_string())
+        .unwrap_or(name)
+}
+
+impl DnsProvider for Ovh {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn provider_name(&self) -> &str {
+        "ovh"
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Ovh
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn insert_into_teracontext<'a>(&self, context: &'a mut TeraContext) -> &'a mut TeraContext {
+        context.insert("external_dns_provider", &self.provider_name());
+        context.insert("ovh_endpoint", &self.dns_config.endpoint);
+        context.insert("ovh_application_key", &self.dns_config.application_key);
+        context.insert("ovh_application_secret", &self.dns_config.application_secret);
+        context.insert("ovh_consumer_key", &self.dns_config.consumer_key);
+        context
+    }
+
+    fn provider_configuration(&self) -> DnsProviderConfiguration {
+        DnsProviderConfiguration::Ovh(self.dns_config.clone())
+    }
+
+    fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    fn resolvers(&self) -> Vec<Ipv4Addr> {
+        vec![Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)]
+    }
+
+    fn is_valid(&self) -> Result<(), DnsProviderError> {
+        if self.dns_config.application_key.is_empty()
+            || self.dns_config.application_secret.is_empty()
+            || self.dns_config.consumer_key.is_empty()
+        {
+            return Err(DnsProviderError::InvalidCredentials);
+        }
+
+        let zones = self.list_zones()?;
+        let domain = self.domain.to_string();
+        match find_zone(&domain, &zones) {
+            Some(_) => Ok(()),
+            None => Err(DnsProviderError::ZoneNotFound { domain }),
+        }
+    }
+
+    fn get_records(&self, name: &str, record_type: DnsRecordType) -> Result<Vec<String>, DnsProviderError> {
+        let cannot_manage_records = |raw_error_message: String| DnsProviderError::CannotManageRecords {
+            name: name.to_string(),
+            raw_error_message,
+        };
+        let (zone, sub_domain) = self.zone_and_sub_domain(name)?;
+
+        self.list_record_ids(&zone, &sub_domain, record_type, &cannot_manage_records)?
+            .into_iter()
+            .map(|id| {
+                self.request(
+                    Method::GET,
+                    &format!("/domain/zone/{zone}/record/{id}"),
+                    None,
+                    &cannot_manage_records,
+                )
+                .map(|record: OvhRecord| record.target)
+            })
+            .collect()
+    }
+
+    fn set_records(&self, name: &str, record_type: DnsRecordType, values: &[String]) -> Result<(), DnsProviderError> {
+        let cannot_manage_records = |raw_error_message: String| DnsProviderError::CannotManageRecords {
+            name: name.to_string(),
+            raw_error_message,
+        };
+        let (zone, sub_domain) = self.zone_and_sub_domain(name)?;
+
+        // OVH has no upsert, the previous records are replaced by the new ones
+        for id in self.list_record_ids(&zone, &sub_domain, record_type, &cannot_manage_records)? {
+            let _: serde_json::Value = self.request(
+                Method::DELETE,
+                &format!("/domain/zone/{zone}/record/{id}"),
+                None,
+                &cannot_manage_records,
+            )?;
+        }
+        for value in values {
+            let _: serde_json::Value = self.request(
+                Method::POST,
+                &format!("/domain/zone/{zone}/record"),
+                Some(serde_json::json!({
+                    "fieldType": record_type.as_str(),
+                    "subDomain": sub_domain,
+                    "target": value,
+                    "ttl": RECORDS_TTL_IN_SECONDS,
+                })),
+                &cannot_manage_records,
+            )?;
+        }
+
+        // changes are only served once the zone is refreshed
+        let _: serde_json::Value = self.request(
+            Method::POST,
+            &format!("/domain/zone/{zone}/refresh"),
+            None,
+            &cannot_manage_records,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(
+            signature(
+                "EgWIz07P0HYwtQDs",
+                "MtSwSrPpTxaYHzPvPU0UrzJvRlvNpHq2",
+                "GET",
+                "https://eu.api.ovh.com/1.0/domain/zone",
+                "",
+                1366560945
+            ),
+            "$1$58ee6ff67faa6b75d905ef454c90076935462155"
+        );
+    }
+
+    #[test]
+    fn test_find_zone() {
+        let zones = vec![
+            "example.com".to_string(),
+            "qovery.example.com".to_string(),
+            "other.io".to_string(),
+        ];
+        let found_zone = |domain: &str| find_zone(domain, &zones).map(String::as_str);
+
+        assert_eq!(found_zone("example.com"), Some("example.com"));
+        assert_eq!(found_zone("app.example.com"), Some("example.com"));
+        assert_eq!(found_zone("cluster.qovery.example.com"), Some("qovery.example.com"));
+        assert_eq!(found_zone("notexample.com"), None);
+    }
+
+    #[test]
+    fn test_sub_domain() {
+        assert_eq!(sub_domain("_acme.app.example.com", "example.com"), "_acme.app");
+        assert_eq!(sub_domain("*.Cluster.example.com.", "example.com"), "*.cluster");
+        assert_eq!(sub_domain("example.com", "example.com"), "");
+    }
+
+    #[test]
+    fn test_api_url() {
+        assert_eq!(api_url("ovh-eu"), Some("https://eu.api.ovh.com/1.0"));
+        assert_eq!(api_url("kimsufi-eu"), None);
+    }
+}
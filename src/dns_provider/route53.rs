@@ -0,0 +1,393 @@
+use once_cell::sync::OnceCell;
+use rusoto_core::credential::StaticProvider;
+use rusoto_core::{Client, HttpClient, Region as RusotoRegion, RusotoError};
+use rusoto_route53::{
+    Change, ChangeBatch, ChangeResourceRecordSetsRequest, HostedZone, ListHostedZonesRequest,
+    ListResourceRecordSetsRequest, ResourceRecord, ResourceRecordSet, Route53 as Route53Api, Route53Client,
+};
+use std::net::Ipv4Addr;
+use tera::Context as TeraContext;
+use uuid::Uuid;
+
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{
+    is_domain_in_zone, DnsProvider, DnsProviderConfiguration, DnsRecordType, Kind, WeightedRecord,
+};
+use crate::io_models::context::Context;
+use crate::models::domain::Domain;
+use crate::runtime::block_on;
+
+// Route53 is a global service, its API is only served from us-east-1
+const ROUTE53_API_REGION: RusotoRegion = RusotoRegion::UsEast1;
+const INVALID_CREDENTIALS_ERROR_CODES: [&str; 3] = ["InvalidClientTokenId", "SignatureDoesNotMatch", "AccessDenied"];
+const RECORDS_TTL_IN_SECONDS: i64 = 300;
+
+#[derive(Clone, Debug)]
+pub struct Route53DnsConfig {
+    pub aws_access_key_id: String,
+    pub aws_secret_access_key: String,
+    /// Hosted zone holding the records of the domain, i.e: `Z0123456789ABCDEFGHIJ`
+    pub hosted_zone_id: Option<String>,
+    /// Whether the hosted zone is a private one, associated to the VPC of the cluster
+    pub private_zone: bool,
+}
+
+pub struct Route53 {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    domain: Domain,
+    aws_access_key_id: String,
+    aws_secret_access_key: String,
+    // either given by the user or looked up from the domain when validating the provider
+    hosted_zone_id: OnceCell<String>,
+    private_zone: bool,
+}
+
+impl Route53 {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        domain: Domain,
+        aws_access_key_id: &str,
+        aws_secret_access_key: &str,
+        hosted_zone_id: Option<&str>,
+        private_zone: bool,
+    ) -> Self {
+        let hosted_zone_cell = OnceCell::new();
+        if let Some(hosted_zone_id) = hosted_zone_id.filter(|id| !id.is_empty()) {
+            let _ = hosted_zone_cell.set(hosted_zone_id.trim_start_matches("/hostedzone/").to_string());
+        }
+
+        Route53 {
+            context,
+            long_id,
+            name: name.to_string(),
+            domain,
+            aws_access_key_id: aws_access_key_id.to_string(),
+            aws_secret_access_key: aws_secret_access_key.to_string(),
+            hosted_zone_id: hosted_zone_cell,
+            private_zone,
+        }
+    }
+
+    fn get_route53_client(&self) -> Route53Client {
+        let client = Client::new_with(
+            StaticProvider::new(self.aws_access_key_id.clone(), self.aws_secret_access_key.clone(), None, None),
+            HttpClient::new().expect("unable to create new Http client"),
+        );
+
+        Route53Client::new_with_client(client, ROUTE53_API_REGION)
+    }
+
+    fn list_hosted_zones(&self) -> Result<Vec<HostedZone>, DnsProviderError> {
+        let client = self.get_route53_client();
+        let mut hosted_zones = vec![];
+        let mut marker = None;
+
+        loop {
+            let response = block_on(client.list_hosted_zones(ListHostedZonesRequest {
+                marker,
+                ..Default::default()
+            }))
+            .map_err(|e| match &e {
+                RusotoError::Credentials(_) => DnsProviderError::InvalidCredentials,
+                RusotoError::Unknown(response)
+                    if INVALID_CREDENTIALS_ERROR_CODES
+                        .iter()
+                        .any(|code| response.body_as_str().contains(code)) =>
+                {
+                    DnsProviderError::InvalidCredentials
+                }
+                _ => DnsProviderError::CannotListZones {
+                    raw_error_message: e.to_string(),
+                },
+            })?;
+
+            hosted_zones.extend(response.hosted_zones);
+            match (response.is_truncated, response.next_marker) {
+                (true, Some(next_marker)) => marker = Some(next_marker),
+                _ => break,
+            }
+        }
+
+        Ok(hosted_zones)
+    }
+
+    fn lookup_hosted_zone_id(&self) -> Result<String, DnsProviderError> {
+        let hosted_zones = self.list_hosted_zones()?;
+
+        match self.hosted_zone_id.get() {
+            Some(hosted_zone_id) => hosted_zones
+                .iter()
+                .find(|zone| {
+                    zone_id(zone) == hosted_zone_id
+                        && zone_matches_domain(zone, &self.domain.to_string(), self.private_zone)
+                })
+                .map(|zone| zone_id(zone).to_string()),
+            None => find_hosted_zone(&self.domain.to_string(), &hosted_zones, self.private_zone)
+                .map(|zone| zone_id(zone).to_string()),
+        }
+        .ok_or_else(|| DnsProviderError::ZoneNotFound {
+            domain: self.domain.to_string(),
+        })
+    }
+
+    fn records_hosted_zone_id(&self, name: &str) -> Result<String, DnsProviderError> {
+        let hosted_zones = self.list_hosted_zones()?;
+
+        find_hosted_zone(name, &hosted_zones, self.private_zone)
+            .map(|zone| zone_id(zone).to_string())
+            .ok_or_else(|| DnsProviderError::ZoneNotFound {
+                domain: name.to_string(),
+            })
+    }
+}
+
+/// Route53 returns absolute names, with the `*` of wildcards escaped
+fn normalize_record_name(name: &str) -> String {
+    name.replace("\\052", "*").trim_end_matches('.').to_lowercase()
+}
+
+fn zone_id(zone: &HostedZone) -> &str {
+    zone.id.trim_start_matches("/hostedzone/")
+}
+
+fn is_private_zone(zone: &HostedZone) -> bool {
+    zone.config
+        .as_ref()
+        .and_then(|config| config.private_zone)
+        .unwrap_or(false)
+}
+
+fn zone_matches_domain(zone: &HostedZone, domain: &str, private_zone: bool) -> bool {
+    is_private_zone(zone) == private_zone && is_domain_in_zone(domain, &zone.name)
+}
+
+/// Most specific zone of the given visibility holding the domain, records of delegated sub domains must go to their
+/// own zone
+fn find_hosted_zone<'a>(domain: &str, hosted_zones: &'a [HostedZone], private_zone: bool) -> Option<&'a HostedZone> {
+    hosted_zones
+        .iter()
+        .filter(|zone| zone_matches_domain(zone, domain, private_zone))
+        .max_by_key(|zone| zone.name.trim_end_matches('.').len())
+}
+
+impl DnsProvider for Route53 {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn provider_name(&self) -> &str {
+        "route53"
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Route53
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn insert_into_teracontext<'a>(&self, context: &'a mut TeraContext) -> &'a mut TeraContext {
+        context.insert("external_dns_provider", &self.provider_name());
+        context.insert("route53_aws_access_key_id", &self.aws_access_key_id);
+        context.insert("route53_aws_secret_access_key", &self.aws_secret_access_key);
+        context.insert("route53_hosted_zone_id", &self.hosted_zone_id.get());
+        context
+    }
+
+    fn provider_configuration(&self) -> DnsProviderConfiguration {
+        DnsProviderConfiguration::Route53(Route53DnsConfig {
+            aws_access_key_id: self.aws_access_key_id.clone(),
+            aws_secret_access_key: self.aws_secret_access_key.clone(),
+            hosted_zone_id: self.hosted_zone_id.get().cloned(),
+            private_zone: self.private_zone,
+        })
+    }
+
+    fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    fn resolvers(&self) -> Vec<Ipv4Addr> {
+        vec![Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)]
+    }
+
+    fn is_private_zone(&self) -> bool {
+        self.private_zone
+    }
+
+    fn is_valid(&self) -> Result<(), DnsProviderError> {
+        if self.aws_access_key_id.is_empty() || self.aws_secret_access_key.is_empty() {
+            return Err(DnsProviderError::InvalidCredentials);
+        }
+
+        let hosted_zone_id = self.lookup_hosted_zone_id()?;
+        let _ = self.hosted_zone_id.set(hosted_zone_id);
+
+        Ok(())
+    }
+
+    fn get_records(&self, name: &str, record_type: DnsRecordType) -> Result<Vec<String>, DnsProviderError> {
+        let hosted_zone_id = self.records_hosted_zone_id(name)?;
+        // record sets are sorted by name then type, the first one returned is the requested one when it exists
+        let response = block_on(
+            self.get_route53_client()
+                .list_resource_record_sets(ListResourceRecordSetsRequest {
+                    hosted_zone_id,
+                    start_record_name: Some(name.to_string()),
+                    start_record_type: Some(record_type.as_str().to_string()),
+                    max_items: Some("1".to_string()),
+                    ..Default::default()
+                }),
+        )
+        .map_err(|e| DnsProviderError::CannotManageRecords {
+            name: name.to_string(),
+            raw_error_message: e.to_string(),
+        })?;
+
+        Ok(response
+            .resource_record_sets
+            .into_iter()
+            .filter(|record_set| {
+                record_set.type_ == record_type.as_str()
+                    && normalize_record_name(&record_set.name) == normalize_record_name(name)
+            })
+            .flat_map(|record_set| record_set.resource_records.unwrap_or_default())
+            .map(|record| record.value)
+            .collect())
+    }
+
+    fn set_records(&self, name: &str, record_type: DnsRecordType, values: &[String]) -> Result<(), DnsProviderError> {
+        let hosted_zone_id = self.records_hosted_zone_id(name)?;
+        let change = Change {
+            action: "UPSERT".to_string(),
+            resource_record_set: ResourceRecordSet {
+                name: name.to_string(),
+                type_: record_type.as_str().to_string(),
+                ttl: Some(RECORDS_TTL_IN_SECONDS),
+                resource_records: Some(
+                    values
+                        .iter()
+                        .map(|value| ResourceRecord {
+                            value: value.to_string(),
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+        };
+
+        block_on(
+            self.get_route53_client()
+                .change_resource_record_sets(ChangeResourceRecordSetsRequest {
+                    hosted_zone_id,
+                    change_batch: ChangeBatch {
+                        changes: vec![change],
+                        comment: None,
+                    },
+                }),
+        )
+        .map(|_| ())
+        .map_err(|e| DnsProviderError::CannotManageRecords {
+            name: name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    fn set_weighted_records(
+        &self,
+        name: &str,
+        record_type: DnsRecordType,
+        records: &[WeightedRecord],
+    ) -> Result<(), DnsProviderError> {
+        let hosted_zone_id = self.records_hosted_zone_id(name)?;
+        // all the weights are changed in the same batch, Route53 applying it atomically
+        let changes = records
+            .iter()
+            .map(|record| Change {
+                action: "UPSERT".to_string(),
+                resource_record_set: ResourceRecordSet {
+                    name: name.to_string(),
+                    type_: record_type.as_str().to_string(),
+                    set_identifier: Some(record.set_identifier.clone()),
+                    weight: Some(i64::from(record.weight)),
+                    ttl: Some(RECORDS_TTL_IN_SECONDS),
+                    resource_records: Some(vec![ResourceRecord {
+                        value: record.value.clone(),
+                    }]),
+                    ..Default::default()
+                },
+            })
+            .collect();
+
+        block_on(
+            self.get_route53_client()
+                .change_resource_record_sets(ChangeResourceRecordSetsRequest {
+                    hosted_zone_id,
+                    change_batch: ChangeBatch { changes, comment: None },
+                }),
+        )
+        .map(|_| ())
+        .map_err(|e| DnsProviderError::CannotManageRecords {
+            name: name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusoto_route53::HostedZoneConfig;
+
+    fn zone(id: &str, name: &str, private_zone: bool) -> HostedZone {
+        HostedZone {
+            id: format!("/hostedzone/{id}"),
+            name: name.to_string(),
+            config: Some(HostedZoneConfig {
+                private_zone: Some(private_zone),
+                comment: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_hosted_zone() {
+        let zones = vec![
+            zone("Z1", "example.com.", false),
+            zone("Z2", "qovery.example.com.", false),
+            zone("Z3", "internal.example.com.", true),
+            zone("Z4", "other.io.", false),
+        ];
+        let found_zone_id = |domain: &str| find_hosted_zone(domain, &zones, false).map(zone_id);
+
+        assert_eq!(found_zone_id("example.com"), Some("Z1"));
+        assert_eq!(found_zone_id("app.example.com"), Some("Z1"));
+        assert_eq!(found_zone_id("cluster.qovery.example.com"), Some("Z2"));
+        assert_eq!(found_zone_id("QOVERY.example.com."), Some("Z2"));
+        // private zones are not resolvable by Let's Encrypt and users
+        assert_eq!(found_zone_id("db.internal.example.com"), Some("Z1"));
+        assert_eq!(found_zone_id("notexample.com"), None);
+        assert_eq!(found_zone_id("example.org"), None);
+
+        // clusters of split-horizon setups only use the private zones
+        let found_private_zone_id = |domain: &str| find_hosted_zone(domain, &zones, true).map(zone_id);
+        assert_eq!(found_private_zone_id("db.internal.example.com"), Some("Z3"));
+        assert_eq!(found_private_zone_id("app.example.com"), None);
+    }
+
+    #[test]
+    fn test_normalize_record_name() {
+        assert_eq!(normalize_record_name("\\052.Cluster.example.com."), "*.cluster.example.com");
+        assert_eq!(normalize_record_name("*.cluster.example.com"), "*.cluster.example.com");
+    }
+}
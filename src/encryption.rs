@@ -0,0 +1,212 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fs;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+
+/// Base64 encoded 256 bits key, i.e: decrypted from KMS by the worker. It is required, the engine refusing to start
+/// without it rather than writing secrets in clear or with a key archives could not be decrypted with.
+pub const WORKSPACE_ENCRYPTION_KEY_ENV_VAR: &str = "WORKSPACE_ENCRYPTION_KEY";
+const KEY_LEN: usize = 32;
+// Prefix of encrypted contents, followed by the nonce then the ciphertext and its tag
+const ENCRYPTED_CONTENT_HEADER: &[u8] = b"QENC1";
+
+static WORKSPACE_CIPHER: Lazy<Result<WorkspaceCipher, EncryptionError>> = Lazy::new(|| {
+    match std::env::var(WORKSPACE_ENCRYPTION_KEY_ENV_VAR) {
+        Ok(key) => WorkspaceCipher::from_base64_key(&key),
+        // unit tests only write files they read back in the same process
+        Err(_) if cfg!(test) => WorkspaceCipher::new_ephemeral(),
+        Err(_) => Err(EncryptionError::MissingKey),
+    }
+});
+
+#[derive(Clone, Error, Debug, PartialEq, Eq)]
+pub enum EncryptionError {
+    #[error("WORKSPACE_ENCRYPTION_KEY is not set, it must hold a base64 encoded 256 bits key, i.e: generated with `openssl rand -base64 32`")]
+    MissingKey,
+    #[error("Invalid workspace encryption key: {raw_error_message}")]
+    InvalidKey { raw_error_message: String },
+    #[error("Cannot encrypt content")]
+    CannotEncrypt,
+    #[error("Cannot decrypt content: {raw_error_message}")]
+    CannotDecrypt { raw_error_message: String },
+    #[error("Cannot access encrypted file `{path}`: {raw_error_message}")]
+    CannotAccessFile { path: String, raw_error_message: String },
+}
+
+/// Authenticated encryption (AES-256-GCM) of the sensitive contents written to the workspace
+pub struct WorkspaceCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl WorkspaceCipher {
+    pub fn new(key: &[u8]) -> Result<Self, EncryptionError> {
+        let key = UnboundKey::new(&AES_256_GCM, key).map_err(|_| EncryptionError::InvalidKey {
+            raw_error_message: format!("key must be {KEY_LEN} bytes long, got {} bytes", key.len()),
+        })?;
+
+        Ok(WorkspaceCipher {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    pub fn from_base64_key(key: &str) -> Result<Self, EncryptionError> {
+        let key = general_purpose::STANDARD
+            .decode(key.trim())
+            .map_err(|e| EncryptionError::InvalidKey {
+                raw_error_message: e.to_string(),
+            })?;
+        WorkspaceCipher::new(&key)
+    }
+
+    pub fn new_ephemeral() -> Result<Self, EncryptionError> {
+        let mut key = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| EncryptionError::InvalidKey {
+                raw_error_message: "cannot generate random key".to_string(),
+            })?;
+        WorkspaceCipher::new(&key)
+    }
+
+    pub fn encrypt(&self, content: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng.fill(&mut nonce).map_err(|_| EncryptionError::CannotEncrypt)?;
+
+        let mut ciphertext = content.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut ciphertext)
+            .map_err(|_| EncryptionError::CannotEncrypt)?;
+
+        Ok([ENCRYPTED_CONTENT_HEADER, &nonce, &ciphertext].concat())
+    }
+
+    pub fn decrypt(&self, content: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let content = content
+            .strip_prefix(ENCRYPTED_CONTENT_HEADER)
+            .filter(|content| content.len() >= NONCE_LEN)
+            .ok_or_else(|| EncryptionError::CannotDecrypt {
+                raw_error_message: "content is not encrypted".to_string(),
+            })?;
+        let (nonce, ciphertext) = content.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| EncryptionError::CannotDecrypt {
+            raw_error_message: "invalid nonce".to_string(),
+        })?;
+
+        let mut content = ciphertext.to_vec();
+        let plaintext_len = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut content)
+            .map_err(|_| EncryptionError::CannotDecrypt {
+                raw_error_message: "content has been altered or encrypted with another key".to_string(),
+            })?
+            .len();
+        content.truncate(plaintext_len);
+
+        Ok(content)
+    }
+}
+
+pub fn is_encrypted(content: &[u8]) -> bool {
+    content.starts_with(ENCRYPTED_CONTENT_HEADER)
+}
+
+/// Whether the file has been written with `write_encrypted_file`, only its header is read
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut header = [0u8; ENCRYPTED_CONTENT_HEADER.len()];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|_| is_encrypted(&header))
+        .unwrap_or(false)
+}
+
+/// Cipher of the process, keyed from `WORKSPACE_ENCRYPTION_KEY_ENV_VAR`
+pub fn workspace_cipher() -> Result<&'static WorkspaceCipher, EncryptionError> {
+    WORKSPACE_CIPHER.as_ref().map_err(|e| e.clone())
+}
+
+/// Fails when the workspace encryption key is missing or invalid, to be checked at startup rather than by the first
+/// execution writing a secret
+pub fn check_workspace_encryption_key() -> Result<(), EncryptionError> {
+    workspace_cipher().map(|_| ())
+}
+
+pub fn write_encrypted_file(path: &Path, content: &[u8]) -> Result<(), EncryptionError> {
+    let encrypted_content = workspace_cipher()?.encrypt(content)?;
+    fs::write(path, encrypted_content).map_err(|e| EncryptionError::CannotAccessFile {
+        path: path.to_string_lossy().to_string(),
+        raw_error_message: e.to_string(),
+    })
+}
+
+/// Content of a file written with `write_encrypted_file`, decrypted in memory only
+pub fn read_encrypted_file(path: &Path) -> Result<Vec<u8>, EncryptionError> {
+    let content = fs::read(path).map_err(|e| EncryptionError::CannotAccessFile {
+        path: path.to_string_lossy().to_string(),
+        raw_error_message: e.to_string(),
+    })?;
+    workspace_cipher()?.decrypt(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let cipher = WorkspaceCipher::new_ephemeral().expect("Cannot create cipher");
+        let content = b"DATABASE_PASSWORD=super-secret";
+
+        let encrypted = cipher.encrypt(content).expect("Cannot encrypt");
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted
+            .windows(content.len())
+            .any(|window| window == content.as_slice()));
+        // a new nonce is used for each encryption
+        assert_ne!(encrypted, cipher.encrypt(content).expect("Cannot encrypt"));
+        assert_eq!(cipher.decrypt(&encrypted).expect("Cannot decrypt"), content.to_vec());
+
+        // altered content or another key
+        let mut altered = encrypted.clone();
+        let last = altered.len() - 1;
+        altered[last] ^= 1;
+        assert!(cipher.decrypt(&altered).is_err());
+        let other_cipher = WorkspaceCipher::new_ephemeral().expect("Cannot create cipher");
+        assert!(other_cipher.decrypt(&encrypted).is_err());
+        assert!(cipher.decrypt(content).is_err());
+    }
+
+    #[test]
+    fn test_from_base64_key() {
+        let key = general_purpose::STANDARD.encode([7u8; KEY_LEN]);
+        let cipher = WorkspaceCipher::from_base64_key(&key).expect("Cannot create cipher");
+        let same_key_cipher = WorkspaceCipher::from_base64_key(&key).expect("Cannot create cipher");
+        let encrypted = cipher.encrypt(b"token").expect("Cannot encrypt");
+        assert_eq!(same_key_cipher.decrypt(&encrypted).expect("Cannot decrypt"), b"token".to_vec());
+
+        assert!(WorkspaceCipher::from_base64_key("not base64 !").is_err());
+        assert!(WorkspaceCipher::from_base64_key(&general_purpose::STANDARD.encode([7u8; 16])).is_err());
+    }
+
+    #[test]
+    fn test_write_read_encrypted_file() {
+        let dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let path = dir.path().join("payload.json");
+
+        write_encrypted_file(&path, b"{\"token\": \"secret\"}").expect("Cannot write file");
+        assert!(is_encrypted(&fs::read(&path).expect("Cannot read file")));
+        assert!(is_encrypted_file(&path));
+        assert!(!is_encrypted_file(&dir.path().join("missing.json")));
+        assert_eq!(
+            read_encrypted_file(&path).expect("Cannot read encrypted file"),
+            b"{\"token\": \"secret\"}".to_vec()
+        );
+    }
+}
@@ -6,6 +6,7 @@ use crate::build_platform::BuildPlatform;
 use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::CloudProvider;
 use crate::container_registry::ContainerRegistry;
+use crate::deployment_hook::DeploymentHooks;
 use crate::dns_provider::DnsProvider;
 use crate::errors::EngineError;
 use crate::io_models::context::Context;
@@ -42,6 +43,7 @@ pub struct InfrastructureContext {
     dns_provider: Arc<dyn DnsProvider>,
     kubernetes: Box<dyn Kubernetes>,
     metrics_registry: Box<dyn MetricsRegistry>,
+    deployment_hooks: DeploymentHooks,
 }
 
 impl InfrastructureContext {
@@ -62,6 +64,7 @@ impl InfrastructureContext {
             dns_provider,
             kubernetes,
             metrics_registry,
+            deployment_hooks: DeploymentHooks::default(),
         }
     }
 
@@ -97,6 +100,14 @@ impl InfrastructureContext {
         self.metrics_registry.borrow()
     }
 
+    pub fn deployment_hooks(&self) -> &DeploymentHooks {
+        &self.deployment_hooks
+    }
+
+    pub fn set_deployment_hooks(&mut self, deployment_hooks: DeploymentHooks) {
+        self.deployment_hooks = deployment_hooks;
+    }
+
     pub fn is_valid(&self) -> Result<(), Box<EngineConfigError>> {
         if let Err(e) = self.cloud_provider.is_valid() {
             return Err(Box::new(EngineConfigError::CloudProviderNotValid(*e)));
@@ -1,23 +1,32 @@
 use super::Task;
 use crate::build_platform;
+use crate::build_platform::base_image_mirror::BaseImageMirror;
+use crate::build_platform::build_cache::BuildCache;
+use crate::build_platform::sbom::Sbom;
 use crate::build_platform::{to_build_error, BuildError, BuildPlatform};
 use crate::cloud_provider::aws::regions::AwsRegion;
 use crate::cloud_provider::environment::Environment;
+use crate::cloud_provider::kubeconfig_helper::get_bucket_name;
 use crate::cloud_provider::service;
 use crate::cloud_provider::service::Service;
+use crate::cluster_state_migrations::migrate_cluster_state;
 use crate::cmd::command::CommandKiller;
 use crate::cmd::docker;
 use crate::cmd::docker::{BuilderHandle, Docker};
 use crate::container_registry::errors::ContainerRegistryError;
 use crate::container_registry::{to_engine_error, ContainerRegistry};
 use crate::deployment_action::deploy_environment::EnvironmentDeployment;
+use crate::deployment_freeze::{enforce_deployment_freeze, FreezeScope};
+use crate::deployment_hook::{DeploymentHookStage, DeploymentHooks};
 use crate::deployment_report::logger::EnvLogger;
 use crate::engine::InfrastructureContext;
 use crate::engine_task::qovery_api::QoveryApi;
 use crate::errors::{EngineError, ErrorMessageVerbosity};
-use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, MessageCode, Stage};
+use crate::fs::workspace_directory;
 use crate::io_models::context::Context;
 use crate::io_models::engine_request::EnvironmentEngineRequest;
+use crate::io_models::environment::RemoteBuilder;
 use crate::io_models::Action;
 use crate::logger::Logger;
 use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepRecordHandle, StepStatus};
@@ -44,6 +53,8 @@ pub struct EnvironmentTask {
     logger: Box<dyn Logger>,
     metrics_registry: Box<dyn MetricsRegistry>,
     qovery_api: Arc<dyn QoveryApi>,
+    deployment_hooks: DeploymentHooks,
+    max_parallel_builds: Option<NonZeroUsize>,
     span: tracing::Span,
     is_terminated: (RwLock<Option<broadcast::Sender<()>>>, broadcast::Receiver<()>),
 }
@@ -75,6 +86,8 @@ impl EnvironmentTask {
             metrics_registry,
             cancel_requested: Arc::new(AtomicBool::new(false)),
             qovery_api: Arc::from(qovery_api),
+            deployment_hooks: DeploymentHooks::default(),
+            max_parallel_builds: None,
             span,
             is_terminated: {
                 let (tx, rx) = broadcast::channel(1);
@@ -83,6 +96,18 @@ impl EnvironmentTask {
         }
     }
 
+    /// Runs the hooks of the integrator around the build, terraform, helm and readiness stages of the deployment
+    pub fn with_deployment_hooks(mut self, deployment_hooks: DeploymentHooks) -> Self {
+        self.deployment_hooks = deployment_hooks;
+        self
+    }
+
+    /// Caps the builds of the environment running at the same time, below the parallelism it asks for
+    pub fn with_max_parallel_builds(mut self, max_parallel_builds: Option<NonZeroUsize>) -> Self {
+        self.max_parallel_builds = max_parallel_builds;
+        self
+    }
+
     fn info_context(&self) -> Context {
         Context::new(
             self.request.organization_long_id,
@@ -97,17 +122,20 @@ impl EnvironmentTask {
             self.qovery_api.clone(),
             self.request.event_details(),
         )
+        .with_max_parallel_builds(self.max_parallel_builds)
     }
 
     // FIXME: Remove EngineConfig type, there is no use for it
     // merge it with DeploymentTarget type
     fn infrastructure_context(&self) -> Result<InfrastructureContext, Box<EngineError>> {
-        self.request.engine(
+        let mut infra_ctx = self.request.engine(
             &self.info_context(),
             self.request.event_details(),
             self.logger.clone(),
             self.metrics_registry.clone(),
-        )
+        )?;
+        infra_ctx.set_deployment_hooks(self.deployment_hooks.clone());
+        Ok(infra_ctx)
     }
 
     fn _is_canceled(&self) -> bool {
@@ -124,6 +152,7 @@ impl EnvironmentTask {
         option: &DeploymentOption,
         infra_ctx: &InfrastructureContext,
         max_build_in_parallel: usize,
+        remote_builder: Option<&RemoteBuilder>,
         env_logger: impl Fn(String),
         mk_logger: impl Fn(&dyn Service) -> EnvLogger + Send + Sync,
         should_abort: &(dyn Fn() -> bool + Send + Sync),
@@ -131,7 +160,7 @@ impl EnvironmentTask {
         // Only keep services that have something to build
         let mut build_needs_buildpacks = false;
         let metrics_registry: Arc<dyn MetricsRegistry> = Arc::from(infra_ctx.metrics_registry().clone_dyn());
-        let services = services
+        let mut services = services
             .into_iter()
             .filter(|srv| {
                 if let Some(build) = srv.build() {
@@ -149,11 +178,16 @@ impl EnvironmentTask {
             Some(srv) => srv,
         };
 
+        let max_build_in_parallel = match infra_ctx.context().max_parallel_builds() {
+            Some(max_parallel_builds) => min(max_build_in_parallel, max_parallel_builds.get()),
+            None => max_build_in_parallel,
+        };
         let provision_builder =
             metrics_registry.start_record(environment_id, StepLabel::Environment, StepName::ProvisionBuilder);
         let builder_handle = match Self::provision_builder(
             infra_ctx,
             max_build_in_parallel,
+            remote_builder,
             env_logger,
             &should_abort,
             build_needs_buildpacks,
@@ -193,10 +227,125 @@ impl EnvironmentTask {
         let cr_registry = infra_ctx.container_registry();
         let build_platform = infra_ctx.build_platform();
 
+        // Public base images are copied in a repository shared by all the builds of the cluster
+        let advanced_settings = infra_ctx.kubernetes().advanced_settings();
+        if advanced_settings.registry_base_image_mirroring_enabled {
+            let repository_name = format!("qovery-base-images-{}", infra_ctx.kubernetes().long_id());
+            cr_registry
+                .create_repository(&repository_name, img_retention_time_sec, resource_ttl)
+                .map_err(cr_to_engine_error)?;
+            let base_image_mirror = BaseImageMirror {
+                registry_url: cr_registry.registry_info().endpoint.clone(),
+                image_name: cr_registry.registry_info().get_image_name(&repository_name),
+                source_registries: advanced_settings.registry_base_image_mirroring_registries.clone(),
+            };
+            for service in services.iter_mut() {
+                if let Some(build) = service.build_mut() {
+                    build.base_image_mirror = Some(base_image_mirror.clone());
+                }
+            }
+        }
+        if let Some(image_signing_key) = &advanced_settings.registry_image_signing_key {
+            for service in services.iter_mut() {
+                if let Some(build) = service.build_mut() {
+                    build.image_signing_key = Some(image_signing_key.clone());
+                }
+            }
+        }
+
+        // BuildKit caches are imported before the builds, and exported once they are all done, from this thread
+        let cache_object_storage = infra_ctx
+            .kubernetes()
+            .object_storage()
+            .filter(|_| advanced_settings.build_object_storage_cache_enabled);
+        let mut build_caches: Vec<(BuildCache, EnvLogger)> = vec![];
+        if let Some(object_storage) = cache_object_storage {
+            let bucket_name = get_bucket_name(infra_ctx.kubernetes().long_id());
+            for service in services.iter_mut() {
+                let logger = mk_logger(&**service);
+                let cache_dir = match workspace_directory(
+                    infra_ctx.context().workspace_root_dir(),
+                    infra_ctx.context().execution_id(),
+                    format!("build-cache/{}", service.long_id()),
+                ) {
+                    Ok(cache_dir) => cache_dir,
+                    Err(err) => {
+                        logger.send_warning(format!("⚠️ Build cache disabled, cannot create its directory: {err}"));
+                        continue;
+                    }
+                };
+                let Some(build) = service.build_mut() else {
+                    continue;
+                };
+                if build.use_buildpacks() {
+                    continue;
+                }
+
+                let mut build_cache = BuildCache::new(bucket_name.clone(), &build.image.service_long_id, cache_dir);
+                if build.bust_cache || build.disable_cache {
+                    logger.send_progress("🧹 Build cache busted, the build starts without it".to_string());
+                } else {
+                    match build_cache.import(object_storage) {
+                        Ok(true) => logger.send_progress("♻️ Build cache restored from object storage".to_string()),
+                        Ok(false) => {}
+                        Err(err) => logger.send_warning(format!("⚠️ Cannot restore build cache: {err}")),
+                    }
+                }
+                build.cache = Some(build_cache.clone());
+                build_caches.push((build_cache, logger));
+            }
+        }
+
+        // SBOMs are generated by the builds, and uploaded once they are all done, along the caches
+        let sbom_format = advanced_settings
+            .build_sbom_format
+            .filter(|_| infra_ctx.kubernetes().object_storage().is_some());
+        let mut sboms: Vec<(Sbom, EnvLogger)> = vec![];
+        if let Some(sbom_format) = sbom_format {
+            let bucket_name = get_bucket_name(infra_ctx.kubernetes().long_id());
+            for service in services.iter_mut() {
+                let logger = mk_logger(&**service);
+                let Some(build) = service.build_mut() else {
+                    continue;
+                };
+                let sbom_dir = match workspace_directory(
+                    infra_ctx.context().workspace_root_dir(),
+                    infra_ctx.context().execution_id(),
+                    format!("sbom/{}", build.image.service_long_id),
+                ) {
+                    Ok(sbom_dir) => sbom_dir,
+                    Err(err) => {
+                        logger.send_warning(format!("⚠️ SBOM disabled, cannot create its directory: {err}"));
+                        continue;
+                    }
+                };
+
+                let sbom = Sbom::new(
+                    sbom_format,
+                    bucket_name.clone(),
+                    &build.image.service_long_id,
+                    infra_ctx.context().execution_id(),
+                    sbom_dir,
+                );
+                build.sbom = Some(sbom.clone());
+                sboms.push((sbom, logger));
+            }
+        }
+
         services.iter().for_each(|service| {
             metrics_registry.start_record(*service.long_id(), StepLabel::Service, StepName::BuildQueueing);
         });
 
+        // Output of the builds running in parallel is interleaved, each line tells which service it comes from
+        let interleaved_builds = builder_handle.nb_builder.get() > 1 && services.len() > 1;
+        let mk_build_logger = |service: &dyn Service| {
+            let logger = mk_logger(service);
+            match interleaved_builds {
+                true => logger.with_prefix(format!("[{}] ", service.name())),
+                false => logger,
+            }
+        };
+
         let build_tasks = services
             .into_iter()
             .map(|service| {
@@ -210,7 +359,7 @@ impl EnvironmentTask {
                         img_retention_time_sec,
                         resource_ttl,
                         cr_to_engine_error,
-                        &mk_logger,
+                        &mk_build_logger,
                         metrics_registry.clone(),
                         &should_abort,
                     )
@@ -219,12 +368,43 @@ impl EnvironmentTask {
             .collect_vec();
 
         let builder_threadpool = BuilderThreadPool::new();
-        builder_threadpool.run(build_tasks, builder_handle.nb_builder, &should_abort_flag, should_abort)
+        let build_result =
+            builder_threadpool.run(build_tasks, builder_handle.nb_builder, &should_abort_flag, should_abort);
+
+        // Caches of the successful builds are kept even if another one failed
+        if let Some(object_storage) = cache_object_storage {
+            for (build_cache, logger) in build_caches {
+                match build_cache.export(object_storage) {
+                    Ok(Some(size)) => logger.send_progress(format!(
+                        "♻️ Build cache saved to object storage ({} MiB)",
+                        size / (1024 * 1024)
+                    )),
+                    Ok(None) => {}
+                    Err(err) => logger.send_warning(format!("⚠️ Cannot save build cache: {err}")),
+                }
+            }
+        }
+        if let Some(object_storage) = infra_ctx.kubernetes().object_storage() {
+            for (sbom, logger) in sboms {
+                match sbom.upload(object_storage) {
+                    Ok(Some(_)) => logger.send_progress_message(EventMessage::new_from_code(
+                        MessageCode::SbomAttached,
+                        &[("format", sbom.format.to_string()), ("location", sbom.location())],
+                        None,
+                    )),
+                    Ok(None) => {}
+                    Err(err) => logger.send_warning(format!("⚠️ Cannot upload SBOM: {err}")),
+                }
+            }
+        }
+
+        build_result
     }
 
     fn provision_builder(
         infra_ctx: &InfrastructureContext,
         max_build_in_parallel: usize,
+        remote_builder: Option<&RemoteBuilder>,
         env_logger: impl Fn(String),
         should_abort: &(dyn Fn() -> bool + Send + Sync),
         build_needs_builpacks: bool,
@@ -241,6 +421,27 @@ impl EnvironmentTask {
                 NonZeroUsize::new(max(min(max_build_in_parallel, services.len()), 1)).unwrap()
             };
 
+            // Builds are delegated to a BuildKit daemon we do not manage, its resources are its own
+            if let Some(remote_builder) = remote_builder {
+                env_logger(format!(
+                    "🧑‍🏭 Using remote BuildKit builder {} for {nb_builder} parallel build",
+                    remote_builder.endpoint
+                ));
+                return infra_ctx
+                    .context()
+                    .docker
+                    .spawn_remote_builder(nb_builder, remote_builder, &CommandKiller::from_cancelable(should_abort))
+                    .map_err(|err| {
+                        env_logger("❌ Cannot connect to remote BuildKit builder. Aborting".to_string());
+                        let build_error = to_build_error(first_service.long_id().to_string(), err);
+                        Box::new(build_platform::to_engine_error(
+                            first_service.get_event_details(Stage::Environment(EnvironmentStep::BuiltError)),
+                            build_error,
+                            format!("Cannot connect to remote BuildKit builder {}.", remote_builder.endpoint),
+                        ))
+                    });
+            }
+
             // Compute max resources needed for the builders
             let (max_cpu, max_ram) = services.iter().fold((2000u32, 2u32), |(cpu, ram), s| {
                 s.build()
@@ -395,25 +596,33 @@ impl EnvironmentTask {
             }
 
             let logger = Arc::new(infra_ctx.kubernetes().logger().clone_dyn());
+            let remote_builder = environment.remote_builder.clone();
             let services_to_build: Vec<&mut dyn Service> = environment
                 .applications
                 .iter_mut()
                 .map(|app| app.as_service_mut())
                 .chain(environment.jobs.iter_mut().map(|job| job.as_service_mut()))
+                // pods are restarted with the image they already run, there is nothing to build
+                .filter(|srv| *srv.action() != service::Action::Restart)
                 .collect();
-            Self::build_and_push_services(
-                environment.long_id,
-                services_to_build,
-                &DeploymentOption {
-                    force_build: false,
-                    force_push: false,
-                },
-                infra_ctx,
-                environment.max_parallel_build as usize,
-                env_logger,
-                |srv: &dyn Service| EnvLogger::new(srv, EnvironmentStep::Build, logger.clone()),
-                should_abort,
-            )?;
+            infra_ctx
+                .deployment_hooks()
+                .run_stage(DeploymentHookStage::Build, &event_details, || {
+                    Self::build_and_push_services(
+                        environment.long_id,
+                        services_to_build,
+                        &DeploymentOption {
+                            force_build: false,
+                            force_push: false,
+                        },
+                        infra_ctx,
+                        environment.max_parallel_build as usize,
+                        remote_builder.as_ref(),
+                        env_logger,
+                        |srv: &dyn Service| EnvLogger::new(srv, EnvironmentStep::Build, logger.clone()),
+                        should_abort,
+                    )
+                })?;
 
             if should_abort() {
                 return Err(Box::new(EngineError::new_task_cancellation_requested(event_details)));
@@ -421,7 +630,7 @@ impl EnvironmentTask {
 
             let mut env_deployment = EnvironmentDeployment::new(infra_ctx, &environment, should_abort, logger.clone())?;
             let deployment_ret = match environment.action {
-                service::Action::Create => env_deployment.on_create(),
+                service::Action::Create | service::Action::TriggerNow => env_deployment.on_create(),
                 service::Action::Pause => env_deployment.on_pause(),
                 service::Action::Delete => env_deployment.on_delete(),
                 service::Action::Restart => env_deployment.on_restart(),
@@ -445,7 +654,9 @@ impl EnvironmentTask {
             .chain(environment.routers.iter().map(|x| x.as_service()))
             .chain(environment.databases.iter().map(|x| x.as_service()))
             .chain(environment.jobs.iter().map(|x| x.as_service()))
-            .chain(environment.helm_charts.iter().map(|x| x.as_service()));
+            .chain(environment.helm_charts.iter().map(|x| x.as_service()))
+            .chain(environment.kustomizations.iter().map(|x| x.as_service()))
+            .chain(environment.terraform_services.iter().map(|x| x.as_service()));
 
         for service in services {
             if deployed_services.contains(service.long_id()) {
@@ -487,6 +698,20 @@ impl EnvironmentTask {
                     .helms
                     .iter()
                     .flat_map(|x| x.environment_vars_with_infos.values()),
+            )
+            .chain(
+                request
+                    .target_environment
+                    .kustomizations
+                    .iter()
+                    .flat_map(|x| x.environment_vars_with_infos.values()),
+            )
+            .chain(
+                request
+                    .target_environment
+                    .terraform_services
+                    .iter()
+                    .flat_map(|x| x.environment_vars_with_infos.values()),
             );
 
         let service_secrets = services_secrets.filter_map(|v| {
@@ -546,12 +771,12 @@ impl Task for EnvironmentTask {
 
         self.logger.log(EngineEvent::Info(
             self.get_event_details(EnvironmentStep::Start),
-            EventMessage::new("🚀 Qovery Engine starts to execute the deployment".to_string(), None),
+            EventMessage::new_from_code(MessageCode::DeploymentStarted, &[], None),
         ));
         let guard = scopeguard::guard((), |_| {
             self.logger.log(EngineEvent::Info(
                 self.get_event_details(EnvironmentStep::Terminated),
-                EventMessage::new("Qovery Engine has terminated the deployment".to_string(), None),
+                EventMessage::new_from_code(MessageCode::DeploymentTerminated, &[], None),
             ));
             let Some(is_terminated_tx) = self.is_terminated.0.write().unwrap().take() else {
                 return;
@@ -559,6 +784,16 @@ impl Task for EnvironmentTask {
             let _ = is_terminated_tx.send(());
         });
 
+        if let Err(err) = super::enforce_workspace_quota(
+            &self.workspace_root_dir,
+            &self.request.id,
+            self.get_event_details(EnvironmentStep::Start),
+            self.logger.as_ref(),
+        ) {
+            self.logger.log(EngineEvent::Error(*err, None));
+            return;
+        }
+
         let infra_context = match self.infrastructure_context() {
             Ok(infra_ctx) => infra_ctx,
             Err(err) => {
@@ -573,6 +808,27 @@ impl Task for EnvironmentTask {
             .to_service_action()
             .to_environment_step();
         let event_details = self.get_event_details(env_step);
+
+        if let Err(err) = enforce_deployment_freeze(
+            &infra_context,
+            &[
+                FreezeScope::Cluster,
+                FreezeScope::Environment(self.request.target_environment.long_id),
+            ],
+            self.request.deployment_freeze_override.as_ref(),
+            false,
+            event_details.clone(),
+            self.logger.as_ref(),
+        ) {
+            self.logger.log(EngineEvent::Error(*err, None));
+            return;
+        }
+
+        if let Err(err) = migrate_cluster_state(&infra_context, event_details.clone(), self.logger.as_ref()) {
+            self.logger.log(EngineEvent::Error(*err, None));
+            return;
+        }
+
         let environment = match self.request.target_environment.to_environment_domain(
             infra_context.context(),
             infra_context.cloud_provider(),
@@ -602,7 +858,9 @@ impl Task for EnvironmentTask {
             .chain(environment.routers.iter().map(|x| x.as_service().long_id()))
             .chain(environment.databases.iter().map(|x| x.as_service().long_id()))
             .chain(environment.jobs.iter().map(|x| x.as_service().long_id()))
-            .chain(environment.helm_charts.iter().map(|x| x.as_service().long_id()));
+            .chain(environment.helm_charts.iter().map(|x| x.as_service().long_id()))
+            .chain(environment.kustomizations.iter().map(|x| x.as_service().long_id()))
+            .chain(environment.terraform_services.iter().map(|x| x.as_service().long_id()));
 
         let record = metrics_registry.start_record(environment.long_id, StepLabel::Environment, StepName::Total);
         let service_records: Vec<StepRecordHandle> = service_ids
@@ -616,33 +874,32 @@ impl Task for EnvironmentTask {
         Self::stop_total_steps_records(&deployment_ret, record, service_records);
 
         match (&self.request.action, deployment_ret) {
-            (Action::Create, Ok(())) => self.logger.log(EngineEvent::Info(
+            (Action::Create | Action::TriggerNow, Ok(())) => self.logger.log(EngineEvent::Info(
                 self.get_event_details(EnvironmentStep::Deployed),
-                EventMessage::new("❤️ Deployment succeeded ❤️".to_string(), None),
+                EventMessage::new_from_code(MessageCode::DeploymentSucceeded, &[], None),
             )),
             (Action::Pause, Ok(())) => self.logger.log(EngineEvent::Info(
                 self.get_event_details(EnvironmentStep::Paused),
-                EventMessage::new("⏸️ Environment is paused".to_string(), None),
+                EventMessage::new_from_code(MessageCode::EnvironmentPaused, &[], None),
             )),
             (Action::Delete, Ok(())) => self.logger.log(EngineEvent::Info(
                 self.get_event_details(EnvironmentStep::Deleted),
-                EventMessage::new("🗑️ Environment is deleted".to_string(), None),
+                EventMessage::new_from_code(MessageCode::EnvironmentDeleted, &[], None),
             )),
             (Action::Restart, Ok(_)) => self.logger.log(EngineEvent::Info(
                 self.get_event_details(EnvironmentStep::Restarted),
-                EventMessage::new("⟳️ Environment is restarted".to_string(), None),
+                EventMessage::new_from_code(MessageCode::EnvironmentRestarted, &[], None),
             )),
             (_, Err(err)) if err.tag().is_cancel() => self.logger.log(EngineEvent::Info(
                 self.get_event_details(EnvironmentStep::Cancelled),
-                EventMessage::new("🚫 Deployment has been canceled at user request 🚫".to_string(), None),
+                EventMessage::new_from_code(MessageCode::DeploymentCancelled, &[], None),
             )),
-            (Action::Create, Err(err)) => {
+            (Action::Create | Action::TriggerNow, Err(err)) => {
                 self.logger.log(EngineEvent::Info(
                     self.get_event_details(EnvironmentStep::DeployedError),
-                    EventMessage::new(
-                        "💣 Deployment aborted following a failure to deploy a service. This is a general/global message. Look at your services deployment status to know which one made the deployment fail"
-                            .trim()
-                            .to_string(),
+                    EventMessage::new_from_code(
+                        MessageCode::DeploymentFailed,
+                        &[],
                         Some(err.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
                     ),
                 ));
@@ -650,8 +907,9 @@ impl Task for EnvironmentTask {
             (Action::Pause, Err(err)) => {
                 self.logger.log(EngineEvent::Info(
                     self.get_event_details(EnvironmentStep::PausedError),
-                    EventMessage::new(
-                        "💣 Environment failed to be paused".to_string(),
+                    EventMessage::new_from_code(
+                        MessageCode::EnvironmentPauseFailed,
+                        &[],
                         Some(err.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
                     ),
                 ));
@@ -659,8 +917,9 @@ impl Task for EnvironmentTask {
             (Action::Delete, Err(err)) => {
                 self.logger.log(EngineEvent::Info(
                     self.get_event_details(EnvironmentStep::DeletedError),
-                    EventMessage::new(
-                        "💣 Environment failed to be deleted".to_string(),
+                    EventMessage::new_from_code(
+                        MessageCode::EnvironmentDeleteFailed,
+                        &[],
                         Some(err.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
                     ),
                 ));
@@ -668,8 +927,9 @@ impl Task for EnvironmentTask {
             (Action::Restart, Err(err)) => {
                 self.logger.log(EngineEvent::Info(
                     self.get_event_details(EnvironmentStep::RestartedError),
-                    EventMessage::new(
-                        "💣 Environment failed to be restarted".to_string(),
+                    EventMessage::new_from_code(
+                        MessageCode::EnvironmentRestartFailed,
+                        &[],
                         Some(err.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
                     ),
                 ));
@@ -709,12 +969,7 @@ impl Task for EnvironmentTask {
         self.cancel_requested.store(true, Ordering::Relaxed);
         self.logger.log(EngineEvent::Info(
             self.get_event_details(EnvironmentStep::Cancel),
-            EventMessage::new(r#"
-                    🚫 Cancel received, deployment is going to stop.
-                    This may take a while, as a safe point need to be reached.
-                    Some operation cannot be stopped (i.e: terraform actions) and need to be completed before stopping the deployment
-                    "#.trim().to_string()
-                              , None),
+            EventMessage::new_from_code(MessageCode::DeploymentCancelRequested, &[], None),
         ));
         true
     }
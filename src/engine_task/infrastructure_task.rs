@@ -1,11 +1,16 @@
 use super::Task;
 use crate::cloud_provider::aws::regions::AwsRegion;
+use crate::cloud_provider::support_bundle::attach_support_bundle;
+use crate::cluster_state_migrations::migrate_cluster_state;
 use crate::cmd::docker::Docker;
+use crate::deployment_freeze::{enforce_deployment_freeze, FreezeScope};
+use crate::deployment_hook::{DeploymentHookStage, DeploymentHooks};
 use crate::engine::EngineConfigError;
 use crate::engine_task::qovery_api::QoveryApi;
 use crate::errors::EngineError;
 use crate::events::Stage::Infrastructure;
-use crate::events::{EngineEvent, EventDetails, EventMessage, InfrastructureStep, Transmitter};
+use crate::events::{EngineEvent, EventDetails, EventMessage, InfrastructureStep, MessageCode, Transmitter};
+use crate::heartbeat::{Heartbeat, StepDurationHistory};
 use crate::io_models::context::Context;
 use crate::io_models::engine_request::InfrastructureEngineRequest;
 use crate::io_models::{Action, QoveryIdentifier};
@@ -24,6 +29,7 @@ pub struct InfrastructureTask {
     logger: Box<dyn Logger>,
     metrics_registry: Box<dyn MetricsRegistry>,
     qovery_api: Arc<dyn QoveryApi>,
+    deployment_hooks: DeploymentHooks,
     span: tracing::Span,
     is_terminated: (RwLock<Option<broadcast::Sender<()>>>, broadcast::Receiver<()>),
 }
@@ -53,6 +59,7 @@ impl InfrastructureTask {
             logger,
             metrics_registry,
             qovery_api: Arc::from(qovery_api),
+            deployment_hooks: DeploymentHooks::default(),
             span,
             is_terminated: {
                 let (tx, rx) = broadcast::channel(1);
@@ -61,6 +68,12 @@ impl InfrastructureTask {
         }
     }
 
+    /// Runs the hooks of the integrator around the terraform stage of the cluster operation
+    pub fn with_deployment_hooks(mut self, deployment_hooks: DeploymentHooks) -> Self {
+        self.deployment_hooks = deployment_hooks;
+        self
+    }
+
     fn info_context(&self) -> Context {
         Context::new(
             self.request.organization_long_id,
@@ -104,9 +117,13 @@ impl InfrastructureTask {
                 Action::Pause => InfrastructureStep::PauseError,
                 Action::Delete => InfrastructureStep::DeleteError,
                 Action::Restart => InfrastructureStep::RestartedError,
+                Action::TriggerNow => InfrastructureStep::CreateError,
             };
-            let event_message =
-                EventMessage::new_from_safe(format!("Kubernetes cluster failure {}", &infrastructure_step));
+            let event_message = EventMessage::new_from_code(
+                MessageCode::InfrastructureStepFailed,
+                &[("step", infrastructure_step.to_string())],
+                None,
+            );
 
             let engine_event = EngineEvent::Error(
                 engine_error.clone_engine_error_with_stage(Infrastructure(infrastructure_step)),
@@ -120,9 +137,13 @@ impl InfrastructureTask {
                 Action::Pause => InfrastructureStep::Paused,
                 Action::Delete => InfrastructureStep::Deleted,
                 Action::Restart => InfrastructureStep::RestartedError,
+                Action::TriggerNow => InfrastructureStep::CreateError,
             };
-            let event_message =
-                EventMessage::new_from_safe(format!("Kubernetes cluster successfully {}", &infrastructure_step));
+            let event_message = EventMessage::new_from_code(
+                MessageCode::InfrastructureStepSucceeded,
+                &[("step", infrastructure_step.to_string())],
+                None,
+            );
             let engine_event = EngineEvent::Info(
                 EventDetails::new(
                     Some(self.request.cloud_provider.kind.clone()),
@@ -157,12 +178,12 @@ impl Task for InfrastructureTask {
 
         self.logger.log(EngineEvent::Info(
             self.get_event_details(InfrastructureStep::Start),
-            EventMessage::new("Qovery Engine has started the infrastructure deployment".to_string(), None),
+            EventMessage::new_from_code(MessageCode::InfrastructureStarted, &[], None),
         ));
         let guard = scopeguard::guard((), |_| {
             self.logger.log(EngineEvent::Info(
                 self.get_event_details(InfrastructureStep::Terminated),
-                EventMessage::new("Qovery Engine has terminated the infrastructure deployment".to_string(), None),
+                EventMessage::new_from_code(MessageCode::InfrastructureTerminated, &[], None),
             ));
             let Some(is_terminated_tx) = self.is_terminated.0.write().unwrap().take() else {
                 return;
@@ -170,7 +191,17 @@ impl Task for InfrastructureTask {
             let _ = is_terminated_tx.send(());
         });
 
-        let engine = match self.request.engine(
+        if let Err(err) = super::enforce_workspace_quota(
+            &self.workspace_root_dir,
+            &self.request.id,
+            self.get_event_details(InfrastructureStep::Start),
+            self.logger.as_ref(),
+        ) {
+            self.send_infrastructure_progress(self.logger.clone(), Some(*err));
+            return;
+        }
+
+        let mut engine = match self.request.engine(
             &self.info_context(),
             self.request.event_details(),
             self.logger.clone(),
@@ -182,6 +213,7 @@ impl Task for InfrastructureTask {
                 return;
             }
         };
+        engine.set_deployment_hooks(self.deployment_hooks.clone());
 
         // check and init the connection to all services
         let mut tx = match Transaction::new(&engine) {
@@ -199,14 +231,65 @@ impl Task for InfrastructureTask {
             }
         };
 
+        if let Err(err) = enforce_deployment_freeze(
+            &engine,
+            &[FreezeScope::Cluster],
+            self.request.deployment_freeze_override.as_ref(),
+            // creating a cluster, or pausing and deleting a broken one, must not depend on reaching it
+            matches!(self.request.action, Action::Create | Action::Pause | Action::Delete),
+            self.request.event_details(),
+            self.logger.as_ref(),
+        ) {
+            self.send_infrastructure_progress(self.logger.clone(), Some(*err));
+            return;
+        }
+
+        if let Err(err) = migrate_cluster_state(&engine, self.request.event_details(), self.logger.as_ref()) {
+            self.send_infrastructure_progress(self.logger.clone(), Some(*err));
+            return;
+        }
+
         let _ = match self.request.action {
             Action::Create => tx.create_kubernetes(),
             Action::Pause => tx.pause_kubernetes(),
             Action::Delete => tx.delete_kubernetes(),
             Action::Restart => tx.restart_kubernetes(),
+            Action::TriggerNow => tx.trigger_kubernetes(),
+        };
+
+        let (infrastructure_step, operation) = match self.request.action {
+            Action::Create | Action::TriggerNow => (InfrastructureStep::Create, "creation"),
+            Action::Pause => (InfrastructureStep::Pause, "pause"),
+            Action::Delete => (InfrastructureStep::Delete, "deletion"),
+            Action::Restart => (InfrastructureStep::Restart, "restart"),
         };
+        let heartbeat = Heartbeat::start(
+            &format!("{} cluster {}", self.request.kubernetes.kind, operation),
+            self.get_event_details(infrastructure_step),
+            self.logger.clone(),
+            StepDurationHistory::new(&self.workspace_root_dir),
+        );
+        let transaction_result = engine.deployment_hooks().run_stage(
+            DeploymentHookStage::Terraform,
+            &self.get_event_details(infrastructure_step),
+            || match tx.commit() {
+                TransactionResult::Error(err)
+                    if matches!(self.request.action, Action::Create | Action::Delete | Action::TriggerNow) =>
+                {
+                    Err(Box::new(attach_support_bundle(
+                        engine.kubernetes(),
+                        engine.cloud_provider(),
+                        *err,
+                    )))
+                }
+                TransactionResult::Error(err) => Err(err),
+                transaction_result => Ok(transaction_result),
+            },
+        );
+        let transaction_result = transaction_result.unwrap_or_else(TransactionResult::Error);
+        heartbeat.stop(matches!(transaction_result, TransactionResult::Ok));
 
-        self.handle_transaction_result(self.logger.clone(), tx.commit());
+        self.handle_transaction_result(self.logger.clone(), transaction_result);
 
         // Uploading to S3 can take a lot of time, and might hit the core timeout
         // So we early drop the guard to notify core that the task is done
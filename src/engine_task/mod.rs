@@ -1,9 +1,14 @@
 use crate::cloud_provider::aws::regions::AwsRegion;
 
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EventDetails, EventMessage, MessageCode};
 use crate::io_models::context::Context;
 use crate::io_models::engine_request::Archive;
+use crate::logger::Logger;
 use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::retry::{RetryPolicy, RetryableObjectStorage};
 use crate::object_storage::ObjectStorage;
+use crate::workspace_quota::{WorkspaceQuota, WorkspaceQuotaError, WorkspaceQuotaManager};
 use std::borrow::Cow;
 use std::path::Path;
 use std::time::Duration;
@@ -12,6 +17,7 @@ use tokio::sync::broadcast;
 pub mod environment_task;
 pub mod infrastructure_task;
 pub mod qovery_api;
+pub mod traffic_failover_task;
 
 pub trait Task: Send + Sync {
     fn id(&self) -> &str;
@@ -30,6 +36,41 @@ fn basename(path: &str, sep: char) -> Cow<str> {
     }
 }
 
+/// Free the disk space of old executions before starting a new one, which is refused if the quota is still exceeded
+fn enforce_workspace_quota(
+    workspace_root_dir: &str,
+    execution_id: &str,
+    event_details: EventDetails,
+    logger: &dyn Logger,
+) -> Result<(), Box<EngineError>> {
+    let quota_manager = WorkspaceQuotaManager::new(workspace_root_dir, WorkspaceQuota::from_env());
+    match quota_manager.make_room_for(execution_id) {
+        Ok(report) if report.is_nearly_exceeded() => {
+            logger.log(EngineEvent::Warning(
+                event_details,
+                EventMessage::new_from_code(
+                    MessageCode::WorkspaceNearlyFull,
+                    &[("usage", report.usage.to_string()), ("quota", report.quota.to_string())],
+                    None,
+                ),
+            ));
+            Ok(())
+        }
+        Ok(_) => Ok(()),
+        Err(err @ WorkspaceQuotaError::QuotaExceeded { .. }) => {
+            Err(Box::new(EngineError::new_workspace_quota_exceeded(
+                event_details,
+                CommandError::new_from_safe_message(err.to_string()),
+            )))
+        }
+        // the quota is not enforced rather than failing the execution on a disk usage computation issue
+        Err(err) => {
+            error!("Cannot enforce workspace quota: {}", err);
+            Ok(())
+        }
+    }
+}
+
 fn upload_s3_file(
     context: &Context,
     archive: Option<&Archive>,
@@ -61,12 +102,15 @@ fn upload_s3_file(
     );
 
     // I am using this s3 object directly to avoid reinventing the wheel.
-    let s3 = crate::object_storage::s3::S3::new(
-        "archive-123abc".to_string(),
-        "archive-s3".to_string(),
-        archive.access_key_id.to_string(),
-        archive.secret_access_key.to_string(),
-        region,
+    let s3 = RetryableObjectStorage::new(
+        crate::object_storage::s3::S3::new(
+            "archive-123abc".to_string(),
+            "archive-s3".to_string(),
+            archive.access_key_id.to_string(),
+            archive.secret_access_key.to_string(),
+            region,
+        ),
+        RetryPolicy::default(),
     );
 
     match s3.put_object(archive.bucket_name.as_str(), object_key.as_str(), file_path) {
@@ -1,5 +1,6 @@
 use crate::cloud_provider::service::ServiceType;
 use crate::io_models::application::GitCredentials;
+use crate::janitor::ExistingOwners;
 use anyhow::anyhow;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -16,6 +17,9 @@ pub trait QoveryApi: Send + Sync {
     fn git_token(&self, service_type: ServiceType, service_id: &Uuid) -> anyhow::Result<GitCredentials>;
 
     fn update_cluster_credentials(&self, kubeconfig: String) -> anyhow::Result<()>;
+
+    /// Clusters and environments of the organization that still exist
+    fn existing_owners(&self, organization_long_id: &Uuid) -> anyhow::Result<ExistingOwners>;
 }
 
 pub struct FakeQoveryApi {}
@@ -32,6 +36,10 @@ impl QoveryApi for FakeQoveryApi {
     fn update_cluster_credentials(&self, _kubeconfig: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn existing_owners(&self, _organization_long_id: &Uuid) -> anyhow::Result<ExistingOwners> {
+        Err(anyhow!("not implemented"))
+    }
 }
 
 pub struct StaticQoveryApi {
@@ -54,4 +62,8 @@ impl QoveryApi for StaticQoveryApi {
     fn update_cluster_credentials(&self, _kubeconfig: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn existing_owners(&self, _organization_long_id: &Uuid) -> anyhow::Result<ExistingOwners> {
+        Err(anyhow!("not implemented"))
+    }
 }
@@ -0,0 +1,159 @@
+use super::Task;
+use crate::cmd::docker::Docker;
+use crate::deployment_action::traffic_failover::run_traffic_failover;
+use crate::engine_task::qovery_api::QoveryApi;
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EventMessage};
+use crate::io_models::context::Context;
+use crate::io_models::engine_request::TrafficFailoverEngineRequest;
+use crate::logger::Logger;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Monitors the clusters serving a DNS record until canceled, shifting the traffic away from the unhealthy ones
+pub struct TrafficFailoverTask {
+    workspace_root_dir: String,
+    lib_root_dir: String,
+    docker: Arc<Docker>,
+    request: TrafficFailoverEngineRequest,
+    logger: Box<dyn Logger>,
+    qovery_api: Arc<dyn QoveryApi>,
+    span: tracing::Span,
+    cancel_requested: Arc<AtomicBool>,
+    is_terminated: (RwLock<Option<broadcast::Sender<()>>>, broadcast::Receiver<()>),
+}
+
+impl TrafficFailoverTask {
+    pub fn new(
+        request: TrafficFailoverEngineRequest,
+        workspace_root_dir: String,
+        lib_root_dir: String,
+        docker: Arc<Docker>,
+        logger: Box<dyn Logger>,
+        qovery_api: Box<dyn QoveryApi>,
+    ) -> Self {
+        let span = info_span!(
+            "traffic_failover_task",
+            organization_id = request.organization_long_id.to_string(),
+            cluster_id = request.cluster_long_id.to_string(),
+            execution_id = request.id,
+        );
+
+        TrafficFailoverTask {
+            workspace_root_dir,
+            lib_root_dir,
+            docker,
+            request,
+            logger,
+            qovery_api: Arc::from(qovery_api),
+            span,
+            cancel_requested: Arc::new(AtomicBool::new(false)),
+            is_terminated: {
+                let (tx, rx) = broadcast::channel(1);
+                (RwLock::new(Some(tx)), rx)
+            },
+        }
+    }
+
+    fn info_context(&self) -> Context {
+        Context::new(
+            self.request.organization_long_id,
+            self.request.cluster_long_id,
+            self.request.id.to_string(),
+            self.workspace_root_dir.to_string(),
+            self.lib_root_dir.to_string(),
+            false,
+            self.request.features.clone(),
+            self.request.metadata.clone(),
+            self.docker.clone(),
+            self.qovery_api.clone(),
+            self.request.event_details(),
+        )
+    }
+
+    fn run_failover(&self) -> Result<(), Box<EngineError>> {
+        let event_details = self.request.event_details();
+        let dns_provider = self
+            .request
+            .dns_provider
+            .to_engine_dns_provider(self.info_context(), self.request.cluster_jwt_token.to_string())
+            .ok_or_else(|| {
+                EngineError::new_error_on_dns_provider_information(
+                    event_details.clone(),
+                    CommandError::new(
+                        "Invalid DNS provider information".to_string(),
+                        Some(format!("Invalid DNS provider information: {:?}", self.request.dns_provider)),
+                        None,
+                    ),
+                )
+            })?;
+
+        let mut failover = self.request.traffic_failover();
+        let cancel_checker = self.cancel_checker();
+        run_traffic_failover(
+            &mut failover,
+            &self.request.record_name,
+            dns_provider.as_ref(),
+            Duration::from_secs(self.request.probe_interval_in_seconds.max(1)),
+            &event_details,
+            self.logger.as_ref(),
+            cancel_checker.as_ref(),
+        )
+    }
+}
+
+impl Task for TrafficFailoverTask {
+    fn id(&self) -> &str {
+        self.request.id.as_str()
+    }
+
+    fn run(&self) {
+        let _span = self.span.enter();
+        info!(
+            "traffic failover task {} started for record {}",
+            self.id(),
+            self.request.record_name
+        );
+
+        self.logger.log(EngineEvent::Info(
+            self.request.event_details(),
+            EventMessage::new_from_safe(format!(
+                "Monitoring {} clusters serving `{}`",
+                self.request.endpoints.len(),
+                self.request.record_name
+            )),
+        ));
+        let _guard = scopeguard::guard((), |_| {
+            let Some(is_terminated_tx) = self.is_terminated.0.write().unwrap().take() else {
+                return;
+            };
+            let _ = is_terminated_tx.send(());
+        });
+
+        if let Err(err) = self.run_failover() {
+            self.logger.log(EngineEvent::Error(*err, None));
+        }
+
+        info!("traffic failover task {} finished", self.id());
+    }
+
+    fn cancel(&self) -> bool {
+        self.cancel_requested.store(true, Ordering::Relaxed);
+        true
+    }
+
+    fn cancel_checker(&self) -> Box<dyn Fn() -> bool + Send + Sync> {
+        let cancel_requested = self.cancel_requested.clone();
+        Box::new(move || cancel_requested.load(Ordering::Relaxed))
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.is_terminated.0.read().map(|tx| tx.is_none()).unwrap_or(true)
+    }
+
+    fn await_terminated(&self) -> broadcast::Receiver<()> {
+        self.is_terminated.1.resubscribe()
+    }
+}
@@ -21,6 +21,7 @@ impl From<errors::CommandError> for CommandError {
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Tag {
+    ApplicationSmokeTestFailed,
     AwsCloudwatchRetentionConfigurationError,
     AwsSdkDetachEC2Volumes,
     AwsSdkGetClient,
@@ -30,6 +31,7 @@ pub enum Tag {
     AwsSdkListElasticacheClusters,
     AwsSdkListRdsInstances,
     Base64DecodeIssue,
+    BlueGreenDeploymentFailed,
     BuilderBuildpackCannotBuildContainerImage,
     BuilderBuildpackInvalidLanguageFormat,
     BuilderCloningRepositoryError,
@@ -40,6 +42,9 @@ pub enum Tag {
     BuilderDockerCannotReadDockerfile,
     BuilderError,
     BuilderGetBuildError,
+    CanaryDeploymentFailed,
+    CannotAdvanceStatefulSetPartition,
+    CannotCheckDeploymentFreeze,
     CannotConnectK8sCluster,
     CannotCopyFilesFromDirectoryToDirectory,
     CannotCreateFile,
@@ -67,8 +72,10 @@ pub enum Tag {
     CannotReadFile,
     CannotRestartService,
     CannotRetrieveClusterConfigFile,
+    CannotSnapshotManagedDatabase,
     CannotUninstallHelmChart,
     CannotWriteToFile,
+    ClientServiceDependenciesNotReady,
     ClientServiceFailedToDeployBeforeStart,
     ClientServiceFailedToStart,
     CloudProviderApiMissingInfo,
@@ -79,6 +86,7 @@ pub enum Tag {
     CloudProviderInformationError,
     ClusterHasNoWorkerNodes,
     ClusterSecretsManipulationError,
+    ClusterStateMigrationFailed,
     ClusterWorkerNodeNotFound,
     CompressionError,
     ContainerRegistryCannotInstantiateClient,
@@ -92,6 +100,7 @@ pub enum Tag {
     ContainerRegistryInvalidRegistryUrl,
     ContainerRegistryCannotLinkRegistryToCluster,
     ContainerRegistryCannotSetRepositoryLifecycleError,
+    ContainerRegistryCannotSetReplication,
     ContainerRegistryCannotSetRepositoryTags,
     ContainerRegistryImageDoesntExist,
     ContainerRegistryImageUnreachableAfterPush,
@@ -101,16 +110,26 @@ pub enum Tag {
     ContainerRegistryRepositoryDoesntExistInRegistry,
     ContainerRegistryRepositoryNameInvalid,
     ContainerRegistryUnknownError,
+    CustomMetricsNotAvailable,
     DatabaseError,
     DatabaseFailedToStartAfterSeveralRetries,
+    DatabaseHighAvailabilityModeChanged,
     DeleteLocalKubeconfigFileError,
+    DeploymentFrozen,
+    DeploymentHookFailed,
+    DnsCaaRecordsForbidCertificateIssuance,
+    DnsProviderCannotManageRecords,
     DnsProviderInformationError,
     DnsProviderInvalidApiUrl,
     DnsProviderInvalidCredentials,
+    DnsProviderZoneNotFound,
+    DnsWildcardRecordMismatch,
     DoNotRespectCloudProviderBestPractices,
     DockerError,
     DockerPullImageError,
     DockerPushImageError,
+    EventDrivenAutoscalingNotEnabled,
+    ExternalSecretsNotEnabled,
     HelmChartUninstallError,
     HelmChartsDeployError,
     HelmChartsSetupError,
@@ -119,9 +138,13 @@ pub enum Tag {
     HelmHistoryError,
     HelmReleaseDataNotFound,
     HelmSecretNotFound,
+    ImageSignatureVerificationFailed,
+    ImageSigningFailed,
     InvalidEngineApiInputCannotBeDeserialized,
     InvalidEnginePayload,
     InvalidJobOutputCannotBeSerialized,
+    InvalidKubeletParameters,
+    InvalidNodeGroupLabelsOrTaints,
     JobFailure,
     JsonDeserializationError,
     JsonSerializationError,
@@ -167,6 +190,7 @@ pub enum Tag {
     K8sPatchNodeError,
     KubeconfigFileDoNotPermitToConnectToK8sCluster,
     KubeconfigSecurityCheckError,
+    KustomizeDeploymentFailed,
     MissingRequiredEnvVariable,
     NoClusterFound,
     NotAllowedInstanceType,
@@ -181,13 +205,20 @@ pub enum Tag {
     ObjectStorageCannotGetBucket,
     ObjectStorageCannotDeleteFileIntoBucket,
     ObjectStorageCannotEmptyBucket,
+    ObjectStorageCannotGeneratePresignedUrl,
     ObjectStorageCannotGetObjectFile,
+    ObjectStorageCannotListObjects,
     ObjectStorageCannotPutFileIntoBucket,
+    ObjectStorageCannotSetBucketEncryption,
+    ObjectStorageCannotSetBucketLifecyclePolicy,
     ObjectStorageCannotTagBucket,
+    ObjectStorageInvalidBucketEncryption,
     ObjectStorageInvalidBucketName,
     ObjectStorageQuotaExceeded,
     OnlyOneClusterExpected,
     RouterFailedToDeploy,
+    ServiceDependencyCycle,
+    SharedVolumesNotSupported,
     SubnetsCountShouldBeEven,
     TaskCancelled,
     TerraformAccountBlockedByProvider,
@@ -216,6 +247,7 @@ pub enum Tag {
     TerraformQoveryConfigMismatch,
     TerraformResourceDependencyViolation,
     TerraformS3BucketCreationErrorAlreadyOwnedByYou,
+    TerraformServiceDeploymentFailed,
     TerraformServiceNotActivatedOptInRequired,
     TerraformStateLocked,
     TerraformStatelistError,
@@ -235,6 +267,7 @@ pub enum Tag {
     VaultSecretCouldNotBeDeleted,
     VaultSecretCouldNotBeRetrieved,
     VersionNumberParsingError,
+    WorkspaceQuotaExceeded,
     RouterInvalidConfiguration,
     RouterBasicAuthEnvVarCannotDecodeBase64Error,
     RouterBasicAuthEnvVarNotFound,
@@ -325,6 +358,7 @@ impl From<errors::Tag> for Tag {
             errors::Tag::TerraformContextUnsupportedParameterValue => Tag::TerraformContextUnsupportedParameterValue,
             errors::Tag::ClientServiceFailedToStart => Tag::ClientServiceFailedToStart,
             errors::Tag::ClientServiceFailedToDeployBeforeStart => Tag::ClientServiceFailedToDeployBeforeStart,
+            errors::Tag::ClientServiceDependenciesNotReady => Tag::ClientServiceDependenciesNotReady,
             errors::Tag::DatabaseFailedToStartAfterSeveralRetries => Tag::DatabaseFailedToStartAfterSeveralRetries,
             errors::Tag::RouterFailedToDeploy => Tag::RouterFailedToDeploy,
             errors::Tag::CloudProviderClientInvalidCredentials => Tag::CloudProviderClientInvalidCredentials,
@@ -366,6 +400,7 @@ impl From<errors::Tag> for Tag {
             }
             errors::Tag::ContainerRegistryCannotDeleteRegistry => Tag::ContainerRegistryCannotDeleteRegistry,
             errors::Tag::ContainerRegistryCannotSetRepositoryTags => Tag::ContainerRegistryCannotSetRepositoryTags,
+            errors::Tag::ContainerRegistryCannotSetReplication => Tag::ContainerRegistryCannotSetReplication,
             errors::Tag::ContainerRegistryUnknownError => Tag::ContainerRegistryUnknownError,
             errors::Tag::ContainerRegistryRepositoryNameInvalid => Tag::ContainerRegistryRepositoryNameInvalid,
             errors::Tag::BuilderDockerCannotListImages => Tag::BuilderDockerCannotListImages,
@@ -377,10 +412,17 @@ impl From<errors::Tag> for Tag {
             errors::Tag::ObjectStorageCannotActivateBucketVersioning => {
                 Tag::ObjectStorageCannotActivateBucketVersioning
             }
+            errors::Tag::ObjectStorageCannotSetBucketLifecyclePolicy => {
+                Tag::ObjectStorageCannotSetBucketLifecyclePolicy
+            }
+            errors::Tag::ObjectStorageCannotSetBucketEncryption => Tag::ObjectStorageCannotSetBucketEncryption,
+            errors::Tag::ObjectStorageInvalidBucketEncryption => Tag::ObjectStorageInvalidBucketEncryption,
             errors::Tag::BuilderError => Tag::BuilderError,
             errors::Tag::ContainerRegistryCannotCreateRegistry => Tag::ContainerRegistryCannotCreateRegistry,
             errors::Tag::UnsupportedClusterKind => Tag::UnsupportedClusterKind,
             errors::Tag::NotAllowedInstanceType => Tag::NotAllowedInstanceType,
+            errors::Tag::InvalidKubeletParameters => Tag::InvalidKubeletParameters,
+            errors::Tag::InvalidNodeGroupLabelsOrTaints => Tag::InvalidNodeGroupLabelsOrTaints,
             errors::Tag::TerraformConfigFileNotFound => Tag::TerraformQoveryConfigMismatch,
             errors::Tag::KubeconfigFileDoNotPermitToConnectToK8sCluster => {
                 Tag::KubeconfigFileDoNotPermitToConnectToK8sCluster
@@ -404,6 +446,10 @@ impl From<errors::Tag> for Tag {
             errors::Tag::CloudProviderInformationError => Tag::CloudProviderInformationError,
             errors::Tag::DnsProviderInvalidCredentials => Tag::DnsProviderInvalidCredentials,
             errors::Tag::DnsProviderInvalidApiUrl => Tag::DnsProviderInvalidApiUrl,
+            errors::Tag::DnsProviderZoneNotFound => Tag::DnsProviderZoneNotFound,
+            errors::Tag::DnsProviderCannotManageRecords => Tag::DnsProviderCannotManageRecords,
+            errors::Tag::DnsCaaRecordsForbidCertificateIssuance => Tag::DnsCaaRecordsForbidCertificateIssuance,
+            errors::Tag::DnsWildcardRecordMismatch => Tag::DnsWildcardRecordMismatch,
             errors::Tag::K8sErrorCopySecret => Tag::K8sErrorCopySecret,
             errors::Tag::K8sCannotReachToApi => Tag::K8sCannotReachToApi,
             errors::Tag::TerraformUnknownError => Tag::TerraformUnknownError,
@@ -432,10 +478,13 @@ impl From<errors::Tag> for Tag {
             errors::Tag::TerraformManagedDatabaseError => Tag::TerraformManagedDatabaseError,
             errors::Tag::HelmDeployTimeout => Tag::HelmDeployTimeout,
             errors::Tag::CannotPauseManagedDatabase => Tag::CannotPauseManagedDatabase,
+            errors::Tag::CannotSnapshotManagedDatabase => Tag::CannotSnapshotManagedDatabase,
             errors::Tag::ObjectStorageCannotDeleteBucket => Tag::ObjectStorageCannotDeleteBucket,
             errors::Tag::ObjectStorageCannotGetBucket => Tag::ObjectStorageCannotGetBucket,
             errors::Tag::ObjectStorageQuotaExceeded => Tag::ObjectStorageQuotaExceeded,
             errors::Tag::ObjectStorageCannotGetObjectFile => Tag::ObjectStorageCannotGetObjectFile,
+            errors::Tag::ObjectStorageCannotListObjects => Tag::ObjectStorageCannotListObjects,
+            errors::Tag::ObjectStorageCannotGeneratePresignedUrl => Tag::ObjectStorageCannotGeneratePresignedUrl,
             errors::Tag::CloudProviderGetLoadBalancer => Tag::CloudProviderGetLoadBalancer,
             errors::Tag::CloudProviderGetLoadBalancerTags => Tag::CloudProviderGetLoadBalancerTags,
             errors::Tag::K8sCannotDeletePvc => Tag::K8sCannotDeletePvc,
@@ -458,6 +507,7 @@ impl From<errors::Tag> for Tag {
             errors::Tag::CannotParseString => Tag::CannotParseString,
             errors::Tag::CannotDeleteNodeGroup => Tag::CannotDeleteNodeGroup,
             errors::Tag::CannotRestartService => Tag::CannotRestartService,
+            errors::Tag::CannotAdvanceStatefulSetPartition => Tag::CannotAdvanceStatefulSetPartition,
             errors::Tag::AwsSdkGetClient => Tag::AwsSdkGetClient,
             errors::Tag::AwsSdkListRdsInstances => Tag::AwsSdkListRdsInstances,
             errors::Tag::AwsSdkListElasticacheClusters => Tag::AwsSdkListElasticacheClusters,
@@ -481,6 +531,24 @@ impl From<errors::Tag> for Tag {
             errors::Tag::RouterBasicAuthEnvVarNotFound => Tag::RouterBasicAuthEnvVarNotFound,
             errors::Tag::CannotFetchScalewayPrivateNetworks => Tag::CannotFetchScalewayPrivateNetworks,
             errors::Tag::CannotWriteToFile => Tag::CannotWriteToFile,
+            errors::Tag::WorkspaceQuotaExceeded => Tag::WorkspaceQuotaExceeded,
+            errors::Tag::DeploymentFrozen => Tag::DeploymentFrozen,
+            errors::Tag::DeploymentHookFailed => Tag::DeploymentHookFailed,
+            errors::Tag::ImageSigningFailed => Tag::ImageSigningFailed,
+            errors::Tag::ImageSignatureVerificationFailed => Tag::ImageSignatureVerificationFailed,
+            errors::Tag::ClusterStateMigrationFailed => Tag::ClusterStateMigrationFailed,
+            errors::Tag::CanaryDeploymentFailed => Tag::CanaryDeploymentFailed,
+            errors::Tag::KustomizeDeploymentFailed => Tag::KustomizeDeploymentFailed,
+            errors::Tag::TerraformServiceDeploymentFailed => Tag::TerraformServiceDeploymentFailed,
+            errors::Tag::SharedVolumesNotSupported => Tag::SharedVolumesNotSupported,
+            errors::Tag::EventDrivenAutoscalingNotEnabled => Tag::EventDrivenAutoscalingNotEnabled,
+            errors::Tag::CustomMetricsNotAvailable => Tag::CustomMetricsNotAvailable,
+            errors::Tag::BlueGreenDeploymentFailed => Tag::BlueGreenDeploymentFailed,
+            errors::Tag::ServiceDependencyCycle => Tag::ServiceDependencyCycle,
+            errors::Tag::ApplicationSmokeTestFailed => Tag::ApplicationSmokeTestFailed,
+            errors::Tag::ExternalSecretsNotEnabled => Tag::ExternalSecretsNotEnabled,
+            errors::Tag::DatabaseHighAvailabilityModeChanged => Tag::DatabaseHighAvailabilityModeChanged,
+            errors::Tag::CannotCheckDeploymentFreeze => Tag::CannotCheckDeploymentFreeze,
         }
     }
 }
@@ -7,10 +7,14 @@ use crate::build_platform::BuildError;
 use crate::cloud_provider::helm::HelmChartError;
 use crate::cloud_provider::service::DatabaseType;
 use crate::cloud_provider::Kind;
+use crate::cluster_state_migrations::ClusterStateMigrationError;
+use crate::cmd::cosign::CosignError;
 use crate::cmd::docker::DockerError;
 use crate::cmd::helm::HelmError;
 use crate::cmd::terraform::{QuotaExceededError, TerraformError};
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::deployment_freeze::DeploymentFreeze;
+use crate::deployment_hook::{DeploymentHookError, DeploymentHookPhase, DeploymentHookStage};
 
 use crate::cloud_provider::kubernetes::KubernetesError;
 use crate::cmd::{command, terraform};
@@ -313,6 +317,49 @@ impl From<ObjectStorageError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
+            ObjectStorageError::CannotSetBucketLifecyclePolicy {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot set lifecycle policy for: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::CannotSetBucketEncryption {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot set encryption for: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::InvalidBucketEncryption {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, invalid encryption for: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::CannotGeneratePresignedUrl {
+                bucket_name,
+                object_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!(
+                    "Object storage error, cannot generate presigned url for `{object_name}` in bucket: `{bucket_name}`"
+                ),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::CannotListObjects {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot list objects from bucket: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
         }
     }
 }
@@ -511,6 +558,18 @@ impl From<ContainerRegistryError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
+            ContainerRegistryError::CannotSetReplication {
+                registry_name,
+                regions,
+                raw_error_message,
+            } => CommandError::new(
+                format!(
+                    "Container registry error, cannot set replication to regions `{}` in registry: `{registry_name}`",
+                    regions.join(", ")
+                ),
+                Some(raw_error_message),
+                None,
+            ),
             ContainerRegistryError::RepositoryNameNotValid {
                 registry_name,
                 repository_name,
@@ -541,9 +600,13 @@ impl From<BuildError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
-            BuildError::GitError { application, raw_error } => CommandError::new(
-                format!("Build error, cannot build application `{application}` due to a git error"),
-                Some(raw_error.to_string()),
+            BuildError::CloningRepositoryError {
+                application,
+                cause,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Build error, cannot clone the repository of application `{application}` ({cause:?})"),
+                Some(raw_error_message),
                 None,
             ),
             BuildError::Aborted { application } => CommandError::new_from_safe_message(format!(
@@ -570,6 +633,11 @@ impl From<BuildError> for CommandError {
                 Some(raw_error.to_string()),
                 None,
             ),
+            BuildError::ImageSigningError { application, raw_error } => CommandError::new(
+                format!("Build error, cannot sign the image of application `{application}`"),
+                Some(raw_error.to_string()),
+                None,
+            ),
         }
     }
 }
@@ -674,6 +742,10 @@ pub enum Tag {
     UnsupportedInstanceType,
     /// NotAllowedInstanceType: represents not allowed instance type for a specific kind of cluster
     NotAllowedInstanceType,
+    /// InvalidKubeletParameters: represents kubelet parameters of the cluster nodes which cannot be applied.
+    InvalidKubeletParameters,
+    /// InvalidNodeGroupLabelsOrTaints: represents node group labels or taints which cannot be set on nodes.
+    InvalidNodeGroupLabelsOrTaints,
     /// UnsupportedClusterKind: represents an unsupported cluster kind by Qovery.
     UnsupportedClusterKind,
     /// UnsupportedRegion: represents an unsupported region for the given cloud provider.
@@ -690,6 +762,8 @@ pub enum Tag {
     CannotGetClusterNodes,
     /// CannotRestartService: represents an error while trying to restart a service.
     CannotRestartService,
+    /// CannotAdvanceStatefulSetPartition: represents an error while rolling out a statefulset update to a partition.
+    CannotAdvanceStatefulSetPartition,
     /// NotEnoughNodesAvailableToDeployEnvironment: represents an error when trying to deploy an environment but there the desired number of nodes exceeds the maximum value.
     NotEnoughNodesAvailableToDeployEnvironment,
     /// NotEnoughResourcesToDeployEnvironment: represents an error when trying to deploy an environment but there are not enough resources available on the cluster.
@@ -716,6 +790,8 @@ pub enum Tag {
     CannotDetermineK8sKubeProxyVersion,
     /// CannotPauseManagedDatabase: as the title says
     CannotPauseManagedDatabase,
+    /// CannotSnapshotManagedDatabase: represents an error while taking a snapshot of a managed database before upgrading it.
+    CannotSnapshotManagedDatabase,
     /// CannotConnectK8sCluster: represents an error when trying to connect to the kubernetes cluster
     CannotConnectK8sCluster,
     /// CannotExecuteK8sApiCustomMetrics: represents an error when trying to get K8s API custom metrics.
@@ -910,6 +986,8 @@ pub enum Tag {
     ClientServiceFailedToStart,
     /// ClientServiceFailedToDeployBeforeStart: represents an error while trying to deploy a client's service before start.
     ClientServiceFailedToDeployBeforeStart,
+    /// ClientServiceDependenciesNotReady: represents an error where external dependencies of a client's service are not reachable.
+    ClientServiceDependenciesNotReady,
     /// DatabaseFailedToStartAfterSeveralRetries: represents an error while trying to start a database after several retries.
     DatabaseFailedToStartAfterSeveralRetries,
     /// RouterFailedToDeploy: represents an error while trying to deploy a router.
@@ -990,6 +1068,8 @@ pub enum Tag {
     ContainerRegistryCannotDeleteRegistry,
     /// ContainerRegistryCannotSetTags: represents an error on container registry where it cannot cannot set tags.
     ContainerRegistryCannotSetRepositoryTags,
+    /// ContainerRegistryCannotSetReplication: represents an error on container registry where it cannot set the replication of its images to other regions.
+    ContainerRegistryCannotSetReplication,
     /// ContainerRegistryCannotSetTags: represents an unknown error on container registry.
     ContainerRegistryUnknownError,
     /// KubeconfigFileDoNotPermitToConnectToK8sCluster: represent a kubeconfig mismatch, not permitting to connect to k8s cluster
@@ -1016,6 +1096,14 @@ pub enum Tag {
     DnsProviderInvalidCredentials,
     /// DnsProviderInvalidApiUrl: represent an error on invalid DNS provider api url.
     DnsProviderInvalidApiUrl,
+    /// DnsProviderZoneNotFound: represent an error where no DNS zone of the provider holds the domain.
+    DnsProviderZoneNotFound,
+    /// DnsProviderCannotManageRecords: represent an error while reading or writing records through the DNS provider.
+    DnsProviderCannotManageRecords,
+    /// DnsCaaRecordsForbidCertificateIssuance: represent an error where CAA records don't allow Let's Encrypt to issue certificates.
+    DnsCaaRecordsForbidCertificateIssuance,
+    /// DnsWildcardRecordMismatch: represent an error where the wildcard record of the cluster domain doesn't target its load balancer.
+    DnsWildcardRecordMismatch,
     /// ObjectStorageCannotInstantiateClient: represents an error while trying to instantiate object storage client.
     ObjectStorageCannotInstantiateClient,
     /// ObjectStorageCannotCreateBucket: represents an error while trying to create a new object storage bucket.
@@ -1030,6 +1118,12 @@ pub enum Tag {
     ObjectStorageCannotGetBucket,
     /// ObjectStorageCannotActivateBucketVersioning: represents an error while trying to activate bucket versioning for bucket.
     ObjectStorageCannotActivateBucketVersioning,
+    /// ObjectStorageCannotSetBucketLifecyclePolicy: represents an error while trying to set the lifecycle policy of a bucket.
+    ObjectStorageCannotSetBucketLifecyclePolicy,
+    /// ObjectStorageCannotSetBucketEncryption: represents an error while trying to set the default encryption of a bucket.
+    ObjectStorageCannotSetBucketEncryption,
+    /// ObjectStorageInvalidBucketEncryption: represents an error, bucket objects are not encrypted as required.
+    ObjectStorageInvalidBucketEncryption,
     /// ObjectStorageQuotaExceeded: represents an error, quotas has been exceeded.
     ObjectStorageQuotaExceeded,
     /// ObjectStorageInvalidBucketName: represents an error, bucket name is not valid.
@@ -1040,6 +1134,10 @@ pub enum Tag {
     ObjectStorageCannotTagBucket,
     /// ObjectStorageCannotGetObjectFile: represents an error while trying to get a file from object storage bucket.
     ObjectStorageCannotGetObjectFile,
+    /// ObjectStorageCannotListObjects: represents an error while trying to list objects of an object storage bucket.
+    ObjectStorageCannotListObjects,
+    /// ObjectStorageCannotGeneratePresignedUrl: represents an error while trying to generate a time limited url to an object.
+    ObjectStorageCannotGeneratePresignedUrl,
     /// JobFailure: represents an error while indicating that the job failed to terminate properly
     JobFailure,
     /// CannotParseString: represents an error while trying to parse a string
@@ -1086,6 +1184,42 @@ pub enum Tag {
     K8sCannotGetNodes,
     /// K8sPatchNodeError: represents an error where we are not able to patch a node.
     K8sPatchNodeError,
+    /// WorkspaceQuotaExceeded: represents an error where the engine workspace has not enough disk space left.
+    WorkspaceQuotaExceeded,
+    /// DeploymentFrozen: represents an error where a request targets a frozen cluster or environment.
+    DeploymentFrozen,
+    /// DeploymentHookFailed: represents an error where a deployment hook registered by the integrator stopped the deployment.
+    DeploymentHookFailed,
+    /// ImageSigningFailed: represents an error where a built image cannot be signed with the cluster signing key.
+    ImageSigningFailed,
+    /// ImageSignatureVerificationFailed: represents an error where an image to deploy has no valid signature.
+    ImageSignatureVerificationFailed,
+    /// ClusterStateMigrationFailed: represents an error where the state kept in a cluster cannot be migrated.
+    ClusterStateMigrationFailed,
+    /// CanaryDeploymentFailed: represents an error where the canary of a new version is rolled back.
+    CanaryDeploymentFailed,
+    /// KustomizeDeploymentFailed: represents an error where the overlay of a kustomize service cannot be rendered or applied.
+    KustomizeDeploymentFailed,
+    /// TerraformServiceDeploymentFailed: represents an error where the module of a terraform service cannot be prepared or its outputs cannot be read.
+    TerraformServiceDeploymentFailed,
+    /// SharedVolumesNotSupported: represents an error where an environment has shared volumes but its cluster provides no ReadWriteMany storage.
+    SharedVolumesNotSupported,
+    /// EventDrivenAutoscalingNotEnabled: represents an error where a service has autoscaling triggers but KEDA is not installed on its cluster.
+    EventDrivenAutoscalingNotEnabled,
+    /// CustomMetricsNotAvailable: represents an error where a service scales on custom metrics but its cluster does not serve the custom metrics API.
+    CustomMetricsNotAvailable,
+    /// BlueGreenDeploymentFailed: represents an error where the new version deployed next to the previous one is rolled back.
+    BlueGreenDeploymentFailed,
+    /// ServiceDependencyCycle: represents an error where services of an environment depend on each other.
+    ServiceDependencyCycle,
+    /// ApplicationSmokeTestFailed: represents an error where the smoke test of a new version of an application fails.
+    ApplicationSmokeTestFailed,
+    /// ExternalSecretsNotEnabled: represents an error where a service has external secrets but External Secrets Operator is not installed on its cluster.
+    ExternalSecretsNotEnabled,
+    /// DatabaseHighAvailabilityModeChanged: represents an error where high availability is toggled on an existing container PostgreSQL database.
+    DatabaseHighAvailabilityModeChanged,
+    /// CannotCheckDeploymentFreeze: represents an error where the deployment freeze of a cluster cannot be read.
+    CannotCheckDeploymentFreeze,
 }
 
 impl Tag {
@@ -1214,6 +1348,20 @@ impl EngineError {
         }
     }
 
+    /// Points to the support bundle collected after the error, keeping the original hint if any
+    ///
+    /// Arguments:
+    ///
+    /// * `support_bundle_url`: time limited download url of the support bundle.
+    pub fn with_support_bundle_url(mut self, support_bundle_url: &Url) -> Self {
+        let support_bundle_hint = format!("Support bundle of the failed operation: {support_bundle_url}");
+        self.hint_message = Some(match self.hint_message.take() {
+            Some(hint_message) => format!("{hint_message}\n{support_bundle_hint}"),
+            None => support_bundle_hint,
+        });
+        self
+    }
+
     /// Creates new unknown error.
     ///
     /// Note: do not use unless really needed, every error should have a clear type.
@@ -1438,6 +1586,43 @@ impl EngineError {
         )
     }
 
+    /// Creates new error for kubelet parameters which cannot be applied to the cluster nodes.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `reason`: Why the parameters are invalid.
+    pub fn new_invalid_kubelet_parameters(event_details: EventDetails, reason: String) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::InvalidKubeletParameters,
+            format!("Invalid kubelet parameters: {reason}"),
+            None,
+            None,
+            Some("Please check the kubelet parameters of your cluster nodes.".to_string()),
+        )
+    }
+
+    /// Creates new error for node group labels or taints which cannot be set on the nodes.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `reason`: Why the labels or taints are invalid.
+    pub fn new_invalid_node_group_labels_or_taints(event_details: EventDetails, reason: String) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::InvalidNodeGroupLabelsOrTaints,
+            format!("Invalid node group labels or taints: {reason}"),
+            None,
+            None,
+            Some(
+                "Labels and taints keys must be valid kubernetes label keys, not reserved by kubernetes nor Qovery."
+                    .to_string(),
+            ),
+        )
+    }
+
     /// Creates new error for unsupported instance type.
     ///
     /// Cloud provider doesn't support the requested instance type.
@@ -1941,6 +2126,33 @@ impl EngineError {
         )
     }
 
+    /// Creates new error for managed database snapshot which cannot be taken before a version upgrade.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `database_id`: Managed database identifier.
+    /// * `command_error`: Raw error message.
+    pub fn new_cannot_snapshot_managed_database(
+        event_details: EventDetails,
+        database_id: &str,
+        command_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Unable to take a snapshot of managed database `{}` before upgrading it: {}",
+            database_id, command_error.message_safe
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::CannotSnapshotManagedDatabase,
+            message,
+            Some(command_error),
+            None,
+            Some("The database has not been upgraded. Please retry the deployment, or contact Qovery support if the issue persists.".to_string()),
+        )
+    }
+
     pub fn new_cannot_connect_to_k8s_cluster(event_details: EventDetails, kube_error: kube::Error) -> EngineError {
         let message = format!("Unable to connect to target k8s cluster: `{kube_error}`");
 
@@ -3162,6 +3374,14 @@ impl EngineError {
                                                                                                                                None,
                                                                                                                                None,
             ),
+            ContainerRegistryError::CannotSetReplication { ref registry_name, ref regions, .. } => EngineError::new(
+                event_details,
+                Tag::ContainerRegistryCannotSetReplication,
+                format!("Container registry: cannot replicate registry `{registry_name}` to regions `{}`.", regions.join(", ")),
+                Some(error.into()),
+                None,
+                None,
+            ),
             ContainerRegistryError::RepositoryNameNotValid {ref registry_name, ref repository_name, ..} => EngineError::new(
                 event_details,
                 Tag::ContainerRegistryRepositoryNameInvalid,
@@ -3181,9 +3401,18 @@ impl EngineError {
     /// * `event_details`: Error linked event details.
     /// * `error`: Raw error message.
     pub fn new_build_error(event_details: EventDetails, error: BuildError, user_message: String) -> EngineError {
+        let tag = match &error {
+            BuildError::ImageSigningError { .. } => Tag::ImageSigningFailed,
+            BuildError::CloningRepositoryError { .. } => Tag::BuilderCloningRepositoryError,
+            _ => Tag::BuilderError,
+        };
+        let hint = match &error {
+            BuildError::CloningRepositoryError { cause, .. } => Some(cause.hint().to_string()),
+            _ => None,
+        };
         let command_error = CommandError::from(error);
 
-        EngineError::new(event_details, Tag::BuilderError, user_message, Some(command_error), None, None)
+        EngineError::new(event_details, tag, user_message, Some(command_error), None, hint)
     }
 
     /// Creates new error from an Container Registry error
@@ -3223,6 +3452,7 @@ impl EngineError {
             HelmChartError::CommandError(cmd_error) => Some(cmd_error),
             HelmChartError::CreateTemplateError { .. }
             | HelmChartError::RenderingError { .. }
+            | HelmChartError::WorkloadsNotReady { .. }
             | HelmChartError::HelmError(_) => None,
         };
 
@@ -3607,6 +3837,33 @@ impl EngineError {
         )
     }
 
+    /// Creates new error when external dependencies of a client service are still not ready after waiting for them.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Client service name.
+    /// * `failing_checks`: Description of the checks which did not pass.
+    pub fn new_client_service_dependencies_not_ready(
+        event_details: EventDetails,
+        service_name: String,
+        failing_checks: Vec<String>,
+    ) -> EngineError {
+        let message = format!(
+            "Dependencies of service `{service_name}` are not ready, rollout has not been started: {}",
+            failing_checks.join(", ")
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::ClientServiceDependenciesNotReady,
+            message,
+            None,
+            None,
+            Some("Check that the dependencies declared in the readiness gates of the service are up and reachable from the cluster.".to_string()),
+        )
+    }
+
     /// Creates new error while trying to start a client service before start.
     ///
     /// Arguments:
@@ -4463,6 +4720,30 @@ impl EngineError {
                 None,
                 None,
             ),
+            ObjectStorageError::CannotSetBucketLifecyclePolicy { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotSetBucketLifecyclePolicy,
+                format!("Error while trying to set lifecycle policy for object storage bucket `{bucket_name}`."),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
+            ObjectStorageError::CannotSetBucketEncryption { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotSetBucketEncryption,
+                format!("Error while trying to set encryption for object storage bucket `{bucket_name}`."),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
+            ObjectStorageError::InvalidBucketEncryption { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageInvalidBucketEncryption,
+                format!("Error, object storage bucket `{bucket_name}` is not encrypted as required."),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
             ObjectStorageError::CannotGetObjectFile {
                 ref bucket_name,
                 object_name: ref file_name,
@@ -4499,6 +4780,28 @@ impl EngineError {
                 None,
                 None,
             ),
+            ObjectStorageError::CannotGeneratePresignedUrl {
+                ref bucket_name,
+                ref object_name,
+                ..
+            } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotGeneratePresignedUrl,
+                format!(
+                    "Error, cannot generate presigned url for `{object_name}` from object storage bucket `{bucket_name}`."
+                ),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
+            ObjectStorageError::CannotListObjects { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotListObjects,
+                format!("Error, cannot list objects from object storage bucket `{bucket_name}`."),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
         }
     }
 
@@ -4713,6 +5016,109 @@ impl EngineError {
         )
     }
 
+    /// Creates new error when no zone of the client DNS provider holds the domain
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `domain`: Domain having no zone.
+    pub fn new_error_on_dns_provider_zone_not_found(event_details: EventDetails, domain: &str) -> EngineError {
+        let message_safe = format!("No public DNS zone found for domain `{domain}`");
+
+        EngineError::new(
+            event_details,
+            Tag::DnsProviderZoneNotFound,
+            message_safe,
+            None,
+            None,
+            Some("Check a public hosted zone of your DNS provider holds this domain".to_string()),
+        )
+    }
+
+    /// Creates new error when records cannot be read or written through the DNS provider
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `name`: Name of the records.
+    /// * `raw_error`: Raw error message.
+    pub fn new_error_on_dns_provider_records(
+        event_details: EventDetails,
+        name: &str,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message_safe = format!("Cannot manage DNS records of `{name}`");
+
+        EngineError::new(
+            event_details,
+            Tag::DnsProviderCannotManageRecords,
+            message_safe,
+            Some(raw_error),
+            None,
+            Some("Check your DNS provider credentials are allowed to edit the records of this zone".to_string()),
+        )
+    }
+
+    /// Creates new error when the CAA records of a domain don't allow Let's Encrypt to issue its certificate
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `domain`: Domain of the certificate.
+    /// * `caa_name`: Name holding the CAA records applying to the domain.
+    /// * `caa_record`: CAA record allowing Let's Encrypt.
+    pub fn new_dns_caa_records_forbid_certificate_issuance(
+        event_details: EventDetails,
+        domain: &str,
+        caa_name: &str,
+        caa_record: &str,
+    ) -> EngineError {
+        let message_safe =
+            format!("CAA records of `{caa_name}` don't allow Let's Encrypt to issue a certificate for `{domain}`");
+
+        EngineError::new(
+            event_details,
+            Tag::DnsCaaRecordsForbidCertificateIssuance,
+            message_safe,
+            None,
+            None,
+            Some(format!(
+                "Add the CAA record `{caa_record}` to `{caa_name}` in your DNS zone, or disable certificate generation for this domain"
+            )),
+        )
+    }
+
+    /// Creates new error when the wildcard record of the cluster domain doesn't target its load balancer
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `wildcard_domain`: Wildcard domain of the cluster, i.e: `*.cluster.example.com`.
+    /// * `current_values`: Values of the existing records.
+    /// * `expected_value`: Load balancer the records should target.
+    pub fn new_dns_wildcard_record_mismatch(
+        event_details: EventDetails,
+        wildcard_domain: &str,
+        current_values: &[String],
+        expected_value: &str,
+    ) -> EngineError {
+        let message_safe = format!(
+            "Records of `{wildcard_domain}` target `{}` instead of the cluster load balancer `{expected_value}`",
+            current_values.join(", ")
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::DnsWildcardRecordMismatch,
+            message_safe,
+            None,
+            None,
+            Some(format!(
+                "Update the records of `{wildcard_domain}` to target `{expected_value}`, or delete them so they are created again"
+            )),
+        )
+    }
+
     /// Creates new error to match Cloud Provider best practices
     ///
     /// Arguments:
@@ -4845,6 +5251,36 @@ impl EngineError {
         )
     }
 
+    /// Creates new error when the update of statefulsets cannot be rolled out to a partition
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `namespace`: Namespace of the statefulsets.
+    /// * `selector`: Label selector of the statefulsets.
+    /// * `partition`: Requested rolling update partition.
+    /// * `raw_error`: Raw error message.
+    pub fn new_cannot_advance_statefulset_partition(
+        event_details: EventDetails,
+        namespace: &str,
+        selector: &str,
+        partition: u32,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message_safe = format!(
+            "Cannot roll out statefulsets update to partition {partition} in namespace {namespace} for selector {selector}"
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::CannotAdvanceStatefulSetPartition,
+            message_safe,
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
     /// Creates new error for cluster restart
     ///
     /// Arguments:
@@ -4855,6 +5291,16 @@ impl EngineError {
         EngineError::new(event_details, Tag::NotImplementedError, message.to_string(), None, None, None)
     }
 
+    /// Creates new error for cluster on demand trigger, only cronjobs can be triggered
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    pub fn new_cannot_trigger_kubernetes_cluster(event_details: EventDetails) -> EngineError {
+        let message = "Triggering a cluster is not allowed";
+        EngineError::new(event_details, Tag::NotImplementedError, message.to_string(), None, None, None)
+    }
+
     /// Creates new error for Job output cannot be serialized.
     /// Arguments:
     ///
@@ -4982,6 +5428,372 @@ impl EngineError {
             None,
         )
     }
+
+    /// Creates new error for an engine workspace not having enough disk space left to start an execution.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `error`: Raw error message, holding the workspace usage.
+    pub fn new_workspace_quota_exceeded(event_details: EventDetails, error: CommandError) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::WorkspaceQuotaExceeded,
+            "Engine workspace has not enough disk space left to start the deployment.".to_string(),
+            Some(error),
+            None,
+            Some("This is a Qovery issue, please contact our support team".to_string()),
+        )
+    }
+
+    /// Creates new error for a request targeting a frozen cluster or environment.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `scope`: Frozen cluster or environment.
+    /// * `freeze`: Freeze rejecting the request.
+    pub fn new_deployment_frozen(event_details: EventDetails, scope: &str, freeze: &DeploymentFreeze) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::DeploymentFrozen,
+            format!(
+                "Deployments of {} are frozen since {} by {}: {}",
+                scope,
+                freeze.frozen_at.to_rfc3339(),
+                freeze.frozen_by,
+                freeze.reason
+            ),
+            None,
+            None,
+            Some("Wait for the freeze to be lifted, or ask its owner for a break-glass override".to_string()),
+        )
+    }
+
+    /// Creates new error for a deployment hook failing before or after a deployment stage.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `hook_name`: Name of the failing hook.
+    /// * `stage`: Deployment stage the hook runs around.
+    /// * `phase`: Whether the hook runs before or after the stage.
+    /// * `error`: Reason given by the hook.
+    pub fn new_deployment_hook_failed(
+        event_details: EventDetails,
+        hook_name: &str,
+        stage: DeploymentHookStage,
+        phase: DeploymentHookPhase,
+        error: DeploymentHookError,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::DeploymentHookFailed,
+            format!("Deployment hook `{hook_name}` failed {phase} {stage} stage: {error}"),
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for an image to deploy whose signature cannot be verified.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `image_name`: Image to deploy.
+    /// * `error`: Raw error message.
+    pub fn new_image_signature_verification_failed(
+        event_details: EventDetails,
+        image_name: String,
+        error: CosignError,
+    ) -> EngineError {
+        if error.is_aborted() {
+            return EngineError::new_task_cancellation_requested(event_details);
+        }
+
+        EngineError::new(
+            event_details,
+            Tag::ImageSignatureVerificationFailed,
+            format!("Image `{image_name}` has no valid signature, it is not deployed"),
+            Some(CommandError::new(
+                format!("Cannot verify the signature of image `{image_name}`"),
+                Some(error.to_string()),
+                None,
+            )),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for a migration of the state kept in the cluster failing.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `error`: Raw error message.
+    pub fn new_cluster_state_migration_failed(
+        event_details: EventDetails,
+        error: ClusterStateMigrationError,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::ClusterStateMigrationFailed,
+            "Cannot migrate the state of the cluster to the one expected by this version of the engine".to_string(),
+            Some(CommandError::new_from_safe_message(error.to_string())),
+            None,
+            Some(
+                "The migration is retried on the next deployment, please contact Qovery if it keeps failing"
+                    .to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for the canary of a new version of an application being rolled back.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `reason`: Why the canary is rolled back.
+    /// * `raw_error`: Raw error message, if the canary cannot be handled at all.
+    pub fn new_canary_deployment_failed(
+        event_details: EventDetails,
+        reason: String,
+        raw_error: Option<CommandError>,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::CanaryDeploymentFailed,
+            format!("Canary of the new version is rolled back, it is not deployed: {reason}"),
+            raw_error,
+            None,
+            Some("The previous version keeps serving all the traffic, check the logs of the canary pods".to_string()),
+        )
+    }
+
+    /// Creates new error for a kustomize service whose overlay cannot be rendered or applied.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `kustomize_name`: Name of the kustomize service.
+    /// * `message`: What failed.
+    /// * `raw_error`: Raw error message.
+    pub fn new_kustomize_deployment_failed(
+        event_details: EventDetails,
+        kustomize_name: &str,
+        message: String,
+        raw_error: Option<CommandError>,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::KustomizeDeploymentFailed,
+            format!("Cannot deploy kustomize service `{kustomize_name}`: {message}"),
+            raw_error,
+            None,
+            Some("Check that `kubectl kustomize` succeeds on the overlay at this commit".to_string()),
+        )
+    }
+
+    /// Creates new error for a terraform service whose module cannot be prepared or whose outputs cannot be read.
+    /// Errors of terraform itself are raised with `new_terraform_error`.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `terraform_service_name`: Name of the terraform service.
+    /// * `message`: What failed.
+    pub fn new_terraform_service_deployment_failed(
+        event_details: EventDetails,
+        terraform_service_name: &str,
+        message: String,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::TerraformServiceDeploymentFailed,
+            format!("Cannot deploy terraform service `{terraform_service_name}`: {message}"),
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for an environment with shared volumes deployed on a cluster without ReadWriteMany storage.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `cluster_kind`: Kind of the cluster the environment is deployed on.
+    pub fn new_shared_volumes_not_supported(event_details: EventDetails, cluster_kind: &str) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::SharedVolumesNotSupported,
+            format!("Shared volumes are not supported by the {cluster_kind} cluster of the environment"),
+            None,
+            None,
+            Some(
+                "Shared volumes are available on EKS, GKE and Kapsule clusters, on EKS and Kapsule enable the `storage.shared_volumes_enabled` advanced setting of the cluster and redeploy it".to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a service with autoscaling triggers deployed on a cluster without KEDA.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Name of the service defining the triggers.
+    pub fn new_event_driven_autoscaling_not_enabled(event_details: EventDetails, service_name: &str) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::EventDrivenAutoscalingNotEnabled,
+            format!("Service {service_name} has autoscaling triggers but KEDA is not installed on the cluster"),
+            None,
+            None,
+            Some(
+                "Autoscaling triggers are available on EKS and Kapsule clusters, enable the `keda.enabled` advanced setting of the cluster and redeploy it, or remove the `hpa.keda.triggers` of the service".to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a service scaling on custom metrics deployed on a cluster without the prometheus adapter.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Name of the service defining the custom metrics.
+    pub fn new_custom_metrics_not_available(event_details: EventDetails, service_name: &str) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::CustomMetricsNotAvailable,
+            format!("Service {service_name} scales on custom metrics but the cluster does not serve them"),
+            None,
+            None,
+            Some(
+                "Custom metrics are served by the prometheus adapter of EKS and Kapsule clusters with metrics history enabled, or remove the `hpa.custom_metrics` of the service".to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a blue/green deployment of an application being rolled back.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `reason`: Why the new version is rolled back.
+    /// * `raw_error`: Raw error message, if the kubernetes resources cannot be handled.
+    pub fn new_blue_green_deployment_failed(
+        event_details: EventDetails,
+        reason: String,
+        raw_error: Option<CommandError>,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::BlueGreenDeploymentFailed,
+            format!("Blue/green deployment of the new version is rolled back: {reason}"),
+            raw_error,
+            None,
+            Some(
+                "Traffic is sent back to the previous version, check the logs of the pods of the new version"
+                    .to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for services of an environment which cannot be deployed in order of their dependencies.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `services`: Services depending on each other, directly or not.
+    pub fn new_service_dependency_cycle(event_details: EventDetails, services: Vec<String>) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::ServiceDependencyCycle,
+            format!(
+                "Services cannot be deployed as they depend on each other: {}",
+                services.join(", ")
+            ),
+            None,
+            None,
+            Some("Remove one of the dependencies between those services".to_string()),
+        )
+    }
+
+    /// Creates new error for an application whose new version fails its smoke test.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `reason`: Why the smoke test failed.
+    pub fn new_application_smoke_test_failed(event_details: EventDetails, reason: String) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::ApplicationSmokeTestFailed,
+            format!("Smoke test of the new version of the application failed: {reason}"),
+            None,
+            None,
+            Some("Application is rolled back to its previous version, check the logs of its smoke test".to_string()),
+        )
+    }
+
+    /// Creates new error for a service with external secrets deployed on a cluster without External Secrets Operator.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Name of the service defining the external secrets.
+    pub fn new_external_secrets_not_enabled(event_details: EventDetails, service_name: &str) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::ExternalSecretsNotEnabled,
+            format!("Service {service_name} has external secrets but External Secrets Operator is not installed on the cluster"),
+            None,
+            None,
+            Some(
+                "External secrets are available on EKS and Kapsule clusters, enable the `external_secrets.enabled` advanced setting of the cluster and redeploy it, or remove the `deployment.external_secrets` of the service".to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a container PostgreSQL database whose high availability mode changes once deployed.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `database_name`: Name of the database.
+    pub fn new_database_high_availability_mode_changed(
+        event_details: EventDetails,
+        database_name: &str,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::DatabaseHighAvailabilityModeChanged,
+            format!("High availability of database {database_name} cannot be changed once it is deployed"),
+            None,
+            None,
+            Some(
+                "Standalone and high availability PostgreSQL do not share their volumes, create a new database with the wanted mode and restore a dump of this one into it".to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a deployment freeze which cannot be read, the request being rejected as it may be frozen.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_k8s_error`: Raw Kubernetes error message.
+    pub fn new_cannot_check_deployment_freeze(event_details: EventDetails, raw_k8s_error: CommandError) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::CannotCheckDeploymentFreeze,
+            "Cannot check whether deployments are frozen, the request is rejected".to_string(),
+            Some(raw_k8s_error),
+            None,
+            Some("Make sure the cluster is reachable, then retry the request".to_string()),
+        )
+    }
 }
 impl Display for EngineError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
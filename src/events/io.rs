@@ -4,6 +4,7 @@ use crate::errors::io::EngineError;
 use crate::events;
 use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Deserialize, Serialize)]
@@ -89,6 +90,10 @@ impl From<events::EngineEvent> for EngineEvent {
 pub struct EventMessage {
     safe_message: String,
     full_details: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code: Option<MessageCode>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: BTreeMap<String, String>,
 }
 
 impl From<events::EventMessage> for EventMessage {
@@ -96,6 +101,68 @@ impl From<events::EventMessage> for EventMessage {
         EventMessage {
             safe_message: message.safe_message,
             full_details: message.full_details,
+            code: message.code.map(MessageCode::from),
+            parameters: message.parameters,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MessageCode {
+    DeploymentStarted,
+    DeploymentTerminated,
+    DeploymentSucceeded,
+    DeploymentCancelled,
+    DeploymentFailed,
+    DeploymentCancelRequested,
+    EnvironmentPaused,
+    EnvironmentPauseFailed,
+    EnvironmentDeleted,
+    EnvironmentDeleteFailed,
+    EnvironmentRestarted,
+    EnvironmentRestartFailed,
+    InfrastructureStarted,
+    InfrastructureTerminated,
+    InfrastructureStepSucceeded,
+    InfrastructureStepFailed,
+    WorkspaceNearlyFull,
+    StepHeartbeat,
+    StepLongerThanUsual,
+    DnsResolverPropagated,
+    DnsResolverNotPropagated,
+    DeploymentFreezeOverridden,
+    ChartUpgradeImpact,
+    SbomAttached,
+}
+
+impl From<events::MessageCode> for MessageCode {
+    fn from(code: events::MessageCode) -> Self {
+        match code {
+            events::MessageCode::DeploymentStarted => MessageCode::DeploymentStarted,
+            events::MessageCode::DeploymentTerminated => MessageCode::DeploymentTerminated,
+            events::MessageCode::DeploymentSucceeded => MessageCode::DeploymentSucceeded,
+            events::MessageCode::DeploymentCancelled => MessageCode::DeploymentCancelled,
+            events::MessageCode::DeploymentFailed => MessageCode::DeploymentFailed,
+            events::MessageCode::DeploymentCancelRequested => MessageCode::DeploymentCancelRequested,
+            events::MessageCode::EnvironmentPaused => MessageCode::EnvironmentPaused,
+            events::MessageCode::EnvironmentPauseFailed => MessageCode::EnvironmentPauseFailed,
+            events::MessageCode::EnvironmentDeleted => MessageCode::EnvironmentDeleted,
+            events::MessageCode::EnvironmentDeleteFailed => MessageCode::EnvironmentDeleteFailed,
+            events::MessageCode::EnvironmentRestarted => MessageCode::EnvironmentRestarted,
+            events::MessageCode::EnvironmentRestartFailed => MessageCode::EnvironmentRestartFailed,
+            events::MessageCode::InfrastructureStarted => MessageCode::InfrastructureStarted,
+            events::MessageCode::InfrastructureTerminated => MessageCode::InfrastructureTerminated,
+            events::MessageCode::InfrastructureStepSucceeded => MessageCode::InfrastructureStepSucceeded,
+            events::MessageCode::InfrastructureStepFailed => MessageCode::InfrastructureStepFailed,
+            events::MessageCode::WorkspaceNearlyFull => MessageCode::WorkspaceNearlyFull,
+            events::MessageCode::StepHeartbeat => MessageCode::StepHeartbeat,
+            events::MessageCode::StepLongerThanUsual => MessageCode::StepLongerThanUsual,
+            events::MessageCode::DnsResolverPropagated => MessageCode::DnsResolverPropagated,
+            events::MessageCode::DnsResolverNotPropagated => MessageCode::DnsResolverNotPropagated,
+            events::MessageCode::DeploymentFreezeOverridden => MessageCode::DeploymentFreezeOverridden,
+            events::MessageCode::ChartUpgradeImpact => MessageCode::ChartUpgradeImpact,
+            events::MessageCode::SbomAttached => MessageCode::SbomAttached,
         }
     }
 }
@@ -142,6 +209,7 @@ pub enum InfrastructureStep {
     Restarted,
     RestartedError,
     CannotProcessRequest,
+    TrafficFailover,
 }
 
 impl From<events::InfrastructureStep> for InfrastructureStep {
@@ -170,6 +238,7 @@ impl From<events::InfrastructureStep> for InfrastructureStep {
             events::InfrastructureStep::Restarted => InfrastructureStep::Restarted,
             events::InfrastructureStep::RestartedError => InfrastructureStep::RestartedError,
             events::InfrastructureStep::CannotProcessRequest => InfrastructureStep::CannotProcessRequest,
+            events::InfrastructureStep::TrafficFailover => InfrastructureStep::TrafficFailover,
         }
     }
 }
@@ -200,6 +269,7 @@ pub enum EnvironmentStep {
     UnderMigration,
     JobOutput,
     DatabaseOutput,
+    TerraformOutput,
     Recap,
     Restart,
     Restarted,
@@ -236,6 +306,7 @@ impl From<events::EnvironmentStep> for EnvironmentStep {
             events::EnvironmentStep::RestartedError => EnvironmentStep::RestartedError,
             events::EnvironmentStep::JobOutput => EnvironmentStep::JobOutput,
             events::EnvironmentStep::DatabaseOutput => EnvironmentStep::DatabaseOutput,
+            events::EnvironmentStep::TerraformOutput => EnvironmentStep::TerraformOutput,
             events::EnvironmentStep::Recap => EnvironmentStep::Recap,
         }
     }
@@ -261,6 +332,8 @@ pub enum Transmitter {
     Router { id: TransmitterId, name: TransmitterName },
     Job { id: TransmitterId, name: TransmitterName },
     Helm { id: TransmitterId, name: TransmitterName },
+    Kustomize { id: TransmitterId, name: TransmitterName },
+    TerraformService { id: TransmitterId, name: TransmitterName },
 }
 
 impl From<events::Transmitter> for Transmitter {
@@ -280,6 +353,8 @@ impl From<events::Transmitter> for Transmitter {
             events::Transmitter::Container(id, name) => Transmitter::Container { id, name },
             events::Transmitter::Job(id, name) => Transmitter::Job { id, name },
             events::Transmitter::Helm(id, name) => Transmitter::Helm { id, name },
+            events::Transmitter::Kustomize(id, name) => Transmitter::Kustomize { id, name },
+            events::Transmitter::TerraformService(id, name) => Transmitter::TerraformService { id, name },
         }
     }
 }
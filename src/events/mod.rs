@@ -12,6 +12,7 @@ use crate::errors::{CommandError, EngineError, ErrorMessageVerbosity};
 use crate::io_models::QoveryIdentifier;
 use crate::metrics_registry::StepRecord;
 use derivative::Derivative;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use uuid::Uuid;
 
@@ -116,6 +117,80 @@ pub struct EventMessage {
     // env_vars field is ignored from any wild Debug printing because of it touchy data it carries.
     #[derivative(Debug = "ignore")]
     env_vars: Option<Vec<(String, String)>>,
+    // Stable identifier of the message, consumers can localize it or react to it without parsing the English text.
+    code: Option<MessageCode>,
+    // Values substituted into the message code template, they must be safe to expose.
+    parameters: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// MessageCode: identifies a well known event message, independently of its rendering.
+pub enum MessageCode {
+    DeploymentStarted,
+    DeploymentTerminated,
+    DeploymentSucceeded,
+    DeploymentCancelled,
+    DeploymentFailed,
+    DeploymentCancelRequested,
+    EnvironmentPaused,
+    EnvironmentPauseFailed,
+    EnvironmentDeleted,
+    EnvironmentDeleteFailed,
+    EnvironmentRestarted,
+    EnvironmentRestartFailed,
+    InfrastructureStarted,
+    InfrastructureTerminated,
+    InfrastructureStepSucceeded,
+    InfrastructureStepFailed,
+    WorkspaceNearlyFull,
+    StepHeartbeat,
+    StepLongerThanUsual,
+    DnsResolverPropagated,
+    DnsResolverNotPropagated,
+    DeploymentFreezeOverridden,
+    ChartUpgradeImpact,
+    SbomAttached,
+}
+
+impl MessageCode {
+    /// English template of the message, `{name}` placeholders being replaced by the parameter `name`.
+    pub fn english_template(&self) -> &'static str {
+        match self {
+            MessageCode::DeploymentStarted => "🚀 Qovery Engine starts to execute the deployment",
+            MessageCode::DeploymentTerminated => "Qovery Engine has terminated the deployment",
+            MessageCode::DeploymentSucceeded => "❤️ Deployment succeeded ❤️",
+            MessageCode::DeploymentCancelled => "🚫 Deployment has been canceled at user request 🚫",
+            MessageCode::DeploymentFailed => "💣 Deployment aborted following a failure to deploy a service. This is a general/global message. Look at your services deployment status to know which one made the deployment fail",
+            MessageCode::DeploymentCancelRequested => "🚫 Cancel received, deployment is going to stop.\nThis may take a while, as a safe point need to be reached.\nSome operation cannot be stopped (i.e: terraform actions) and need to be completed before stopping the deployment",
+            MessageCode::EnvironmentPaused => "⏸️ Environment is paused",
+            MessageCode::EnvironmentPauseFailed => "💣 Environment failed to be paused",
+            MessageCode::EnvironmentDeleted => "🗑️ Environment is deleted",
+            MessageCode::EnvironmentDeleteFailed => "💣 Environment failed to be deleted",
+            MessageCode::EnvironmentRestarted => "⟳️ Environment is restarted",
+            MessageCode::EnvironmentRestartFailed => "💣 Environment failed to be restarted",
+            MessageCode::InfrastructureStarted => "Qovery Engine has started the infrastructure deployment",
+            MessageCode::InfrastructureTerminated => "Qovery Engine has terminated the infrastructure deployment",
+            MessageCode::InfrastructureStepSucceeded => "Kubernetes cluster successfully {step}",
+            MessageCode::InfrastructureStepFailed => "Kubernetes cluster failure {step}",
+            MessageCode::WorkspaceNearlyFull => "Engine workspace is nearly full: {usage} used out of {quota}",
+            MessageCode::StepHeartbeat => "⏳ {step} is still in progress, running for {elapsed}",
+            MessageCode::StepLongerThanUsual => "🐢 {step} has been running for {elapsed}, longer than 99% of its previous runs ({p99}), it may be stuck",
+            MessageCode::DnsResolverPropagated => "✨ Domain {domain} resolves to {resolution} on resolver {resolver}",
+            MessageCode::DnsResolverNotPropagated => "🌍 Domain {domain} is not propagated yet on resolver {resolver}: {reason}",
+            MessageCode::ChartUpgradeImpact => "⚠️ Upgrading chart {chart} from {installed_version} to {target_version} {disruption}, it impacts {services} services of {environments} environments",
+            MessageCode::DeploymentFreezeOverridden => "🚨 Deployment freeze of {scope} ({freeze_reason}, by {frozen_by}) overridden by {requested_by}: {reason}",
+            MessageCode::SbomAttached => "📜 {format} SBOM of the image is available in the object storage at {location}",
+        }
+    }
+
+    /// Renders the English message, unknown placeholders are left untouched.
+    pub fn render(&self, parameters: &BTreeMap<String, String>) -> String {
+        parameters
+            .iter()
+            .fold(self.english_template().to_string(), |message, (name, value)| {
+                message.replace(&format!("{{{name}}}"), value)
+            })
+    }
 }
 
 impl EventMessage {
@@ -130,6 +205,8 @@ impl EventMessage {
             safe_message,
             full_details,
             env_vars: None,
+            code: None,
+            parameters: BTreeMap::new(),
         }
     }
 
@@ -149,9 +226,43 @@ impl EventMessage {
             safe_message,
             full_details,
             env_vars,
+            code: None,
+            parameters: BTreeMap::new(),
         }
     }
 
+    /// Creates e new EventMessage from a message code, its safe message being the rendered English template.
+    ///
+    /// Arguments
+    ///
+    /// * `code`: Message code.
+    /// * `parameters`: Values of the template placeholders (must not contain any unsafe text).
+    /// * `full_details`: Event raw message string (which may include unsafe text such as passwords and tokens).
+    pub fn new_from_code(code: MessageCode, parameters: &[(&str, String)], full_details: Option<String>) -> Self {
+        let parameters: BTreeMap<String, String> = parameters
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        EventMessage {
+            safe_message: code.render(&parameters),
+            full_details,
+            env_vars: None,
+            code: Some(code),
+            parameters,
+        }
+    }
+
+    /// Returns message code, if any.
+    pub fn code(&self) -> Option<MessageCode> {
+        self.code
+    }
+
+    /// Returns message code template parameters.
+    pub fn parameters(&self) -> &BTreeMap<String, String> {
+        &self.parameters
+    }
+
     pub fn transform(&mut self, transformer: impl Fn(String) -> String) {
         self.full_details = self.full_details.take().map(transformer);
     }
@@ -166,6 +277,8 @@ impl EventMessage {
             safe_message,
             full_details: None,
             env_vars: None,
+            code: None,
+            parameters: BTreeMap::new(),
         }
     }
 
@@ -180,6 +293,8 @@ impl EventMessage {
             safe_message,
             full_details: Some(json),
             env_vars: None,
+            code: None,
+            parameters: BTreeMap::new(),
         }
     }
 
@@ -193,6 +308,8 @@ impl EventMessage {
             safe_message: engine_error.message(ErrorMessageVerbosity::SafeOnly),
             full_details: Some(engine_error.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
             env_vars: None,
+            code: None,
+            parameters: BTreeMap::new(),
         }
     }
 
@@ -332,6 +449,8 @@ pub enum InfrastructureStep {
     RestartedError,
     /// CannotProcessRequest: error returned if the payload sent is wrong
     CannotProcessRequest,
+    /// TrafficFailover: probing clusters sharing the same traffic and shifting it away from the unhealthy ones
+    TrafficFailover,
 }
 
 impl Display for InfrastructureStep {
@@ -363,6 +482,7 @@ impl Display for InfrastructureStep {
                 InfrastructureStep::Restarted => "restarted",
                 InfrastructureStep::RestartedError => "restart-error",
                 InfrastructureStep::CannotProcessRequest => "cannot-process-request",
+                InfrastructureStep::TrafficFailover => "traffic-failover",
             },
         )
     }
@@ -433,6 +553,9 @@ pub enum EnvironmentStep {
 
     /// DatabaseOutput: contains the environment variables to upsert
     DatabaseOutput,
+
+    /// TerraformOutput: contains the environment variables to upsert
+    TerraformOutput,
 }
 
 impl EnvironmentStep {
@@ -449,7 +572,10 @@ impl EnvironmentStep {
     }
 
     pub fn is_core_output(&self) -> bool {
-        matches!(self, EnvironmentStep::JobOutput | EnvironmentStep::DatabaseOutput)
+        matches!(
+            self,
+            EnvironmentStep::JobOutput | EnvironmentStep::DatabaseOutput | EnvironmentStep::TerraformOutput
+        )
     }
 }
 
@@ -486,6 +612,7 @@ impl Display for EnvironmentStep {
                 EnvironmentStep::RestartedError => "restarted-error",
                 EnvironmentStep::JobOutput => "job-output",
                 EnvironmentStep::DatabaseOutput => "database-output",
+                EnvironmentStep::TerraformOutput => "terraform-output",
                 EnvironmentStep::Recap => "recap",
             },
         )
@@ -528,6 +655,10 @@ pub enum Transmitter {
     Router(TransmitterId, TransmitterName),
     /// Job: job engine part.
     Job(TransmitterId, TransmitterName),
+    /// Kustomize: kustomize engine part.
+    Kustomize(TransmitterId, TransmitterName),
+    /// TerraformService: terraform service engine part.
+    TerraformService(TransmitterId, TransmitterName),
 }
 
 impl Display for Transmitter {
@@ -550,6 +681,8 @@ impl Display for Transmitter {
                 Transmitter::Container(id, name) => format!("container({id}, {name})"),
                 Transmitter::Job(id, name) => format!("job({id}, {name})"),
                 Transmitter::Helm(id, name) => format!("helm_chart({id}, {name})"),
+                Transmitter::Kustomize(id, name) => format!("kustomize({id}, {name})"),
+                Transmitter::TerraformService(id, name) => format!("terraform_service({id}, {name})"),
             }
         )
     }
@@ -631,7 +764,8 @@ impl EventDetails {
                 | InfrastructureStep::UpgradeError
                 | InfrastructureStep::DeleteError
                 | InfrastructureStep::RestartedError
-                | InfrastructureStep::CannotProcessRequest => return,
+                | InfrastructureStep::CannotProcessRequest
+                | InfrastructureStep::TrafficFailover => return,
             },
             Stage::Environment(step) => match step {
                 EnvironmentStep::Build | EnvironmentStep::Built => Stage::Environment(EnvironmentStep::BuiltError),
@@ -660,7 +794,8 @@ impl EventDetails {
                 | EnvironmentStep::RestartedError
                 | EnvironmentStep::JobOutput
                 | EnvironmentStep::Recap
-                | EnvironmentStep::DatabaseOutput => return,
+                | EnvironmentStep::DatabaseOutput
+                | EnvironmentStep::TerraformOutput => return,
             },
         };
     }
@@ -728,8 +863,8 @@ mod tests {
     use crate::cloud_provider::Kind;
     use crate::errors::{CommandError, EngineError};
     use crate::events::{
-        EngineEvent, EnvironmentStep, EventDetails, EventMessage, EventMessageVerbosity, InfrastructureStep, Stage,
-        Transmitter,
+        EngineEvent, EnvironmentStep, EventDetails, EventMessage, EventMessageVerbosity, InfrastructureStep,
+        MessageCode, Stage, Transmitter,
     };
     use crate::io_models::QoveryIdentifier;
     use uuid::Uuid;
@@ -799,6 +934,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_event_message_from_code() {
+        // execute:
+        let event_message = EventMessage::new_from_code(
+            MessageCode::WorkspaceNearlyFull,
+            &[("usage", "17 GiB".to_string()), ("quota", "20 GiB".to_string())],
+            Some("raw".to_string()),
+        );
+
+        // validate:
+        assert_eq!(Some(MessageCode::WorkspaceNearlyFull), event_message.code());
+        assert_eq!(Some(&"17 GiB".to_string()), event_message.parameters().get("usage"));
+        assert_eq!(
+            "Engine workspace is nearly full: 17 GiB used out of 20 GiB",
+            event_message.message(EventMessageVerbosity::SafeOnly)
+        );
+        assert_eq!(
+            "Engine workspace is nearly full: 17 GiB used out of 20 GiB / Full details: raw",
+            event_message.message(EventMessageVerbosity::FullDetailsWithoutEnvVars)
+        );
+        assert_eq!(
+            "Kubernetes cluster failure {step}",
+            EventMessage::new_from_code(MessageCode::InfrastructureStepFailed, &[], None).to_string()
+        );
+    }
+
     #[test]
     fn test_stage_sub_step_name() {
         // setup:
@@ -5,6 +5,7 @@ use std::io::{BufRead, BufReader, Error, ErrorKind, Write};
 use std::path::{Path, PathBuf};
 
 use crate::cmd::structs::SecretItem;
+use crate::encryption::{is_encrypted, workspace_cipher};
 use crate::errors::CommandError;
 use base64::engine::general_purpose;
 use base64::Engine;
@@ -15,6 +16,9 @@ use serde::__private::from_utf8_lossy;
 use std::ffi::OsStr;
 use walkdir::WalkDir;
 
+/// Directory of the working root dir holding the workspace of each execution
+pub const WORKSPACE_DIRECTORY_NAME: &str = ".qovery-workspace";
+
 pub fn delete_file_if_exists(file: &Path) -> Result<(), Error> {
     if !file.exists() {
         return Ok(());
@@ -69,7 +73,7 @@ where
 {
     let dir = working_root_dir
         .as_ref()
-        .join(".qovery-workspace")
+        .join(WORKSPACE_DIRECTORY_NAME)
         .join(execution_id)
         .join(dir_name);
 
@@ -78,9 +82,34 @@ where
     Ok(dir)
 }
 
+// Files holding credentials, passwords or tokens of the request, they are encrypted when archived
+const SENSITIVE_FILE_NAMES: [&str; 6] = [
+    "qovery-tf-config.json",
+    "database-tf-config.json",
+    "terraform.tfstate",
+    "terraform.tfstate.backup",
+    "gcp-credentials.json",
+    "qovery-values.yaml",
+];
+const SENSITIVE_FILE_SUFFIXES: [&str; 2] = [".tfvars", "-q-backup.yaml"];
+const SENSITIVE_DIRECTORY_PREFIX: &str = "qovery-kubeconfigs-";
+
+fn is_sensitive_file(relative_path: &Path) -> bool {
+    let file_name = relative_path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+    SENSITIVE_FILE_NAMES.contains(&file_name)
+        || SENSITIVE_FILE_SUFFIXES.iter().any(|suffix| file_name.ends_with(suffix))
+        || relative_path.components().any(|component| {
+            component
+                .as_os_str()
+                .to_string_lossy()
+                .starts_with(SENSITIVE_DIRECTORY_PREFIX)
+        })
+}
+
 fn archive_workspace_directory(working_root_dir: &str, execution_id: &str) -> Result<PathBuf, Error> {
     let workspace_dir = root_workspace_directory(working_root_dir, execution_id)?;
-    let tgz_file_path = PathBuf::from(format!("{working_root_dir}/.qovery-workspace/{execution_id}.tgz").as_str());
+    let tgz_file_path =
+        PathBuf::from(format!("{working_root_dir}/{WORKSPACE_DIRECTORY_NAME}/{execution_id}.tgz").as_str());
     let tgz_file = File::create(&tgz_file_path)?;
 
     let enc = GzEncoder::new(tgz_file, Compression::fast());
@@ -109,7 +138,29 @@ fn archive_workspace_directory(working_root_dir: &str, execution_id: &str) -> Re
             .strip_prefix(&workspace_dir)
             .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
 
-        tar.append_path_with_name(entry, relative_path)?;
+        if !is_sensitive_file(relative_path) {
+            tar.append_path_with_name(entry, relative_path)?;
+            continue;
+        }
+
+        // the archive is uploaded and kept after the workspace is cleaned up, so secrets must not be stored in clear
+        let content = fs::read(entry)?;
+        // files holding secrets during the execution are already encrypted with the same key
+        let content = match is_encrypted(&content) {
+            true => content,
+            false => workspace_cipher()
+                .and_then(|cipher| cipher.encrypt(&content))
+                .map_err(|err| Error::new(ErrorKind::Other, err))?,
+        };
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o600);
+        header.set_cksum();
+        tar.append_data(
+            &mut header,
+            format!("{}.enc", relative_path.to_string_lossy()),
+            content.as_slice(),
+        )?;
     }
 
     Ok(tgz_file_path)
@@ -477,6 +528,17 @@ mod tests {
         tmp_dir.close().expect("error closing temporary directory");
     }
 
+    #[test]
+    fn test_is_sensitive_file() {
+        assert!(is_sensitive_file(Path::new("qovery-tf-config.json")));
+        assert!(is_sensitive_file(Path::new("dir-1/terraform.tfstate")));
+        assert!(is_sensitive_file(Path::new("dir-1/cluster.tfvars")));
+        assert!(is_sensitive_file(Path::new("backups/postgresql-q-backup.yaml")));
+        assert!(is_sensitive_file(Path::new("qovery-kubeconfigs-z1234/z1234.yaml")));
+        assert!(!is_sensitive_file(Path::new("dir-1/main.tf")));
+        assert!(!is_sensitive_file(Path::new("charts/values.yaml")));
+    }
+
     #[test]
     fn test_backup_cleaning() {
         let content = r#"
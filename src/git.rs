@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use git2::build::CheckoutBuilder;
 use git2::ErrorCode::Auth;
@@ -9,6 +9,29 @@ use git2::{
 };
 use url::Url;
 
+/// Step of the clone of a repository which failed
+#[derive(thiserror::Error, Debug)]
+pub enum CloneError {
+    #[error("Cannot fetch repository: {0}")]
+    Fetch(Error),
+
+    #[error("Cannot checkout commit: {0}")]
+    Checkout(Error),
+
+    #[error("Cannot update submodule `{path}`: {raw_error}")]
+    Submodule { path: String, raw_error: Error },
+}
+
+impl From<CloneError> for Error {
+    fn from(clone_error: CloneError) -> Self {
+        match clone_error {
+            CloneError::Fetch(raw_error)
+            | CloneError::Checkout(raw_error)
+            | CloneError::Submodule { raw_error, .. } => raw_error,
+        }
+    }
+}
+
 pub fn clone_at_commit<P>(
     repository_url: &Url,
     commit_id: &str,
@@ -18,29 +41,72 @@ pub fn clone_at_commit<P>(
 where
     P: AsRef<Path>,
 {
-    let repo = fetch(repository_url, into_dir, get_credentials, commit_id)?;
+    clone_at_commit_with_submodules(repository_url, commit_id, into_dir, get_credentials, false)?;
+    Ok(())
+}
+
+/// Clones the repository at a commit with its submodules. Only the submodules of the repository are fetched,
+/// unless `recursive_submodules` is set, in which case the submodules of the submodules are fetched too.
+pub fn clone_at_commit_with_submodules<P>(
+    repository_url: &Url,
+    commit_id: &str,
+    into_dir: P,
+    get_credentials: &impl Fn(&str) -> Vec<(CredentialType, Cred)>,
+    recursive_submodules: bool,
+) -> Result<(), CloneError>
+where
+    P: AsRef<Path>,
+{
+    let repo = fetch(repository_url, into_dir, get_credentials, commit_id).map_err(CloneError::Fetch)?;
 
     // position the repo at the correct commit
-    let _ = checkout(&repo, commit_id)?;
-
-    // check submodules if needed
-    {
-        let submodules = repo.submodules()?;
-        if !submodules.is_empty() {
-            // for auth
-            let mut callbacks = RemoteCallbacks::new();
-            callbacks.credentials(authentication_callback(&get_credentials));
-            callbacks.certificate_check(|_, _| Ok(CertificateCheckStatus::CertificateOk));
-
-            let mut fo = FetchOptions::new();
-            fo.remote_callbacks(callbacks);
-            let mut opts = SubmoduleUpdateOptions::new();
-            opts.fetch(fo);
-
-            for mut submodule in submodules {
-                info!("getting submodule {:?} from {:?}", submodule.name(), submodule.url());
-                submodule.update(true, Some(&mut opts))?
-            }
+    let _ = checkout(&repo, commit_id).map_err(CloneError::Checkout)?;
+
+    update_submodules(&repo, Path::new(""), get_credentials, recursive_submodules)
+}
+
+fn update_submodules(
+    repo: &Repository,
+    parent_path: &Path,
+    get_credentials: &impl Fn(&str) -> Vec<(CredentialType, Cred)>,
+    recursive: bool,
+) -> Result<(), CloneError> {
+    let submodule_error = |path: PathBuf, raw_error: Error| CloneError::Submodule {
+        path: path.to_string_lossy().to_string(),
+        raw_error,
+    };
+
+    let submodules = repo.submodules().map_err(|err| {
+        let path = match parent_path.as_os_str().is_empty() {
+            true => PathBuf::from("."),
+            false => parent_path.to_path_buf(),
+        };
+        submodule_error(path, err)
+    })?;
+    if submodules.is_empty() {
+        return Ok(());
+    }
+
+    // for auth
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(authentication_callback(&get_credentials));
+    callbacks.certificate_check(|_, _| Ok(CertificateCheckStatus::CertificateOk));
+
+    let mut fo = FetchOptions::new();
+    fo.remote_callbacks(callbacks);
+    let mut opts = SubmoduleUpdateOptions::new();
+    opts.fetch(fo);
+
+    for mut submodule in submodules {
+        let path = parent_path.join(submodule.path());
+        info!("getting submodule {:?} from {:?}", submodule.name(), submodule.url());
+        submodule
+            .update(true, Some(&mut opts))
+            .map_err(|err| submodule_error(path.clone(), err))?;
+
+        if recursive {
+            let submodule_repo = submodule.open().map_err(|err| submodule_error(path.clone(), err))?;
+            update_submodules(&submodule_repo, &path, get_credentials, recursive)?;
         }
     }
 
@@ -0,0 +1,790 @@
+use crate::cmd::docker::Docker;
+use crate::deployment_freeze::DeploymentFreezeOverride;
+use crate::encryption::{check_workspace_encryption_key, EncryptionError};
+use crate::engine_task::environment_task::EnvironmentTask;
+use crate::engine_task::infrastructure_task::InfrastructureTask;
+use crate::engine_task::qovery_api::QoveryApi;
+use crate::engine_task::traffic_failover_task::TrafficFailoverTask;
+use crate::engine_task::Task;
+use crate::errors;
+use crate::events;
+use crate::events::{EventMessageVerbosity, Transmitter};
+use crate::io_models::context::{Context, Features, Metadata};
+use crate::io_models::engine_request::{
+    EngineRequest, EnvironmentEngineRequest, TrafficFailoverEndpoint, TrafficFailoverEngineRequest,
+    DEFAULT_TRAFFIC_FAILOVER_PROBE_INTERVAL_IN_SECONDS,
+};
+use crate::io_models::environment::EnvironmentRequest;
+use crate::io_models::Action;
+use crate::janitor::{existing_owners, run_cluster_janitor, JanitorError, JanitorPolicy, JanitorReport};
+use crate::lib_archive::{fetch_lib_directory, lib_cache_dir};
+use crate::logger::{Logger, UnboundedSenderLogger};
+use crate::metrics_registry::MetricsRegistry;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use proto::engine_worker_server::{EngineWorker, EngineWorkerServer};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use url::Url;
+use uuid::Uuid;
+
+pub mod proto {
+    tonic::include_proto!("qovery.engine.v1");
+}
+
+/// Builds the Qovery API client of a request, from its deployment token
+pub type QoveryApiFactory = Box<dyn Fn(&str) -> Box<dyn QoveryApi> + Send + Sync>;
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<proto::EngineEvent, Status>> + Send>>;
+
+// terminated tasks are only remembered to answer the status requests following their termination
+const TERMINATED_TASK_TTL: Duration = Duration::from_secs(60 * 60);
+const MAX_TERMINATED_TASKS: usize = 1000;
+// Caps the builds of each environment running at the same time, whatever the parallelism its request asks for
+const MAX_PARALLEL_BUILDS_ENV_VAR: &str = "ENGINE_MAX_PARALLEL_BUILDS";
+const MAX_JANITOR_ORPHAN_TTL_IN_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+fn parse_max_parallel_builds(value: Option<&str>) -> Option<NonZeroUsize> {
+    let value = value?;
+    match value.trim().parse::<NonZeroUsize>() {
+        Ok(max_parallel_builds) => Some(max_parallel_builds),
+        Err(_) => {
+            warn!("Ignoring invalid {}: `{}`", MAX_PARALLEL_BUILDS_ENV_VAR, value);
+            None
+        }
+    }
+}
+
+enum TaskEntry {
+    Running(Arc<dyn Task>),
+    // the task is dropped once terminated, closing the event stream of its request
+    Terminated(Instant),
+}
+
+/// Forgets the tasks terminated for too long, and the oldest ones when too many are remembered
+fn evict_terminated_tasks(tasks: &mut HashMap<String, TaskEntry>, now: Instant) {
+    tasks.retain(|_, entry| match entry {
+        TaskEntry::Running(_) => true,
+        TaskEntry::Terminated(terminated_at) => now.duration_since(*terminated_at) < TERMINATED_TASK_TTL,
+    });
+
+    let mut terminated: Vec<(Instant, String)> = tasks
+        .iter()
+        .filter_map(|(execution_id, entry)| match entry {
+            TaskEntry::Running(_) => None,
+            TaskEntry::Terminated(terminated_at) => Some((*terminated_at, execution_id.clone())),
+        })
+        .collect();
+    if terminated.len() > MAX_TERMINATED_TASKS {
+        terminated.sort();
+        for (_, execution_id) in &terminated[..terminated.len() - MAX_TERMINATED_TASKS] {
+            tasks.remove(execution_id);
+        }
+    }
+}
+
+/// gRPC service of an engine worker, running each request it receives in a blocking thread
+pub struct EngineWorkerService {
+    workspace_root_dir: String,
+    lib_root_dir: String,
+    docker: Arc<Docker>,
+    metrics_registry: Box<dyn MetricsRegistry>,
+    qovery_api_factory: QoveryApiFactory,
+    max_parallel_builds: Option<NonZeroUsize>,
+    tasks: Arc<Mutex<HashMap<String, TaskEntry>>>,
+}
+
+impl EngineWorkerService {
+    /// Fails without a valid workspace encryption key, the secrets of the requests being written encrypted
+    pub fn new(
+        workspace_root_dir: String,
+        lib_root_dir: String,
+        docker: Arc<Docker>,
+        metrics_registry: Box<dyn MetricsRegistry>,
+        qovery_api_factory: QoveryApiFactory,
+    ) -> Result<Self, EncryptionError> {
+        check_workspace_encryption_key()?;
+
+        Ok(EngineWorkerService {
+            workspace_root_dir,
+            lib_root_dir,
+            docker,
+            metrics_registry,
+            qovery_api_factory,
+            max_parallel_builds: parse_max_parallel_builds(std::env::var(MAX_PARALLEL_BUILDS_ENV_VAR).ok().as_deref()),
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Caps the builds of each environment deployed by the worker running at the same time, overriding the
+    /// `ENGINE_MAX_PARALLEL_BUILDS` environment variable
+    pub fn with_max_parallel_builds(mut self, max_parallel_builds: Option<NonZeroUsize>) -> Self {
+        self.max_parallel_builds = max_parallel_builds;
+        self
+    }
+
+    /// Renders the templates from the lib archive of the release instead of a lib directory checkout, the archive
+    /// being fetched and verified once into the workspace cache
+    pub fn with_lib_archive(mut self, archive_base_url: &Url, digest: &str) -> Result<Self, io::Error> {
+        let lib_root_dir = fetch_lib_directory(archive_base_url, digest, &lib_cache_dir(&self.workspace_root_dir))?;
+        self.lib_root_dir = lib_root_dir.to_string_lossy().to_string();
+        Ok(self)
+    }
+
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+        info!("Engine worker gRPC server listening on {}", addr);
+        Server::builder()
+            .add_service(EngineWorkerServer::new(self))
+            .serve(addr)
+            .await
+    }
+
+    fn spawn_task(
+        &self,
+        build_task: impl FnOnce(Box<dyn Logger>) -> Arc<dyn Task>,
+    ) -> Result<Response<EventStream>, Status> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = build_task(Box::new(UnboundedSenderLogger::new(tx, vec![])));
+        let execution_id = task.id().to_string();
+
+        {
+            let mut tasks = self
+                .tasks
+                .lock()
+                .map_err(|_| Status::internal("tasks lock is poisoned"))?;
+            if matches!(tasks.get(&execution_id), Some(TaskEntry::Running(_))) {
+                return Err(Status::already_exists(format!("execution {execution_id} is already running")));
+            }
+            tasks.insert(execution_id.clone(), TaskEntry::Running(task.clone()));
+        }
+
+        let tasks = self.tasks.clone();
+        let run = tokio::task::spawn_blocking(move || task.run());
+        tokio::spawn(async move {
+            // a task which panicked is terminated too, its execution can be retried
+            if let Err(err) = run.await {
+                error!("Execution {} terminated abruptly: {}", execution_id, err);
+            }
+            if let Ok(mut tasks) = tasks.lock() {
+                let now = Instant::now();
+                tasks.insert(execution_id, TaskEntry::Terminated(now));
+                evict_terminated_tasks(&mut tasks, now);
+            }
+        });
+
+        let events = UnboundedReceiverStream::new(rx).map(|event| Ok(to_proto_event(event)));
+        Ok(Response::new(Box::pin(events)))
+    }
+
+    fn running_task(&self, execution_id: &str) -> Result<Option<Arc<dyn Task>>, Status> {
+        let tasks = self
+            .tasks
+            .lock()
+            .map_err(|_| Status::internal("tasks lock is poisoned"))?;
+        Ok(match tasks.get(execution_id) {
+            Some(TaskEntry::Running(task)) => Some(task.clone()),
+            _ => None,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl EngineWorker for EngineWorkerService {
+    type DeployEnvironmentStream = EventStream;
+    type DeployClusterStream = EventStream;
+    type MonitorTrafficFailoverStream = EventStream;
+
+    async fn deploy_environment(
+        &self,
+        request: Request<proto::EnvironmentRequest>,
+    ) -> Result<Response<Self::DeployEnvironmentStream>, Status> {
+        let request = from_proto_environment_engine_request(request.into_inner())?;
+
+        self.spawn_task(|logger| {
+            let qovery_api = (self.qovery_api_factory)(&request.deployment_jwt_token);
+            Arc::new(
+                EnvironmentTask::new(
+                    request,
+                    self.workspace_root_dir.clone(),
+                    self.lib_root_dir.clone(),
+                    self.docker.clone(),
+                    logger,
+                    self.metrics_registry.clone_dyn(),
+                    qovery_api,
+                )
+                .with_max_parallel_builds(self.max_parallel_builds),
+            )
+        })
+    }
+
+    async fn deploy_cluster(
+        &self,
+        request: Request<proto::ClusterRequest>,
+    ) -> Result<Response<Self::DeployClusterStream>, Status> {
+        let request = request.into_inner();
+        let request = from_proto_engine_request(request.envelope, request.cluster, None)?;
+
+        self.spawn_task(|logger| {
+            let qovery_api = (self.qovery_api_factory)(&request.deployment_jwt_token);
+            Arc::new(InfrastructureTask::new(
+                request,
+                self.workspace_root_dir.clone(),
+                self.lib_root_dir.clone(),
+                self.docker.clone(),
+                logger,
+                self.metrics_registry.clone_dyn(),
+                qovery_api,
+            ))
+        })
+    }
+
+    async fn monitor_traffic_failover(
+        &self,
+        request: Request<proto::TrafficFailoverRequest>,
+    ) -> Result<Response<Self::MonitorTrafficFailoverStream>, Status> {
+        let request = from_proto_traffic_failover_request(request.into_inner())?;
+
+        self.spawn_task(|logger| {
+            let qovery_api = (self.qovery_api_factory)(&request.cluster_jwt_token);
+            Arc::new(TrafficFailoverTask::new(
+                request,
+                self.workspace_root_dir.clone(),
+                self.lib_root_dir.clone(),
+                self.docker.clone(),
+                logger,
+                qovery_api,
+            ))
+        })
+    }
+
+    async fn run_janitor(
+        &self,
+        request: Request<proto::JanitorRequest>,
+    ) -> Result<Response<proto::JanitorResponse>, Status> {
+        let request = request.into_inner();
+        let policy = from_proto_janitor_policy(request.apply, request.orphan_ttl_in_seconds)?;
+        let request = from_proto_engine_request(request.envelope, request.cluster, None)?;
+        let qovery_api: Arc<dyn QoveryApi> = Arc::from((self.qovery_api_factory)(&request.deployment_jwt_token));
+        let context = Context::new(
+            request.organization_long_id,
+            request.kubernetes.long_id,
+            request.id.to_string(),
+            self.workspace_root_dir.clone(),
+            self.lib_root_dir.clone(),
+            request.test_cluster,
+            request.features.clone(),
+            request.metadata.clone(),
+            self.docker.clone(),
+            qovery_api.clone(),
+            request.event_details(),
+        );
+
+        // the existing owners are fetched from the API of the organization, the janitor refusing to run without them
+        let report = tokio::task::spawn_blocking(move || {
+            let owners = existing_owners(&request, qovery_api.as_ref())?;
+            run_cluster_janitor(&request, &context, &owners, &policy)
+        })
+        .await
+        .map_err(|err| Status::internal(format!("janitor terminated abruptly: {err}")))?
+        .map_err(to_janitor_status)?;
+
+        Ok(Response::new(to_proto_janitor_response(report)))
+    }
+
+    async fn cancel(&self, request: Request<proto::CancelRequest>) -> Result<Response<proto::CancelResponse>, Status> {
+        let cancel_requested = match self.running_task(&request.into_inner().execution_id)? {
+            Some(task) => task.cancel(),
+            None => false,
+        };
+
+        Ok(Response::new(proto::CancelResponse { cancel_requested }))
+    }
+
+    async fn status(&self, request: Request<proto::StatusRequest>) -> Result<Response<proto::StatusResponse>, Status> {
+        let execution_id = request.into_inner().execution_id;
+        let tasks = self
+            .tasks
+            .lock()
+            .map_err(|_| Status::internal("tasks lock is poisoned"))?;
+        let status = match tasks.get(&execution_id) {
+            Some(TaskEntry::Running(task)) if !task.is_terminated() => proto::TaskStatus::Running,
+            Some(_) => proto::TaskStatus::Terminated,
+            None => proto::TaskStatus::Unknown,
+        };
+
+        Ok(Response::new(proto::StatusResponse { status: status.into() }))
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(field: &str, json: &str) -> Result<T, Status> {
+    serde_json::from_str(json).map_err(|err| Status::invalid_argument(format!("invalid `{field}`: {err}")))
+}
+
+fn parse_json_list<T: DeserializeOwned>(field: &str, jsons: &[String]) -> Result<Vec<T>, Status> {
+    jsons
+        .iter()
+        .enumerate()
+        .map(|(idx, json)| parse_json(&format!("{field}[{idx}]"), json))
+        .collect()
+}
+
+/// Optional document, its default being used when empty
+fn parse_json_or_default<T: DeserializeOwned + Default>(field: &str, json: &str) -> Result<T, Status> {
+    match json {
+        "" => Ok(T::default()),
+        json => parse_json(field, json),
+    }
+}
+
+fn parse_uuid(field: &str, id: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(id).map_err(|err| Status::invalid_argument(format!("invalid `{field}`: {err}")))
+}
+
+fn parse_timestamp(field: &str, timestamp_ms: i64) -> Result<DateTime<Utc>, Status> {
+    Utc.timestamp_millis_opt(timestamp_ms)
+        .single()
+        .ok_or_else(|| Status::invalid_argument(format!("invalid `{field}`: {timestamp_ms}")))
+}
+
+fn from_proto_features(features: impl Iterator<Item = proto::Feature>) -> Vec<Features> {
+    features
+        .filter_map(|feature| match feature {
+            proto::Feature::Unspecified => None,
+            proto::Feature::LogsHistory => Some(Features::LogsHistory),
+            proto::Feature::MetricsHistory => Some(Features::MetricsHistory),
+            proto::Feature::Grafana => Some(Features::Grafana),
+        })
+        .collect()
+}
+
+fn from_proto_metadata(metadata: Option<proto::RequestMetadata>) -> Option<Metadata> {
+    metadata.map(|metadata| {
+        Metadata::new(
+            metadata.dry_run_deploy,
+            metadata.resource_expiration_in_seconds,
+            metadata.forced_upgrade,
+            metadata.is_first_cluster_deployment,
+        )
+    })
+}
+
+fn from_proto_action(action: proto::Action) -> Result<Action, Status> {
+    Ok(match action {
+        proto::Action::Unspecified => return Err(Status::invalid_argument("missing `envelope.action`")),
+        proto::Action::Create => Action::Create,
+        proto::Action::Pause => Action::Pause,
+        proto::Action::Delete => Action::Delete,
+        proto::Action::Restart => Action::Restart,
+        proto::Action::TriggerNow => Action::TriggerNow,
+    })
+}
+
+fn from_proto_engine_request<T>(
+    envelope: Option<proto::RequestEnvelope>,
+    cluster: Option<proto::ClusterDefinition>,
+    target_environment: T,
+) -> Result<EngineRequest<T>, Status> {
+    let envelope = envelope.ok_or_else(|| Status::invalid_argument("missing `envelope`"))?;
+    let cluster = cluster.ok_or_else(|| Status::invalid_argument("missing `cluster`"))?;
+    let action = from_proto_action(envelope.action())?;
+    let features = from_proto_features(envelope.features());
+
+    Ok(EngineRequest {
+        id: envelope.execution_id,
+        organization_id: envelope.organization_id,
+        organization_long_id: parse_uuid("envelope.organization_long_id", &envelope.organization_long_id)?,
+        deployment_jwt_token: envelope.deployment_jwt_token,
+        created_at: parse_timestamp("envelope.created_at_ms", envelope.created_at_ms)?,
+        action,
+        features,
+        test_cluster: cluster.test_cluster,
+        build_platform: parse_json("cluster.build_platform_json", &cluster.build_platform_json)?,
+        cloud_provider: parse_json("cluster.cloud_provider_json", &cluster.cloud_provider_json)?,
+        dns_provider: parse_json("cluster.dns_provider_json", &cluster.dns_provider_json)?,
+        container_registry: parse_json("cluster.container_registry_json", &cluster.container_registry_json)?,
+        kubernetes: parse_json("cluster.kubernetes_json", &cluster.kubernetes_json)?,
+        target_environment,
+        metadata: from_proto_metadata(envelope.metadata),
+        archive: match cluster.archive_json.as_str() {
+            "" => None,
+            archive_json => Some(parse_json("cluster.archive_json", archive_json)?),
+        },
+        deployment_freeze_override: envelope.deployment_freeze_override.map(|freeze_override| {
+            DeploymentFreezeOverride {
+                reason: freeze_override.reason,
+                requested_by: freeze_override.requested_by,
+            }
+        }),
+    })
+}
+
+fn from_proto_environment_engine_request(
+    request: proto::EnvironmentRequest,
+) -> Result<EnvironmentEngineRequest, Status> {
+    let envelope = request
+        .envelope
+        .ok_or_else(|| Status::invalid_argument("missing `envelope`"))?;
+    let environment = request
+        .target_environment
+        .ok_or_else(|| Status::invalid_argument("missing `target_environment`"))?;
+    let service_dependencies = environment
+        .service_dependencies
+        .into_iter()
+        .map(|(service_long_id, dependencies)| {
+            let field = "target_environment.service_dependencies";
+            Ok((
+                parse_uuid(field, &service_long_id)?,
+                dependencies
+                    .service_long_ids
+                    .iter()
+                    .map(|dependency_long_id| parse_uuid(field, dependency_long_id))
+                    .collect::<Result<Vec<_>, Status>>()?,
+            ))
+        })
+        .collect::<Result<BTreeMap<_, _>, Status>>()?;
+
+    let target_environment = EnvironmentRequest {
+        execution_id: envelope.execution_id.clone(),
+        long_id: parse_uuid("target_environment.long_id", &environment.long_id)?,
+        name: environment.name,
+        kube_name: environment.kube_name,
+        project_long_id: parse_uuid("target_environment.project_long_id", &environment.project_long_id)?,
+        organization_long_id: parse_uuid("envelope.organization_long_id", &envelope.organization_long_id)?,
+        action: from_proto_action(envelope.action())?,
+        max_parallel_build: environment.max_parallel_build.max(1),
+        max_parallel_deploy: environment.max_parallel_deploy.max(1),
+        applications: parse_json_list("target_environment.applications_json", &environment.applications_json)?,
+        containers: parse_json_list("target_environment.containers_json", &environment.containers_json)?,
+        jobs: parse_json_list("target_environment.jobs_json", &environment.jobs_json)?,
+        routers: parse_json_list("target_environment.routers_json", &environment.routers_json)?,
+        databases: parse_json_list("target_environment.databases_json", &environment.databases_json)?,
+        helms: parse_json_list("target_environment.helms_json", &environment.helms_json)?,
+        kustomizations: parse_json_list("target_environment.kustomizations_json", &environment.kustomizations_json)?,
+        terraform_services: parse_json_list(
+            "target_environment.terraform_services_json",
+            &environment.terraform_services_json,
+        )?,
+        shared_volumes: parse_json_list("target_environment.shared_volumes_json", &environment.shared_volumes_json)?,
+        service_account: parse_json_or_default(
+            "target_environment.service_account_json",
+            &environment.service_account_json,
+        )?,
+        remote_builder: parse_json_or_default(
+            "target_environment.remote_builder_json",
+            &environment.remote_builder_json,
+        )?,
+        deployment_waves: parse_json_or_default(
+            "target_environment.deployment_waves_json",
+            &environment.deployment_waves_json,
+        )?,
+        service_dependencies,
+    };
+
+    from_proto_engine_request(Some(envelope), request.cluster, target_environment)
+}
+
+fn from_proto_traffic_failover_request(
+    request: proto::TrafficFailoverRequest,
+) -> Result<TrafficFailoverEngineRequest, Status> {
+    let features = from_proto_features(request.features());
+    let endpoints = request
+        .endpoints
+        .into_iter()
+        .map(|endpoint| {
+            Ok(TrafficFailoverEndpoint {
+                cluster_long_id: parse_uuid("endpoints.cluster_long_id", &endpoint.cluster_long_id)?,
+                probe_address: endpoint.probe_address,
+                record_value: endpoint.record_value,
+                weight: endpoint.weight,
+            })
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    Ok(TrafficFailoverEngineRequest {
+        id: request.execution_id,
+        organization_long_id: parse_uuid("organization_long_id", &request.organization_long_id)?,
+        cluster_long_id: parse_uuid("cluster_long_id", &request.cluster_long_id)?,
+        cluster_jwt_token: request.cluster_jwt_token,
+        created_at: parse_timestamp("created_at_ms", request.created_at_ms)?,
+        features,
+        metadata: from_proto_metadata(request.metadata),
+        dns_provider: parse_json("dns_provider_json", &request.dns_provider_json)?,
+        record_name: request.record_name,
+        endpoints,
+        probe_interval_in_seconds: match request.probe_interval_in_seconds {
+            0 => DEFAULT_TRAFFIC_FAILOVER_PROBE_INTERVAL_IN_SECONDS,
+            probe_interval_in_seconds => probe_interval_in_seconds,
+        },
+        failure_threshold: request.failure_threshold,
+        recovery_threshold: request.recovery_threshold,
+    })
+}
+
+/// Only reports the orphans unless asked to delete them
+fn from_proto_janitor_policy(apply: bool, orphan_ttl_in_seconds: u64) -> Result<JanitorPolicy, Status> {
+    let default_policy = JanitorPolicy::default();
+    let orphan_ttl = match orphan_ttl_in_seconds {
+        0 => default_policy.orphan_ttl,
+        orphan_ttl_in_seconds if orphan_ttl_in_seconds <= MAX_JANITOR_ORPHAN_TTL_IN_SECONDS => {
+            chrono::Duration::from_std(Duration::from_secs(orphan_ttl_in_seconds))
+                .map_err(|err| Status::invalid_argument(format!("invalid `orphan_ttl_in_seconds`: {err}")))?
+        }
+        orphan_ttl_in_seconds => {
+            return Err(Status::invalid_argument(format!(
+                "invalid `orphan_ttl_in_seconds`: {orphan_ttl_in_seconds} is above {MAX_JANITOR_ORPHAN_TTL_IN_SECONDS}"
+            )))
+        }
+    };
+
+    Ok(JanitorPolicy {
+        orphan_ttl,
+        dry_run: !apply,
+    })
+}
+
+fn to_janitor_status(error: JanitorError) -> Status {
+    match error {
+        JanitorError::NoExistingOwners => Status::failed_precondition(error.to_string()),
+        JanitorError::CannotGetExistingOwners { .. } => Status::unavailable(error.to_string()),
+        JanitorError::InvalidCloudProvider => Status::invalid_argument(error.to_string()),
+        JanitorError::UnsupportedCloudProvider(_) => Status::unimplemented(error.to_string()),
+        JanitorError::CannotListResources { .. } | JanitorError::CannotDeleteResource { .. } => {
+            Status::internal(error.to_string())
+        }
+    }
+}
+
+fn to_proto_janitor_response(report: JanitorReport) -> proto::JanitorResponse {
+    let JanitorReport {
+        dry_run,
+        scanned,
+        expired,
+        failed,
+    } = report;
+    let expired = expired
+        .into_iter()
+        .map(|(resource, reason)| proto::ExpiredResource {
+            deletion_error: failed
+                .iter()
+                .find(|(failed_resource, _)| failed_resource == &resource)
+                .map(|(_, err)| err.to_string())
+                .unwrap_or_default(),
+            kind: resource.kind.to_string(),
+            id: resource.id,
+            reason: reason.to_string(),
+        })
+        .collect();
+
+    proto::JanitorResponse {
+        dry_run,
+        scanned: scanned as u64,
+        expired,
+    }
+}
+
+/// Name of a unit variant as serialized on the event bus, i.e: `CANNOT_CHECK_DEPLOYMENT_FREEZE`
+fn serialized_name(value: impl Serialize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn to_proto_transmitter(transmitter: Transmitter) -> proto::Transmitter {
+    let (kind, id, name) = match transmitter {
+        Transmitter::TaskManager(id, name) => ("engine_task_manager", id, name),
+        Transmitter::BuildPlatform(id, name) => ("build_platform", id, name),
+        Transmitter::ContainerRegistry(id, name) => ("container_registry", id, name),
+        Transmitter::CloudProvider(id, name) => ("cloud_provider", id, name),
+        Transmitter::Kubernetes(id, name) => ("kubernetes", id, name),
+        Transmitter::DnsProvider(id, name) => ("dns_provider", id, name),
+        Transmitter::ObjectStorage(id, name) => ("object_storage", id, name),
+        Transmitter::Environment(id, name) => ("environment", id, name),
+        Transmitter::Database(id, name) => ("database", id, name),
+        Transmitter::Application(id, name) => ("application", id, name),
+        Transmitter::Container(id, name) => ("container", id, name),
+        Transmitter::Helm(id, name) => ("helm_chart", id, name),
+        Transmitter::Router(id, name) => ("router", id, name),
+        Transmitter::Job(id, name) => ("job", id, name),
+        Transmitter::Kustomize(id, name) => ("kustomize", id, name),
+        Transmitter::TerraformService(id, name) => ("terraform_service", id, name),
+    };
+
+    proto::Transmitter {
+        kind: kind.to_string(),
+        id: id.to_string(),
+        name,
+    }
+}
+
+fn to_proto_error(error: &errors::EngineError) -> proto::EngineError {
+    proto::EngineError {
+        tag: serialized_name(errors::io::Tag::from(error.tag().clone())),
+        user_log_message: error.user_log_message().to_string(),
+        hint_message: error.hint_message().clone().unwrap_or_default(),
+        link: error.link().as_ref().map(|link| link.to_string()).unwrap_or_default(),
+        underlying_error_message: error
+            .underlying_error()
+            .map(|underlying_error| underlying_error.message_safe())
+            .unwrap_or_default(),
+    }
+}
+
+fn to_proto_event(event: events::EngineEvent) -> proto::EngineEvent {
+    let (level, message, error) = match &event {
+        events::EngineEvent::Debug(_, message) => (proto::EventLevel::Debug, Some(message), None),
+        events::EngineEvent::Info(_, message) => (proto::EventLevel::Info, Some(message), None),
+        events::EngineEvent::Warning(_, message) => (proto::EventLevel::Warning, Some(message), None),
+        events::EngineEvent::Error(error, message) => (proto::EventLevel::Error, message.as_ref(), Some(error)),
+    };
+    let details = event.get_details();
+
+    proto::EngineEvent {
+        level: level.into(),
+        timestamp_ms: Utc::now().timestamp_millis(),
+        execution_id: details.execution_id().to_string(),
+        stage: details.stage().to_string(),
+        step: details.stage().sub_step_name(),
+        transmitter: Some(to_proto_transmitter(details.transmitter())),
+        message: event.message(EventMessageVerbosity::SafeOnly),
+        organization_id: details.organisation_id().to_string(),
+        cluster_id: details.cluster_id().to_string(),
+        full_details: event.message(EventMessageVerbosity::FullDetailsWithoutEnvVars),
+        message_code: message
+            .and_then(|message| message.code())
+            .map(|code| serialized_name(events::io::MessageCode::from(code)))
+            .unwrap_or_default(),
+        message_parameters: message
+            .map(|message| message.parameters().clone().into_iter().collect())
+            .unwrap_or_default(),
+        error: error.map(to_proto_error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::janitor::{CloudResource, CloudResourceKind, ExpirationReason};
+
+    #[test]
+    fn test_evict_terminated_tasks() {
+        let started_at = Instant::now();
+        let mut tasks: HashMap<String, TaskEntry> = (0..MAX_TERMINATED_TASKS + 10)
+            .map(|i| {
+                (
+                    format!("execution-{i}"),
+                    TaskEntry::Terminated(started_at + Duration::from_secs(i as u64)),
+                )
+            })
+            .collect();
+
+        // the oldest ones are evicted first
+        evict_terminated_tasks(&mut tasks, started_at + Duration::from_secs(MAX_TERMINATED_TASKS as u64 + 10));
+        assert_eq!(tasks.len(), MAX_TERMINATED_TASKS);
+        assert!(!tasks.contains_key("execution-9"));
+        assert!(tasks.contains_key("execution-10"));
+
+        evict_terminated_tasks(&mut tasks, started_at + TERMINATED_TASK_TTL + Duration::from_secs(20));
+        assert_eq!(tasks.len(), MAX_TERMINATED_TASKS - 11);
+        assert!(!tasks.contains_key("execution-20"));
+        assert!(tasks.contains_key("execution-21"));
+    }
+
+    #[test]
+    fn test_parse_max_parallel_builds() {
+        assert_eq!(parse_max_parallel_builds(None), None);
+        assert_eq!(parse_max_parallel_builds(Some("4")), NonZeroUsize::new(4));
+        assert_eq!(parse_max_parallel_builds(Some("0")), None);
+        assert_eq!(parse_max_parallel_builds(Some("many")), None);
+    }
+
+    #[test]
+    fn test_from_proto_janitor_policy() {
+        // dry run unless asked otherwise
+        assert_eq!(from_proto_janitor_policy(false, 0).unwrap(), JanitorPolicy::default());
+        assert_eq!(
+            from_proto_janitor_policy(true, 3600).unwrap(),
+            JanitorPolicy {
+                orphan_ttl: chrono::Duration::hours(1),
+                dry_run: false,
+            }
+        );
+        assert_eq!(
+            from_proto_janitor_policy(false, u64::MAX).unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_to_proto_janitor_response() {
+        let resource = |id: &str| CloudResource {
+            kind: CloudResourceKind::Volume,
+            id: id.to_string(),
+            tags: HashMap::new(),
+            created_at: None,
+        };
+        let reason = ExpirationReason::ClusterDeleted("z1234".to_string());
+        let response = to_proto_janitor_response(JanitorReport {
+            dry_run: false,
+            scanned: 3,
+            expired: vec![(resource("vol-1"), reason.clone()), (resource("vol-2"), reason)],
+            failed: vec![(
+                resource("vol-2"),
+                JanitorError::CannotDeleteResource {
+                    resource: "volume vol-2".to_string(),
+                    raw_error_message: "volume is in use".to_string(),
+                },
+            )],
+        });
+
+        assert!(!response.dry_run);
+        assert_eq!(response.scanned, 3);
+        assert_eq!(
+            response.expired,
+            vec![
+                proto::ExpiredResource {
+                    kind: "volume".to_string(),
+                    id: "vol-1".to_string(),
+                    reason: "cluster z1234 does not exist anymore".to_string(),
+                    deletion_error: "".to_string(),
+                },
+                proto::ExpiredResource {
+                    kind: "volume".to_string(),
+                    id: "vol-2".to_string(),
+                    reason: "cluster z1234 does not exist anymore".to_string(),
+                    deletion_error: "Cannot delete volume vol-2: volume is in use".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_json_list() {
+        let volumes = ["{\"id\": 1}".to_string(), "{\"id\"".to_string()];
+        let err = parse_json_list::<serde_json::Value>("target_environment.shared_volumes_json", &volumes).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err
+            .message()
+            .starts_with("invalid `target_environment.shared_volumes_json[1]`"));
+
+        assert_eq!(
+            parse_json_or_default::<Option<Vec<u32>>>("target_environment.deployment_waves_json", "").unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_json_or_default::<Option<Vec<u32>>>("target_environment.deployment_waves_json", "[1]").unwrap(),
+            Some(vec![1])
+        );
+    }
+}
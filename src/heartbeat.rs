@@ -0,0 +1,301 @@
+use crate::events::{EngineEvent, EventDetails, EventMessage, MessageCode};
+use crate::logger::Logger;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+// kept next to the executions workspaces, so it survives their cleanup
+const STEP_DURATIONS_FILE_NAME: &str = ".qovery-step-durations.json";
+const MAX_RECORDED_DURATIONS_PER_STEP: usize = 100;
+// below this, a p99 would only be the slowest run and flag way too many steps
+const MIN_RECORDED_DURATIONS_FOR_WATCHDOG: usize = 20;
+
+/// Durations, in seconds, of the last successful runs of each long step
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+struct StepDurations {
+    steps: HashMap<String, Vec<u64>>,
+}
+
+impl StepDurations {
+    fn p99(&self, step: &str) -> Option<Duration> {
+        let mut durations = self.steps.get(step)?.clone();
+        if durations.len() < MIN_RECORDED_DURATIONS_FOR_WATCHDOG {
+            return None;
+        }
+
+        durations.sort_unstable();
+        // nearest rank percentile
+        let rank = (durations.len() * 99).div_ceil(100);
+        Some(Duration::from_secs(durations[rank - 1]))
+    }
+
+    fn record(&mut self, step: &str, duration: Duration) {
+        let durations = self.steps.entry(step.to_string()).or_default();
+        durations.push(duration.as_secs());
+        if durations.len() > MAX_RECORDED_DURATIONS_PER_STEP {
+            durations.drain(..durations.len() - MAX_RECORDED_DURATIONS_PER_STEP);
+        }
+    }
+}
+
+/// History of the step durations shared by all the executions of the engine
+#[derive(Clone, Debug)]
+pub struct StepDurationHistory {
+    file_path: PathBuf,
+}
+
+impl StepDurationHistory {
+    pub fn new<P: AsRef<Path>>(working_root_dir: P) -> Self {
+        StepDurationHistory {
+            file_path: working_root_dir.as_ref().join(STEP_DURATIONS_FILE_NAME),
+        }
+    }
+
+    // a missing or corrupted history only disables the watchdog
+    fn load(&self) -> StepDurations {
+        fs::read(&self.file_path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn p99(&self, step: &str) -> Option<Duration> {
+        self.load().p99(step)
+    }
+
+    pub fn record(&self, step: &str, duration: Duration) -> Result<(), std::io::Error> {
+        let mut step_durations = self.load();
+        step_durations.record(step, duration);
+
+        // written aside then renamed, so concurrent executions never read a partial file
+        let tmp_file_path = self.file_path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp_file_path, serde_json::to_vec(&step_durations)?)?;
+        fs::rename(&tmp_file_path, &self.file_path)
+    }
+}
+
+fn format_elapsed(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Periodically tells listeners that a long step, i.e: terraform apply, is still running so they can tell it apart
+/// from a hung one. A warning is emitted once when the step lasts longer than 99% of its previous runs.
+pub struct Heartbeat {
+    step: String,
+    started_at: Instant,
+    history: StepDurationHistory,
+    stop_tx: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    pub fn start(
+        step: &str,
+        event_details: EventDetails,
+        logger: Box<dyn Logger>,
+        history: StepDurationHistory,
+    ) -> Heartbeat {
+        Heartbeat::start_with_interval(step, event_details, logger, history, HEARTBEAT_INTERVAL)
+    }
+
+    fn start_with_interval(
+        step: &str,
+        event_details: EventDetails,
+        logger: Box<dyn Logger>,
+        history: StepDurationHistory,
+        interval: Duration,
+    ) -> Heartbeat {
+        let started_at = Instant::now();
+        let p99 = history.p99(step);
+        let (stop_tx, stop_rx) = channel::<()>();
+
+        let handle = std::thread::Builder::new()
+            .name(format!("heartbeat-{step}"))
+            .spawn({
+                let step = step.to_string();
+                move || {
+                    let mut watchdog_triggered = false;
+                    // the sender is dropped when the heartbeat stops, which wakes up the thread
+                    while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                        let elapsed = started_at.elapsed();
+                        logger.log(EngineEvent::Info(
+                            event_details.clone(),
+                            EventMessage::new_from_code(
+                                MessageCode::StepHeartbeat,
+                                &[("step", step.to_string()), ("elapsed", format_elapsed(elapsed))],
+                                None,
+                            ),
+                        ));
+
+                        match p99 {
+                            Some(p99) if !watchdog_triggered && elapsed > p99 => {
+                                watchdog_triggered = true;
+                                logger.log(EngineEvent::Warning(
+                                    event_details.clone(),
+                                    EventMessage::new_from_code(
+                                        MessageCode::StepLongerThanUsual,
+                                        &[
+                                            ("step", step.to_string()),
+                                            ("elapsed", format_elapsed(elapsed)),
+                                            ("p99", format_elapsed(p99)),
+                                        ],
+                                        None,
+                                    ),
+                                ));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            })
+            .map_err(|err| error!("Cannot start heartbeat of step {}: {}", step, err))
+            .ok();
+
+        Heartbeat {
+            step: step.to_string(),
+            started_at,
+            history,
+            stop_tx: Some(stop_tx),
+            handle,
+        }
+    }
+
+    /// Stops the heartbeat, only the duration of succeeded steps feeds the history as failures end at random times
+    pub fn stop(mut self, succeeded: bool) {
+        self.stop_thread();
+
+        if succeeded {
+            if let Err(err) = self.history.record(&self.step, self.started_at.elapsed()) {
+                warn!("Cannot record duration of step {}: {}", self.step, err);
+            }
+        }
+    }
+
+    fn stop_thread(&mut self) {
+        drop(self.stop_tx.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{InfrastructureStep, Stage, Transmitter};
+    use crate::io_models::QoveryIdentifier;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct RecordingLogger {
+        events: Arc<Mutex<Vec<EngineEvent>>>,
+    }
+
+    impl Logger for RecordingLogger {
+        fn log(&self, event: EngineEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Logger> {
+            Box::new(self.clone())
+        }
+
+        fn with_secrets(&self, _secrets: Vec<String>) -> Box<dyn Logger> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn event_details() -> EventDetails {
+        EventDetails::new(
+            None,
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random(),
+            "execution_id".to_string(),
+            Stage::Infrastructure(InfrastructureStep::Create),
+            Transmitter::Kubernetes(uuid::Uuid::new_v4(), "cluster".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_step_durations_p99() {
+        let mut step_durations = StepDurations::default();
+        for secs in 1..=19 {
+            step_durations.record("apply", Duration::from_secs(secs));
+        }
+        assert_eq!(step_durations.p99("apply"), None);
+        assert_eq!(step_durations.p99("unknown"), None);
+
+        for secs in 20..=200 {
+            step_durations.record("apply", Duration::from_secs(secs));
+        }
+        // only the last runs are kept, i.e: 101 to 200 seconds
+        assert_eq!(step_durations.steps["apply"].len(), MAX_RECORDED_DURATIONS_PER_STEP);
+        assert_eq!(step_durations.p99("apply"), Some(Duration::from_secs(199)));
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(42)), "42s");
+        assert_eq!(format_elapsed(Duration::from_secs(605)), "10m05s");
+        assert_eq!(format_elapsed(Duration::from_secs(7380)), "2h03m");
+    }
+
+    #[test]
+    fn test_heartbeat() {
+        // setup:
+        let working_root_dir = tempfile::tempdir().unwrap();
+        let history = StepDurationHistory::new(working_root_dir.path());
+        for _ in 0..MIN_RECORDED_DURATIONS_FOR_WATCHDOG {
+            history.record("apply", Duration::ZERO).unwrap();
+        }
+        let logger = RecordingLogger::default();
+
+        // execute:
+        let heartbeat = Heartbeat::start_with_interval(
+            "apply",
+            event_details(),
+            Box::new(logger.clone()),
+            history.clone(),
+            Duration::from_millis(50),
+        );
+        std::thread::sleep(Duration::from_millis(180));
+        heartbeat.stop(true);
+
+        // verify:
+        let codes = logger
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                EngineEvent::Info(_, message) | EngineEvent::Warning(_, message) => message.code(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(codes.iter().filter(|code| **code == MessageCode::StepHeartbeat).count() >= 2);
+        assert_eq!(
+            codes
+                .iter()
+                .filter(|code| **code == MessageCode::StepLongerThanUsual)
+                .count(),
+            1
+        );
+        assert_eq!(history.load().steps["apply"].len(), MIN_RECORDED_DURATIONS_FOR_WATCHDOG + 1);
+    }
+}
@@ -5,8 +5,11 @@ use crate::container_registry::ContainerRegistry;
 use crate::io_models::application::{to_environment_variable, Port, Storage};
 use crate::io_models::context::Context;
 use crate::io_models::probe::Probe;
+use crate::io_models::shared_volume::SharedVolumeMount;
 use crate::io_models::variable_utils::{default_environment_vars_with_info, VariableInfo};
-use crate::io_models::{Action, MountedFile};
+use crate::io_models::{
+    Action, ExternalSecretVariable, HpaCustomMetric, IpFamilyPolicy, KedaTrigger, LifecycleHooks, MountedFile,
+};
 use crate::models;
 use crate::models::aws::AwsAppExtraSettings;
 use crate::models::aws_ec2::AwsEc2AppExtraSettings;
@@ -25,7 +28,10 @@ use std::str::FromStr;
 use url::Url;
 use uuid::Uuid;
 
-use super::{PodAntiAffinity, UpdateStrategy};
+use super::{
+    ConfigReloadStrategy, PodAntiAffinity, PodAntiAffinityTopology, StatefulSetUpdateStrategy, StickySessionHashPolicy,
+    Toleration, TopologySpreadKey, TopologySpreadWhenUnsatisfiable, UpdateStrategy, WafMode,
+};
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Credentials {
@@ -228,6 +234,36 @@ pub struct ContainerAdvancedSettings {
     pub deployment_affinity_node_required: BTreeMap<String, String>,
     #[serde(alias = "deployment.antiaffinity.pod")]
     pub deployment_antiaffinity_pod: PodAntiAffinity,
+    #[serde(alias = "deployment.antiaffinity.pod.topology")]
+    pub deployment_antiaffinity_pod_topology: PodAntiAffinityTopology,
+    #[serde(alias = "deployment.config_reload.strategy")]
+    pub deployment_config_reload_strategy: ConfigReloadStrategy,
+    #[serde(alias = "deployment.config_reload.mount_path")]
+    pub deployment_config_reload_mount_path: String,
+    #[serde(alias = "deployment.config_reload.webhook_url")]
+    pub deployment_config_reload_webhook_url: String,
+    #[serde(alias = "deployment.topology_spread.key")]
+    pub deployment_topology_spread_key: TopologySpreadKey,
+    #[serde(alias = "deployment.topology_spread.max_skew")]
+    pub deployment_topology_spread_max_skew: u32,
+    #[serde(alias = "deployment.topology_spread.when_unsatisfiable")]
+    pub deployment_topology_spread_when_unsatisfiable: TopologySpreadWhenUnsatisfiable,
+    #[serde(alias = "deployment.tolerations")]
+    pub deployment_tolerations: Vec<Toleration>,
+    #[serde(alias = "deployment.env_vars_fast_path_enabled")]
+    pub deployment_env_vars_fast_path_enabled: bool,
+
+    // Statefulset, only used when the service has storages
+    #[serde(alias = "statefulset.update_strategy.type")]
+    pub statefulset_update_strategy_type: StatefulSetUpdateStrategy,
+    #[serde(alias = "statefulset.update_strategy.rolling_update.partition")]
+    pub statefulset_update_strategy_rolling_update_partition: u32,
+
+    // Pod disruption budget, only created when the service can run several instances
+    #[serde(alias = "pdb.min_available_percent")]
+    pub pdb_min_available_percent: u32,
+    #[serde(alias = "pdb.max_unavailable_percent")]
+    pub pdb_max_unavailable_percent: u32,
 
     // Ingress
     #[serde(alias = "network.ingress.proxy_body_size_mb")]
@@ -236,6 +272,12 @@ pub struct ContainerAdvancedSettings {
     pub network_ingress_cors_enable: bool,
     #[serde(alias = "network.ingress.enable_sticky_session")]
     pub network_ingress_sticky_session_enable: bool,
+    #[serde(alias = "network.ingress.sticky_session_cookie_name")]
+    pub network_ingress_sticky_session_cookie_name: String,
+    #[serde(alias = "network.ingress.sticky_session_cookie_max_age_seconds")]
+    pub network_ingress_sticky_session_cookie_max_age_seconds: u32,
+    #[serde(alias = "network.ingress.sticky_session_hash_policy")]
+    pub network_ingress_sticky_session_hash_policy: StickySessionHashPolicy,
     #[serde(alias = "network.ingress.cors_allow_origin")]
     pub network_ingress_cors_allow_origin: String,
     #[serde(alias = "network.ingress.cors_allow_methods")]
@@ -268,15 +310,52 @@ pub struct ContainerAdvancedSettings {
     pub network_ingress_denylist_source_range: String,
     #[serde(alias = "network.ingress.basic_auth_env_var")]
     pub network_ingress_basic_auth_env_var: String,
+    // Limits of the requests of each client IP, 0 meaning no limit
+    #[serde(alias = "network.ingress.limit_rps")]
+    pub network_ingress_limit_rps: u32,
+    #[serde(alias = "network.ingress.limit_burst")]
+    pub network_ingress_limit_burst: u32,
+    #[serde(alias = "network.ingress.limit_connections")]
+    pub network_ingress_limit_connections: u32,
+    // Web application firewall of the routes, the one of the cluster applies when not set
+    #[serde(alias = "network.ingress.waf_mode")]
+    pub network_ingress_waf_mode: Option<WafMode>,
+    #[serde(alias = "network.ingress.waf_custom_rules")]
+    pub network_ingress_waf_custom_rules: Vec<String>,
+    // Client certificates required by the routes, verified against the CA bundle of the router
+    #[serde(alias = "network.ingress.mtls_enabled")]
+    pub network_ingress_mtls_enabled: bool,
+    #[serde(alias = "network.ingress.mtls_verify_depth")]
+    pub network_ingress_mtls_verify_depth: u32,
+    #[serde(alias = "network.ingress.mtls_pass_certificate_to_upstream")]
+    pub network_ingress_mtls_pass_certificate_to_upstream: bool,
 
     #[serde(alias = "network.ingress.grpc_send_timeout_seconds")]
     pub network_ingress_grpc_send_timeout_seconds: u32,
     #[serde(alias = "network.ingress.grpc_read_timeout_seconds")]
     pub network_ingress_grpc_read_timeout_seconds: u32,
+    #[serde(alias = "network.ip_family_policy")]
+    pub network_ip_family_policy: IpFamilyPolicy,
+    #[serde(alias = "network.ingress.probe_enabled")]
+    pub network_ingress_probe_enabled: bool,
+    // Status code the routes of the service must answer with, any but a server error being accepted when not set
+    #[serde(alias = "network.ingress.probe_expected_status_code")]
+    pub network_ingress_probe_expected_status_code: Option<u16>,
 
     // Pod autoscaler
     #[serde(alias = "hpa.cpu.average_utilization_percent")]
     pub hpa_cpu_average_utilization_percent: u8,
+    // Scaling on memory and custom metrics comes on top of the cpu, the highest number of instances wins
+    #[serde(alias = "hpa.memory.average_utilization_percent")]
+    pub hpa_memory_average_utilization_percent: Option<u8>,
+    #[serde(alias = "hpa.custom_metrics")]
+    pub hpa_custom_metrics: Vec<HpaCustomMetric>,
+    // Event-driven autoscaling, the cluster must have KEDA enabled
+    #[serde(alias = "hpa.keda.triggers")]
+    pub hpa_keda_triggers: Vec<KedaTrigger>,
+    // Environment variables synced from a secret store, the cluster must have External Secrets Operator enabled
+    #[serde(alias = "deployment.external_secrets")]
+    pub deployment_external_secrets: Vec<ExternalSecretVariable>,
 }
 
 impl Default for ContainerAdvancedSettings {
@@ -292,9 +371,25 @@ impl Default for ContainerAdvancedSettings {
             deployment_update_strategy_rolling_update_max_surge_percent: 25,
             deployment_affinity_node_required: BTreeMap::new(),
             deployment_antiaffinity_pod: PodAntiAffinity::Preferred,
+            deployment_antiaffinity_pod_topology: PodAntiAffinityTopology::Hostname,
+            deployment_config_reload_strategy: ConfigReloadStrategy::RollingRestart,
+            deployment_config_reload_mount_path: "/qovery-config".to_string(),
+            deployment_config_reload_webhook_url: "".to_string(),
+            deployment_topology_spread_key: TopologySpreadKey::Auto,
+            deployment_topology_spread_max_skew: 1,
+            deployment_topology_spread_when_unsatisfiable: TopologySpreadWhenUnsatisfiable::ScheduleAnyway,
+            deployment_tolerations: vec![],
+            deployment_env_vars_fast_path_enabled: true,
+            statefulset_update_strategy_type: StatefulSetUpdateStrategy::RollingUpdate,
+            statefulset_update_strategy_rolling_update_partition: 0,
+            pdb_min_available_percent: 0,
+            pdb_max_unavailable_percent: 10,
             network_ingress_proxy_body_size_mb: 100,
             network_ingress_cors_enable: false,
             network_ingress_sticky_session_enable: false,
+            network_ingress_sticky_session_cookie_name: "INGRESSCOOKIE_QOVERY".to_string(),
+            network_ingress_sticky_session_cookie_max_age_seconds: 85400,
+            network_ingress_sticky_session_hash_policy: StickySessionHashPolicy::Persistent,
             network_ingress_cors_allow_origin: "*".to_string(),
             network_ingress_cors_allow_methods: "GET, PUT, POST, DELETE, PATCH, OPTIONS".to_string(),
             network_ingress_cors_allow_headers: "DNT,Keep-Alive,User-Agent,X-Requested-With,If-Modified-Since,Cache-Control,Content-Type,Range,Authorization".to_string(),
@@ -311,9 +406,24 @@ impl Default for ContainerAdvancedSettings {
             network_ingress_whitelist_source_range: "0.0.0.0/0".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_limit_rps: 0,
+            network_ingress_limit_burst: 0,
+            network_ingress_limit_connections: 0,
+            network_ingress_waf_mode: None,
+            network_ingress_waf_custom_rules: vec![],
+            network_ingress_mtls_enabled: false,
+            network_ingress_mtls_verify_depth: 1,
+            network_ingress_mtls_pass_certificate_to_upstream: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
+            network_ip_family_policy: IpFamilyPolicy::SingleStack,
+            network_ingress_probe_enabled: true,
+            network_ingress_probe_expected_status_code: None,
             hpa_cpu_average_utilization_percent: 60,
+            hpa_memory_average_utilization_percent: None,
+            hpa_custom_metrics: vec![],
+            hpa_keda_triggers: vec![],
+            deployment_external_secrets: vec![],
         }
     }
 }
@@ -348,6 +458,11 @@ pub struct Container {
     pub liveness_probe: Option<Probe>,
     #[serde(default)]
     pub advanced_settings: ContainerAdvancedSettings,
+    #[serde(default)]
+    pub lifecycle_hooks: LifecycleHooks,
+    /// Shared volumes of the environment mounted in the instances
+    #[serde(default)]
+    pub shared_volume_mounts: Vec<SharedVolumeMount>,
 }
 
 impl Container {
@@ -372,7 +487,8 @@ impl Container {
             image: self.image,
             tag: self.tag,
             registry_mirroring_mode: cluster.advanced_settings().registry_mirroring_mode.clone(),
-        };
+        }
+        .with_cluster_docker_hub_settings(cluster.advanced_settings());
         let service: Box<dyn ContainerService> = match cloud_provider.kind() {
             CPKind::Aws => {
                 if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
@@ -402,6 +518,8 @@ impl Container {
                         self.readiness_probe.map(|p| p.to_domain()),
                         self.liveness_probe.map(|p| p.to_domain()),
                         self.advanced_settings,
+                        self.lifecycle_hooks,
+                        self.shared_volume_mounts,
                         AwsAppExtraSettings {},
                         |transmitter| context.get_event_details(transmitter),
                     )?)
@@ -432,6 +550,8 @@ impl Container {
                         self.readiness_probe.map(|p| p.to_domain()),
                         self.liveness_probe.map(|p| p.to_domain()),
                         self.advanced_settings,
+                        self.lifecycle_hooks,
+                        self.shared_volume_mounts,
                         AwsEc2AppExtraSettings {},
                         |transmitter| context.get_event_details(transmitter),
                     )?)
@@ -463,6 +583,8 @@ impl Container {
                 self.readiness_probe.map(|p| p.to_domain()),
                 self.liveness_probe.map(|p| p.to_domain()),
                 self.advanced_settings,
+                self.lifecycle_hooks,
+                self.shared_volume_mounts,
                 ScwAppExtraSettings {},
                 |transmitter| context.get_event_details(transmitter),
             )?),
@@ -492,6 +614,8 @@ impl Container {
                 self.readiness_probe.map(|p| p.to_domain()),
                 self.liveness_probe.map(|p| p.to_domain()),
                 self.advanced_settings,
+                self.lifecycle_hooks,
+                self.shared_volume_mounts,
                 GcpAppExtraSettings {},
                 |transmitter| context.get_event_details(transmitter),
             )?),
@@ -521,6 +645,8 @@ impl Container {
                 self.readiness_probe.map(|p| p.to_domain()),
                 self.liveness_probe.map(|p| p.to_domain()),
                 self.advanced_settings,
+                self.lifecycle_hooks,
+                self.shared_volume_mounts,
                 SelfManagedAppExtraSettings {},
                 |transmitter| context.get_event_details(transmitter),
             )?),
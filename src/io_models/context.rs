@@ -5,6 +5,7 @@ use crate::utilities::to_short_id;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -23,6 +24,7 @@ pub struct Context {
     pub docker: Arc<Docker>,
     pub qovery_api: Arc<dyn QoveryApi>,
     event_details: EventDetails,
+    max_parallel_builds: Option<NonZeroUsize>,
 }
 
 impl Context {
@@ -53,9 +55,20 @@ impl Context {
             docker,
             qovery_api,
             event_details,
+            max_parallel_builds: None,
         }
     }
 
+    /// Caps the builds running at the same time on this engine, whatever the environments ask for
+    pub fn with_max_parallel_builds(mut self, max_parallel_builds: Option<NonZeroUsize>) -> Self {
+        self.max_parallel_builds = max_parallel_builds;
+        self
+    }
+
+    pub fn max_parallel_builds(&self) -> Option<NonZeroUsize> {
+        self.max_parallel_builds
+    }
+
     pub fn organization_short_id(&self) -> &str {
         &self.organization_short_id
     }
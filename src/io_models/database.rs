@@ -3,17 +3,20 @@ use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
 use crate::cloud_provider::scaleway::database_instance_type::ScwDatabaseInstanceType;
 use crate::cloud_provider::{service, CloudProvider, Kind as CPKind, Kind};
 use crate::io_models::context::Context;
-use crate::io_models::Action;
+use crate::io_models::{Action, CustomMetadata, StatefulSetUpdateStrategy};
 use crate::models;
 use crate::models::database::{
     Container, DatabaseError, DatabaseInstanceType, DatabaseService, Managed, MongoDB, MySQL, PostgresSQL, Redis,
 };
+use crate::models::database_utils::to_managed_database_parameters;
 use crate::models::types::{AWSEc2, VersionsNumber, AWS, SCW};
 use crate::models::types::{CloudProvider as CloudProviderTrait, GCP};
+use crate::models::utils::validate_custom_metadata;
 use chrono::{DateTime, Utc};
 use core::result::Result;
 use core::result::Result::{Err, Ok};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -49,6 +52,120 @@ pub struct Database {
     pub activate_backups: bool,
     pub publicly_accessible: bool,
     pub mode: DatabaseMode,
+    #[serde(default)]
+    pub advanced_settings: DatabaseAdvancedSettings,
+    // Customer managed key for managed databases disk encryption, AWS default key is used when not set
+    #[serde(default)]
+    pub kms_key_arn: Option<String>,
+    #[serde(default)]
+    pub init_scripts: Vec<DatabaseInitScript>,
+    // Engine parameters of managed databases, i.e: `max_connections`, rendered in their parameter group
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
+    #[serde(default)]
+    pub custom_metadata: CustomMetadata,
+}
+
+/// Script run against the database once, right after its first creation.
+/// Scripts are executed in the order of the payload and the run stops at the first failure.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct DatabaseInitScript {
+    /// File name of the script, i.e: `001-schema.sql`
+    pub name: String,
+    pub source: DatabaseInitScriptSource,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseInitScriptSource {
+    Inline {
+        content: String,
+    },
+    ObjectStorage {
+        bucket_name: String,
+        key: String,
+        region: String,
+        /// Endpoint of S3 compatible object storages (Scaleway, GCS interoperability), AWS S3 if not set
+        endpoint: Option<String>,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionPoolerPoolMode {
+    Session,
+    #[default]
+    Transaction,
+    Statement,
+}
+
+impl ConnectionPoolerPoolMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ConnectionPoolerPoolMode::Session => "session",
+            ConnectionPoolerPoolMode::Transaction => "transaction",
+            ConnectionPoolerPoolMode::Statement => "statement",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(default)]
+pub struct DatabaseAdvancedSettings {
+    // Connection pooler (pgbouncer), only available for container PostgreSQL
+    #[serde(alias = "database.connection_pooler.enabled")]
+    pub connection_pooler_enabled: bool,
+    #[serde(alias = "database.connection_pooler.pool_mode")]
+    pub connection_pooler_pool_mode: ConnectionPoolerPoolMode,
+    #[serde(alias = "database.connection_pooler.default_pool_size")]
+    pub connection_pooler_default_pool_size: u32,
+    #[serde(alias = "database.connection_pooler.max_client_connections")]
+    pub connection_pooler_max_client_connections: u32,
+
+    // Storage auto resize, only available for container databases
+    #[serde(alias = "database.storage.auto_resize.enabled")]
+    pub storage_auto_resize_enabled: bool,
+    #[serde(alias = "database.storage.auto_resize.usage_threshold_percent")]
+    pub storage_auto_resize_usage_threshold_percent: u32,
+    #[serde(alias = "database.storage.auto_resize.increment_percent")]
+    pub storage_auto_resize_increment_percent: u32,
+    #[serde(alias = "database.storage.auto_resize.max_size_in_gib")]
+    pub storage_auto_resize_max_size_in_gib: u32,
+
+    // Prometheus exporter sidecar, only available for container databases
+    #[serde(alias = "database.metrics_exporter.enabled")]
+    pub metrics_exporter_enabled: bool,
+
+    // Statefulsets update strategy, only available for container databases
+    #[serde(alias = "database.update_strategy.type")]
+    pub update_strategy_type: StatefulSetUpdateStrategy,
+    #[serde(alias = "database.update_strategy.rolling_update.partition")]
+    pub update_strategy_rolling_update_partition: u32,
+
+    // Connection check from inside the cluster once the database is ready
+    #[serde(alias = "database.smoke_test.enabled")]
+    pub smoke_test_enabled: bool,
+}
+
+impl Default for DatabaseAdvancedSettings {
+    fn default() -> Self {
+        DatabaseAdvancedSettings {
+            connection_pooler_enabled: false,
+            connection_pooler_pool_mode: ConnectionPoolerPoolMode::Transaction,
+            connection_pooler_default_pool_size: 20,
+            connection_pooler_max_client_connections: 100,
+            storage_auto_resize_enabled: false,
+            storage_auto_resize_usage_threshold_percent: 80,
+            storage_auto_resize_increment_percent: 20,
+            storage_auto_resize_max_size_in_gib: 1000,
+            metrics_exporter_enabled: false,
+            update_strategy_type: StatefulSetUpdateStrategy::RollingUpdate,
+            update_strategy_rolling_update_partition: 0,
+            smoke_test_enabled: true,
+        }
+    }
 }
 
 impl Database {
@@ -57,6 +174,74 @@ impl Database {
         context: &Context,
         cloud_provider: &dyn CloudProvider,
     ) -> Result<Box<dyn DatabaseService>, DatabaseError> {
+        if self.advanced_settings.connection_pooler_enabled
+            && (self.kind != DatabaseKind::Postgresql || self.mode != DatabaseMode::CONTAINER)
+        {
+            return Err(DatabaseError::InvalidConfig(
+                "Connection pooler is only available for container PostgreSQL databases".to_string(),
+            ));
+        }
+
+        if self.advanced_settings.storage_auto_resize_enabled {
+            if self.mode != DatabaseMode::CONTAINER {
+                return Err(DatabaseError::InvalidConfig(
+                    "Storage auto resize is only available for container databases".to_string(),
+                ));
+            }
+            if !(1..100).contains(&self.advanced_settings.storage_auto_resize_usage_threshold_percent) {
+                return Err(DatabaseError::InvalidConfig(
+                    "Storage auto resize usage threshold must be between 1 and 99 percent".to_string(),
+                ));
+            }
+        }
+
+        if self.advanced_settings.metrics_exporter_enabled && self.mode != DatabaseMode::CONTAINER {
+            return Err(DatabaseError::InvalidConfig(
+                "Metrics exporter is only available for container databases".to_string(),
+            ));
+        }
+
+        if (self.advanced_settings.update_strategy_type != StatefulSetUpdateStrategy::RollingUpdate
+            || self.advanced_settings.update_strategy_rolling_update_partition > 0)
+            && self.mode != DatabaseMode::CONTAINER
+        {
+            return Err(DatabaseError::InvalidConfig(
+                "Update strategy is only available for container databases".to_string(),
+            ));
+        }
+
+        if let Some(kms_key_arn) = &self.kms_key_arn {
+            if self.mode != DatabaseMode::MANAGED || cloud_provider.kind() != Kind::Aws {
+                return Err(DatabaseError::InvalidConfig(
+                    "KMS key is only available for AWS managed databases".to_string(),
+                ));
+            }
+            if !self.encrypt_disk && self.kind != DatabaseKind::Redis {
+                return Err(DatabaseError::InvalidConfig(
+                    "KMS key requires disk encryption to be enabled".to_string(),
+                ));
+            }
+            validate_kms_key_arn(kms_key_arn, &cloud_provider.region()).map_err(DatabaseError::InvalidConfig)?;
+        }
+
+        validate_init_scripts(&self.kind, &self.init_scripts).map_err(DatabaseError::InvalidConfig)?;
+        validate_custom_metadata(&self.custom_metadata).map_err(DatabaseError::InvalidConfig)?;
+
+        if !self.parameters.is_empty() {
+            let database_type = match self.kind {
+                DatabaseKind::Postgresql => service::DatabaseType::PostgreSQL,
+                DatabaseKind::Mysql => service::DatabaseType::MySQL,
+                DatabaseKind::Mongodb => service::DatabaseType::MongoDB,
+                DatabaseKind::Redis => service::DatabaseType::Redis,
+            };
+            if self.mode != DatabaseMode::MANAGED || cloud_provider.kind() != Kind::Aws {
+                return Err(DatabaseError::InvalidConfig(
+                    "Database parameters are only available for AWS managed databases".to_string(),
+                ));
+            }
+            to_managed_database_parameters(database_type, &self.parameters)?;
+        }
+
         let database_options = DatabaseOptions {
             mode: self.mode.clone(),
             login: self.username.clone(),
@@ -69,6 +254,11 @@ impl Database {
             activate_high_availability: self.activate_high_availability,
             activate_backups: self.activate_backups,
             publicly_accessible: self.publicly_accessible,
+            advanced_settings: self.advanced_settings.clone(),
+            kms_key_arn: self.kms_key_arn.clone(),
+            init_scripts: self.init_scripts.clone(),
+            parameters: self.parameters.clone(),
+            custom_metadata: self.custom_metadata.clone(),
         };
 
         let version = VersionsNumber::from_str(self.version.as_str())
@@ -778,4 +968,161 @@ pub struct DatabaseOptions {
     pub activate_high_availability: bool,
     pub activate_backups: bool,
     pub publicly_accessible: bool,
+    pub advanced_settings: DatabaseAdvancedSettings,
+    pub kms_key_arn: Option<String>,
+    pub init_scripts: Vec<DatabaseInitScript>,
+    pub parameters: BTreeMap<String, String>,
+    pub custom_metadata: CustomMetadata,
+}
+
+// Expected format: arn:{partition}:kms:{region}:{account_id}:key/{key_id}
+// Key has to live in the database region, and aliases are not accepted by RDS
+fn validate_kms_key_arn(kms_key_arn: &str, region: &str) -> Result<(), String> {
+    let invalid_arn =
+        || format!("Invalid KMS key ARN `{kms_key_arn}`, expected arn:aws:kms:{region}:<account_id>:key/<key_id>");
+    let parts: Vec<&str> = kms_key_arn.splitn(6, ':').collect();
+    let [prefix, partition, service, key_region, account_id, resource] = parts[..] else {
+        return Err(invalid_arn());
+    };
+
+    if prefix != "arn" || !partition.starts_with("aws") || service != "kms" {
+        return Err(invalid_arn());
+    }
+    if account_id.len() != 12 || !account_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid_arn());
+    }
+    match resource.strip_prefix("key/") {
+        Some(key_id) if !key_id.is_empty() => {}
+        _ => return Err(invalid_arn()),
+    }
+    if key_region != region {
+        return Err(format!(
+            "KMS key `{kms_key_arn}` is in region `{key_region}` while the database is in region `{region}`"
+        ));
+    }
+
+    Ok(())
+}
+
+// Script names end up as file names inside the init job, so keep them simple and unique
+fn validate_init_scripts(kind: &DatabaseKind, init_scripts: &[DatabaseInitScript]) -> Result<(), String> {
+    if init_scripts.is_empty() {
+        return Ok(());
+    }
+
+    let extension = match kind {
+        DatabaseKind::Postgresql | DatabaseKind::Mysql => ".sql",
+        DatabaseKind::Mongodb => ".js",
+        DatabaseKind::Redis => return Err("Init scripts are not available for Redis databases".to_string()),
+    };
+
+    let mut names: Vec<&str> = Vec::with_capacity(init_scripts.len());
+    for script in init_scripts {
+        let name = script.name.as_str();
+        let is_valid_name = name.len() <= 200
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+        if !is_valid_name {
+            return Err(format!(
+                "Invalid init script name `{name}`, only alphanumeric characters, `-`, `_` and `.` are allowed"
+            ));
+        }
+        if !name.ends_with(extension) {
+            return Err(format!("Init script `{name}` must have the `{extension}` extension"));
+        }
+        if names.contains(&name) {
+            return Err(format!("Init script `{name}` is defined more than once"));
+        }
+        names.push(name);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io_models::database::{
+        validate_init_scripts, validate_kms_key_arn, ConnectionPoolerPoolMode, DatabaseAdvancedSettings,
+        DatabaseInitScript, DatabaseInitScriptSource, DatabaseKind,
+    };
+    use crate::io_models::StatefulSetUpdateStrategy;
+
+    #[test]
+    fn test_database_advanced_settings_deserialization() {
+        let settings: DatabaseAdvancedSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, DatabaseAdvancedSettings::default());
+        assert!(!settings.connection_pooler_enabled);
+
+        let settings: DatabaseAdvancedSettings = serde_json::from_str(
+            r#"{
+                "database.connection_pooler.enabled": true,
+                "database.connection_pooler.pool_mode": "session",
+                "database.connection_pooler.default_pool_size": 50
+            }"#,
+        )
+        .unwrap();
+        assert!(settings.connection_pooler_enabled);
+        assert_eq!(settings.connection_pooler_pool_mode, ConnectionPoolerPoolMode::Session);
+        assert_eq!(settings.connection_pooler_default_pool_size, 50);
+        assert_eq!(settings.connection_pooler_max_client_connections, 100);
+        assert!(!settings.storage_auto_resize_enabled);
+
+        let settings: DatabaseAdvancedSettings = serde_json::from_str(
+            r#"{
+                "database.storage.auto_resize.enabled": true,
+                "database.storage.auto_resize.usage_threshold_percent": 90
+            }"#,
+        )
+        .unwrap();
+        assert!(settings.storage_auto_resize_enabled);
+        assert_eq!(settings.storage_auto_resize_usage_threshold_percent, 90);
+        assert_eq!(settings.storage_auto_resize_increment_percent, 20);
+
+        let settings: DatabaseAdvancedSettings = serde_json::from_str(
+            r#"{
+                "database.update_strategy.type": "OnDelete"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(settings.update_strategy_type, StatefulSetUpdateStrategy::OnDelete);
+        assert_eq!(settings.update_strategy_rolling_update_partition, 0);
+    }
+
+    #[test]
+    fn test_validate_kms_key_arn() {
+        let key_arn = "arn:aws:kms:eu-west-3:123456789012:key/1234abcd-12ab-34cd-56ef-1234567890ab";
+        assert!(validate_kms_key_arn(key_arn, "eu-west-3").is_ok());
+        // key must be in the same region than the database
+        assert!(validate_kms_key_arn(key_arn, "us-east-2").is_err());
+
+        assert!(validate_kms_key_arn("arn:aws:kms:eu-west-3:123456789012:alias/my-key", "eu-west-3").is_err());
+        assert!(validate_kms_key_arn("arn:aws:s3:eu-west-3:123456789012:key/1234abcd", "eu-west-3").is_err());
+        assert!(validate_kms_key_arn("arn:aws:kms:eu-west-3:1234:key/1234abcd", "eu-west-3").is_err());
+        assert!(validate_kms_key_arn("1234abcd-12ab-34cd-56ef-1234567890ab", "eu-west-3").is_err());
+    }
+
+    #[test]
+    fn test_validate_init_scripts() {
+        let script = |name: &str| DatabaseInitScript {
+            name: name.to_string(),
+            source: DatabaseInitScriptSource::Inline {
+                content: "SELECT 1;".to_string(),
+            },
+        };
+
+        assert!(validate_init_scripts(&DatabaseKind::Redis, &[]).is_ok());
+        assert!(
+            validate_init_scripts(&DatabaseKind::Postgresql, &[script("001-schema.sql"), script("002-seed.sql")])
+                .is_ok()
+        );
+        assert!(validate_init_scripts(&DatabaseKind::Mongodb, &[script("seed.js")]).is_ok());
+
+        assert!(validate_init_scripts(&DatabaseKind::Redis, &[script("seed.sql")]).is_err());
+        assert!(validate_init_scripts(&DatabaseKind::Mysql, &[script("seed.js")]).is_err());
+        assert!(validate_init_scripts(&DatabaseKind::Mysql, &[script("../seed.sql")]).is_err());
+        assert!(validate_init_scripts(&DatabaseKind::Mysql, &[script("my seed.sql")]).is_err());
+        assert!(validate_init_scripts(&DatabaseKind::Mysql, &[script("seed.sql"), script("seed.sql")]).is_err());
+    }
 }
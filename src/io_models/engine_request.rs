@@ -17,11 +17,20 @@ use crate::cloud_provider::scaleway::Scaleway;
 use crate::cloud_provider::self_managed::SelfManaged;
 use crate::container_registry::ecr::ECR;
 use crate::container_registry::generic_cr::GenericCr;
+use crate::container_registry::ghcr::GithubContainerRegistry;
 use crate::container_registry::google_artifact_registry::GoogleArtifactRegistry;
+use crate::container_registry::harbor::Harbor;
 use crate::container_registry::scaleway_container_registry::ScalewayCR;
+use crate::deployment_action::traffic_failover::{
+    ClusterEndpoint, TrafficFailover, DEFAULT_FAILURE_THRESHOLD, DEFAULT_RECOVERY_THRESHOLD,
+};
+use crate::deployment_freeze::DeploymentFreezeOverride;
 use crate::dns_provider::cloudflare::Cloudflare;
+use crate::dns_provider::gcloud_dns::GcloudDns;
 use crate::dns_provider::io::Kind;
+use crate::dns_provider::ovh::Ovh;
 use crate::dns_provider::qoverydns::QoveryDns;
+use crate::dns_provider::route53::Route53;
 use crate::engine::InfrastructureContext;
 use crate::errors::{CommandError, EngineError as IoEngineError, EngineError};
 use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
@@ -69,6 +78,9 @@ pub struct EngineRequest<T> {
     pub target_environment: T,
     pub metadata: Option<Metadata>,
     pub archive: Option<Archive>,
+    /// Lets the request go through a freeze of its cluster or environment, the override being recorded in its events
+    #[serde(default)]
+    pub deployment_freeze_override: Option<DeploymentFreezeOverride>,
 }
 
 impl<T> EngineRequest<T> {
@@ -174,6 +186,7 @@ impl InfrastructureEngineRequest {
             Action::Pause => Stage::Infrastructure(InfrastructureStep::Pause),
             Action::Delete => Stage::Infrastructure(InfrastructureStep::Delete),
             Action::Restart => Stage::Infrastructure(InfrastructureStep::Restart),
+            Action::TriggerNow => Stage::Infrastructure(InfrastructureStep::Create),
         };
 
         EventDetails::new(
@@ -187,6 +200,73 @@ impl InfrastructureEngineRequest {
     }
 }
 
+/// Keeps the traffic of a DNS record on the healthy clusters among the ones serving it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrafficFailoverEngineRequest {
+    pub id: String,
+    pub organization_long_id: Uuid,
+    /// Cluster running the failover, its events are reported on it
+    pub cluster_long_id: Uuid,
+    #[serde(default)]
+    pub cluster_jwt_token: String,
+    pub created_at: DateTime<Utc>,
+    pub features: Vec<Features>,
+    pub metadata: Option<Metadata>,
+    pub dns_provider: DnsProvider,
+    /// Name of the weighted records, i.e: `app.example.com`
+    pub record_name: String,
+    pub endpoints: Vec<TrafficFailoverEndpoint>,
+    #[serde(default = "default_traffic_failover_probe_interval_in_seconds")]
+    pub probe_interval_in_seconds: u64,
+    pub failure_threshold: Option<u32>,
+    pub recovery_threshold: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrafficFailoverEndpoint {
+    pub cluster_long_id: Uuid,
+    pub probe_address: String,
+    pub record_value: String,
+    pub weight: u32,
+}
+
+pub const DEFAULT_TRAFFIC_FAILOVER_PROBE_INTERVAL_IN_SECONDS: u64 = 30;
+
+fn default_traffic_failover_probe_interval_in_seconds() -> u64 {
+    DEFAULT_TRAFFIC_FAILOVER_PROBE_INTERVAL_IN_SECONDS
+}
+
+impl TrafficFailoverEngineRequest {
+    pub fn event_details(&self) -> EventDetails {
+        EventDetails::new(
+            None,
+            QoveryIdentifier::new(self.organization_long_id),
+            QoveryIdentifier::new(self.cluster_long_id),
+            self.id.to_string(),
+            Stage::Infrastructure(InfrastructureStep::TrafficFailover),
+            Transmitter::DnsProvider(self.dns_provider.long_id, self.dns_provider.name.to_string()),
+        )
+    }
+
+    pub fn traffic_failover(&self) -> TrafficFailover {
+        TrafficFailover::new(
+            self.endpoints
+                .iter()
+                .map(|endpoint| ClusterEndpoint {
+                    cluster_long_id: endpoint.cluster_long_id,
+                    probe_address: endpoint.probe_address.to_string(),
+                    record_value: endpoint.record_value.to_string(),
+                    weight: endpoint.weight,
+                })
+                .collect(),
+        )
+        .with_thresholds(
+            self.failure_threshold.unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+            self.recovery_threshold.unwrap_or(DEFAULT_RECOVERY_THRESHOLD),
+        )
+    }
+}
+
 impl EnvironmentEngineRequest {
     pub fn event_details(&self) -> EventDetails {
         let kubernetes = &self.kubernetes;
@@ -612,6 +692,34 @@ impl ContainerRegistry {
                     options.login.and_then(|l| options.password.map(|p| (l, p))),
                 )?))
             }
+            container_registry::Kind::Ghcr => {
+                let options: GhcrOptions = serde_json::from_value(self.options.clone())
+                    .with_context(|| "cannot deserialize container registry option")?;
+                Ok(Box::new(GithubContainerRegistry::new(
+                    context,
+                    self.id.as_str(),
+                    self.long_id,
+                    self.name.as_str(),
+                    &options.owner,
+                    &options.login,
+                    &options.token,
+                )?))
+            }
+            container_registry::Kind::Harbor => {
+                let options: HarborOptions = serde_json::from_value(self.options.clone())
+                    .with_context(|| "cannot deserialize container registry option")?;
+                Ok(Box::new(Harbor::new(
+                    context,
+                    self.id.as_str(),
+                    self.long_id,
+                    self.name.as_str(),
+                    options.url,
+                    &options.project_name,
+                    &options.login,
+                    &options.password,
+                    options.skip_tls_verify,
+                )?))
+            }
         }
     }
 }
@@ -667,6 +775,59 @@ impl DnsProvider {
 
                 None
             }
+            Kind::Route53 => {
+                let access_key_id = self.options.get("aws_access_key_id")?;
+                let secret_access_key = self.options.get("aws_secret_access_key")?;
+
+                Some(Box::new(Route53::new(
+                    context,
+                    self.long_id,
+                    self.name.as_str(),
+                    Domain::new(self.domain.clone()),
+                    access_key_id.as_str(),
+                    secret_access_key.as_str(),
+                    self.options.get("route53_hosted_zone_id").map(String::as_str),
+                    self.options
+                        .get("route53_private_zone")
+                        .map(|s| s.parse::<bool>().unwrap_or(false))
+                        .unwrap_or(false),
+                )))
+            }
+            Kind::GcloudDns => {
+                let credentials = self.options.get("gcloud_dns_credentials")?;
+                let credentials = JsonCredentialsIo::try_new_from_json_str(credentials).ok()?;
+                let credentials = JsonCredentials::try_from(credentials).ok()?;
+
+                Some(Box::new(GcloudDns::new(
+                    context,
+                    self.long_id,
+                    self.name.as_str(),
+                    Domain::new(self.domain.clone()),
+                    credentials,
+                    self.options.get("gcloud_dns_project_id").map(String::as_str),
+                    self.options.get("gcloud_dns_managed_zone").map(String::as_str),
+                    self.options
+                        .get("gcloud_dns_private_zone")
+                        .map(|s| s.parse::<bool>().unwrap_or(false))
+                        .unwrap_or(false),
+                )))
+            }
+            Kind::Ovh => {
+                let application_key = self.options.get("ovh_application_key")?;
+                let application_secret = self.options.get("ovh_application_secret")?;
+                let consumer_key = self.options.get("ovh_consumer_key")?;
+
+                Some(Box::new(Ovh::new(
+                    context,
+                    self.long_id,
+                    self.name.as_str(),
+                    Domain::new(self.domain.clone()),
+                    self.options.get("ovh_endpoint").map(String::as_str),
+                    application_key.as_str(),
+                    application_secret.as_str(),
+                    consumer_key.as_str(),
+                )))
+            }
         }
     }
 }
@@ -724,6 +885,28 @@ pub struct GenericCrOptions {
     repository_name: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Derivative)]
+pub struct GhcrOptions {
+    // user or organization owning the packages
+    owner: String,
+    login: String,
+    #[derivative(Debug = "ignore")]
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Derivative)]
+pub struct HarborOptions {
+    pub url: Url,
+    // created when missing, which requires a system robot account
+    project_name: String,
+    // robot account, i.e: `robot$qovery`
+    login: String,
+    #[derivative(Debug = "ignore")]
+    pub password: String,
+    #[serde(default)]
+    pub skip_tls_verify: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Derivative)]
 pub struct GcpCrOptions {
     #[derivative(Debug = "ignore")]
@@ -1,23 +1,32 @@
 use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::CloudProvider;
 use crate::container_registry::ContainerRegistry;
+use crate::dependency_graph::dependency_levels;
 use crate::io_models::application::Application;
 use crate::io_models::container::Container;
 use crate::io_models::context::Context;
 use crate::io_models::database::Database;
 use crate::io_models::helm_chart::HelmChart;
 use crate::io_models::job::Job;
+use crate::io_models::kustomize::Kustomize;
 use crate::io_models::router::Router;
+use crate::io_models::shared_volume::SharedVolume;
+use crate::io_models::terraform_service::TerraformService;
 use crate::io_models::Action;
 use crate::models::application::{ApplicationError, ApplicationService};
 use crate::models::container::{ContainerError, ContainerService};
 use crate::models::database::{DatabaseError, DatabaseService};
 use crate::models::helm_chart::{HelmChartError, HelmChartService};
 use crate::models::job::{JobError, JobService};
-use crate::models::router::RouterError;
+use crate::models::kustomize::{KustomizeError, KustomizeService};
+use crate::models::router::{ClientTls, NginxAnnotations, RouteLimits, RouterError, RouterWaf, StickySession};
+use crate::models::terraform_service::{TerraformServiceError, TerraformServiceTrait};
+use crate::naming;
 use crate::utilities::base64_replace_comma_to_new_line;
 use crate::{cloud_provider::environment::Environment, models::router::RouterAdvancedSettings};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use url::Url;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -40,6 +49,81 @@ pub struct EnvironmentRequest {
     pub databases: Vec<Database>,
     #[serde(default)]
     pub helms: Vec<HelmChart>,
+    #[serde(default)]
+    pub kustomizations: Vec<Kustomize>,
+    #[serde(default)]
+    pub terraform_services: Vec<TerraformService>,
+    #[serde(default)]
+    pub shared_volumes: Vec<SharedVolume>,
+    #[serde(default)]
+    pub service_account: EnvironmentServiceAccount,
+    #[serde(default)]
+    pub remote_builder: Option<RemoteBuilder>,
+    #[serde(default)]
+    pub deployment_waves: Option<DeploymentWaves>,
+    /// Services each service depends on, by id. A service is only deployed once its dependencies are, services without
+    /// dependencies between them being deployed in parallel.
+    #[serde(default)]
+    pub service_dependencies: BTreeMap<Uuid, Vec<Uuid>>,
+}
+
+/// Service account created in the environment namespace and used by its workloads instead of the `default` one,
+/// unless they set their own. Its role only grants read access to the pods, services and endpoints of the namespace.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(default)]
+pub struct EnvironmentServiceAccount {
+    pub enabled: bool,
+    pub automount_token: bool,
+    /// i.e: `eks.amazonaws.com/role-arn` for IRSA or `iam.gke.io/gcp-service-account` for workload identity
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl Default for EnvironmentServiceAccount {
+    fn default() -> Self {
+        EnvironmentServiceAccount {
+            enabled: true,
+            automount_token: false,
+            annotations: BTreeMap::new(),
+        }
+    }
+}
+
+/// BuildKit daemon the images of the environment are built with, instead of the builders provisioned by the engine
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RemoteBuilder {
+    /// i.e: `tcp://buildkitd.example.com:1234`, or `kube-pod://buildkitd-0?namespace=buildkit` for an in-cluster buildkitd
+    pub endpoint: Url,
+    #[serde(default)]
+    pub tls: Option<RemoteBuilderTls>,
+}
+
+/// Mutual TLS with the BuildKit daemon, certificates and key being PEM encoded
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RemoteBuilderTls {
+    pub ca_cert: String,
+    pub client_cert: String,
+    pub client_key: String,
+    /// Name the server certificate is verified against, the host of the endpoint when not set
+    #[serde(default)]
+    pub server_name: Option<String>,
+}
+
+/// Services of the environment deployed in successive waves instead of all at once, so clusters with little headroom
+/// are not flooded with pod creations and image pulls
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct DeploymentWaves {
+    /// Services deployed by each wave, their routers being deployed with them
+    pub batch_size: u32,
+    /// How long a wave waits for the pending pods of the namespace to be scheduled before starting
+    #[serde(default = "default_wave_stabilization_timeout_sec")]
+    pub stabilization_timeout_sec: u32,
+    /// Pause between two waves, once the pods are scheduled
+    #[serde(default)]
+    pub interval_sec: u32,
+}
+
+fn default_wave_stabilization_timeout_sec() -> u32 {
+    300
 }
 
 fn default_max_parallel_build() -> u32 {
@@ -64,6 +148,16 @@ pub enum DomainError {
     JobError(#[from] JobError),
     #[error("Invalid helm chart: {0}")]
     HelmChartError(#[from] HelmChartError),
+    #[error("Invalid kustomize service: {0}")]
+    KustomizeError(#[from] KustomizeError),
+    #[error("Invalid terraform service: {0}")]
+    TerraformServiceError(#[from] TerraformServiceError),
+    #[error("Kubernetes name `{name}` is used by several services: {}", .owners.join(", "))]
+    KubeNameCollision { name: String, owners: Vec<String> },
+    #[error("Shared volume {shared_volume_id} mounted by {service} does not exist in the environment")]
+    UnknownSharedVolume { service: String, shared_volume_id: Uuid },
+    #[error("Services depend on each other: {}", .services.iter().map(Uuid::to_string).collect::<Vec<_>>().join(", "))]
+    ServiceDependencyCycle { services: Vec<Uuid> },
 }
 
 impl EnvironmentRequest {
@@ -79,11 +173,13 @@ impl EnvironmentRequest {
             .iter()
             .cloned()
             .map(|srv| {
-                let build = srv.to_build(
-                    container_registry.registry_info(),
-                    context.qovery_api.clone(),
-                    cluster.cpu_architectures(),
-                );
+                let build = srv
+                    .to_build(
+                        container_registry.registry_info(),
+                        context.qovery_api.clone(),
+                        cluster.cpu_architectures(),
+                    )
+                    .with_cluster_docker_hub_registry(cluster.advanced_settings().docker_hub_registry());
                 srv.to_application_domain(context, build, cloud_provider)
             })
             .collect();
@@ -104,10 +200,57 @@ impl EnvironmentRequest {
             for app in &self.applications {
                 for route in &router.routes {
                     if route.service_long_id == app.long_id {
+                        // rate and connection limits
+                        if let Some(limits) = RouteLimits::new(
+                            app.advanced_settings.network_ingress_limit_rps,
+                            app.advanced_settings.network_ingress_limit_burst,
+                            app.advanced_settings.network_ingress_limit_connections,
+                        )
+                        .map_err(DomainError::RouterError)?
+                        {
+                            router_advanced_settings.limits = Some(limits);
+                        }
+                        // client certificates
+                        if app.advanced_settings.network_ingress_mtls_enabled {
+                            router_advanced_settings.client_tls = Some(
+                                ClientTls::new(
+                                    router.client_ca_bundle_pem.clone(),
+                                    app.advanced_settings.network_ingress_mtls_verify_depth,
+                                    app.advanced_settings.network_ingress_mtls_pass_certificate_to_upstream,
+                                )
+                                .map_err(DomainError::RouterError)?,
+                            );
+                        }
+                        // web application firewall
+                        if let Some(waf_mode) = app.advanced_settings.network_ingress_waf_mode {
+                            router_advanced_settings.waf = Some(RouterWaf {
+                                mode: waf_mode,
+                                custom_rules: app.advanced_settings.network_ingress_waf_custom_rules.clone(),
+                            });
+                        }
+                        // cookie based session affinity
+                        if app.advanced_settings.network_ingress_sticky_session_enable {
+                            router_advanced_settings.sticky_session = Some(StickySession {
+                                cookie_name: app.advanced_settings.network_ingress_sticky_session_cookie_name.clone(),
+                                cookie_max_age_seconds: app
+                                    .advanced_settings
+                                    .network_ingress_sticky_session_cookie_max_age_seconds,
+                                hash_policy: app.advanced_settings.network_ingress_sticky_session_hash_policy,
+                            });
+                        }
                         // disable custom domain check for this router
                         if !app.advanced_settings.deployment_custom_domain_check_enabled {
                             router_advanced_settings.custom_domain_check_enabled = false;
                         }
+                        // probes of the routes once deployed
+                        if !app.advanced_settings.network_ingress_probe_enabled {
+                            router_advanced_settings.probe_enabled = false;
+                        }
+                        if let Some(status_code) = app.advanced_settings.network_ingress_probe_expected_status_code {
+                            router_advanced_settings
+                                .probe_expected_status_codes
+                                .insert(route.path.clone(), status_code);
+                        }
                         // whitelist source range
                         if app.advanced_settings.network_ingress_whitelist_source_range
                             != RouterAdvancedSettings::whitelist_source_range_default_value()
@@ -156,10 +299,64 @@ impl EnvironmentRequest {
             for container in &self.containers {
                 for route in &router.routes {
                     if route.service_long_id == container.long_id {
+                        // rate and connection limits
+                        if let Some(limits) = RouteLimits::new(
+                            container.advanced_settings.network_ingress_limit_rps,
+                            container.advanced_settings.network_ingress_limit_burst,
+                            container.advanced_settings.network_ingress_limit_connections,
+                        )
+                        .map_err(DomainError::RouterError)?
+                        {
+                            router_advanced_settings.limits = Some(limits);
+                        }
+                        // client certificates
+                        if container.advanced_settings.network_ingress_mtls_enabled {
+                            router_advanced_settings.client_tls = Some(
+                                ClientTls::new(
+                                    router.client_ca_bundle_pem.clone(),
+                                    container.advanced_settings.network_ingress_mtls_verify_depth,
+                                    container
+                                        .advanced_settings
+                                        .network_ingress_mtls_pass_certificate_to_upstream,
+                                )
+                                .map_err(DomainError::RouterError)?,
+                            );
+                        }
+                        // web application firewall
+                        if let Some(waf_mode) = container.advanced_settings.network_ingress_waf_mode {
+                            router_advanced_settings.waf = Some(RouterWaf {
+                                mode: waf_mode,
+                                custom_rules: container.advanced_settings.network_ingress_waf_custom_rules.clone(),
+                            });
+                        }
+                        // cookie based session affinity
+                        if container.advanced_settings.network_ingress_sticky_session_enable {
+                            router_advanced_settings.sticky_session = Some(StickySession {
+                                cookie_name: container
+                                    .advanced_settings
+                                    .network_ingress_sticky_session_cookie_name
+                                    .clone(),
+                                cookie_max_age_seconds: container
+                                    .advanced_settings
+                                    .network_ingress_sticky_session_cookie_max_age_seconds,
+                                hash_policy: container.advanced_settings.network_ingress_sticky_session_hash_policy,
+                            });
+                        }
                         // disable custom domain check for this router
                         if !container.advanced_settings.deployment_custom_domain_check_enabled {
                             router_advanced_settings.custom_domain_check_enabled = false;
                         }
+                        // probes of the routes once deployed
+                        if !container.advanced_settings.network_ingress_probe_enabled {
+                            router_advanced_settings.probe_enabled = false;
+                        }
+                        if let Some(status_code) =
+                            container.advanced_settings.network_ingress_probe_expected_status_code
+                        {
+                            router_advanced_settings
+                                .probe_expected_status_codes
+                                .insert(route.path.clone(), status_code);
+                        }
                         // whitelist source range
                         if container.advanced_settings.network_ingress_whitelist_source_range
                             != RouterAdvancedSettings::whitelist_source_range_default_value()
@@ -216,6 +413,47 @@ impl EnvironmentRequest {
             for helm in &self.helms {
                 for route in &router.routes {
                     if route.service_long_id == helm.long_id {
+                        // rate and connection limits
+                        if let Some(limits) = RouteLimits::new(
+                            helm.advanced_settings.network_ingress_limit_rps,
+                            helm.advanced_settings.network_ingress_limit_burst,
+                            helm.advanced_settings.network_ingress_limit_connections,
+                        )
+                        .map_err(DomainError::RouterError)?
+                        {
+                            router_advanced_settings.limits = Some(limits);
+                        }
+                        // client certificates
+                        if helm.advanced_settings.network_ingress_mtls_enabled {
+                            router_advanced_settings.client_tls = Some(
+                                ClientTls::new(
+                                    router.client_ca_bundle_pem.clone(),
+                                    helm.advanced_settings.network_ingress_mtls_verify_depth,
+                                    helm.advanced_settings.network_ingress_mtls_pass_certificate_to_upstream,
+                                )
+                                .map_err(DomainError::RouterError)?,
+                            );
+                        }
+                        // web application firewall
+                        if let Some(waf_mode) = helm.advanced_settings.network_ingress_waf_mode {
+                            router_advanced_settings.waf = Some(RouterWaf {
+                                mode: waf_mode,
+                                custom_rules: helm.advanced_settings.network_ingress_waf_custom_rules.clone(),
+                            });
+                        }
+                        // cookie based session affinity
+                        if helm.advanced_settings.network_ingress_sticky_session_enable {
+                            router_advanced_settings.sticky_session = Some(StickySession {
+                                cookie_name: helm
+                                    .advanced_settings
+                                    .network_ingress_sticky_session_cookie_name
+                                    .clone(),
+                                cookie_max_age_seconds: helm
+                                    .advanced_settings
+                                    .network_ingress_sticky_session_cookie_max_age_seconds,
+                                hash_policy: helm.advanced_settings.network_ingress_sticky_session_hash_policy,
+                            });
+                        }
                         // disable custom domain check for this router
 
                         if !helm.advanced_settings.deployment_custom_domain_check_enabled {
@@ -266,6 +504,12 @@ impl EnvironmentRequest {
                 }
             }
 
+            router_advanced_settings.nginx_annotations = NginxAnnotations::new(
+                router.nginx_annotations.clone(),
+                cluster.advanced_settings().nginx_custom_snippet_annotations_enabled,
+            )
+            .map_err(DomainError::RouterError)?;
+
             match router.to_router_domain(context, router_advanced_settings, cloud_provider) {
                 Ok(router) => routers.push(router),
                 Err(err) => {
@@ -298,6 +542,83 @@ impl EnvironmentRequest {
             .collect();
         let helm_charts = helm_charts?;
 
+        let kustomizations: Result<Vec<Box<dyn KustomizeService>>, KustomizeError> = self
+            .kustomizations
+            .iter()
+            .cloned()
+            .map(|kustomize| kustomize.to_kustomize_domain(context))
+            .collect();
+        let kustomizations = kustomizations?;
+
+        let terraform_services: Result<Vec<Box<dyn TerraformServiceTrait>>, TerraformServiceError> = self
+            .terraform_services
+            .iter()
+            .cloned()
+            .map(|terraform_service| terraform_service.to_terraform_service_domain(context))
+            .collect();
+        let terraform_services = terraform_services?;
+
+        // Services are deployed in the same namespace, so their kubernetes names must not collide
+        let kube_names = applications
+            .iter()
+            .map(|srv| srv.as_service())
+            .chain(containers.iter().map(|srv| srv.as_service()))
+            .chain(databases.iter().map(|srv| srv.as_service()))
+            .chain(jobs.iter().map(|srv| srv.as_service()))
+            .chain(routers.iter().map(|srv| srv.as_service()))
+            .chain(helm_charts.iter().map(|srv| srv.as_service()))
+            .chain(kustomizations.iter().map(|srv| srv.as_service()))
+            .chain(terraform_services.iter().map(|srv| srv.as_service()))
+            .map(|srv| (srv.kube_name(), format!("{} {}", srv.service_type().name(), srv.long_id())));
+        if let Some(collision) = naming::find_collisions(kube_names).into_iter().next() {
+            return Err(DomainError::KubeNameCollision {
+                name: collision.name,
+                owners: collision.owners,
+            });
+        }
+
+        // Services can only mount the shared volumes of their own environment
+        let shared_volume_ids: HashSet<Uuid> = self.shared_volumes.iter().map(|volume| volume.long_id).collect();
+        let shared_volume_mounts = self
+            .applications
+            .iter()
+            .flat_map(|app| {
+                app.shared_volume_mounts
+                    .iter()
+                    .map(|mount| (format!("application {}", app.long_id), mount))
+            })
+            .chain(self.containers.iter().flat_map(|container| {
+                container
+                    .shared_volume_mounts
+                    .iter()
+                    .map(|mount| (format!("container {}", container.long_id), mount))
+            }));
+        for (service, mount) in shared_volume_mounts {
+            if !shared_volume_ids.contains(&mount.shared_volume_id) {
+                return Err(DomainError::UnknownSharedVolume {
+                    service,
+                    shared_volume_id: mount.shared_volume_id,
+                });
+            }
+        }
+
+        // Services are deployed in order of their dependencies, which must not loop
+        let service_ids = self
+            .applications
+            .iter()
+            .map(|srv| srv.long_id)
+            .chain(self.containers.iter().map(|srv| srv.long_id))
+            .chain(self.jobs.iter().map(|srv| srv.long_id))
+            .chain(self.databases.iter().map(|srv| srv.long_id))
+            .chain(self.helms.iter().map(|srv| srv.long_id))
+            .chain(self.kustomizations.iter().map(|srv| srv.long_id))
+            .chain(self.terraform_services.iter().map(|srv| srv.long_id));
+        if let Err(cycle) = dependency_levels(service_ids, &self.service_dependencies) {
+            return Err(DomainError::ServiceDependencyCycle {
+                services: cycle.services,
+            });
+        }
+
         Ok(Environment::new(
             self.long_id,
             self.name.clone(),
@@ -314,6 +635,13 @@ impl EnvironmentRequest {
             databases,
             jobs,
             helm_charts,
-        ))
+        )
+        .with_kustomizations(kustomizations)
+        .with_terraform_services(terraform_services)
+        .with_shared_volumes(self.shared_volumes.clone())
+        .with_service_account(self.service_account.clone())
+        .with_remote_builder(self.remote_builder.clone())
+        .with_deployment_waves(self.deployment_waves.clone())
+        .with_service_dependencies(self.service_dependencies.clone()))
     }
 }
@@ -6,7 +6,7 @@ use crate::io_models::application::{GitCredentials, Port};
 use crate::io_models::container::Registry;
 use crate::io_models::context::Context;
 use crate::io_models::variable_utils::{default_environment_vars_with_info, VariableInfo};
-use crate::io_models::{fetch_git_token, ssh_keys_from_env_vars, Action};
+use crate::io_models::{fetch_git_token, ssh_keys_from_env_vars, Action, StickySessionHashPolicy, WafMode};
 use crate::models;
 use crate::models::aws::AwsAppExtraSettings;
 use crate::models::aws_ec2::AwsEc2AppExtraSettings;
@@ -42,6 +42,12 @@ pub struct HelmChartAdvancedSettings {
     pub network_ingress_cors_enable: bool,
     #[serde(alias = "network.ingress.enable_sticky_session")]
     pub network_ingress_sticky_session_enable: bool,
+    #[serde(alias = "network.ingress.sticky_session_cookie_name")]
+    pub network_ingress_sticky_session_cookie_name: String,
+    #[serde(alias = "network.ingress.sticky_session_cookie_max_age_seconds")]
+    pub network_ingress_sticky_session_cookie_max_age_seconds: u32,
+    #[serde(alias = "network.ingress.sticky_session_hash_policy")]
+    pub network_ingress_sticky_session_hash_policy: StickySessionHashPolicy,
     #[serde(alias = "network.ingress.cors_allow_origin")]
     pub network_ingress_cors_allow_origin: String,
     #[serde(alias = "network.ingress.cors_allow_methods")]
@@ -74,6 +80,25 @@ pub struct HelmChartAdvancedSettings {
     pub network_ingress_denylist_source_range: String,
     #[serde(alias = "network.ingress.basic_auth_env_var")]
     pub network_ingress_basic_auth_env_var: String,
+    // Limits of the requests of each client IP, 0 meaning no limit
+    #[serde(alias = "network.ingress.limit_rps")]
+    pub network_ingress_limit_rps: u32,
+    #[serde(alias = "network.ingress.limit_burst")]
+    pub network_ingress_limit_burst: u32,
+    #[serde(alias = "network.ingress.limit_connections")]
+    pub network_ingress_limit_connections: u32,
+    // Web application firewall of the routes, the one of the cluster applies when not set
+    #[serde(alias = "network.ingress.waf_mode")]
+    pub network_ingress_waf_mode: Option<WafMode>,
+    #[serde(alias = "network.ingress.waf_custom_rules")]
+    pub network_ingress_waf_custom_rules: Vec<String>,
+    // Client certificates required by the routes, verified against the CA bundle of the router
+    #[serde(alias = "network.ingress.mtls_enabled")]
+    pub network_ingress_mtls_enabled: bool,
+    #[serde(alias = "network.ingress.mtls_verify_depth")]
+    pub network_ingress_mtls_verify_depth: u32,
+    #[serde(alias = "network.ingress.mtls_pass_certificate_to_upstream")]
+    pub network_ingress_mtls_pass_certificate_to_upstream: bool,
 
     #[serde(alias = "network.ingress.grpc_send_timeout_seconds")]
     pub network_ingress_grpc_send_timeout_seconds: u32,
@@ -89,6 +114,9 @@ impl Default for HelmChartAdvancedSettings {
             network_ingress_proxy_body_size_mb: 100,
             network_ingress_cors_enable: false,
             network_ingress_sticky_session_enable: false,
+            network_ingress_sticky_session_cookie_name: "INGRESSCOOKIE_QOVERY".to_string(),
+            network_ingress_sticky_session_cookie_max_age_seconds: 85400,
+            network_ingress_sticky_session_hash_policy: StickySessionHashPolicy::Persistent,
             network_ingress_cors_allow_origin: "*".to_string(),
             network_ingress_cors_allow_methods: "GET, PUT, POST, DELETE, PATCH, OPTIONS".to_string(),
             network_ingress_cors_allow_headers: "DNT,Keep-Alive,User-Agent,X-Requested-With,If-Modified-Since,Cache-Control,Content-Type,Range,Authorization".to_string(),
@@ -105,6 +133,14 @@ impl Default for HelmChartAdvancedSettings {
             network_ingress_whitelist_source_range: "0.0.0.0/0".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_limit_rps: 0,
+            network_ingress_limit_burst: 0,
+            network_ingress_limit_connections: 0,
+            network_ingress_waf_mode: None,
+            network_ingress_waf_custom_rules: vec![],
+            network_ingress_mtls_enabled: false,
+            network_ingress_mtls_verify_depth: 1,
+            network_ingress_mtls_pass_certificate_to_upstream: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
         }
@@ -66,6 +66,14 @@ pub struct JobAdvancedSettings {
     pub security_read_only_root_filesystem: bool,
     #[serde(alias = "security.automount_service_account_token")]
     pub security_automount_service_account_token: bool,
+
+    // Artifacts
+    #[serde(alias = "job.artifacts.enabled")]
+    pub job_artifacts_enabled: bool,
+    #[serde(alias = "job.artifacts.path")]
+    pub job_artifacts_path: String,
+    #[serde(alias = "job.artifacts.retention_in_days")]
+    pub job_artifacts_retention_in_days: u32,
 }
 
 impl Default for JobAdvancedSettings {
@@ -83,10 +91,24 @@ impl Default for JobAdvancedSettings {
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            job_artifacts_enabled: false,
+            job_artifacts_path: "/qovery-artifacts".to_string(),
+            job_artifacts_retention_in_days: 7,
         }
     }
 }
 
+/// S3 compatible bucket where the files produced by job runs are uploaded
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct JobArtifactsStorage {
+    pub bucket_name: String,
+    pub region: String,
+    /// Endpoint of S3 compatible object storages (Scaleway, GCS interoperability), AWS S3 if not set
+    pub endpoint: Option<String>,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum JobSchedule {
@@ -156,6 +178,8 @@ pub struct Job {
     pub liveness_probe: Option<Probe>,
     #[serde(default)]
     pub advanced_settings: JobAdvancedSettings,
+    #[serde(default)]
+    pub artifacts_storage: Option<JobArtifactsStorage>,
     pub container_registries: ContainerRegistries,
 }
 
@@ -210,6 +234,8 @@ impl Job {
                 dockerfile_path,
                 root_path,
                 buildpack_language: None,
+                recursive_submodules: false,
+                lfs: true,
             },
             image: self.to_image(commit_id.to_string(), registry_url),
             environment_variables: self
@@ -231,12 +257,18 @@ impl Job {
                     Some((k.clone(), v))
                 })
                 .collect::<BTreeMap<_, _>>(),
+            secrets: BTreeMap::new(),
             disable_cache: disable_build_cache,
             timeout: Duration::from_secs(self.advanced_settings.build_timeout_max_sec as u64),
             architectures,
             max_cpu_in_milli: self.advanced_settings.build_cpu_max_in_milli,
             max_ram_in_gib: self.advanced_settings.build_ram_max_in_gib,
             registries: self.container_registries.registries.clone(),
+            base_image_mirror: None,
+            image_signing_key: None,
+            cache: None,
+            bust_cache: false,
+            sbom: None,
         };
 
         build.compute_image_tag();
@@ -279,7 +311,9 @@ impl Job {
                 }?;
 
                 ImageSource::Build {
-                    source: Box::new(build),
+                    source: Box::new(
+                        build.with_cluster_docker_hub_registry(cluster.advanced_settings().docker_hub_registry()),
+                    ),
                 }
             }
             JobSource::Image {
@@ -292,12 +326,15 @@ impl Job {
                     registry.set_url(default_container_registry.registry_info().endpoint.clone());
                 }
                 ImageSource::Registry {
-                    source: Box::new(RegistryImageSource {
-                        registry,
-                        image,
-                        tag,
-                        registry_mirroring_mode: cluster.advanced_settings().registry_mirroring_mode.clone(),
-                    }),
+                    source: Box::new(
+                        RegistryImageSource {
+                            registry,
+                            image,
+                            tag,
+                            registry_mirroring_mode: cluster.advanced_settings().registry_mirroring_mode.clone(),
+                        }
+                        .with_cluster_docker_hub_settings(cluster.advanced_settings()),
+                    ),
                 }
             }
         };
@@ -331,6 +368,7 @@ impl Job {
                             .map(|e| e.to_domain())
                             .collect::<BTreeSet<_>>(),
                         self.advanced_settings,
+                        self.artifacts_storage,
                         self.readiness_probe.map(|p| p.to_domain()),
                         self.liveness_probe.map(|p| p.to_domain()),
                         AwsAppExtraSettings {},
@@ -361,6 +399,7 @@ impl Job {
                             .map(|e| e.to_domain())
                             .collect::<BTreeSet<_>>(),
                         self.advanced_settings,
+                        self.artifacts_storage,
                         self.readiness_probe.map(|p| p.to_domain()),
                         self.liveness_probe.map(|p| p.to_domain()),
                         AwsEc2AppExtraSettings {},
@@ -392,6 +431,7 @@ impl Job {
                     .map(|e| e.to_domain())
                     .collect::<BTreeSet<_>>(),
                 self.advanced_settings,
+                self.artifacts_storage,
                 self.readiness_probe.map(|p| p.to_domain()),
                 self.liveness_probe.map(|p| p.to_domain()),
                 ScwAppExtraSettings {},
@@ -421,6 +461,7 @@ impl Job {
                     .map(|e| e.to_domain())
                     .collect::<BTreeSet<_>>(),
                 self.advanced_settings,
+                self.artifacts_storage,
                 self.readiness_probe.map(|p| p.to_domain()),
                 self.liveness_probe.map(|p| p.to_domain()),
                 GcpAppExtraSettings {},
@@ -450,6 +491,7 @@ impl Job {
                     .map(|e| e.to_domain())
                     .collect::<BTreeSet<_>>(),
                 self.advanced_settings,
+                self.artifacts_storage,
                 self.readiness_probe.map(|p| p.to_domain()),
                 self.liveness_probe.map(|p| p.to_domain()),
                 SelfManagedAppExtraSettings {},
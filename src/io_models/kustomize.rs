@@ -0,0 +1,91 @@
+use crate::build_platform::SshKey;
+use crate::cloud_provider::service::ServiceType;
+use crate::io_models::application::GitCredentials;
+use crate::io_models::context::Context;
+use crate::io_models::variable_utils::{default_environment_vars_with_info, VariableInfo};
+use crate::io_models::{fetch_git_token, ssh_keys_from_env_vars, Action};
+use crate::models;
+use crate::models::kustomize::{KustomizeError, KustomizeService};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
+
+/// Image of the manifests of the overlay replaced by the engine, i.e: to deploy the tag built for this environment
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct KustomizeImage {
+    /// Name of the image as written in the manifests, without its tag
+    pub name: String,
+    pub new_name: Option<String>,
+    pub new_tag: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct Kustomize {
+    pub long_id: Uuid,
+    pub name: String,
+    pub kube_name: String,
+    pub action: Action,
+    pub git_url: Url,
+    pub git_credentials: Option<GitCredentials>,
+    pub commit_id: String,
+    /// Directory of the repository holding the `kustomization.yaml` of the overlay to deploy
+    pub overlay_path: PathBuf,
+    #[serde(default)]
+    pub images: Vec<KustomizeImage>,
+    pub timeout_sec: u64,
+    /// Key is a String, Value is a base64 encoded String
+    /// Use BTreeMap to get Hash trait which is not available on HashMap
+    #[serde(default = "default_environment_vars_with_info")]
+    pub environment_vars_with_infos: BTreeMap<String, VariableInfo>,
+}
+
+impl Kustomize {
+    pub fn to_kustomize_domain(self, context: &Context) -> Result<Box<dyn KustomizeService>, KustomizeError> {
+        // Get passphrase and public key if provided by the user
+        let ssh_keys: Vec<SshKey> = ssh_keys_from_env_vars(&self.environment_vars_with_infos);
+        let environment_variables_with_info: HashMap<String, VariableInfo> = self
+            .environment_vars_with_infos
+            .into_iter()
+            .map(|(k, mut v)| {
+                v.value = String::from_utf8_lossy(
+                    &base64::engine::general_purpose::STANDARD
+                        .decode(v.value)
+                        .unwrap_or_default(),
+                )
+                .to_string();
+                (k, v)
+            })
+            .collect();
+
+        let qovery_api = context.qovery_api.clone();
+        let service_id = self.long_id;
+        let source = models::kustomize::KustomizeSource {
+            git_url: self.git_url,
+            get_credentials: if self.git_credentials.is_none() {
+                Box::new(|| Ok(None))
+            } else {
+                Box::new(move || fetch_git_token(&*qovery_api, ServiceType::Kustomize, &service_id).map(Some))
+            },
+            commit_id: self.commit_id,
+            overlay_path: self.overlay_path,
+            ssh_keys,
+        };
+
+        Ok(Box::new(models::kustomize::Kustomize::new(
+            context,
+            self.long_id,
+            self.name,
+            self.kube_name,
+            self.action.to_service_action(),
+            source,
+            self.images,
+            Duration::from_secs(self.timeout_sec),
+            environment_variables_with_info,
+            |transmitter| context.get_event_details(transmitter),
+        )?))
+    }
+}
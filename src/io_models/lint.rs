@@ -0,0 +1,415 @@
+use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
+use crate::io_models::application::{Application, Port};
+use crate::io_models::container::Container;
+use crate::io_models::environment::EnvironmentRequest;
+use crate::io_models::job::{Job, JobSource};
+use crate::io_models::probe::Probe;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Allocatable resources of the biggest node available in the cluster.
+/// Used to detect services requesting more than what a single node can offer.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NodeCapacity {
+    pub cpu_in_milli: u32,
+    pub ram_in_mib: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LintRule {
+    NoResourceLimits,
+    SingleReplicaWithPublicTraffic,
+    LatestImageTag,
+    MissingHealthProbe,
+    OversizedResourceRequest,
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LintRule::NoResourceLimits => "NoResourceLimits",
+            LintRule::SingleReplicaWithPublicTraffic => "SingleReplicaWithPublicTraffic",
+            LintRule::LatestImageTag => "LatestImageTag",
+            LintRule::MissingHealthProbe => "MissingHealthProbe",
+            LintRule::OversizedResourceRequest => "OversizedResourceRequest",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LintServiceKind {
+    Application,
+    Container,
+    Job,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct LintWarning {
+    pub rule: LintRule,
+    pub service_kind: LintServiceKind,
+    pub service_long_id: Uuid,
+    pub service_name: String,
+    pub message: String,
+}
+
+/// Normalized view of a service, only keeping what the lint rules are looking at
+struct LintedService<'a> {
+    kind: LintServiceKind,
+    long_id: Uuid,
+    name: &'a str,
+    image_tag: Option<&'a str>,
+    cpu_request_in_milli: u32,
+    cpu_limit_in_milli: u32,
+    ram_request_in_mib: u32,
+    ram_limit_in_mib: u32,
+    min_instances: u32,
+    has_public_port: bool,
+    has_ports: bool,
+    readiness_probe: Option<&'a Probe>,
+    liveness_probe: Option<&'a Probe>,
+}
+
+impl<'a> LintedService<'a> {
+    fn warning(&self, rule: LintRule, message: String) -> LintWarning {
+        LintWarning {
+            rule,
+            service_kind: self.kind,
+            service_long_id: self.long_id,
+            service_name: self.name.to_string(),
+            message,
+        }
+    }
+
+    fn from_application(app: &'a Application) -> Self {
+        // invalid units are rejected when the application is deployed, they are linted as unset here
+        let resources = app.resources();
+        let milli_cpu = |cpu: &str| KubernetesCpuResourceUnit::from_str(cpu).map_or(0, |cpu| cpu.to_milli_cpu());
+        let mebibytes = |ram: &str| KubernetesMemoryResourceUnit::from_str(ram).map_or(0, |ram| ram.to_mebibytes());
+        LintedService {
+            kind: LintServiceKind::Application,
+            long_id: app.long_id,
+            name: &app.name,
+            image_tag: None,
+            cpu_request_in_milli: milli_cpu(&resources.cpu_request),
+            cpu_limit_in_milli: milli_cpu(&resources.cpu_limit),
+            ram_request_in_mib: mebibytes(&resources.ram_request),
+            ram_limit_in_mib: mebibytes(&resources.ram_limit),
+            min_instances: app.min_instances,
+            has_public_port: has_public_port(&app.ports),
+            has_ports: !app.ports.is_empty(),
+            readiness_probe: app.readiness_probe.as_ref(),
+            liveness_probe: app.liveness_probe.as_ref(),
+        }
+    }
+
+    fn from_container(container: &'a Container) -> Self {
+        LintedService {
+            kind: LintServiceKind::Container,
+            long_id: container.long_id,
+            name: &container.name,
+            image_tag: Some(&container.tag),
+            cpu_request_in_milli: container.cpu_request_in_mili,
+            cpu_limit_in_milli: container.cpu_limit_in_mili,
+            ram_request_in_mib: container.ram_request_in_mib,
+            ram_limit_in_mib: container.ram_limit_in_mib,
+            min_instances: container.min_instances,
+            has_public_port: has_public_port(&container.ports),
+            has_ports: !container.ports.is_empty(),
+            readiness_probe: container.readiness_probe.as_ref(),
+            liveness_probe: container.liveness_probe.as_ref(),
+        }
+    }
+
+    fn from_job(job: &'a Job) -> Self {
+        let image_tag = match &job.source {
+            JobSource::Image { tag, .. } => Some(tag.as_str()),
+            JobSource::Docker { .. } => None,
+        };
+        LintedService {
+            kind: LintServiceKind::Job,
+            long_id: job.long_id,
+            name: &job.name,
+            image_tag,
+            cpu_request_in_milli: job.cpu_request_in_milli,
+            cpu_limit_in_milli: job.cpu_limit_in_milli,
+            ram_request_in_mib: job.ram_request_in_mib,
+            ram_limit_in_mib: job.ram_limit_in_mib,
+            // jobs are run to completion, replicas and probes are irrelevant
+            min_instances: 1,
+            has_public_port: false,
+            has_ports: false,
+            readiness_probe: None,
+            liveness_probe: None,
+        }
+    }
+}
+
+fn has_public_port(ports: &[Port]) -> bool {
+    ports.iter().any(|port| port.publicly_accessible)
+}
+
+fn lint_resource_limits(service: &LintedService, warnings: &mut Vec<LintWarning>) {
+    let mut missing = vec![];
+    if service.cpu_limit_in_milli == 0 {
+        missing.push("cpu");
+    }
+    if service.ram_limit_in_mib == 0 {
+        missing.push("memory");
+    }
+
+    if !missing.is_empty() {
+        warnings.push(service.warning(
+            LintRule::NoResourceLimits,
+            format!(
+                "No {} limit is set, the service can starve other workloads running on the same node",
+                missing.join(" and ")
+            ),
+        ));
+    }
+}
+
+fn lint_single_replica(service: &LintedService, warnings: &mut Vec<LintWarning>) {
+    if service.has_public_port && service.min_instances <= 1 {
+        warnings.push(service.warning(
+            LintRule::SingleReplicaWithPublicTraffic,
+            "Service is publicly exposed with a single instance, any restart or node failure will cause downtime. Consider setting min instances to 2 or more".to_string(),
+        ));
+    }
+}
+
+fn lint_image_tag(service: &LintedService, warnings: &mut Vec<LintWarning>) {
+    let Some(tag) = service.image_tag else {
+        return;
+    };
+
+    let tag = tag.trim();
+    if tag.is_empty() || tag.eq_ignore_ascii_case("latest") {
+        warnings.push(service.warning(
+            LintRule::LatestImageTag,
+            "Image uses the `latest` tag, deployments are not reproducible and a restart can silently pull a new version. Pin a specific tag instead".to_string(),
+        ));
+    }
+}
+
+fn lint_health_probes(service: &LintedService, warnings: &mut Vec<LintWarning>) {
+    if !service.has_ports {
+        return;
+    }
+
+    let mut missing = vec![];
+    if service.readiness_probe.is_none() {
+        missing.push("readiness");
+    }
+    if service.liveness_probe.is_none() {
+        missing.push("liveness");
+    }
+
+    if !missing.is_empty() {
+        warnings.push(service.warning(
+            LintRule::MissingHealthProbe,
+            format!(
+                "No {} probe is configured, Kubernetes cannot detect when the service is not able to handle traffic",
+                missing.join(" and ")
+            ),
+        ));
+    }
+}
+
+fn lint_oversized_requests(service: &LintedService, node_capacity: &NodeCapacity, warnings: &mut Vec<LintWarning>) {
+    if service.cpu_request_in_milli > node_capacity.cpu_in_milli {
+        warnings.push(service.warning(
+            LintRule::OversizedResourceRequest,
+            format!(
+                "Requested cpu ({}m) is higher than the biggest node of the cluster ({}m), the service will never be scheduled",
+                service.cpu_request_in_milli, node_capacity.cpu_in_milli
+            ),
+        ));
+    }
+
+    if service.ram_request_in_mib > node_capacity.ram_in_mib {
+        warnings.push(service.warning(
+            LintRule::OversizedResourceRequest,
+            format!(
+                "Requested memory ({}Mi) is higher than the biggest node of the cluster ({}Mi), the service will never be scheduled",
+                service.ram_request_in_mib, node_capacity.ram_in_mib
+            ),
+        ));
+    }
+}
+
+fn lint_service(service: &LintedService, node_capacity: Option<&NodeCapacity>) -> Vec<LintWarning> {
+    let mut warnings = vec![];
+    lint_resource_limits(service, &mut warnings);
+    lint_single_replica(service, &mut warnings);
+    lint_image_tag(service, &mut warnings);
+    lint_health_probes(service, &mut warnings);
+    if let Some(node_capacity) = node_capacity {
+        lint_oversized_requests(service, node_capacity, &mut warnings);
+    }
+
+    warnings
+}
+
+impl EnvironmentRequest {
+    /// Analyze the environment payload for common anti-patterns, without deploying anything.
+    /// When `node_capacity` is not provided, requests are not checked against the cluster nodes.
+    pub fn lint(&self, node_capacity: Option<&NodeCapacity>) -> Vec<LintWarning> {
+        let applications = self.applications.iter().map(LintedService::from_application);
+        let containers = self.containers.iter().map(LintedService::from_container);
+        let jobs = self.jobs.iter().map(LintedService::from_job);
+
+        applications
+            .chain(containers)
+            .chain(jobs)
+            .flat_map(|service| lint_service(&service, node_capacity))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_models::probe::ProbeType;
+
+    fn probe() -> Probe {
+        Probe {
+            r#type: ProbeType::Tcp { host: None },
+            port: 8080,
+            initial_delay_seconds: 10,
+            period_seconds: 10,
+            timeout_seconds: 5,
+            success_threshold: 1,
+            failure_threshold: 3,
+        }
+    }
+
+    fn service(probe: &Probe) -> LintedService {
+        LintedService {
+            kind: LintServiceKind::Container,
+            long_id: Uuid::new_v4(),
+            name: "my-service",
+            image_tag: Some("1.2.3"),
+            cpu_request_in_milli: 500,
+            cpu_limit_in_milli: 1000,
+            ram_request_in_mib: 256,
+            ram_limit_in_mib: 512,
+            min_instances: 2,
+            has_public_port: true,
+            has_ports: true,
+            readiness_probe: Some(probe),
+            liveness_probe: Some(probe),
+        }
+    }
+
+    fn rules(warnings: &[LintWarning]) -> Vec<LintRule> {
+        warnings.iter().map(|w| w.rule).collect()
+    }
+
+    #[test]
+    fn test_lint_healthy_service_has_no_warning() {
+        let probe = probe();
+        let node_capacity = NodeCapacity {
+            cpu_in_milli: 2000,
+            ram_in_mib: 4096,
+        };
+
+        assert!(lint_service(&service(&probe), Some(&node_capacity)).is_empty());
+    }
+
+    #[test]
+    fn test_lint_each_rule() {
+        let probe = probe();
+        let node_capacity = NodeCapacity {
+            cpu_in_milli: 2000,
+            ram_in_mib: 4096,
+        };
+
+        struct TestCase<'a> {
+            service: LintedService<'a>,
+            expected: Vec<LintRule>,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                service: LintedService {
+                    cpu_limit_in_milli: 0,
+                    ..service(&probe)
+                },
+                expected: vec![LintRule::NoResourceLimits],
+            },
+            TestCase {
+                service: LintedService {
+                    min_instances: 1,
+                    ..service(&probe)
+                },
+                expected: vec![LintRule::SingleReplicaWithPublicTraffic],
+            },
+            TestCase {
+                service: LintedService {
+                    min_instances: 1,
+                    has_public_port: false,
+                    ..service(&probe)
+                },
+                expected: vec![],
+            },
+            TestCase {
+                service: LintedService {
+                    image_tag: Some("latest"),
+                    ..service(&probe)
+                },
+                expected: vec![LintRule::LatestImageTag],
+            },
+            TestCase {
+                service: LintedService {
+                    liveness_probe: None,
+                    ..service(&probe)
+                },
+                expected: vec![LintRule::MissingHealthProbe],
+            },
+            TestCase {
+                service: LintedService {
+                    has_ports: false,
+                    has_public_port: false,
+                    readiness_probe: None,
+                    liveness_probe: None,
+                    ..service(&probe)
+                },
+                expected: vec![],
+            },
+            TestCase {
+                service: LintedService {
+                    cpu_request_in_milli: 4000,
+                    cpu_limit_in_milli: 4000,
+                    ram_request_in_mib: 8192,
+                    ram_limit_in_mib: 8192,
+                    ..service(&probe)
+                },
+                expected: vec![LintRule::OversizedResourceRequest, LintRule::OversizedResourceRequest],
+            },
+        ];
+
+        for tc in test_cases {
+            // execute:
+            let warnings = lint_service(&tc.service, Some(&node_capacity));
+
+            // verify:
+            assert_eq!(rules(&warnings), tc.expected);
+        }
+    }
+
+    #[test]
+    fn test_lint_without_node_capacity_skip_oversized_check() {
+        let probe = probe();
+        let service = LintedService {
+            cpu_request_in_milli: 64000,
+            cpu_limit_in_milli: 64000,
+            ..service(&probe)
+        };
+
+        assert!(lint_service(&service, None).is_empty());
+    }
+}
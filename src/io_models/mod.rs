@@ -1,5 +1,6 @@
 use crate::build_platform::{Credentials, SshKey};
 use crate::cloud_provider;
+use crate::cloud_provider::models::TaintEffect;
 use crate::cloud_provider::service;
 use crate::cloud_provider::service::ServiceType;
 use crate::engine_task::qovery_api::QoveryApi;
@@ -21,8 +22,12 @@ pub mod engine_request;
 pub mod environment;
 pub mod helm_chart;
 pub mod job;
+pub mod kustomize;
+pub mod lint;
 pub mod probe;
 pub mod router;
+pub mod shared_volume;
+pub mod terraform_service;
 pub mod variable_utils;
 
 #[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
@@ -32,6 +37,264 @@ pub enum UpdateStrategy {
     Recreate,
 }
 
+/// How a new version of an application replaces the running one
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum DeploymentStrategy {
+    /// Pods of the deployment are replaced progressively, following its update strategy
+    #[default]
+    RollingUpdate,
+    /// New version runs next to the previous one, traffic is switched to it at once when it is ready
+    BlueGreen,
+    /// A single instance of the new version gets a share of the traffic of the routers, the new version is rolled
+    /// out if it does not fail too many requests during its analysis
+    Canary,
+}
+
+/// How the pods of a statefulset are replaced when its spec changes
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum StatefulSetUpdateStrategy {
+    /// Pods are replaced one at a time, from the highest ordinal down to the partition
+    #[default]
+    RollingUpdate,
+    /// Pods keep running the previous spec until they are deleted
+    OnDelete,
+}
+
+/// How running pods get changes of their configuration (mounted files)
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum ConfigReloadStrategy {
+    /// Pods are restarted with a rolling update when their configuration changes
+    #[default]
+    RollingRestart,
+    /// Pods are kept running, their config checksum annotation is updated and exposed to them
+    Annotation,
+    /// Same as `Annotation`, with a sidecar calling a reload webhook of the application on change
+    Sidecar,
+}
+
+/// How the cookie of a sticky session picks the instance the session sticks to
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StickySessionHashPolicy {
+    /// Sessions stay on their instance while it runs, even when instances are added
+    #[default]
+    Persistent,
+    /// Sessions are redistributed when instances are added, to keep the load balanced
+    Balanced,
+}
+
+/// Mode of the web application firewall (ModSecurity with the OWASP core rule set) of the nginx ingress
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum WafMode {
+    #[default]
+    Disabled,
+    /// Requests matching the rules are only logged
+    DetectionOnly,
+    /// Requests matching the rules are denied
+    Blocking,
+}
+
+impl WafMode {
+    /// Value of the `SecRuleEngine` directive of ModSecurity
+    pub fn rule_engine(&self) -> &'static str {
+        match self {
+            WafMode::Disabled => "Off",
+            WafMode::DetectionOnly => "DetectionOnly",
+            WafMode::Blocking => "On",
+        }
+    }
+}
+
+/// Topology domain across which the pods of a service are spread
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum TopologySpreadKey {
+    /// Spread across zones when the cluster spans several of them, no constraint otherwise
+    #[default]
+    Auto,
+    Zone,
+    Hostname,
+    /// Spread across zones, then across the nodes of each zone
+    ZoneAndHostname,
+    Disabled,
+}
+
+/// What the scheduler does with a pod that would break the max skew of its topology spread constraint
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum TopologySpreadWhenUnsatisfiable {
+    #[default]
+    ScheduleAnyway,
+    DoNotSchedule,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum TolerationOperator {
+    #[default]
+    Equal,
+    Exists,
+}
+
+/// Lets the pods of a service be scheduled on nodes having a matching taint, i.e: on a dedicated node group
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Hash, Debug)]
+pub struct Toleration {
+    pub key: String,
+    #[serde(default)]
+    pub operator: TolerationOperator,
+    #[serde(default)]
+    pub value: String,
+    /// Tolerates all the effects when not set
+    #[serde(default)]
+    pub effect: Option<TaintEffect>,
+}
+
+/// Extra labels and annotations set by users on the kubernetes resources generated for a service,
+/// i.e: for external monitoring or backup tools selecting resources
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(default)]
+pub struct CustomMetadata {
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl CustomMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.annotations.is_empty()
+    }
+}
+
+/// One-shot commands run with a kubernetes job at some points of the lifecycle of a service.
+/// They get the environment variables of the service, and its image when they do not set their own.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(default)]
+pub struct LifecycleHooks {
+    /// Run once the service has been deployed successfully
+    pub post_create: Option<LifecycleHook>,
+    /// Run before the service is deleted, while it is still running
+    pub pre_delete: Option<LifecycleHook>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct LifecycleHook {
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default = "default_lifecycle_hook_timeout_in_seconds")]
+    pub timeout_in_seconds: u32,
+    #[serde(default)]
+    pub failure_policy: LifecycleHookFailurePolicy,
+}
+
+fn default_lifecycle_hook_timeout_in_seconds() -> u32 {
+    600
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleHookFailurePolicy {
+    /// The deployment or the deletion of the service fails with the hook
+    #[default]
+    FailDeployment,
+    /// A failure of the hook is only reported as a warning
+    Warn,
+}
+
+/// Check run with a kubernetes job once the new version of an application is rolled out.
+/// Its failure fails the deployment, the application being rolled back to its previous version.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SmokeTest {
+    #[serde(flatten)]
+    pub check: SmokeTestCheck,
+    #[serde(default = "default_smoke_test_timeout_in_seconds")]
+    pub timeout_in_seconds: u32,
+}
+
+fn default_smoke_test_timeout_in_seconds() -> u32 {
+    300
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SmokeTestCheck {
+    /// GET request sent to a port of the application through its service, failing on any other status than the
+    /// expected one
+    Http {
+        path: String,
+        port: u16,
+        #[serde(default = "default_smoke_test_expected_status")]
+        expected_status: u16,
+    },
+    /// Command failing with a non zero exit code, run with the environment variables of the application and its
+    /// image when it does not set its own
+    Command {
+        command: Vec<String>,
+        #[serde(default)]
+        image: Option<String>,
+    },
+}
+
+fn default_smoke_test_expected_status() -> u16 {
+    200
+}
+
+/// Environment variable of a service synced by External Secrets Operator from a secret store of the cluster, its value
+/// never going through the engine
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ExternalSecretVariable {
+    /// Name of the environment variable
+    pub name: String,
+    /// ClusterSecretStore holding the secret, `vault` being the Vault server configured on the cluster
+    #[serde(default = "default_external_secret_store")]
+    pub store: String,
+    /// Path of the secret in the store
+    pub key: String,
+    /// Field of the secret to read, the whole secret otherwise
+    #[serde(default)]
+    pub property: Option<String>,
+}
+
+fn default_external_secret_store() -> String {
+    "vault".to_string()
+}
+
+/// Event source scaling the instances of a service with KEDA, on top of their cpu usage
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct KedaTrigger {
+    pub r#type: KedaTriggerType,
+    /// Parameters of the KEDA scaler, i.e: `queueURL` and `queueLength` for an SQS queue
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// Parameters of the scaler holding credentials, read from environment variables of the service by parameter name
+    #[serde(default)]
+    pub authentication_env_vars: BTreeMap<String, String>,
+}
+
+/// Scalers supported by the triggers, named as in KEDA
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum KedaTriggerType {
+    AwsSqsQueue,
+    Kafka,
+    Cron,
+}
+
+impl KedaTriggerType {
+    pub fn required_metadata(&self) -> &'static [&'static str] {
+        match self {
+            KedaTriggerType::AwsSqsQueue => &["queueURL", "awsRegion"],
+            KedaTriggerType::Kafka => &["bootstrapServers", "consumerGroup", "topic"],
+            KedaTriggerType::Cron => &["timezone", "start", "end", "desiredReplicas"],
+        }
+    }
+}
+
+/// Prometheus metric of the instances, served by the custom metrics API of the cluster.
+/// The instances are scaled to keep its average value at the target.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct HpaCustomMetric {
+    pub name: String,
+    /// Kubernetes quantity, i.e: `100` or `500m`
+    pub target_average_value: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug, Default)]
 pub enum PodAntiAffinity {
     #[default]
@@ -39,6 +302,24 @@ pub enum PodAntiAffinity {
     Required,
 }
 
+/// Topology domain the pods of a service avoid sharing. With a required anti-affinity on zones, the number of
+/// running instances cannot exceed the number of zones of the cluster.
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum PodAntiAffinityTopology {
+    #[default]
+    Hostname,
+    Zone,
+}
+
+/// IP families of the kubernetes services of an application, dual stack ones only apply on dual stack clusters
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, Debug, Default)]
+pub enum IpFamilyPolicy {
+    #[default]
+    SingleStack,
+    PreferDualStack,
+    RequireDualStack,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct QoveryIdentifier {
     long_id: Uuid,
@@ -85,6 +366,7 @@ pub enum Action {
     Pause,
     Delete,
     Restart,
+    TriggerNow,
 }
 
 impl Action {
@@ -94,6 +376,7 @@ impl Action {
             Action::Pause => service::Action::Pause,
             Action::Delete => service::Action::Delete,
             Action::Restart => service::Action::Restart,
+            Action::TriggerNow => service::Action::TriggerNow,
         }
     }
 }
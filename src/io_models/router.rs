@@ -1,7 +1,9 @@
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
+use crate::cloud_provider::models::CustomCertificate;
 use crate::cloud_provider::{CloudProvider, Kind as CPKind};
+use crate::io_models::application::Protocol;
 use crate::io_models::context::Context;
-use crate::io_models::Action;
+use crate::io_models::{Action, CustomMetadata};
 use crate::models;
 use crate::models::aws::AwsRouterExtraSettings;
 use crate::models::aws_ec2::AwsEc2RouterExtraSettings;
@@ -11,6 +13,8 @@ use crate::models::scaleway::ScwRouterExtraSettings;
 use crate::models::selfmanaged::SelfManagedRouterExtraSettings;
 use crate::models::types::{AWSEc2, SelfManaged, AWS, GCP, SCW};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use url::Url;
 use uuid::Uuid;
 
 fn default_generate_certificate() -> bool {
@@ -27,6 +31,16 @@ pub struct Router {
     pub public_port: u16,
     pub custom_domains: Vec<CustomDomain>,
     pub routes: Vec<Route>,
+    #[serde(default)]
+    pub l4_routes: Vec<L4Route>,
+    /// Annotations of the ingress of the router, on top of the ones set by the engine
+    #[serde(default)]
+    pub nginx_annotations: BTreeMap<String, String>,
+    /// Certificates of the authorities issuing the client certificates, when the routed service requires them
+    #[serde(default)]
+    pub client_ca_bundle_pem: Option<String>,
+    #[serde(default)]
+    pub custom_metadata: CustomMetadata,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -35,12 +49,81 @@ pub struct CustomDomain {
     pub target_domain: String,
     #[serde(default = "default_generate_certificate")]
     pub generate_certificate: bool,
+    /// Certificate of the domain provided by the user, cert-manager does not issue one for it
+    #[serde(default)]
+    pub certificate: Option<CustomDomainCertificate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct CustomDomainCertificate {
+    pub certificate_pem: String,
+    pub private_key_pem: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct Route {
     pub path: String,
     pub service_long_id: Uuid,
+    #[serde(default)]
+    pub backend_protocol: BackendProtocol,
+    /// Path the route path is replaced with before the requests reach the service
+    #[serde(default)]
+    pub rewrite_target: Option<String>,
+    /// URL the requests are permanently redirected to, without reaching the service
+    #[serde(default)]
+    pub permanent_redirect: Option<String>,
+    #[serde(default)]
+    pub trailing_slash: TrailingSlash,
+}
+
+/// Normalization of the trailing slash of the request paths, clients being permanently redirected to the normalized one
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingSlash {
+    #[default]
+    Keep,
+    Add,
+    Remove,
+}
+
+impl TrailingSlash {
+    /// Rewrite of nginx redirecting the paths to normalize, the ones of files, i.e: `/logo.png`, keep having none
+    pub fn rewrite_rule(&self) -> Option<&'static str> {
+        match self {
+            TrailingSlash::Keep => None,
+            TrailingSlash::Add => Some(r"rewrite ^([^.]*[^/])$ $1/ permanent;"),
+            TrailingSlash::Remove => Some(r"rewrite ^(.+)/$ $1 permanent;"),
+        }
+    }
+}
+
+/// Protocol spoken by the service behind a route, nginx proxies requests to it in HTTP/1.1 unless told otherwise
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendProtocol {
+    #[default]
+    Http,
+    Grpc,
+    /// HTTP/2 without TLS, proxied by the gRPC module of nginx, the only one speaking cleartext HTTP/2 to upstreams
+    H2c,
+}
+
+impl BackendProtocol {
+    pub fn is_http2(&self) -> bool {
+        matches!(self, BackendProtocol::Grpc | BackendProtocol::H2c)
+    }
+}
+
+/// TCP or UDP port of a service exposed as is, i.e: a game server or an MQTT broker
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct L4Route {
+    pub protocol: Protocol,
+    /// Port of the load balancer
+    pub port: u16,
+    /// Port of the service, the one of the load balancer if not set
+    #[serde(default)]
+    pub target_port: Option<u16>,
+    pub service_long_id: Uuid,
 }
 
 impl Router {
@@ -53,21 +136,92 @@ impl Router {
         let custom_domains = self
             .custom_domains
             .iter()
-            .map(|x| crate::cloud_provider::models::CustomDomain {
-                domain: x.domain.clone(),
-                target_domain: x.target_domain.clone(),
-                generate_certificate: x.generate_certificate,
+            .map(|x| {
+                let certificate = x.certificate.as_ref().map(|certificate| CustomCertificate {
+                    certificate_pem: certificate.certificate_pem.clone(),
+                    private_key_pem: certificate.private_key_pem.clone(),
+                });
+                if let Some(certificate) = &certificate {
+                    if certificate.private_key_pem.trim().is_empty() {
+                        return Err(RouterError::InvalidConfig(format!(
+                            "Certificate of custom domain {} has no private key",
+                            x.domain
+                        )));
+                    }
+                    certificate.expires_at().map_err(|err| {
+                        RouterError::InvalidConfig(format!(
+                            "Certificate of custom domain {} is invalid: {err}",
+                            x.domain
+                        ))
+                    })?;
+                }
+                Ok(crate::cloud_provider::models::CustomDomain {
+                    domain: x.domain.clone(),
+                    target_domain: x.target_domain.clone(),
+                    generate_certificate: x.generate_certificate,
+                    certificate,
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
         let routes = self
             .routes
             .iter()
-            .map(|x| crate::cloud_provider::models::Route {
-                path: x.path.clone(),
-                service_long_id: x.service_long_id,
+            .map(|x| {
+                if let Some(rewrite_target) = &x.rewrite_target {
+                    if !rewrite_target.starts_with('/') {
+                        return Err(RouterError::InvalidConfig(format!(
+                            "Rewrite target `{rewrite_target}` of route {} must be an absolute path",
+                            x.path
+                        )));
+                    }
+                }
+                if let Some(permanent_redirect) = &x.permanent_redirect {
+                    if x.rewrite_target.is_some() {
+                        return Err(RouterError::InvalidConfig(format!(
+                            "Route {} cannot both redirect and rewrite its requests",
+                            x.path
+                        )));
+                    }
+                    let is_http_url = Url::parse(permanent_redirect)
+                        .map(|url| url.scheme() == "http" || url.scheme() == "https")
+                        .unwrap_or(false);
+                    if !is_http_url {
+                        return Err(RouterError::InvalidConfig(format!(
+                            "Permanent redirect `{permanent_redirect}` of route {} must be an HTTP(S) URL",
+                            x.path
+                        )));
+                    }
+                }
+                Ok(crate::cloud_provider::models::Route {
+                    path: x.path.clone(),
+                    service_long_id: x.service_long_id,
+                    backend_protocol: x.backend_protocol,
+                    rewrite_target: x.rewrite_target.clone(),
+                    permanent_redirect: x.permanent_redirect.clone(),
+                    trailing_slash: x.trailing_slash,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let l4_routes = self
+            .l4_routes
+            .iter()
+            .map(|x| {
+                if !x.protocol.is_layer4() {
+                    return Err(RouterError::InvalidConfig(format!(
+                        "Route on port {} must use TCP or UDP, not {:?}",
+                        x.port, x.protocol
+                    )));
+                }
+                Ok(crate::cloud_provider::models::L4Route {
+                    protocol: x.protocol.clone(),
+                    port: x.port,
+                    target_port: x.target_port.unwrap_or(x.port),
+                    service_long_id: x.service_long_id,
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
         match cloud_provider.kind() {
             CPKind::Aws => {
@@ -84,8 +238,10 @@ impl Router {
                         self.default_domain.as_str(),
                         custom_domains,
                         routes,
+                        l4_routes,
                         AwsRouterExtraSettings {},
                         advanced_settings,
+                        self.custom_metadata.clone(),
                         |transmitter| context.get_event_details(transmitter),
                     )?))
                 } else {
@@ -98,8 +254,10 @@ impl Router {
                         self.default_domain.as_str(),
                         custom_domains,
                         routes,
+                        l4_routes,
                         AwsEc2RouterExtraSettings {},
                         advanced_settings,
+                        self.custom_metadata.clone(),
                         |transmitter| context.get_event_details(transmitter),
                     )?))
                 }
@@ -114,8 +272,10 @@ impl Router {
                     self.default_domain.as_str(),
                     custom_domains,
                     routes,
+                    l4_routes,
                     ScwRouterExtraSettings {},
                     advanced_settings,
+                    self.custom_metadata.clone(),
                     |transmitter| context.get_event_details(transmitter),
                 )?);
                 Ok(router)
@@ -129,8 +289,10 @@ impl Router {
                 self.default_domain.as_str(),
                 custom_domains,
                 routes,
+                l4_routes,
                 GcpRouterExtraSettings {},
                 advanced_settings,
+                self.custom_metadata.clone(),
                 |transmitter| context.get_event_details(transmitter),
             )?)),
             CPKind::SelfManaged => {
@@ -143,8 +305,10 @@ impl Router {
                     self.default_domain.as_str(),
                     custom_domains,
                     routes,
+                    l4_routes,
                     SelfManagedRouterExtraSettings {},
                     advanced_settings,
+                    self.custom_metadata.clone(),
                     |transmitter| context.get_event_details(transmitter),
                 )?);
                 Ok(router)
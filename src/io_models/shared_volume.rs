@@ -0,0 +1,32 @@
+use crate::utilities::to_short_id;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// ReadWriteMany volume of the environment, mountable at the same time by several of its services.
+/// It is backed by the shared storage of the cluster (i.e: EFS on EKS, an NFS server on Kapsule).
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SharedVolume {
+    pub long_id: Uuid,
+    pub name: String,
+    pub size_in_gib: u32,
+}
+
+impl SharedVolume {
+    /// Name of the persistent volume claim of the shared volume, the services mount it by this name
+    pub fn claim_name(&self) -> String {
+        shared_volume_claim_name(&self.long_id)
+    }
+}
+
+pub fn shared_volume_claim_name(shared_volume_long_id: &Uuid) -> String {
+    format!("shared-volume-{}", to_short_id(shared_volume_long_id))
+}
+
+/// Shared volume of the environment mounted in a service
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SharedVolumeMount {
+    pub shared_volume_id: Uuid,
+    pub mount_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
@@ -0,0 +1,92 @@
+use crate::build_platform::SshKey;
+use crate::cloud_provider::service::ServiceType;
+use crate::io_models::application::GitCredentials;
+use crate::io_models::context::Context;
+use crate::io_models::variable_utils::{default_environment_vars_with_info, VariableInfo};
+use crate::io_models::{fetch_git_token, ssh_keys_from_env_vars, Action};
+use crate::models;
+use crate::models::terraform_service::{TerraformServiceError, TerraformServiceTrait};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct TerraformService {
+    pub long_id: Uuid,
+    pub name: String,
+    pub kube_name: String,
+    pub action: Action,
+    pub git_url: Url,
+    pub git_credentials: Option<GitCredentials>,
+    pub commit_id: String,
+    /// Directory of the repository holding the root module to apply
+    pub root_path: PathBuf,
+    /// Input variables of the module
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// Outputs of the module exposed to the other services, key is the output name, value the environment variable
+    #[serde(default)]
+    pub exposed_outputs: BTreeMap<String, String>,
+    /// Credentials of the cloud account managed by the module, given to terraform as environment variables,
+    /// i.e: `AWS_ACCESS_KEY_ID`. The module never gets the credentials of the cluster.
+    #[serde(default)]
+    pub provider_credentials: BTreeMap<String, String>,
+    /// Key is a String, Value is a base64 encoded String
+    /// Use BTreeMap to get Hash trait which is not available on HashMap
+    #[serde(default = "default_environment_vars_with_info")]
+    pub environment_vars_with_infos: BTreeMap<String, VariableInfo>,
+}
+
+impl TerraformService {
+    pub fn to_terraform_service_domain(
+        self,
+        context: &Context,
+    ) -> Result<Box<dyn TerraformServiceTrait>, TerraformServiceError> {
+        // Get passphrase and public key if provided by the user
+        let ssh_keys: Vec<SshKey> = ssh_keys_from_env_vars(&self.environment_vars_with_infos);
+        let environment_variables_with_info: HashMap<String, VariableInfo> = self
+            .environment_vars_with_infos
+            .into_iter()
+            .map(|(k, mut v)| {
+                v.value = String::from_utf8_lossy(
+                    &base64::engine::general_purpose::STANDARD
+                        .decode(v.value)
+                        .unwrap_or_default(),
+                )
+                .to_string();
+                (k, v)
+            })
+            .collect();
+
+        let qovery_api = context.qovery_api.clone();
+        let service_id = self.long_id;
+        let source = models::terraform_service::TerraformModuleSource {
+            git_url: self.git_url,
+            get_credentials: if self.git_credentials.is_none() {
+                Box::new(|| Ok(None))
+            } else {
+                Box::new(move || fetch_git_token(&*qovery_api, ServiceType::Terraform, &service_id).map(Some))
+            },
+            commit_id: self.commit_id,
+            root_path: self.root_path,
+            ssh_keys,
+        };
+
+        Ok(Box::new(models::terraform_service::TerraformService::new(
+            context,
+            self.long_id,
+            self.name,
+            self.kube_name,
+            self.action.to_service_action(),
+            source,
+            self.variables,
+            self.exposed_outputs,
+            self.provider_credentials,
+            environment_variables_with_info,
+            |transmitter| context.get_event_details(transmitter),
+        )?))
+    }
+}
@@ -0,0 +1,495 @@
+use crate::cloud_provider::aws::janitor::AwsResourceScanner;
+use crate::cloud_provider::{CloudProvider, Kind};
+use crate::engine_task::qovery_api::QoveryApi;
+use crate::io_models::context::Context;
+use crate::io_models::engine_request::InfrastructureEngineRequest;
+use crate::utilities::to_short_id;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use thiserror::Error;
+use uuid::Uuid;
+
+const CLUSTER_ID_TAGS: [&str; 2] = ["ClusterId", "cluster_id"];
+const ENVIRONMENT_ID_TAGS: [&str; 1] = ["q_environment_id"];
+// set by kubernetes on the load balancers and volumes it creates, the cluster name being `qovery-<cluster id>`
+const KUBERNETES_CLUSTER_TAG_PREFIX: &str = "kubernetes.io/cluster/qovery-";
+const CREATION_DATE_TAGS: [&str; 2] = ["creationDate", "CreationDate"];
+const TTL_TAGS: [&str; 2] = ["ttl", "Ttl"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CloudResourceKind {
+    LoadBalancer,
+    Volume,
+    Snapshot,
+    NetworkInterface,
+    Bucket,
+}
+
+impl fmt::Display for CloudResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CloudResourceKind::LoadBalancer => write!(f, "load balancer"),
+            CloudResourceKind::Volume => write!(f, "volume"),
+            CloudResourceKind::Snapshot => write!(f, "snapshot"),
+            CloudResourceKind::NetworkInterface => write!(f, "network interface"),
+            CloudResourceKind::Bucket => write!(f, "bucket"),
+        }
+    }
+}
+
+/// Resource of the cloud account, with the tags the engine sets on what it creates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CloudResource {
+    pub kind: CloudResourceKind,
+    /// Identifier used to delete the resource, i.e: its ARN
+    pub id: String,
+    pub tags: HashMap<String, String>,
+    /// Creation date given by the cloud provider API, if any
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl CloudResource {
+    fn tag(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter()
+            .find_map(|key| self.tags.get(*key))
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn cluster_id(&self) -> Option<&str> {
+        self.tag(&CLUSTER_ID_TAGS).or_else(|| {
+            self.tags
+                .keys()
+                .find_map(|key| key.strip_prefix(KUBERNETES_CLUSTER_TAG_PREFIX))
+        })
+    }
+
+    pub fn environment_id(&self) -> Option<&str> {
+        self.tag(&ENVIRONMENT_ID_TAGS)
+    }
+
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.tag(&CREATION_DATE_TAGS)
+            .and_then(|creation_date| DateTime::parse_from_rfc3339(creation_date).ok())
+            .map(|creation_date| creation_date.with_timezone(&Utc))
+            .or(self.created_at)
+    }
+
+    /// Lifetime given to the resource at its creation, `0` meaning it has none
+    pub fn ttl(&self) -> Option<Duration> {
+        self.tag(&TTL_TAGS)
+            .and_then(|ttl| ttl.parse::<i64>().ok())
+            .filter(|ttl| *ttl > 0)
+            .map(Duration::seconds)
+    }
+}
+
+impl fmt::Display for CloudResource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.id)
+    }
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum JanitorError {
+    #[error("Cannot list the {kind}s of the cloud account: {raw_error_message}")]
+    CannotListResources {
+        kind: CloudResourceKind,
+        raw_error_message: String,
+    },
+    #[error("Cannot delete {resource}: {raw_error_message}")]
+    CannotDeleteResource {
+        resource: String,
+        raw_error_message: String,
+    },
+    #[error("No existing cluster given, all the resources of the cloud account would be considered orphans")]
+    NoExistingOwners,
+    #[error("Cannot get the existing clusters and environments of the organization: {raw_error_message}")]
+    CannotGetExistingOwners { raw_error_message: String },
+    #[error("Cannot build the cloud provider of the cluster")]
+    InvalidCloudProvider,
+    #[error("The janitor does not support the {0} cloud provider yet")]
+    UnsupportedCloudProvider(Kind),
+}
+
+/// Access to the resources of a cloud account the janitor cleans up
+pub trait CloudResourceScanner {
+    /// Resources the engine may have created, the ones still in use by the cloud provider being excluded,
+    /// i.e: attached volumes
+    fn list_resources(&self) -> Result<Vec<CloudResource>, JanitorError>;
+    fn delete_resource(&self, resource: &CloudResource) -> Result<(), JanitorError>;
+}
+
+/// Clusters and environments that still exist, the resources tagged with other ones being orphans
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ExistingOwners {
+    pub cluster_ids: Vec<Uuid>,
+    pub environment_ids: Vec<Uuid>,
+}
+
+impl ExistingOwners {
+    // resources are tagged with either the long or the short id of their owner
+    fn contains(ids: &[Uuid], tag_value: &str) -> bool {
+        ids.iter()
+            .any(|id| id.to_string() == tag_value || to_short_id(id) == tag_value)
+    }
+
+    pub fn has_cluster(&self, cluster_id: &str) -> bool {
+        Self::contains(&self.cluster_ids, cluster_id)
+    }
+
+    pub fn has_environment(&self, environment_id: &str) -> bool {
+        Self::contains(&self.environment_ids, environment_id)
+    }
+
+    /// Whether the owners are unknown, i.e: the control plane did not answer, rather than all deleted
+    pub fn is_empty(&self) -> bool {
+        self.cluster_ids.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JanitorPolicy {
+    /// Orphans created more recently are kept, as their owner may not be registered yet
+    pub orphan_ttl: Duration,
+    /// Resources to delete are only reported
+    pub dry_run: bool,
+}
+
+impl Default for JanitorPolicy {
+    fn default() -> Self {
+        JanitorPolicy {
+            orphan_ttl: Duration::hours(24),
+            dry_run: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExpirationReason {
+    ClusterDeleted(String),
+    EnvironmentDeleted(String),
+    TtlExpired(Duration),
+}
+
+impl fmt::Display for ExpirationReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpirationReason::ClusterDeleted(cluster_id) => write!(f, "cluster {cluster_id} does not exist anymore"),
+            ExpirationReason::EnvironmentDeleted(environment_id) => {
+                write!(f, "environment {environment_id} does not exist anymore")
+            }
+            ExpirationReason::TtlExpired(ttl) => write!(f, "ttl of {}s expired", ttl.num_seconds()),
+        }
+    }
+}
+
+/// Why the resource must be deleted, if it must. Resources without the owner tags of the engine are never deleted,
+/// whatever their ttl, as a `ttl` tag alone may have been set by anyone.
+pub fn expiration_reason(
+    resource: &CloudResource,
+    owners: &ExistingOwners,
+    policy: &JanitorPolicy,
+    now: DateTime<Utc>,
+) -> Option<ExpirationReason> {
+    let cluster_id = resource.cluster_id()?;
+    let created_at = resource.created_at();
+    if let (Some(ttl), Some(created_at)) = (resource.ttl(), created_at) {
+        if created_at + ttl < now {
+            return Some(ExpirationReason::TtlExpired(ttl));
+        }
+    }
+
+    // without creation date, the resource is old enough as its owner is gone
+    if created_at.is_some_and(|created_at| created_at + policy.orphan_ttl > now) {
+        return None;
+    }
+    if !owners.has_cluster(cluster_id) {
+        return Some(ExpirationReason::ClusterDeleted(cluster_id.to_string()));
+    }
+    match resource.environment_id() {
+        Some(environment_id) if !owners.has_environment(environment_id) => {
+            Some(ExpirationReason::EnvironmentDeleted(environment_id.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JanitorReport {
+    pub dry_run: bool,
+    pub scanned: usize,
+    /// Resources deleted, or that would be in dry run
+    pub expired: Vec<(CloudResource, ExpirationReason)>,
+    pub failed: Vec<(CloudResource, JanitorError)>,
+}
+
+impl fmt::Display for JanitorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let action = if self.dry_run { "would be deleted" } else { "deleted" };
+        writeln!(
+            f,
+            "{} resource(s) scanned, {} {}, {} failed",
+            self.scanned,
+            self.expired.len() - self.failed.len(),
+            action,
+            self.failed.len()
+        )?;
+        for (resource, reason) in &self.expired {
+            if !self
+                .failed
+                .iter()
+                .any(|(failed_resource, _)| failed_resource == resource)
+            {
+                writeln!(f, "- {resource} {action}: {reason}")?;
+            }
+        }
+        for (resource, err) in &self.failed {
+            writeln!(f, "- {resource} not deleted: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Deletes the resources of the account whose owner does not exist anymore, or whose ttl expired.
+/// A failing deletion does not stop the others, it is part of the report. Nothing is scanned without existing owners.
+pub fn run_janitor(
+    scanner: &dyn CloudResourceScanner,
+    owners: &ExistingOwners,
+    policy: &JanitorPolicy,
+) -> Result<JanitorReport, JanitorError> {
+    if owners.is_empty() {
+        return Err(JanitorError::NoExistingOwners);
+    }
+
+    let resources = scanner.list_resources()?;
+    let now = Utc::now();
+    let mut report = JanitorReport {
+        dry_run: policy.dry_run,
+        scanned: resources.len(),
+        ..Default::default()
+    };
+
+    for resource in resources {
+        let Some(reason) = expiration_reason(&resource, owners, policy, now) else {
+            continue;
+        };
+
+        if !policy.dry_run {
+            info!("Janitor deleting {}: {}", resource, reason);
+            if let Err(err) = scanner.delete_resource(&resource) {
+                report.failed.push((resource.clone(), err));
+            }
+        }
+        report.expired.push((resource, reason));
+    }
+
+    Ok(report)
+}
+
+/// Scanner of the cloud account of the cluster of a request
+pub fn cloud_resource_scanner(
+    cloud_provider: &dyn CloudProvider,
+) -> Result<Box<dyn CloudResourceScanner>, JanitorError> {
+    match cloud_provider.kind() {
+        Kind::Aws => AwsResourceScanner::new(cloud_provider)
+            .map(|scanner| Box::new(scanner) as Box<dyn CloudResourceScanner>)
+            .ok_or(JanitorError::InvalidCloudProvider),
+        kind => Err(JanitorError::UnsupportedCloudProvider(kind)),
+    }
+}
+
+/// Clusters and environments of the organization of a request, as known by the Qovery API
+pub fn existing_owners(
+    request: &InfrastructureEngineRequest,
+    qovery_api: &dyn QoveryApi,
+) -> Result<ExistingOwners, JanitorError> {
+    qovery_api
+        .existing_owners(&request.organization_long_id)
+        .map_err(|err| JanitorError::CannotGetExistingOwners {
+            raw_error_message: err.to_string(),
+        })
+}
+
+/// Runs the janitor on the cloud account of the cluster of a request
+pub fn run_cluster_janitor(
+    request: &InfrastructureEngineRequest,
+    context: &Context,
+    owners: &ExistingOwners,
+    policy: &JanitorPolicy,
+) -> Result<JanitorReport, JanitorError> {
+    let cloud_provider = request
+        .cloud_provider
+        .to_engine_cloud_provider(context.clone(), &request.kubernetes.region, request.kubernetes.kind.clone())
+        .ok_or(JanitorError::InvalidCloudProvider)?;
+    let scanner = cloud_resource_scanner(cloud_provider.as_ref())?;
+
+    run_janitor(scanner.as_ref(), owners, policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn resource(id: &str, tags: &[(&str, &str)], created_days_ago: Option<i64>) -> CloudResource {
+        CloudResource {
+            kind: CloudResourceKind::Volume,
+            id: id.to_string(),
+            tags: tags.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            created_at: created_days_ago.map(|days| Utc::now() - Duration::days(days)),
+        }
+    }
+
+    struct FakeScanner {
+        resources: Vec<CloudResource>,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    impl CloudResourceScanner for FakeScanner {
+        fn list_resources(&self) -> Result<Vec<CloudResource>, JanitorError> {
+            Ok(self.resources.clone())
+        }
+
+        fn delete_resource(&self, resource: &CloudResource) -> Result<(), JanitorError> {
+            self.deleted.lock().unwrap().push(resource.id.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_expiration_reason() {
+        let cluster_id = Uuid::new_v4();
+        let environment_id = Uuid::new_v4();
+        let owners = ExistingOwners {
+            cluster_ids: vec![cluster_id],
+            environment_ids: vec![environment_id],
+        };
+        let policy = JanitorPolicy::default();
+        let short_cluster_id = to_short_id(&cluster_id);
+        let now = Utc::now();
+
+        // not created by the engine
+        assert_eq!(
+            expiration_reason(&resource("vol-1", &[], Some(30)), &owners, &policy, now),
+            None
+        );
+        // owned by an existing cluster, by its short id or the kubernetes tag
+        assert_eq!(
+            expiration_reason(
+                &resource("vol-2", &[("ClusterId", &short_cluster_id)], Some(30)),
+                &owners,
+                &policy,
+                now
+            ),
+            None
+        );
+        let kubernetes_tag = format!("kubernetes.io/cluster/qovery-{short_cluster_id}");
+        assert_eq!(
+            expiration_reason(
+                &resource("vol-3", &[(&kubernetes_tag, "owned")], Some(30)),
+                &owners,
+                &policy,
+                now
+            ),
+            None
+        );
+        // orphans
+        assert_eq!(
+            expiration_reason(
+                &resource("vol-4", &[("ClusterId", "zdeleted")], Some(30)),
+                &owners,
+                &policy,
+                now
+            ),
+            Some(ExpirationReason::ClusterDeleted("zdeleted".to_string()))
+        );
+        assert_eq!(
+            expiration_reason(
+                &resource(
+                    "vol-5",
+                    &[
+                        ("cluster_id", &cluster_id.to_string()),
+                        ("q_environment_id", "zdeleted")
+                    ],
+                    None
+                ),
+                &owners,
+                &policy,
+                now
+            ),
+            Some(ExpirationReason::EnvironmentDeleted("zdeleted".to_string()))
+        );
+        // recent orphans are kept
+        assert_eq!(
+            expiration_reason(&resource("vol-6", &[("ClusterId", "zdeleted")], Some(0)), &owners, &policy, now),
+            None
+        );
+        // ttl expired, whatever its owner
+        let creation_date = (now - Duration::hours(2)).to_rfc3339();
+        assert_eq!(
+            expiration_reason(
+                &resource(
+                    "vol-7",
+                    &[
+                        ("ClusterId", &short_cluster_id),
+                        ("creationDate", &creation_date),
+                        ("ttl", "3600")
+                    ],
+                    None
+                ),
+                &owners,
+                &policy,
+                now
+            ),
+            Some(ExpirationReason::TtlExpired(Duration::hours(1)))
+        );
+        // ttl expired, but not created by the engine
+        assert_eq!(
+            expiration_reason(
+                &resource("vol-8", &[("creationDate", &creation_date), ("ttl", "3600")], None),
+                &owners,
+                &policy,
+                now
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_run_janitor_dry_run() {
+        let owners = ExistingOwners {
+            cluster_ids: vec![Uuid::new_v4()],
+            environment_ids: vec![],
+        };
+        let scanner = FakeScanner {
+            resources: vec![
+                resource("vol-1", &[("ClusterId", "zdeleted")], Some(30)),
+                resource("vol-2", &[], Some(30)),
+            ],
+            deleted: Mutex::new(vec![]),
+        };
+
+        let report = run_janitor(&scanner, &owners, &JanitorPolicy::default()).unwrap();
+        assert_eq!(report.scanned, 2);
+        assert_eq!(report.expired.len(), 1);
+        assert!(scanner.deleted.lock().unwrap().is_empty());
+
+        let policy = JanitorPolicy {
+            dry_run: false,
+            ..Default::default()
+        };
+        let report = run_janitor(&scanner, &owners, &policy).unwrap();
+        assert!(report.failed.is_empty());
+        assert_eq!(*scanner.deleted.lock().unwrap(), vec!["vol-1"]);
+
+        // unknown owners make every resource look like an orphan
+        assert_eq!(
+            run_janitor(&scanner, &ExistingOwners::default(), &policy),
+            Err(JanitorError::NoExistingOwners)
+        );
+        assert_eq!(scanner.deleted.lock().unwrap().len(), 1);
+    }
+}
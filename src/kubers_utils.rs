@@ -1,14 +1,52 @@
 use crate::cloud_provider::models::InvalidPVCStorage;
 use crate::errors::CommandError;
 use k8s_openapi::api::apps::v1::StatefulSet;
-use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use k8s_openapi::api::authentication::v1::{TokenRequest, TokenRequestSpec};
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod, Secret, ServiceAccount};
 use k8s_openapi::NamespaceResourceScope;
 use kube::api::{DeleteParams, ListParams, ObjectList, Patch, PatchParams, PostParams};
 use kube::{Api, Resource};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 
+/// Volume usage of a PVC, as reported by the kubelet
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PvcUsage {
+    pub pvc_name: String,
+    pub capacity_bytes: u64,
+    pub used_bytes: u64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct KubeletStatsSummary {
+    #[serde(default)]
+    pods: Vec<KubeletPodStats>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct KubeletPodStats {
+    #[serde(default)]
+    volume: Vec<KubeletVolumeStats>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct KubeletVolumeStats {
+    pvc_ref: Option<KubeletPvcRef>,
+    capacity_bytes: Option<u64>,
+    used_bytes: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+struct KubeletPvcRef {
+    name: String,
+    namespace: String,
+}
+
 pub enum KubeDeleteMode {
     Normal,
     Orphan,
@@ -124,6 +162,26 @@ where
     Ok(())
 }
 
+/// Create or update a resource with server side apply, so it can be applied again on each deployment
+pub async fn kube_apply_resource<K>(client: &kube::Client, namespace: &str, resource: &K) -> Result<(), CommandError>
+where
+    K: Clone + DeserializeOwned + Debug + Resource<Scope = NamespaceResourceScope> + Serialize,
+    <K as Resource>::DynamicType: Default,
+{
+    let obj_name = K::kind(&K::DynamicType::default()).to_string();
+    let name = resource.meta().name.clone().unwrap_or_default();
+    info!("Applying k8s {} {} in {}", obj_name, name, namespace);
+
+    let api: Api<K> = Api::namespaced(client.clone(), namespace);
+    let mut params = PatchParams::apply("qovery");
+    params.force = true;
+    api.patch(&name, &params, &Patch::Apply(resource))
+        .await
+        .map_err(|e| CommandError::new(format!("Unable to apply {obj_name} {name}."), Some(e.to_string()), None))?;
+
+    Ok(())
+}
+
 pub async fn kube_rollout_restart_statefulset(
     client: &kube::Client,
     namespace: &str,
@@ -143,3 +201,143 @@ pub async fn kube_rollout_restart_statefulset(
 
     Ok(())
 }
+
+/// Annotations of a running pod can be updated without restarting it
+pub async fn kube_annotate_pods_by_selector(
+    client: &kube::Client,
+    namespace: &str,
+    selector: &str,
+    annotation_key: &str,
+    annotation_value: &str,
+) -> Result<(), CommandError> {
+    let pods = kube_get_resources_by_selector::<Pod>(client, namespace, selector).await?;
+    let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let patch = serde_json::json!({ "metadata": { "annotations": { annotation_key: annotation_value } } });
+
+    for pod_name in pods.items.iter().filter_map(|pod| pod.metadata.name.as_deref()) {
+        info!("Annotating k8s Pod {} with {}={}", pod_name, annotation_key, annotation_value);
+        api.patch(pod_name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .map_err(|e| CommandError::new(format!("Unable to annotate pod {pod_name}."), Some(e.to_string()), None))?;
+    }
+
+    Ok(())
+}
+
+/// Merge labels and annotations into the metadata of a resource, keys with a `None` value are removed
+pub async fn kube_patch_metadata<K>(
+    client: &kube::Client,
+    namespace: &str,
+    name: &str,
+    labels: &BTreeMap<String, Option<String>>,
+    annotations: &BTreeMap<String, Option<String>>,
+) -> Result<(), CommandError>
+where
+    K: Clone + DeserializeOwned + Debug + Resource<Scope = NamespaceResourceScope>,
+    <K as Resource>::DynamicType: Default,
+{
+    let obj_name = K::kind(&K::DynamicType::default()).to_string();
+    info!("Patching metadata of k8s {} {} in {}", obj_name, name, namespace);
+
+    let api: Api<K> = Api::namespaced(client.clone(), namespace);
+    let patch = serde_json::json!({ "metadata": { "labels": labels, "annotations": annotations } });
+    api.patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                format!("Unable to patch metadata of {obj_name} {name}."),
+                Some(e.to_string()),
+                None,
+            )
+        })?;
+
+    Ok(())
+}
+
+/// Merge base64 encoded values into the data of a secret, keys missing from `data` are left untouched and the ones
+/// set to null are removed
+pub async fn kube_patch_secret_data<V: Serialize>(
+    client: &kube::Client,
+    namespace: &str,
+    name: &str,
+    data: &BTreeMap<String, V>,
+) -> Result<(), CommandError> {
+    info!("Patching data of k8s Secret {} in {}", name, namespace);
+
+    let api: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let patch = serde_json::json!({ "data": data });
+    api.patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await
+        .map_err(|e| CommandError::new(format!("Unable to patch data of secret {name}."), Some(e.to_string()), None))?;
+
+    Ok(())
+}
+
+/// Short lived token of a service account, nothing is stored in the cluster so it cannot be used once expired
+pub async fn kube_create_service_account_token(
+    client: &kube::Client,
+    namespace: &str,
+    service_account_name: &str,
+    expiration_in_seconds: i64,
+) -> Result<String, CommandError> {
+    info!("Creating token of k8s ServiceAccount {} in {}", service_account_name, namespace);
+
+    let api: Api<ServiceAccount> = Api::namespaced(client.clone(), namespace);
+    let token_request = TokenRequest {
+        spec: TokenRequestSpec {
+            expiration_seconds: Some(expiration_in_seconds),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let token_request = api
+        .create_token_request(service_account_name, &PostParams::default(), &token_request)
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                format!("Unable to create token of service account {service_account_name}."),
+                Some(e.to_string()),
+                None,
+            )
+        })?;
+
+    token_request.status.map(|status| status.token).ok_or_else(|| {
+        CommandError::new_from_safe_message(format!("No token returned for service account {service_account_name}."))
+    })
+}
+
+/// Volume usage is not part of the PVC status, it is only exposed by the kubelet stats summary of each node
+pub async fn kube_get_pvcs_usage(
+    client: &kube::Client,
+    namespace: &str,
+    node_name: &str,
+) -> Result<Vec<PvcUsage>, CommandError> {
+    info!("Getting PVCs usage from k8s node {}", node_name);
+
+    let request = k8s_openapi::http::Request::get(format!("/api/v1/nodes/{node_name}/proxy/stats/summary"))
+        .body(vec![])
+        .map_err(|e| CommandError::new_from_safe_message(format!("Invalid kubelet stats request: {e}")))?;
+    let summary: KubeletStatsSummary = client.request(request).await.map_err(|e| {
+        CommandError::new(
+            format!("Unable to get kubelet stats summary of node {node_name}"),
+            Some(e.to_string()),
+            None,
+        )
+    })?;
+
+    Ok(summary
+        .pods
+        .into_iter()
+        .flat_map(|pod| pod.volume)
+        .filter_map(|volume| match (volume.pvc_ref, volume.capacity_bytes, volume.used_bytes) {
+            (Some(pvc_ref), Some(capacity_bytes), Some(used_bytes)) if pvc_ref.namespace == namespace => {
+                Some(PvcUsage {
+                    pvc_name: pvc_ref.name,
+                    capacity_bytes,
+                    used_bytes,
+                })
+            }
+            _ => None,
+        })
+        .collect())
+}
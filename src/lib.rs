@@ -9,25 +9,36 @@ extern crate trust_dns_resolver;
 
 pub mod build_platform;
 pub mod cloud_provider;
+pub mod cluster_state_migrations;
 pub mod cmd;
 pub mod constants;
 pub mod container_registry;
 mod deletion_utilities;
+pub mod dependency_graph;
 pub mod deployment_action;
+pub mod deployment_freeze;
+pub mod deployment_hook;
 pub mod deployment_report;
 pub mod dns_provider;
+pub mod encryption;
 pub mod engine;
 pub mod engine_task;
 pub mod errors;
 pub mod events;
 pub mod fs;
 pub mod git;
+#[cfg(feature = "grpc-server")]
+pub mod grpc_server;
+pub mod heartbeat;
 pub mod io_models;
+pub mod janitor;
 pub mod kubers_utils;
+pub mod lib_archive;
 pub mod logger;
 pub mod metrics_registry;
 pub mod models;
 pub mod msg_publisher;
+mod naming;
 pub mod object_storage;
 pub mod runtime;
 mod secret_manager;
@@ -38,3 +49,4 @@ pub mod tera_utils;
 pub mod transaction;
 mod unit_conversion;
 pub mod utilities;
+pub mod workspace_quota;
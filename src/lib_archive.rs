@@ -0,0 +1,253 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ring::digest::{Context as DigestContext, SHA256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use url::Url;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::runtime::block_on;
+
+/// Written at the root of an extracted lib directory, holding the digest of each of its files
+const LIB_MANIFEST_FILE_NAME: &str = ".qovery-lib-manifest.json";
+const LIB_CACHE_DIR_NAME: &str = "lib-cache";
+
+/// Archive of a lib directory, named after the sha256 digest of its content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LibArchive {
+    pub digest: String,
+    pub path: PathBuf,
+}
+
+impl LibArchive {
+    pub fn file_name(digest: &str) -> String {
+        format!("lib-{digest}.tar.gz")
+    }
+}
+
+fn sha256_hex(reader: &mut impl Read) -> Result<String, Error> {
+    let mut context = DigestContext::new(&SHA256);
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+
+    Ok(context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+fn file_sha256_hex(path: &Path) -> Result<String, Error> {
+    sha256_hex(&mut BufReader::new(File::open(path)?))
+}
+
+/// Digest of every file of the directory, by path relative to it
+fn directory_manifest(dir: &Path) -> Result<BTreeMap<String, String>, Error> {
+    let mut manifest = BTreeMap::new();
+    for entry in WalkDir::new(dir).follow_links(true) {
+        let entry = entry.map_err(|err| Error::new(ErrorKind::Other, err))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(dir)
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        if relative_path == Path::new(LIB_MANIFEST_FILE_NAME) {
+            continue;
+        }
+
+        manifest.insert(relative_path.to_string_lossy().to_string(), file_sha256_hex(entry.path())?);
+    }
+
+    Ok(manifest)
+}
+
+/// Packages the lib directory of a release. Entries are sorted and stripped of their ownership and dates,
+/// so the same content always gives the same archive, hence the same digest.
+pub fn package_lib_directory(lib_root_dir: &Path, output_dir: &Path) -> Result<LibArchive, Error> {
+    fs::create_dir_all(output_dir)?;
+    let tmp_archive_path = output_dir.join(format!("lib-{}.tar.gz.tmp", Uuid::new_v4()));
+
+    let mut tar = tar::Builder::new(GzEncoder::new(File::create(&tmp_archive_path)?, Compression::best()));
+    for entry in WalkDir::new(lib_root_dir).follow_links(true).sort_by_file_name() {
+        let entry = entry.map_err(|err| Error::new(ErrorKind::Other, err))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(lib_root_dir)
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        let metadata = entry.metadata().map_err(|err| Error::new(ErrorKind::Other, err))?;
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata_in_mode(&metadata, tar::HeaderMode::Deterministic);
+        tar.append_data(&mut header, relative_path, File::open(entry.path())?)?;
+    }
+    tar.into_inner()?.finish()?;
+
+    let digest = file_sha256_hex(&tmp_archive_path)?;
+    let path = output_dir.join(LibArchive::file_name(&digest));
+    fs::rename(&tmp_archive_path, &path)?;
+
+    Ok(LibArchive { digest, path })
+}
+
+/// Whether the files of an extracted lib directory are still the ones of its archive
+pub fn verify_lib_directory(lib_root_dir: &Path) -> Result<(), Error> {
+    let expected_manifest: BTreeMap<String, String> =
+        serde_json::from_reader(File::open(lib_root_dir.join(LIB_MANIFEST_FILE_NAME))?)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+    let manifest = directory_manifest(lib_root_dir)?;
+    if let Some(path) = expected_manifest
+        .iter()
+        .filter(|(path, digest)| manifest.get(*path) != Some(*digest))
+        .map(|(path, _)| path)
+        .chain(manifest.keys().filter(|path| !expected_manifest.contains_key(*path)))
+        .next()
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("lib file `{path}` differs from the one of its archive"),
+        ));
+    }
+
+    Ok(())
+}
+
+fn download_lib_archive(archive_url: &Url, archive_path: &Path) -> Result<(), Error> {
+    let content = block_on(async {
+        reqwest::get(archive_url.clone())
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await
+    })
+    .map_err(|err| Error::new(ErrorKind::Other, err))?;
+
+    fs::write(archive_path, content)
+}
+
+/// Where the lib directories fetched by the workers of a workspace are extracted
+pub fn lib_cache_dir(workspace_root_dir: &str) -> PathBuf {
+    Path::new(workspace_root_dir).join(LIB_CACHE_DIR_NAME)
+}
+
+/// Lib directory of the archive with the given digest, downloaded from `archive_base_url` and extracted into
+/// `cache_dir` unless a previous extraction is still intact. The archive digest is checked before it is extracted,
+/// and the cached files are checked before being used to render templates.
+pub fn fetch_lib_directory(archive_base_url: &Url, digest: &str, cache_dir: &Path) -> Result<PathBuf, Error> {
+    let lib_root_dir = cache_dir.join(digest);
+    if lib_root_dir.exists() {
+        match verify_lib_directory(&lib_root_dir) {
+            Ok(()) => return Ok(lib_root_dir),
+            Err(err) => {
+                warn!(
+                    "Cached lib directory {:?} is corrupted, fetching it again: {}",
+                    lib_root_dir, err
+                );
+                fs::remove_dir_all(&lib_root_dir)?;
+            }
+        }
+    }
+
+    fs::create_dir_all(cache_dir)?;
+    let archive_url = archive_base_url
+        .join(&LibArchive::file_name(digest))
+        .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+    let tmp_id = Uuid::new_v4();
+    let archive_path = cache_dir.join(format!("{}.{}", LibArchive::file_name(digest), tmp_id));
+    let tmp_lib_root_dir = cache_dir.join(format!("{digest}.{tmp_id}"));
+
+    let extracted = download_lib_archive(&archive_url, &archive_path).and_then(|_| {
+        let archive_digest = file_sha256_hex(&archive_path)?;
+        if archive_digest != digest {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("lib archive {archive_url} has digest {archive_digest}, expected {digest}"),
+            ));
+        }
+
+        tar::Archive::new(GzDecoder::new(File::open(&archive_path)?)).unpack(&tmp_lib_root_dir)?;
+        let manifest = directory_manifest(&tmp_lib_root_dir)?;
+        fs::write(
+            tmp_lib_root_dir.join(LIB_MANIFEST_FILE_NAME),
+            serde_json::to_vec(&manifest).map_err(|err| Error::new(ErrorKind::Other, err))?,
+        )
+    });
+    let _ = fs::remove_file(&archive_path);
+    if let Err(err) = extracted {
+        let _ = fs::remove_dir_all(&tmp_lib_root_dir);
+        return Err(err);
+    }
+
+    // another worker sharing the cache may have extracted the same archive meanwhile
+    if fs::rename(&tmp_lib_root_dir, &lib_root_dir).is_err() {
+        fs::remove_dir_all(&tmp_lib_root_dir)?;
+        verify_lib_directory(&lib_root_dir)?;
+    }
+
+    Ok(lib_root_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lib_directory() -> TempDir {
+        let lib_root_dir = TempDir::new().unwrap();
+        fs::create_dir_all(lib_root_dir.path().join("aws/bootstrap")).unwrap();
+        fs::write(lib_root_dir.path().join("aws/bootstrap/main.j2.tf"), "resource {}").unwrap();
+        fs::write(lib_root_dir.path().join("helm-freeze.yaml"), "charts: []").unwrap();
+        lib_root_dir
+    }
+
+    #[test]
+    fn test_package_lib_directory_is_content_addressed() {
+        let lib_root_dir = lib_directory();
+        let output_dir = TempDir::new().unwrap();
+
+        let archive = package_lib_directory(lib_root_dir.path(), output_dir.path()).unwrap();
+        assert_eq!(archive.path, output_dir.path().join(LibArchive::file_name(&archive.digest)));
+        assert_eq!(file_sha256_hex(&archive.path).unwrap(), archive.digest);
+
+        // same content, same archive
+        let other_archive = package_lib_directory(lib_directory().path(), output_dir.path()).unwrap();
+        assert_eq!(other_archive.digest, archive.digest);
+
+        fs::write(lib_root_dir.path().join("helm-freeze.yaml"), "charts: [loki]").unwrap();
+        let updated_archive = package_lib_directory(lib_root_dir.path(), output_dir.path()).unwrap();
+        assert_ne!(updated_archive.digest, archive.digest);
+    }
+
+    #[test]
+    fn test_verify_lib_directory() {
+        let lib_root_dir = lib_directory();
+        let manifest = directory_manifest(lib_root_dir.path()).unwrap();
+        fs::write(
+            lib_root_dir.path().join(LIB_MANIFEST_FILE_NAME),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        assert!(verify_lib_directory(lib_root_dir.path()).is_ok());
+
+        fs::write(lib_root_dir.path().join("aws/bootstrap/main.j2.tf"), "resource { tampered }").unwrap();
+        assert!(verify_lib_directory(lib_root_dir.path()).is_err());
+    }
+}
@@ -1,12 +1,17 @@
 use crate::build_platform::Build;
 use crate::cloud_provider::models::{
-    EnvironmentVariable, InvalidPVCStorage, InvalidStatefulsetStorage, MountedFile, Storage,
+    EnvironmentVariable, InvalidPVCStorage, InvalidStatefulsetStorage, KubernetesCpuResourceUnit,
+    KubernetesMemoryResourceUnit, MountedFile, Storage,
 };
 use crate::cloud_provider::service::{get_service_statefulset_name_and_volumes, Action, Service, ServiceType};
 use crate::deployment_action::DeploymentAction;
 use crate::events::{EventDetails, Stage, Transmitter};
-use crate::io_models::application::{ApplicationAdvancedSettings, Port};
+use crate::io_models::application::{
+    ApplicationAdvancedSettings, ApplicationInitContainer, ApplicationMigrations, Port, ReadinessGates,
+};
 use crate::io_models::context::Context;
+use crate::io_models::shared_volume::SharedVolumeMount;
+use crate::io_models::{CustomMetadata, LifecycleHooks, SmokeTest};
 use std::collections::BTreeSet;
 
 use crate::cloud_provider::DeploymentTarget;
@@ -15,11 +20,14 @@ use crate::errors::EngineError;
 use crate::io_models::application::Protocol::{TCP, UDP};
 use crate::kubers_utils::kube_get_resources_by_selector;
 use crate::models::container::{
-    to_public_l4_ports, ClusterTeraContext, ContainerTeraContext, RegistryTeraContext, ServiceTeraContext,
+    shared_volume_mounts_tera_context, to_public_l4_ports, ClusterTeraContext, ContainerTeraContext,
+    InitContainerTeraContext, RegistryTeraContext, ServiceTeraContext,
 };
 use crate::models::probe::Probe;
+use crate::models::readiness_gates::validate_readiness_gates;
 use crate::models::types::{CloudProvider, ToTeraContext};
 use crate::models::utils;
+use crate::naming;
 use crate::runtime::block_on;
 use crate::unit_conversion::extract_volume_size;
 use crate::utilities::to_short_id;
@@ -46,20 +54,31 @@ pub struct Application<T: CloudProvider> {
     pub(super) kube_name: String,
     pub(super) public_domain: String,
     pub(super) ports: Vec<Port>,
-    pub(super) total_cpus: String,
-    pub(super) cpu_burst: String,
-    pub(super) total_ram_in_mib: u32,
+    pub(super) cpu_request: KubernetesCpuResourceUnit,
+    pub(super) cpu_limit: KubernetesCpuResourceUnit,
+    pub(super) ram_request: KubernetesMemoryResourceUnit,
+    pub(super) ram_limit: KubernetesMemoryResourceUnit,
+    pub(super) gpu: u32,
     pub(super) min_instances: u32,
     pub(super) max_instances: u32,
     pub(super) build: Build,
     pub(super) command_args: Vec<String>,
     pub(super) entrypoint: Option<String>,
     pub(super) storage: Vec<Storage<T::StorageTypes>>,
+    pub(super) stateful: bool,
     pub(super) environment_variables: Vec<EnvironmentVariable>,
     pub(super) mounted_files: BTreeSet<MountedFile>,
     pub(super) readiness_probe: Option<Probe>,
     pub(super) liveness_probe: Option<Probe>,
+    pub(super) startup_probe: Option<Probe>,
     pub(super) advanced_settings: ApplicationAdvancedSettings,
+    pub(super) migrations: Option<ApplicationMigrations>,
+    pub(super) init_containers: Vec<ApplicationInitContainer>,
+    pub(super) readiness_gates: Option<ReadinessGates>,
+    pub(super) custom_metadata: CustomMetadata,
+    pub(super) lifecycle_hooks: LifecycleHooks,
+    pub(super) smoke_test: Option<SmokeTest>,
+    pub(super) shared_volume_mounts: Vec<SharedVolumeMount>,
     pub(super) _extra_settings: T::AppExtraSettings,
     pub(super) workspace_directory: PathBuf,
     pub(super) lib_root_directory: String,
@@ -75,25 +94,112 @@ impl<T: CloudProvider> Application<T> {
         kube_name: String,
         public_domain: String,
         ports: Vec<Port>,
-        total_cpus: String,
-        cpu_burst: String,
-        total_ram_in_mib: u32,
+        cpu_request: KubernetesCpuResourceUnit,
+        cpu_limit: KubernetesCpuResourceUnit,
+        ram_request: KubernetesMemoryResourceUnit,
+        ram_limit: KubernetesMemoryResourceUnit,
+        gpu: u32,
         min_instances: u32,
         max_instances: u32,
         build: Build,
         command_args: Vec<String>,
         entrypoint: Option<String>,
         storage: Vec<Storage<T::StorageTypes>>,
+        stateful: bool,
         environment_variables: Vec<EnvironmentVariable>,
         mounted_files: BTreeSet<MountedFile>,
         readiness_probe: Option<Probe>,
         liveness_probe: Option<Probe>,
+        startup_probe: Option<Probe>,
         advanced_settings: ApplicationAdvancedSettings,
+        migrations: Option<ApplicationMigrations>,
+        init_containers: Vec<ApplicationInitContainer>,
+        readiness_gates: Option<ReadinessGates>,
+        custom_metadata: CustomMetadata,
+        lifecycle_hooks: LifecycleHooks,
+        smoke_test: Option<SmokeTest>,
+        shared_volume_mounts: Vec<SharedVolumeMount>,
         extra_settings: T::AppExtraSettings,
         mk_event_details: impl Fn(Transmitter) -> EventDetails,
     ) -> Result<Self, ApplicationError> {
         // TODO: Check that the information provided are coherent
 
+        utils::validate_config_reload_settings(
+            advanced_settings.deployment_config_reload_strategy,
+            &advanced_settings.deployment_config_reload_mount_path,
+            &advanced_settings.deployment_config_reload_webhook_url,
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_topology_spread_settings(
+            advanced_settings.deployment_topology_spread_key,
+            advanced_settings.deployment_topology_spread_max_skew,
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_tolerations(&advanced_settings.deployment_tolerations)
+            .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_pod_disruption_budget_settings(
+            advanced_settings.pdb_min_available_percent,
+            advanced_settings.pdb_max_unavailable_percent,
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_resources(
+            &cpu_request,
+            &cpu_limit,
+            &ram_request,
+            &ram_limit,
+            advanced_settings.resources_limit_max_ratio,
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+
+        if let Some(migrations) = &migrations {
+            if migrations.command.is_empty() {
+                return Err(ApplicationError::InvalidConfig(
+                    "Migrations command cannot be empty".to_string(),
+                ));
+            }
+            if migrations.timeout_in_seconds == 0 {
+                return Err(ApplicationError::InvalidConfig(
+                    "Migrations timeout must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        utils::validate_init_containers(&init_containers, &kube_name).map_err(ApplicationError::InvalidConfig)?;
+
+        if let Some(readiness_gates) = &readiness_gates {
+            validate_readiness_gates(readiness_gates).map_err(ApplicationError::InvalidConfig)?;
+        }
+        utils::validate_custom_metadata(&custom_metadata).map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_lifecycle_hooks(&lifecycle_hooks).map_err(ApplicationError::InvalidConfig)?;
+        if let Some(smoke_test) = &smoke_test {
+            utils::validate_smoke_test(smoke_test, ports.iter().map(|port| port.port))
+                .map_err(ApplicationError::InvalidConfig)?;
+        }
+        utils::validate_probes(readiness_probe.as_ref(), liveness_probe.as_ref(), startup_probe.as_ref())
+            .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_storages(&storage).map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_shared_volume_mounts(
+            &shared_volume_mounts,
+            storage.iter().map(|storage| storage.mount_point.as_str()),
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_hpa_metrics(
+            advanced_settings.hpa_memory_average_utilization_percent,
+            &advanced_settings.hpa_custom_metrics,
+            &advanced_settings.hpa_keda_triggers,
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_keda_triggers(
+            &advanced_settings.hpa_keda_triggers,
+            environment_variables.iter().map(|env_var| env_var.key.as_str()),
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+        utils::validate_external_secrets(
+            &advanced_settings.deployment_external_secrets,
+            environment_variables.iter().map(|env_var| env_var.key.as_str()),
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
+
         let workspace_directory = crate::fs::workspace_directory(
             context.workspace_root_dir(),
             context.execution_id(),
@@ -113,20 +219,31 @@ impl<T: CloudProvider> Application<T> {
             kube_name,
             public_domain,
             ports,
-            total_cpus,
-            cpu_burst,
-            total_ram_in_mib,
+            cpu_request,
+            cpu_limit,
+            ram_request,
+            ram_limit,
+            gpu,
             min_instances,
             max_instances,
             build,
             command_args,
             entrypoint,
             storage,
+            stateful,
             environment_variables,
             mounted_files,
             readiness_probe,
             liveness_probe,
+            startup_probe,
             advanced_settings,
+            migrations,
+            init_containers,
+            readiness_gates,
+            custom_metadata,
+            lifecycle_hooks,
+            smoke_test,
+            shared_volume_mounts,
             _extra_settings: extra_settings,
             workspace_directory,
             lib_root_directory: context.lib_root_dir().to_string(),
@@ -134,7 +251,7 @@ impl<T: CloudProvider> Application<T> {
     }
 
     pub fn helm_release_name(&self) -> String {
-        crate::string::cut(format!("application-{}-{}", self.id(), self.id()), 50)
+        naming::helm_release_name("application", format!("{}-{}", self.id(), self.id()))
     }
 
     pub fn helm_chart_dir(&self) -> String {
@@ -154,6 +271,20 @@ impl<T: CloudProvider> Application<T> {
         );
         let mut advanced_settings = self.advanced_settings.clone();
         advanced_settings.deployment_affinity_node_required = deployment_affinity_node_required;
+        advanced_settings.deployment_topology_spread_key =
+            utils::resolve_topology_spread_key(advanced_settings.deployment_topology_spread_key, kubernetes.zones());
+        advanced_settings.deployment_antiaffinity_pod_topology = utils::resolve_antiaffinity_pod_topology(
+            advanced_settings.deployment_antiaffinity_pod_topology,
+            kubernetes.zones(),
+        );
+        advanced_settings.network_ip_family_policy = utils::resolve_ip_family_policy(
+            advanced_settings.network_ip_family_policy,
+            kubernetes.advanced_settings().network_enable_dual_stack,
+        );
+        advanced_settings.security_service_account_name = utils::resolve_service_account_name(
+            &advanced_settings.security_service_account_name,
+            environment.service_account_name(),
+        );
         let registry_info = target.container_registry.registry_info();
         let ctx = ContainerTeraContext {
             organization_long_id: environment.organization_long_id,
@@ -173,10 +304,24 @@ impl<T: CloudProvider> Application<T> {
                 version: self.version(),
                 command_args: self.command_args.clone(),
                 entrypoint: self.entrypoint.clone(),
-                cpu_request_in_mili: self.total_cpus.clone(),
-                cpu_limit_in_mili: self.total_cpus.clone(),
-                ram_request_in_mib: format!("{}Mi", self.total_ram_in_mib),
-                ram_limit_in_mib: format!("{}Mi", self.total_ram_in_mib),
+                init_containers: self
+                    .init_containers
+                    .iter()
+                    .map(|init_container| InitContainerTeraContext {
+                        name: init_container.name.clone(),
+                        image_full: init_container
+                            .image
+                            .clone()
+                            .unwrap_or_else(|| self.build.image.full_image_name_with_tag()),
+                        command: init_container.command.clone(),
+                        environment_vars: init_container.environment_vars.clone(),
+                    })
+                    .collect(),
+                cpu_request_in_mili: self.cpu_request.to_string(),
+                cpu_limit_in_mili: self.cpu_limit.to_string(),
+                ram_request_in_mib: self.ram_request.to_string(),
+                ram_limit_in_mib: self.ram_limit.to_string(),
+                gpu: self.gpu,
                 min_instances: self.min_instances,
                 max_instances: self.max_instances,
                 public_domain: self.public_domain.clone(),
@@ -193,8 +338,11 @@ impl<T: CloudProvider> Application<T> {
                 },
                 default_port: self.ports.iter().find_or_first(|p| p.is_default).cloned(),
                 storages: vec![],
+                is_stateful: self.is_stateful(),
+                shared_volumes: shared_volume_mounts_tera_context(&self.shared_volume_mounts),
                 readiness_probe: self.readiness_probe.clone(),
                 liveness_probe: self.liveness_probe.clone(),
+                startup_probe: self.startup_probe.clone(),
                 advanced_settings: advanced_settings.to_container_advanced_settings(),
                 legacy_deployment_matchlabels: true,
                 legacy_volumeclaim_template: true,
@@ -204,7 +352,7 @@ impl<T: CloudProvider> Application<T> {
                 .registry_docker_json_config
                 .as_ref()
                 .map(|docker_json| RegistryTeraContext {
-                    secret_name: format!("{}-registry", self.kube_name()),
+                    secret_name: naming::secret_name(self.kube_name(), "registry"),
                     docker_json_config: Some(docker_json.to_string()),
                 }),
             environment_variables: self.environment_variables.clone(),
@@ -216,8 +364,12 @@ impl<T: CloudProvider> Application<T> {
         ctx
     }
 
+    pub fn config_checksum(&self) -> String {
+        utils::config_checksum(&self.mounted_files)
+    }
+
     pub fn is_stateful(&self) -> bool {
-        !self.storage.is_empty()
+        self.stateful || !self.storage.is_empty()
     }
 
     pub fn service_type(&self) -> ServiceType {
@@ -240,16 +392,24 @@ impl<T: CloudProvider> Application<T> {
         &self.action
     }
 
-    pub fn total_cpus(&self) -> String {
-        self.total_cpus.to_string()
+    pub fn cpu_request(&self) -> &KubernetesCpuResourceUnit {
+        &self.cpu_request
+    }
+
+    pub fn cpu_limit(&self) -> &KubernetesCpuResourceUnit {
+        &self.cpu_limit
+    }
+
+    pub fn ram_request(&self) -> &KubernetesMemoryResourceUnit {
+        &self.ram_request
     }
 
-    pub fn cpu_burst(&self) -> String {
-        self.cpu_burst.to_string()
+    pub fn ram_limit(&self) -> &KubernetesMemoryResourceUnit {
+        &self.ram_limit
     }
 
-    pub fn total_ram_in_mib(&self) -> u32 {
-        self.total_ram_in_mib
+    pub fn gpu(&self) -> u32 {
+        self.gpu
     }
 
     pub fn min_instances(&self) -> u32 {
@@ -372,6 +532,12 @@ pub trait ApplicationService: Service + DeploymentAction + ToTeraContext + Send
     fn get_build_mut(&mut self) -> &mut Build;
     fn public_ports(&self) -> Vec<&Port>;
     fn advanced_settings(&self) -> &ApplicationAdvancedSettings;
+    fn migrations(&self) -> Option<&ApplicationMigrations>;
+    fn init_containers(&self) -> &[ApplicationInitContainer];
+    fn readiness_gates(&self) -> Option<&ReadinessGates>;
+    fn custom_metadata(&self) -> &CustomMetadata;
+    fn lifecycle_hooks(&self) -> &LifecycleHooks;
+    fn smoke_test(&self) -> Option<&SmokeTest>;
     fn startup_timeout(&self) -> Duration;
     fn as_deployment_action(&self) -> &dyn DeploymentAction;
 }
@@ -396,6 +562,30 @@ where
         &self.advanced_settings
     }
 
+    fn migrations(&self) -> Option<&ApplicationMigrations> {
+        self.migrations.as_ref()
+    }
+
+    fn init_containers(&self) -> &[ApplicationInitContainer] {
+        &self.init_containers
+    }
+
+    fn readiness_gates(&self) -> Option<&ReadinessGates> {
+        self.readiness_gates.as_ref()
+    }
+
+    fn custom_metadata(&self) -> &CustomMetadata {
+        &self.custom_metadata
+    }
+
+    fn lifecycle_hooks(&self) -> &LifecycleHooks {
+        &self.lifecycle_hooks
+    }
+
+    fn smoke_test(&self) -> Option<&SmokeTest> {
+        self.smoke_test.as_ref()
+    }
+
     fn startup_timeout(&self) -> Duration {
         let readiness_probe_timeout = if let Some(p) = &self.readiness_probe {
             p.initial_delay_seconds + ((p.timeout_seconds + p.period_seconds) * p.failure_threshold)
@@ -409,7 +599,12 @@ where
             60 * 5
         };
 
-        let probe_timeout = std::cmp::max(readiness_probe_timeout, liveness_probe_timeout);
+        // readiness and liveness probes only start once the startup probe succeeded
+        let startup_probe_timeout = self.startup_probe.as_ref().map_or(0, |p| {
+            p.initial_delay_seconds + ((p.timeout_seconds + p.period_seconds) * p.failure_threshold)
+        });
+
+        let probe_timeout = startup_probe_timeout + std::cmp::max(readiness_probe_timeout, liveness_probe_timeout);
         let startup_timeout = std::cmp::max(probe_timeout /* * 10 rolling restart percent */, 60 * 10);
         Duration::from_secs(startup_timeout as u64)
     }
@@ -425,7 +620,8 @@ pub fn get_application_with_invalid_storage_size<T: CloudProvider>(
     namespace: &str,
     event_details: &EventDetails,
 ) -> Result<Option<InvalidStatefulsetStorage>, Box<EngineError>> {
-    match !application.is_stateful() {
+    // Statefulsets without storage have no volume to resize
+    match application.storage.is_empty() {
         true => Ok(None),
         false => {
             let selector = Application::kube_label_selector(application);
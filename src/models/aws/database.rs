@@ -14,6 +14,7 @@ use crate::models::aws::database_utils::{
 use crate::models::database::{Container, Database, DatabaseType, Managed, MongoDB, MySQL, PostgresSQL, Redis};
 
 use crate::io_models::database::DatabaseOptions;
+use crate::models::database_utils::to_managed_database_parameters;
 use crate::models::types::{ToTeraContext, AWS};
 use crate::unit_conversion::cpu_string_to_float;
 use chrono::{DateTime, TimeZone, Utc};
@@ -289,6 +290,20 @@ where
             );
         }
 
+        // Specific to postgresql and documentdb, a parameter group is only created when parameters are set
+        if T::db_type() == service::DatabaseType::PostgreSQL {
+            context.insert("parameter_group_family", &format!("postgres{}", self.version.major));
+        }
+        if T::db_type() == service::DatabaseType::MongoDB {
+            context.insert(
+                "parameter_group_family",
+                &format!("docdb{}.{}", self.version.major, self.version.minor.as_deref().unwrap_or("0")),
+            );
+        }
+        let database_parameters = to_managed_database_parameters(T::db_type(), &options.parameters)
+            .map_err(|err| Box::new(EngineError::new_database_error(event_details.clone(), err)))?;
+        context.insert("database_parameters", &database_parameters);
+
         // Specific for redis
         if T::db_type() == service::DatabaseType::Redis {
             let parameter_group_name = if self.version.major == "5" {
@@ -357,6 +372,7 @@ where
         }
         context.insert("database_disk_type", &options.database_disk_type);
         context.insert("encrypt_disk", &options.encrypt_disk);
+        context.insert("kms_key_arn", options.kms_key_arn.as_deref().unwrap_or_default());
         context.insert("database_ram_size_in_mib", &self.total_ram_in_mib);
         context.insert("database_total_cpus", &self.total_cpus);
         context.insert("database_fqdn", &options.host.as_str());
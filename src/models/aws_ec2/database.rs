@@ -12,6 +12,7 @@ use crate::models::aws_ec2::database_utils::{
     is_allowed_managed_mongodb_version, is_allowed_managed_mysql_version, is_allowed_managed_postgres_version,
     is_allowed_managed_redis_version,
 };
+use crate::models::database_utils::to_managed_database_parameters;
 use crate::models::types::{AWSEc2, ToTeraContext};
 use crate::unit_conversion::cpu_string_to_float;
 use tera::Context as TeraContext;
@@ -266,7 +267,7 @@ where
         context.insert("namespace", environment.namespace());
 
         let version = self
-            .get_version_aws_managed(event_details)?
+            .get_version_aws_managed(event_details.clone())?
             .matched_version()
             .to_string();
         context.insert("version", &version);
@@ -283,6 +284,20 @@ where
             );
         }
 
+        // Specific to postgresql and documentdb, a parameter group is only created when parameters are set
+        if T::db_type() == service::DatabaseType::PostgreSQL {
+            context.insert("parameter_group_family", &format!("postgres{}", self.version.major));
+        }
+        if T::db_type() == service::DatabaseType::MongoDB {
+            context.insert(
+                "parameter_group_family",
+                &format!("docdb{}.{}", self.version.major, self.version.minor.as_deref().unwrap_or("0")),
+            );
+        }
+        let database_parameters = to_managed_database_parameters(T::db_type(), &options.parameters)
+            .map_err(|err| Box::new(EngineError::new_database_error(event_details, err)))?;
+        context.insert("database_parameters", &database_parameters);
+
         // Specific for redis
         if T::db_type() == service::DatabaseType::Redis {
             let parameter_group_name = if self.version.major == "5" {
@@ -325,6 +340,7 @@ where
         }
         context.insert("database_disk_type", &options.database_disk_type);
         context.insert("encrypt_disk", &options.encrypt_disk);
+        context.insert("kms_key_arn", options.kms_key_arn.as_deref().unwrap_or_default());
         context.insert("database_ram_size_in_mib", &self.total_ram_in_mib);
         context.insert("database_total_cpus", &self.total_cpus);
         context.insert("database_fqdn", &options.host.as_str());
@@ -1,6 +1,7 @@
 use crate::build_platform::Build;
 use crate::cloud_provider::io::RegistryMirroringMode;
 use crate::cloud_provider::kubernetes::Kubernetes;
+use crate::cloud_provider::kubernetes_api::KubernetesApiVersions;
 use crate::cloud_provider::models::{
     EnvironmentVariable, InvalidPVCStorage, InvalidStatefulsetStorage, MountedFile, Storage, StorageDataTemplate,
 };
@@ -13,18 +14,21 @@ use crate::io_models::application::Protocol::{TCP, UDP};
 use crate::io_models::application::{Port, Protocol};
 use crate::io_models::container::{ContainerAdvancedSettings, Registry};
 use crate::io_models::context::Context;
+use crate::io_models::shared_volume::{shared_volume_claim_name, SharedVolumeMount};
+use crate::io_models::LifecycleHooks;
 use crate::kubers_utils::kube_get_resources_by_selector;
 use crate::models::probe::Probe;
 use crate::models::registry_image_source::RegistryImageSource;
 use crate::models::types::{CloudProvider, ToTeraContext};
 use crate::models::utils;
+use crate::naming;
 use crate::runtime::block_on;
 use crate::unit_conversion::extract_volume_size;
 use crate::utilities::to_short_id;
 use itertools::Itertools;
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
 use serde::Serialize;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -61,6 +65,8 @@ pub struct Container<T: CloudProvider> {
     pub(super) readiness_probe: Option<Probe>,
     pub(super) liveness_probe: Option<Probe>,
     pub(super) advanced_settings: ContainerAdvancedSettings,
+    pub(super) lifecycle_hooks: LifecycleHooks,
+    pub(super) shared_volume_mounts: Vec<SharedVolumeMount>,
     pub(super) _extra_settings: T::AppExtraSettings,
     pub(super) workspace_directory: PathBuf,
     pub(super) lib_root_directory: String,
@@ -122,6 +128,8 @@ impl<T: CloudProvider> Container<T> {
         readiness_probe: Option<Probe>,
         liveness_probe: Option<Probe>,
         advanced_settings: ContainerAdvancedSettings,
+        lifecycle_hooks: LifecycleHooks,
+        shared_volume_mounts: Vec<SharedVolumeMount>,
         extra_settings: T::AppExtraSettings,
         mk_event_details: impl Fn(Transmitter) -> EventDetails,
     ) -> Result<Self, ContainerError> {
@@ -161,6 +169,50 @@ impl<T: CloudProvider> Container<T> {
             ));
         }
 
+        utils::validate_config_reload_settings(
+            advanced_settings.deployment_config_reload_strategy,
+            &advanced_settings.deployment_config_reload_mount_path,
+            &advanced_settings.deployment_config_reload_webhook_url,
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_topology_spread_settings(
+            advanced_settings.deployment_topology_spread_key,
+            advanced_settings.deployment_topology_spread_max_skew,
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_tolerations(&advanced_settings.deployment_tolerations)
+            .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_pod_disruption_budget_settings(
+            advanced_settings.pdb_min_available_percent,
+            advanced_settings.pdb_max_unavailable_percent,
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_lifecycle_hooks(&lifecycle_hooks).map_err(ContainerError::InvalidConfig)?;
+        utils::validate_probes(readiness_probe.as_ref(), liveness_probe.as_ref(), None)
+            .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_storages(&storages).map_err(ContainerError::InvalidConfig)?;
+        utils::validate_shared_volume_mounts(
+            &shared_volume_mounts,
+            storages.iter().map(|storage| storage.mount_point.as_str()),
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_hpa_metrics(
+            advanced_settings.hpa_memory_average_utilization_percent,
+            &advanced_settings.hpa_custom_metrics,
+            &advanced_settings.hpa_keda_triggers,
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_keda_triggers(
+            &advanced_settings.hpa_keda_triggers,
+            environment_variables.iter().map(|env_var| env_var.key.as_str()),
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+        utils::validate_external_secrets(
+            &advanced_settings.deployment_external_secrets,
+            environment_variables.iter().map(|env_var| env_var.key.as_str()),
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+
         let workspace_directory = crate::fs::workspace_directory(
             context.workspace_root_dir(),
             context.execution_id(),
@@ -195,6 +247,8 @@ impl<T: CloudProvider> Container<T> {
             readiness_probe,
             liveness_probe,
             advanced_settings,
+            lifecycle_hooks,
+            shared_volume_mounts,
             _extra_settings: extra_settings,
             workspace_directory,
             lib_root_directory: context.lib_root_dir().to_string(),
@@ -206,7 +260,7 @@ impl<T: CloudProvider> Container<T> {
     }
 
     pub fn helm_release_name(&self) -> String {
-        format!("container-{}", self.long_id)
+        naming::helm_release_name("container", self.long_id)
     }
 
     pub fn helm_chart_dir(&self) -> String {
@@ -230,6 +284,20 @@ impl<T: CloudProvider> Container<T> {
         );
         let mut advanced_settings = self.advanced_settings.clone();
         advanced_settings.deployment_affinity_node_required = deployment_affinity_node_required;
+        advanced_settings.deployment_topology_spread_key =
+            utils::resolve_topology_spread_key(advanced_settings.deployment_topology_spread_key, kubernetes.zones());
+        advanced_settings.deployment_antiaffinity_pod_topology = utils::resolve_antiaffinity_pod_topology(
+            advanced_settings.deployment_antiaffinity_pod_topology,
+            kubernetes.zones(),
+        );
+        advanced_settings.network_ip_family_policy = utils::resolve_ip_family_policy(
+            advanced_settings.network_ip_family_policy,
+            kubernetes.advanced_settings().network_enable_dual_stack,
+        );
+        advanced_settings.security_service_account_name = utils::resolve_service_account_name(
+            &advanced_settings.security_service_account_name,
+            environment.service_account_name(),
+        );
 
         let registry_info = target.container_registry.registry_info();
         let ctx = ContainerTeraContext {
@@ -246,24 +314,17 @@ impl<T: CloudProvider> Container<T> {
                 name: self.kube_name().to_string(),
                 user_unsafe_name: self.name.clone(),
                 // FIXME: We mirror images to cluster private registry
-                image_full: format!(
-                    "{}/{}:{}",
-                    registry_info.endpoint.host_str().unwrap_or_default(),
-                    registry_info.get_image_name(&get_mirror_repository_name(
-                        self.long_id(),
-                        kubernetes.long_id(),
-                        &kubernetes.advanced_settings().registry_mirroring_mode,
-                    )),
-                    self.source.tag_for_mirror(&self.long_id)
-                ),
+                image_full: self.mirrored_image_full(target),
                 image_tag: self.source.tag_for_mirror(&self.long_id),
                 version: self.service_version(),
                 command_args: self.command_args.clone(),
                 entrypoint: self.entrypoint.clone(),
+                init_containers: vec![],
                 cpu_request_in_mili: format!("{}m", self.cpu_request_in_mili),
                 cpu_limit_in_mili: format!("{}m", self.cpu_limit_in_mili),
                 ram_request_in_mib: format!("{}Mi", self.ram_request_in_mib),
                 ram_limit_in_mib: format!("{}Mi", self.ram_limit_in_mib),
+                gpu: 0,
                 min_instances: self.min_instances,
                 max_instances: self.max_instances,
                 public_domain: self.public_domain.clone(),
@@ -280,8 +341,11 @@ impl<T: CloudProvider> Container<T> {
                 },
                 default_port: self.ports.iter().find_or_first(|p| p.is_default).cloned(),
                 storages: vec![],
+                is_stateful: self.is_stateful(),
+                shared_volumes: shared_volume_mounts_tera_context(&self.shared_volume_mounts),
                 readiness_probe: self.readiness_probe.clone(),
                 liveness_probe: self.liveness_probe.clone(),
+                startup_probe: None,
                 advanced_settings,
                 legacy_deployment_matchlabels: false,
                 legacy_volumeclaim_template: false,
@@ -291,7 +355,7 @@ impl<T: CloudProvider> Container<T> {
                 .registry_docker_json_config
                 .as_ref()
                 .map(|docker_json| RegistryTeraContext {
-                    secret_name: format!("{}-registry", self.kube_name()),
+                    secret_name: naming::secret_name(self.kube_name(), "registry"),
                     docker_json_config: Some(docker_json.to_string()),
                 }),
             environment_variables: self.environment_variables.clone(),
@@ -303,6 +367,25 @@ impl<T: CloudProvider> Container<T> {
         ctx
     }
 
+    /// Image deployed in the cluster, mirrored from the registry of the container to the registry of the cluster
+    pub fn mirrored_image_full(&self, target: &DeploymentTarget) -> String {
+        let registry_info = target.container_registry.registry_info();
+        format!(
+            "{}/{}:{}",
+            registry_info.endpoint.host_str().unwrap_or_default(),
+            registry_info.get_image_name(&get_mirror_repository_name(
+                self.long_id(),
+                target.kubernetes.long_id(),
+                &target.kubernetes.advanced_settings().registry_mirroring_mode,
+            )),
+            self.source.tag_for_mirror(&self.long_id)
+        )
+    }
+
+    pub fn config_checksum(&self) -> String {
+        utils::config_checksum(&self.mounted_files)
+    }
+
     pub fn is_stateful(&self) -> bool {
         !self.storages.is_empty()
     }
@@ -331,10 +414,6 @@ impl<T: CloudProvider> Container<T> {
         format!("qovery.com/service-id={}", self.long_id)
     }
 
-    pub fn kube_legacy_label_selector(&self) -> String {
-        format!("appId={}", self.id)
-    }
-
     pub fn workspace_directory(&self) -> &str {
         self.workspace_directory.to_str().unwrap_or("")
     }
@@ -420,6 +499,7 @@ pub trait ContainerService: Service + DeploymentAction + ToTeraContext + Send {
     fn public_ports(&self) -> Vec<&Port>;
     fn advanced_settings(&self) -> &ContainerAdvancedSettings;
     fn image_full(&self) -> String;
+    fn lifecycle_hooks(&self) -> &LifecycleHooks;
     fn startup_timeout(&self) -> Duration;
     fn as_deployment_action(&self) -> &dyn DeploymentAction;
 }
@@ -445,6 +525,10 @@ where
         )
     }
 
+    fn lifecycle_hooks(&self) -> &LifecycleHooks {
+        &self.lifecycle_hooks
+    }
+
     fn startup_timeout(&self) -> Duration {
         let readiness_probe_timeout = if let Some(p) = &self.readiness_probe {
             p.initial_delay_seconds + ((p.timeout_seconds + p.period_seconds) * p.failure_threshold)
@@ -474,6 +558,7 @@ pub(super) struct ClusterTeraContext {
     pub(super) name: String,
     pub(super) region: String,
     pub(super) zone: String,
+    pub(super) api_versions: KubernetesApiVersions,
 }
 
 impl From<&dyn Kubernetes> for ClusterTeraContext {
@@ -483,6 +568,7 @@ impl From<&dyn Kubernetes> for ClusterTeraContext {
             name: k.name().to_string(),
             region: k.region().to_string(),
             zone: k.default_zone().unwrap_or("").to_string(),
+            api_versions: KubernetesApiVersions::new(&k.version()),
         }
     }
 }
@@ -506,10 +592,13 @@ pub(super) struct ServiceTeraContext {
     pub(super) version: String,
     pub(super) command_args: Vec<String>,
     pub(super) entrypoint: Option<String>,
+    pub(super) init_containers: Vec<InitContainerTeraContext>,
     pub(super) cpu_request_in_mili: String,
     pub(super) cpu_limit_in_mili: String,
     pub(super) ram_request_in_mib: String,
     pub(super) ram_limit_in_mib: String,
+    /// NVIDIA GPUs requested by each instance, scheduling it on the GPU nodes
+    pub(super) gpu: u32,
     pub(super) min_instances: u32,
     pub(super) max_instances: u32,
     pub(super) public_domain: String,
@@ -517,14 +606,46 @@ pub(super) struct ServiceTeraContext {
     pub(super) ports_layer4_public: Vec<PublicL4Ports>,
     pub(super) default_port: Option<Port>,
     pub(super) storages: Vec<StorageDataTemplate>,
+    /// Deployed as a statefulset rather than a deployment
+    pub(super) is_stateful: bool,
+    pub(super) shared_volumes: Vec<SharedVolumeMountTeraContext>,
     pub(super) readiness_probe: Option<Probe>,
     pub(super) liveness_probe: Option<Probe>,
+    pub(super) startup_probe: Option<Probe>,
     pub(super) advanced_settings: ContainerAdvancedSettings,
     pub(super) legacy_deployment_matchlabels: bool,
     pub(super) legacy_volumeclaim_template: bool,
     pub(super) legacy_deployment_from_scaleway: bool,
 }
 
+#[derive(Serialize, Debug, Clone)]
+pub(super) struct InitContainerTeraContext {
+    pub(super) name: String,
+    pub(super) image_full: String,
+    pub(super) command: Vec<String>,
+    pub(super) environment_vars: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub(super) struct SharedVolumeMountTeraContext {
+    pub(super) claim_name: String,
+    pub(super) mount_path: String,
+    pub(super) read_only: bool,
+}
+
+pub(super) fn shared_volume_mounts_tera_context(
+    shared_volume_mounts: &[SharedVolumeMount],
+) -> Vec<SharedVolumeMountTeraContext> {
+    shared_volume_mounts
+        .iter()
+        .map(|mount| SharedVolumeMountTeraContext {
+            claim_name: shared_volume_claim_name(&mount.shared_volume_id),
+            mount_path: mount.mount_path.clone(),
+            read_only: mount.read_only,
+        })
+        .collect()
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub(super) struct RegistryTeraContext {
     pub(super) secret_name: String,
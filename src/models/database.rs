@@ -8,25 +8,37 @@ use crate::cloud_provider::{service, DeploymentTarget, Kind};
 use crate::deployment_action::DeploymentAction;
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EnvironmentStep, EventDetails, Stage, Transmitter};
-use crate::io_models::context::Context;
-use crate::io_models::database::DatabaseOptions;
-use crate::kubers_utils::kube_get_resources_by_selector;
+use crate::io_models::context::{Context, Features};
+use crate::io_models::database::{DatabaseAdvancedSettings, DatabaseOptions};
+use crate::kubers_utils::{kube_get_pvcs_usage, kube_get_resources_by_selector, PvcUsage};
 use crate::models::database_utils::{
     is_allowed_containered_mongodb_version, is_allowed_containered_mysql_version,
     is_allowed_containered_postgres_version, is_allowed_containered_redis_version,
 };
 use crate::models::types::{CloudProvider, ToTeraContext, VersionsNumber};
+use crate::naming;
 use crate::runtime::block_on;
 use crate::unit_conversion::extract_volume_size;
 use crate::utilities::to_short_id;
 use chrono::{DateTime, Utc};
-use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod, Secret};
+use kube::Api;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tera::Context as TeraContext;
 use uuid::Uuid;
 
+const CONNECTION_POOLER_IMAGE: &str = "public.ecr.aws/bitnami/pgbouncer:1.21.0";
+pub const CONNECTION_POOLER_PORT: u16 = 6432;
+/// Total number of pods (leader included) of a container database in high availability mode
+const CONTAINER_DATABASE_HA_INSTANCES: u32 = 3;
+/// Key of the replication password in the secret of the PostgreSQL chart
+const REPLICATION_PASSWORD_SECRET_KEY: &str = "replication-password";
+
 /////////////////////////////////////////////////////////////////
 // Database mode
 pub trait DatabaseInstanceType: Send + Sync {
@@ -135,6 +147,18 @@ pub enum DatabaseError {
         database_type: service::DatabaseType,
     },
 
+    #[error("Parameter `{parameter}` is not supported for managed {database_type:?} databases")]
+    UnsupportedDatabaseParameter {
+        database_type: service::DatabaseType,
+        parameter: String,
+    },
+
+    #[error("Database init scripts failed: {0}")]
+    InitScriptsFailed(String),
+
+    #[error("Database connection smoke test failed: {0}")]
+    SmokeTestFailed(String),
+
     #[error("Unknown Database error: {0}")]
     UnknownError(String),
 }
@@ -326,20 +350,7 @@ impl<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>> Service for Datab
 // Method Only For all container database
 impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T> {
     pub fn helm_release_name(&self) -> String {
-        format!("{}-{}", T::lib_directory_name(), self.id)
-    }
-
-    pub fn helm_chart_dir(&self) -> String {
-        format!("{}/common/services/{}", self.lib_root_directory, T::lib_directory_name())
-    }
-
-    pub fn helm_chart_values_dir(&self) -> String {
-        format!(
-            "{}/{}/chart_values/{}",
-            self.lib_root_directory,
-            C::lib_directory_name(),
-            T::lib_directory_name()
-        )
+        naming::helm_release_name(T::lib_directory_name(), &self.id)
     }
 
     pub(super) fn to_tera_context_for_container(
@@ -379,7 +390,7 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
         context.insert("kubeconfig_path", &kubernetes.kubeconfig_local_file_path());
         context.insert("namespace", environment.namespace());
 
-        let version = self.get_version(event_details)?.matched_version().to_string();
+        let version = self.get_version(event_details.clone())?.matched_version().to_string();
         context.insert("version", &version);
 
         for (k, v) in target.cloud_provider.tera_context_environment_variables() {
@@ -408,6 +419,53 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
         context.insert("database_id", &self.id());
         context.insert("publicly_accessible", &container_database_publicly_accessible);
 
+        let high_availability = options.activate_high_availability && supports_high_availability(T::db_type());
+        context.insert("database_high_availability", &high_availability);
+        context.insert("database_ha_instances", &CONTAINER_DATABASE_HA_INSTANCES);
+        if high_availability && T::db_type() == service::DatabaseType::PostgreSQL {
+            context.insert(
+                "database_replication_password",
+                &self.replication_password(target, &event_details)?,
+            );
+        }
+
+        // pgbouncer sidecar, pooled connections are exposed on another port of the database service
+        let advanced_settings = &options.advanced_settings;
+        let connection_pooler_enabled =
+            T::db_type() == service::DatabaseType::PostgreSQL && advanced_settings.connection_pooler_enabled;
+        context.insert("connection_pooler_enabled", &connection_pooler_enabled);
+        if connection_pooler_enabled {
+            context.insert("connection_pooler_image", CONNECTION_POOLER_IMAGE);
+            context.insert("connection_pooler_port", &CONNECTION_POOLER_PORT);
+            context.insert(
+                "connection_pooler_pool_mode",
+                advanced_settings.connection_pooler_pool_mode.as_str(),
+            );
+            context.insert(
+                "connection_pooler_default_pool_size",
+                &advanced_settings.connection_pooler_default_pool_size,
+            );
+            context.insert(
+                "connection_pooler_max_client_connections",
+                &advanced_settings.connection_pooler_max_client_connections,
+            );
+        }
+
+        // a partition stages updates: only the pods with an ordinal greater or equal to it are replaced
+        context.insert("update_strategy_type", &advanced_settings.update_strategy_type);
+        context.insert(
+            "update_strategy_rolling_update_partition",
+            &advanced_settings.update_strategy_rolling_update_partition,
+        );
+
+        // ServiceMonitor objects are only understood by the prometheus operator of the monitoring stack
+        context.insert("metrics_exporter_enabled", &advanced_settings.metrics_exporter_enabled);
+        context.insert(
+            "metrics_service_monitor_enabled",
+            &(advanced_settings.metrics_exporter_enabled
+                && kubernetes.context().is_feature_enabled(&Features::MetricsHistory)),
+        );
+
         context.insert(
             "resource_expiration_in_seconds",
             &kubernetes.advanced_settings().pleco_resources_ttl,
@@ -416,6 +474,35 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
         Ok(context)
     }
 
+    /// Password of the PostgreSQL replication user, generated on the first deployment in high availability mode.
+    /// The chart keeps it in the secret of the release, it is read back from there on the next deployments.
+    fn replication_password(
+        &self,
+        target: &DeploymentTarget,
+        event_details: &EventDetails,
+    ) -> Result<String, Box<EngineError>> {
+        let secret_name = naming::kube_name(self.kube_name());
+        let secrets: Api<Secret> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+        let secret = block_on(secrets.get_opt(&secret_name)).map_err(|e| {
+            Box::new(EngineError::new_k8s_get_secret_error(
+                event_details.clone(),
+                CommandError::new(
+                    format!("Cannot get secret {secret_name} of database {}", self.name()),
+                    Some(e.to_string()),
+                    None,
+                ),
+            ))
+        })?;
+
+        Ok(stored_replication_password(secret.as_ref()).unwrap_or_else(|| {
+            rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(32)
+                .map(char::from)
+                .collect()
+        }))
+    }
+
     fn get_version(&self, event_details: EventDetails) -> Result<ServiceVersionCheckResult, Box<EngineError>> {
         let fn_version = match T::db_type() {
             service::DatabaseType::PostgreSQL => is_allowed_containered_postgres_version,
@@ -434,6 +521,40 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
     }
 }
 
+impl<C: CloudProvider, T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>> Database<C, Container, T> {
+    pub fn is_high_availability(&self) -> bool {
+        self.options.activate_high_availability && supports_high_availability(T::db_type())
+    }
+
+    pub fn helm_chart_dir(&self) -> String {
+        format!("{}/common/services/{}", self.lib_root_directory, T::lib_directory_name())
+    }
+
+    pub fn helm_chart_values_dir(&self) -> String {
+        format!(
+            "{}/{}/chart_values/{}",
+            self.lib_root_directory,
+            C::lib_directory_name(),
+            T::lib_directory_name()
+        )
+    }
+}
+
+/// Container databases able to run several instances: PostgreSQL streams its writes to read replicas, at least one
+/// of them synchronously, and Sentinel promotes a Redis replica when the master is lost. MySQL and MongoDB stay
+/// standalone.
+pub fn supports_high_availability(db_type: service::DatabaseType) -> bool {
+    matches!(db_type, service::DatabaseType::PostgreSQL | service::DatabaseType::Redis)
+}
+
+fn stored_replication_password(secret: Option<&Secret>) -> Option<String> {
+    secret
+        .and_then(|secret| secret.data.as_ref())
+        .and_then(|data| data.get(REPLICATION_PASSWORD_SECRET_KEY))
+        .and_then(|password| String::from_utf8(password.0.clone()).ok())
+        .filter(|password| !password.is_empty())
+}
+
 // methods for all Managed databases
 impl<C: CloudProvider, T: DatabaseType<C, Managed>> Database<C, Managed, T> {
     pub fn helm_chart_external_name_service_dir(&self) -> String {
@@ -494,6 +615,141 @@ where
     }
 }
 
+/// Size requested by the volume claim template of the database statefulset, it can be bigger than the
+/// requested disk size once volumes have been grown by the auto resize
+pub fn get_database_statefulset_disk_size_in_gib<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>>(
+    database: &Database<C, M, T>,
+    kube_client: &kube::Client,
+    namespace: &str,
+    event_details: &EventDetails,
+) -> Result<Option<u32>, Box<EngineError>> {
+    let (_, volumes) = get_service_statefulset_name_and_volumes(
+        kube_client,
+        namespace,
+        &database.kube_label_selector(),
+        event_details,
+    )?;
+    let Some(storage) = volumes
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|volume| volume.spec?.resources?.requests?.get("storage").cloned())
+    else {
+        return Ok(None);
+    };
+
+    extract_volume_size(storage.0.to_string())
+        .map(Some)
+        .map_err(|e| Box::new(EngineError::new_cannot_parse_string(event_details.clone(), &storage.0, e)))
+}
+
+/// New size of a volume whose usage crossed the auto resize threshold, None if it does not need to grow
+pub fn auto_resized_disk_size_in_gib(
+    usage: &PvcUsage,
+    current_size_in_gib: u32,
+    settings: &DatabaseAdvancedSettings,
+) -> Option<u32> {
+    if usage.capacity_bytes == 0 {
+        return None;
+    }
+
+    let usage_percent = usage.used_bytes.saturating_mul(100) / usage.capacity_bytes;
+    if usage_percent < u64::from(settings.storage_auto_resize_usage_threshold_percent) {
+        return None;
+    }
+
+    let increment = ((current_size_in_gib * settings.storage_auto_resize_increment_percent + 99) / 100).max(1);
+    let new_size = current_size_in_gib
+        .saturating_add(increment)
+        .min(settings.storage_auto_resize_max_size_in_gib);
+
+    if new_size > current_size_in_gib {
+        Some(new_size)
+    } else {
+        None
+    }
+}
+
+/// Look at the effective usage of the database volumes and return the ones to grow according to the
+/// auto resize settings. Unlike `get_database_with_invalid_storage_size`, the requested size is not involved.
+pub fn get_database_with_storage_to_auto_resize<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>>(
+    database: &Database<C, M, T>,
+    settings: &DatabaseAdvancedSettings,
+    kube_client: &kube::Client,
+    namespace: &str,
+    event_details: &EventDetails,
+) -> Result<Option<InvalidStatefulsetStorage>, Box<EngineError>> {
+    if !settings.storage_auto_resize_enabled {
+        return Ok(None);
+    }
+
+    let selector = database.kube_label_selector();
+    let (statefulset_name, _) =
+        get_service_statefulset_name_and_volumes(kube_client, namespace, &selector, event_details)?;
+
+    let pvcs = block_on(kube_get_resources_by_selector::<PersistentVolumeClaim>(
+        kube_client,
+        namespace,
+        &format!("app={}", database.kube_name()),
+    ))
+    .map_err(|e| EngineError::new_k8s_cannot_get_pvcs(event_details.clone(), namespace, e))?
+    .items;
+
+    // volume usage is reported by the kubelet of the nodes running the database pods
+    let node_names: BTreeSet<String> =
+        block_on(kube_get_resources_by_selector::<Pod>(kube_client, namespace, &selector))
+            .map_err(|e| EngineError::new_k8s_cannot_get_pods(event_details.clone(), e))?
+            .items
+            .into_iter()
+            .filter_map(|pod| pod.spec.and_then(|spec| spec.node_name))
+            .collect();
+
+    let mut usages: Vec<PvcUsage> = vec![];
+    for node_name in &node_names {
+        usages.extend(
+            block_on(kube_get_pvcs_usage(kube_client, namespace, node_name))
+                .map_err(|e| EngineError::new_k8s_cannot_get_pvcs(event_details.clone(), namespace, e))?,
+        );
+    }
+
+    let mut invalid_pvcs: Vec<InvalidPVCStorage> = vec![];
+    for pvc in pvcs {
+        let Some(pvc_name) = pvc.metadata.name else {
+            continue;
+        };
+        let Some(usage) = usages.iter().find(|usage| usage.pvc_name == pvc_name) else {
+            continue;
+        };
+        let Some(capacity) = pvc
+            .status
+            .and_then(|status| status.capacity)
+            .and_then(|capacity| capacity.get("storage").cloned())
+        else {
+            continue;
+        };
+        let current_size = extract_volume_size(capacity.0.to_string())
+            .map_err(|e| Box::new(EngineError::new_cannot_parse_string(event_details.clone(), &capacity.0, e)))?;
+
+        if let Some(required_disk_size_in_gib) = auto_resized_disk_size_in_gib(usage, current_size, settings) {
+            invalid_pvcs.push(InvalidPVCStorage {
+                pvc_name,
+                required_disk_size_in_gib,
+            });
+        }
+    }
+
+    if invalid_pvcs.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(InvalidStatefulsetStorage {
+        service_type: Database::service_type(database),
+        service_id: database.long_id,
+        statefulset_selector: selector,
+        statefulset_name,
+        invalid_pvcs,
+    }))
+}
+
 pub fn get_database_with_invalid_storage_size<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>>(
     database: &Database<C, M, T>,
     kube_client: &kube::Client,
@@ -535,29 +791,33 @@ pub fn get_database_with_invalid_storage_size<C: CloudProvider, M: DatabaseMode,
                 })?;
 
                 if database.total_disk_size_in_gb > size {
-                    // if volume size in request is bigger than effective size we get related PVC to get its infos
-                    if let Some(pvc) = block_on(kube_get_resources_by_selector::<PersistentVolumeClaim>(
-                        kube_client,
-                        namespace,
-                        &format!("app={}", database.kube_name()),
-                    ))
-                    .map_err(|e| EngineError::new_k8s_cannot_get_pvcs(event_details.clone(), namespace, e))?
-                    .items
-                    .first()
-                    {
-                        if let Some(pvc_name) = &pvc.metadata.name {
-                            return Ok(Some(InvalidStatefulsetStorage {
-                                service_type: Database::service_type(database),
-                                service_id: database.long_id,
-                                statefulset_selector: selector,
-                                statefulset_name,
-                                invalid_pvcs: vec![InvalidPVCStorage {
-                                    pvc_name: pvc_name.to_string(),
-                                    required_disk_size_in_gib: database.total_disk_size_in_gb,
-                                }],
-                            }));
-                        }
-                    };
+                    // if volume size in request is bigger than effective size we get related PVCs to get their infos.
+                    // In high availability mode, each replica has its own PVC, they all need to be resized
+                    let invalid_pvcs: Vec<InvalidPVCStorage> =
+                        block_on(kube_get_resources_by_selector::<PersistentVolumeClaim>(
+                            kube_client,
+                            namespace,
+                            &format!("app={}", database.kube_name()),
+                        ))
+                        .map_err(|e| EngineError::new_k8s_cannot_get_pvcs(event_details.clone(), namespace, e))?
+                        .items
+                        .into_iter()
+                        .filter_map(|pvc| pvc.metadata.name)
+                        .map(|pvc_name| InvalidPVCStorage {
+                            pvc_name,
+                            required_disk_size_in_gib: database.total_disk_size_in_gb,
+                        })
+                        .collect();
+
+                    if !invalid_pvcs.is_empty() {
+                        return Ok(Some(InvalidStatefulsetStorage {
+                            service_type: Database::service_type(database),
+                            service_id: database.long_id,
+                            statefulset_selector: selector,
+                            statefulset_name,
+                            invalid_pvcs,
+                        }));
+                    }
                 }
 
                 if database.total_disk_size_in_gb < size {
@@ -577,3 +837,68 @@ pub fn get_database_with_invalid_storage_size<C: CloudProvider, M: DatabaseMode,
 
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::io_models::database::DatabaseAdvancedSettings;
+    use crate::kubers_utils::PvcUsage;
+    use crate::models::database::{
+        auto_resized_disk_size_in_gib, stored_replication_password, REPLICATION_PASSWORD_SECRET_KEY,
+    };
+    use k8s_openapi::api::core::v1::Secret;
+    use k8s_openapi::ByteString;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_auto_resized_disk_size_in_gib() {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        let settings = DatabaseAdvancedSettings {
+            storage_auto_resize_enabled: true,
+            storage_auto_resize_usage_threshold_percent: 80,
+            storage_auto_resize_increment_percent: 20,
+            storage_auto_resize_max_size_in_gib: 30,
+            ..Default::default()
+        };
+        let usage = |used_in_gib: u64| PvcUsage {
+            pvc_name: "data-postgresql-0".to_string(),
+            capacity_bytes: 10 * GIB,
+            used_bytes: used_in_gib * GIB,
+        };
+
+        // below threshold
+        assert_eq!(auto_resized_disk_size_in_gib(&usage(7), 10, &settings), None);
+        // threshold crossed, grow by 20%
+        assert_eq!(auto_resized_disk_size_in_gib(&usage(8), 10, &settings), Some(12));
+        // increment is at least 1GiB
+        assert_eq!(auto_resized_disk_size_in_gib(&usage(9), 2, &settings), Some(3));
+        // never above the max size
+        assert_eq!(auto_resized_disk_size_in_gib(&usage(9), 28, &settings), Some(30));
+        assert_eq!(auto_resized_disk_size_in_gib(&usage(9), 30, &settings), None);
+        // unknown capacity
+        let empty_usage = PvcUsage {
+            capacity_bytes: 0,
+            ..usage(0)
+        };
+        assert_eq!(auto_resized_disk_size_in_gib(&empty_usage, 10, &settings), None);
+    }
+
+    #[test]
+    fn test_stored_replication_password() {
+        let secret = |key: &str, value: &str| Secret {
+            data: Some(BTreeMap::from([(key.to_string(), ByteString(value.as_bytes().to_vec()))])),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            stored_replication_password(Some(&secret(REPLICATION_PASSWORD_SECRET_KEY, "s3cr3t"))),
+            Some("s3cr3t".to_string())
+        );
+        // first deployment, or a database deployed standalone until now
+        assert_eq!(stored_replication_password(None), None);
+        assert_eq!(stored_replication_password(Some(&secret("password", "s3cr3t"))), None);
+        assert_eq!(
+            stored_replication_password(Some(&secret(REPLICATION_PASSWORD_SECRET_KEY, ""))),
+            None
+        );
+    }
+}
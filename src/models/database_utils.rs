@@ -1,8 +1,91 @@
 use crate::cloud_provider::service::DatabaseType;
 use crate::models::database::DatabaseError;
 use crate::models::types::VersionsNumber;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+// Parameters users can tune on managed databases, with whether they are static (only applied after a reboot).
+// Parameters managed by Qovery itself, like mysql `log_bin_trust_function_creators`, are not listed on purpose.
+const RDS_POSTGRES_PARAMETERS: &[(&str, bool)] = &[
+    ("autovacuum", false),
+    ("autovacuum_analyze_scale_factor", false),
+    ("autovacuum_vacuum_scale_factor", false),
+    ("checkpoint_completion_target", false),
+    ("default_statistics_target", false),
+    ("effective_cache_size", false),
+    ("effective_io_concurrency", false),
+    ("idle_in_transaction_session_timeout", false),
+    ("lock_timeout", false),
+    ("log_connections", false),
+    ("log_disconnections", false),
+    ("log_min_duration_statement", false),
+    ("log_statement", false),
+    ("maintenance_work_mem", false),
+    ("max_connections", true),
+    ("max_parallel_workers", false),
+    ("max_parallel_workers_per_gather", false),
+    ("max_wal_size", false),
+    ("max_worker_processes", true),
+    ("min_wal_size", false),
+    ("pg_stat_statements.track", false),
+    ("random_page_cost", false),
+    ("rds.force_ssl", false),
+    ("shared_buffers", true),
+    ("shared_preload_libraries", true),
+    ("statement_timeout", false),
+    ("temp_buffers", false),
+    ("timezone", false),
+    ("track_io_timing", false),
+    ("work_mem", false),
+];
+
+const RDS_MYSQL_PARAMETERS: &[(&str, bool)] = &[
+    ("binlog_format", false),
+    ("character_set_server", false),
+    ("collation_server", false),
+    ("general_log", false),
+    ("group_concat_max_len", false),
+    ("innodb_buffer_pool_size", false),
+    ("innodb_flush_log_at_trx_commit", false),
+    ("innodb_lock_wait_timeout", false),
+    ("innodb_log_file_size", true),
+    ("interactive_timeout", false),
+    ("join_buffer_size", false),
+    ("log_output", false),
+    ("long_query_time", false),
+    ("max_allowed_packet", false),
+    ("max_connections", false),
+    ("max_heap_table_size", false),
+    ("require_secure_transport", false),
+    ("slow_query_log", false),
+    ("sort_buffer_size", false),
+    ("sql_mode", false),
+    ("table_open_cache", false),
+    ("thread_cache_size", false),
+    ("time_zone", false),
+    ("tmp_table_size", false),
+    ("wait_timeout", false),
+];
+
+const DOCUMENTDB_PARAMETERS: &[(&str, bool)] = &[
+    ("audit_logs", false),
+    ("change_stream_log_retention_duration", false),
+    ("profiler", false),
+    ("profiler_sampling_rate", false),
+    ("profiler_threshold_ms", false),
+    ("tls", true),
+    ("ttl_monitor", false),
+];
+
+/// Engine parameter of a managed database, as rendered in its terraform parameter group
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+pub struct ManagedDatabaseParameter {
+    pub name: String,
+    pub value: String,
+    pub apply_method: &'static str,
+}
+
 pub fn is_allowed_containered_postgres_version(requested_version: &VersionsNumber) -> Result<(), DatabaseError> {
     // https://hub.docker.com/r/bitnami/postgresql/tags?page=1&ordering=last_updated
 
@@ -71,17 +154,101 @@ pub fn is_allowed_containered_redis_version(requested_version: &VersionsNumber)
     Ok(())
 }
 
+/// Check parameters against the ones supported by the managed database engine, and attach how AWS has to apply them
+pub fn to_managed_database_parameters(
+    database_type: DatabaseType,
+    parameters: &BTreeMap<String, String>,
+) -> Result<Vec<ManagedDatabaseParameter>, DatabaseError> {
+    let supported_parameters = match database_type {
+        DatabaseType::PostgreSQL => RDS_POSTGRES_PARAMETERS,
+        DatabaseType::MySQL => RDS_MYSQL_PARAMETERS,
+        DatabaseType::MongoDB => DOCUMENTDB_PARAMETERS,
+        DatabaseType::Redis => &[],
+    };
+
+    parameters
+        .iter()
+        .map(|(name, value)| {
+            let Some((_, is_static)) = supported_parameters.iter().find(|(p, _)| p == name) else {
+                return Err(DatabaseError::UnsupportedDatabaseParameter {
+                    database_type,
+                    parameter: name.to_string(),
+                });
+            };
+
+            // values end up in terraform strings, so no quote nor interpolation is allowed
+            let is_valid_value = !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || " _-.,:/+*{}".contains(c));
+            if !is_valid_value {
+                return Err(DatabaseError::InvalidConfig(format!(
+                    "Invalid value `{value}` for database parameter `{name}`"
+                )));
+            }
+
+            Ok(ManagedDatabaseParameter {
+                name: name.to_string(),
+                value: value.to_string(),
+                apply_method: if *is_static { "pending-reboot" } else { "immediate" },
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::cloud_provider::service::DatabaseType;
     use crate::models::database::DatabaseError;
     use crate::models::database_utils::{
         is_allowed_containered_mongodb_version, is_allowed_containered_mysql_version,
-        is_allowed_containered_postgres_version, is_allowed_containered_redis_version,
+        is_allowed_containered_postgres_version, is_allowed_containered_redis_version, to_managed_database_parameters,
     };
     use crate::models::types::VersionsNumberBuilder;
+    use std::collections::BTreeMap;
     use std::sync::Arc;
 
+    #[test]
+    fn test_to_managed_database_parameters() {
+        let parameters = BTreeMap::from([
+            ("max_connections".to_string(), "500".to_string()),
+            ("work_mem".to_string(), "16384".to_string()),
+        ]);
+        let managed_parameters = to_managed_database_parameters(DatabaseType::PostgreSQL, &parameters).unwrap();
+        assert_eq!(managed_parameters.len(), 2);
+        assert_eq!(managed_parameters[0].name, "max_connections");
+        assert_eq!(managed_parameters[0].apply_method, "pending-reboot");
+        assert_eq!(managed_parameters[1].apply_method, "immediate");
+
+        // mysql parameters are not postgres ones
+        assert!(matches!(
+            to_managed_database_parameters(
+                DatabaseType::PostgreSQL,
+                &BTreeMap::from([("sql_mode".to_string(), "TRADITIONAL".to_string())])
+            ),
+            Err(DatabaseError::UnsupportedDatabaseParameter { .. })
+        ));
+        assert!(matches!(
+            to_managed_database_parameters(
+                DatabaseType::MySQL,
+                &BTreeMap::from([("log_bin_trust_function_creators".to_string(), "0".to_string())])
+            ),
+            Err(DatabaseError::UnsupportedDatabaseParameter { .. })
+        ));
+        assert!(matches!(
+            to_managed_database_parameters(
+                DatabaseType::MySQL,
+                &BTreeMap::from([("time_zone".to_string(), "${file(\"/etc/passwd\")}".to_string())])
+            ),
+            Err(DatabaseError::InvalidConfig(_))
+        ));
+        assert!(to_managed_database_parameters(
+            DatabaseType::MongoDB,
+            &BTreeMap::from([("profiler".to_string(), "enabled".to_string())])
+        )
+        .is_ok());
+    }
+
     #[test]
     fn test_is_allowed_containered_mysql_versions() {
         // v5
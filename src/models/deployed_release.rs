@@ -0,0 +1,175 @@
+use crate::cmd::helm::{Helm, HelmError};
+use serde_yaml::Value;
+
+const REDACTED: &str = "<redacted>";
+// Values whose key contains one of those words are considered sensitive
+const SENSITIVE_KEY_PATTERNS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "apikey",
+    "api_key",
+    "private_key",
+    "privatekey",
+    "credentials",
+    "dockerconfigjson",
+];
+
+/// What the engine applied for a service: the values and the rendered manifest of its helm release, secrets redacted
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeployedRelease {
+    pub release_name: String,
+    pub namespace: String,
+    pub values: String,
+    pub manifest: String,
+}
+
+/// Fetch a deployed helm release, i.e: `Application::helm_release_name()` in the environment namespace
+pub fn get_deployed_release(helm: &Helm, release_name: &str, namespace: &str) -> Result<DeployedRelease, HelmError> {
+    let values = helm.get_release_values(release_name, namespace, &[])?;
+    let manifest = helm.get_release_manifest(release_name, namespace, &[])?;
+
+    Ok(DeployedRelease {
+        release_name: release_name.to_string(),
+        namespace: namespace.to_string(),
+        values: redact_values(&values),
+        manifest: redact_manifest(&manifest),
+    })
+}
+
+fn is_sensitive_key(key: &Value) -> bool {
+    let Some(key) = key.as_str() else {
+        return false;
+    };
+    let key = key.to_ascii_lowercase();
+    SENSITIVE_KEY_PATTERNS.iter().any(|pattern| key.contains(pattern))
+}
+
+fn redact_sensitive_values(value: &mut Value) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping.iter_mut() {
+                if is_sensitive_key(key) && !matches!(value, Value::Mapping(_) | Value::Sequence(_) | Value::Null) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_sensitive_values(value);
+                }
+            }
+        }
+        Value::Sequence(sequence) => sequence.iter_mut().for_each(redact_sensitive_values),
+        Value::Tagged(tagged) => redact_sensitive_values(&mut tagged.value),
+        _ => {}
+    }
+}
+
+fn redact_values(values: &str) -> String {
+    let mut values: Value = match serde_yaml::from_str(values) {
+        Ok(values) => values,
+        // never return something we were not able to redact
+        Err(_) => return REDACTED.to_string(),
+    };
+    redact_sensitive_values(&mut values);
+
+    serde_yaml::to_string(&values).unwrap_or_else(|_| REDACTED.to_string())
+}
+
+// Only secrets are rewritten, other documents are kept as is to preserve the `# Source:` comments of helm
+fn redact_manifest_document(document: &str) -> String {
+    let Ok(mut resource) = serde_yaml::from_str::<Value>(document) else {
+        return REDACTED.to_string();
+    };
+    if resource.get("kind").and_then(Value::as_str) != Some("Secret") {
+        return document.to_string();
+    }
+
+    for field in ["data", "stringData"] {
+        if let Some(Value::Mapping(data)) = resource.get_mut(field) {
+            data.iter_mut()
+                .for_each(|(_, value)| *value = Value::String(REDACTED.to_string()));
+        }
+    }
+    let comments: Vec<&str> = document
+        .lines()
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .collect();
+    let resource = serde_yaml::to_string(&resource).unwrap_or_else(|_| REDACTED.to_string());
+
+    if comments.is_empty() {
+        resource.trim_end().to_string()
+    } else {
+        format!("{}\n{}", comments.join("\n"), resource.trim_end())
+    }
+}
+
+fn redact_manifest(manifest: &str) -> String {
+    let mut documents: Vec<Vec<&str>> = vec![vec![]];
+    for line in manifest.lines() {
+        match documents.last_mut() {
+            Some(document) if line.trim_end() != "---" => document.push(line),
+            _ => documents.push(vec![]),
+        }
+    }
+
+    documents
+        .iter()
+        .filter(|document| document.iter().any(|line| !line.trim().is_empty()))
+        .map(|document| redact_manifest_document(&document.join("\n")))
+        .collect::<Vec<String>>()
+        .join("\n---\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_values() {
+        let values = r#"
+replicaCount: 2
+auth:
+  password: my-password
+  username: qovery
+env:
+  - name: API_TOKEN
+    apiToken: abcdef
+"#;
+        let redacted = redact_values(values);
+        assert!(!redacted.contains("my-password"));
+        assert!(!redacted.contains("abcdef"));
+        assert!(redacted.contains("username: qovery"));
+        assert!(redacted.contains("replicaCount: 2"));
+
+        assert_eq!(redact_values("password: [unclosed"), REDACTED);
+    }
+
+    #[test]
+    fn test_redact_manifest() {
+        let manifest = r#"---
+# Source: q-container/templates/secret.yaml
+apiVersion: v1
+kind: Secret
+metadata:
+  name: app-secrets
+type: Opaque
+data:
+  DATABASE_URL: cG9zdGdyZXM6Ly91c2VyOnBhc3N3b3JkQGRi
+stringData:
+  API_KEY: plain-key
+---
+# Source: q-container/templates/service.yaml
+apiVersion: v1
+kind: Service
+metadata:
+  name: app
+spec:
+  ports:
+    - port: 80"#;
+        let redacted = redact_manifest(manifest);
+        assert!(!redacted.contains("cG9zdGdyZXM6Ly91c2VyOnBhc3N3b3JkQGRi"));
+        assert!(!redacted.contains("plain-key"));
+        assert_eq!(redacted.matches(REDACTED).count(), 2);
+        assert!(redacted.contains("# Source: q-container/templates/secret.yaml"));
+        assert!(redacted.contains("# Source: q-container/templates/service.yaml\napiVersion: v1\nkind: Service"));
+    }
+}
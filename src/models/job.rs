@@ -5,13 +5,15 @@ use crate::cloud_provider::DeploymentTarget;
 use crate::deployment_action::DeploymentAction;
 use crate::events::{EventDetails, Stage, Transmitter};
 use crate::io_models::context::Context;
-use crate::io_models::job::{JobAdvancedSettings, JobSchedule};
+use crate::io_models::job::{JobAdvancedSettings, JobArtifactsStorage, JobSchedule};
 use crate::models;
 use crate::models::container::{ClusterTeraContext, RegistryTeraContext};
+use crate::models::job_artifacts;
 use crate::models::probe::Probe;
 use crate::models::registry_image_source::RegistryImageSource;
 use crate::models::types::{CloudProvider, ToTeraContext};
 use crate::models::utils;
+use crate::naming;
 use crate::utilities::to_short_id;
 use serde::Serialize;
 use std::collections::BTreeSet;
@@ -20,6 +22,8 @@ use std::path::PathBuf;
 use std::time::Duration;
 use uuid::Uuid;
 
+const JOB_ARTIFACTS_UPLOADER_IMAGE: &str = "public.ecr.aws/aws-cli/aws-cli:2.15.0";
+
 #[derive(thiserror::Error, Debug)]
 pub enum JobError {
     #[error("Job invalid configuration: {0}")]
@@ -50,6 +54,7 @@ pub struct Job<T: CloudProvider> {
     pub(super) environment_variables: Vec<EnvironmentVariable>,
     pub(super) mounted_files: BTreeSet<MountedFile>,
     pub(super) advanced_settings: JobAdvancedSettings,
+    pub(super) artifacts_storage: Option<JobArtifactsStorage>,
     pub(super) _extra_settings: T::AppExtraSettings,
     pub(super) workspace_directory: PathBuf,
     pub(super) lib_root_directory: String,
@@ -80,6 +85,7 @@ impl<T: CloudProvider> Job<T> {
         environment_variables: Vec<EnvironmentVariable>,
         mounted_files: BTreeSet<MountedFile>,
         advanced_settings: JobAdvancedSettings,
+        artifacts_storage: Option<JobArtifactsStorage>,
         readiness_probe: Option<Probe>,
         liveness_probe: Option<Probe>,
         extra_settings: T::AppExtraSettings,
@@ -107,6 +113,17 @@ impl<T: CloudProvider> Job<T> {
             return Err(JobError::InvalidConfig("ram_request_in_mib must be greater than 0".to_string()));
         }
 
+        if advanced_settings.job_artifacts_enabled {
+            if artifacts_storage.is_none() {
+                return Err(JobError::InvalidConfig(
+                    "job artifacts are enabled but no artifacts storage is configured".to_string(),
+                ));
+            }
+            if !advanced_settings.job_artifacts_path.starts_with('/') {
+                return Err(JobError::InvalidConfig("job artifacts path must be absolute".to_string()));
+            }
+        }
+
         let workspace_directory = crate::fs::workspace_directory(
             context.workspace_root_dir(),
             context.execution_id(),
@@ -138,6 +155,7 @@ impl<T: CloudProvider> Job<T> {
             environment_variables,
             mounted_files,
             advanced_settings,
+            artifacts_storage,
             _extra_settings: extra_settings,
             workspace_directory,
             readiness_probe,
@@ -152,7 +170,7 @@ impl<T: CloudProvider> Job<T> {
     }
 
     pub fn helm_release_name(&self) -> String {
-        format!("job-{}", self.long_id)
+        naming::helm_release_name("job", self.long_id)
     }
 
     pub fn helm_chart_dir(&self) -> String {
@@ -180,6 +198,10 @@ impl<T: CloudProvider> Job<T> {
         );
         let mut advanced_settings = self.advanced_settings.clone();
         advanced_settings.deployment_affinity_node_required = deployment_affinity_node_required;
+        advanced_settings.security_service_account_name = utils::resolve_service_account_name(
+            &advanced_settings.security_service_account_name,
+            environment.service_account_name(),
+        );
 
         let registry_info = target.container_registry.registry_info();
         let (image_full, image_tag) = match &self.image_source {
@@ -242,11 +264,24 @@ impl<T: CloudProvider> Job<T> {
                 .registry_docker_json_config
                 .as_ref()
                 .map(|docker_json| RegistryTeraContext {
-                    secret_name: format!("{}-registry", self.kube_name()),
+                    secret_name: naming::secret_name(self.kube_name(), "registry"),
                     docker_json_config: Some(docker_json.to_string()),
                 }),
             environment_variables: self.environment_variables.clone(),
             mounted_files: self.mounted_files.clone().into_iter().collect::<Vec<_>>(),
+            artifacts: match &self.artifacts_storage {
+                Some(storage) if self.advanced_settings.job_artifacts_enabled => Some(JobArtifactsTeraContext {
+                    uploader_image: JOB_ARTIFACTS_UPLOADER_IMAGE.to_string(),
+                    secret_name: naming::secret_name(self.kube_name(), "artifacts"),
+                    bucket_name: storage.bucket_name.clone(),
+                    region: storage.region.clone(),
+                    endpoint: storage.endpoint.clone(),
+                    access_key_id: storage.access_key_id.clone(),
+                    secret_access_key: storage.secret_access_key.clone(),
+                    prefix: job_artifacts::job_artifacts_prefix(&self.long_id),
+                }),
+                _ => None,
+            },
             resource_expiration_in_seconds: Some(kubernetes.advanced_settings().pleco_resources_ttl),
         };
 
@@ -510,5 +545,18 @@ pub(super) struct JobTeraContext {
     pub(super) registry: Option<RegistryTeraContext>,
     pub(super) environment_variables: Vec<EnvironmentVariable>,
     pub(super) mounted_files: Vec<MountedFile>,
+    pub(super) artifacts: Option<JobArtifactsTeraContext>,
     pub(super) resource_expiration_in_seconds: Option<i32>,
 }
+
+#[derive(Serialize, Debug, Clone)]
+pub(super) struct JobArtifactsTeraContext {
+    pub(super) uploader_image: String,
+    pub(super) secret_name: String,
+    pub(super) bucket_name: String,
+    pub(super) region: String,
+    pub(super) endpoint: Option<String>,
+    pub(super) access_key_id: String,
+    pub(super) secret_access_key: String,
+    pub(super) prefix: String,
+}
@@ -0,0 +1,149 @@
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::{BucketObject, BucketObjectSummary, ObjectStorage};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+// Artifacts are uploaded by the job uploader sidecar under `jobs/{job_long_id}/{run_id}/{artifact path}`,
+// the run id being the name of the pod which produced them
+const JOB_ARTIFACTS_ROOT: &str = "jobs";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobArtifact {
+    pub job_long_id: Uuid,
+    pub run_id: String,
+    /// Path of the artifact, relative to the artifacts directory of the job
+    pub name: String,
+    pub size_in_bytes: u64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub(crate) key: String,
+}
+
+impl JobArtifact {
+    fn from_object(job_long_id: &Uuid, object: BucketObjectSummary) -> Option<JobArtifact> {
+        let relative_key = object
+            .key
+            .strip_prefix(&job_artifacts_prefix(job_long_id))?
+            .strip_prefix('/')?;
+        let (run_id, name) = relative_key.split_once('/')?;
+        if run_id.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(JobArtifact {
+            job_long_id: *job_long_id,
+            run_id: run_id.to_string(),
+            name: name.to_string(),
+            size_in_bytes: object.size_in_bytes,
+            created_at: object.last_modified,
+            key: object.key,
+        })
+    }
+
+    fn is_expired(&self, retention_in_days: u32, now: DateTime<Utc>) -> bool {
+        match self.created_at {
+            Some(created_at) => created_at + Duration::days(i64::from(retention_in_days)) < now,
+            None => false,
+        }
+    }
+}
+
+pub fn job_artifacts_prefix(job_long_id: &Uuid) -> String {
+    format!("{JOB_ARTIFACTS_ROOT}/{job_long_id}")
+}
+
+/// List artifacts of every run of a job, or of a single run if `run_id` is set
+pub fn list_job_artifacts(
+    object_storage: &dyn ObjectStorage,
+    bucket_name: &str,
+    job_long_id: &Uuid,
+    run_id: Option<&str>,
+) -> Result<Vec<JobArtifact>, ObjectStorageError> {
+    let prefix = match run_id {
+        Some(run_id) => format!("{}/{}/", job_artifacts_prefix(job_long_id), run_id),
+        None => format!("{}/", job_artifacts_prefix(job_long_id)),
+    };
+
+    object_storage
+        .list_objects(bucket_name, Some(&prefix))?
+        .filter_map(|object| match object {
+            Ok(object) => JobArtifact::from_object(job_long_id, object).map(Ok),
+            Err(e) => Some(Err(e)),
+        })
+        .collect()
+}
+
+pub fn fetch_job_artifact(
+    object_storage: &dyn ObjectStorage,
+    bucket_name: &str,
+    artifact: &JobArtifact,
+) -> Result<BucketObject, ObjectStorageError> {
+    object_storage.get_object(bucket_name, &artifact.key)
+}
+
+/// Delete artifacts older than the retention of the job, returns the deleted artifacts
+pub fn delete_expired_job_artifacts(
+    object_storage: &dyn ObjectStorage,
+    bucket_name: &str,
+    job_long_id: &Uuid,
+    retention_in_days: u32,
+    now: DateTime<Utc>,
+) -> Result<Vec<JobArtifact>, ObjectStorageError> {
+    let expired_artifacts: Vec<JobArtifact> = list_job_artifacts(object_storage, bucket_name, job_long_id, None)?
+        .into_iter()
+        .filter(|artifact| artifact.is_expired(retention_in_days, now))
+        .collect();
+
+    for artifact in &expired_artifacts {
+        object_storage.delete_object(bucket_name, &artifact.key)?;
+    }
+
+    Ok(expired_artifacts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn object(key: &str, last_modified: Option<DateTime<Utc>>) -> BucketObjectSummary {
+        BucketObjectSummary {
+            bucket_name: "artifacts".to_string(),
+            key: key.to_string(),
+            size_in_bytes: 42,
+            last_modified,
+        }
+    }
+
+    #[test]
+    fn test_job_artifact_from_object() {
+        let job_long_id = Uuid::new_v4();
+        let prefix = job_artifacts_prefix(&job_long_id);
+
+        let artifact =
+            JobArtifact::from_object(&job_long_id, object(&format!("{prefix}/my-job-x2kq1/reports/out.xml"), None))
+                .unwrap();
+        assert_eq!(artifact.run_id, "my-job-x2kq1");
+        assert_eq!(artifact.name, "reports/out.xml");
+        assert_eq!(artifact.size_in_bytes, 42);
+
+        // not an artifact of this job or without run id
+        assert!(JobArtifact::from_object(&Uuid::new_v4(), object(&format!("{prefix}/run/out.xml"), None)).is_none());
+        assert!(JobArtifact::from_object(&job_long_id, object(&format!("{prefix}/out.xml"), None)).is_none());
+        assert!(JobArtifact::from_object(&job_long_id, object(&format!("{prefix}-other/run/out.xml"), None)).is_none());
+    }
+
+    #[test]
+    fn test_job_artifact_is_expired() {
+        let job_long_id = Uuid::new_v4();
+        let now = Utc.with_ymd_and_hms(2023, 6, 10, 12, 0, 0).unwrap();
+        let key = format!("{}/run/out.xml", job_artifacts_prefix(&job_long_id));
+
+        let recent = JobArtifact::from_object(&job_long_id, object(&key, Some(now - Duration::days(6)))).unwrap();
+        let old = JobArtifact::from_object(&job_long_id, object(&key, Some(now - Duration::days(8)))).unwrap();
+        let unknown = JobArtifact::from_object(&job_long_id, object(&key, None)).unwrap();
+
+        assert!(!recent.is_expired(7, now));
+        assert!(old.is_expired(7, now));
+        assert!(!unknown.is_expired(7, now));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::errors::CommandError;
+use crate::kubers_utils::kube_get_resources_by_selector;
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::batch::v1::Job as K8sJob;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::LogParams;
+use kube::Api;
+use uuid::Uuid;
+
+// Annotation set on jobs created out of a cronjob on demand, same as `kubectl create job --from=cronjob/xxx`
+pub const MANUAL_TRIGGER_ANNOTATION: &str = "cronjob.kubernetes.io/instantiate";
+pub const MANUAL_TRIGGER_ANNOTATION_VALUE: &str = "manual";
+pub const DEFAULT_JOB_RUN_LOGS_MAX_LINES: i64 = 100;
+const JOB_RUN_LOGS_MAX_BYTES: i64 = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JobRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A run of a cronjob. History depth is bounded by the cronjob `*_jobs_history_limit` advanced settings,
+/// as kubernetes garbage collects older runs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JobRun {
+    pub name: String,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Elapsed time until completion, or until now if the run is still running
+    pub duration: Option<Duration>,
+    pub status: JobRunStatus,
+    pub exit_code: Option<i32>,
+    pub triggered_manually: bool,
+    /// Last lines of the run output, None if the pod of the run is already gone
+    pub logs: Option<String>,
+}
+
+impl JobRun {
+    fn from_k8s_job(job: &K8sJob, now: DateTime<Utc>) -> Option<JobRun> {
+        let name = job.metadata.name.clone()?;
+        let status = job.status.clone().unwrap_or_default();
+        let conditions = status.conditions.unwrap_or_default();
+        let terminal_condition = conditions
+            .iter()
+            .find(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True");
+
+        let run_status = match terminal_condition {
+            Some(c) if c.type_ == "Complete" => JobRunStatus::Succeeded,
+            Some(_) => JobRunStatus::Failed,
+            None => JobRunStatus::Running,
+        };
+        let started_at = status.start_time.map(|t| t.0);
+        let finished_at = match run_status {
+            JobRunStatus::Running => Some(now),
+            JobRunStatus::Succeeded | JobRunStatus::Failed => status
+                .completion_time
+                .map(|t| t.0)
+                .or_else(|| terminal_condition.and_then(|c| c.last_transition_time.clone().map(|t| t.0))),
+        };
+        let triggered_manually = job
+            .metadata
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.get(MANUAL_TRIGGER_ANNOTATION))
+            .map(|value| value == MANUAL_TRIGGER_ANNOTATION_VALUE)
+            .unwrap_or(false);
+
+        Some(JobRun {
+            name,
+            started_at,
+            duration: started_at.zip(finished_at).map(|(start, end)| end - start),
+            status: run_status,
+            exit_code: None,
+            triggered_manually,
+            logs: None,
+        })
+    }
+}
+
+fn container_exit_code(pod: &Pod, container_name: &str) -> Option<i32> {
+    pod.status
+        .as_ref()?
+        .container_statuses
+        .as_ref()?
+        .iter()
+        .find(|c| c.name == container_name)?
+        .state
+        .as_ref()?
+        .terminated
+        .as_ref()
+        .map(|terminated| terminated.exit_code)
+}
+
+/// List the runs of a cronjob, most recent first, with their exit code and the last `max_log_lines` lines of logs
+pub async fn list_cronjob_runs(
+    client: &kube::Client,
+    namespace: &str,
+    service_long_id: &Uuid,
+    container_name: &str,
+    max_log_lines: i64,
+) -> Result<Vec<JobRun>, CommandError> {
+    let now = Utc::now();
+    let jobs: Vec<K8sJob> =
+        kube_get_resources_by_selector(client, namespace, &format!("qovery.com/service-id={service_long_id}"))
+            .await?
+            .items;
+
+    let mut runs: Vec<JobRun> = jobs.iter().filter_map(|job| JobRun::from_k8s_job(job, now)).collect();
+    runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+
+    let pod_api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    for run in runs.iter_mut() {
+        let pods: Vec<Pod> = kube_get_resources_by_selector(client, namespace, &format!("job-name={}", run.name))
+            .await?
+            .items;
+        // keep the last attempt of the run, previous ones have been retried
+        let Some(pod) = pods
+            .iter()
+            .max_by_key(|pod| pod.metadata.creation_timestamp.as_ref().map(|t| t.0))
+        else {
+            continue;
+        };
+        let Some(pod_name) = pod.metadata.name.as_deref() else {
+            continue;
+        };
+
+        run.exit_code = container_exit_code(pod, container_name);
+        let log_params = LogParams {
+            container: Some(container_name.to_string()),
+            tail_lines: Some(max_log_lines),
+            limit_bytes: Some(JOB_RUN_LOGS_MAX_BYTES),
+            ..Default::default()
+        };
+        run.logs = match pod_api.logs(pod_name, &log_params).await {
+            Ok(logs) => Some(logs),
+            Err(err) => {
+                warn!("Cannot get logs of job run {}: {}", run.name, err);
+                None
+            }
+        };
+    }
+
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use k8s_openapi::api::batch::v1::{JobCondition, JobStatus};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+    use std::collections::BTreeMap;
+
+    fn k8s_job(start: Option<DateTime<Utc>>, condition: Option<(&str, DateTime<Utc>)>, manual: bool) -> K8sJob {
+        K8sJob {
+            metadata: ObjectMeta {
+                name: Some("my-cron-28356120".to_string()),
+                annotations: manual.then(|| {
+                    BTreeMap::from([(
+                        MANUAL_TRIGGER_ANNOTATION.to_string(),
+                        MANUAL_TRIGGER_ANNOTATION_VALUE.to_string(),
+                    )])
+                }),
+                ..Default::default()
+            },
+            spec: None,
+            status: Some(JobStatus {
+                start_time: start.map(Time),
+                conditions: condition.map(|(type_, at)| {
+                    vec![JobCondition {
+                        type_: type_.to_string(),
+                        status: "True".to_string(),
+                        last_transition_time: Some(Time(at)),
+                        ..Default::default()
+                    }]
+                }),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_job_run_from_k8s_job() {
+        let start = Utc.with_ymd_and_hms(2023, 11, 2, 10, 0, 0).unwrap();
+        let now = start + Duration::minutes(10);
+
+        let running = JobRun::from_k8s_job(&k8s_job(Some(start), None, false), now).unwrap();
+        assert_eq!(running.status, JobRunStatus::Running);
+        assert_eq!(running.duration, Some(Duration::minutes(10)));
+        assert!(!running.triggered_manually);
+
+        let succeeded = JobRun::from_k8s_job(
+            &k8s_job(Some(start), Some(("Complete", start + Duration::seconds(42))), true),
+            now,
+        )
+        .unwrap();
+        assert_eq!(succeeded.status, JobRunStatus::Succeeded);
+        assert_eq!(succeeded.duration, Some(Duration::seconds(42)));
+        assert!(succeeded.triggered_manually);
+
+        let failed =
+            JobRun::from_k8s_job(&k8s_job(None, Some(("Failed", start + Duration::seconds(42))), false), now).unwrap();
+        assert_eq!(failed.status, JobRunStatus::Failed);
+        assert_eq!(failed.duration, None);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::build_platform::{Build, Credentials, SshKey};
+use crate::cloud_provider::models::EnvironmentVariable;
+use crate::cloud_provider::service::{Action, Service, ServiceType};
+use crate::deployment_action::DeploymentAction;
+use crate::events::{EventDetails, Stage, Transmitter};
+use crate::io_models::context::Context;
+use crate::io_models::kustomize::KustomizeImage;
+use crate::io_models::variable_utils::VariableInfo;
+use crate::utilities::to_short_id;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum KustomizeError {
+    #[error("Kustomize invalid configuration: {0}")]
+    InvalidConfig(String),
+}
+
+pub struct KustomizeSource {
+    pub git_url: Url,
+    pub get_credentials: Box<dyn Fn() -> anyhow::Result<Option<Credentials>> + Send + Sync>,
+    pub commit_id: String,
+    pub overlay_path: PathBuf,
+    pub ssh_keys: Vec<SshKey>,
+}
+
+pub struct Kustomize {
+    pub(super) mk_event_details: Box<dyn Fn(Stage) -> EventDetails + Send + Sync>,
+    pub(super) id: String,
+    pub(super) long_id: Uuid,
+    pub(super) name: String,
+    pub(super) kube_name: String,
+    pub(super) action: Action,
+    pub(super) source: KustomizeSource,
+    pub(super) images: Vec<KustomizeImage>,
+    pub(super) timeout: Duration,
+    pub(super) environment_variables: HashMap<String, VariableInfo>,
+    pub(super) workspace_directory: PathBuf,
+}
+
+impl Kustomize {
+    pub fn new(
+        context: &Context,
+        long_id: Uuid,
+        name: String,
+        kube_name: String,
+        action: Action,
+        mut source: KustomizeSource,
+        images: Vec<KustomizeImage>,
+        timeout: Duration,
+        environment_variables: HashMap<String, VariableInfo>,
+        mk_event_details: impl Fn(Transmitter) -> EventDetails,
+    ) -> Result<Self, KustomizeError> {
+        let workspace_directory = crate::fs::workspace_directory(
+            context.workspace_root_dir(),
+            context.execution_id(),
+            format!("kustomizations/{long_id}"),
+        )
+        .map_err(|_| KustomizeError::InvalidConfig("Can't create workspace directory".to_string()))?;
+
+        // The overlay is referenced from the generated kustomization, it must stay inside the cloned repository
+        source.overlay_path = source
+            .overlay_path
+            .components()
+            .filter(|component| !matches!(component, Component::RootDir | Component::CurDir))
+            .collect();
+        if source
+            .overlay_path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            return Err(KustomizeError::InvalidConfig(format!(
+                "Overlay path {:?} must be inside the git repository",
+                source.overlay_path
+            )));
+        }
+
+        if let Some(image) = images
+            .iter()
+            .find(|image| image.name.is_empty() || image.new_tag.is_empty())
+        {
+            return Err(KustomizeError::InvalidConfig(format!(
+                "Image `{}` must have a name and a tag to replace it with",
+                image.name
+            )));
+        }
+
+        let event_details = mk_event_details(Transmitter::Kustomize(long_id, name.to_string()));
+        let mk_event_details = move |stage: Stage| EventDetails::clone_changing_stage(event_details.clone(), stage);
+        Ok(Self {
+            mk_event_details: Box::new(mk_event_details),
+            id: to_short_id(&long_id),
+            long_id,
+            name,
+            kube_name,
+            action,
+            source,
+            images,
+            timeout,
+            environment_variables,
+            workspace_directory,
+        })
+    }
+
+    pub fn source(&self) -> &KustomizeSource {
+        &self.source
+    }
+
+    pub fn workspace_directory(&self) -> &Path {
+        &self.workspace_directory
+    }
+
+    /// Where the git repository is cloned
+    pub fn repository_directory(&self) -> PathBuf {
+        self.workspace_directory.join("repository")
+    }
+
+    /// Where the kustomization patching the overlay with the settings of the engine is written
+    pub fn kustomization_directory(&self) -> PathBuf {
+        self.workspace_directory.join("kustomization")
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn kube_label_selector(&self) -> String {
+        format!("qovery.com/service-id={}", self.long_id)
+    }
+
+    /// Kustomization deploying the overlay in the environment namespace, with the images replaced and the labels
+    /// used to track, prune and delete its resources
+    pub fn kustomization(&self, namespace: &str, environment_id: &Uuid, project_id: &Uuid) -> Kustomization {
+        let labels = BTreeMap::from([
+            ("qovery.com/service-id".to_string(), self.long_id.to_string()),
+            ("qovery.com/service-type".to_string(), "kustomize".to_string()),
+            ("qovery.com/environment-id".to_string(), environment_id.to_string()),
+            ("qovery.com/project-id".to_string(), project_id.to_string()),
+        ]);
+
+        Kustomization {
+            api_version: "kustomize.config.k8s.io/v1beta1",
+            kind: "Kustomization",
+            namespace: namespace.to_string(),
+            resources: vec![Path::new("../repository")
+                .join(&self.source.overlay_path)
+                .to_string_lossy()
+                .to_string()],
+            labels: vec![KustomizationLabels {
+                pairs: labels,
+                include_selectors: false,
+                include_templates: true,
+            }],
+            images: self
+                .images
+                .iter()
+                .map(|image| KustomizationImage {
+                    name: image.name.clone(),
+                    new_name: image.new_name.clone(),
+                    new_tag: image.new_tag.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Kustomization {
+    pub api_version: &'static str,
+    pub kind: &'static str,
+    pub namespace: String,
+    pub resources: Vec<String>,
+    pub labels: Vec<KustomizationLabels>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<KustomizationImage>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KustomizationLabels {
+    pub pairs: BTreeMap<String, String>,
+    // selectors are immutable, adding labels to them would prevent updating the workloads deployed before
+    pub include_selectors: bool,
+    pub include_templates: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KustomizationImage {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_name: Option<String>,
+    pub new_tag: String,
+}
+
+impl Service for Kustomize {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Kustomize
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> String {
+        self.source.commit_id.clone()
+    }
+
+    fn kube_name(&self) -> &str {
+        &self.kube_name
+    }
+
+    fn kube_label_selector(&self) -> String {
+        self.kube_label_selector()
+    }
+
+    fn get_event_details(&self, stage: Stage) -> EventDetails {
+        (self.mk_event_details)(stage)
+    }
+
+    fn action(&self) -> &Action {
+        &self.action
+    }
+
+    fn as_service(&self) -> &dyn Service {
+        self
+    }
+
+    fn as_service_mut(&mut self) -> &mut dyn Service {
+        self
+    }
+
+    fn build(&self) -> Option<&Build> {
+        None
+    }
+
+    fn build_mut(&mut self) -> Option<&mut Build> {
+        None
+    }
+
+    fn get_environment_variables(&self) -> Vec<EnvironmentVariable> {
+        self.environment_variables
+            .iter()
+            .map(|(key, variable_infos)| EnvironmentVariable {
+                key: key.clone(),
+                value: variable_infos.value.clone(),
+                is_secret: variable_infos.is_secret,
+            })
+            .collect()
+    }
+
+    fn get_passwords(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+pub trait KustomizeService: Service + DeploymentAction + Send {
+    fn as_deployment_action(&self) -> &dyn DeploymentAction;
+}
+
+impl KustomizeService for Kustomize {
+    fn as_deployment_action(&self) -> &dyn DeploymentAction {
+        self
+    }
+}
@@ -3,17 +3,23 @@ pub mod aws;
 pub mod aws_ec2;
 pub mod container;
 pub mod database;
+pub mod deployed_release;
 pub(crate) mod database_utils;
 pub mod domain;
 pub mod gcp;
 pub mod helm_chart;
 pub mod job;
+pub mod job_artifacts;
+pub mod job_runs;
 pub mod kubernetes;
+pub mod kustomize;
 pub mod probe;
+pub mod readiness_gates;
 pub mod registry_image_source;
 pub mod router;
 pub mod scaleway;
 pub mod selfmanaged;
+pub mod terraform_service;
 pub mod third_parties;
 pub mod types;
 pub mod utils;
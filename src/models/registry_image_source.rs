@@ -1,4 +1,4 @@
-use crate::cloud_provider::io::RegistryMirroringMode;
+use crate::cloud_provider::io::{ClusterAdvancedSettings, RegistryMirroringMode};
 use crate::io_models::container::Registry;
 use crate::string::cut;
 use uuid::Uuid;
@@ -21,4 +21,109 @@ impl RegistryImageSource {
             RegistryMirroringMode::Cluster => cut(format!("{}.{}", self.image.replace('/', "."), self.tag), 128),
         }
     }
+
+    /// Public Docker Hub images are pulled through the cluster proxy, or with the cluster account, to avoid
+    /// anonymous pull rate limits. Images of users having their own Docker Hub credentials are left untouched.
+    pub fn with_cluster_docker_hub_settings(mut self, settings: &ClusterAdvancedSettings) -> Self {
+        let (long_id, url) = match &self.registry {
+            Registry::DockerHub {
+                long_id,
+                url,
+                credentials: None,
+            } => (*long_id, url.clone()),
+            _ => return self,
+        };
+
+        if let Some(proxy_url) = &settings.registry_docker_hub_proxy_url {
+            self.registry = Registry::GenericCr {
+                long_id,
+                url: proxy_url.clone(),
+                credentials: None,
+            };
+            self.image = to_docker_hub_repository(&self.image);
+        } else if let Some(credentials) = settings.docker_hub_credentials() {
+            self.registry = Registry::DockerHub {
+                long_id,
+                url,
+                credentials: Some(credentials),
+            };
+        }
+
+        self
+    }
+}
+
+// Official images are implicitly under `library/` on Docker Hub, proxies expect the full repository name
+fn to_docker_hub_repository(image: &str) -> String {
+    let image = image
+        .trim_start_matches("docker.io/")
+        .trim_start_matches("index.docker.io/")
+        .trim_start_matches("registry-1.docker.io/");
+    match image.contains('/') {
+        true => image.to_string(),
+        false => format!("library/{image}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_models::container::Credentials;
+    use url::Url;
+
+    fn docker_hub_source(image: &str, credentials: Option<Credentials>) -> RegistryImageSource {
+        RegistryImageSource {
+            registry: Registry::DockerHub {
+                long_id: Uuid::nil(),
+                url: Url::parse("https://docker.io").unwrap(),
+                credentials,
+            },
+            image: image.to_string(),
+            tag: "latest".to_string(),
+            registry_mirroring_mode: RegistryMirroringMode::Service,
+        }
+    }
+
+    fn credentials(login: &str) -> Credentials {
+        Credentials {
+            login: login.to_string(),
+            password: "password".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_to_docker_hub_repository() {
+        assert_eq!(to_docker_hub_repository("nginx"), "library/nginx");
+        assert_eq!(to_docker_hub_repository("docker.io/nginx"), "library/nginx");
+        assert_eq!(to_docker_hub_repository("bitnami/redis"), "bitnami/redis");
+    }
+
+    #[test]
+    fn test_with_cluster_docker_hub_settings() {
+        let proxy_url = Url::parse("https://mirror.gcr.io").unwrap();
+        let mut settings = ClusterAdvancedSettings {
+            registry_docker_hub_login: Some("qovery".to_string()),
+            registry_docker_hub_password: Some("password".to_string()),
+            ..Default::default()
+        };
+
+        // cluster account is used for anonymous pulls
+        let source = docker_hub_source("nginx", None).with_cluster_docker_hub_settings(&settings);
+        assert_eq!(source.image, "nginx");
+        assert!(matches!(source.registry, Registry::DockerHub { credentials: Some(c), .. } if c.login == "qovery"));
+
+        // users credentials are kept
+        let source = docker_hub_source("nginx", Some(credentials("user"))).with_cluster_docker_hub_settings(&settings);
+        assert!(matches!(source.registry, Registry::DockerHub { credentials: Some(c), .. } if c.login == "user"));
+
+        // proxy takes precedence over the cluster account
+        settings.registry_docker_hub_proxy_url = Some(proxy_url.clone());
+        let source = docker_hub_source("nginx", None).with_cluster_docker_hub_settings(&settings);
+        assert_eq!(source.image, "library/nginx");
+        assert!(matches!(source.registry, Registry::GenericCr { url, credentials: None, .. } if url == proxy_url));
+
+        let source = docker_hub_source("nginx", Some(credentials("user"))).with_cluster_docker_hub_settings(&settings);
+        assert_eq!(source.image, "nginx");
+        assert!(matches!(source.registry, Registry::DockerHub { .. }));
+    }
 }
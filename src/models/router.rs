@@ -1,6 +1,6 @@
 use crate::build_platform::Build;
 use crate::cloud_provider::models::{
-    CustomDomain, CustomDomainDataTemplate, EnvironmentVariable, HostDataTemplate, Route,
+    CustomDomain, CustomDomainDataTemplate, EnvironmentVariable, HostDataTemplate, L4Route, Route,
 };
 use crate::cloud_provider::service::{default_tera_context, Action, Service, ServiceType};
 use crate::cloud_provider::DeploymentTarget;
@@ -9,15 +9,21 @@ use crate::errors::EngineError;
 use crate::events::{EnvironmentStep, EventDetails, Stage, Transmitter};
 use crate::io_models::application::{Port, Protocol};
 use crate::io_models::context::Context;
+use crate::io_models::{CustomMetadata, StickySessionHashPolicy, WafMode};
 use crate::models::types::CloudProvider;
 use crate::models::types::ToTeraContext;
+use crate::models::utils::validate_custom_metadata;
+use crate::naming;
 use crate::utilities::to_short_id;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::iter;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use tera::Context as TeraContext;
 use uuid::Uuid;
+use x509_parser::pem::Pem;
 
 #[derive(thiserror::Error, Debug)]
 pub enum RouterError {
@@ -32,11 +38,200 @@ pub enum RouterError {
     BasicAuthEnvVarNotFound { env_var_name: String },
 }
 
+/// Cookie based session affinity, the requests of a session keep reaching the same instance of the service
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StickySession {
+    pub cookie_name: String,
+    pub cookie_max_age_seconds: u32,
+    pub hash_policy: StickySessionHashPolicy,
+}
+
+/// Limits of the requests each client IP sends to the routes of the router, enforced by nginx
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RouteLimits {
+    pub requests_per_second: Option<u32>,
+    /// Requests allowed in a burst, as a multiple of the requests per second
+    pub burst_multiplier: u32,
+    pub max_connections: Option<u32>,
+}
+
+impl RouteLimits {
+    /// Same as the one of nginx, when no burst is set
+    pub const DEFAULT_BURST_MULTIPLIER: u32 = 5;
+
+    /// Limits from the advanced settings of the routed service, where 0 means no limit.
+    /// The burst is rounded up to a multiple of the requests per second, which it cannot be below.
+    pub fn new(requests_per_second: u32, burst: u32, max_connections: u32) -> Result<Option<Self>, RouterError> {
+        let burst_multiplier = match (requests_per_second, burst) {
+            (_, 0) => Self::DEFAULT_BURST_MULTIPLIER,
+            (0, _) => {
+                return Err(RouterError::InvalidConfig(
+                    "A burst of requests is set without a limit of requests per second".to_string(),
+                ))
+            }
+            (rps, burst) if burst < rps => {
+                return Err(RouterError::InvalidConfig(format!(
+                    "The burst of requests ({burst}) cannot be below the limit of requests per second ({rps})"
+                )))
+            }
+            (rps, burst) => burst.div_ceil(rps),
+        };
+        if requests_per_second == 0 && max_connections == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(RouteLimits {
+            requests_per_second: (requests_per_second > 0).then_some(requests_per_second),
+            burst_multiplier,
+            max_connections: (max_connections > 0).then_some(max_connections),
+        }))
+    }
+}
+
+/// Web application firewall of the routes of the router, instead of the one of the cluster
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouterWaf {
+    pub mode: WafMode,
+    /// ModSecurity rules added to the OWASP core rule set
+    pub custom_rules: Vec<String>,
+}
+
+/// Mutual TLS of the routes, clients must present a certificate issued by one of the authorities of the bundle
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ClientTls {
+    pub ca_bundle_pem: String,
+    /// Maximum length of the chain between the client certificate and an authority of the bundle
+    pub verify_depth: u32,
+    /// The client certificate is forwarded to the service in the `ssl-client-cert` header
+    pub pass_certificate_to_upstream: bool,
+}
+
+impl ClientTls {
+    pub fn new(
+        ca_bundle_pem: Option<String>,
+        verify_depth: u32,
+        pass_certificate_to_upstream: bool,
+    ) -> Result<Self, RouterError> {
+        let ca_bundle_pem = ca_bundle_pem.filter(|pem| !pem.trim().is_empty()).ok_or_else(|| {
+            RouterError::InvalidConfig("Client certificates are required but the router has no CA bundle".to_string())
+        })?;
+        if verify_depth == 0 {
+            return Err(RouterError::InvalidConfig(
+                "Verification depth of client certificates must be at least 1".to_string(),
+            ));
+        }
+        let invalid_bundle =
+            || RouterError::InvalidConfig("CA bundle of the router is not PEM certificates".to_string());
+        let mut certificates = 0;
+        for pem in Pem::iter_from_buffer(ca_bundle_pem.as_bytes()) {
+            match pem {
+                Ok(pem) if pem.parse_x509().is_ok() => certificates += 1,
+                _ => return Err(invalid_bundle()),
+            }
+        }
+        if certificates == 0 {
+            return Err(invalid_bundle());
+        }
+
+        Ok(ClientTls {
+            ca_bundle_pem,
+            verify_depth,
+            pass_certificate_to_upstream,
+        })
+    }
+}
+
+/// Annotations of the ingress of the router set by the user, on top of the ones set by the engine
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NginxAnnotations(BTreeMap<String, String>);
+
+impl NginxAnnotations {
+    const PREFIX: &'static str = "nginx.ingress.kubernetes.io/";
+    const CONFIGURATION_SNIPPET: &'static str = "configuration-snippet";
+    const SERVER_SNIPPET: &'static str = "server-snippet";
+    /// Annotations the engine never sets, and which cannot reach other ingresses, services or the controller itself
+    const ALLOWED: &'static [&'static str] = &[
+        "app-root",
+        "client-body-buffer-size",
+        "custom-http-errors",
+        "enable-access-log",
+        "enable-rewrite-log",
+        "force-ssl-redirect",
+        "from-to-www-redirect",
+        "load-balance",
+        "permanent-redirect-code",
+        "proxy-buffers-number",
+        "proxy-cookie-domain",
+        "proxy-cookie-path",
+        "proxy-http-version",
+        "proxy-max-temp-file-size",
+        "proxy-next-upstream",
+        "proxy-next-upstream-timeout",
+        "proxy-next-upstream-tries",
+        "proxy-redirect-from",
+        "proxy-redirect-to",
+        "ssl-ciphers",
+        "ssl-prefer-server-ciphers",
+        "temporal-redirect",
+        "upstream-hash-by",
+        "x-forwarded-prefix",
+    ];
+
+    /// Snippets hold raw nginx configuration, they are only accepted when the cluster allows them
+    pub fn new(annotations: BTreeMap<String, String>, snippets_allowed: bool) -> Result<Self, RouterError> {
+        for key in annotations.keys() {
+            let name = key.strip_prefix(Self::PREFIX).ok_or_else(|| {
+                RouterError::InvalidConfig(format!(
+                    "Annotation `{key}` is not an nginx ingress one, it must start with `{}`",
+                    Self::PREFIX
+                ))
+            })?;
+            let is_snippet = name == Self::CONFIGURATION_SNIPPET || name == Self::SERVER_SNIPPET;
+            if is_snippet && !snippets_allowed {
+                return Err(RouterError::InvalidConfig(format!(
+                    "Annotation `{key}` is a snippet, snippets must be enabled on the cluster to use it"
+                )));
+            }
+            if !is_snippet && !Self::ALLOWED.contains(&name) {
+                return Err(RouterError::InvalidConfig(format!(
+                    "Annotation `{key}` is not allowed on routers"
+                )));
+            }
+        }
+
+        Ok(NginxAnnotations(annotations))
+    }
+
+    /// Snippets are appended to the ones of the engine, an ingress cannot have the same annotation twice
+    fn snippet(&self, name: &str) -> Option<&str> {
+        self.0.get(&format!("{}{name}", Self::PREFIX)).map(String::as_str)
+    }
+
+    fn without_snippets(&self) -> BTreeMap<&str, &str> {
+        self.0
+            .iter()
+            .filter(|(key, _)| {
+                let name = key.trim_start_matches(Self::PREFIX);
+                name != Self::CONFIGURATION_SNIPPET && name != Self::SERVER_SNIPPET
+            })
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+}
+
 pub struct RouterAdvancedSettings {
     pub custom_domain_check_enabled: bool,
     pub whitelist_source_range: Option<String>,
     pub denylist_source_range: Option<String>,
     pub basic_auth: Option<String>,
+    pub probe_enabled: bool,
+    /// Status code each route must answer with once deployed, by route path
+    pub probe_expected_status_codes: BTreeMap<String, u16>,
+    pub sticky_session: Option<StickySession>,
+    pub limits: Option<RouteLimits>,
+    pub waf: Option<RouterWaf>,
+    pub nginx_annotations: NginxAnnotations,
+    pub client_tls: Option<ClientTls>,
 }
 
 impl Default for RouterAdvancedSettings {
@@ -46,6 +241,13 @@ impl Default for RouterAdvancedSettings {
             whitelist_source_range: None,
             denylist_source_range: None,
             basic_auth: None,
+            probe_enabled: true,
+            probe_expected_status_codes: BTreeMap::new(),
+            sticky_session: None,
+            limits: None,
+            waf: None,
+            nginx_annotations: NginxAnnotations::default(),
+            client_tls: None,
         }
     }
 }
@@ -64,6 +266,13 @@ impl RouterAdvancedSettings {
             whitelist_source_range: definitive_whitelist,
             denylist_source_range,
             basic_auth,
+            probe_enabled: true,
+            probe_expected_status_codes: BTreeMap::new(),
+            sticky_session: None,
+            limits: None,
+            waf: None,
+            nginx_annotations: NginxAnnotations::default(),
+            client_tls: None,
         }
     }
 
@@ -83,8 +292,10 @@ pub struct Router<T: CloudProvider> {
     pub(crate) default_domain: String,
     pub(crate) custom_domains: Vec<CustomDomain>,
     pub(crate) routes: Vec<Route>,
+    pub(crate) l4_routes: Vec<L4Route>,
     pub(crate) _extra_settings: T::RouterExtraSettings,
     pub(crate) advanced_settings: RouterAdvancedSettings,
+    pub(crate) custom_metadata: CustomMetadata,
     pub(super) workspace_directory: PathBuf,
     pub(super) lib_root_directory: String,
 }
@@ -99,10 +310,14 @@ impl<T: CloudProvider> Router<T> {
         default_domain: &str,
         custom_domains: Vec<CustomDomain>,
         routes: Vec<Route>,
+        l4_routes: Vec<L4Route>,
         extra_settings: T::RouterExtraSettings,
         advanced_settings: RouterAdvancedSettings,
+        custom_metadata: CustomMetadata,
         mk_event_details: impl Fn(Transmitter) -> EventDetails,
     ) -> Result<Self, RouterError> {
+        validate_custom_metadata(&custom_metadata).map_err(RouterError::InvalidConfig)?;
+
         let workspace_directory = crate::fs::workspace_directory(
             context.workspace_root_dir(),
             context.execution_id(),
@@ -123,8 +338,10 @@ impl<T: CloudProvider> Router<T> {
             default_domain: default_domain.to_string(),
             custom_domains,
             routes,
+            l4_routes,
             _extra_settings: extra_settings,
             advanced_settings,
+            custom_metadata,
             workspace_directory,
             lib_root_directory: context.lib_root_dir().to_string(),
         })
@@ -134,6 +351,14 @@ impl<T: CloudProvider> Router<T> {
         format!("qovery.com/service-id={}", self.long_id)
     }
 
+    /// Service of the routes, a router never mixes several services, whether its routes are HTTP or L4 ones
+    fn routed_service_id(&self) -> Option<Uuid> {
+        self.routes
+            .first()
+            .map(|route| route.service_long_id)
+            .or_else(|| self.l4_routes.first().map(|route| route.service_long_id))
+    }
+
     pub fn workspace_directory(&self) -> &str {
         self.workspace_directory.to_str().unwrap_or("")
     }
@@ -150,10 +375,8 @@ impl<T: CloudProvider> Router<T> {
         // We can only have 1 router per application/container.
         // Core never mix multiple services inside one router
         let service_id = self
-            .routes
-            .first()
-            .ok_or_else(|| EngineError::new_router_failed_to_deploy(event_details.clone()))?
-            .service_long_id;
+            .routed_service_id()
+            .ok_or_else(|| EngineError::new_router_failed_to_deploy(event_details.clone()))?;
 
         // Check if the service is an application
         let (service_name, ports) =
@@ -192,6 +415,20 @@ impl<T: CloudProvider> Router<T> {
 
         // inject basic auth data
         context.insert("basic_auth_htaccess", &self.advanced_settings.basic_auth);
+        context.insert("sticky_session", &self.advanced_settings.sticky_session);
+        context.insert("route_limits", &self.advanced_settings.limits);
+        context.insert("waf", &self.advanced_settings.waf.as_ref().map(to_waf_data_template));
+        context.insert("client_tls", &self.advanced_settings.client_tls);
+        let nginx_annotations = &self.advanced_settings.nginx_annotations;
+        context.insert("custom_nginx_annotations", &nginx_annotations.without_snippets());
+        context.insert(
+            "custom_configuration_snippet",
+            &nginx_annotations.snippet(NginxAnnotations::CONFIGURATION_SNIPPET),
+        );
+        context.insert(
+            "custom_server_snippet",
+            &nginx_annotations.snippet(NginxAnnotations::SERVER_SNIPPET),
+        );
 
         // Get the alternative names we need to generate for the certificate
         // For custom domain, we need to generate a subdomain for each port. p80.mydomain.com, p443.mydomain.com
@@ -200,17 +437,24 @@ impl<T: CloudProvider> Router<T> {
             "certificate_alternative_names",
             &generate_certificate_alternative_names(&self.custom_domains, &cluster_domain, &ports),
         );
+        context.insert(
+            "custom_certificates",
+            &to_custom_certificate_data_template(&self.id, &self.custom_domains, &ports),
+        );
 
-        let http_ports: Vec<&Port> = ports
-            .iter()
-            .filter(|port| port.protocol == Protocol::HTTP)
-            .cloned()
-            .collect();
-        let grpc_ports: Vec<&Port> = ports
+        context.insert("url_rewrite", &to_url_rewrite_data_template(self.routes.first()));
+
+        // HTTP ports of a service speaking HTTP/2 are proxied like the gRPC ones, nginx talks HTTP/1.1 to the others
+        let backend_protocol = self
+            .routes
+            .first()
+            .map(|route| route.backend_protocol)
+            .unwrap_or_default();
+        let (grpc_ports, http_ports): (Vec<&Port>, Vec<&Port>) = ports
             .iter()
-            .filter(|port| port.protocol == Protocol::GRPC)
-            .cloned()
-            .collect();
+            .copied()
+            .filter(|port| port.protocol == Protocol::HTTP || port.protocol == Protocol::GRPC)
+            .partition(|port| port.protocol == Protocol::GRPC || backend_protocol.is_http2());
         let cluster_domain = target.dns_provider.domain().to_string();
         let http_hosts_per_namespace = to_host_data_template(
             service_name,
@@ -231,6 +475,17 @@ impl<T: CloudProvider> Router<T> {
         context.insert("has_wildcard_domain", &self.custom_domains.iter().any(|d| d.is_wildcard()));
         context.insert("http_hosts_per_namespace", &http_hosts_per_namespace);
         context.insert("grpc_hosts_per_namespace", &grpc_hosts_per_namespace);
+        // certificates provided by the user are stored next to the ingresses using them
+        let tls_secret_namespaces: BTreeSet<&String> = http_hosts_per_namespace
+            .keys()
+            .chain(grpc_hosts_per_namespace.keys())
+            .collect();
+        context.insert("tls_secret_namespaces", &tls_secret_namespaces);
+        context.insert(
+            "l4_services",
+            &to_l4_service_data_template(&self.l4_routes, &self.default_domain),
+        );
+        context.insert("loadbalancer_l4_annotations", T::loadbalancer_l4_annotations());
 
         let lets_encrypt_url = match target.is_test_cluster {
             true => "https://acme-staging-v02.api.letsencrypt.org/directory",
@@ -242,7 +497,7 @@ impl<T: CloudProvider> Router<T> {
     }
 
     pub fn helm_release_name(&self) -> String {
-        crate::string::cut(format!("router-{}", self.id), 50)
+        naming::helm_release_name("router", &self.id)
     }
 
     pub fn helm_chart_dir(&self) -> String {
@@ -351,6 +606,94 @@ fn to_host_data_template(
     hosts_per_namespace
 }
 
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct L4PortDataTemplate {
+    port: u16,
+    target_port: u16,
+}
+
+/// Load balancer exposing the routes of a protocol
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct L4ServiceDataTemplate {
+    protocol: Protocol,
+    ports: Vec<L4PortDataTemplate>,
+    hostnames: Vec<String>,
+}
+
+fn to_l4_service_data_template(l4_routes: &[L4Route], default_domain: &str) -> Vec<L4ServiceDataTemplate> {
+    [Protocol::TCP, Protocol::UDP]
+        .into_iter()
+        .filter_map(|protocol| {
+            let ports = l4_routes
+                .iter()
+                .filter(|route| route.protocol == protocol)
+                .map(|route| L4PortDataTemplate {
+                    port: route.port,
+                    target_port: route.target_port,
+                })
+                .collect::<Vec<_>>();
+            if ports.is_empty() {
+                return None;
+            }
+
+            Some(L4ServiceDataTemplate {
+                protocol,
+                ports,
+                hostnames: vec![default_domain.to_string()],
+            })
+        })
+        .collect()
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct WafDataTemplate {
+    enabled: bool,
+    rule_engine: &'static str,
+    custom_rules: Vec<String>,
+}
+
+fn to_waf_data_template(waf: &RouterWaf) -> WafDataTemplate {
+    WafDataTemplate {
+        enabled: waf.mode != WafMode::Disabled,
+        rule_engine: waf.mode.rule_engine(),
+        custom_rules: waf.custom_rules.clone(),
+    }
+}
+
+/// URL normalization of the route, done by nginx instead of a proxy in front of the service
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct UrlRewriteDataTemplate {
+    path: String,
+    path_type: &'static str,
+    rewrite_target: Option<String>,
+    permanent_redirect: Option<String>,
+    trailing_slash_rule: Option<&'static str>,
+}
+
+fn to_url_rewrite_data_template(route: Option<&Route>) -> UrlRewriteDataTemplate {
+    let mut url_rewrite = UrlRewriteDataTemplate {
+        path: "/".to_string(),
+        path_type: "Prefix",
+        rewrite_target: None,
+        permanent_redirect: route.and_then(|route| route.permanent_redirect.clone()),
+        trailing_slash_rule: route.and_then(|route| route.trailing_slash.rewrite_rule()),
+    };
+
+    // The rest of the path is captured, to be appended to the target, i.e: `/api/users` to `/v2/users` for `/api`
+    let rewrite = route.and_then(|route| Some((route.path.trim_matches('/'), route.rewrite_target.as_ref()?)));
+    if let Some((route_path, rewrite_target)) = rewrite {
+        url_rewrite.path = if route_path.is_empty() {
+            "/(.*)".to_string()
+        } else {
+            format!("/{}(?:/|$)(.*)", regex::escape(route_path))
+        };
+        url_rewrite.path_type = "ImplementationSpecific";
+        url_rewrite.rewrite_target = Some(format!("{}/$1", rewrite_target.trim_end_matches('/')));
+    }
+
+    url_rewrite
+}
+
 fn get_ports_by_namespace(ports: &[&Port]) -> HashMap<Option<String>, Vec<Port>> {
     let mut ports_by_namespace: HashMap<Option<String>, Vec<Port>> = HashMap::new();
     for &port in ports {
@@ -367,6 +710,47 @@ fn get_service_name(port: &Port, default_service_name: &str) -> String {
         .unwrap_or_else(|| default_service_name.to_string())
 }
 
+/// Whether a certificate has to be generated for the custom domain
+pub(crate) fn requires_certificate(custom_domain: &CustomDomain, cluster_domain: &str) -> bool {
+    // we filter out domain that belongs to our cluster, we dont need to create certificate for them
+    // we keep wildcard domains, as we will need to create certificate for them
+    // we also filter out domains coming with their own certificate
+    (custom_domain.is_wildcard() || !custom_domain.domain.ends_with(cluster_domain))
+        && custom_domain.generate_certificate
+        && custom_domain.certificate.is_none()
+}
+
+/// Certificates provided by the user are not renewed by cert-manager, they are reported once this close to expire
+const CUSTOM_CERTIFICATE_EXPIRY_WARNING_DAYS: i64 = 30;
+
+pub(crate) fn custom_certificate_warnings(custom_domains: &[CustomDomain], now: DateTime<Utc>) -> Vec<String> {
+    custom_domains
+        .iter()
+        .filter_map(|cd| {
+            let expires_at = match cd.certificate.as_ref()?.expires_at() {
+                Ok(expires_at) => expires_at,
+                Err(err) => return Some(format!("Certificate of custom domain {} is invalid: {err}", cd.domain)),
+            };
+            let days_left = (expires_at - now).num_days();
+            if expires_at <= now {
+                Some(format!(
+                    "Certificate of custom domain {} expired on {}, clients will reject it",
+                    cd.domain,
+                    expires_at.format("%Y-%m-%d")
+                ))
+            } else if days_left < CUSTOM_CERTIFICATE_EXPIRY_WARNING_DAYS {
+                Some(format!(
+                    "Certificate of custom domain {} expires in {days_left} day(s), on {}, it must be renewed",
+                    cd.domain,
+                    expires_at.format("%Y-%m-%d")
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 // Generating certificates correctly is tricky
 // Ideally we would like to always generate a certificate for the root domain (I.e: example.com) and all the subdomains (I.e: *.example.com)
 // But we have limited access to the dns of the clients. So we can't always generate a wildcard certificate for the domain.
@@ -384,35 +768,67 @@ fn generate_certificate_alternative_names(
 
     custom_domains
         .iter()
-        // we filter out domain that belongs to our cluster, we dont need to create certificate for them
-        // we keep wildcard domains, as we will need to create certificate for them
-        .filter(|domain| {
-            (domain.is_wildcard() || !domain.domain.ends_with(&cluster_domain)) && domain.generate_certificate
+        .filter(|domain| requires_certificate(domain, cluster_domain))
+        .flat_map(|cd| certificate_hosts(cd, ports))
+        .collect::<Vec<_>>()
+}
+
+fn certificate_hosts(cd: &CustomDomain, ports: &[&Port]) -> Vec<CustomDomainDataTemplate> {
+    // We always want the root domain to be in the certificate (I.e: example.com, or if *.example.com -> example.com)
+    let default_domain = CustomDomainDataTemplate {
+        domain: cd.domain_without_wildcard().to_string(),
+    };
+
+    // If it is a wildcard domain, we want to generate the wildcard certificate (*.example.com)
+    // if there is a single public port, we can use only the default domain and don't generate subdomains for each port. (to avoid migration for clients)
+    iter::once(default_domain)
+        .chain(if cd.is_wildcard() {
+            vec![CustomDomainDataTemplate {
+                domain: cd.domain.to_string(),
+            }]
+        } else if ports.len() == 1 {
+            vec![]
+        } else {
+            ports
+                .iter()
+                .map(|port| CustomDomainDataTemplate {
+                    domain: format!("{}.{}", port.name, cd.domain),
+                })
+                .collect()
         })
-        .flat_map(|cd| {
-            // We always want the root domain to be in the certificate (I.e: example.com, or if *.example.com -> example.com)
-            let default_domain = CustomDomainDataTemplate {
-                domain: cd.domain_without_wildcard().to_string(),
-            };
+        .collect()
+}
 
-            // If it is a wildcard domain, we want to generate the wildcard certificate (*.example.com)
-            // if there is a single public port, we can use only the default domain and don't generate subdomains for each port. (to avoid migration for clients)
-            iter::once(default_domain).chain(if cd.is_wildcard() {
-                vec![CustomDomainDataTemplate {
-                    domain: cd.domain.to_string(),
-                }]
-            } else if ports.len() == 1 {
-                vec![]
-            } else {
-                ports
-                    .iter()
-                    .map(|port| CustomDomainDataTemplate {
-                        domain: format!("{}.{}", port.name, cd.domain),
-                    })
-                    .collect()
+/// TLS secret of a custom domain whose certificate is provided by the user
+#[derive(Serialize)]
+struct CustomCertificateDataTemplate {
+    secret_name: String,
+    hosts: Vec<CustomDomainDataTemplate>,
+    certificate_pem: String,
+    private_key_pem: String,
+}
+
+fn to_custom_certificate_data_template(
+    router_id: &str,
+    custom_domains: &[CustomDomain],
+    ports: &[&Port],
+) -> Vec<CustomCertificateDataTemplate> {
+    if ports.is_empty() {
+        return vec![];
+    }
+
+    custom_domains
+        .iter()
+        .filter_map(|cd| {
+            let certificate = cd.certificate.as_ref()?;
+            Some(CustomCertificateDataTemplate {
+                secret_name: format!("router-tls-{}-{}", router_id, cd.domain_without_wildcard().to_lowercase()),
+                hosts: certificate_hosts(cd, ports),
+                certificate_pem: certificate.certificate_pem.clone(),
+                private_key_pem: certificate.private_key_pem.clone(),
             })
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
 impl<T: CloudProvider> Service for Router<T> {
@@ -499,24 +915,216 @@ where
     }
 
     fn associated_service_id(&self) -> Option<Uuid> {
-        self.routes.first().map(|route| route.service_long_id)
+        self.routed_service_id()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RouterAdvancedSettings;
-    use crate::cloud_provider::models::{CustomDomain, CustomDomainDataTemplate, HostDataTemplate};
+    use super::{
+        custom_certificate_warnings, requires_certificate, ClientTls, NginxAnnotations, RouteLimits,
+        RouterAdvancedSettings,
+    };
+    use crate::cloud_provider::models::{
+        CustomCertificate, CustomDomain, CustomDomainDataTemplate, HostDataTemplate, L4Route, Route,
+    };
     use crate::io_models::application::{Port, Protocol};
-    use crate::models::router::{generate_certificate_alternative_names, to_host_data_template};
+    use crate::io_models::router::{BackendProtocol, TrailingSlash};
+    use crate::models::router::{
+        generate_certificate_alternative_names, to_host_data_template, to_l4_service_data_template,
+        to_url_rewrite_data_template, L4PortDataTemplate, L4ServiceDataTemplate, UrlRewriteDataTemplate,
+    };
+    use chrono::{DateTime, Duration};
+    use std::collections::BTreeMap;
+    use uuid::Uuid;
 
     #[test]
     pub fn test_router_advanced_settings() {
         // this should be true by default
         let router_advanced_settings_defaults = RouterAdvancedSettings::default();
         assert!(router_advanced_settings_defaults.custom_domain_check_enabled);
+        assert_eq!(router_advanced_settings_defaults.sticky_session, None);
+        assert_eq!(router_advanced_settings_defaults.limits, None);
     }
 
+    #[test]
+    pub fn test_route_limits() {
+        assert_eq!(RouteLimits::new(0, 0, 0).unwrap(), None);
+        assert_eq!(
+            RouteLimits::new(10, 0, 0).unwrap(),
+            Some(RouteLimits {
+                requests_per_second: Some(10),
+                burst_multiplier: RouteLimits::DEFAULT_BURST_MULTIPLIER,
+                max_connections: None,
+            })
+        );
+        assert_eq!(
+            RouteLimits::new(10, 25, 100).unwrap(),
+            Some(RouteLimits {
+                requests_per_second: Some(10),
+                burst_multiplier: 3,
+                max_connections: Some(100),
+            })
+        );
+        assert_eq!(
+            RouteLimits::new(0, 0, 100).unwrap(),
+            Some(RouteLimits {
+                requests_per_second: None,
+                burst_multiplier: RouteLimits::DEFAULT_BURST_MULTIPLIER,
+                max_connections: Some(100),
+            })
+        );
+        assert!(RouteLimits::new(0, 20, 0).is_err());
+        assert!(RouteLimits::new(0, 20, 100).is_err());
+        assert!(RouteLimits::new(10, 5, 0).is_err());
+    }
+
+    #[test]
+    pub fn test_nginx_annotations() {
+        let annotations = |keys: &[&str]| {
+            keys.iter()
+                .map(|key| (format!("nginx.ingress.kubernetes.io/{key}"), "value".to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let nginx_annotations =
+            NginxAnnotations::new(annotations(&["proxy-http-version", "configuration-snippet"]), true).unwrap();
+        assert_eq!(
+            nginx_annotations.without_snippets(),
+            BTreeMap::from([("nginx.ingress.kubernetes.io/proxy-http-version", "value")])
+        );
+        assert_eq!(
+            nginx_annotations.snippet(NginxAnnotations::CONFIGURATION_SNIPPET),
+            Some("value")
+        );
+        assert_eq!(nginx_annotations.snippet(NginxAnnotations::SERVER_SNIPPET), None);
+
+        // snippets must be enabled on the cluster
+        assert!(NginxAnnotations::new(annotations(&["server-snippet"]), false).is_err());
+        // annotations already set by the engine, or reaching beyond the router, are rejected
+        assert!(NginxAnnotations::new(annotations(&["proxy-body-size"]), true).is_err());
+        assert!(NginxAnnotations::new(annotations(&["rewrite-target"]), true).is_err());
+        assert!(NginxAnnotations::new(annotations(&["auth-url"]), true).is_err());
+        assert!(NginxAnnotations::new(annotations(&["auth-snippet"]), true).is_err());
+        assert!(NginxAnnotations::new(
+            BTreeMap::from([("kubernetes.io/ingress.class".to_string(), "nginx".to_string())]),
+            true
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_url_rewrite_data_template() {
+        let route = |path: &str, rewrite_target: Option<&str>, trailing_slash: TrailingSlash| Route {
+            path: path.to_string(),
+            service_long_id: Uuid::new_v4(),
+            backend_protocol: BackendProtocol::Http,
+            rewrite_target: rewrite_target.map(str::to_string),
+            permanent_redirect: None,
+            trailing_slash,
+        };
+
+        assert_eq!(
+            to_url_rewrite_data_template(None),
+            UrlRewriteDataTemplate {
+                path: "/".to_string(),
+                path_type: "Prefix",
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash_rule: None,
+            }
+        );
+        assert_eq!(
+            to_url_rewrite_data_template(Some(&route("/api/", Some("/v2/"), TrailingSlash::Keep))),
+            UrlRewriteDataTemplate {
+                path: "/api(?:/|$)(.*)".to_string(),
+                path_type: "ImplementationSpecific",
+                rewrite_target: Some("/v2/$1".to_string()),
+                permanent_redirect: None,
+                trailing_slash_rule: None,
+            }
+        );
+
+        let url_rewrite = to_url_rewrite_data_template(Some(&route("/", Some("/"), TrailingSlash::Remove)));
+        assert_eq!(url_rewrite.path, "/(.*)");
+        assert_eq!(url_rewrite.rewrite_target, Some("/$1".to_string()));
+        assert_eq!(url_rewrite.trailing_slash_rule, TrailingSlash::Remove.rewrite_rule());
+    }
+
+    #[test]
+    pub fn test_custom_certificate_warnings() {
+        let certificate = CustomCertificate {
+            certificate_pem: TEST_CERTIFICATE_PEM.to_string(),
+            private_key_pem: "key".to_string(),
+        };
+        let expires_at = DateTime::from_timestamp(1823696672, 0).unwrap();
+        assert_eq!(certificate.expires_at(), Ok(expires_at));
+
+        let custom_domains = vec![
+            CustomDomain {
+                domain: "www.example.com".to_string(),
+                target_domain: "my-router.qovery.io".to_string(),
+                generate_certificate: true,
+                certificate: Some(certificate),
+            },
+            CustomDomain {
+                domain: "api.example.com".to_string(),
+                target_domain: "my-router.qovery.io".to_string(),
+                generate_certificate: true,
+                certificate: None,
+            },
+        ];
+        assert!(!requires_certificate(&custom_domains[0], "qovery.io"));
+        assert!(requires_certificate(&custom_domains[1], "qovery.io"));
+
+        assert!(custom_certificate_warnings(&custom_domains, expires_at - Duration::days(90)).is_empty());
+        assert_eq!(
+            custom_certificate_warnings(&custom_domains, expires_at - Duration::days(10)),
+            vec![
+                "Certificate of custom domain www.example.com expires in 10 day(s), on 2027-10-16, it must be renewed"
+            ]
+        );
+        assert_eq!(
+            custom_certificate_warnings(&custom_domains, expires_at + Duration::days(1)),
+            vec!["Certificate of custom domain www.example.com expired on 2027-10-16, clients will reject it"]
+        );
+
+        let invalid_certificate = CustomCertificate {
+            certificate_pem: "not a certificate".to_string(),
+            private_key_pem: "key".to_string(),
+        };
+        assert!(invalid_certificate.expires_at().is_err());
+    }
+
+    #[test]
+    pub fn test_client_tls() {
+        let ca_bundle = format!("{TEST_CERTIFICATE_PEM}\n{TEST_CERTIFICATE_PEM}\n");
+        assert_eq!(
+            ClientTls::new(Some(ca_bundle.clone()), 2, true).unwrap(),
+            ClientTls {
+                ca_bundle_pem: ca_bundle.clone(),
+                verify_depth: 2,
+                pass_certificate_to_upstream: true,
+            }
+        );
+        assert!(ClientTls::new(None, 1, false).is_err());
+        assert!(ClientTls::new(Some(" ".to_string()), 1, false).is_err());
+        assert!(ClientTls::new(Some(ca_bundle), 0, false).is_err());
+        assert!(ClientTls::new(Some("not a certificate".to_string()), 1, false).is_err());
+    }
+
+    const TEST_CERTIFICATE_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBiDCCAS+gAwIBAgIUeeaq3YXuqcRuJrPxzMW8z8ztI+gwCgYIKoZIzj0EAwIw
+GjEYMBYGA1UEAwwPd3d3LmV4YW1wbGUuY29tMB4XDTI2MTAxNjE0MjQzMloXDTI3
+MTAxNjE0MjQzMlowGjEYMBYGA1UEAwwPd3d3LmV4YW1wbGUuY29tMFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAESicqNI5/vmcKrQRTs2MH07VLFvMbWJm0fVjmuM0Y
+4XjSwxpNZeV+UoHA7UaNMj0EdunvUgEVKHuhC60wmwTZwqNTMFEwHQYDVR0OBBYE
+FJqkVi0gNpPTchcbpcsIOkhmsWVBMB8GA1UdIwQYMBaAFJqkVi0gNpPTchcbpcsI
+OkhmsWVBMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDRwAwRAIgWmilTeqz
+RuxRhfBUONRiRXtoKVGd9+KtbIvFqnUWaAUCIDVa5f16PpyZiaf2PT9k1xc4qMIn
+VEbfKkCF74KWpP6c
+-----END CERTIFICATE-----";
+
     #[test]
     pub fn test_certificate_alternative_names() {
         let custom_domains = vec![
@@ -524,16 +1132,19 @@ mod tests {
                 domain: "toto.com".to_string(),
                 target_domain: "".to_string(),
                 generate_certificate: true,
+                certificate: None,
             },
             CustomDomain {
                 domain: "cluster.com".to_string(),
                 target_domain: "".to_string(),
                 generate_certificate: true,
+                certificate: None,
             },
             CustomDomain {
                 domain: "titi.com".to_string(),
                 target_domain: "".to_string(),
                 generate_certificate: false,
+                certificate: None,
             },
         ];
 
@@ -588,6 +1199,7 @@ mod tests {
             domain: "*.toto.cluster.com".to_string(),
             target_domain: "".to_string(),
             generate_certificate: true,
+            certificate: None,
         }];
         let port2 = Port {
             long_id: Default::default(),
@@ -637,6 +1249,7 @@ mod tests {
             domain: "*.toto.mydomain.com".to_string(),
             target_domain: "".to_string(),
             generate_certificate: true,
+            certificate: None,
         }];
 
         let namespace = "env_namespace";
@@ -693,11 +1306,13 @@ mod tests {
                 domain: "super.mydomain.com".to_string(),
                 target_domain: "".to_string(),
                 generate_certificate: true,
+                certificate: None,
             },
             CustomDomain {
                 domain: "*.toto.mydomain.com".to_string(),
                 target_domain: "".to_string(),
                 generate_certificate: true,
+                certificate: None,
             },
         ];
 
@@ -781,6 +1396,7 @@ mod tests {
             domain: "toto.cluster.com".to_string(),
             target_domain: "".to_string(),
             generate_certificate: true,
+            certificate: None,
         }];
 
         let namespace = "namespace1";
@@ -832,6 +1448,7 @@ mod tests {
             domain: "*.toto.mydomain.com".to_string(),
             target_domain: "".to_string(),
             generate_certificate: true,
+            certificate: None,
         }];
 
         let namespace = "env_namespace";
@@ -894,6 +1511,7 @@ mod tests {
             domain: "*.toto.mydomain.com".to_string(),
             target_domain: "".to_string(),
             generate_certificate: true,
+            certificate: None,
         }];
 
         let namespace = "env_namespace";
@@ -931,4 +1549,49 @@ mod tests {
             service_port: 8080,
         }));
     }
+
+    #[test]
+    pub fn test_l4_service_template() {
+        let service_long_id = uuid::Uuid::new_v4();
+        let l4_route = |protocol: Protocol, port: u16, target_port: u16| L4Route {
+            protocol,
+            port,
+            target_port,
+            service_long_id,
+        };
+        let l4_routes = vec![
+            l4_route(Protocol::UDP, 27015, 27015),
+            l4_route(Protocol::TCP, 1883, 1883),
+            l4_route(Protocol::TCP, 8883, 9883),
+        ];
+
+        assert_eq!(
+            to_l4_service_data_template(&l4_routes, "router.example.com"),
+            vec![
+                L4ServiceDataTemplate {
+                    protocol: Protocol::TCP,
+                    ports: vec![
+                        L4PortDataTemplate {
+                            port: 1883,
+                            target_port: 1883,
+                        },
+                        L4PortDataTemplate {
+                            port: 8883,
+                            target_port: 9883,
+                        },
+                    ],
+                    hostnames: vec!["router.example.com".to_string()],
+                },
+                L4ServiceDataTemplate {
+                    protocol: Protocol::UDP,
+                    ports: vec![L4PortDataTemplate {
+                        port: 27015,
+                        target_port: 27015,
+                    }],
+                    hostnames: vec!["router.example.com".to_string()],
+                },
+            ]
+        );
+        assert!(to_l4_service_data_template(&[], "router.example.com").is_empty());
+    }
 }
@@ -17,14 +17,16 @@ impl ToTeraContext for Container<SCW> {
                 id: s.id.clone(),
                 long_id: self.long_id,
                 name: s.name.clone(),
-                storage_type: match s.storage_type {
-                    // TODO(benjaminch): Switch to proper storage class
-                    // Note: Seems volume storage type are not supported, only blocked storage for the time being
-                    // https://github.com/scaleway/scaleway-csi/tree/master/examples/kubernetes#different-storageclass
-                    ScwStorageType::BlockSsd => "scw-sbv-ssd-0", // "b_ssd",
-                    ScwStorageType::LocalSsd => "l_ssd",
-                }
-                .to_string(),
+                storage_type: s.storage_class.clone().unwrap_or_else(|| {
+                    match s.storage_type {
+                        // TODO(benjaminch): Switch to proper storage class
+                        // Note: Seems volume storage type are not supported, only blocked storage for the time being
+                        // https://github.com/scaleway/scaleway-csi/tree/master/examples/kubernetes#different-storageclass
+                        ScwStorageType::BlockSsd => "scw-sbv-ssd-0", // "b_ssd",
+                        ScwStorageType::LocalSsd => "l_ssd",
+                    }
+                    .to_string()
+                }),
                 size_in_gib: s.size_in_gib,
                 mount_point: s.mount_point.clone(),
                 snapshot_retention_in_days: s.snapshot_retention_in_days,
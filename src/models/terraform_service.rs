@@ -0,0 +1,247 @@
+use crate::build_platform::{Build, Credentials, SshKey};
+use crate::cloud_provider::models::EnvironmentVariable;
+use crate::cloud_provider::service::{get_tfstate_name, get_tfstate_suffix, Action, Service, ServiceType};
+use crate::deployment_action::DeploymentAction;
+use crate::events::{EventDetails, Stage, Transmitter};
+use crate::io_models::context::Context;
+use crate::io_models::variable_utils::VariableInfo;
+use crate::utilities::to_short_id;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use url::Url;
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TerraformServiceError {
+    #[error("Terraform service invalid configuration: {0}")]
+    InvalidConfig(String),
+}
+
+pub struct TerraformModuleSource {
+    pub git_url: Url,
+    pub get_credentials: Box<dyn Fn() -> anyhow::Result<Option<Credentials>> + Send + Sync>,
+    pub commit_id: String,
+    pub root_path: PathBuf,
+    pub ssh_keys: Vec<SshKey>,
+}
+
+pub struct TerraformService {
+    pub(super) mk_event_details: Box<dyn Fn(Stage) -> EventDetails + Send + Sync>,
+    pub(super) id: String,
+    pub(super) long_id: Uuid,
+    pub(super) name: String,
+    pub(super) kube_name: String,
+    pub(super) action: Action,
+    pub(super) source: TerraformModuleSource,
+    pub(super) variables: BTreeMap<String, String>,
+    pub(super) exposed_outputs: BTreeMap<String, String>,
+    pub(super) provider_credentials: BTreeMap<String, String>,
+    pub(super) environment_variables: HashMap<String, VariableInfo>,
+    pub(super) workspace_directory: PathBuf,
+}
+
+impl TerraformService {
+    pub fn new(
+        context: &Context,
+        long_id: Uuid,
+        name: String,
+        kube_name: String,
+        action: Action,
+        mut source: TerraformModuleSource,
+        variables: BTreeMap<String, String>,
+        exposed_outputs: BTreeMap<String, String>,
+        provider_credentials: BTreeMap<String, String>,
+        environment_variables: HashMap<String, VariableInfo>,
+        mk_event_details: impl Fn(Transmitter) -> EventDetails,
+    ) -> Result<Self, TerraformServiceError> {
+        let workspace_directory = crate::fs::workspace_directory(
+            context.workspace_root_dir(),
+            context.execution_id(),
+            format!("terraform_services/{long_id}"),
+        )
+        .map_err(|_| TerraformServiceError::InvalidConfig("Can't create workspace directory".to_string()))?;
+
+        // The module is run from the cloned repository, it must stay inside of it
+        source.root_path = source
+            .root_path
+            .components()
+            .filter(|component| !matches!(component, Component::RootDir | Component::CurDir))
+            .collect();
+        if source
+            .root_path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            return Err(TerraformServiceError::InvalidConfig(format!(
+                "Module path {:?} must be inside the git repository",
+                source.root_path
+            )));
+        }
+
+        if let Some(env_var) = exposed_outputs
+            .values()
+            .find(|env_var| env_var.is_empty() || !env_var.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        {
+            return Err(TerraformServiceError::InvalidConfig(format!(
+                "`{env_var}` is not a valid environment variable name for an output"
+            )));
+        }
+
+        let event_details = mk_event_details(Transmitter::TerraformService(long_id, name.to_string()));
+        let mk_event_details = move |stage: Stage| EventDetails::clone_changing_stage(event_details.clone(), stage);
+        Ok(Self {
+            mk_event_details: Box::new(mk_event_details),
+            id: to_short_id(&long_id),
+            long_id,
+            name,
+            kube_name,
+            action,
+            source,
+            variables,
+            exposed_outputs,
+            provider_credentials,
+            environment_variables,
+            workspace_directory,
+        })
+    }
+
+    pub fn source(&self) -> &TerraformModuleSource {
+        &self.source
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        &self.variables
+    }
+
+    /// Outputs of the module exposed to the other services, by environment variable name
+    pub fn exposed_outputs(&self) -> &BTreeMap<String, String> {
+        &self.exposed_outputs
+    }
+
+    /// Environment variables giving the module access to the cloud account it manages
+    pub fn provider_credentials(&self) -> &BTreeMap<String, String> {
+        &self.provider_credentials
+    }
+
+    pub fn workspace_directory(&self) -> &Path {
+        &self.workspace_directory
+    }
+
+    /// Where the git repository is cloned
+    pub fn repository_directory(&self) -> PathBuf {
+        self.workspace_directory.join("repository")
+    }
+
+    /// Where terraform is run, the root module of the repository
+    pub fn module_directory(&self) -> PathBuf {
+        self.repository_directory().join(&self.source.root_path)
+    }
+
+    pub fn tfstate_name(&self) -> String {
+        get_tfstate_name(self)
+    }
+
+    /// Name of the lease terraform takes to lock the state, in the format `lock-tfstate-{workspace}-{secret_suffix}`
+    pub fn tfstate_lock_name(&self) -> String {
+        format!("lock-{}", self.tfstate_name())
+    }
+
+    /// Backend storing the state of the module in a secret of the environment namespace, as for the databases,
+    /// written in an override file so it replaces the backend the module may declare.
+    /// The kubeconfig must be the one of a service account of the namespace, the module must not get the admin one.
+    pub fn backend_override(&self, kubeconfig_path: &Path, namespace: &str) -> String {
+        format!(
+            r#"terraform {{
+  backend "kubernetes" {{
+    secret_suffix    = "{}"
+    load_config_file = true
+    config_path      = "{}"
+    namespace        = "{}"
+  }}
+}}
+"#,
+            get_tfstate_suffix(self),
+            kubeconfig_path.to_string_lossy(),
+            namespace
+        )
+    }
+}
+
+impl Service for TerraformService {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Terraform
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> String {
+        self.source.commit_id.clone()
+    }
+
+    fn kube_name(&self) -> &str {
+        &self.kube_name
+    }
+
+    fn kube_label_selector(&self) -> String {
+        format!("qovery.com/service-id={}", self.long_id)
+    }
+
+    fn get_event_details(&self, stage: Stage) -> EventDetails {
+        (self.mk_event_details)(stage)
+    }
+
+    fn action(&self) -> &Action {
+        &self.action
+    }
+
+    fn as_service(&self) -> &dyn Service {
+        self
+    }
+
+    fn as_service_mut(&mut self) -> &mut dyn Service {
+        self
+    }
+
+    fn build(&self) -> Option<&Build> {
+        None
+    }
+
+    fn build_mut(&mut self) -> Option<&mut Build> {
+        None
+    }
+
+    fn get_environment_variables(&self) -> Vec<EnvironmentVariable> {
+        self.environment_variables
+            .iter()
+            .map(|(key, variable_infos)| EnvironmentVariable {
+                key: key.clone(),
+                value: variable_infos.value.clone(),
+                is_secret: variable_infos.is_secret,
+            })
+            .collect()
+    }
+
+    fn get_passwords(&self) -> Vec<String> {
+        self.provider_credentials.values().cloned().collect()
+    }
+}
+
+pub trait TerraformServiceTrait: Service + DeploymentAction + Send {
+    fn as_deployment_action(&self) -> &dyn DeploymentAction;
+}
+
+impl TerraformServiceTrait for TerraformService {
+    fn as_deployment_action(&self) -> &dyn DeploymentAction {
+        self
+    }
+}
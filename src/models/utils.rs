@@ -1,5 +1,26 @@
-use crate::cloud_provider::models::CpuArchitecture;
-use std::collections::BTreeMap;
+use crate::cloud_provider::models::{
+    CpuArchitecture, KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit, MountedFile, Storage,
+};
+use crate::io_models::application::ApplicationInitContainer;
+use crate::io_models::shared_volume::SharedVolumeMount;
+use crate::io_models::{
+    ConfigReloadStrategy, CustomMetadata, ExternalSecretVariable, HpaCustomMetric, IpFamilyPolicy, KedaTrigger,
+    LifecycleHooks, PodAntiAffinityTopology, SmokeTest, SmokeTestCheck, Toleration, TolerationOperator,
+    TopologySpreadKey,
+};
+use crate::models::probe::{Probe, ProbeType};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use tera::Context as TeraContext;
+
+/// Pod annotation holding the checksum of the mounted files, exposed to the pods when they reload their config in place
+pub const CONFIG_CHECKSUM_ANNOTATION: &str = "qovery.com/config-checksum";
+/// Workload annotation holding the `spec_checksum` of the context it has been rendered from
+pub const SPEC_CHECKSUM_ANNOTATION: &str = "qovery.com/spec-checksum";
+/// Tera context key under which the `spec_checksum` is exposed to the chart
+pub const SPEC_CHECKSUM_CONTEXT_KEY: &str = "spec_checksum";
+/// Name of the sidecar container reloading the mounted files with the Sidecar config reload strategy
+pub const CONFIG_RELOADER_CONTAINER_NAME: &str = "qovery-config-reloader";
 
 pub fn add_arch_to_deployment_affinity_node(
     deployment_affinity_node_required: &BTreeMap<String, String>,
@@ -21,11 +42,591 @@ pub fn add_arch_to_deployment_affinity_node(
     deployment_affinity_node_required
 }
 
+pub fn validate_config_reload_settings(
+    strategy: ConfigReloadStrategy,
+    mount_path: &str,
+    webhook_url: &str,
+) -> Result<(), String> {
+    if strategy == ConfigReloadStrategy::RollingRestart {
+        return Ok(());
+    }
+
+    if !mount_path.starts_with('/') || mount_path == "/" {
+        return Err(format!(
+            "deployment.config_reload.mount_path must be an absolute path other than /, got `{mount_path}`"
+        ));
+    }
+
+    if strategy == ConfigReloadStrategy::Sidecar && url::Url::parse(webhook_url).is_err() {
+        return Err(format!(
+            "deployment.config_reload.webhook_url must be a valid url with the Sidecar strategy, got `{webhook_url}`"
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_topology_spread_settings(key: TopologySpreadKey, max_skew: u32) -> Result<(), String> {
+    if key != TopologySpreadKey::Disabled && max_skew == 0 {
+        return Err("deployment.topology_spread.max_skew must be greater than 0".to_string());
+    }
+
+    Ok(())
+}
+
+/// minAvailable takes over maxUnavailable when set, none of them can forbid every eviction or nodes would never drain
+pub fn validate_pod_disruption_budget_settings(
+    min_available_percent: u32,
+    max_unavailable_percent: u32,
+) -> Result<(), String> {
+    if min_available_percent >= 100 {
+        return Err("pdb.min_available_percent must be lower than 100".to_string());
+    }
+    if min_available_percent == 0 && (max_unavailable_percent == 0 || max_unavailable_percent > 100) {
+        return Err("pdb.max_unavailable_percent must be between 1 and 100".to_string());
+    }
+
+    Ok(())
+}
+
+/// Requests must fit in the limits, and limits cannot exceed the requests more than the max ratio, as nodes are
+/// overcommitted by the difference
+pub fn validate_resources(
+    cpu_request: &KubernetesCpuResourceUnit,
+    cpu_limit: &KubernetesCpuResourceUnit,
+    ram_request: &KubernetesMemoryResourceUnit,
+    ram_limit: &KubernetesMemoryResourceUnit,
+    limit_max_ratio: u32,
+) -> Result<(), String> {
+    let (cpu_request, cpu_limit) = (cpu_request.to_milli_cpu() as u64, cpu_limit.to_milli_cpu() as u64);
+    let (ram_request, ram_limit) = (ram_request.to_bytes(), ram_limit.to_bytes());
+    if cpu_request == 0 || ram_request == 0 {
+        return Err("cpu and ram requests must be greater than 0".to_string());
+    }
+    if cpu_request > cpu_limit || ram_request > ram_limit {
+        return Err("cpu and ram requests must be less or equal to their limits".to_string());
+    }
+    if cpu_limit > cpu_request * limit_max_ratio as u64 || ram_limit > ram_request * limit_max_ratio as u64 {
+        return Err(format!(
+            "cpu and ram limits cannot exceed {limit_max_ratio} times their requests (resources.limit_max_ratio)"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Spreading across zones only makes sense on clusters having nodes in several of them
+pub fn resolve_topology_spread_key(key: TopologySpreadKey, cluster_zones: Option<Vec<&str>>) -> TopologySpreadKey {
+    match key {
+        TopologySpreadKey::Auto if cluster_zones.map_or(0, |zones| zones.len()) > 1 => TopologySpreadKey::Zone,
+        TopologySpreadKey::Auto => TopologySpreadKey::Disabled,
+        key => key,
+    }
+}
+
+/// Every pod would be alone in the only zone of a single zone cluster, so the anti-affinity falls back to the nodes
+pub fn resolve_antiaffinity_pod_topology(
+    topology: PodAntiAffinityTopology,
+    cluster_zones: Option<Vec<&str>>,
+) -> PodAntiAffinityTopology {
+    match topology {
+        PodAntiAffinityTopology::Zone if cluster_zones.map_or(0, |zones| zones.len()) <= 1 => {
+            PodAntiAffinityTopology::Hostname
+        }
+        topology => topology,
+    }
+}
+
+/// Services cannot get an IPv6 address on single stack clusters, `RequireDualStack` ones would be rejected
+pub fn resolve_ip_family_policy(policy: IpFamilyPolicy, cluster_dual_stack_enabled: bool) -> IpFamilyPolicy {
+    match cluster_dual_stack_enabled {
+        true => policy,
+        false => IpFamilyPolicy::SingleStack,
+    }
+}
+
+/// Workloads without a service account of their own run with the one of their environment, when it has one
+pub fn resolve_service_account_name(
+    service_account_name: &str,
+    environment_service_account_name: Option<&str>,
+) -> String {
+    match (service_account_name, environment_service_account_name) {
+        ("", Some(environment_service_account_name)) => environment_service_account_name.to_string(),
+        (service_account_name, _) => service_account_name.to_string(),
+    }
+}
+
+// Keys set by the engine, kubernetes or helm on generated resources, users cannot override them
+const RESERVED_METADATA_KEYS: &[&str] = &[
+    "app",
+    "appId",
+    "databaseId",
+    "databaseLongId",
+    "diskId",
+    "diskType",
+    "envId",
+    "envLongId",
+    "ownerId",
+    "projectLongId",
+    "releaseTime",
+    "routerId",
+];
+const RESERVED_METADATA_KEY_PREFIXES: &[&str] =
+    &["qovery.com", "helm.sh", "meta.helm.sh", "cert-manager.io", "checksum"];
+const RESERVED_METADATA_KEY_PREFIX_DOMAINS: &[&str] = &["kubernetes.io", "k8s.io"];
+
+// Label values and key names: at most 63 alphanumeric characters, `-`, `_` or `.` in between
+fn is_valid_metadata_name(name: &str) -> bool {
+    name.len() <= 63
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+fn split_metadata_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once('/') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, key),
+    }
+}
+
+// Keys are an optional dns subdomain prefix and a name separated by `/`
+fn is_valid_metadata_key(key: &str) -> bool {
+    let (prefix, name) = split_metadata_key(key);
+    let is_valid_prefix = prefix.map_or(true, |prefix| {
+        prefix.len() <= 253
+            && !prefix.is_empty()
+            && prefix.split('.').all(|part| {
+                !part.is_empty()
+                    && !part.starts_with('-')
+                    && !part.ends_with('-')
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            })
+    });
+
+    is_valid_prefix && is_valid_metadata_name(name)
+}
+
+fn validate_metadata_key(key: &str) -> Result<(), String> {
+    if !is_valid_metadata_key(key) {
+        return Err(format!("Invalid label or annotation key `{key}`"));
+    }
+
+    let (prefix, name) = split_metadata_key(key);
+    let is_reserved = match prefix {
+        Some(prefix) => {
+            RESERVED_METADATA_KEY_PREFIXES.contains(&prefix)
+                || RESERVED_METADATA_KEY_PREFIX_DOMAINS
+                    .iter()
+                    .any(|domain| prefix == *domain || prefix.ends_with(&format!(".{domain}")))
+        }
+        None => RESERVED_METADATA_KEYS.contains(&name),
+    };
+    if is_reserved {
+        return Err(format!("Label or annotation key `{key}` is reserved"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_labels(labels: &BTreeMap<String, String>) -> Result<(), String> {
+    for (key, value) in labels {
+        validate_metadata_key(key)?;
+        if !value.is_empty() && !is_valid_metadata_name(value) {
+            return Err(format!("Invalid value `{value}` for label `{key}`"));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_custom_metadata(custom_metadata: &CustomMetadata) -> Result<(), String> {
+    validate_labels(&custom_metadata.labels)?;
+    for key in custom_metadata.annotations.keys() {
+        validate_metadata_key(key)?;
+    }
+
+    Ok(())
+}
+
+// Names of the containers of a pod are dns labels, unique among its containers and init containers
+pub fn validate_init_containers(
+    init_containers: &[ApplicationInitContainer],
+    container_name: &str,
+) -> Result<(), String> {
+    let mut names = BTreeSet::from([container_name, CONFIG_RELOADER_CONTAINER_NAME]);
+    for init_container in init_containers {
+        let name = init_container.name.as_str();
+        let is_dns_label = name.len() <= 63
+            && name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+            && name.ends_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !is_dns_label {
+            return Err(format!("Invalid init container name `{name}`"));
+        }
+        if !names.insert(name) {
+            return Err(format!("Init container name `{name}` is already used by another container"));
+        }
+        if init_container.command.is_empty() {
+            return Err(format!("Command of init container `{name}` cannot be empty"));
+        }
+        if let Some(key) = init_container
+            .environment_vars
+            .keys()
+            .find(|key| key.is_empty() || key.contains('='))
+        {
+            return Err(format!("Invalid environment variable `{key}` for init container `{name}`"));
+        }
+    }
+
+    Ok(())
+}
+
+// A path can only be mounted once in a container, whether by a storage of the service or a shared volume
+// Volume claims of the statefulset, their storage class being a kubernetes object name when declared
+pub fn validate_storages<T>(storages: &[Storage<T>]) -> Result<(), String> {
+    let mut mount_points = BTreeSet::new();
+    for storage in storages {
+        let mount_point = storage.mount_point.as_str();
+        if storage.size_in_gib == 0 {
+            return Err(format!("Storage {} size must be greater than 0", storage.name));
+        }
+        if !mount_point.starts_with('/') {
+            return Err(format!("Storage mount point `{mount_point}` must be absolute"));
+        }
+        if !mount_points.insert(mount_point) {
+            return Err(format!("Mount point `{mount_point}` is used by more than one storage"));
+        }
+        if let Some(storage_class) = &storage.storage_class {
+            let is_valid = !storage_class.is_empty()
+                && storage_class.len() <= 253
+                && storage_class
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+                && storage_class.starts_with(|c: char| c.is_ascii_alphanumeric())
+                && storage_class.ends_with(|c: char| c.is_ascii_alphanumeric());
+            if !is_valid {
+                return Err(format!(
+                    "Storage class `{storage_class}` of storage {} is not a valid kubernetes name",
+                    storage.name
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_shared_volume_mounts<'a>(
+    shared_volume_mounts: &'a [SharedVolumeMount],
+    storage_mount_points: impl Iterator<Item = &'a str>,
+) -> Result<(), String> {
+    let mut mount_paths: BTreeSet<&str> = storage_mount_points.collect();
+    let mut shared_volume_ids = BTreeSet::new();
+    for mount in shared_volume_mounts {
+        let mount_path = mount.mount_path.as_str();
+        if !mount_path.starts_with('/') {
+            return Err(format!("Shared volume mount path `{mount_path}` must be absolute"));
+        }
+        if !mount_paths.insert(mount_path) {
+            return Err(format!("Mount path `{mount_path}` is already used by another volume"));
+        }
+        if !shared_volume_ids.insert(mount.shared_volume_id) {
+            return Err(format!("Shared volume {} is mounted more than once", mount.shared_volume_id));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_lifecycle_hooks(lifecycle_hooks: &LifecycleHooks) -> Result<(), String> {
+    for (name, hook) in [
+        ("post_create", &lifecycle_hooks.post_create),
+        ("pre_delete", &lifecycle_hooks.pre_delete),
+    ] {
+        let Some(hook) = hook else {
+            continue;
+        };
+        if hook.command.is_empty() {
+            return Err(format!("lifecycle_hooks.{name}.command cannot be empty"));
+        }
+        if hook.timeout_in_seconds == 0 {
+            return Err(format!("lifecycle_hooks.{name}.timeout_in_seconds must be greater than 0"));
+        }
+    }
+
+    Ok(())
+}
+
+// The http check is run by a shell in the job, the path is kept free of anything it could interpret
+pub fn validate_smoke_test(smoke_test: &SmokeTest, ports: impl IntoIterator<Item = u16>) -> Result<(), String> {
+    if smoke_test.timeout_in_seconds == 0 {
+        return Err("smoke_test.timeout_in_seconds must be greater than 0".to_string());
+    }
+
+    match &smoke_test.check {
+        SmokeTestCheck::Http {
+            path,
+            port,
+            expected_status,
+        } => {
+            if !path.starts_with('/') || path.chars().any(|c| c.is_whitespace() || "'\"`$\\".contains(c)) {
+                return Err(format!(
+                    "smoke_test.path `{path}` must be an absolute path without whitespaces, quotes, backslashes or `$`"
+                ));
+            }
+            if !ports.into_iter().any(|app_port| app_port == *port) {
+                return Err(format!("smoke_test.port {port} is not a port of the application"));
+            }
+            if !(100..=599).contains(expected_status) {
+                return Err(format!("smoke_test.expected_status {expected_status} is not an HTTP status"));
+            }
+        }
+        SmokeTestCheck::Command { command, .. } => {
+            if command.is_empty() {
+                return Err("smoke_test.command cannot be empty".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Kubernetes rejects liveness and startup probes with a success threshold other than 1,
+// a threshold of 0 is defaulted by the API server so it is accepted everywhere
+pub fn validate_probes(
+    readiness_probe: Option<&Probe>,
+    liveness_probe: Option<&Probe>,
+    startup_probe: Option<&Probe>,
+) -> Result<(), String> {
+    for (name, probe) in [
+        ("readiness_probe", readiness_probe),
+        ("liveness_probe", liveness_probe),
+        ("startup_probe", startup_probe),
+    ] {
+        let Some(probe) = probe else {
+            continue;
+        };
+        if name != "readiness_probe" && probe.success_threshold > 1 {
+            return Err(format!("{name}.success_threshold must be 1"));
+        }
+        match &probe.r#type {
+            ProbeType::Exec { commands } => {
+                if commands.is_empty() || commands.iter().all(|command| command.trim().is_empty()) {
+                    return Err(format!("{name} exec command cannot be empty"));
+                }
+            }
+            ProbeType::Http { .. } | ProbeType::Tcp { .. } | ProbeType::Grpc { .. } => {
+                if probe.port == 0 || probe.port > u32::from(u16::MAX) {
+                    return Err(format!("{name}.port {} is not a valid port", probe.port));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// KEDA owns the HPA of a service with triggers, so custom metrics could not be added to it
+pub fn validate_hpa_metrics(
+    memory_average_utilization_percent: Option<u8>,
+    custom_metrics: &[HpaCustomMetric],
+    keda_triggers: &[KedaTrigger],
+) -> Result<(), String> {
+    if let Some(percent) = memory_average_utilization_percent {
+        if percent == 0 || percent > 100 {
+            return Err("hpa.memory.average_utilization_percent must be between 1 and 100".to_string());
+        }
+    }
+    if !custom_metrics.is_empty() && !keda_triggers.is_empty() {
+        return Err("hpa.custom_metrics cannot be used with hpa.keda.triggers".to_string());
+    }
+
+    let mut names = BTreeSet::new();
+    for metric in custom_metrics {
+        if metric.name.is_empty()
+            || !metric
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        {
+            return Err(format!("Invalid custom metric name `{}`", metric.name));
+        }
+        if !names.insert(metric.name.as_str()) {
+            return Err(format!("Custom metric `{}` is defined more than once", metric.name));
+        }
+        if !metric.target_average_value.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(format!(
+                "Invalid target average value `{}` for custom metric `{}`",
+                metric.target_average_value, metric.name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Credentials of the triggers are read from the secret of the service, so they must be among its environment variables
+pub fn validate_keda_triggers<'a>(
+    triggers: &[KedaTrigger],
+    environment_variable_keys: impl Iterator<Item = &'a str>,
+) -> Result<(), String> {
+    let environment_variable_keys: BTreeSet<&str> = environment_variable_keys.collect();
+    for trigger in triggers {
+        if let Some(key) = trigger
+            .r#type
+            .required_metadata()
+            .iter()
+            .find(|key| !trigger.metadata.contains_key(**key))
+        {
+            return Err(format!(
+                "Autoscaling trigger {:?} requires the `{key}` metadata",
+                trigger.r#type
+            ));
+        }
+        if let Some(env_var) = trigger
+            .authentication_env_vars
+            .values()
+            .find(|env_var| !environment_variable_keys.contains(env_var.as_str()))
+        {
+            return Err(format!(
+                "Autoscaling trigger {:?} uses unknown environment variable `{env_var}`",
+                trigger.r#type
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Synced variables come on top of the environment variables of the service, so their names must not collide
+pub fn validate_external_secrets<'a>(
+    external_secrets: &[ExternalSecretVariable],
+    environment_variable_keys: impl Iterator<Item = &'a str>,
+) -> Result<(), String> {
+    let mut names: BTreeSet<&str> = environment_variable_keys.collect();
+    for secret in external_secrets {
+        let is_valid_name = secret.name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && secret.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid_name {
+            return Err(format!("Invalid external secret environment variable name `{}`", secret.name));
+        }
+        if !names.insert(secret.name.as_str()) {
+            return Err(format!("External secret `{}` is already an environment variable", secret.name));
+        }
+        if !is_valid_metadata_name(&secret.store) {
+            return Err(format!(
+                "Invalid secret store `{}` for external secret `{}`",
+                secret.store, secret.name
+            ));
+        }
+        if secret.key.trim().is_empty() {
+            return Err(format!(
+                "External secret `{}` requires the key of the secret in its store",
+                secret.name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Tolerating taints set by kubernetes, i.e: `node.kubernetes.io/not-ready`, is allowed, so only the syntax is checked
+pub fn validate_tolerations(tolerations: &[Toleration]) -> Result<(), String> {
+    for toleration in tolerations {
+        if !is_valid_metadata_key(&toleration.key) {
+            return Err(format!("Invalid toleration key `{}`", toleration.key));
+        }
+        match toleration.operator {
+            TolerationOperator::Exists if !toleration.value.is_empty() => {
+                return Err(format!(
+                    "Toleration of `{}` cannot have a value with the Exists operator",
+                    toleration.key
+                ));
+            }
+            _ if !toleration.value.is_empty() && !is_valid_metadata_name(&toleration.value) => {
+                return Err(format!(
+                    "Invalid value `{}` for toleration `{}`",
+                    toleration.value, toleration.key
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// FNV-1a 64 bits, only used to detect changes
+fn checksum<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> String {
+    let hash = bytes.into_iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+/// Checksum of the mounted files, changing as soon as one of them is added, removed or updated
+pub fn config_checksum<'a>(mounted_files: impl IntoIterator<Item = &'a MountedFile>) -> String {
+    checksum(mounted_files.into_iter().flat_map(|file| {
+        [
+            file.mount_path.as_bytes(),
+            file.file_content_b64.as_bytes(),
+            "\0".as_bytes(),
+        ]
+        .into_iter()
+        .flatten()
+    }))
+}
+
+/// Checksum of the tera context of a service, leaving aside the values of its environment variables and its
+/// registry credentials. Two deployments with the same checksum only differ by the content of their secrets.
+pub fn spec_checksum(tera_context: &TeraContext) -> String {
+    let mut spec = tera_context.clone().into_json();
+    if let Some(spec) = spec.as_object_mut() {
+        spec.remove(SPEC_CHECKSUM_CONTEXT_KEY);
+        // registry tokens are short-lived, they are renewed on each deployment
+        spec.remove("registry");
+        // keys are kept, the pods reference each of them
+        if let Some(Value::Array(environment_variables)) = spec.get_mut("environment_variables") {
+            for environment_variable in environment_variables.iter_mut().filter_map(Value::as_object_mut) {
+                environment_variable.remove("value");
+            }
+        }
+    }
+
+    checksum(spec.to_string().as_bytes())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::cloud_provider::models::CpuArchitecture;
-    use crate::models::utils::add_arch_to_deployment_affinity_node;
+    use crate::cloud_provider::models::TaintEffect;
+    use crate::cloud_provider::models::{
+        CpuArchitecture, KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit, MountedFile, Storage,
+    };
+    use crate::io_models::application::ApplicationInitContainer;
+    use crate::io_models::shared_volume::SharedVolumeMount;
+    use crate::io_models::{
+        ConfigReloadStrategy, CustomMetadata, ExternalSecretVariable, HpaCustomMetric, KedaTrigger, KedaTriggerType,
+        PodAntiAffinityTopology, SmokeTest, SmokeTestCheck, Toleration, TolerationOperator, TopologySpreadKey,
+    };
+    use crate::models::probe::{Probe, ProbeType};
+    use crate::models::utils::{
+        add_arch_to_deployment_affinity_node, config_checksum, resolve_antiaffinity_pod_topology,
+        resolve_topology_spread_key, spec_checksum, validate_config_reload_settings, validate_custom_metadata,
+        validate_external_secrets, validate_hpa_metrics, validate_init_containers, validate_keda_triggers,
+        validate_pod_disruption_budget_settings, validate_probes, validate_resources, validate_shared_volume_mounts,
+        validate_smoke_test, validate_storages, validate_tolerations, validate_topology_spread_settings,
+        SPEC_CHECKSUM_CONTEXT_KEY,
+    };
     use std::collections::BTreeMap;
+    use uuid::Uuid;
 
     #[test]
     fn test_add_arch_to_deployment_affinity_node_with_empty_arch() {
@@ -79,4 +680,482 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(result.get("kubernetes.io/arch"), Some(&"value".to_string()));
     }
+
+    #[test]
+    fn test_validate_config_reload_settings() {
+        assert!(validate_config_reload_settings(ConfigReloadStrategy::RollingRestart, "", "").is_ok());
+        assert!(validate_config_reload_settings(ConfigReloadStrategy::Annotation, "/qovery-config", "").is_ok());
+        assert!(validate_config_reload_settings(ConfigReloadStrategy::Annotation, "qovery-config", "").is_err());
+        assert!(validate_config_reload_settings(ConfigReloadStrategy::Annotation, "/", "").is_err());
+        assert!(validate_config_reload_settings(ConfigReloadStrategy::Sidecar, "/qovery-config", "").is_err());
+        assert!(validate_config_reload_settings(
+            ConfigReloadStrategy::Sidecar,
+            "/qovery-config",
+            "http://127.0.0.1:8080/-/reload"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_resolve_topology_spread_key() {
+        let multi_az = || Some(vec!["eu-west-3a", "eu-west-3b", "eu-west-3c"]);
+
+        assert_eq!(
+            resolve_topology_spread_key(TopologySpreadKey::Auto, multi_az()),
+            TopologySpreadKey::Zone
+        );
+        assert_eq!(
+            resolve_topology_spread_key(TopologySpreadKey::Auto, Some(vec!["eu-west-3a"])),
+            TopologySpreadKey::Disabled
+        );
+        assert_eq!(
+            resolve_topology_spread_key(TopologySpreadKey::Auto, None),
+            TopologySpreadKey::Disabled
+        );
+        assert_eq!(
+            resolve_topology_spread_key(TopologySpreadKey::Hostname, multi_az()),
+            TopologySpreadKey::Hostname
+        );
+        assert_eq!(
+            resolve_topology_spread_key(TopologySpreadKey::Zone, None),
+            TopologySpreadKey::Zone
+        );
+
+        assert!(validate_topology_spread_settings(TopologySpreadKey::Auto, 1).is_ok());
+        assert!(validate_topology_spread_settings(TopologySpreadKey::Zone, 0).is_err());
+        assert!(validate_topology_spread_settings(TopologySpreadKey::Disabled, 0).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_antiaffinity_pod_topology() {
+        let multi_az = || Some(vec!["eu-west-3a", "eu-west-3b", "eu-west-3c"]);
+
+        assert_eq!(
+            resolve_antiaffinity_pod_topology(PodAntiAffinityTopology::Zone, multi_az()),
+            PodAntiAffinityTopology::Zone
+        );
+        assert_eq!(
+            resolve_antiaffinity_pod_topology(PodAntiAffinityTopology::Zone, Some(vec!["eu-west-3a"])),
+            PodAntiAffinityTopology::Hostname
+        );
+        assert_eq!(
+            resolve_antiaffinity_pod_topology(PodAntiAffinityTopology::Zone, None),
+            PodAntiAffinityTopology::Hostname
+        );
+        assert_eq!(
+            resolve_antiaffinity_pod_topology(PodAntiAffinityTopology::Hostname, multi_az()),
+            PodAntiAffinityTopology::Hostname
+        );
+        assert_eq!(
+            resolve_topology_spread_key(TopologySpreadKey::ZoneAndHostname, None),
+            TopologySpreadKey::ZoneAndHostname
+        );
+    }
+
+    #[test]
+    fn test_validate_pod_disruption_budget_settings() {
+        assert!(validate_pod_disruption_budget_settings(0, 10).is_ok());
+        assert!(validate_pod_disruption_budget_settings(50, 0).is_ok());
+        assert!(validate_pod_disruption_budget_settings(100, 10).is_err());
+        assert!(validate_pod_disruption_budget_settings(0, 0).is_err());
+        assert!(validate_pod_disruption_budget_settings(0, 101).is_err());
+    }
+
+    #[test]
+    fn test_validate_resources() {
+        let milli_cpu = KubernetesCpuResourceUnit::MilliCpu;
+        let mib = KubernetesMemoryResourceUnit::MebiByte;
+        assert!(validate_resources(&milli_cpu(500), &milli_cpu(500), &mib(256), &mib(256), 10).is_ok());
+        assert!(validate_resources(
+            &milli_cpu(500),
+            &milli_cpu(2000),
+            &mib(256),
+            &KubernetesMemoryResourceUnit::GibiByte(1),
+            10
+        )
+        .is_ok());
+        assert!(validate_resources(&milli_cpu(500), &milli_cpu(250), &mib(256), &mib(256), 10).is_err());
+        assert!(validate_resources(&milli_cpu(500), &milli_cpu(500), &mib(512), &mib(256), 10).is_err());
+        assert!(validate_resources(&milli_cpu(100), &milli_cpu(2000), &mib(256), &mib(256), 10).is_err());
+        assert!(validate_resources(&milli_cpu(0), &milli_cpu(500), &mib(256), &mib(256), 10).is_err());
+    }
+
+    #[test]
+    fn test_resolve_service_account_name() {
+        assert_eq!(
+            resolve_service_account_name("", Some("qovery-environment")),
+            "qovery-environment"
+        );
+        assert_eq!(resolve_service_account_name("my-sa", Some("qovery-environment")), "my-sa");
+        assert_eq!(resolve_service_account_name("", None), "");
+    }
+
+    #[test]
+    fn test_resolve_ip_family_policy() {
+        assert_eq!(
+            resolve_ip_family_policy(IpFamilyPolicy::RequireDualStack, true),
+            IpFamilyPolicy::RequireDualStack
+        );
+        assert_eq!(
+            resolve_ip_family_policy(IpFamilyPolicy::PreferDualStack, false),
+            IpFamilyPolicy::SingleStack
+        );
+        assert_eq!(
+            resolve_ip_family_policy(IpFamilyPolicy::SingleStack, true),
+            IpFamilyPolicy::SingleStack
+        );
+    }
+
+    #[test]
+    fn test_validate_tolerations() {
+        let toleration = |key: &str, operator: TolerationOperator, value: &str| Toleration {
+            key: key.to_string(),
+            operator,
+            value: value.to_string(),
+            effect: Some(TaintEffect::NoSchedule),
+        };
+
+        assert!(validate_tolerations(&[]).is_ok());
+        assert!(validate_tolerations(&[toleration("qovery.com/pool", TolerationOperator::Equal, "gpu")]).is_ok());
+        assert!(validate_tolerations(&[toleration("spot", TolerationOperator::Exists, "")]).is_ok());
+        assert!(validate_tolerations(&[toleration(
+            "node.kubernetes.io/not-ready",
+            TolerationOperator::Exists,
+            ""
+        )])
+        .is_ok());
+
+        assert!(validate_tolerations(&[toleration("", TolerationOperator::Exists, "")]).is_err());
+        assert!(validate_tolerations(&[toleration("spot", TolerationOperator::Exists, "true")]).is_err());
+        assert!(validate_tolerations(&[toleration("pool", TolerationOperator::Equal, "gpu nodes")]).is_err());
+    }
+
+    #[test]
+    fn test_config_checksum() {
+        let file = |mount_path: &str, content: &str| MountedFile {
+            id: "file".to_string(),
+            long_id: Uuid::nil(),
+            mount_path: mount_path.to_string(),
+            file_content_b64: content.to_string(),
+        };
+
+        let checksum = config_checksum(&[file("/app/config.json", "e30=")]);
+        assert_eq!(checksum.len(), 16);
+        assert_eq!(checksum, config_checksum(&[file("/app/config.json", "e30=")]));
+        assert_ne!(checksum, config_checksum(&[file("/app/config.json", "e30K")]));
+        assert_ne!(checksum, config_checksum(&[file("/app/config.yaml", "e30=")]));
+        assert_ne!(checksum, config_checksum(&Vec::<MountedFile>::new()));
+    }
+
+    #[test]
+    fn test_spec_checksum() {
+        let context = |image_tag: &str, environment_variables: &[(&str, &str)], registry_token: &str| {
+            let mut context = tera::Context::new();
+            context.insert("image_tag", image_tag);
+            context.insert(
+                "environment_variables",
+                &environment_variables
+                    .iter()
+                    .map(|(key, value)| BTreeMap::from([("key", key), ("value", value)]))
+                    .collect::<Vec<_>>(),
+            );
+            context.insert("registry", &BTreeMap::from([("docker_json_config", registry_token)]));
+            context
+        };
+
+        let checksum = spec_checksum(&context("v1", &[("PORT", "ODA=")], "token"));
+        assert_eq!(checksum, spec_checksum(&context("v1", &[("PORT", "ODA4MA==")], "token")));
+        assert_eq!(checksum, spec_checksum(&context("v1", &[("PORT", "ODA=")], "renewed_token")));
+        assert_ne!(checksum, spec_checksum(&context("v2", &[("PORT", "ODA=")], "token")));
+        assert_ne!(checksum, spec_checksum(&context("v1", &[("HTTP_PORT", "ODA=")], "token")));
+        assert_ne!(checksum, spec_checksum(&context("v1", &[], "token")));
+
+        let mut context_with_checksum = context("v1", &[("PORT", "ODA=")], "token");
+        context_with_checksum.insert(SPEC_CHECKSUM_CONTEXT_KEY, &checksum);
+        assert_eq!(checksum, spec_checksum(&context_with_checksum));
+    }
+
+    #[test]
+    fn test_validate_custom_metadata() {
+        let labels = |key: &str, value: &str| CustomMetadata {
+            labels: BTreeMap::from([(key.to_string(), value.to_string())]),
+            annotations: BTreeMap::new(),
+        };
+        let annotations = |key: &str, value: &str| CustomMetadata {
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::from([(key.to_string(), value.to_string())]),
+        };
+
+        assert!(validate_custom_metadata(&CustomMetadata::default()).is_ok());
+        assert!(validate_custom_metadata(&labels("team", "backend")).is_ok());
+        assert!(validate_custom_metadata(&labels("backup.example.com/enabled", "true")).is_ok());
+        assert!(validate_custom_metadata(&labels("team", "")).is_ok());
+        assert!(validate_custom_metadata(&annotations("ad.datadoghq.com/logs", "[{\"source\": \"rust\"}]")).is_ok());
+
+        // invalid syntax
+        assert!(validate_custom_metadata(&labels("team", "back end")).is_err());
+        assert!(validate_custom_metadata(&labels("-team", "backend")).is_err());
+        assert!(validate_custom_metadata(&labels("Example.com/team", "backend")).is_err());
+        assert!(validate_custom_metadata(&annotations(&"a".repeat(64), "value")).is_err());
+
+        // reserved keys
+        assert!(validate_custom_metadata(&labels("qovery.com/service-id", "id")).is_err());
+        assert!(validate_custom_metadata(&labels("envId", "id")).is_err());
+        assert!(validate_custom_metadata(&labels("app.kubernetes.io/name", "app")).is_err());
+        assert!(validate_custom_metadata(&annotations("meta.helm.sh/release-name", "app")).is_err());
+        assert!(validate_custom_metadata(&annotations("nginx.ingress.kubernetes.io/rewrite-target", "/")).is_err());
+    }
+
+    #[test]
+    fn test_validate_init_containers() {
+        let init_container = |name: &str, command: &[&str], env_var: Option<&str>| ApplicationInitContainer {
+            name: name.to_string(),
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            image: None,
+            environment_vars: env_var
+                .map(|key| BTreeMap::from([(key.to_string(), "value".to_string())]))
+                .unwrap_or_default(),
+        };
+
+        assert!(validate_init_containers(&[], "app-z123").is_ok());
+        assert!(validate_init_containers(
+            &[
+                init_container("fetch-config", &["sh", "-c", "curl -o /config/app.yaml $CONFIG_URL"], None),
+                init_container("migrate", &["./migrate"], Some("DATABASE_TIMEOUT")),
+            ],
+            "app-z123"
+        )
+        .is_ok());
+
+        assert!(validate_init_containers(&[init_container("Migrate", &["./migrate"], None)], "app-z123").is_err());
+        assert!(validate_init_containers(&[init_container("migrate-", &["./migrate"], None)], "app-z123").is_err());
+        assert!(validate_init_containers(&[init_container("migrate", &[], None)], "app-z123").is_err());
+        assert!(
+            validate_init_containers(&[init_container("migrate", &["./migrate"], Some("A=B"))], "app-z123").is_err()
+        );
+        assert!(validate_init_containers(&[init_container("app-z123", &["./migrate"], None)], "app-z123").is_err());
+        assert!(validate_init_containers(
+            &[
+                init_container("migrate", &["./migrate"], None),
+                init_container("migrate", &["./seed"], None)
+            ],
+            "app-z123"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_storages() {
+        let storage = |mount_point: &str, size_in_gib: u32, storage_class: Option<&str>| Storage {
+            id: "z1234".to_string(),
+            long_id: Uuid::new_v4(),
+            name: "data".to_string(),
+            storage_type: (),
+            storage_class: storage_class.map(str::to_string),
+            size_in_gib,
+            mount_point: mount_point.to_string(),
+            snapshot_retention_in_days: 0,
+        };
+
+        assert!(validate_storages::<()>(&[]).is_ok());
+        assert!(validate_storages(&[storage("/data", 10, None), storage("/logs", 1, Some("nfs"))]).is_ok());
+        assert!(validate_storages(&[storage("/data", 10, Some("gp3.encrypted-1"))]).is_ok());
+
+        assert!(validate_storages(&[storage("/data", 0, None)]).is_err());
+        assert!(validate_storages(&[storage("data", 10, None)]).is_err());
+        assert!(validate_storages(&[storage("/data", 10, None), storage("/data", 1, None)]).is_err());
+        assert!(validate_storages(&[storage("/data", 10, Some(""))]).is_err());
+        assert!(validate_storages(&[storage("/data", 10, Some("Fast_SSD"))]).is_err());
+        assert!(validate_storages(&[storage("/data", 10, Some("-nfs"))]).is_err());
+    }
+
+    #[test]
+    fn test_validate_shared_volume_mounts() {
+        let uploads = Uuid::new_v4();
+        let assets = Uuid::new_v4();
+        let mount = |shared_volume_id: Uuid, mount_path: &str| SharedVolumeMount {
+            shared_volume_id,
+            mount_path: mount_path.to_string(),
+            read_only: false,
+        };
+
+        assert!(validate_shared_volume_mounts(&[], ["/data"].into_iter()).is_ok());
+        assert!(validate_shared_volume_mounts(
+            &[mount(uploads, "/uploads"), mount(assets, "/assets")],
+            ["/data"].into_iter()
+        )
+        .is_ok());
+
+        assert!(validate_shared_volume_mounts(&[mount(uploads, "uploads")], [].into_iter()).is_err());
+        assert!(validate_shared_volume_mounts(&[mount(uploads, "/data")], ["/data"].into_iter()).is_err());
+        assert!(validate_shared_volume_mounts(
+            &[mount(uploads, "/uploads"), mount(assets, "/uploads")],
+            [].into_iter()
+        )
+        .is_err());
+        assert!(validate_shared_volume_mounts(
+            &[mount(uploads, "/uploads"), mount(uploads, "/uploads-copy")],
+            [].into_iter()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_hpa_metrics() {
+        let metric = |name: &str, target_average_value: &str| HpaCustomMetric {
+            name: name.to_string(),
+            target_average_value: target_average_value.to_string(),
+        };
+        let cron_trigger = KedaTrigger {
+            r#type: KedaTriggerType::Cron,
+            metadata: BTreeMap::new(),
+            authentication_env_vars: BTreeMap::new(),
+        };
+
+        assert!(validate_hpa_metrics(None, &[], &[]).is_ok());
+        assert!(validate_hpa_metrics(
+            Some(80),
+            &[metric("http_requests_per_second", "100"), metric("queue_depth", "500m")],
+            &[]
+        )
+        .is_ok());
+
+        assert!(validate_hpa_metrics(Some(0), &[], &[]).is_err());
+        assert!(validate_hpa_metrics(Some(101), &[], &[]).is_err());
+        assert!(validate_hpa_metrics(None, &[metric("http-requests", "100")], &[]).is_err());
+        assert!(validate_hpa_metrics(None, &[metric("http_requests", "")], &[]).is_err());
+        assert!(validate_hpa_metrics(None, &[metric("queue_depth", "1"), metric("queue_depth", "2")], &[]).is_err());
+        assert!(validate_hpa_metrics(None, &[metric("queue_depth", "1")], &[cron_trigger]).is_err());
+    }
+
+    #[test]
+    fn test_validate_keda_triggers() {
+        let sqs_trigger = KedaTrigger {
+            r#type: KedaTriggerType::AwsSqsQueue,
+            metadata: BTreeMap::from([
+                (
+                    "queueURL".to_string(),
+                    "https://sqs.eu-west-3.amazonaws.com/1234/jobs".to_string(),
+                ),
+                ("awsRegion".to_string(), "eu-west-3".to_string()),
+            ]),
+            authentication_env_vars: BTreeMap::from([
+                ("awsAccessKeyID".to_string(), "AWS_ACCESS_KEY_ID".to_string()),
+                ("awsSecretAccessKey".to_string(), "AWS_SECRET_ACCESS_KEY".to_string()),
+            ]),
+        };
+        let env_vars = ["AWS_ACCESS_KEY_ID", "AWS_SECRET_ACCESS_KEY"];
+
+        assert!(validate_keda_triggers(&[], [].into_iter()).is_ok());
+        assert!(validate_keda_triggers(&[sqs_trigger.clone()], env_vars.into_iter()).is_ok());
+        assert!(validate_keda_triggers(&[sqs_trigger.clone()], ["AWS_ACCESS_KEY_ID"].into_iter()).is_err());
+
+        let mut missing_region = sqs_trigger;
+        missing_region.metadata.remove("awsRegion");
+        assert!(validate_keda_triggers(&[missing_region], env_vars.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_validate_external_secrets() {
+        let secret = |name: &str, store: &str, key: &str| ExternalSecretVariable {
+            name: name.to_string(),
+            store: store.to_string(),
+            key: key.to_string(),
+            property: Some("password".to_string()),
+        };
+
+        assert!(validate_external_secrets(&[], ["DATABASE_URL"].into_iter()).is_ok());
+        assert!(validate_external_secrets(
+            &[
+                secret("DB_PASSWORD", "vault", "apps/db"),
+                secret("_API_KEY", "aws", "api")
+            ],
+            ["DATABASE_URL"].into_iter()
+        )
+        .is_ok());
+        assert!(validate_external_secrets(&[secret("DB-PASSWORD", "vault", "apps/db")], [].into_iter()).is_err());
+        assert!(validate_external_secrets(&[secret("1_PASSWORD", "vault", "apps/db")], [].into_iter()).is_err());
+        assert!(validate_external_secrets(&[secret("DB_PASSWORD", "vault", "")], [].into_iter()).is_err());
+        assert!(validate_external_secrets(&[secret("DB_PASSWORD", "Vault!", "apps/db")], [].into_iter()).is_err());
+        assert!(
+            validate_external_secrets(&[secret("DB_PASSWORD", "vault", "apps/db")], ["DB_PASSWORD"].into_iter())
+                .is_err()
+        );
+        assert!(validate_external_secrets(
+            &[
+                secret("DB_PASSWORD", "vault", "apps/db"),
+                secret("DB_PASSWORD", "vault", "apps/other")
+            ],
+            [].into_iter()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_probes() {
+        let probe = |r#type: ProbeType, port: u32, success_threshold: u32| Probe {
+            r#type,
+            port,
+            initial_delay_seconds: 0,
+            period_seconds: 10,
+            timeout_seconds: 1,
+            success_threshold,
+            failure_threshold: 3,
+        };
+        let grpc = probe(ProbeType::Grpc { service: None }, 50051, 1);
+        let exec = probe(
+            ProbeType::Exec {
+                commands: vec!["cat".to_string(), "/tmp/healthy".to_string()],
+            },
+            0,
+            1,
+        );
+        let readiness = probe(ProbeType::Tcp { host: None }, 8080, 2);
+
+        assert!(validate_probes(None, None, None).is_ok());
+        assert!(validate_probes(Some(&readiness), Some(&grpc), Some(&exec)).is_ok());
+        // only the readiness probe may need several successes
+        assert!(validate_probes(None, Some(&readiness), None).is_err());
+        assert!(validate_probes(None, None, Some(&readiness)).is_err());
+        assert!(validate_probes(None, Some(&probe(ProbeType::Grpc { service: None }, 0, 1)), None).is_err());
+        assert!(validate_probes(
+            None,
+            None,
+            Some(&probe(
+                ProbeType::Http {
+                    path: "/".to_string(),
+                    scheme: "HTTP".to_string()
+                },
+                70000,
+                1
+            ))
+        )
+        .is_err());
+        assert!(validate_probes(Some(&probe(ProbeType::Exec { commands: vec![] }, 0, 1)), None, None).is_err());
+    }
+
+    #[test]
+    fn test_validate_smoke_test() {
+        let http = |path: &str, port: u16, expected_status: u16| SmokeTest {
+            check: SmokeTestCheck::Http {
+                path: path.to_string(),
+                port,
+                expected_status,
+            },
+            timeout_in_seconds: 60,
+        };
+        assert!(validate_smoke_test(&http("/health?ready=true", 8080, 200), [80, 8080]).is_ok());
+        assert!(validate_smoke_test(&http("health", 8080, 200), [8080]).is_err());
+        assert!(validate_smoke_test(&http("/health';reboot", 8080, 200), [8080]).is_err());
+        assert!(validate_smoke_test(&http("/health", 8081, 200), [8080]).is_err());
+        assert!(validate_smoke_test(&http("/health", 8080, 1200), [8080]).is_err());
+
+        let command = |command: Vec<String>, timeout_in_seconds: u32| SmokeTest {
+            check: SmokeTestCheck::Command { command, image: None },
+            timeout_in_seconds,
+        };
+        assert!(validate_smoke_test(&command(vec!["./smoke-test".to_string()], 60), []).is_ok());
+        assert!(validate_smoke_test(&command(vec![], 60), []).is_err());
+        assert!(validate_smoke_test(&command(vec!["./smoke-test".to_string()], 0), []).is_err());
+    }
 }
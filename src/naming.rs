@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Max length of a Kubernetes object name following RFC 1123 label rules (service, secret, deployment, ...)
+pub const KUBE_NAME_MAX_LENGTH: usize = 63;
+/// Helm stores the release name in labels, with some room for its own suffixes
+pub const HELM_RELEASE_NAME_MAX_LENGTH: usize = 53;
+const HASH_SUFFIX_LENGTH: usize = 8;
+
+/// Lower case the name and replace every char not allowed in a RFC 1123 label by a dash.
+/// Consecutive dashes are collapsed and the name cannot start or end with a dash.
+pub fn sanitized_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    for c in name.chars().flat_map(|c| c.to_lowercase()) {
+        let c = if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            c
+        } else {
+            '-'
+        };
+        if c == '-' && (sanitized.is_empty() || sanitized.ends_with('-')) {
+            continue;
+        }
+        sanitized.push(c);
+    }
+
+    sanitized.trim_end_matches('-').to_string()
+}
+
+/// Return the name untouched if it fits in `max_length`, otherwise truncate it and append a hash of the full name.
+/// The hash keeps two long names sharing the same prefix distinct once truncated.
+pub fn truncate_with_hash(name: &str, max_length: usize) -> String {
+    if name.len() <= max_length {
+        return name.to_string();
+    }
+
+    let hash = format!("{:08x}", fnv1a_hash(name));
+    if max_length <= HASH_SUFFIX_LENGTH + 1 {
+        return hash[..max_length.min(HASH_SUFFIX_LENGTH)].to_string();
+    }
+
+    // names are expected to be ascii, but never split a char in the middle
+    let mut prefix_length = max_length - HASH_SUFFIX_LENGTH - 1;
+    while !name.is_char_boundary(prefix_length) {
+        prefix_length -= 1;
+    }
+
+    format!("{}-{}", name[..prefix_length].trim_end_matches('-'), hash)
+}
+
+/// Name of a Kubernetes object, i.e: for a service, deployment or statefulset
+pub fn kube_name(name: &str) -> String {
+    truncate_with_hash(&sanitized_name(name), KUBE_NAME_MAX_LENGTH)
+}
+
+pub fn helm_release_name(prefix: &str, id: impl Display) -> String {
+    truncate_with_hash(&format!("{prefix}-{id}"), HELM_RELEASE_NAME_MAX_LENGTH)
+}
+
+/// Name of a secret derived from a service kube name, i.e: `my-app-registry`
+pub fn secret_name(kube_name: &str, suffix: &str) -> String {
+    truncate_with_hash(&format!("{kube_name}-{suffix}"), KUBE_NAME_MAX_LENGTH)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NameCollision {
+    pub name: String,
+    pub owners: Vec<String>,
+}
+
+/// Find every name claimed by more than one owner. Owners are kept in their insertion order.
+pub fn find_collisions<N: Into<String>, O: Into<String>>(
+    names: impl IntoIterator<Item = (N, O)>,
+) -> Vec<NameCollision> {
+    let mut order: Vec<String> = vec![];
+    let mut owners_by_name: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, owner) in names {
+        let name = name.into();
+        let owners = owners_by_name.entry(name.clone()).or_insert_with(|| {
+            order.push(name);
+            vec![]
+        });
+        owners.push(owner.into());
+    }
+
+    order
+        .into_iter()
+        .filter_map(|name| {
+            let owners = owners_by_name.remove(&name)?;
+            if owners.len() > 1 {
+                Some(NameCollision { name, owners })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// FNV-1a is stable across Rust releases, unlike DefaultHasher, so a given name always gets the same suffix
+fn fnv1a_hash(value: &str) -> u32 {
+    value
+        .bytes()
+        .fold(0x811c9dc5_u32, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x01000193))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitized_name() {
+        assert_eq!(sanitized_name("my-app"), "my-app");
+        assert_eq!(sanitized_name("My_App v2"), "my-app-v2");
+        assert_eq!(sanitized_name("--front__end--"), "front-end");
+        assert_eq!(sanitized_name("Été"), "t");
+    }
+
+    #[test]
+    fn test_truncate_with_hash() {
+        let short = "application-z1234567-z1234567";
+        assert_eq!(truncate_with_hash(short, HELM_RELEASE_NAME_MAX_LENGTH), short);
+
+        let long = "a".repeat(80);
+        let truncated = truncate_with_hash(&long, KUBE_NAME_MAX_LENGTH);
+        assert_eq!(truncated.len(), KUBE_NAME_MAX_LENGTH);
+        assert!(truncated.starts_with(&"a".repeat(54)));
+        // deterministic
+        assert_eq!(truncated, truncate_with_hash(&long, KUBE_NAME_MAX_LENGTH));
+
+        // names sharing the same prefix stay distinct
+        let other_long = format!("{}b", "a".repeat(79));
+        assert_ne!(truncated, truncate_with_hash(&other_long, KUBE_NAME_MAX_LENGTH));
+    }
+
+    #[test]
+    fn test_truncate_with_hash_does_not_end_prefix_with_dash() {
+        let name = format!("{}-{}", "a".repeat(53), "b".repeat(20));
+        let truncated = truncate_with_hash(&name, KUBE_NAME_MAX_LENGTH);
+        assert!(!truncated.contains("--"));
+        assert!(truncated.len() <= KUBE_NAME_MAX_LENGTH);
+    }
+
+    #[test]
+    fn test_names_fit_kubernetes_limits() {
+        let long_name = "x".repeat(100);
+        assert!(kube_name(&long_name).len() <= KUBE_NAME_MAX_LENGTH);
+        assert!(secret_name(&long_name, "registry").len() <= KUBE_NAME_MAX_LENGTH);
+        assert!(helm_release_name("container", &long_name).len() <= HELM_RELEASE_NAME_MAX_LENGTH);
+        assert_eq!(secret_name("my-app", "registry"), "my-app-registry");
+    }
+
+    #[test]
+    fn test_find_collisions() {
+        let collisions = find_collisions(vec![
+            ("app", "application app-1"),
+            ("db", "database db-1"),
+            ("app", "container app-2"),
+            ("job", "job job-1"),
+        ]);
+
+        assert_eq!(
+            collisions,
+            vec![NameCollision {
+                name: "app".to_string(),
+                owners: vec!["application app-1".to_string(), "container app-2".to_string()],
+            }]
+        );
+        assert!(find_collisions(vec![("a", "1"), ("b", "2")]).is_empty());
+    }
+}
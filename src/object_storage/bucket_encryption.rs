@@ -0,0 +1,142 @@
+use crate::object_storage::errors::ObjectStorageError;
+use crate::runtime::block_on;
+use rusoto_s3::{
+    GetBucketEncryptionRequest, PutBucketEncryptionRequest, S3Client, ServerSideEncryptionByDefault,
+    ServerSideEncryptionConfiguration, ServerSideEncryptionRule, S3,
+};
+
+const SSE_S3_ALGORITHM: &str = "AES256";
+const SSE_KMS_ALGORITHM: &str = "aws:kms";
+
+/// Server side encryption applied by default to the objects of a bucket
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum BucketEncryption {
+    /// Keys owned by the provider, i.e: SSE-S3 on AWS or Google-managed keys on GCS
+    #[default]
+    ProviderManaged,
+    /// Customer managed key, i.e: a KMS key id or ARN on AWS, a Cloud KMS key resource name on GCS
+    Kms { key_id: String },
+}
+
+impl BucketEncryption {
+    fn to_s3_encryption_by_default(&self) -> ServerSideEncryptionByDefault {
+        match self {
+            BucketEncryption::ProviderManaged => ServerSideEncryptionByDefault {
+                sse_algorithm: SSE_S3_ALGORITHM.to_string(),
+                kms_master_key_id: None,
+            },
+            BucketEncryption::Kms { key_id } => ServerSideEncryptionByDefault {
+                sse_algorithm: SSE_KMS_ALGORITHM.to_string(),
+                kms_master_key_id: Some(key_id.to_string()),
+            },
+        }
+    }
+
+    fn from_s3_encryption_by_default(encryption: ServerSideEncryptionByDefault) -> Option<BucketEncryption> {
+        match (encryption.sse_algorithm.as_str(), encryption.kms_master_key_id) {
+            (SSE_S3_ALGORITHM, _) => Some(BucketEncryption::ProviderManaged),
+            (SSE_KMS_ALGORITHM, Some(key_id)) => Some(BucketEncryption::Kms { key_id }),
+            // AWS managed `aws/s3` key, not owned by the customer
+            (SSE_KMS_ALGORITHM, None) => Some(BucketEncryption::ProviderManaged),
+            _ => None,
+        }
+    }
+
+    /// Whether a bucket encrypted with `actual` satisfies this requirement, KMS keys can be referenced
+    /// either by id or by ARN, the ARN ending with the key id
+    pub fn is_satisfied_by(&self, actual: Option<&BucketEncryption>) -> bool {
+        match (self, actual) {
+            (_, None) => false,
+            (BucketEncryption::ProviderManaged, Some(_)) => true,
+            (BucketEncryption::Kms { key_id: expected }, Some(BucketEncryption::Kms { key_id: actual })) => {
+                expected == actual
+                    || actual.ends_with(&format!("/{expected}"))
+                    || expected.ends_with(&format!("/{actual}"))
+            }
+            (BucketEncryption::Kms { .. }, Some(BucketEncryption::ProviderManaged)) => false,
+        }
+    }
+}
+
+pub(super) fn s3_set_bucket_encryption(
+    client: &S3Client,
+    bucket_name: &str,
+    encryption: &BucketEncryption,
+) -> Result<(), ObjectStorageError> {
+    block_on(client.put_bucket_encryption(PutBucketEncryptionRequest {
+        bucket: bucket_name.to_string(),
+        server_side_encryption_configuration: ServerSideEncryptionConfiguration {
+            rules: vec![ServerSideEncryptionRule {
+                apply_server_side_encryption_by_default: Some(encryption.to_s3_encryption_by_default()),
+                // reduces the number of calls to KMS, and their cost
+                bucket_key_enabled: Some(matches!(encryption, BucketEncryption::Kms { .. })),
+            }],
+        },
+        ..Default::default()
+    }))
+    .map_err(|e| ObjectStorageError::CannotSetBucketEncryption {
+        bucket_name: bucket_name.to_string(),
+        raw_error_message: e.to_string(),
+    })
+}
+
+pub(super) fn s3_get_bucket_encryption(
+    client: &S3Client,
+    bucket_name: &str,
+) -> Result<Option<BucketEncryption>, ObjectStorageError> {
+    let encryption = block_on(client.get_bucket_encryption(GetBucketEncryptionRequest {
+        bucket: bucket_name.to_string(),
+        expected_bucket_owner: None,
+    }))
+    .map_err(|e| ObjectStorageError::CannotGetBucket {
+        bucket_name: bucket_name.to_string(),
+        raw_error_message: e.to_string(),
+    })?;
+
+    Ok(encryption
+        .server_side_encryption_configuration
+        .and_then(|configuration| configuration.rules.into_iter().next())
+        .and_then(|rule| rule.apply_server_side_encryption_by_default)
+        .and_then(BucketEncryption::from_s3_encryption_by_default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kms(key_id: &str) -> BucketEncryption {
+        BucketEncryption::Kms {
+            key_id: key_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_s3_encryption_by_default_roundtrip() {
+        for encryption in [BucketEncryption::ProviderManaged, kms("1234abcd")] {
+            assert_eq!(
+                BucketEncryption::from_s3_encryption_by_default(encryption.to_s3_encryption_by_default()),
+                Some(encryption)
+            );
+        }
+        assert_eq!(
+            BucketEncryption::from_s3_encryption_by_default(ServerSideEncryptionByDefault {
+                sse_algorithm: "aws:kms:dsse".to_string(),
+                kms_master_key_id: None,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_satisfied_by() {
+        let key_arn = "arn:aws:kms:eu-west-3:123456789012:key/1234abcd";
+
+        assert!(BucketEncryption::ProviderManaged.is_satisfied_by(Some(&BucketEncryption::ProviderManaged)));
+        assert!(BucketEncryption::ProviderManaged.is_satisfied_by(Some(&kms("1234abcd"))));
+        assert!(!BucketEncryption::ProviderManaged.is_satisfied_by(None));
+        assert!(kms("1234abcd").is_satisfied_by(Some(&kms(key_arn))));
+        assert!(kms(key_arn).is_satisfied_by(Some(&kms("1234abcd"))));
+        assert!(!kms("1234abcd").is_satisfied_by(Some(&kms("5678efgh"))));
+        assert!(!kms("1234abcd").is_satisfied_by(Some(&BucketEncryption::ProviderManaged)));
+    }
+}
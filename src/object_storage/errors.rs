@@ -39,6 +39,21 @@ pub enum ObjectStorageError {
         bucket_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot set lifecycle policy on bucket `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotSetBucketLifecyclePolicy {
+        bucket_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot set encryption on bucket `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotSetBucketEncryption {
+        bucket_name: String,
+        raw_error_message: String,
+    },
+    #[error("Invalid encryption of bucket `{bucket_name:?}`: {raw_error_message:?}.")]
+    InvalidBucketEncryption {
+        bucket_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot activate bucket versioning on bucket `{bucket_name:?}`: {raw_error_message:?}.")]
     CannotActivateBucketVersioning {
         bucket_name: String,
@@ -62,4 +77,91 @@ pub enum ObjectStorageError {
         object_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot generate presigned url for object `{object_name:?}` in `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotGeneratePresignedUrl {
+        bucket_name: String,
+        object_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot list objects from bucket `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotListObjects {
+        bucket_name: String,
+        raw_error_message: String,
+    },
+}
+
+// Markers of connection errors, timeouts, 5xx and throttling responses, in both S3 and GCS errors
+const TRANSIENT_ERROR_MARKERS: [&str; 16] = [
+    "connection",
+    "connect error",
+    "dispatch failure",
+    "timed out",
+    "timeout",
+    "broken pipe",
+    "internalerror",
+    "internal server error",
+    "bad gateway",
+    "serviceunavailable",
+    "service unavailable",
+    "gateway timeout",
+    "slowdown",
+    "throttl",
+    "too many requests",
+    "rate limit",
+];
+
+impl ObjectStorageError {
+    /// Whether the same call may succeed later, i.e: network blip or provider overloaded,
+    /// as opposed to permanent errors such as invalid names, missing objects or denied accesses
+    pub fn is_transient(&self) -> bool {
+        let raw_error_message = match self {
+            ObjectStorageError::CannotInstantiateClient { .. }
+            | ObjectStorageError::QuotasExceeded { .. }
+            | ObjectStorageError::InvalidBucketName { .. }
+            | ObjectStorageError::InvalidBucketEncryption { .. }
+            | ObjectStorageError::CannotGeneratePresignedUrl { .. } => return false,
+            ObjectStorageError::CannotCreateBucket { raw_error_message, .. }
+            | ObjectStorageError::CannotGetBucket { raw_error_message, .. }
+            | ObjectStorageError::CannotDeleteBucket { raw_error_message, .. }
+            | ObjectStorageError::CannotEmptyBucket { raw_error_message, .. }
+            | ObjectStorageError::CannotTagBucket { raw_error_message, .. }
+            | ObjectStorageError::CannotSetBucketLifecyclePolicy { raw_error_message, .. }
+            | ObjectStorageError::CannotSetBucketEncryption { raw_error_message, .. }
+            | ObjectStorageError::CannotActivateBucketVersioning { raw_error_message, .. }
+            | ObjectStorageError::CannotGetObjectFile { raw_error_message, .. }
+            | ObjectStorageError::CannotUploadFile { raw_error_message, .. }
+            | ObjectStorageError::CannotDeleteFile { raw_error_message, .. }
+            | ObjectStorageError::CannotListObjects { raw_error_message, .. } => raw_error_message.to_lowercase(),
+        };
+
+        TRANSIENT_ERROR_MARKERS
+            .iter()
+            .any(|marker| raw_error_message.contains(marker))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        let get_object_error = |raw_error_message: &str| ObjectStorageError::CannotGetObjectFile {
+            bucket_name: "bucket".to_string(),
+            object_name: "object".to_string(),
+            raw_error_message: raw_error_message.to_string(),
+        };
+
+        assert!(get_object_error("error trying to connect: tcp connect error").is_transient());
+        assert!(get_object_error("<Code>ServiceUnavailable</Code>").is_transient());
+        assert!(get_object_error("503 Service Unavailable").is_transient());
+        assert!(get_object_error("operation timed out").is_transient());
+        assert!(!get_object_error("<Code>NoSuchKey</Code>").is_transient());
+        assert!(!get_object_error("403 Forbidden").is_transient());
+        assert!(!ObjectStorageError::InvalidBucketName {
+            bucket_name: "bucket".to_string(),
+            raw_error_message: "connection".to_string(),
+        }
+        .is_transient());
+    }
 }
@@ -1,13 +1,19 @@
+use crate::object_storage::bucket_encryption::BucketEncryption;
 use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::lifecycle::LifecycleRule;
+use crate::object_storage::listing::{ObjectsIterator, ObjectsPage};
+use crate::object_storage::presigned_url::validate_presigned_url_ttl;
 use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject};
 use crate::object_storage::{Kind, ObjectStorage};
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use crate::services::gcp::object_storage_service::ObjectStorageService;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use url::Url;
 use uuid::Uuid;
 
 pub struct GoogleOS {
@@ -37,6 +43,25 @@ impl GoogleOS {
             service,
         }
     }
+
+    fn set_bucket_encryption(
+        &self,
+        bucket_name: &str,
+        bucket_encryption: &BucketEncryption,
+    ) -> Result<(), ObjectStorageError> {
+        match bucket_encryption {
+            // Google-managed keys are used when no KMS key is set
+            BucketEncryption::ProviderManaged => Ok(()),
+            BucketEncryption::Kms { key_id } => {
+                self.service
+                    .set_bucket_default_kms_key(bucket_name, key_id)
+                    .map_err(|e| ObjectStorageError::CannotSetBucketEncryption {
+                        bucket_name: bucket_name.to_string(),
+                        raw_error_message: e.to_string(),
+                    })
+            }
+        }
+    }
 }
 
 impl ObjectStorage for GoogleOS {
@@ -66,14 +91,16 @@ impl ObjectStorage for GoogleOS {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_encryption: &BucketEncryption,
     ) -> Result<Bucket, ObjectStorageError> {
         if let Ok(existing_bucket) = self.get_bucket(bucket_name) {
+            self.set_bucket_encryption(bucket_name, bucket_encryption)?;
             return Ok(existing_bucket);
         }
 
         let creation_date: DateTime<Utc> = Utc::now();
         // TODO(benjaminch): Add bucket versioning option
-        match self.service.create_bucket(
+        let bucket = match self.service.create_bucket(
             self.project_id.as_str(),
             bucket_name,
             self.region.clone(),
@@ -89,12 +116,33 @@ impl ObjectStorage for GoogleOS {
                 ),
             ])),
         ) {
-            Ok(o) => Ok(o),
-            Err(e) => Err(ObjectStorageError::CannotCreateBucket {
-                bucket_name: bucket_name.to_string(),
-                raw_error_message: e.to_string(),
-            }),
-        }
+            Ok(o) => o,
+            Err(e) => {
+                return Err(ObjectStorageError::CannotCreateBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e.to_string(),
+                })
+            }
+        };
+
+        self.set_bucket_encryption(bucket_name, bucket_encryption)?;
+        Ok(bucket)
+    }
+
+    fn get_bucket_encryption(&self, bucket_name: &str) -> Result<Option<BucketEncryption>, ObjectStorageError> {
+        let kms_key_name =
+            self.service
+                .get_bucket_default_kms_key(bucket_name)
+                .map_err(|e| ObjectStorageError::CannotGetBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e.to_string(),
+                })?;
+
+        // objects are always encrypted at rest on GCS, with Google-managed keys by default
+        Ok(Some(match kms_key_name {
+            Some(key_id) => BucketEncryption::Kms { key_id },
+            None => BucketEncryption::ProviderManaged,
+        }))
     }
 
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
@@ -132,6 +180,13 @@ impl ObjectStorage for GoogleOS {
         }
     }
 
+    fn set_lifecycle_policy(&self, bucket_name: &str, _rules: &[LifecycleRule]) -> Result<(), ObjectStorageError> {
+        Err(ObjectStorageError::CannotSetBucketLifecyclePolicy {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: "Lifecycle policies are not supported on Google Cloud Storage buckets yet".to_string(),
+        })
+    }
+
     fn get_object(&self, bucket_name: &str, object_key: &str) -> Result<BucketObject, ObjectStorageError> {
         match self.service.get_object(bucket_name, object_key) {
             Err(e) => Err(ObjectStorageError::CannotGetObjectFile {
@@ -165,6 +220,36 @@ impl ObjectStorage for GoogleOS {
         }
     }
 
+    fn put_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+    ) -> Result<u64, ObjectStorageError> {
+        self.service
+            .put_object_stream(bucket_name, object_key, reader)
+            .map_err(|e| ObjectStorageError::CannotUploadFile {
+                bucket_name: bucket_name.to_string(),
+                object_name: object_key.to_string(),
+                raw_error_message: e.to_string(),
+            })
+    }
+
+    fn get_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        self.service
+            .get_object_stream(bucket_name, object_key, writer)
+            .map_err(|e| ObjectStorageError::CannotGetObjectFile {
+                bucket_name: bucket_name.to_string(),
+                object_name: object_key.to_string(),
+                raw_error_message: e.to_string(),
+            })
+    }
+
     fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError> {
         // TODO(benjaminch): not optimal since fine grained statuses are not returned, should know if get is error because file doesn't exist or if anything else
         if self.get_object(bucket_name, object_key).is_err() {
@@ -179,13 +264,58 @@ impl ObjectStorage for GoogleOS {
                 raw_error_message: e.to_string(),
             })
     }
+
+    fn generate_presigned_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        ttl: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        let presigned_url_error = |raw_error_message: String| ObjectStorageError::CannotGeneratePresignedUrl {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message,
+        };
+
+        validate_presigned_url_ttl(ttl).map_err(presigned_url_error)?;
+        let url = self
+            .service
+            .generate_signed_url(bucket_name, object_key, ttl)
+            .map_err(|e| presigned_url_error(e.to_string()))?;
+
+        Url::parse(&url).map_err(|e| presigned_url_error(e.to_string()))
+    }
+
+    fn list_objects<'a>(
+        &'a self,
+        bucket_name: &'a str,
+        prefix: Option<&'a str>,
+    ) -> Result<ObjectsIterator<'a>, ObjectStorageError> {
+        Ok(ObjectsIterator::new(move |page_token| {
+            let (objects, next_page_token) = self
+                .service
+                .list_objects_summaries_page(bucket_name, prefix, page_token)
+                .map_err(|e| ObjectStorageError::CannotListObjects {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e.to_string(),
+                })?;
+
+            Ok(ObjectsPage {
+                objects,
+                next_page_token,
+            })
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::object_storage::bucket_encryption::BucketEncryption;
     use crate::object_storage::errors::ObjectStorageError;
     use crate::object_storage::google_object_storage::GoogleOS;
-    use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, BucketRegion, ObjectStorage};
+    use crate::object_storage::{
+        Bucket, BucketDeleteStrategy, BucketObject, BucketObjectSummary, BucketRegion, ObjectStorage,
+    };
     use crate::services::gcp::object_storage_regions::GcpStorageRegion;
     use crate::services::gcp::object_storage_service::{ObjectStorageService, ObjectStorageServiceError};
     use chrono::Utc;
@@ -196,6 +326,7 @@ mod tests {
     use std::sync::Arc;
     use std::time::Duration;
     use tempfile::tempdir;
+    use url::Url;
     use uuid::Uuid;
 
     #[test]
@@ -281,7 +412,7 @@ mod tests {
 
             // execute:
             let created_bucket = object_storage
-                .create_bucket(bucket_name, bucket_ttl, bucket_versioning)
+                .create_bucket(bucket_name, bucket_ttl, bucket_versioning, &BucketEncryption::ProviderManaged)
                 .expect("Error creating bucket");
 
             // verify:
@@ -347,7 +478,7 @@ mod tests {
 
             // execute:
             let created_bucket = object_storage
-                .create_bucket(bucket_name, bucket_ttl, bucket_versioning)
+                .create_bucket(bucket_name, bucket_ttl, bucket_versioning, &BucketEncryption::ProviderManaged)
                 .expect("Error creating bucket");
 
             // verify:
@@ -465,6 +596,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_bucket_encryption_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let kms_key_name = "projects/project_123/locations/europe-west9/keyRings/qovery/cryptoKeys/buckets";
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.get_bucket_default_kms_key(bucket_name)).then_return(Ok(None));
+        faux::when!(service_mock.get_bucket_default_kms_key("kms-bucket"))
+            .then_return(Ok(Some(kms_key_name.to_string())));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        );
+
+        // execute & verify:
+        assert_eq!(
+            Ok(Some(BucketEncryption::ProviderManaged)),
+            object_storage.get_bucket_encryption(bucket_name)
+        );
+        assert_eq!(
+            Ok(()),
+            object_storage.verify_bucket_encryption(
+                "kms-bucket",
+                &BucketEncryption::Kms {
+                    key_id: kms_key_name.to_string()
+                }
+            )
+        );
+        assert!(matches!(
+            object_storage.verify_bucket_encryption(
+                bucket_name,
+                &BucketEncryption::Kms {
+                    key_id: kms_key_name.to_string()
+                }
+            ),
+            Err(ObjectStorageError::InvalidBucketEncryption { .. })
+        ));
+    }
+
     #[test]
     fn delete_bucket_success_test() {
         // setup:
@@ -769,4 +944,121 @@ mod tests {
             retrieved_object.unwrap_err()
         );
     }
+
+    #[test]
+    fn put_stream_failure_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let object_key = "test-object-key";
+        let raw_error_message = "put error message";
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.put_object_stream(bucket_name, object_key, _)).then_return(Err(
+            ObjectStorageServiceError::CannotPutObjectToBucket {
+                bucket_name: bucket_name.to_string(),
+                object_key: object_key.to_string(),
+                raw_error_message: raw_error_message.to_string(),
+            },
+        ));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        );
+
+        // execute:
+        let uploaded_size = object_storage.put_stream(bucket_name, object_key, &mut "test-object-content".as_bytes());
+
+        // verify:
+        assert_eq!(
+            ObjectStorageError::CannotUploadFile {
+                bucket_name: bucket_name.to_string(),
+                object_name: object_key.to_string(),
+                raw_error_message: format!(
+                    "Cannot put object `{}` to bucket `{}`: \"{}\"",
+                    object_key, bucket_name, raw_error_message
+                ),
+            },
+            uploaded_size.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn generate_presigned_url_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let object_key = "test-object-key";
+        let signed_url = format!("https://storage.googleapis.com/{bucket_name}/{object_key}?X-Goog-Signature=abc");
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.generate_signed_url(bucket_name, object_key, _)).then_return(Ok(signed_url.clone()));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        );
+
+        // execute:
+        let url = object_storage.generate_presigned_url(bucket_name, object_key, Duration::from_secs(3600));
+        let too_long_url =
+            object_storage.generate_presigned_url(bucket_name, object_key, Duration::from_secs(30 * 24 * 3600));
+
+        // verify:
+        assert_eq!(Some(signed_url.as_str()), url.ok().as_ref().map(Url::as_str));
+        assert!(matches!(
+            too_long_url,
+            Err(ObjectStorageError::CannotGeneratePresignedUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn list_objects_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let prefix = "backups/";
+        let objects = vec![
+            BucketObjectSummary {
+                bucket_name: bucket_name.to_string(),
+                key: "backups/1.tgz".to_string(),
+                size_in_bytes: 42,
+                last_modified: None,
+            },
+            BucketObjectSummary {
+                bucket_name: bucket_name.to_string(),
+                key: "backups/2.tgz".to_string(),
+                size_in_bytes: 42,
+                last_modified: None,
+            },
+        ];
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.list_objects_summaries_page(bucket_name, _, _))
+            .then_return(Ok((objects.clone(), None)));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        );
+
+        // execute:
+        let listed_objects = object_storage
+            .list_objects(bucket_name, Some(prefix))
+            .expect("Cannot list objects")
+            .collect::<Result<Vec<_>, _>>();
+
+        // verify:
+        assert_eq!(Ok(objects), listed_objects);
+    }
 }
@@ -0,0 +1,207 @@
+use crate::object_storage::errors::ObjectStorageError;
+use crate::runtime::block_on;
+use rusoto_s3::{
+    BucketLifecycleConfiguration, DeleteBucketLifecycleRequest, LifecycleExpiration, LifecycleRule as S3LifecycleRule,
+    LifecycleRuleFilter, PutBucketLifecycleConfigurationRequest, S3Client, Transition, S3,
+};
+use std::time::Duration;
+
+const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+
+/// Cheaper storage classes objects can be moved to when getting old
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageClass {
+    InfrequentAccess,
+    Archive,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifecycleTransition {
+    /// Age of the objects to move, rounded down to whole days
+    pub after: Duration,
+    pub storage_class: StorageClass,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifecycleRule {
+    pub id: String,
+    /// Only objects whose key starts with this prefix are concerned, all objects of the bucket otherwise
+    pub prefix: Option<String>,
+    /// Age after which objects are deleted, rounded down to whole days
+    pub expiration: Option<Duration>,
+    pub transitions: Vec<LifecycleTransition>,
+}
+
+fn to_days(duration: Duration) -> u64 {
+    duration.as_secs() / DAY_IN_SECONDS
+}
+
+impl LifecycleRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("lifecycle rule id cannot be empty".to_string());
+        }
+        if self.expiration.is_none() && self.transitions.is_empty() {
+            return Err(format!("lifecycle rule `{}` has neither expiration nor transitions", self.id));
+        }
+
+        let transitions_days = self.transitions.iter().map(|t| to_days(t.after)).collect::<Vec<_>>();
+        if let Some(days) = self.expiration.map(to_days) {
+            if days == 0 {
+                return Err(format!("lifecycle rule `{}` expiration must be at least one day", self.id));
+            }
+            if transitions_days.iter().any(|transition_days| *transition_days >= days) {
+                return Err(format!(
+                    "lifecycle rule `{}` transitions must happen before objects expiration",
+                    self.id
+                ));
+            }
+        }
+        if transitions_days.iter().any(|days| *days == 0) {
+            return Err(format!("lifecycle rule `{}` transitions must be at least one day", self.id));
+        }
+
+        Ok(())
+    }
+
+    /// Rule for S3 compatible object storages, storage classes names being specific to each provider
+    pub(super) fn to_s3_lifecycle_rule(&self, storage_class_name: fn(StorageClass) -> &'static str) -> S3LifecycleRule {
+        S3LifecycleRule {
+            id: Some(self.id.to_string()),
+            status: "Enabled".to_string(),
+            filter: Some(LifecycleRuleFilter {
+                prefix: Some(self.prefix.clone().unwrap_or_default()),
+                ..Default::default()
+            }),
+            expiration: self.expiration.map(|expiration| LifecycleExpiration {
+                days: Some(to_days(expiration) as i64),
+                ..Default::default()
+            }),
+            transitions: match self.transitions.is_empty() {
+                true => None,
+                false => Some(
+                    self.transitions
+                        .iter()
+                        .map(|transition| Transition {
+                            days: Some(to_days(transition.after) as i64),
+                            storage_class: Some(storage_class_name(transition.storage_class).to_string()),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Replace the lifecycle policy of an S3 compatible bucket, an empty list of rules removes the policy
+pub(super) fn s3_set_lifecycle_policy(
+    client: &S3Client,
+    bucket_name: &str,
+    rules: &[LifecycleRule],
+    storage_class_name: fn(StorageClass) -> &'static str,
+) -> Result<(), ObjectStorageError> {
+    let lifecycle_error = |raw_error_message: String| ObjectStorageError::CannotSetBucketLifecyclePolicy {
+        bucket_name: bucket_name.to_string(),
+        raw_error_message,
+    };
+
+    for rule in rules {
+        rule.validate().map_err(lifecycle_error)?;
+    }
+
+    if rules.is_empty() {
+        return block_on(client.delete_bucket_lifecycle(DeleteBucketLifecycleRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+        }))
+        .map_err(|e| lifecycle_error(e.to_string()));
+    }
+
+    block_on(
+        client.put_bucket_lifecycle_configuration(PutBucketLifecycleConfigurationRequest {
+            bucket: bucket_name.to_string(),
+            lifecycle_configuration: Some(BucketLifecycleConfiguration {
+                rules: rules
+                    .iter()
+                    .map(|rule| rule.to_s3_lifecycle_rule(storage_class_name))
+                    .collect(),
+            }),
+            ..Default::default()
+        }),
+    )
+    .map_err(|e| lifecycle_error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days(days: u64) -> Duration {
+        Duration::from_secs(days * DAY_IN_SECONDS)
+    }
+
+    fn logs_rule() -> LifecycleRule {
+        LifecycleRule {
+            id: "logs".to_string(),
+            prefix: Some("logs/".to_string()),
+            expiration: Some(days(365)),
+            transitions: vec![
+                LifecycleTransition {
+                    after: days(30),
+                    storage_class: StorageClass::InfrequentAccess,
+                },
+                LifecycleTransition {
+                    after: days(90),
+                    storage_class: StorageClass::Archive,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_rule_validate() {
+        assert!(logs_rule().validate().is_ok());
+
+        let with = |update: fn(&mut LifecycleRule)| {
+            let mut rule = logs_rule();
+            update(&mut rule);
+            rule.validate()
+        };
+        assert!(with(|r| r.id = "".to_string()).is_err());
+        assert!(with(|r| {
+            r.expiration = None;
+            r.transitions = vec![];
+        })
+        .is_err());
+        assert!(with(|r| r.expiration = Some(Duration::from_secs(3600))).is_err());
+        assert!(with(|r| r.expiration = Some(days(60))).is_err());
+        assert!(with(|r| r.transitions[0].after = Duration::from_secs(3600)).is_err());
+        assert!(with(|r| r.expiration = None).is_ok());
+    }
+
+    #[test]
+    fn test_to_s3_lifecycle_rule() {
+        let rule = logs_rule().to_s3_lifecycle_rule(|storage_class| match storage_class {
+            StorageClass::InfrequentAccess => "STANDARD_IA",
+            StorageClass::Archive => "GLACIER",
+        });
+
+        assert_eq!(rule.id.as_deref(), Some("logs"));
+        assert_eq!(rule.status, "Enabled");
+        assert_eq!(rule.filter.and_then(|filter| filter.prefix).as_deref(), Some("logs/"));
+        assert_eq!(rule.expiration.and_then(|expiration| expiration.days), Some(365));
+        assert_eq!(
+            rule.transitions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|transition| (transition.days, transition.storage_class))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(30), Some("STANDARD_IA".to_string())),
+                (Some(90), Some("GLACIER".to_string())),
+            ]
+        );
+    }
+}
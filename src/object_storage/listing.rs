@@ -0,0 +1,175 @@
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::retry::RetryPolicy;
+use crate::object_storage::BucketObjectSummary;
+use crate::runtime::block_on;
+use chrono::{DateTime, Utc};
+use rusoto_s3::{ListObjectsV2Request, S3Client, S3};
+use std::vec;
+
+pub struct ObjectsPage {
+    pub objects: Vec<BucketObjectSummary>,
+    /// Continuation token of the next page, none for the last page
+    pub next_page_token: Option<String>,
+}
+
+type FetchPage<'a> = Box<dyn FnMut(Option<String>) -> Result<ObjectsPage, ObjectStorageError> + 'a>;
+
+/// Objects of a bucket, pages are only fetched from the provider once the previous one has been consumed.
+/// Iteration stops after the first error.
+pub struct ObjectsIterator<'a> {
+    fetch_page: FetchPage<'a>,
+    current_page: vec::IntoIter<BucketObjectSummary>,
+    next_page_token: Option<String>,
+    is_last_page_fetched: bool,
+}
+
+impl<'a> ObjectsIterator<'a> {
+    pub fn new(fetch_page: impl FnMut(Option<String>) -> Result<ObjectsPage, ObjectStorageError> + 'a) -> Self {
+        ObjectsIterator {
+            fetch_page: Box::new(fetch_page),
+            current_page: vec![].into_iter(),
+            next_page_token: None,
+            is_last_page_fetched: false,
+        }
+    }
+
+    /// Retry the fetch of each page on its own, so a transient error does not restart the whole listing
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let mut fetch_page = self.fetch_page;
+        ObjectsIterator {
+            fetch_page: Box::new(move |page_token: Option<String>| policy.retry(|| fetch_page(page_token.clone()))),
+            ..self
+        }
+    }
+}
+
+impl Iterator for ObjectsIterator<'_> {
+    type Item = Result<BucketObjectSummary, ObjectStorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(object) = self.current_page.next() {
+                return Some(Ok(object));
+            }
+            if self.is_last_page_fetched {
+                return None;
+            }
+
+            match (self.fetch_page)(self.next_page_token.take()) {
+                Ok(page) => {
+                    self.current_page = page.objects.into_iter();
+                    self.is_last_page_fetched = page.next_page_token.is_none();
+                    self.next_page_token = page.next_page_token;
+                }
+                Err(e) => {
+                    self.is_last_page_fetched = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Single page of objects of an S3 compatible bucket
+pub(super) fn s3_list_objects_page(
+    client: &S3Client,
+    bucket_name: &str,
+    prefix: Option<&str>,
+    continuation_token: Option<String>,
+) -> Result<ObjectsPage, ObjectStorageError> {
+    let res = block_on(client.list_objects_v2(ListObjectsV2Request {
+        bucket: bucket_name.to_string(),
+        prefix: prefix.map(str::to_string),
+        continuation_token,
+        ..Default::default()
+    }))
+    .map_err(|e| ObjectStorageError::CannotListObjects {
+        bucket_name: bucket_name.to_string(),
+        raw_error_message: e.to_string(),
+    })?;
+
+    Ok(ObjectsPage {
+        objects: res
+            .contents
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|o| {
+                Some(BucketObjectSummary {
+                    bucket_name: bucket_name.to_string(),
+                    key: o.key?,
+                    size_in_bytes: o.size.unwrap_or_default().max(0) as u64,
+                    last_modified: o
+                        .last_modified
+                        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+                        .map(|date| date.with_timezone(&Utc)),
+                })
+            })
+            .collect(),
+        next_page_token: res.next_continuation_token,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(key: &str) -> BucketObjectSummary {
+        BucketObjectSummary {
+            bucket_name: "backups".to_string(),
+            key: key.to_string(),
+            size_in_bytes: 42,
+            last_modified: None,
+        }
+    }
+
+    #[test]
+    fn test_objects_iterator_follows_continuation_tokens() {
+        let mut requested_tokens = vec![];
+        let keys = ObjectsIterator::new(|token: Option<String>| {
+            requested_tokens.push(token.clone());
+            Ok(match token.as_deref() {
+                None => ObjectsPage {
+                    objects: vec![object("a"), object("b")],
+                    next_page_token: Some("page-2".to_string()),
+                },
+                Some("page-2") => ObjectsPage {
+                    objects: vec![],
+                    next_page_token: Some("page-3".to_string()),
+                },
+                _ => ObjectsPage {
+                    objects: vec![object("c")],
+                    next_page_token: None,
+                },
+            })
+        })
+        .map(|object| object.map(|o| o.key))
+        .collect::<Result<Vec<_>, _>>();
+
+        assert_eq!(keys, Ok(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+        assert_eq!(
+            requested_tokens,
+            vec![None, Some("page-2".to_string()), Some("page-3".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_objects_iterator_stops_on_error() {
+        let mut objects = ObjectsIterator::new(|token: Option<String>| match token {
+            None => Ok(ObjectsPage {
+                objects: vec![object("a")],
+                next_page_token: Some("page-2".to_string()),
+            }),
+            Some(_) => Err(ObjectStorageError::CannotListObjects {
+                bucket_name: "backups".to_string(),
+                raw_error_message: "timeout".to_string(),
+            }),
+        });
+
+        assert_eq!(objects.next().map(|o| o.map(|o| o.key)), Some(Ok("a".to_string())));
+        assert!(matches!(
+            objects.next(),
+            Some(Err(ObjectStorageError::CannotListObjects { .. }))
+        ));
+        assert!(objects.next().is_none());
+    }
+}
@@ -1,17 +1,29 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::time::Duration;
 
 use crate::cloud_provider::aws::regions::AwsRegion;
 use crate::models::scaleway::ScwZone;
 use crate::models::ToCloudProviderFormat;
+use crate::object_storage::bucket_encryption::BucketEncryption;
 use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::lifecycle::LifecycleRule;
+use crate::object_storage::listing::ObjectsIterator;
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use enum_dispatch::enum_dispatch;
+use url::Url;
 
+pub mod bucket_encryption;
 pub mod errors;
 pub mod google_object_storage;
+pub mod lifecycle;
+pub mod listing;
+pub mod multipart;
+pub mod presigned_url;
+pub mod retry;
 pub mod s3;
 pub mod scaleway_object_storage;
 
@@ -49,13 +61,33 @@ pub trait ObjectStorage {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_encryption: &BucketEncryption,
     ) -> Result<Bucket, ObjectStorageError>;
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError>;
+    /// Default server side encryption of the bucket objects, none when they are not encrypted by default
+    fn get_bucket_encryption(&self, bucket_name: &str) -> Result<Option<BucketEncryption>, ObjectStorageError>;
+    /// Make sure objects of a bucket are encrypted as required, i.e: with a given KMS key
+    fn verify_bucket_encryption(
+        &self,
+        bucket_name: &str,
+        expected_encryption: &BucketEncryption,
+    ) -> Result<(), ObjectStorageError> {
+        let encryption = self.get_bucket_encryption(bucket_name)?;
+        match expected_encryption.is_satisfied_by(encryption.as_ref()) {
+            true => Ok(()),
+            false => Err(ObjectStorageError::InvalidBucketEncryption {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: format!("expected {expected_encryption:?} encryption, got {encryption:?}"),
+            }),
+        }
+    }
     fn delete_bucket(
         &self,
         bucket_name: &str,
         bucket_delete_strategy: BucketDeleteStrategy,
     ) -> Result<(), ObjectStorageError>;
+    /// Replace the expiration and transition rules of a bucket, an empty list of rules removes them all
+    fn set_lifecycle_policy(&self, bucket_name: &str, rules: &[LifecycleRule]) -> Result<(), ObjectStorageError>;
     fn get_object(&self, bucket_name: &str, object_key: &str) -> Result<BucketObject, ObjectStorageError>;
     fn put_object(
         &self,
@@ -63,7 +95,34 @@ pub trait ObjectStorage {
         object_key: &str,
         file_path: &Path,
     ) -> Result<BucketObject, ObjectStorageError>;
+    /// Upload the content of a reader without loading it all in memory, returns the size of the uploaded object.
+    /// Objects bigger than `multipart::MULTIPART_UPLOAD_THRESHOLD_IN_BYTES` are uploaded in parts,
+    /// each part being retried on failure.
+    fn put_stream(&self, bucket_name: &str, object_key: &str, reader: &mut dyn Read)
+        -> Result<u64, ObjectStorageError>;
+    /// Download an object straight into a writer, returns the number of bytes written
+    fn get_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError>;
     fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError>;
+    /// Time limited download url of an object, to be handed out without proxying its content.
+    /// `ttl` cannot exceed `presigned_url::PRESIGNED_URL_MAX_TTL`.
+    fn generate_presigned_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        ttl: Duration,
+    ) -> Result<Url, ObjectStorageError>;
+    /// List objects metadata, without fetching their content.
+    /// Objects are fetched page by page while iterating, following the provider continuation tokens.
+    fn list_objects<'a>(
+        &'a self,
+        bucket_name: &'a str,
+        prefix: Option<&'a str>,
+    ) -> Result<ObjectsIterator<'a>, ObjectStorageError>;
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -108,3 +167,11 @@ pub struct BucketObject {
     pub key: String,
     pub value: Vec<u8>,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BucketObjectSummary {
+    pub bucket_name: String,
+    pub key: String,
+    pub size_in_bytes: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
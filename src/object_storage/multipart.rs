@@ -0,0 +1,311 @@
+use crate::object_storage::errors::ObjectStorageError;
+use crate::runtime::block_on;
+use retry::delay::Fibonacci;
+use rusoto_s3::{
+    AbortMultipartUploadRequest, CompleteMultipartUploadRequest, CompletedMultipartUpload, CompletedPart,
+    CreateMultipartUploadRequest, GetObjectRequest, PutObjectRequest, S3Client, StreamingBody, UploadPartRequest, S3,
+};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// Streamed objects bigger than this are uploaded in several parts
+pub const MULTIPART_UPLOAD_THRESHOLD_IN_BYTES: usize = 100 * 1024 * 1024;
+/// S3 accepts up to 10 000 parts per upload and GCS wants chunks aligned on 256KiB, which both fit 64MiB parts
+pub const MULTIPART_UPLOAD_PART_SIZE_IN_BYTES: usize = 64 * 1024 * 1024;
+const PART_UPLOAD_MAX_RETRIES: usize = 5;
+
+/// Chunk of a streamed object, uploaded on its own
+pub(crate) struct Part {
+    /// Starts at 1, as S3 part numbers do
+    pub number: i64,
+    /// Position of the first byte of the part in the object
+    pub offset: u64,
+    pub content: Vec<u8>,
+    pub is_last: bool,
+}
+
+pub(crate) enum StreamUpload<'a> {
+    /// The whole stream is under the multipart threshold, it is sent in a single request
+    Single(Vec<u8>),
+    Multipart(Parts<'a>),
+}
+
+/// Parts of a stream, read lazily so at most two parts are held in memory once the upload has started
+pub(crate) struct Parts<'a> {
+    reader: &'a mut dyn Read,
+    part_size: usize,
+    pending: VecDeque<Vec<u8>>,
+    next_number: i64,
+    offset: u64,
+    is_eof: bool,
+}
+
+fn read_part(reader: &mut dyn Read, part_size: usize) -> std::io::Result<Vec<u8>> {
+    let mut part = Vec::with_capacity(part_size);
+    reader.take(part_size as u64).read_to_end(&mut part)?;
+    Ok(part)
+}
+
+impl Parts<'_> {
+    // One part is read ahead, to know whether the current one is the last of the stream
+    fn read_ahead(&mut self) -> std::io::Result<()> {
+        while !self.is_eof && self.pending.len() < 2 {
+            let part = read_part(self.reader, self.part_size)?;
+            self.is_eof = part.len() < self.part_size;
+            if !part.is_empty() {
+                self.pending.push_back(part);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for Parts<'_> {
+    type Item = std::io::Result<Part>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(err) = self.read_ahead() {
+            return Some(Err(err));
+        }
+
+        let content = self.pending.pop_front()?;
+        let part = Part {
+            number: self.next_number,
+            offset: self.offset,
+            is_last: self.pending.is_empty(),
+            content,
+        };
+        self.next_number += 1;
+        self.offset += part.content.len() as u64;
+
+        Some(Ok(part))
+    }
+}
+
+fn prepare_stream_upload_with(
+    reader: &mut dyn Read,
+    threshold: usize,
+    part_size: usize,
+) -> std::io::Result<StreamUpload<'_>> {
+    let mut pending = VecDeque::new();
+    let mut buffered_size = 0;
+
+    loop {
+        let part = read_part(reader, part_size)?;
+        let is_eof = part.len() < part_size;
+        buffered_size += part.len();
+        if !part.is_empty() {
+            pending.push_back(part);
+        }
+
+        if buffered_size > threshold {
+            return Ok(StreamUpload::Multipart(Parts {
+                reader,
+                part_size,
+                pending,
+                next_number: 1,
+                offset: 0,
+                is_eof,
+            }));
+        }
+
+        if is_eof {
+            return Ok(StreamUpload::Single(Vec::from(pending).concat()));
+        }
+    }
+}
+
+/// Buffer the stream up to the multipart threshold, to know if it can be sent in a single request
+pub(crate) fn prepare_stream_upload(reader: &mut dyn Read) -> std::io::Result<StreamUpload<'_>> {
+    prepare_stream_upload_with(reader, MULTIPART_UPLOAD_THRESHOLD_IN_BYTES, MULTIPART_UPLOAD_PART_SIZE_IN_BYTES)
+}
+
+/// Retry the upload of a single part, so a transient error does not restart the whole upload
+pub(crate) fn retry_part<T, E>(mut upload: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    retry::retry(Fibonacci::from(Duration::from_secs(1)).take(PART_UPLOAD_MAX_RETRIES), || {
+        upload()
+    })
+    .map_err(|err| err.error)
+}
+
+fn s3_upload_parts(
+    client: &S3Client,
+    bucket_name: &str,
+    object_key: &str,
+    upload_id: &str,
+    parts: Parts,
+) -> Result<u64, String> {
+    let mut completed_parts = vec![];
+    let mut size = 0;
+
+    for part in parts {
+        let part = part.map_err(|e| format!("Cannot read object content: {e}"))?;
+        let uploaded_part = retry_part(|| {
+            block_on(client.upload_part(UploadPartRequest {
+                bucket: bucket_name.to_string(),
+                key: object_key.to_string(),
+                upload_id: upload_id.to_string(),
+                part_number: part.number,
+                content_length: Some(part.content.len() as i64),
+                body: Some(StreamingBody::from(part.content.clone())),
+                ..Default::default()
+            }))
+        })
+        .map_err(|e| format!("Cannot upload part {}: {}", part.number, e))?;
+
+        size += part.content.len() as u64;
+        completed_parts.push(CompletedPart {
+            e_tag: uploaded_part.e_tag,
+            part_number: Some(part.number),
+        });
+    }
+
+    block_on(client.complete_multipart_upload(CompleteMultipartUploadRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        upload_id: upload_id.to_string(),
+        multipart_upload: Some(CompletedMultipartUpload {
+            parts: Some(completed_parts),
+        }),
+        ..Default::default()
+    }))
+    .map_err(|e| e.to_string())?;
+
+    Ok(size)
+}
+
+/// Streamed upload for S3 compatible object storages
+pub(crate) fn s3_put_stream(
+    client: &S3Client,
+    bucket_name: &str,
+    object_key: &str,
+    reader: &mut dyn Read,
+) -> Result<u64, ObjectStorageError> {
+    let upload_error = |raw_error_message: String| ObjectStorageError::CannotUploadFile {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message,
+    };
+
+    let parts = match prepare_stream_upload(reader).map_err(|e| upload_error(e.to_string()))? {
+        StreamUpload::Single(content) => {
+            let size = content.len() as u64;
+            block_on(client.put_object(PutObjectRequest {
+                bucket: bucket_name.to_string(),
+                key: object_key.to_string(),
+                body: Some(StreamingBody::from(content)),
+                ..Default::default()
+            }))
+            .map_err(|e| upload_error(e.to_string()))?;
+            return Ok(size);
+        }
+        StreamUpload::Multipart(parts) => parts,
+    };
+
+    let upload_id = block_on(client.create_multipart_upload(CreateMultipartUploadRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        ..Default::default()
+    }))
+    .map_err(|e| upload_error(e.to_string()))?
+    .upload_id
+    .ok_or_else(|| upload_error("No upload id returned for the multipart upload".to_string()))?;
+
+    s3_upload_parts(client, bucket_name, object_key, &upload_id, parts).map_err(|raw_error_message| {
+        // parts already uploaded are kept, and billed, until the upload is aborted
+        if let Err(e) = block_on(client.abort_multipart_upload(AbortMultipartUploadRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            upload_id: upload_id.to_string(),
+            ..Default::default()
+        })) {
+            error!("Cannot abort multipart upload of `{}` in `{}`: {}", object_key, bucket_name, e);
+        }
+        upload_error(raw_error_message)
+    })
+}
+
+/// Streamed download for S3 compatible object storages
+pub(crate) fn s3_get_stream(
+    client: &S3Client,
+    bucket_name: &str,
+    object_key: &str,
+    writer: &mut dyn Write,
+) -> Result<u64, ObjectStorageError> {
+    let get_error = |raw_error_message: String| ObjectStorageError::CannotGetObjectFile {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message,
+    };
+
+    let body = block_on(client.get_object(GetObjectRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        ..Default::default()
+    }))
+    .map_err(|e| get_error(e.to_string()))?
+    .body
+    .ok_or_else(|| get_error("Cannot get response body".to_string()))?;
+
+    std::io::copy(&mut body.into_blocking_read(), writer)
+        .map_err(|e| get_error(format!("Cannot write object content: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts_content(upload: StreamUpload) -> Vec<(i64, u64, Vec<u8>, bool)> {
+        match upload {
+            StreamUpload::Single(_) => panic!("expected a multipart upload"),
+            StreamUpload::Multipart(parts) => parts
+                .map(|part| {
+                    let part = part.expect("Cannot read part");
+                    (part.number, part.offset, part.content, part.is_last)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_prepare_stream_upload_under_threshold() {
+        let content = b"0123456789".to_vec();
+
+        // exactly at the threshold, still a single request
+        match prepare_stream_upload_with(&mut content.as_slice(), 10, 4).expect("Cannot prepare upload") {
+            StreamUpload::Single(body) => assert_eq!(body, content),
+            StreamUpload::Multipart(_) => panic!("expected a single upload"),
+        }
+
+        match prepare_stream_upload_with(&mut &b""[..], 10, 4).expect("Cannot prepare upload") {
+            StreamUpload::Single(body) => assert!(body.is_empty()),
+            StreamUpload::Multipart(_) => panic!("expected a single upload"),
+        }
+    }
+
+    #[test]
+    fn test_prepare_stream_upload_over_threshold() {
+        let content = b"0123456789abcd".to_vec();
+
+        let parts =
+            parts_content(prepare_stream_upload_with(&mut content.as_slice(), 5, 4).expect("Cannot prepare upload"));
+        assert_eq!(
+            parts,
+            vec![
+                (1, 0, b"0123".to_vec(), false),
+                (2, 4, b"4567".to_vec(), false),
+                (3, 8, b"89ab".to_vec(), false),
+                (4, 12, b"cd".to_vec(), true),
+            ]
+        );
+
+        // last part filling the part size entirely
+        let content = b"0123456789ab".to_vec();
+        let parts =
+            parts_content(prepare_stream_upload_with(&mut content.as_slice(), 5, 4).expect("Cannot prepare upload"));
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts.last(), Some(&(3, 8, b"89ab".to_vec(), true)));
+    }
+}
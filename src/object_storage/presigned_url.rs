@@ -0,0 +1,89 @@
+use crate::object_storage::errors::ObjectStorageError;
+use rusoto_core::credential::AwsCredentials;
+use rusoto_core::Region as RusotoRegion;
+use rusoto_s3::util::{PreSignedRequest, PreSignedRequestOption};
+use rusoto_s3::GetObjectRequest;
+use std::time::Duration;
+use url::Url;
+
+/// Signatures of S3 and GCS cannot be valid for more than a week
+pub const PRESIGNED_URL_MAX_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+pub(super) fn validate_presigned_url_ttl(ttl: Duration) -> Result<(), String> {
+    if ttl.as_secs() == 0 || ttl > PRESIGNED_URL_MAX_TTL {
+        return Err(format!(
+            "presigned url ttl must be between 1 second and {} seconds, got {} seconds",
+            PRESIGNED_URL_MAX_TTL.as_secs(),
+            ttl.as_secs()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Download url of an object of an S3 compatible object storage, signed locally without any call to the provider
+pub(super) fn s3_presigned_url(
+    region: &RusotoRegion,
+    credentials: &AwsCredentials,
+    bucket_name: &str,
+    object_key: &str,
+    ttl: Duration,
+) -> Result<Url, ObjectStorageError> {
+    let presigned_url_error = |raw_error_message: String| ObjectStorageError::CannotGeneratePresignedUrl {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message,
+    };
+
+    validate_presigned_url_ttl(ttl).map_err(presigned_url_error)?;
+
+    let url = GetObjectRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        ..Default::default()
+    }
+    .get_presigned_url(region, credentials, &PreSignedRequestOption { expires_in: ttl });
+
+    Url::parse(&url).map_err(|e| presigned_url_error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_presigned_url_ttl() {
+        assert!(validate_presigned_url_ttl(Duration::from_secs(3600)).is_ok());
+        assert!(validate_presigned_url_ttl(PRESIGNED_URL_MAX_TTL).is_ok());
+        assert!(validate_presigned_url_ttl(Duration::from_secs(0)).is_err());
+        assert!(validate_presigned_url_ttl(PRESIGNED_URL_MAX_TTL + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_s3_presigned_url() {
+        let credentials = AwsCredentials::new("ACCESS_KEY", "SECRET_KEY", None, None);
+        let url = s3_presigned_url(
+            &RusotoRegion::EuWest3,
+            &credentials,
+            "my-bucket",
+            "logs/report.json",
+            Duration::from_secs(900),
+        )
+        .expect("Cannot generate presigned url");
+
+        assert_eq!(url.host_str(), Some("s3.eu-west-3.amazonaws.com"));
+        assert_eq!(url.path(), "/my-bucket/logs/report.json");
+        let query = url.query_pairs().collect::<Vec<_>>();
+        assert!(query.iter().any(|(k, v)| k == "X-Amz-Expires" && v == "900"));
+        assert!(query.iter().any(|(k, _)| k == "X-Amz-Signature"));
+
+        assert!(s3_presigned_url(
+            &RusotoRegion::EuWest3,
+            &credentials,
+            "my-bucket",
+            "logs/report.json",
+            PRESIGNED_URL_MAX_TTL * 2,
+        )
+        .is_err());
+    }
+}
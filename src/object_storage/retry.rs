@@ -0,0 +1,268 @@
+use crate::object_storage::bucket_encryption::BucketEncryption;
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::lifecycle::LifecycleRule;
+use crate::object_storage::listing::ObjectsIterator;
+use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, Kind, ObjectStorage};
+use retry::delay::jitter;
+use retry::OperationResult;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+use url::Url;
+
+/// How failed object storage calls are retried, only transient errors (connection, timeout, 5xx, throttling)
+/// are retried, permanent ones being returned right away
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: usize,
+    /// Delay before the first retry, doubled on each following one
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Randomize delays, so concurrent engines do not hit the provider at the same time
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn no_retry() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    fn delays(&self) -> impl Iterator<Item = Duration> {
+        let (initial_delay, max_delay, with_jitter) = (self.initial_delay, self.max_delay, self.jitter);
+        (0..self.max_attempts.saturating_sub(1) as u32).map(move |retry| {
+            let delay = initial_delay.saturating_mul(2_u32.saturating_pow(retry)).min(max_delay);
+            match with_jitter {
+                true => jitter(delay),
+                false => delay,
+            }
+        })
+    }
+
+    pub fn retry<T>(
+        &self,
+        mut operation: impl FnMut() -> Result<T, ObjectStorageError>,
+    ) -> Result<T, ObjectStorageError> {
+        retry::retry(self.delays(), || match operation() {
+            Ok(result) => OperationResult::Ok(result),
+            Err(err) if err.is_transient() => {
+                warn!("Transient object storage error, retrying: {}", err);
+                OperationResult::Retry(err)
+            }
+            Err(err) => OperationResult::Err(err),
+        })
+        .map_err(|err| err.error)
+    }
+}
+
+/// Object storage retrying its calls according to a `RetryPolicy`
+pub struct RetryableObjectStorage<S: ObjectStorage> {
+    inner: S,
+    policy: RetryPolicy,
+}
+
+impl<S: ObjectStorage> RetryableObjectStorage<S> {
+    pub fn new(inner: S, policy: RetryPolicy) -> Self {
+        RetryableObjectStorage { inner, policy }
+    }
+}
+
+impl<S: ObjectStorage> ObjectStorage for RetryableObjectStorage<S> {
+    fn kind(&self) -> Kind {
+        self.inner.kind()
+    }
+
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_valid(&self) -> Result<(), ObjectStorageError> {
+        self.inner.is_valid()
+    }
+
+    fn workspace_dir_relative_path(&self) -> String {
+        self.inner.workspace_dir_relative_path()
+    }
+
+    fn bucket_exists(&self, bucket_name: &str) -> bool {
+        self.inner.bucket_exists(bucket_name)
+    }
+
+    fn create_bucket(
+        &self,
+        bucket_name: &str,
+        bucket_ttl: Option<Duration>,
+        bucket_versioning_activated: bool,
+        bucket_encryption: &BucketEncryption,
+    ) -> Result<Bucket, ObjectStorageError> {
+        self.policy.retry(|| {
+            self.inner
+                .create_bucket(bucket_name, bucket_ttl, bucket_versioning_activated, bucket_encryption)
+        })
+    }
+
+    fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
+        self.policy.retry(|| self.inner.get_bucket(bucket_name))
+    }
+
+    fn get_bucket_encryption(&self, bucket_name: &str) -> Result<Option<BucketEncryption>, ObjectStorageError> {
+        self.policy.retry(|| self.inner.get_bucket_encryption(bucket_name))
+    }
+
+    fn delete_bucket(
+        &self,
+        bucket_name: &str,
+        bucket_delete_strategy: BucketDeleteStrategy,
+    ) -> Result<(), ObjectStorageError> {
+        self.policy
+            .retry(|| self.inner.delete_bucket(bucket_name, bucket_delete_strategy.clone()))
+    }
+
+    fn set_lifecycle_policy(&self, bucket_name: &str, rules: &[LifecycleRule]) -> Result<(), ObjectStorageError> {
+        self.policy
+            .retry(|| self.inner.set_lifecycle_policy(bucket_name, rules))
+    }
+
+    fn get_object(&self, bucket_name: &str, object_key: &str) -> Result<BucketObject, ObjectStorageError> {
+        self.policy.retry(|| self.inner.get_object(bucket_name, object_key))
+    }
+
+    fn put_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        file_path: &Path,
+    ) -> Result<BucketObject, ObjectStorageError> {
+        self.policy
+            .retry(|| self.inner.put_object(bucket_name, object_key, file_path))
+    }
+
+    // A consumed reader cannot be replayed, parts of multipart uploads are retried on their own instead
+    fn put_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+    ) -> Result<u64, ObjectStorageError> {
+        self.inner.put_stream(bucket_name, object_key, reader)
+    }
+
+    // Bytes already written cannot be taken back, a retry would corrupt the output
+    fn get_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        self.inner.get_stream(bucket_name, object_key, writer)
+    }
+
+    fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError> {
+        self.policy.retry(|| self.inner.delete_object(bucket_name, object_key))
+    }
+
+    fn generate_presigned_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        ttl: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        self.policy
+            .retry(|| self.inner.generate_presigned_url(bucket_name, object_key, ttl))
+    }
+
+    fn list_objects<'a>(
+        &'a self,
+        bucket_name: &'a str,
+        prefix: Option<&'a str>,
+    ) -> Result<ObjectsIterator<'a>, ObjectStorageError> {
+        Ok(self
+            .inner
+            .list_objects(bucket_name, prefix)?
+            .with_retry_policy(self.policy.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn policy(max_attempts: usize) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            jitter: false,
+        }
+    }
+
+    fn list_error(raw_error_message: &str) -> ObjectStorageError {
+        ObjectStorageError::CannotListObjects {
+            bucket_name: "bucket".to_string(),
+            raw_error_message: raw_error_message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_delays() {
+        assert_eq!(
+            policy(6).delays().collect::<Vec<_>>(),
+            vec![1, 2, 4, 4, 4]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(RetryPolicy::no_retry().delays().count(), 0);
+        assert!(RetryPolicy::default()
+            .delays()
+            .all(|delay| delay <= RetryPolicy::default().max_delay));
+    }
+
+    #[test]
+    fn test_retry_policy_retries_transient_errors_only() {
+        let attempts = Cell::new(0);
+        let result = policy(3).retry(|| {
+            attempts.set(attempts.get() + 1);
+            match attempts.get() {
+                1 => Err(list_error("error trying to connect: Connection reset by peer")),
+                _ => Ok(attempts.get()),
+            }
+        });
+        assert_eq!(result, Ok(2));
+
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = policy(3).retry(|| {
+            attempts.set(attempts.get() + 1);
+            Err(list_error("<Code>SlowDown</Code>"))
+        });
+        assert_eq!(result, Err(list_error("<Code>SlowDown</Code>")));
+        assert_eq!(attempts.get(), 3);
+
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = policy(3).retry(|| {
+            attempts.set(attempts.get() + 1);
+            Err(list_error("<Code>AccessDenied</Code>"))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+}
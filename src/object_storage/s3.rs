@@ -1,13 +1,14 @@
 use chrono::{DateTime, Utc};
 use retry::delay::Fixed;
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
+use url::Url;
 
 use crate::cloud_provider::aws::regions::AwsRegion;
-use rusoto_core::credential::StaticProvider;
+use rusoto_core::credential::{AwsCredentials, StaticProvider};
 use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
 use rusoto_s3::{
     CreateBucketConfiguration, CreateBucketRequest, Delete, DeleteBucketRequest, DeleteObjectRequest,
@@ -17,7 +18,12 @@ use rusoto_s3::{
 };
 
 use crate::models::ToCloudProviderFormat;
+use crate::object_storage::bucket_encryption::{s3_get_bucket_encryption, s3_set_bucket_encryption, BucketEncryption};
 use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::lifecycle::{s3_set_lifecycle_policy, LifecycleRule, StorageClass};
+use crate::object_storage::listing::{s3_list_objects_page, ObjectsIterator};
+use crate::object_storage::multipart::{s3_get_stream, s3_put_stream};
+use crate::object_storage::presigned_url::s3_presigned_url;
 use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, BucketRegion, Kind, ObjectStorage};
 use crate::runtime::block_on;
 
@@ -44,19 +50,22 @@ impl S3 {
         StaticProvider::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None)
     }
 
-    fn get_s3_client(&self) -> S3Client {
-        let region = RusotoRegion::from_str(self.region.to_cloud_provider_format()).unwrap_or_else(|_| {
+    fn get_region(&self) -> RusotoRegion {
+        RusotoRegion::from_str(self.region.to_cloud_provider_format()).unwrap_or_else(|_| {
             panic!(
                 "S3 region `{}` doesn't seems to be valid.",
                 self.region.to_cloud_provider_format()
             )
-        });
+        })
+    }
+
+    fn get_s3_client(&self) -> S3Client {
         let client = Client::new_with(
             self.get_credentials(),
             HttpClient::new().expect("unable to create new Http client"),
         );
 
-        S3Client::new_with_client(client, region)
+        S3Client::new_with_client(client, self.get_region())
     }
 
     fn is_bucket_name_valid(bucket_name: &str) -> Result<(), ObjectStorageError> {
@@ -152,13 +161,16 @@ impl ObjectStorage for S3 {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_encryption: &BucketEncryption,
     ) -> Result<Bucket, ObjectStorageError> {
         S3::is_bucket_name_valid(bucket_name)?;
 
         let s3_client = self.get_s3_client();
 
         // check if bucket already exists, if so, no need to recreate it
+        // but its encryption is updated, so buckets created before a KMS key is required get it
         if let Ok(existing_bucket) = self.get_bucket(bucket_name) {
+            s3_set_bucket_encryption(&s3_client, bucket_name, bucket_encryption)?;
             return Ok(existing_bucket);
         }
 
@@ -175,6 +187,8 @@ impl ObjectStorage for S3 {
             });
         }
 
+        s3_set_bucket_encryption(&s3_client, bucket_name, bucket_encryption)?;
+
         let creation_date: DateTime<Utc> = Utc::now();
         if let Err(e) = block_on(s3_client.put_bucket_tagging(PutBucketTaggingRequest {
             bucket: bucket_name.to_string(),
@@ -210,6 +224,12 @@ impl ObjectStorage for S3 {
         self.get_bucket(bucket_name) // TODO(benjaminch): maybe doing a get here is avoidable
     }
 
+    fn get_bucket_encryption(&self, bucket_name: &str) -> Result<Option<BucketEncryption>, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        s3_get_bucket_encryption(&self.get_s3_client(), bucket_name)
+    }
+
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
         // if bucket doesn't exist, then return an error
         if !self.bucket_exists(bucket_name) {
@@ -294,6 +314,15 @@ impl ObjectStorage for S3 {
         }
     }
 
+    fn set_lifecycle_policy(&self, bucket_name: &str, rules: &[LifecycleRule]) -> Result<(), ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        s3_set_lifecycle_policy(&self.get_s3_client(), bucket_name, rules, |storage_class| match storage_class {
+            StorageClass::InfrequentAccess => "STANDARD_IA",
+            StorageClass::Archive => "GLACIER",
+        })
+    }
+
     fn get_object(&self, bucket_name: &str, object_key: &str) -> Result<BucketObject, ObjectStorageError> {
         S3::is_bucket_name_valid(bucket_name)?;
 
@@ -375,6 +404,28 @@ impl ObjectStorage for S3 {
         }
     }
 
+    fn put_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+    ) -> Result<u64, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        s3_put_stream(&self.get_s3_client(), bucket_name, object_key, reader)
+    }
+
+    fn get_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        s3_get_stream(&self.get_s3_client(), bucket_name, object_key, writer)
+    }
+
     fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError> {
         if S3::is_bucket_name_valid(bucket_name).is_err() {
             // bucket is missing it's ok as file can't be present
@@ -401,6 +452,31 @@ impl ObjectStorage for S3 {
             }),
         }
     }
+
+    fn generate_presigned_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        ttl: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        let credentials = AwsCredentials::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None);
+        s3_presigned_url(&self.get_region(), &credentials, bucket_name, object_key, ttl)
+    }
+
+    fn list_objects<'a>(
+        &'a self,
+        bucket_name: &'a str,
+        prefix: Option<&'a str>,
+    ) -> Result<ObjectsIterator<'a>, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+        Ok(ObjectsIterator::new(move |continuation_token| {
+            s3_list_objects_page(&s3_client, bucket_name, prefix, continuation_token)
+        }))
+    }
 }
 
 #[cfg(test)]
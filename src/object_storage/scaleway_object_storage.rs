@@ -1,16 +1,22 @@
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::time::Duration;
+use url::Url;
 
 use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, BucketRegion, Kind, ObjectStorage};
 
 use crate::models::scaleway::ScwZone;
+use crate::object_storage::bucket_encryption::BucketEncryption;
 use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::lifecycle::{s3_set_lifecycle_policy, LifecycleRule, StorageClass};
+use crate::object_storage::listing::{s3_list_objects_page, ObjectsIterator};
+use crate::object_storage::multipart::{s3_get_stream, s3_put_stream};
+use crate::object_storage::presigned_url::s3_presigned_url;
 use crate::runtime::block_on;
 use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
-use rusoto_credential::StaticProvider;
+use rusoto_credential::{AwsCredentials, StaticProvider};
 use rusoto_s3::{
     CreateBucketConfiguration, CreateBucketRequest, Delete, DeleteBucketRequest, DeleteObjectRequest,
     DeleteObjectsRequest, GetBucketLifecycleRequest, GetBucketTaggingRequest, GetBucketVersioningRequest,
@@ -38,15 +44,17 @@ impl ScalewayOS {
         }
     }
 
-    fn get_s3_client(&self) -> S3Client {
-        let region = RusotoRegion::Custom {
+    fn get_region(&self) -> RusotoRegion {
+        RusotoRegion::Custom {
             name: self.zone.region().to_string(),
             endpoint: self.get_endpoint_url_for_region(),
-        };
+        }
+    }
 
+    fn get_s3_client(&self) -> S3Client {
         let client = Client::new_with(self.get_credentials(), HttpClient::new().unwrap());
 
-        S3Client::new_with_client(client, region)
+        S3Client::new_with_client(client, self.get_region())
     }
 
     fn get_credentials(&self) -> StaticProvider {
@@ -157,10 +165,18 @@ impl ObjectStorage for ScalewayOS {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_encryption: &BucketEncryption,
     ) -> Result<Bucket, ObjectStorageError> {
         // TODO(benjamin): switch to `scaleway-api-rs` once object storage will be supported (https://github.com/Qovery/scaleway-api-rs/issues/12).
         ScalewayOS::is_bucket_name_valid(bucket_name)?;
 
+        if let BucketEncryption::Kms { .. } = bucket_encryption {
+            return Err(ObjectStorageError::CannotSetBucketEncryption {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: "KMS encryption is not supported by Scaleway object storage".to_string(),
+            });
+        }
+
         let s3_client = self.get_s3_client();
 
         // check if bucket already exists, if so, no need to recreate it
@@ -226,6 +242,13 @@ impl ObjectStorage for ScalewayOS {
         self.get_bucket(bucket_name) // TODO(benjaminch): maybe doing a get here is avoidable
     }
 
+    fn get_bucket_encryption(&self, bucket_name: &str) -> Result<Option<BucketEncryption>, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        // Scaleway has no default bucket encryption, objects can only be encrypted one by one with SSE-C
+        Ok(None)
+    }
+
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
         // if bucket doesn't exist, then return an error
         if !self.bucket_exists(bucket_name) {
@@ -314,6 +337,15 @@ impl ObjectStorage for ScalewayOS {
         }
     }
 
+    fn set_lifecycle_policy(&self, bucket_name: &str, rules: &[LifecycleRule]) -> Result<(), ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        s3_set_lifecycle_policy(&self.get_s3_client(), bucket_name, rules, |storage_class| match storage_class {
+            StorageClass::InfrequentAccess => "ONEZONE_IA",
+            StorageClass::Archive => "GLACIER",
+        })
+    }
+
     fn get_object(&self, bucket_name: &str, object_key: &str) -> Result<BucketObject, ObjectStorageError> {
         // TODO(benjamin): switch to `scaleway-api-rs` once object storage will be supported (https://github.com/Qovery/scaleway-api-rs/issues/12).
         ScalewayOS::is_bucket_name_valid(bucket_name)?;
@@ -395,6 +427,28 @@ impl ObjectStorage for ScalewayOS {
         }
     }
 
+    fn put_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+    ) -> Result<u64, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        s3_put_stream(&self.get_s3_client(), bucket_name, object_key, reader)
+    }
+
+    fn get_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        s3_get_stream(&self.get_s3_client(), bucket_name, object_key, writer)
+    }
+
     fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError> {
         if ScalewayOS::is_bucket_name_valid(bucket_name).is_err() {
             // bucket is missing it's ok as file can't be present
@@ -421,6 +475,31 @@ impl ObjectStorage for ScalewayOS {
             }),
         }
     }
+
+    fn generate_presigned_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        ttl: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        let credentials = AwsCredentials::new(self.access_key.clone(), self.secret_token.clone(), None, None);
+        s3_presigned_url(&self.get_region(), &credentials, bucket_name, object_key, ttl)
+    }
+
+    fn list_objects<'a>(
+        &'a self,
+        bucket_name: &'a str,
+        prefix: Option<&'a str>,
+    ) -> Result<ObjectsIterator<'a>, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+        Ok(ObjectsIterator::new(move |continuation_token| {
+            s3_list_objects_page(&s3_client, bucket_name, prefix, continuation_token)
+        }))
+    }
 }
 
 struct ScalewayObjectStorageErrorManager {}
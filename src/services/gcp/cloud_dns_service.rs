@@ -0,0 +1,145 @@
+use crate::models::gcp::JsonCredentials;
+use crate::runtime::block_on;
+use crate::services::gcp::google_cloud_sdk_types::new_gcp_credentials_file_from_credentials;
+use google_cloud_auth::project::Config;
+use google_cloud_auth::token::DefaultTokenSourceProvider;
+use google_cloud_token::{TokenSource, TokenSourceProvider};
+use reqwest::header::AUTHORIZATION;
+use reqwest::StatusCode;
+use serde_derive::Deserialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+const CLOUD_DNS_API_URL: &str = "https://dns.googleapis.com/dns/v1";
+const CLOUD_DNS_SCOPES: [&str; 1] = ["https://www.googleapis.com/auth/ndev.clouddns.readwrite"];
+
+#[derive(Clone, Error, Debug, PartialEq, Eq)]
+pub enum CloudDnsServiceError {
+    #[error("Cannot create cloud DNS service: {raw_error_message:?}")]
+    CannotCreateService { raw_error_message: String },
+    #[error("Cannot authenticate to cloud DNS: {raw_error_message:?}")]
+    InvalidCredentials { raw_error_message: String },
+    #[error("Cannot list managed zones of project `{project_id}`: {raw_error_message:?}")]
+    CannotListManagedZones {
+        project_id: String,
+        raw_error_message: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedZone {
+    pub name: String,
+    /// Fully qualified name of the zone, i.e: `example.com.`
+    pub dns_name: String,
+    /// Either `public` or `private`, zones are public when not set
+    #[serde(default)]
+    pub visibility: Option<String>,
+}
+
+impl ManagedZone {
+    pub fn is_private(&self) -> bool {
+        self.visibility.as_deref() == Some("private")
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListManagedZonesResponse {
+    #[serde(default)]
+    managed_zones: Vec<ManagedZone>,
+    next_page_token: Option<String>,
+}
+
+/// Google Cloud DNS has no client in the google cloud sdk we use, its REST API is called directly
+pub struct CloudDnsService {
+    token_source: Arc<dyn TokenSource>,
+    client: reqwest::Client,
+}
+
+impl CloudDnsService {
+    pub fn new(google_credentials: JsonCredentials) -> Result<Self, CloudDnsServiceError> {
+        let credentials_file = new_gcp_credentials_file_from_credentials(google_credentials).map_err(|e| {
+            CloudDnsServiceError::CannotCreateService {
+                raw_error_message: e.to_string(),
+            }
+        })?;
+        let token_source_provider = block_on(DefaultTokenSourceProvider::new_with_credentials(
+            Config {
+                audience: None,
+                scopes: Some(&CLOUD_DNS_SCOPES),
+                sub: None,
+            },
+            Box::new(credentials_file),
+        ))
+        .map_err(|e| CloudDnsServiceError::CannotCreateService {
+            raw_error_message: e.to_string(),
+        })?;
+
+        Ok(Self {
+            token_source: token_source_provider.token_source(),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    pub fn list_managed_zones(&self, project_id: &str) -> Result<Vec<ManagedZone>, CloudDnsServiceError> {
+        let cannot_list_managed_zones = |raw_error_message: String| CloudDnsServiceError::CannotListManagedZones {
+            project_id: project_id.to_string(),
+            raw_error_message,
+        };
+
+        block_on(async {
+            // token source returns the whole header value, i.e: `Bearer <token>`
+            let authorization =
+                self.token_source
+                    .token()
+                    .await
+                    .map_err(|e| CloudDnsServiceError::InvalidCredentials {
+                        raw_error_message: e.to_string(),
+                    })?;
+
+            let mut managed_zones = vec![];
+            let mut page_token: Option<String> = None;
+            loop {
+                let mut request = self
+                    .client
+                    .get(format!("{CLOUD_DNS_API_URL}/projects/{project_id}/managedZones"))
+                    .header(AUTHORIZATION, &authorization);
+                if let Some(page_token) = &page_token {
+                    request = request.query(&[("pageToken", page_token)]);
+                }
+
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|e| cannot_list_managed_zones(e.to_string()))?;
+                match response.status() {
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                        return Err(CloudDnsServiceError::InvalidCredentials {
+                            raw_error_message: response.text().await.unwrap_or_default(),
+                        })
+                    }
+                    status if !status.is_success() => {
+                        return Err(cannot_list_managed_zones(format!(
+                            "{status}: {}",
+                            response.text().await.unwrap_or_default()
+                        )))
+                    }
+                    _ => {}
+                }
+
+                let page: ListManagedZonesResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| cannot_list_managed_zones(e.to_string()))?;
+                managed_zones.extend(page.managed_zones);
+                match page.next_page_token {
+                    Some(next_page_token) => page_token = Some(next_page_token),
+                    None => break,
+                }
+            }
+
+            Ok(managed_zones)
+        })
+    }
+}
@@ -1,4 +1,5 @@
 pub mod artifact_registry_service;
+pub mod cloud_dns_service;
 mod google_cloud_sdk_types;
 pub mod object_storage_regions;
 pub mod object_storage_service;
@@ -1,9 +1,12 @@
 use crate::models::gcp::JsonCredentials;
 use crate::models::ToCloudProviderFormat;
-use crate::object_storage::{Bucket, BucketObject};
+use crate::object_storage::multipart::{prepare_stream_upload, retry_part, StreamUpload};
+use crate::object_storage::{Bucket, BucketObject, BucketObjectSummary};
 use crate::runtime::block_on;
 use crate::services::gcp::google_cloud_sdk_types::new_gcp_credentials_file_from_credentials;
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
 use google_cloud_storage::client::{Client, ClientConfig};
 use google_cloud_storage::http::buckets::delete::DeleteBucketRequest;
 use google_cloud_storage::http::buckets::get::GetBucketRequest;
@@ -11,20 +14,24 @@ use google_cloud_storage::http::buckets::insert::{BucketCreationConfig, InsertBu
 use google_cloud_storage::http::buckets::lifecycle::rule::{Action, ActionType, Condition};
 use google_cloud_storage::http::buckets::lifecycle::Rule;
 use google_cloud_storage::http::buckets::list::ListBucketsRequest;
+use google_cloud_storage::http::buckets::patch::{BucketPatchConfig, PatchBucketRequest};
 use google_cloud_storage::http::buckets::Lifecycle;
-use google_cloud_storage::http::buckets::{Bucket as GcpBucket, Versioning};
+use google_cloud_storage::http::buckets::{Bucket as GcpBucket, Encryption, Versioning};
 use google_cloud_storage::http::objects::delete::DeleteObjectRequest;
 use google_cloud_storage::http::objects::download::Range;
 use google_cloud_storage::http::objects::get::GetObjectRequest;
 use google_cloud_storage::http::objects::list::ListObjectsRequest;
 use google_cloud_storage::http::objects::upload::{UploadObjectRequest, UploadType};
 use google_cloud_storage::http::objects::Object as GcpObject;
+use google_cloud_storage::http::resumable_upload_client::ChunkSize;
+use google_cloud_storage::sign::{SignedURLMethod, SignedURLOptions};
 use governor::middleware::NoOpMiddleware;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{clock, RateLimiter};
 use reqwest::Body;
 use std::cmp::max;
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -43,6 +50,11 @@ pub enum ObjectStorageServiceError {
         bucket_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot update bucket `{bucket_name}`: {raw_error_message:?}")]
+    CannotUpdateBucket {
+        bucket_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot delete bucket `{bucket_name}`: {raw_error_message:?}")]
     CannotDeleteBucket {
         bucket_name: String,
@@ -73,6 +85,12 @@ pub enum ObjectStorageServiceError {
         bucket_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot generate signed url for object `{object_key}` from bucket `{bucket_name}`: {raw_error_message:?}")]
+    CannotGenerateSignedUrl {
+        object_key: String,
+        bucket_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot proceed, admission control blocked after several tries")]
     AdmissionControlCannotProceedAfterSeveralTries,
 }
@@ -236,6 +254,52 @@ impl ObjectStorageService {
         }
     }
 
+    /// Cloud KMS key used to encrypt new objects of the bucket, instead of a Google-managed key
+    pub fn set_bucket_default_kms_key(
+        &self,
+        bucket_name: &str,
+        kms_key_name: &str,
+    ) -> Result<(), ObjectStorageServiceError> {
+        self.wait_for_a_slot_in_admission_control(
+            std::time::Duration::from_secs(10 * 60),
+            StorageResourceKind::Bucket,
+        )?;
+        block_on(self.client.patch_bucket(&PatchBucketRequest {
+            bucket: bucket_name.to_string(),
+            metadata: Some(BucketPatchConfig {
+                encryption: Some(Encryption {
+                    default_kms_key_name: kms_key_name.to_string(),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .map(|_| ())
+        .map_err(|e| ObjectStorageServiceError::CannotUpdateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    /// Cloud KMS key used to encrypt new objects of the bucket, none when Google-managed keys are used
+    pub fn get_bucket_default_kms_key(&self, bucket_name: &str) -> Result<Option<String>, ObjectStorageServiceError> {
+        let gcp_bucket: GcpBucket = block_on(self.client.get_bucket(&GetBucketRequest {
+            bucket: bucket_name.to_string(),
+            if_metageneration_match: None,
+            if_metageneration_not_match: None,
+            projection: None,
+        }))
+        .map_err(|e| ObjectStorageServiceError::CannotGetBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })?;
+
+        Ok(gcp_bucket
+            .encryption
+            .map(|encryption| encryption.default_kms_key_name)
+            .filter(|kms_key_name| !kms_key_name.is_empty()))
+    }
+
     pub fn delete_bucket(
         &self,
         bucket_name: &str,
@@ -390,6 +454,133 @@ impl ObjectStorageService {
         })
     }
 
+    /// Upload a stream, objects over the multipart threshold are sent as a resumable upload, chunk by chunk
+    pub fn put_object_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+    ) -> Result<u64, ObjectStorageServiceError> {
+        let put_error = |raw_error_message: String| ObjectStorageServiceError::CannotPutObjectToBucket {
+            bucket_name: bucket_name.to_string(),
+            object_key: object_key.to_string(),
+            raw_error_message,
+        };
+
+        let parts = match prepare_stream_upload(reader).map_err(|e| put_error(e.to_string()))? {
+            StreamUpload::Single(content) => {
+                let size = content.len() as u64;
+                self.put_object(bucket_name, object_key, content)?;
+                return Ok(size);
+            }
+            StreamUpload::Multipart(parts) => parts,
+        };
+
+        self.wait_for_a_slot_in_admission_control(
+            std::time::Duration::from_secs(10 * 60),
+            StorageResourceKind::Object,
+        )?;
+        let upload_client = block_on(self.client.prepare_resumable_upload(
+            &UploadObjectRequest {
+                bucket: bucket_name.to_string(),
+                ..Default::default()
+            },
+            &UploadType::Multipart(Box::new(GcpObject {
+                name: object_key.to_string(),
+                ..Default::default()
+            })),
+        ))
+        .map_err(|e| put_error(e.to_string()))?;
+
+        let mut size = 0;
+        for part in parts {
+            let part = part.map_err(|e| put_error(format!("Cannot read object content: {e}")))?;
+            let last_byte = part.offset + part.content.len() as u64 - 1;
+            // total size of the object is only known once the last chunk has been read
+            let chunk_size = ChunkSize::new(part.offset, last_byte, part.is_last.then_some(last_byte + 1));
+            if let Err(e) =
+                retry_part(|| block_on(upload_client.upload_multiple_chunk(part.content.clone(), &chunk_size)))
+            {
+                if let Err(cancel_error) = block_on(upload_client.cancel()) {
+                    error!(
+                        "Cannot cancel resumable upload of `{}` in `{}`: {}",
+                        object_key, bucket_name, cancel_error
+                    );
+                }
+                return Err(put_error(format!("Cannot upload part {}: {}", part.number, e)));
+            }
+            size += part.content.len() as u64;
+        }
+
+        Ok(size)
+    }
+
+    /// Download an object chunk by chunk into a writer
+    pub fn get_object_stream(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageServiceError> {
+        let get_error = |raw_error_message: String| ObjectStorageServiceError::CannotGetObject {
+            bucket_name: bucket_name.to_string(),
+            object_key: object_key.to_string(),
+            raw_error_message,
+        };
+
+        block_on(async {
+            let mut stream = Box::pin(
+                self.client
+                    .download_streamed_object(
+                        &GetObjectRequest {
+                            bucket: bucket_name.to_string(),
+                            object: object_key.to_string(),
+                            ..Default::default()
+                        },
+                        &Range(None, None),
+                    )
+                    .await
+                    .map_err(|e| get_error(e.to_string()))?,
+            );
+
+            let mut size = 0;
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(|e| get_error(e.to_string()))?;
+                writer
+                    .write_all(&chunk)
+                    .map_err(|e| get_error(format!("Cannot write object content: {e}")))?;
+                size += chunk.len() as u64;
+            }
+
+            Ok(size)
+        })
+    }
+
+    /// Download url of an object valid for `ttl`, signed with the service account of the client
+    pub fn generate_signed_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        ttl: Duration,
+    ) -> Result<String, ObjectStorageServiceError> {
+        block_on(self.client.signed_url(
+            bucket_name,
+            object_key,
+            None,
+            None,
+            SignedURLOptions {
+                method: SignedURLMethod::GET,
+                expires: ttl,
+                ..Default::default()
+            },
+        ))
+        .map_err(|e| ObjectStorageServiceError::CannotGenerateSignedUrl {
+            object_key: object_key.to_string(),
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
     pub fn list_objects_keys_only(
         &self,
         bucket_name: &str,
@@ -428,6 +619,66 @@ impl ObjectStorageService {
         Ok(objects)
     }
 
+    /// List objects metadata with given predicates, objects content is not fetched.
+    pub fn list_objects_summaries(
+        &self,
+        bucket_name: &str,
+        object_id_prefix: Option<&str>,
+    ) -> Result<Vec<BucketObjectSummary>, ObjectStorageServiceError> {
+        let mut objects: Vec<BucketObjectSummary> = vec![];
+        let mut next_page_token: Option<String> = None;
+
+        loop {
+            let (new_objects, page_token) =
+                self.list_objects_summaries_page(bucket_name, object_id_prefix, next_page_token)?;
+            objects.extend(new_objects);
+            next_page_token = page_token;
+
+            if next_page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Single page of objects metadata along with the token of the next page, none for the last page.
+    pub fn list_objects_summaries_page(
+        &self,
+        bucket_name: &str,
+        object_id_prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<(Vec<BucketObjectSummary>, Option<String>), ObjectStorageServiceError> {
+        match block_on(self.client.list_objects(&ListObjectsRequest {
+            page_token,
+            bucket: bucket_name.to_string(),
+            prefix: object_id_prefix.map(str::to_string),
+            max_results: Some(1000),
+            ..Default::default()
+        })) {
+            Ok(objects_list_response) => Ok((
+                objects_list_response
+                    .items
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|o| BucketObjectSummary {
+                        bucket_name: o.bucket,
+                        key: o.name,
+                        size_in_bytes: o.size.max(0) as u64,
+                        last_modified: o
+                            .updated
+                            .and_then(|date| Utc.timestamp_opt(date.unix_timestamp(), 0).single()),
+                    })
+                    .collect(),
+                objects_list_response.next_page_token,
+            )),
+            Err(e) => Err(ObjectStorageServiceError::CannotListObjects {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
+
     /// List all objects with given predicates.
     /// This function should be used wisely has a GET request is triggered per object.
     pub fn list_objects(
@@ -0,0 +1,120 @@
+//! Limits the requests the kube-rs clients of the engine send to the Kubernetes API.
+//! The kubectl and helm commands the engine runs are not accounted for, each of them being throttled on its own
+//! by the client-go defaults of its binary.
+
+use futures::future::{poll_fn, BoxFuture};
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use once_cell::sync::Lazy;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::Mutex;
+use tower::{Layer, Service};
+
+/// Requests per second sent to the Kubernetes API by all the kube-rs clients of the engine, whatever their task
+pub const KUBE_CLIENT_QPS_ENV_VAR: &str = "KUBE_CLIENT_QPS";
+/// Requests that can be sent at once before being throttled to `KUBE_CLIENT_QPS`
+pub const KUBE_CLIENT_BURST_ENV_VAR: &str = "KUBE_CLIENT_BURST";
+const DEFAULT_KUBE_CLIENT_QPS: u32 = 50;
+const DEFAULT_KUBE_CLIENT_BURST: u32 = 100;
+
+// Shared by every kube-rs client, a limiter per client would let parallel deployments add up and get throttled
+// by the API server, the resulting 429 errors surfacing as readiness failures
+static KUBE_CLIENT_RATE_LIMITER: Lazy<Arc<DefaultDirectRateLimiter>> = Lazy::new(|| {
+    let qps = env_non_zero(KUBE_CLIENT_QPS_ENV_VAR, DEFAULT_KUBE_CLIENT_QPS);
+    let burst = env_non_zero(KUBE_CLIENT_BURST_ENV_VAR, DEFAULT_KUBE_CLIENT_BURST);
+    info!(
+        "Kubernetes API requests of the kube-rs clients are limited to {} per second, with bursts of {}",
+        qps, burst
+    );
+
+    Arc::new(RateLimiter::direct(kube_client_quota(qps, burst)))
+});
+
+fn env_non_zero(env_var: &str, default: u32) -> NonZeroU32 {
+    std::env::var(env_var)
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .and_then(NonZeroU32::new)
+        .unwrap_or_else(|| NonZeroU32::new(default).expect("default must not be 0"))
+}
+
+fn kube_client_quota(qps: NonZeroU32, burst: NonZeroU32) -> Quota {
+    // a burst smaller than the qps would lower the qps
+    Quota::per_second(qps).allow_burst(burst.max(qps))
+}
+
+/// Delays the requests of a kube-rs client so they stay within the quota shared by all the kube-rs clients
+#[derive(Clone)]
+pub struct KubeClientRateLimitLayer {
+    limiter: Arc<DefaultDirectRateLimiter>,
+}
+
+impl Default for KubeClientRateLimitLayer {
+    fn default() -> Self {
+        KubeClientRateLimitLayer {
+            limiter: KUBE_CLIENT_RATE_LIMITER.clone(),
+        }
+    }
+}
+
+impl<S> Layer<S> for KubeClientRateLimitLayer {
+    type Service = KubeClientRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        KubeClientRateLimit {
+            inner: Arc::new(Mutex::new(inner)),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+pub struct KubeClientRateLimit<S> {
+    // shared with the futures of the requests, which only call it once the limiter lets them go
+    inner: Arc<Mutex<S>>,
+    limiter: Arc<DefaultDirectRateLimiter>,
+}
+
+impl<S, Request> Service<Request> for KubeClientRateLimit<S>
+where
+    S: Service<Request> + Send + 'static,
+    S::Future: Send + 'static,
+    Request: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // the inner service readiness is awaited by each request, after the limiter
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+        Box::pin(async move {
+            limiter.until_ready().await;
+            let response = {
+                let mut inner = inner.lock().await;
+                poll_fn(|cx| inner.poll_ready(cx)).await?;
+                inner.call(request)
+            };
+            response.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kube_client_quota() {
+        let qps = NonZeroU32::new(50).unwrap();
+
+        assert_eq!(kube_client_quota(qps, NonZeroU32::new(100).unwrap()).burst_size().get(), 100);
+        assert_eq!(kube_client_quota(qps, NonZeroU32::new(10).unwrap()).burst_size().get(), 50);
+        assert_eq!(env_non_zero("KUBE_CLIENT_QPS_NOT_SET_IN_TESTS", 50), qps);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod gcp;
 pub mod kube_client;
+pub mod kube_client_rate_limiter;
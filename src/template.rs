@@ -5,12 +5,16 @@ use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
+use crate::encryption::write_encrypted_file;
 use crate::errors::CommandError;
 use crate::tera_utils::{Base64EncodeFilter, NginxHeaderValueEscapeFilter, TeraFilter};
 use tera::Error as TeraError;
 use tera::{Context, Tera};
 use walkdir::WalkDir;
 
+// Rendered values holding the passwords of the services, they are only decrypted in memory when given to helm
+const ENCRYPTED_RENDERED_FILE_NAMES: [&str; 1] = ["qovery-values.yaml"];
+
 pub fn generate_and_copy_all_files_into_dir<S, P>(from_dir: S, to_dir: P, context: Context) -> Result<(), CommandError>
 where
     S: AsRef<Path>,
@@ -129,6 +133,13 @@ pub fn write_rendered_templates(rendered_templates: &[RenderedTemplate], into: &
         // remove file if it already exists
         let _ = fs::remove_file(dest.as_str());
 
+        if ENCRYPTED_RENDERED_FILE_NAMES.contains(&rt.file_name.as_str()) {
+            write_encrypted_file(Path::new(&dest), rt.content.as_bytes()).map_err(|e| {
+                CommandError::new("Error while writing encrypted template.".to_string(), Some(e.to_string()), None)
+            })?;
+            continue;
+        }
+
         // create an empty file
         let mut f = File::create(&dest).map_err(|e| {
             CommandError::new(
@@ -45,6 +45,14 @@ impl<'a> Transaction<'a> {
         )))
     }
 
+    pub fn trigger_kubernetes(&mut self) -> Result<(), Box<EngineError>> {
+        Err(Box::new(EngineError::new_cannot_trigger_kubernetes_cluster(
+            self.engine
+                .kubernetes()
+                .get_event_details(Stage::Infrastructure(InfrastructureStep::CreateError)),
+        )))
+    }
+
     pub fn commit(mut self) -> TransactionResult {
         for step in self.steps.clone().into_iter() {
             // execution loop
@@ -7,6 +7,7 @@ use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 
+use crate::services::kube_client_rate_limiter::KubeClientRateLimitLayer;
 use reqwest::header::{HeaderMap, HeaderValue};
 use uuid::Uuid;
 
@@ -84,13 +85,51 @@ pub async fn create_kube_client<P: AsRef<Path>>(
     let kube_config = kube::Config::from_custom_kubeconfig(kubeconfig, &KubeConfigOptions::default())
         .await
         .map_err(to_err)?;
-    let kube_client = kube::Client::try_from(kube_config)?;
+    let kube_client = kube::client::ClientBuilder::try_from(kube_config)?
+        .with_layer(&KubeClientRateLimitLayer::default())
+        .build();
 
     // Try to contact the api to verify we are correctly connected
     kube_client.apiserver_version().await?;
     Ok(kube_client)
 }
 
+/// Kubeconfig authenticating with the token of a service account on the cluster of the current context of
+/// `kubeconfig`, so a tool only gets the access of the service account instead of the one of `kubeconfig`
+pub fn service_account_kubeconfig(kubeconfig: &Kubeconfig, namespace: &str, token: &str) -> Result<String, String> {
+    let cluster_name = kubeconfig
+        .current_context
+        .as_ref()
+        .and_then(|current_context| {
+            kubeconfig
+                .contexts
+                .iter()
+                .find(|context| &context.name == current_context)
+        })
+        .and_then(|context| context.context.as_ref())
+        .map(|context| context.cluster.as_str());
+    let cluster = kubeconfig
+        .clusters
+        .iter()
+        .find(|cluster| Some(cluster.name.as_str()) == cluster_name)
+        .or_else(|| kubeconfig.clusters.first())
+        .and_then(|cluster| cluster.cluster.as_ref())
+        .ok_or_else(|| "No cluster defined in the kubeconfig".to_string())?;
+
+    let kubeconfig = serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "Config",
+        "clusters": [{ "name": "cluster", "cluster": cluster }],
+        "users": [{ "name": "service-account", "user": { "token": token } }],
+        "contexts": [{
+            "name": "service-account",
+            "context": { "cluster": "cluster", "user": "service-account", "namespace": namespace }
+        }],
+        "current-context": "service-account",
+    });
+    serde_yaml::to_string(&kubeconfig).map_err(|err| format!("Cannot serialize the kubeconfig: {err}"))
+}
+
 pub fn base64_replace_comma_to_new_line(multiple_credentials: String) -> Result<String, DecodeError> {
     let decoded_value_byte = general_purpose::STANDARD.decode(multiple_credentials)?;
     let decoded_value = decoded_value_byte.iter().map(|c| *c as char).collect::<String>();
@@ -100,9 +139,10 @@ pub fn base64_replace_comma_to_new_line(multiple_credentials: String) -> Result<
 
 #[cfg(test)]
 mod tests_utilities {
-    use crate::utilities::{base64_replace_comma_to_new_line, compute_image_tag};
+    use crate::utilities::{base64_replace_comma_to_new_line, compute_image_tag, service_account_kubeconfig};
     use base64::engine::general_purpose;
     use base64::Engine;
+    use kube::config::Kubeconfig;
     use std::collections::BTreeMap;
 
     #[test]
@@ -170,4 +210,50 @@ mod tests_utilities {
         let decoded_res_string = decoded_res.iter().map(|c| *c as char).collect::<String>();
         assert_eq!(decoded_res_string, "dennis:ritchie\nlinus:torvalds".to_string());
     }
+
+    #[test]
+    fn test_service_account_kubeconfig() {
+        let admin_kubeconfig = Kubeconfig::from_yaml(
+            r#"
+apiVersion: v1
+kind: Config
+clusters:
+- name: other
+  cluster:
+    server: https://other.example.com
+- name: eks
+  cluster:
+    server: https://eks.example.com
+    certificate-authority-data: Y2E=
+users:
+- name: admin
+  user:
+    exec:
+      apiVersion: client.authentication.k8s.io/v1beta1
+      command: aws
+contexts:
+- name: eks
+  context:
+    cluster: eks
+    user: admin
+current-context: eks
+"#,
+        )
+        .unwrap();
+
+        let kubeconfig = service_account_kubeconfig(&admin_kubeconfig, "z1234-z5678", "sa-token").unwrap();
+        let kubeconfig = Kubeconfig::from_yaml(&kubeconfig).unwrap();
+        let cluster = kubeconfig.clusters[0].cluster.as_ref().unwrap();
+        assert_eq!(cluster.server.as_deref(), Some("https://eks.example.com"));
+        assert_eq!(cluster.certificate_authority_data.as_deref(), Some("Y2E="));
+        let user = kubeconfig.auth_infos[0].auth_info.as_ref().unwrap();
+        assert!(user.exec.is_none());
+        assert!(user.token.is_some());
+        assert_eq!(
+            kubeconfig.contexts[0].context.as_ref().unwrap().namespace.as_deref(),
+            Some("z1234-z5678")
+        );
+
+        assert!(service_account_kubeconfig(&Kubeconfig::default(), "z1234-z5678", "sa-token").is_err());
+    }
 }
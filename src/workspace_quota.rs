@@ -0,0 +1,371 @@
+use crate::fs::WORKSPACE_DIRECTORY_NAME;
+use std::cmp::Reverse;
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+pub const WORKSPACE_QUOTA_MAX_SIZE_IN_MIB_ENV_VAR: &str = "WORKSPACE_QUOTA_MAX_SIZE_IN_MIB";
+pub const WORKSPACE_QUOTA_MAX_INODES_ENV_VAR: &str = "WORKSPACE_QUOTA_MAX_INODES";
+const MIB: u64 = 1024 * 1024;
+const ARCHIVE_EXTENSION: &str = "tgz";
+
+/// Limits of the disk used by the workspaces of all the executions of the engine
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkspaceQuota {
+    pub max_size_in_bytes: u64,
+    pub max_inodes: u64,
+    /// Usage, in percent of the quota, from which old executions are cleaned and a warning is emitted
+    pub warning_threshold_percent: u64,
+}
+
+impl Default for WorkspaceQuota {
+    fn default() -> Self {
+        WorkspaceQuota {
+            max_size_in_bytes: 20 * 1024 * MIB,
+            max_inodes: 2_000_000,
+            warning_threshold_percent: 80,
+        }
+    }
+}
+
+impl WorkspaceQuota {
+    pub fn from_env() -> Self {
+        let from_env_var = |name: &str| std::env::var(name).ok().and_then(|value| value.parse::<u64>().ok());
+        let default = WorkspaceQuota::default();
+
+        WorkspaceQuota {
+            max_size_in_bytes: from_env_var(WORKSPACE_QUOTA_MAX_SIZE_IN_MIB_ENV_VAR)
+                .map(|size_in_mib| size_in_mib * MIB)
+                .unwrap_or(default.max_size_in_bytes),
+            max_inodes: from_env_var(WORKSPACE_QUOTA_MAX_INODES_ENV_VAR).unwrap_or(default.max_inodes),
+            ..default
+        }
+    }
+
+    pub fn is_exceeded_by(&self, usage: &DiskUsage) -> bool {
+        usage.size_in_bytes > self.max_size_in_bytes || usage.inodes > self.max_inodes
+    }
+
+    pub fn is_nearly_exceeded_by(&self, usage: &DiskUsage) -> bool {
+        usage.size_in_bytes.saturating_mul(100) > self.max_size_in_bytes.saturating_mul(self.warning_threshold_percent)
+            || usage.inodes.saturating_mul(100) > self.max_inodes.saturating_mul(self.warning_threshold_percent)
+    }
+}
+
+impl Display for WorkspaceQuota {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} MiB and {} inodes", self.max_size_in_bytes / MIB, self.max_inodes)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    pub size_in_bytes: u64,
+    /// Files, directories and links
+    pub inodes: u64,
+}
+
+impl DiskUsage {
+    fn add(&mut self, other: &DiskUsage) {
+        self.size_in_bytes += other.size_in_bytes;
+        self.inodes += other.inodes;
+    }
+
+    fn sub(&mut self, other: &DiskUsage) {
+        self.size_in_bytes = self.size_in_bytes.saturating_sub(other.size_in_bytes);
+        self.inodes = self.inodes.saturating_sub(other.inodes);
+    }
+}
+
+impl Display for DiskUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} MiB and {} inodes", self.size_in_bytes / MIB, self.inodes)
+    }
+}
+
+#[derive(Clone, Error, Debug, PartialEq, Eq)]
+pub enum WorkspaceQuotaError {
+    #[error("Cannot compute disk usage of `{path}`: {raw_error_message}")]
+    CannotComputeUsage { path: String, raw_error_message: String },
+    #[error("Cannot clean workspace of execution `{execution_id}`: {raw_error_message}")]
+    CannotCleanExecution {
+        execution_id: String,
+        raw_error_message: String,
+    },
+    #[error("Workspace quota is exceeded, {usage} used out of {quota}")]
+    QuotaExceeded { usage: DiskUsage, quota: WorkspaceQuota },
+}
+
+/// Workspace directory or archive of a single execution
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ExecutionWorkspace {
+    execution_id: String,
+    path: PathBuf,
+    usage: DiskUsage,
+    last_used_at: SystemTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkspaceQuotaReport {
+    pub usage: DiskUsage,
+    pub quota: WorkspaceQuota,
+    /// Ids of the executions whose workspace has been removed to free disk space
+    pub cleaned_executions: Vec<String>,
+}
+
+impl WorkspaceQuotaReport {
+    pub fn is_nearly_exceeded(&self) -> bool {
+        self.quota.is_nearly_exceeded_by(&self.usage)
+    }
+}
+
+pub struct WorkspaceQuotaManager {
+    workspaces_dir: PathBuf,
+    quota: WorkspaceQuota,
+}
+
+impl WorkspaceQuotaManager {
+    pub fn new<P: AsRef<Path>>(working_root_dir: P, quota: WorkspaceQuota) -> Self {
+        WorkspaceQuotaManager {
+            workspaces_dir: working_root_dir.as_ref().join(WORKSPACE_DIRECTORY_NAME),
+            quota,
+        }
+    }
+
+    pub fn usage(&self) -> Result<DiskUsage, WorkspaceQuotaError> {
+        let mut usage = DiskUsage::default();
+        for execution in self.execution_workspaces()? {
+            usage.add(&execution.usage);
+        }
+
+        Ok(usage)
+    }
+
+    /// Remove the workspaces of the least recently used executions until the usage goes back under the warning
+    /// threshold, so the given execution starts with enough disk space. Its own workspace is never removed.
+    pub fn make_room_for(&self, execution_id: &str) -> Result<WorkspaceQuotaReport, WorkspaceQuotaError> {
+        let executions = self.execution_workspaces()?;
+        let mut usage = DiskUsage::default();
+        for execution in &executions {
+            usage.add(&execution.usage);
+        }
+
+        let mut cleaned_executions = vec![];
+        for execution in executions_to_clean(executions, usage, &self.quota, execution_id) {
+            let removal = match execution.path.is_dir() {
+                true => fs::remove_dir_all(&execution.path),
+                false => fs::remove_file(&execution.path),
+            };
+            removal.map_err(|e| WorkspaceQuotaError::CannotCleanExecution {
+                execution_id: execution.execution_id.to_string(),
+                raw_error_message: e.to_string(),
+            })?;
+
+            info!(
+                "workspace of execution {} removed to free {}",
+                execution.execution_id, execution.usage
+            );
+            usage.sub(&execution.usage);
+            cleaned_executions.push(execution.execution_id);
+        }
+
+        if self.quota.is_exceeded_by(&usage) {
+            return Err(WorkspaceQuotaError::QuotaExceeded {
+                usage,
+                quota: self.quota.clone(),
+            });
+        }
+
+        Ok(WorkspaceQuotaReport {
+            usage,
+            quota: self.quota.clone(),
+            cleaned_executions,
+        })
+    }
+
+    fn execution_workspaces(&self) -> Result<Vec<ExecutionWorkspace>, WorkspaceQuotaError> {
+        if !self.workspaces_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let usage_error = |path: &Path, raw_error_message: String| WorkspaceQuotaError::CannotComputeUsage {
+            path: path.to_string_lossy().to_string(),
+            raw_error_message,
+        };
+
+        let mut executions = vec![];
+        for entry in fs::read_dir(&self.workspaces_dir).map_err(|e| usage_error(&self.workspaces_dir, e.to_string()))? {
+            let path = entry
+                .map_err(|e| usage_error(&self.workspaces_dir, e.to_string()))?
+                .path();
+            // workspace archives are named after their execution, i.e: `<execution_id>.tgz`
+            let execution_id = match path.extension() == Some(OsStr::new(ARCHIVE_EXTENSION)) {
+                true => path.file_stem(),
+                false => path.file_name(),
+            }
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+            let (usage, last_used_at) = disk_usage(&path).map_err(|e| usage_error(&path, e))?;
+            executions.push(ExecutionWorkspace {
+                execution_id,
+                path,
+                usage,
+                last_used_at,
+            });
+        }
+
+        Ok(executions)
+    }
+}
+
+/// Least recently used executions to clean to go back under the warning threshold of the quota
+fn executions_to_clean(
+    mut executions: Vec<ExecutionWorkspace>,
+    mut usage: DiskUsage,
+    quota: &WorkspaceQuota,
+    current_execution_id: &str,
+) -> Vec<ExecutionWorkspace> {
+    executions.retain(|execution| execution.execution_id != current_execution_id);
+    executions.sort_by_key(|execution| Reverse(execution.last_used_at));
+
+    let mut to_clean = vec![];
+    while quota.is_nearly_exceeded_by(&usage) {
+        let Some(execution) = executions.pop() else {
+            break;
+        };
+        usage.sub(&execution.usage);
+        to_clean.push(execution);
+    }
+
+    to_clean
+}
+
+/// Usage of a file or a directory and its content, with the last modification time of its most recent entry
+fn disk_usage(path: &Path) -> Result<(DiskUsage, SystemTime), String> {
+    let mut usage = DiskUsage::default();
+    let mut last_used_at = SystemTime::UNIX_EPOCH;
+
+    for entry in WalkDir::new(path) {
+        let metadata = entry.and_then(|entry| entry.metadata()).map_err(|e| e.to_string())?;
+        usage.inodes += 1;
+        if metadata.is_file() {
+            usage.size_in_bytes += metadata.len();
+        }
+        if let Ok(modified) = metadata.modified() {
+            last_used_at = last_used_at.max(modified);
+        }
+    }
+
+    Ok((usage, last_used_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn quota(max_size_in_bytes: u64) -> WorkspaceQuota {
+        WorkspaceQuota {
+            max_size_in_bytes,
+            max_inodes: 1_000,
+            warning_threshold_percent: 80,
+        }
+    }
+
+    fn execution(execution_id: &str, size_in_bytes: u64, last_used_secs_ago: u64) -> ExecutionWorkspace {
+        ExecutionWorkspace {
+            execution_id: execution_id.to_string(),
+            path: PathBuf::from(execution_id),
+            usage: DiskUsage {
+                size_in_bytes,
+                inodes: 1,
+            },
+            last_used_at: SystemTime::now() - Duration::from_secs(last_used_secs_ago),
+        }
+    }
+
+    #[test]
+    fn test_quota_thresholds() {
+        let quota = quota(100);
+
+        let usage = |size_in_bytes: u64, inodes: u64| DiskUsage { size_in_bytes, inodes };
+        assert!(!quota.is_nearly_exceeded_by(&usage(80, 800)));
+        assert!(quota.is_nearly_exceeded_by(&usage(81, 1)));
+        assert!(quota.is_nearly_exceeded_by(&usage(1, 801)));
+        assert!(!quota.is_exceeded_by(&usage(100, 1_000)));
+        assert!(quota.is_exceeded_by(&usage(101, 1)));
+        assert!(quota.is_exceeded_by(&usage(1, 1_001)));
+    }
+
+    #[test]
+    fn test_executions_to_clean_by_least_recent_use() {
+        let executions = vec![
+            execution("recent", 30, 10),
+            execution("oldest", 30, 3_600),
+            execution("current", 30, 7_200),
+            execution("old", 30, 600),
+        ];
+        let usage = DiskUsage {
+            size_in_bytes: 120,
+            inodes: 4,
+        };
+
+        let cleaned = executions_to_clean(executions.clone(), usage, &quota(120), "current")
+            .into_iter()
+            .map(|execution| execution.execution_id)
+            .collect::<Vec<_>>();
+        assert_eq!(cleaned, vec!["oldest".to_string()]);
+
+        // the current execution is kept even when the quota cannot be met
+        let cleaned = executions_to_clean(executions, usage, &quota(10), "current")
+            .into_iter()
+            .map(|execution| execution.execution_id)
+            .collect::<Vec<_>>();
+        assert_eq!(cleaned, vec!["oldest".to_string(), "old".to_string(), "recent".to_string()]);
+    }
+
+    #[test]
+    fn test_make_room_for() {
+        let working_root_dir = tempfile::tempdir().expect("Cannot create temp dir");
+        let workspaces_dir = working_root_dir.path().join(WORKSPACE_DIRECTORY_NAME);
+        for execution_id in ["previous", "current"] {
+            fs::create_dir_all(workspaces_dir.join(execution_id)).expect("Cannot create workspace");
+            fs::write(workspaces_dir.join(execution_id).join("main.tf"), [0u8; 100]).expect("Cannot write file");
+        }
+        fs::write(workspaces_dir.join("archived.tgz"), [0u8; 100]).expect("Cannot write archive");
+
+        // under the warning threshold, nothing is cleaned
+        let manager = WorkspaceQuotaManager::new(working_root_dir.path(), quota(1_000));
+        assert_eq!(
+            manager.usage(),
+            Ok(DiskUsage {
+                size_in_bytes: 300,
+                inodes: 5,
+            })
+        );
+        let report = manager.make_room_for("current").expect("Cannot make room");
+        assert!(report.cleaned_executions.is_empty());
+        assert!(!report.is_nearly_exceeded());
+
+        // over it, the other executions and archives are cleaned
+        let manager = WorkspaceQuotaManager::new(working_root_dir.path(), quota(150));
+        let mut report = manager.make_room_for("current").expect("Cannot make room");
+        report.cleaned_executions.sort();
+        assert_eq!(report.cleaned_executions, vec!["archived".to_string(), "previous".to_string()]);
+        assert!(workspaces_dir.join("current").join("main.tf").exists());
+        assert!(!workspaces_dir.join("previous").exists());
+
+        // the current execution alone exceeds the quota
+        let manager = WorkspaceQuotaManager::new(working_root_dir.path(), quota(50));
+        assert!(matches!(
+            manager.make_room_for("current"),
+            Err(WorkspaceQuotaError::QuotaExceeded { .. })
+        ));
+    }
+}
@@ -288,6 +288,11 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
             publicly_accessible: false,
             mode: CONTAINER,
             database_instance_type: None,
+            advanced_settings: Default::default(),
+            kms_key_arn: None,
+            init_scripts: vec![],
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
         }];
         environment.applications = environment
             .applications
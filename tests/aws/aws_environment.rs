@@ -595,6 +595,7 @@ fn deploy_a_working_environment_with_custom_domain_and_disable_check_on_custom_d
                 domain: format!("fake-custom-domain-{idx}.qovery.io"),
                 target_domain: format!("validation-domain-{idx}"),
                 generate_certificate: true,
+                certificate: None,
             };
 
             router.custom_domains = vec![cd];
@@ -675,6 +676,7 @@ fn deploy_a_working_environment_with_storage_on_aws_eks() {
                     long_id: id,
                     name: "photos".to_string(),
                     storage_type: StorageType::Ssd,
+                    storage_class: None,
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
@@ -844,6 +846,7 @@ fn redeploy_same_app_with_ebs() {
                     long_id: id,
                     name: "photos".to_string(),
                     storage_type: StorageType::Ssd,
+                    storage_class: None,
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
@@ -1534,6 +1537,8 @@ fn deploy_container_with_no_router_and_affinitiy_on_aws_eks() {
                 failure_threshold: 5,
             }),
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let ret = environment.deploy_environment(&environment, &infra_ctx);
@@ -1741,6 +1746,8 @@ fn deploy_container_with_no_router_on_aws_eks() {
                 failure_threshold: 5,
             }),
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -1855,6 +1862,7 @@ fn deploy_container_with_storages_on_aws_eks() {
                     long_id: storage_id_1,
                     name: "photos1".to_string(),
                     storage_type: StorageType::Ssd,
+                    storage_class: None,
                     size_in_gib: 10,
                     mount_point: "/mnt/photos1".to_string(),
                     snapshot_retention_in_days: 0,
@@ -1864,6 +1872,7 @@ fn deploy_container_with_storages_on_aws_eks() {
                     long_id: storage_id_2,
                     name: "photos2".to_string(),
                     storage_type: StorageType::Ssd,
+                    storage_class: None,
                     size_in_gib: 10,
                     mount_point: "/mnt/photos2".to_string(),
                     snapshot_retention_in_days: 0,
@@ -1872,6 +1881,8 @@ fn deploy_container_with_storages_on_aws_eks() {
             environment_vars_with_infos: BTreeMap::default(),
             mounted_files: vec![],
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2000,6 +2011,8 @@ fn deploy_container_on_aws_eks_with_mounted_files_as_volume() {
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
             mounted_files: vec![mounted_file.clone()],
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2153,6 +2166,8 @@ fn deploy_container_with_router_on_aws_eks() {
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret:false} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         environment.routers = vec![Router {
@@ -2166,7 +2181,15 @@ fn deploy_container_with_router_on_aws_eks() {
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: environment.containers[0].long_id,
+                backend_protocol: Default::default(),
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: Default::default(),
             }],
+            l4_routes: vec![],
+            nginx_annotations: Default::default(),
+            client_ca_bundle_pem: None,
+            custom_metadata: Default::default(),
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2263,6 +2286,7 @@ fn deploy_job_on_aws_eks() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2358,6 +2382,7 @@ fn deploy_cronjob_on_aws_eks() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2455,6 +2480,7 @@ fn deploy_cronjob_force_trigger_on_aws_eks() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2577,6 +2603,7 @@ fn build_and_deploy_job_on_aws_eks() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2695,6 +2722,8 @@ fn test_restart_deployment() {
             mounted_files: vec![],
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2773,6 +2802,7 @@ fn test_restart_statefulset() {
                 mount_point: "/storage".to_string(),
                 size_in_gib: 10,
                 storage_type: StorageType::FastSsd,
+                storage_class: None,
                 snapshot_retention_in_days: 1,
             }],
             mounted_files: vec![],
@@ -2826,6 +2856,8 @@ fn test_restart_statefulset() {
             }),
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2936,6 +2968,7 @@ fn build_and_deploy_job_on_aws_eks_with_mounted_files_as_volume() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -3029,6 +3062,7 @@ fn deploy_a_working_environment_with_multiple_resized_storage_on_aws_eks() {
                         long_id: id_1,
                         name: "photos_1".to_string(),
                         storage_type: StorageType::Ssd,
+                        storage_class: None,
                         size_in_gib: initial_storage_size,
                         mount_point: "/mnt/photos_1".to_string(),
                         snapshot_retention_in_days: 0,
@@ -3038,6 +3072,7 @@ fn deploy_a_working_environment_with_multiple_resized_storage_on_aws_eks() {
                         long_id: id_2,
                         name: "photos_2".to_string(),
                         storage_type: StorageType::Ssd,
+                        storage_class: None,
                         size_in_gib: initial_storage_size,
                         mount_point: "/mnt/photos_2".to_string(),
                         snapshot_retention_in_days: 0,
@@ -3246,6 +3281,8 @@ fn deploy_container_with_udp_tcp_public_ports() {
                 failure_threshold: 5,
             }),
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -3678,7 +3715,15 @@ fn deploy_helm_chart_with_router() {
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: environment.helms[0].long_id,
+                backend_protocol: Default::default(),
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: Default::default(),
             }],
+            l4_routes: vec![],
+            nginx_annotations: Default::default(),
+            client_ca_bundle_pem: None,
+            custom_metadata: Default::default(),
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -230,6 +230,8 @@ fn deploy_container_on_aws_ec2_with_mounted_files_as_volume() {
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo { value:  general_purpose::STANDARD.encode("my_value"), is_secret: false} },
             mounted_files: vec![mounted_file.clone()],
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -370,6 +372,7 @@ fn build_and_deploy_job_on_aws_ec2_with_mounted_files_as_volume() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -3,6 +3,7 @@ use crate::helpers::utilities::{engine_run_test, generate_id, init, FuncTestsSec
 use function_name::named;
 use qovery_engine::cloud_provider::aws::regions::AwsRegion;
 use qovery_engine::models::ToCloudProviderFormat;
+use qovery_engine::object_storage::bucket_encryption::BucketEncryption;
 use qovery_engine::object_storage::s3::S3;
 use qovery_engine::object_storage::{BucketDeleteStrategy, ObjectStorage};
 use retry::delay::Fixed;
@@ -41,6 +42,7 @@ fn test_delete_hard_strategy_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                &BucketEncryption::ProviderManaged,
             )
             .unwrap_or_else(|_| {
                 panic!("error while creating S3 bucket in `{}`", aws_region.to_cloud_provider_format())
@@ -98,6 +100,7 @@ fn test_delete_empty_strategy_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                &BucketEncryption::ProviderManaged,
             )
             .unwrap_or_else(|_| {
                 panic!("error while creating S3 bucket in `{}`", aws_region.to_cloud_provider_format())
@@ -149,6 +152,7 @@ fn test_create_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
             false,
+            &BucketEncryption::ProviderManaged,
         );
 
         // validate:
@@ -172,6 +176,65 @@ fn test_create_bucket() {
     })
 }
 
+#[cfg(feature = "test-aws-minimal")]
+#[named]
+#[test]
+fn test_verify_bucket_encryption() {
+    let test_name = function_name!();
+    engine_run_test(|| {
+        init();
+        let span = span!(Level::INFO, "test", name = test_name);
+        let _enter = span.enter();
+
+        // setup:
+        let secrets = FuncTestsSecrets::new();
+        let id = generate_id();
+        let name = format!("test-{id}");
+        let aws_access_key = secrets.AWS_ACCESS_KEY_ID.expect("AWS_ACCESS_KEY_ID is not set");
+        let aws_secret_key = secrets.AWS_SECRET_ACCESS_KEY.expect("AWS_SECRET_ACCESS_KEY is not set");
+        let aws_region_raw = secrets.AWS_DEFAULT_REGION.expect("AWS_DEFAULT_REGION is not set");
+        let aws_region = AwsRegion::from_str(aws_region_raw.as_str())
+            .unwrap_or_else(|_| panic!("AWS region `{aws_region_raw}` seems not to be valid"));
+
+        let aws_os = S3::new(id.to_string(), name, aws_access_key, aws_secret_key, aws_region);
+
+        let bucket_name = format!("qovery-test-bucket-{}", generate_id());
+
+        aws_os
+            .create_bucket(
+                bucket_name.as_str(),
+                Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
+                false,
+                &BucketEncryption::ProviderManaged,
+            )
+            .expect("Cannot create bucket");
+
+        // compute & validate:
+        assert_eq!(
+            aws_os.get_bucket_encryption(bucket_name.as_str()),
+            Ok(Some(BucketEncryption::ProviderManaged))
+        );
+        assert!(aws_os
+            .verify_bucket_encryption(bucket_name.as_str(), &BucketEncryption::ProviderManaged)
+            .is_ok());
+        assert!(aws_os
+            .verify_bucket_encryption(
+                bucket_name.as_str(),
+                &BucketEncryption::Kms {
+                    key_id: "alias/qovery-not-used".to_string(),
+                },
+            )
+            .is_err());
+
+        // clean-up:
+        assert!(aws_os
+            .delete_bucket(bucket_name.as_str(), BucketDeleteStrategy::HardDelete)
+            .is_ok());
+
+        test_name.to_string()
+    })
+}
+
 #[cfg(feature = "test-aws-minimal")]
 #[named]
 #[test]
@@ -201,6 +264,7 @@ fn test_get_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                &BucketEncryption::ProviderManaged,
             )
             .expect("Cannot create bucket");
 
@@ -248,6 +312,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(create_result.is_ok());
         assert!(aws_os.bucket_exists(bucket_name.as_str()));
@@ -268,6 +333,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(recreate_result.is_ok());
         // retry to check if bucket exists, there is a lag / cache after bucket deletion
@@ -318,6 +384,7 @@ fn test_put_file() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                &BucketEncryption::ProviderManaged,
             )
             .expect("error while creating object-storage bucket");
 
@@ -369,6 +436,7 @@ fn test_get_file() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                &BucketEncryption::ProviderManaged,
             )
             .expect("error while creating object-storage bucket");
 
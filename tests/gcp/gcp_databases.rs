@@ -300,6 +300,11 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
             activate_high_availability: false,
             activate_backups: false,
             publicly_accessible: false,
+            advanced_settings: Default::default(),
+            kms_key_arn: None,
+            init_scripts: vec![],
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
         }];
         environment.applications = environment
             .applications
@@ -289,6 +289,7 @@ fn gcp_gke_deploy_a_working_environment_with_domain() {
                 domain: format!("fake-custom-domain-{idx}.qovery.io"),
                 target_domain: format!("validation-domain-{idx}"),
                 generate_certificate: true,
+                certificate: None,
             };
 
             router.custom_domains = vec![cd];
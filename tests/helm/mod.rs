@@ -27,7 +27,11 @@ use qovery_engine::io_models::application::{ApplicationAdvancedSettings, Port, P
 use qovery_engine::io_models::container::{ContainerAdvancedSettings, Registry};
 use qovery_engine::io_models::database::{DatabaseMode, DatabaseOptions};
 use qovery_engine::io_models::job::{JobAdvancedSettings, JobSchedule};
-use qovery_engine::io_models::{PodAntiAffinity, QoveryIdentifier, UpdateStrategy};
+use qovery_engine::io_models::{
+    ConfigReloadStrategy, CustomMetadata, DeploymentStrategy, IpFamilyPolicy, PodAntiAffinity, PodAntiAffinityTopology,
+    QoveryIdentifier, StatefulSetUpdateStrategy, StickySessionHashPolicy, TopologySpreadKey,
+    TopologySpreadWhenUnsatisfiable, UpdateStrategy,
+};
 use qovery_engine::models::application::Application;
 use qovery_engine::models::aws::{AwsAppExtraSettings, AwsRouterExtraSettings, AwsStorageType};
 use qovery_engine::models::container::Container;
@@ -179,8 +183,9 @@ fn test_storage() -> Storage<AwsStorageType> {
         long_id: Uuid::new_v4(),
         name: "my_storage_name".to_string(),
         storage_type: AwsStorageType::GP2,
+        storage_class: None,
         size_in_gib: 1,
-        mount_point: "my_mount_point".to_string(),
+        mount_point: "/my_mount_point".to_string(),
         snapshot_retention_in_days: 2,
     }
 }
@@ -212,6 +217,7 @@ fn test_custom_domain() -> CustomDomain {
         domain: "my_custom_domain".to_string(),
         target_domain: "my_target_domain".to_string(),
         generate_certificate: true,
+        certificate: None,
     }
 }
 
@@ -251,6 +257,8 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
                 dockerfile_path: Some(PathBuf::from("my_dockerfile_path")),
                 root_path: PathBuf::from("my_root_path"),
                 buildpack_language: Some("my_language".to_string()),
+                recursive_submodules: false,
+                lfs: true,
             },
             image: Image {
                 service_id: "my_application_id".to_string(),
@@ -265,12 +273,18 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
                 repository_name: "my_image_repository_name".to_string(),
             },
             environment_variables: BTreeMap::new(),
+            secrets: BTreeMap::new(),
             disable_cache: false,
             timeout: Duration::from_secs(42),
             architectures: test_kube.cpu_architectures(),
             max_cpu_in_milli: 2000,
             max_ram_in_gib: 4,
             registries: vec![],
+            base_image_mirror: None,
+            image_signing_key: None,
+            cache: None,
+            bust_cache: false,
+            sbom: None,
         },
         vec![],
         None,
@@ -313,6 +327,9 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
             network_ingress_proxy_body_size_mb: 3,
             network_ingress_cors_enable: true,
             network_ingress_sticky_session_enable: false,
+            network_ingress_sticky_session_cookie_name: "INGRESSCOOKIE_QOVERY".to_string(),
+            network_ingress_sticky_session_cookie_max_age_seconds: 85400,
+            network_ingress_sticky_session_hash_policy: StickySessionHashPolicy::Persistent,
             network_ingress_cors_allow_origin: "my_network_ingress_cors_allow_origin".to_string(),
             network_ingress_cors_allow_methods: "my_network_ingress_cors_allow_methods".to_string(),
             network_ingress_cors_allow_headers: "my_network_ingress_cors_allow_headers".to_string(),
@@ -329,12 +346,50 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
             network_ingress_whitelist_source_range: "my_network_ingress_whitelist_source_range".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_limit_rps: 0,
+            network_ingress_limit_burst: 0,
+            network_ingress_limit_connections: 0,
+            network_ingress_waf_mode: None,
+            network_ingress_waf_custom_rules: vec![],
+            network_ingress_mtls_enabled: false,
+            network_ingress_mtls_verify_depth: 1,
+            network_ingress_mtls_pass_certificate_to_upstream: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
+            network_ip_family_policy: IpFamilyPolicy::SingleStack,
+            network_ingress_probe_enabled: true,
+            network_ingress_probe_expected_status_code: None,
             hpa_cpu_average_utilization_percent: 31,
+            hpa_memory_average_utilization_percent: None,
+            hpa_custom_metrics: vec![],
+            hpa_keda_triggers: vec![],
+            deployment_external_secrets: vec![],
+            registry_image_retention_keep_last_tags: None,
+            registry_image_retention_expire_after_days: None,
             deployment_affinity_node_required: BTreeMap::new(),
             deployment_antiaffinity_pod: PodAntiAffinity::Preferred,
+            deployment_antiaffinity_pod_topology: PodAntiAffinityTopology::Hostname,
+            deployment_config_reload_strategy: ConfigReloadStrategy::RollingRestart,
+            deployment_config_reload_mount_path: "/qovery-config".to_string(),
+            deployment_config_reload_webhook_url: "".to_string(),
+            deployment_topology_spread_key: TopologySpreadKey::Auto,
+            deployment_topology_spread_max_skew: 1,
+            deployment_topology_spread_when_unsatisfiable: TopologySpreadWhenUnsatisfiable::ScheduleAnyway,
+            deployment_tolerations: vec![],
+            deployment_env_vars_fast_path_enabled: true,
+            deployment_canary_weight_percent: 10,
+            deployment_canary_analysis_duration_seconds: 300,
+            deployment_canary_max_error_rate_percent: 5,
+            deployment_strategy: DeploymentStrategy::RollingUpdate,
+            statefulset_update_strategy_type: StatefulSetUpdateStrategy::RollingUpdate,
+            statefulset_update_strategy_rolling_update_partition: 0,
+            pdb_min_available_percent: 0,
+            pdb_max_unavailable_percent: 10,
+            resources_limit_max_ratio: 10,
         },
+        None,
+        None,
+        CustomMetadata::default(),
         AwsAppExtraSettings {},
         |transmitter| test_kube.context().get_event_details(transmitter),
     )
@@ -401,9 +456,25 @@ pub fn test_container(test_kube: &dyn Kubernetes) -> Container<AWSType> {
             deployment_update_strategy_rolling_update_max_surge_percent: 25,
             deployment_affinity_node_required: BTreeMap::new(),
             deployment_antiaffinity_pod: PodAntiAffinity::Preferred,
+            deployment_antiaffinity_pod_topology: PodAntiAffinityTopology::Hostname,
+            deployment_config_reload_strategy: ConfigReloadStrategy::RollingRestart,
+            deployment_config_reload_mount_path: "/qovery-config".to_string(),
+            deployment_config_reload_webhook_url: "".to_string(),
+            deployment_topology_spread_key: TopologySpreadKey::Auto,
+            deployment_topology_spread_max_skew: 1,
+            deployment_topology_spread_when_unsatisfiable: TopologySpreadWhenUnsatisfiable::ScheduleAnyway,
+            deployment_tolerations: vec![],
+            deployment_env_vars_fast_path_enabled: true,
+            statefulset_update_strategy_type: StatefulSetUpdateStrategy::RollingUpdate,
+            statefulset_update_strategy_rolling_update_partition: 0,
+            pdb_min_available_percent: 0,
+            pdb_max_unavailable_percent: 10,
             network_ingress_proxy_body_size_mb: 11,
             network_ingress_cors_enable: true,
             network_ingress_sticky_session_enable: false,
+            network_ingress_sticky_session_cookie_name: "INGRESSCOOKIE_QOVERY".to_string(),
+            network_ingress_sticky_session_cookie_max_age_seconds: 85400,
+            network_ingress_sticky_session_hash_policy: StickySessionHashPolicy::Persistent,
             network_ingress_cors_allow_origin: "my_network_ingress_cors_allow_origin".to_string(),
             network_ingress_cors_allow_methods: "my_network_ingress_cors_allow_methods".to_string(),
             network_ingress_cors_allow_headers: "my_network_ingress_cors_allow_headers".to_string(),
@@ -420,9 +491,24 @@ pub fn test_container(test_kube: &dyn Kubernetes) -> Container<AWSType> {
             network_ingress_whitelist_source_range: "my_network_ingress_whitelist_source_range".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_limit_rps: 0,
+            network_ingress_limit_burst: 0,
+            network_ingress_limit_connections: 0,
+            network_ingress_waf_mode: None,
+            network_ingress_waf_custom_rules: vec![],
+            network_ingress_mtls_enabled: false,
+            network_ingress_mtls_verify_depth: 1,
+            network_ingress_mtls_pass_certificate_to_upstream: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
+            network_ip_family_policy: IpFamilyPolicy::SingleStack,
+            network_ingress_probe_enabled: true,
+            network_ingress_probe_expected_status_code: None,
             hpa_cpu_average_utilization_percent: 41,
+            hpa_memory_average_utilization_percent: None,
+            hpa_custom_metrics: vec![],
+            hpa_keda_triggers: vec![],
+            deployment_external_secrets: vec![],
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
@@ -462,6 +548,11 @@ pub fn test_managed_database(test_kube: &dyn Kubernetes) -> Database<AWSType, Ma
             activate_high_availability: true,
             activate_backups: true,
             publicly_accessible: true,
+            advanced_settings: Default::default(),
+            kms_key_arn: None,
+            init_scripts: vec![],
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
         },
         |transmitter| test_kube.context().get_event_details(transmitter),
     )
@@ -497,6 +588,11 @@ pub fn test_container_database(test_kube: &dyn Kubernetes) -> Database<AWSType,
             activate_high_availability: true,
             activate_backups: true,
             publicly_accessible: true,
+            advanced_settings: Default::default(),
+            kms_key_arn: None,
+            init_scripts: vec![],
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
         },
         |transmitter| test_kube.context().get_event_details(transmitter),
     )
@@ -520,7 +616,10 @@ pub fn test_router(test_kube: &dyn Kubernetes, app_id: Uuid) -> Router<AWSType>
             whitelist_source_range: None,
             denylist_source_range: None,
             basic_auth: None,
+            probe_enabled: true,
+            probe_expected_status_codes: BTreeMap::new(),
         },
+        CustomMetadata::default(),
         |transmitter| test_kube.context().get_event_details(transmitter),
     )
     .unwrap()
@@ -574,7 +673,11 @@ fn test_job(test_kube: &dyn Kubernetes) -> Job<AWSType> {
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            job_artifacts_enabled: false,
+            job_artifacts_path: "/qovery-artifacts".to_string(),
+            job_artifacts_retention_in_days: 7,
         },
+        None,
         Some(Probe {
             r#type: ProbeType::Http {
                 path: "/".to_string(),
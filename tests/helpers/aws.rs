@@ -3,6 +3,7 @@ extern crate serde_derive;
 
 use crate::helpers::aws_ec2::container_registry_ecr_ec2;
 use qovery_engine::cloud_provider::aws::database_instance_type::AwsDatabaseInstanceType;
+use qovery_engine::cloud_provider::aws::kubernetes::node_os::{KubeletParameters, NodeOsImage};
 use qovery_engine::cloud_provider::aws::kubernetes::Options;
 use qovery_engine::cloud_provider::aws::regions::AwsRegion;
 use qovery_engine::cloud_provider::aws::AWS;
@@ -306,6 +307,8 @@ impl Cluster<AWS, Options> for AWS {
             aws_addon_kube_proxy_version_override: None,
             aws_addon_coredns_version_override: None,
             ec2_exposed_port: Some(9876),
+            node_os_image: NodeOsImage::AmazonLinux2,
+            kubelet_parameters: KubeletParameters::default(),
         }
     }
 }
@@ -31,7 +31,7 @@ use qovery_engine::cloud_provider::service::Service;
 use qovery_engine::deployment_report::logger::EnvLogger;
 use qovery_engine::engine_task::environment_task::EnvironmentTask;
 use qovery_engine::events::EnvironmentStep;
-use qovery_engine::io_models::environment::EnvironmentRequest;
+use qovery_engine::io_models::environment::{EnvironmentRequest, EnvironmentServiceAccount};
 use qovery_engine::io_models::probe::{Probe, ProbeType};
 use qovery_engine::io_models::variable_utils::VariableInfo;
 use qovery_engine::io_models::{Action, QoveryIdentifier};
@@ -85,6 +85,7 @@ impl Infrastructure for EnvironmentRequest {
             &deployment_option,
             infra_ctx,
             1,
+            None,
             |_| {},
             |srv: &dyn Service| EnvLogger::new(srv, EnvironmentStep::Build, logger.clone()),
             &|| false,
@@ -284,6 +285,11 @@ pub fn environment_3_apps_3_databases(
                 min_instances: 1,
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
+                resources: None,
+                build_cache_bust: false,
+                build_secrets: vec![],
+                git_recursive_submodules: false,
+                git_lfs: true,
                 advanced_settings: Default::default(),
                 readiness_probe: Some(Probe {
                     r#type: ProbeType::Http {
@@ -307,6 +313,15 @@ pub fn environment_3_apps_3_databases(
                     failure_threshold: 5,
                 }),
                 container_registries: Vec::new(),
+                migrations: None,
+                init_containers: vec![],
+                stateful: false,
+                readiness_gates: None,
+                custom_metadata: Default::default(),
+                startup_probe: None,
+                lifecycle_hooks: Default::default(),
+                smoke_test: None,
+                shared_volume_mounts: vec![],
             },
             Application {
                 long_id: Uuid::new_v4(),
@@ -346,6 +361,11 @@ pub fn environment_3_apps_3_databases(
                 min_instances: 1,
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
+                resources: None,
+                build_cache_bust: false,
+                build_secrets: vec![],
+                git_recursive_submodules: false,
+                git_lfs: true,
                 advanced_settings: Default::default(),
                 readiness_probe: Some(Probe {
                     r#type: ProbeType::Http {
@@ -370,6 +390,15 @@ pub fn environment_3_apps_3_databases(
                 }),
                 public_domain: format!("{}.example.com", app_id),
                 container_registries: Vec::new(),
+                migrations: None,
+                init_containers: vec![],
+                stateful: false,
+                readiness_gates: None,
+                custom_metadata: Default::default(),
+                startup_probe: None,
+                lifecycle_hooks: Default::default(),
+                smoke_test: None,
+                shared_volume_mounts: vec![],
             },
             Application {
                 long_id: Uuid::new_v4(),
@@ -412,6 +441,11 @@ pub fn environment_3_apps_3_databases(
                 min_instances: 1,
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
+                resources: None,
+                build_cache_bust: false,
+                build_secrets: vec![],
+                git_recursive_submodules: false,
+                git_lfs: true,
                 advanced_settings: Default::default(),
                 readiness_probe: Some(Probe {
                     r#type: ProbeType::Http {
@@ -435,6 +469,15 @@ pub fn environment_3_apps_3_databases(
                     failure_threshold: 5,
                 }),
                 container_registries: Vec::new(),
+                migrations: None,
+                init_containers: vec![],
+                stateful: false,
+                readiness_gates: None,
+                custom_metadata: Default::default(),
+                startup_probe: None,
+                lifecycle_hooks: Default::default(),
+                smoke_test: None,
+                shared_volume_mounts: vec![],
             },
         ],
         containers: vec![],
@@ -464,6 +507,11 @@ pub fn environment_3_apps_3_databases(
                 activate_backups: false,
                 publicly_accessible: false,
                 mode: CONTAINER,
+                advanced_settings: Default::default(),
+                kms_key_arn: None,
+                init_scripts: vec![],
+                parameters: Default::default(),
+                custom_metadata: Default::default(),
             },
             Database {
                 kind: DatabaseKind::Postgresql,
@@ -488,6 +536,11 @@ pub fn environment_3_apps_3_databases(
                 activate_backups: false,
                 publicly_accessible: false,
                 mode: CONTAINER,
+                advanced_settings: Default::default(),
+                kms_key_arn: None,
+                init_scripts: vec![],
+                parameters: Default::default(),
+                custom_metadata: Default::default(),
             },
             Database {
                 kind: DatabaseKind::Mongodb,
@@ -512,9 +565,21 @@ pub fn environment_3_apps_3_databases(
                 activate_backups: false,
                 publicly_accessible: false,
                 mode: CONTAINER,
+                advanced_settings: Default::default(),
+                kms_key_arn: None,
+                init_scripts: vec![],
+                parameters: Default::default(),
+                custom_metadata: Default::default(),
             },
         ],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     }
 }
 
@@ -556,17 +621,38 @@ pub fn database_test_environment(context: &Context) -> EnvironmentRequest {
             min_instances: 1,
             max_instances: 1,
             cpu_burst: "100m".to_string(),
+            resources: None,
+            build_cache_bust: false,
+            build_secrets: vec![],
+            git_recursive_submodules: false,
+            git_lfs: true,
             advanced_settings: Default::default(),
             readiness_probe: None,
             liveness_probe: None,
             public_domain: format!("{}.example.com", Uuid::new_v4()),
             container_registries: Vec::new(),
+            migrations: None,
+            init_containers: vec![],
+            stateful: false,
+            readiness_gates: None,
+            custom_metadata: Default::default(),
+            startup_probe: None,
+            lifecycle_hooks: Default::default(),
+            smoke_test: None,
+            shared_volume_mounts: vec![],
         }],
         containers: vec![],
         jobs: vec![],
         routers: vec![],
         databases: vec![],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     }
 }
 
@@ -609,16 +695,37 @@ pub fn database_test_environment_on_upgrade(context: &Context) -> EnvironmentReq
             min_instances: 1,
             max_instances: 1,
             cpu_burst: "100m".to_string(),
+            resources: None,
+            build_cache_bust: false,
+            build_secrets: vec![],
+            git_recursive_submodules: false,
+            git_lfs: true,
             advanced_settings: Default::default(),
             readiness_probe: None,
             liveness_probe: None,
             container_registries: Vec::new(),
+            migrations: None,
+            init_containers: vec![],
+            stateful: false,
+            readiness_gates: None,
+            custom_metadata: Default::default(),
+            startup_probe: None,
+            lifecycle_hooks: Default::default(),
+            smoke_test: None,
+            shared_volume_mounts: vec![],
         }],
         containers: vec![],
         jobs: vec![],
         routers: vec![],
         databases: vec![],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     }
 }
 
@@ -727,6 +834,11 @@ pub fn test_db(
         activate_backups: false,
         publicly_accessible: is_public,
         mode: database_mode.clone(),
+        advanced_settings: Default::default(),
+        kms_key_arn: None,
+        init_scripts: vec![],
+        parameters: Default::default(),
+        custom_metadata: Default::default(),
     };
 
     environment.databases = vec![db.clone()];
@@ -1078,6 +1190,11 @@ pub fn test_pause_managed_db(
         activate_backups: false,
         publicly_accessible: is_public,
         mode: database_mode.clone(),
+        advanced_settings: Default::default(),
+        kms_key_arn: None,
+        init_scripts: vec![],
+        parameters: Default::default(),
+        custom_metadata: Default::default(),
     };
 
     environment.databases = vec![db];
@@ -1361,6 +1478,11 @@ pub fn test_db_on_upgrade(
         activate_backups: false,
         publicly_accessible: is_public,
         mode: database_mode.clone(),
+        advanced_settings: Default::default(),
+        kms_key_arn: None,
+        init_scripts: vec![],
+        parameters: Default::default(),
+        custom_metadata: Default::default(),
     };
 
     environment.databases = vec![db];
@@ -7,7 +7,7 @@ use qovery_engine::io_models::application::{Application, ApplicationAdvancedSett
 use qovery_engine::io_models::context::Context;
 use qovery_engine::io_models::database::DatabaseMode::CONTAINER;
 use qovery_engine::io_models::database::{Database, DatabaseKind};
-use qovery_engine::io_models::environment::EnvironmentRequest;
+use qovery_engine::io_models::environment::{EnvironmentRequest, EnvironmentServiceAccount};
 use qovery_engine::io_models::probe::{Probe, ProbeType};
 use qovery_engine::io_models::router::{Route, Router};
 use qovery_engine::io_models::variable_utils::VariableInfo;
@@ -77,6 +77,11 @@ pub fn working_environment(
             min_instances: 1,
             max_instances: 1,
             cpu_burst: "100m".to_string(),
+            resources: None,
+            build_cache_bust: false,
+            build_secrets: vec![],
+            git_recursive_submodules: false,
+            git_lfs: true,
             readiness_probe: Some(Probe {
                 r#type: ProbeType::Http {
                     path: "/".to_string(),
@@ -101,12 +106,28 @@ pub fn working_environment(
             advanced_settings: settings,
             public_domain: format!("{}.{}", application_id.to_uuid(), test_domain),
             container_registries: Vec::new(),
+            migrations: None,
+            init_containers: vec![],
+            stateful: false,
+            readiness_gates: None,
+            custom_metadata: Default::default(),
+            startup_probe: None,
+            lifecycle_hooks: Default::default(),
+            smoke_test: None,
+            shared_volume_mounts: vec![],
         }],
         containers: vec![],
         jobs: vec![],
         routers: vec![],
         databases: vec![],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     };
 
     if with_router {
@@ -121,7 +142,15 @@ pub fn working_environment(
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: application_id.to_uuid(),
+                backend_protocol: Default::default(),
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: Default::default(),
             }],
+            l4_routes: vec![],
+            nginx_annotations: Default::default(),
+            client_ca_bundle_pem: None,
+            custom_metadata: Default::default(),
         }]
     }
 
@@ -197,6 +226,7 @@ pub fn working_environment_with_application_and_stateful_crashing_if_file_doesnt
         long_id: storage_id.to_uuid(),
         name: storage_id.short().to_string(),
         storage_type: StorageType::Ssd,
+        storage_class: None,
         size_in_gib: 10,
         mount_point: format!("/tmp/{}", storage_id.short()),
         snapshot_retention_in_days: 1,
@@ -260,6 +290,11 @@ pub fn environment_2_app_2_routers_1_psql(
             activate_backups: false,
             publicly_accessible: false,
             mode: CONTAINER,
+            advanced_settings: Default::default(),
+            kms_key_arn: None,
+            init_scripts: vec![],
+            parameters: Default::default(),
+            custom_metadata: Default::default(),
         }],
         applications: vec![
             Application {
@@ -300,6 +335,11 @@ pub fn environment_2_app_2_routers_1_psql(
                 min_instances: 1,
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
+                resources: None,
+                build_cache_bust: false,
+                build_secrets: vec![],
+                git_recursive_submodules: false,
+                git_lfs: true,
                 advanced_settings: Default::default(),
                 readiness_probe: Some(Probe {
                     r#type: ProbeType::Http {
@@ -324,6 +364,15 @@ pub fn environment_2_app_2_routers_1_psql(
                 }),
                 public_domain: format!("{}.{}", application_id1, test_domain),
                 container_registries: Vec::new(),
+                migrations: None,
+                init_containers: vec![],
+                stateful: false,
+                readiness_gates: None,
+                custom_metadata: Default::default(),
+                startup_probe: None,
+                lifecycle_hooks: Default::default(),
+                smoke_test: None,
+                shared_volume_mounts: vec![],
             },
             Application {
                 long_id: application_id2,
@@ -364,6 +413,11 @@ pub fn environment_2_app_2_routers_1_psql(
                 min_instances: 1,
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
+                resources: None,
+                build_cache_bust: false,
+                build_secrets: vec![],
+                git_recursive_submodules: false,
+                git_lfs: true,
                 advanced_settings: Default::default(),
                 readiness_probe: Some(Probe {
                     r#type: ProbeType::Http {
@@ -387,6 +441,15 @@ pub fn environment_2_app_2_routers_1_psql(
                     failure_threshold: 5,
                 }),
                 container_registries: Vec::new(),
+                migrations: None,
+                init_containers: vec![],
+                stateful: false,
+                readiness_gates: None,
+                custom_metadata: Default::default(),
+                startup_probe: None,
+                lifecycle_hooks: Default::default(),
+                smoke_test: None,
+                shared_volume_mounts: vec![],
             },
         ],
         containers: vec![],
@@ -403,7 +466,15 @@ pub fn environment_2_app_2_routers_1_psql(
                 routes: vec![Route {
                     path: "/".to_string(),
                     service_long_id: application_id1,
+                    backend_protocol: Default::default(),
+                    rewrite_target: None,
+                    permanent_redirect: None,
+                    trailing_slash: Default::default(),
                 }],
+                l4_routes: vec![],
+                nginx_annotations: Default::default(),
+                client_ca_bundle_pem: None,
+                custom_metadata: Default::default(),
             },
             Router {
                 long_id: router_2,
@@ -416,12 +487,27 @@ pub fn environment_2_app_2_routers_1_psql(
                 routes: vec![Route {
                     path: "/coco".to_string(),
                     service_long_id: application_id2,
+                    backend_protocol: Default::default(),
+                    rewrite_target: None,
+                    permanent_redirect: None,
+                    trailing_slash: Default::default(),
                 }],
+                l4_routes: vec![],
+                nginx_annotations: Default::default(),
+                client_ca_bundle_pem: None,
+                custom_metadata: Default::default(),
             },
         ],
         max_parallel_build: 1,
         max_parallel_deploy: 1,
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     }
 }
 
@@ -493,6 +579,11 @@ pub fn echo_app_environment(context: &Context, test_domain: &str) -> Environment
             min_instances: 1,
             max_instances: 1,
             cpu_burst: "100m".to_string(),
+            resources: None,
+            build_cache_bust: false,
+            build_secrets: vec![],
+            git_recursive_submodules: false,
+            git_lfs: true,
             advanced_settings: Default::default(),
             readiness_probe: Some(Probe {
                 r#type: ProbeType::Http {
@@ -516,6 +607,15 @@ pub fn echo_app_environment(context: &Context, test_domain: &str) -> Environment
                 failure_threshold: 5,
             }),
             container_registries: Vec::new(),
+            migrations: None,
+            init_containers: vec![],
+            stateful: false,
+            readiness_gates: None,
+            custom_metadata: Default::default(),
+            startup_probe: None,
+            lifecycle_hooks: Default::default(),
+            smoke_test: None,
+            shared_volume_mounts: vec![],
         }],
         containers: vec![],
         jobs: vec![],
@@ -530,10 +630,25 @@ pub fn echo_app_environment(context: &Context, test_domain: &str) -> Environment
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: application_id,
+                backend_protocol: Default::default(),
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: Default::default(),
             }],
+            l4_routes: vec![],
+            nginx_annotations: Default::default(),
+            client_ca_bundle_pem: None,
+            custom_metadata: Default::default(),
         }],
         databases: vec![],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     }
 }
 
@@ -600,6 +715,11 @@ pub fn environment_only_http_server(
             min_instances: 1,
             max_instances: 1,
             cpu_burst: "100m".to_string(),
+            resources: None,
+            build_cache_bust: false,
+            build_secrets: vec![],
+            git_recursive_submodules: false,
+            git_lfs: true,
             advanced_settings: settings,
             readiness_probe: Some(Probe {
                 r#type: ProbeType::Http {
@@ -623,12 +743,28 @@ pub fn environment_only_http_server(
                 failure_threshold: 5,
             }),
             container_registries: Vec::new(),
+            migrations: None,
+            init_containers: vec![],
+            stateful: false,
+            readiness_gates: None,
+            custom_metadata: Default::default(),
+            startup_probe: None,
+            lifecycle_hooks: Default::default(),
+            smoke_test: None,
+            shared_volume_mounts: vec![],
         }],
         containers: vec![],
         jobs: vec![],
         routers: vec![],
         databases: vec![],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     };
 
     if with_router {
@@ -643,7 +779,15 @@ pub fn environment_only_http_server(
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: application_id,
+                backend_protocol: Default::default(),
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: Default::default(),
             }],
+            l4_routes: vec![],
+            nginx_annotations: Default::default(),
+            client_ca_bundle_pem: None,
+            custom_metadata: Default::default(),
         }]
     }
 
@@ -6,7 +6,9 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use function_name::named;
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
-use qovery_engine::cloud_provider::models::{EnvironmentVariable, Storage};
+use qovery_engine::cloud_provider::models::{
+    EnvironmentVariable, KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit, Storage,
+};
 use qovery_engine::cloud_provider::service::ServiceType;
 use qovery_engine::cloud_provider::utilities::update_pvcs;
 use qovery_engine::cloud_provider::DeploymentTarget;
@@ -21,6 +23,7 @@ use qovery_engine::models::types::AWS;
 use qovery_engine::runtime::block_on;
 use qovery_engine::transaction::TransactionResult;
 use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
 use tracing::{span, Level};
 
 #[cfg(feature = "test-aws-self-hosted")]
@@ -71,6 +74,7 @@ fn should_increase_app_storage_size() {
                 is_secret: variable_infos.is_secret,
             })
             .collect::<Vec<EnvironmentVariable>>();
+        let resources = resized_app.resources();
         let app: Application<AWS> = Application::new(
             &resized_context,
             resized_app.long_id,
@@ -79,9 +83,11 @@ fn should_increase_app_storage_size() {
             resized_app.name.clone(),
             resized_app.public_domain.clone(),
             resized_app.ports.clone(),
-            resized_app.total_cpus.to_string(),
-            resized_app.cpu_burst.to_string(),
-            resized_app.total_ram_in_mib,
+            KubernetesCpuResourceUnit::from_str(&resources.cpu_request).unwrap(),
+            KubernetesCpuResourceUnit::from_str(&resources.cpu_limit).unwrap(),
+            KubernetesMemoryResourceUnit::from_str(&resources.ram_request).unwrap(),
+            KubernetesMemoryResourceUnit::from_str(&resources.ram_limit).unwrap(),
+            resources.gpu,
             resized_app.min_instances,
             resized_app.max_instances,
             resized_app.to_build(
@@ -92,11 +98,20 @@ fn should_increase_app_storage_size() {
             resized_app.command_args.clone(),
             resized_app.entrypoint.clone(),
             storages,
+            resized_app.stateful,
             envs,
             BTreeSet::default(),
             resized_app.readiness_probe.clone().map(|p| p.to_domain()),
             resized_app.liveness_probe.clone().map(|p| p.to_domain()),
+            resized_app.startup_probe.clone().map(|p| p.to_domain()),
             resized_app.advanced_settings.clone(),
+            resized_app.migrations.clone(),
+            resized_app.init_containers.clone(),
+            resized_app.readiness_gates.clone(),
+            resized_app.custom_metadata.clone(),
+            resized_app.lifecycle_hooks.clone(),
+            resized_app.smoke_test.clone(),
+            resized_app.shared_volume_mounts.clone(),
             AwsAppExtraSettings {},
             |transmitter| infra_ctx.context().get_event_details(transmitter),
         )
@@ -259,6 +274,7 @@ fn should_have_mounted_files_as_volume() {
             long_id: storage_id.to_uuid(),
             name: storage_id.short().to_string(),
             storage_type: StorageType::Ssd,
+            storage_class: None,
             size_in_gib: 10,
             mount_point: format!("/tmp/{}", storage_id.short()),
             snapshot_retention_in_days: 1,
@@ -256,6 +256,7 @@ fn should_have_mounted_files_as_volume() {
             long_id: storage_id.to_uuid(),
             name: storage_id.short().to_string(),
             storage_type: StorageType::Ssd,
+            storage_class: None,
             size_in_gib: 10,
             mount_point: format!("/tmp/{}", storage_id.short()),
             snapshot_retention_in_days: 1,
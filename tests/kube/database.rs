@@ -79,6 +79,11 @@ fn should_increase_db_storage_size() {
                 activate_high_availability: resized_db.activate_high_availability,
                 activate_backups: resized_db.activate_backups,
                 publicly_accessible: resized_db.publicly_accessible,
+                advanced_settings: Default::default(),
+                kms_key_arn: None,
+                init_scripts: vec![],
+                parameters: Default::default(),
+                custom_metadata: Default::default(),
             },
             |transmitter| infra_ctx.context().get_event_details(transmitter),
         )
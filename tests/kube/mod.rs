@@ -10,7 +10,7 @@ use qovery_engine::io_models::application::{Application, Port, Protocol, Storage
 use qovery_engine::io_models::container::{Container, Registry};
 use qovery_engine::io_models::database::DatabaseMode::CONTAINER;
 use qovery_engine::io_models::database::{Database, DatabaseKind};
-use qovery_engine::io_models::environment::EnvironmentRequest;
+use qovery_engine::io_models::environment::{EnvironmentRequest, EnvironmentServiceAccount};
 use qovery_engine::io_models::job::{ContainerRegistries, Job, JobSchedule, JobSource};
 use qovery_engine::io_models::probe::{Probe, ProbeType};
 use qovery_engine::io_models::{Action, QoveryIdentifier};
@@ -66,6 +66,13 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
         routers: vec![],
         databases: vec![],
         helms: vec![],
+        kustomizations: vec![],
+        terraform_services: vec![],
+        shared_volumes: vec![],
+        service_account: EnvironmentServiceAccount::default(),
+        remote_builder: None,
+        deployment_waves: None,
+        service_dependencies: BTreeMap::new(),
     };
 
     match options {
@@ -95,6 +102,11 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                 publicly_accessible: false,
                 mode: CONTAINER,
                 database_instance_type: None,
+                advanced_settings: Default::default(),
+                kms_key_arn: None,
+                init_scripts: vec![],
+                parameters: Default::default(),
+                custom_metadata: Default::default(),
             };
             environment.databases = vec![db];
         }
@@ -169,6 +181,7 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                         long_id: storage_1_id,
                         name: "photos1".to_string(),
                         storage_type: StorageType::Ssd,
+                        storage_class: None,
                         size_in_gib: NormalSize.size(),
                         mount_point: "/mnt/photos1".to_string(),
                         snapshot_retention_in_days: 0,
@@ -178,6 +191,7 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                         long_id: storage_2_id,
                         name: "photos2".to_string(),
                         storage_type: StorageType::Ssd,
+                        storage_class: None,
                         size_in_gib: NormalSize.size(),
                         mount_point: "/mnt/photos2".to_string(),
                         snapshot_retention_in_days: 0,
@@ -186,6 +200,8 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                 environment_vars_with_infos: BTreeMap::default(),
                 advanced_settings: Default::default(),
                 mounted_files: vec![],
+                lifecycle_hooks: Default::default(),
+                shared_volume_mounts: vec![],
             };
             environment.containers = vec![container];
         }
@@ -213,6 +229,7 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                         long_id: storage_1_id,
                         name: "photos1".to_string(),
                         storage_type: StorageType::Ssd,
+                        storage_class: None,
                         size_in_gib: NormalSize.size(),
                         mount_point: "/mnt/photos1".to_string(),
                         snapshot_retention_in_days: 0,
@@ -222,6 +239,7 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                         long_id: storage_2_id,
                         name: "photos2".to_string(),
                         storage_type: StorageType::Ssd,
+                        storage_class: None,
                         size_in_gib: NormalSize.size(),
                         mount_point: "/mnt/photos2".to_string(),
                         snapshot_retention_in_days: 0,
@@ -266,9 +284,23 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                 min_instances: 1,
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
+                resources: None,
+                build_cache_bust: false,
+                build_secrets: vec![],
+                git_recursive_submodules: false,
+                git_lfs: true,
                 advanced_settings: Default::default(),
                 mounted_files: vec![],
                 container_registries: Vec::new(),
+                migrations: None,
+                init_containers: vec![],
+                stateful: false,
+                readiness_gates: None,
+                custom_metadata: Default::default(),
+                startup_probe: None,
+                lifecycle_hooks: Default::default(),
+                smoke_test: None,
+                shared_volume_mounts: vec![],
             };
             environment.applications = vec![app];
         }
@@ -313,6 +345,7 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                 readiness_probe: None,
                 liveness_probe: None,
                 container_registries: ContainerRegistries { registries: vec![] },
+                artifacts_storage: None,
             };
             environment.jobs = vec![job];
         }
@@ -394,6 +394,7 @@ fn scaleway_kapsule_deploy_a_working_environment_with_domain() {
                 domain: format!("fake-custom-domain-{idx}.qovery.io"),
                 target_domain: format!("validation-domain-{idx}"),
                 generate_certificate: true,
+                certificate: None,
             };
 
             router.custom_domains = vec![cd];
@@ -483,6 +484,7 @@ fn scaleway_kapsule_deploy_a_working_environment_with_storage() {
                     long_id: id,
                     name: "photos".to_string(),
                     storage_type: StorageType::Ssd,
+                    storage_class: None,
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
@@ -728,6 +730,7 @@ fn scaleway_kapsule_redeploy_same_app() {
                     long_id: id,
                     name: "photos".to_string(),
                     storage_type: StorageType::Ssd,
+                    storage_class: None,
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
@@ -1514,6 +1517,8 @@ fn deploy_container_with_no_router_on_scw() {
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -1645,6 +1650,8 @@ fn deploy_container_on_scw_with_mounted_files_as_volume() {
                 success_threshold: 1,
                 failure_threshold: 5,
             }),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -1797,6 +1804,8 @@ fn deploy_container_with_router_on_scw() {
                 success_threshold: 1,
                 failure_threshold: 5,
             }),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         environment.routers = vec![Router {
@@ -1810,7 +1819,15 @@ fn deploy_container_with_router_on_scw() {
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: environment.containers[0].long_id,
+                backend_protocol: Default::default(),
+                rewrite_target: None,
+                permanent_redirect: None,
+                trailing_slash: Default::default(),
             }],
+            l4_routes: vec![],
+            nginx_annotations: Default::default(),
+            client_ca_bundle_pem: None,
+            custom_metadata: Default::default(),
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -1907,6 +1924,7 @@ fn deploy_job_on_scw_kapsule() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2002,6 +2020,7 @@ fn deploy_cronjob_on_scw_kapsule() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2097,6 +2116,7 @@ fn deploy_cronjob_force_trigger_on_scw_kapsule() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2191,6 +2211,7 @@ fn build_and_deploy_job_on_scw_kapsule() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2296,6 +2317,7 @@ fn build_and_deploy_job_on_scw_kapsule_with_mounted_files() {
                 failure_threshold: 5,
             }),
             container_registries: ContainerRegistries { registries: vec![] },
+            artifacts_storage: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2463,6 +2485,8 @@ fn deploy_container_with_tcp_public_port() {
             environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret:false} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
+            lifecycle_hooks: Default::default(),
+            shared_volume_mounts: vec![],
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use crate::helpers::scaleway::SCW_BUCKET_TTL_IN_SECONDS;
 use qovery_engine::models::scaleway::ScwZone;
+use qovery_engine::object_storage::bucket_encryption::BucketEncryption;
 use qovery_engine::object_storage::scaleway_object_storage::ScalewayOS;
 use qovery_engine::object_storage::{BucketDeleteStrategy, ObjectStorage};
 use tempfile::NamedTempFile;
@@ -42,6 +43,7 @@ fn test_delete_bucket_hard_delete_strategy() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -86,6 +88,7 @@ fn test_delete_bucket_empty_strategy() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -131,6 +134,7 @@ fn test_create_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
 
         // validate:
@@ -178,6 +182,7 @@ fn test_get_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
                 false,
+                &BucketEncryption::ProviderManaged,
             )
             .expect("Cannot create bucket");
 
@@ -228,6 +233,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -243,6 +249,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(recreate_result.is_ok());
         info!("Bucket {} recreated.", bucket_name);
@@ -288,6 +295,7 @@ fn test_file_handling() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -344,6 +352,7 @@ fn test_ensure_file_is_absent() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            &BucketEncryption::ProviderManaged,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);